@@ -0,0 +1,37 @@
+#!/usr/bin/env bash
+
+# This script is used to download the user agent regexes of the "ua-parser/uap-core" repository
+# for the user_agent processor of pipelines.
+set -ex
+
+declare -r SCRIPT_DIR=$(cd $(dirname ${0}) >/dev/null 2>&1 && pwd)
+declare -r ROOT_DIR=$(dirname ${SCRIPT_DIR})
+declare -r REGEXES_FILE="$ROOT_DIR/src/pipeline/src/etl/processor/user_agent/regexes.yaml"
+
+UAP_CORE_VERSION="${UAP_CORE_VERSION:-v0.18.0}"
+
+if [[ -z "$GITHUB_PROXY_URL" ]]; then
+  GITHUB_URL="https://raw.githubusercontent.com"
+else
+  GITHUB_URL="${GITHUB_PROXY_URL%/}"
+fi
+
+URL="${GITHUB_URL}/ua-parser/uap-core/${UAP_CORE_VERSION}/regexes.yaml"
+curl --connect-timeout 10 --retry 3 -fsSL $URL --output "$REGEXES_FILE.tmp" || {
+    echo "Failed to download $URL"
+    echo "You may try to set http_proxy and https_proxy environment variables."
+    rm -f "$REGEXES_FILE.tmp"
+    exit 1
+}
+
+{
+  echo "# The user agent regex database of the user_agent processor."
+  echo "#"
+  echo "# Downloaded from ua-parser/uap-core ${UAP_CORE_VERSION} by scripts/fetch-uap-regexes.sh, do not edit."
+  echo "# uap-core is licensed under the Apache License, Version 2.0."
+  echo
+  cat "$REGEXES_FILE.tmp"
+} > "$REGEXES_FILE"
+rm "$REGEXES_FILE.tmp"
+
+echo "Successfully download uap-core ${UAP_CORE_VERSION} regexes to $REGEXES_FILE"
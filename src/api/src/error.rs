@@ -81,6 +81,20 @@ pub enum Error {
         #[snafu(implicit)]
         location: Location,
     },
+
+    #[snafu(display(
+        "Invalid logical type {} of column: {}, reason: {}",
+        value,
+        column,
+        reason
+    ))]
+    InvalidLogicalType {
+        value: String,
+        column: String,
+        reason: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
 }
 
 impl ErrorExt for Error {
@@ -88,7 +102,8 @@ impl ErrorExt for Error {
         match self {
             Error::UnknownColumnDataType { .. }
             | Error::InvalidTimeUnit { .. }
-            | Error::InconsistentTimeUnit { .. } => StatusCode::InvalidArguments,
+            | Error::InconsistentTimeUnit { .. }
+            | Error::InvalidLogicalType { .. } => StatusCode::InvalidArguments,
             Error::IntoColumnDataType { .. } | Error::SerializeJson { .. } => {
                 StatusCode::Unexpected
             }
@@ -13,11 +13,13 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::str::FromStr;
 
+use datatypes::data_type::ConcreteDataType;
 use datatypes::schema::{
-    ColumnDefaultConstraint, ColumnSchema, FulltextAnalyzer, FulltextBackend, FulltextOptions,
-    SkippingIndexOptions, SkippingIndexType, COMMENT_KEY, FULLTEXT_KEY, INVERTED_INDEX_KEY,
-    SKIPPING_INDEX_KEY,
+    ColumnDefaultConstraint, ColumnExtType, ColumnSchema, FulltextAnalyzer, FulltextBackend,
+    FulltextOptions, SkippingIndexOptions, SkippingIndexType, COMMENT_KEY, FULLTEXT_KEY,
+    INVERTED_INDEX_KEY, SKIPPING_INDEX_KEY,
 };
 use greptime_proto::v1::{
     Analyzer, FulltextBackend as PbFulltextBackend, SkippingIndexType as PbSkippingIndexType,
@@ -34,11 +36,21 @@ const FULLTEXT_GRPC_KEY: &str = "fulltext";
 const INVERTED_INDEX_GRPC_KEY: &str = "inverted_index";
 /// Key used to store skip index options in gRPC column options.
 const SKIPPING_INDEX_GRPC_KEY: &str = "skipping_index";
+/// Key used to store the logical type of columns that are sent as binary in gRPC, e.g. uuid and map.
+const LOGICAL_TYPE_GRPC_KEY: &str = "logical_type";
 
 /// Tries to construct a `ColumnSchema` from the given  `ColumnDef`.
 pub fn try_as_column_schema(column_def: &ColumnDef) -> Result<ColumnSchema> {
     let data_type =
         ColumnDataTypeWrapper::try_new(column_def.data_type, column_def.datatype_extension)?;
+    let data_type = match column_def
+        .options
+        .as_ref()
+        .and_then(|o| o.options.get(LOGICAL_TYPE_GRPC_KEY))
+    {
+        Some(logical_type) => logical_type_to_data_type(&column_def.name, logical_type)?,
+        None => data_type.into(),
+    };
 
     let constraint = if column_def.default_constraint.is_empty() {
         None
@@ -68,7 +80,7 @@ pub fn try_as_column_schema(column_def: &ColumnDef) -> Result<ColumnSchema> {
         }
     }
 
-    ColumnSchema::new(&column_def.name, data_type.into(), column_def.is_nullable)
+    ColumnSchema::new(&column_def.name, data_type, column_def.is_nullable)
         .with_metadata(metadata)
         .with_time_index(column_def.semantic_type() == SemanticType::Timestamp)
         .with_default_constraint(constraint)
@@ -77,6 +89,29 @@ pub fn try_as_column_schema(column_def: &ColumnDef) -> Result<ColumnSchema> {
         })
 }
 
+/// Resolves the data type of a column from the logical type stored in its options.
+fn logical_type_to_data_type(column: &str, logical_type: &str) -> Result<ConcreteDataType> {
+    let ext_type = ColumnExtType::from_str(logical_type).map_err(|reason| {
+        error::InvalidLogicalTypeSnafu {
+            value: logical_type,
+            column,
+            reason,
+        }
+        .build()
+    })?;
+    match ext_type {
+        ColumnExtType::Uuid => Ok(ConcreteDataType::uuid_datatype()),
+        ColumnExtType::Map(m) => Ok(ConcreteDataType::Map(m)),
+        ColumnExtType::Ip => Ok(ConcreteDataType::ip_datatype()),
+        ColumnExtType::Json | ColumnExtType::Vector(_) => error::InvalidLogicalTypeSnafu {
+            value: logical_type,
+            column,
+            reason: "json and vector have their own gRPC data types",
+        }
+        .fail(),
+    }
+}
+
 /// Tries to construct a `ColumnDef` from the given `ColumnSchema`.
 ///
 /// TODO(weny): Add tests for this function.
@@ -122,6 +157,17 @@ pub fn try_as_column_def(column_schema: &ColumnSchema, is_primary_key: bool) ->
 /// Constructs a `ColumnOptions` from the given `ColumnSchema`.
 pub fn options_from_column_schema(column_schema: &ColumnSchema) -> Option<ColumnOptions> {
     let mut options = ColumnOptions::default();
+    let logical_type = match &column_schema.data_type {
+        ConcreteDataType::Uuid(_) => Some(ColumnExtType::Uuid),
+        ConcreteDataType::Map(m) => Some(ColumnExtType::Map(m.clone())),
+        ConcreteDataType::Ip(_) => Some(ColumnExtType::Ip),
+        _ => None,
+    };
+    if let Some(logical_type) = logical_type {
+        options
+            .options
+            .insert(LOGICAL_TYPE_GRPC_KEY.to_string(), logical_type.to_string());
+    }
     if let Some(fulltext) = column_schema.metadata().get(FULLTEXT_KEY) {
         options
             .options
@@ -289,6 +335,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_logical_type_roundtrip() {
+        let data_types = [
+            ConcreteDataType::uuid_datatype(),
+            ConcreteDataType::map_datatype(
+                ConcreteDataType::string_datatype(),
+                ConcreteDataType::int64_datatype(),
+            ),
+            ConcreteDataType::ip_datatype(),
+        ];
+        for data_type in data_types {
+            let schema = ColumnSchema::new("test", data_type.clone(), true);
+            let column_def = try_as_column_def(&schema, false).unwrap();
+            assert_eq!(ColumnDataType::Binary as i32, column_def.data_type);
+            assert!(column_def
+                .options
+                .as_ref()
+                .unwrap()
+                .options
+                .contains_key(LOGICAL_TYPE_GRPC_KEY));
+
+            let schema = try_as_column_schema(&column_def).unwrap();
+            assert_eq!(data_type, schema.data_type);
+        }
+
+        let column_def = ColumnDef {
+            name: "test".to_string(),
+            data_type: ColumnDataType::Binary as i32,
+            is_nullable: true,
+            default_constraint: vec![],
+            semantic_type: SemanticType::Field as i32,
+            comment: String::new(),
+            datatype_extension: None,
+            options: Some(ColumnOptions {
+                options: HashMap::from([(
+                    LOGICAL_TYPE_GRPC_KEY.to_string(),
+                    "Map<Float64, String>".to_string(),
+                )]),
+            }),
+        };
+        assert!(try_as_column_schema(&column_def).is_err());
+    }
+
     #[test]
     fn test_options_with_fulltext() {
         let fulltext = FulltextOptions::new_unchecked(
@@ -108,6 +108,17 @@ pub fn auth_mysql(
     salt: Salt,
     username: &str,
     save_pwd: &[u8],
+) -> Result<()> {
+    auth_mysql_with_hash(auth_data, salt, username, &mysql_password_hash(save_pwd))
+}
+
+/// Authenticates the mysql native password with the hash of the saved password,
+/// see [mysql_password_hash].
+pub fn auth_mysql_with_hash(
+    auth_data: HashedPassword,
+    salt: Salt,
+    username: &str,
+    hash_stage_2: &[u8],
 ) -> Result<()> {
     ensure!(
         auth_data.len() == 20,
@@ -116,8 +127,7 @@ pub fn auth_mysql(
         }
     );
     // ref: https://github.com/mysql/mysql-server/blob/a246bad76b9271cb4333634e954040a970222e0a/sql/auth/password.cc#L62
-    let hash_stage_2 = double_sha1(save_pwd);
-    let tmp = sha1_two(salt, &hash_stage_2);
+    let tmp = sha1_two(salt, hash_stage_2);
     // xor auth_data and tmp
     let mut xor_result = [0u8; 20];
     for i in 0..20 {
@@ -151,6 +161,12 @@ fn double_sha1(data: &[u8]) -> Vec<u8> {
     sha1_one(&sha1_one(data))
 }
 
+/// Returns the hash of the `password` in the same form as the mysql native password,
+/// so the password doesn't have to be stored in plain text.
+pub fn mysql_password_hash(password: &[u8]) -> Vec<u8> {
+    double_sha1(password)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
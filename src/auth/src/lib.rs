@@ -22,10 +22,10 @@ mod user_provider;
 pub mod tests;
 
 pub use common::{
-    auth_mysql, static_user_provider_from_option, user_provider_from_option, userinfo_by_name,
-    HashedPassword, Identity, Password,
+    auth_mysql, auth_mysql_with_hash, mysql_password_hash, static_user_provider_from_option,
+    user_provider_from_option, userinfo_by_name, HashedPassword, Identity, Password,
 };
-pub use permission::{PermissionChecker, PermissionReq, PermissionResp};
+pub use permission::{PermissionChecker, PermissionReq, PermissionResp, PermissionScope};
 pub use user_info::UserInfo;
 pub use user_provider::static_user_provider::StaticUserProvider;
 pub use user_provider::UserProvider;
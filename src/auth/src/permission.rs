@@ -15,6 +15,7 @@
 use std::fmt::Debug;
 
 use api::v1::greptime_request::Request;
+use api::v1::TableName;
 use sql::statements::statement::Statement;
 
 use crate::error::{PermissionDeniedSnafu, Result};
@@ -24,7 +25,14 @@ use crate::{PermissionCheckerRef, UserInfoRef};
 pub enum PermissionReq<'a> {
     GrpcRequest(&'a Request),
     SqlStatement(&'a Statement),
-    PromQuery,
+    /// A PromQL expression.
+    PromQuery(&'a str),
+    /// A logical plan from gRPC, with the tables it scans and the table it inserts
+    /// into if any.
+    LogicalPlan {
+        scans: &'a [TableName],
+        insert_into: Option<&'a TableName>,
+    },
     LogQuery,
     Opentsdb,
     LineProtocol,
@@ -35,6 +43,21 @@ pub enum PermissionReq<'a> {
     BulkInsert,
 }
 
+/// The catalog and schema that a [PermissionReq] is made in.
+///
+/// Unqualified names in the request are resolved against it.
+#[derive(Debug, Clone, Copy)]
+pub struct PermissionScope<'a> {
+    pub catalog: &'a str,
+    pub schema: &'a str,
+}
+
+impl<'a> PermissionScope<'a> {
+    pub fn new(catalog: &'a str, schema: &'a str) -> Self {
+        Self { catalog, schema }
+    }
+}
+
 #[derive(Debug)]
 pub enum PermissionResp {
     Allow,
@@ -46,6 +69,7 @@ pub trait PermissionChecker: Send + Sync {
         &self,
         user_info: UserInfoRef,
         req: PermissionReq,
+        scope: PermissionScope,
     ) -> Result<PermissionResp>;
 }
 
@@ -54,9 +78,10 @@ impl PermissionChecker for Option<&PermissionCheckerRef> {
         &self,
         user_info: UserInfoRef,
         req: PermissionReq,
+        scope: PermissionScope,
     ) -> Result<PermissionResp> {
         match self {
-            Some(checker) => match checker.check_permission(user_info, req) {
+            Some(checker) => match checker.check_permission(user_info, req, scope) {
                 Ok(PermissionResp::Reject) => PermissionDeniedSnafu.fail(),
                 Ok(PermissionResp::Allow) => Ok(PermissionResp::Allow),
                 Err(e) => Err(e),
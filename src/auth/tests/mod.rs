@@ -19,7 +19,10 @@ use std::sync::Arc;
 use api::v1::greptime_request::Request;
 use auth::error::Error::InternalState;
 use auth::error::InternalStateSnafu;
-use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq, PermissionResp, UserInfoRef};
+use auth::{
+    PermissionChecker, PermissionCheckerRef, PermissionReq, PermissionResp, PermissionScope,
+    UserInfoRef,
+};
 use sql::statements::show::{ShowDatabases, ShowKind};
 use sql::statements::statement::Statement;
 
@@ -30,6 +33,7 @@ impl PermissionChecker for DummyPermissionChecker {
         &self,
         _user_info: UserInfoRef,
         req: PermissionReq,
+        _scope: PermissionScope,
     ) -> auth::error::Result<PermissionResp> {
         match req {
             PermissionReq::GrpcRequest(_) => Ok(PermissionResp::Allow),
@@ -45,10 +49,12 @@ impl PermissionChecker for DummyPermissionChecker {
 #[test]
 fn test_permission_checker() {
     let checker: PermissionCheckerRef = Arc::new(DummyPermissionChecker);
+    let scope = PermissionScope::new("greptime", "public");
 
     let grpc_result = checker.check_permission(
         auth::userinfo_by_name(None),
         PermissionReq::GrpcRequest(&Request::Query(Default::default())),
+        scope,
     );
     assert_matches!(grpc_result, Ok(PermissionResp::Allow));
 
@@ -58,10 +64,11 @@ fn test_permission_checker() {
             ShowKind::All,
             false,
         ))),
+        scope,
     );
     assert_matches!(sql_result, Ok(PermissionResp::Reject));
 
     let err_result =
-        checker.check_permission(auth::userinfo_by_name(None), PermissionReq::Opentsdb);
+        checker.check_permission(auth::userinfo_by_name(None), PermissionReq::Opentsdb, scope);
     assert_matches!(err_result, Err(InternalState { msg }) if msg == "testing");
 }
@@ -23,6 +23,7 @@ use common_meta::cache::{
     new_table_route_cache, new_table_schema_cache, new_view_info_cache, CacheRegistry,
     CacheRegistryBuilder, LayeredCacheRegistryBuilder,
 };
+use common_meta::key::access_control::AccessControlManager;
 use common_meta::kv_backend::KvBackendRef;
 use moka::future::CacheBuilder;
 use snafu::OptionExt;
@@ -78,6 +79,7 @@ pub fn build_datanode_cache_registry(kv_backend: KvBackendRef) -> CacheRegistry
 /// - Table flow node cache
 /// - View cache
 /// - Schema cache
+/// - Access control manager
 pub fn build_fundamental_cache_registry(kv_backend: KvBackendRef) -> CacheRegistry {
     // Builds table info cache
     let cache = CacheBuilder::new(DEFAULT_CACHE_MAX_CAPACITY)
@@ -147,8 +149,11 @@ pub fn build_fundamental_cache_registry(kv_backend: KvBackendRef) -> CacheRegist
     let table_id_schema_cache = Arc::new(new_table_schema_cache(
         TABLE_SCHEMA_NAME_CACHE_NAME.to_string(),
         CacheBuilder::new(DEFAULT_CACHE_MAX_CAPACITY).build(),
-        kv_backend,
+        kv_backend.clone(),
     ));
+
+    // Builds the access control manager that caches users and roles
+    let access_control_manager = Arc::new(AccessControlManager::new(kv_backend));
     CacheRegistryBuilder::default()
         .add_cache(table_info_cache)
         .add_cache(table_name_cache)
@@ -157,6 +162,7 @@ pub fn build_fundamental_cache_registry(kv_backend: KvBackendRef) -> CacheRegist
         .add_cache(table_flownode_set_cache)
         .add_cache(schema_cache)
         .add_cache(table_id_schema_cache)
+        .add_cache(access_control_manager)
         .build()
 }
 
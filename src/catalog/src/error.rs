@@ -71,6 +71,13 @@ pub enum Error {
         source: BoxedError,
     },
 
+    #[snafu(display("Failed to list region moves"))]
+    ListRegionMoves {
+        #[snafu(implicit)]
+        location: Location,
+        source: BoxedError,
+    },
+
     #[snafu(display("Failed to list flows in catalog {catalog}"))]
     ListFlows {
         #[snafu(implicit)]
@@ -349,6 +356,7 @@ impl ErrorExt for Error {
             | Error::ListFlowStats { source, .. }
             | Error::ListProcedures { source, .. }
             | Error::ListRegionStats { source, .. }
+            | Error::ListRegionMoves { source, .. }
             | Error::ConvertProtoData { source, .. } => source.status_code(),
 
             Error::CreateTable { source, .. } => source.status_code(),
@@ -17,6 +17,7 @@ use common_error::ext::BoxedError;
 use common_meta::cluster::{ClusterInfo, NodeInfo};
 use common_meta::datanode::RegionStat;
 use common_meta::key::flow::flow_state::FlowStat;
+use common_meta::key::region_balance::RegionMove;
 use common_meta::procedure_executor::{ExecutorContext, ProcedureExecutor};
 use common_meta::rpc::procedure;
 use common_procedure::{ProcedureInfo, ProcedureState};
@@ -98,4 +99,12 @@ impl InformationExtension for DistributedInformationExtension {
             .map_err(BoxedError::new)
             .context(crate::error::ListFlowStatsSnafu)
     }
+
+    async fn region_moves(&self) -> std::result::Result<Vec<RegionMove>, Self::Error> {
+        self.meta_client
+            .list_region_moves()
+            .await
+            .map_err(BoxedError::new)
+            .context(error::ListRegionMovesSnafu)
+    }
 }
@@ -20,6 +20,7 @@ pub mod key_column_usage;
 mod partitions;
 mod procedure_info;
 pub mod process_list;
+mod region_balance_moves;
 pub mod region_peers;
 mod region_statistics;
 mod runtime_metrics;
@@ -38,6 +39,7 @@ use common_meta::cluster::NodeInfo;
 use common_meta::datanode::RegionStat;
 use common_meta::key::flow::flow_state::FlowStat;
 use common_meta::key::flow::FlowMetadataManager;
+use common_meta::key::region_balance::RegionMove;
 use common_meta::kv_backend::KvBackendRef;
 use common_procedure::ProcedureInfo;
 use common_recordbatch::SendableRecordBatchStream;
@@ -246,6 +248,11 @@ impl SystemSchemaProviderInner for InformationSchemaProvider {
                     self.catalog_manager.clone(),
                 ),
             ) as _),
+            REGION_BALANCE_MOVES => Some(Arc::new(
+                region_balance_moves::InformationSchemaRegionBalanceMoves::new(
+                    self.catalog_manager.clone(),
+                ),
+            ) as _),
             PROCESS_LIST => self
                 .process_manager
                 .as_ref()
@@ -321,6 +328,10 @@ impl InformationSchemaProvider {
                 REGION_STATISTICS.to_string(),
                 self.build_table(REGION_STATISTICS).unwrap(),
             );
+            tables.insert(
+                REGION_BALANCE_MOVES.to_string(),
+                self.build_table(REGION_BALANCE_MOVES).unwrap(),
+            );
         }
 
         tables.insert(TABLES.to_string(), self.build_table(TABLES).unwrap());
@@ -409,6 +420,9 @@ pub trait InformationExtension {
 
     /// Get the flow statistics. If no flownode is available, return `None`.
     async fn flow_stats(&self) -> std::result::Result<Option<FlowStat>, Self::Error>;
+
+    /// Gets the planned and in-flight region moves of the region balancer.
+    async fn region_moves(&self) -> std::result::Result<Vec<RegionMove>, Self::Error>;
 }
 
 pub struct NoopInformationExtension;
@@ -432,4 +446,8 @@ impl InformationExtension for NoopInformationExtension {
     async fn flow_stats(&self) -> std::result::Result<Option<FlowStat>, Self::Error> {
         Ok(None)
     }
+
+    async fn region_moves(&self) -> std::result::Result<Vec<RegionMove>, Self::Error> {
+        Ok(vec![])
+    }
 }
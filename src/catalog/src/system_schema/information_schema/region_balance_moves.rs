@@ -0,0 +1,288 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::{Arc, Weak};
+
+use arrow_schema::SchemaRef as ArrowSchemaRef;
+use common_catalog::consts::INFORMATION_SCHEMA_REGION_BALANCE_MOVES_TABLE_ID;
+use common_error::ext::BoxedError;
+use common_meta::key::region_balance::RegionMove;
+use common_recordbatch::adapter::RecordBatchStreamAdapter;
+use common_recordbatch::{DfSendableRecordBatchStream, RecordBatch, SendableRecordBatchStream};
+use common_time::timestamp::Timestamp;
+use datafusion::execution::TaskContext;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter as DfRecordBatchStreamAdapter;
+use datafusion::physical_plan::streaming::PartitionStream as DfPartitionStream;
+use datatypes::prelude::{ConcreteDataType, ScalarVectorBuilder, VectorRef};
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::timestamp::TimestampMillisecond;
+use datatypes::value::Value;
+use datatypes::vectors::{
+    Float64VectorBuilder, StringVectorBuilder, TimestampMillisecondVectorBuilder,
+    UInt32VectorBuilder, UInt64VectorBuilder,
+};
+use snafu::ResultExt;
+use store_api::storage::{ScanRequest, TableId};
+
+use crate::error::{CreateRecordBatchSnafu, InternalSnafu, Result};
+use crate::information_schema::Predicates;
+use crate::system_schema::information_schema::{InformationTable, REGION_BALANCE_MOVES};
+use crate::system_schema::utils;
+use crate::CatalogManager;
+
+const REGION_ID: &str = "region_id";
+const TABLE_ID: &str = "table_id";
+const REGION_NUMBER: &str = "region_number";
+const FROM_PEER_ID: &str = "from_peer_id";
+const FROM_PEER_ADDR: &str = "from_peer_addr";
+const TO_PEER_ID: &str = "to_peer_id";
+const TO_PEER_ADDR: &str = "to_peer_addr";
+const LOAD: &str = "load";
+const STATE: &str = "state";
+const PROCEDURE_ID: &str = "procedure_id";
+const ERROR: &str = "error";
+const PLANNED_TIME: &str = "planned_time";
+
+const INIT_CAPACITY: usize = 42;
+
+/// The `REGION_BALANCE_MOVES` table provides the region moves planned by the metasrv region balancer. Including fields:
+///
+/// - `region_id`: The region id.
+/// - `table_id`: The table id.
+/// - `region_number`: The region number.
+/// - `from_peer_id`: The id of the datanode the region moves from.
+/// - `from_peer_addr`: The address of the datanode the region moves from.
+/// - `to_peer_id`: The id of the datanode the region moves to.
+/// - `to_peer_addr`: The address of the datanode the region moves to.
+/// - `load`: The load score of the region when the move was planned.
+/// - `state`: The state of the move, `Planned`, `Running` or `Failed`.
+/// - `procedure_id`: The id of the region migration procedure.
+/// - `error`: The error message if the move failed.
+/// - `planned_time`: The time the move was planned.
+#[derive(Debug)]
+pub(super) struct InformationSchemaRegionBalanceMoves {
+    schema: SchemaRef,
+    catalog_manager: Weak<dyn CatalogManager>,
+}
+
+impl InformationSchemaRegionBalanceMoves {
+    pub(super) fn new(catalog_manager: Weak<dyn CatalogManager>) -> Self {
+        Self {
+            schema: Self::schema(),
+            catalog_manager,
+        }
+    }
+
+    pub(crate) fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            ColumnSchema::new(REGION_ID, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(TABLE_ID, ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new(REGION_NUMBER, ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new(FROM_PEER_ID, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(FROM_PEER_ADDR, ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(TO_PEER_ID, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(TO_PEER_ADDR, ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(LOAD, ConcreteDataType::float64_datatype(), false),
+            ColumnSchema::new(STATE, ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(PROCEDURE_ID, ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new(ERROR, ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new(
+                PLANNED_TIME,
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+        ]))
+    }
+
+    fn builder(&self) -> InformationSchemaRegionBalanceMovesBuilder {
+        InformationSchemaRegionBalanceMovesBuilder::new(
+            self.schema.clone(),
+            self.catalog_manager.clone(),
+        )
+    }
+}
+
+impl InformationTable for InformationSchemaRegionBalanceMoves {
+    fn table_id(&self) -> TableId {
+        INFORMATION_SCHEMA_REGION_BALANCE_MOVES_TABLE_ID
+    }
+
+    fn table_name(&self) -> &'static str {
+        REGION_BALANCE_MOVES
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn to_stream(&self, request: ScanRequest) -> Result<SendableRecordBatchStream> {
+        let schema = self.schema.arrow_schema().clone();
+        let mut builder = self.builder();
+
+        let stream = Box::pin(DfRecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::once(async move {
+                builder
+                    .make_region_balance_moves(Some(request))
+                    .await
+                    .map(|x| x.into_df_record_batch())
+                    .map_err(Into::into)
+            }),
+        ));
+
+        Ok(Box::pin(
+            RecordBatchStreamAdapter::try_new(stream)
+                .map_err(BoxedError::new)
+                .context(InternalSnafu)?,
+        ))
+    }
+}
+
+struct InformationSchemaRegionBalanceMovesBuilder {
+    schema: SchemaRef,
+    catalog_manager: Weak<dyn CatalogManager>,
+
+    region_ids: UInt64VectorBuilder,
+    table_ids: UInt32VectorBuilder,
+    region_numbers: UInt32VectorBuilder,
+    from_peer_ids: UInt64VectorBuilder,
+    from_peer_addrs: StringVectorBuilder,
+    to_peer_ids: UInt64VectorBuilder,
+    to_peer_addrs: StringVectorBuilder,
+    loads: Float64VectorBuilder,
+    states: StringVectorBuilder,
+    procedure_ids: StringVectorBuilder,
+    errors: StringVectorBuilder,
+    planned_times: TimestampMillisecondVectorBuilder,
+}
+
+impl InformationSchemaRegionBalanceMovesBuilder {
+    fn new(schema: SchemaRef, catalog_manager: Weak<dyn CatalogManager>) -> Self {
+        Self {
+            schema,
+            catalog_manager,
+            region_ids: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            table_ids: UInt32VectorBuilder::with_capacity(INIT_CAPACITY),
+            region_numbers: UInt32VectorBuilder::with_capacity(INIT_CAPACITY),
+            from_peer_ids: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            from_peer_addrs: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            to_peer_ids: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            to_peer_addrs: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            loads: Float64VectorBuilder::with_capacity(INIT_CAPACITY),
+            states: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            procedure_ids: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            errors: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            planned_times: TimestampMillisecondVectorBuilder::with_capacity(INIT_CAPACITY),
+        }
+    }
+
+    /// Construct a new `InformationSchemaRegionBalanceMoves` from the collected data.
+    async fn make_region_balance_moves(
+        &mut self,
+        request: Option<ScanRequest>,
+    ) -> Result<RecordBatch> {
+        let predicates = Predicates::from_scan_request(&request);
+        let information_extension = utils::information_extension(&self.catalog_manager)?;
+        let region_moves = information_extension.region_moves().await?;
+        for region_move in region_moves {
+            self.add_region_move(&predicates, region_move);
+        }
+        self.finish()
+    }
+
+    fn add_region_move(&mut self, predicate: &Predicates, region_move: RegionMove) {
+        let state = region_move.state.to_string();
+        let planned_time =
+            TimestampMillisecond(Timestamp::new_millisecond(region_move.planned_at_ms));
+        let row = [
+            (REGION_ID, &Value::from(region_move.region_id.as_u64())),
+            (TABLE_ID, &Value::from(region_move.region_id.table_id())),
+            (
+                REGION_NUMBER,
+                &Value::from(region_move.region_id.region_number()),
+            ),
+            (FROM_PEER_ID, &Value::from(region_move.from_peer.id)),
+            (
+                FROM_PEER_ADDR,
+                &Value::from(region_move.from_peer.addr.as_str()),
+            ),
+            (TO_PEER_ID, &Value::from(region_move.to_peer.id)),
+            (
+                TO_PEER_ADDR,
+                &Value::from(region_move.to_peer.addr.as_str()),
+            ),
+            (LOAD, &Value::from(region_move.load)),
+            (STATE, &Value::from(state.as_str())),
+            (PLANNED_TIME, &Value::from(planned_time)),
+        ];
+
+        if !predicate.eval(&row) {
+            return;
+        }
+
+        self.region_ids.push(Some(region_move.region_id.as_u64()));
+        self.table_ids.push(Some(region_move.region_id.table_id()));
+        self.region_numbers
+            .push(Some(region_move.region_id.region_number()));
+        self.from_peer_ids.push(Some(region_move.from_peer.id));
+        self.from_peer_addrs.push(Some(&region_move.from_peer.addr));
+        self.to_peer_ids.push(Some(region_move.to_peer.id));
+        self.to_peer_addrs.push(Some(&region_move.to_peer.addr));
+        self.loads.push(Some(region_move.load));
+        self.states.push(Some(&state));
+        self.procedure_ids.push(region_move.procedure_id.as_deref());
+        self.errors.push(region_move.error.as_deref());
+        self.planned_times.push(Some(planned_time));
+    }
+
+    fn finish(&mut self) -> Result<RecordBatch> {
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(self.region_ids.finish()),
+            Arc::new(self.table_ids.finish()),
+            Arc::new(self.region_numbers.finish()),
+            Arc::new(self.from_peer_ids.finish()),
+            Arc::new(self.from_peer_addrs.finish()),
+            Arc::new(self.to_peer_ids.finish()),
+            Arc::new(self.to_peer_addrs.finish()),
+            Arc::new(self.loads.finish()),
+            Arc::new(self.states.finish()),
+            Arc::new(self.procedure_ids.finish()),
+            Arc::new(self.errors.finish()),
+            Arc::new(self.planned_times.finish()),
+        ];
+
+        RecordBatch::new(self.schema.clone(), columns).context(CreateRecordBatchSnafu)
+    }
+}
+
+impl DfPartitionStream for InformationSchemaRegionBalanceMoves {
+    fn schema(&self) -> &ArrowSchemaRef {
+        self.schema.arrow_schema()
+    }
+
+    fn execute(&self, _: Arc<TaskContext>) -> DfSendableRecordBatchStream {
+        let schema = self.schema.arrow_schema().clone();
+        let mut builder = self.builder();
+        Box::pin(DfRecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::once(async move {
+                builder
+                    .make_region_balance_moves(None)
+                    .await
+                    .map(|x| x.into_df_record_batch())
+                    .map_err(Into::into)
+            }),
+        ))
+    }
+}
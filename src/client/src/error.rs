@@ -40,6 +40,14 @@ pub enum Error {
         source: BoxedError,
     },
 
+    #[snafu(display("Failed to do Flight action {}, code: {}", action_type, tonic_code))]
+    FlightAction {
+        addr: String,
+        action_type: String,
+        tonic_code: Code,
+        source: BoxedError,
+    },
+
     #[snafu(display("Failed to convert FlightData"))]
     ConvertFlightData {
         #[snafu(implicit)]
@@ -153,6 +161,7 @@ impl ErrorExt for Error {
 
             Error::Server { code, .. } | Error::Tonic { code, .. } => *code,
             Error::FlightGet { source, .. }
+            | Error::FlightAction { source, .. }
             | Error::RegionServer { source, .. }
             | Error::FlowServer { source, .. } => source.status_code(),
             Error::CreateChannel { source, .. }
@@ -18,14 +18,16 @@ use api::region::RegionResponse;
 use api::v1::region::RegionRequest;
 use api::v1::ResponseHeader;
 use arc_swap::ArcSwapOption;
-use arrow_flight::Ticket;
+use arrow_flight::{Action, Ticket};
 use async_stream::stream;
 use async_trait::async_trait;
 use common_error::ext::BoxedError;
 use common_error::status_code::StatusCode;
 use common_grpc::flight::{FlightDecoder, FlightMessage};
 use common_meta::error::{self as meta_error, Result as MetaResult};
-use common_meta::node_manager::Datanode;
+use common_meta::node_manager::{
+    Datanode, BACKUP_REGION_ACTION, COPY_REGION_FROM_ACTION, RESTORE_REGION_ACTION,
+};
 use common_query::request::QueryRequest;
 use common_recordbatch::error::ExternalSnafu;
 use common_recordbatch::{RecordBatch, RecordBatchStreamWrapper, SendableRecordBatchStream};
@@ -33,12 +35,16 @@ use common_telemetry::error;
 use common_telemetry::tracing_context::TracingContext;
 use prost::Message;
 use query::query_engine::DefaultSerializer;
-use snafu::{location, OptionExt, ResultExt};
+use snafu::{location, IntoError, OptionExt, ResultExt};
+use store_api::region_engine::{
+    BackupRegionRequest, BackupRegionResponse, CopyRegionFromRequest, CopyRegionFromResponse,
+    RestoreRegionRequest, RestoreRegionResponse,
+};
 use substrait::{DFLogicalSubstraitConvertor, SubstraitPlan};
 use tokio_stream::StreamExt;
 
 use crate::error::{
-    self, ConvertFlightDataSnafu, FlightGetSnafu, IllegalDatabaseResponseSnafu,
+    self, ConvertFlightDataSnafu, FlightActionSnafu, FlightGetSnafu, IllegalDatabaseResponseSnafu,
     IllegalFlightMessagesSnafu, MissingFieldSnafu, Result, ServerSnafu,
 };
 use crate::{metrics, Client, Error};
@@ -88,6 +94,45 @@ impl Datanode for RegionRequester {
             .map_err(BoxedError::new)
             .context(meta_error::ExternalSnafu)
     }
+
+    async fn backup_region(
+        &self,
+        request: BackupRegionRequest,
+    ) -> MetaResult<BackupRegionResponse> {
+        let body = serde_json::to_vec(&request).context(meta_error::SerdeJsonSnafu)?;
+        let response = self
+            .do_action_inner(BACKUP_REGION_ACTION, body)
+            .await
+            .map_err(BoxedError::new)
+            .context(meta_error::ExternalSnafu)?;
+        serde_json::from_slice(&response).context(meta_error::SerdeJsonSnafu)
+    }
+
+    async fn restore_region(
+        &self,
+        request: RestoreRegionRequest,
+    ) -> MetaResult<RestoreRegionResponse> {
+        let body = serde_json::to_vec(&request).context(meta_error::SerdeJsonSnafu)?;
+        let response = self
+            .do_action_inner(RESTORE_REGION_ACTION, body)
+            .await
+            .map_err(BoxedError::new)
+            .context(meta_error::ExternalSnafu)?;
+        serde_json::from_slice(&response).context(meta_error::SerdeJsonSnafu)
+    }
+
+    async fn copy_region_from(
+        &self,
+        request: CopyRegionFromRequest,
+    ) -> MetaResult<CopyRegionFromResponse> {
+        let body = serde_json::to_vec(&request).context(meta_error::SerdeJsonSnafu)?;
+        let response = self
+            .do_action_inner(COPY_REGION_FROM_ACTION, body)
+            .await
+            .map_err(BoxedError::new)
+            .context(meta_error::ExternalSnafu)?;
+        serde_json::from_slice(&response).context(meta_error::SerdeJsonSnafu)
+    }
 }
 
 impl RegionRequester {
@@ -99,6 +144,36 @@ impl RegionRequester {
         }
     }
 
+    /// Does the Flight action and returns the body of the first result.
+    pub async fn do_action_inner(&self, action_type: &str, body: Vec<u8>) -> Result<Vec<u8>> {
+        let mut flight_client = self
+            .client
+            .make_flight_client(self.send_compression, self.accept_compression)?;
+        let action = Action {
+            r#type: action_type.to_string(),
+            body: body.into(),
+        };
+        let mut results = flight_client
+            .mut_inner()
+            .do_action(action)
+            .await
+            .map_err(|e| {
+                let tonic_code = e.code();
+                FlightActionSnafu {
+                    addr: flight_client.addr().to_string(),
+                    action_type,
+                    tonic_code,
+                }
+                .into_error(BoxedError::new(error::Error::from(e)))
+            })?
+            .into_inner();
+
+        let result = results.next().await.context(IllegalFlightMessagesSnafu {
+            reason: "Expect the action result not to be empty",
+        })??;
+        Ok(result.body.to_vec())
+    }
+
     pub async fn do_get_inner(&self, ticket: Ticket) -> Result<SendableRecordBatchStream> {
         let mut flight_client = self
             .client
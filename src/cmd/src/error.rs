@@ -43,6 +43,13 @@ pub enum Error {
         source: common_meta::error::Error,
     },
 
+    #[snafu(display("Failed to init repartition manager"))]
+    InitRepartitionManager {
+        #[snafu(implicit)]
+        location: Location,
+        source: meta_srv::error::Error,
+    },
+
     #[snafu(display("Failed to init default timezone"))]
     InitTimezone {
         #[snafu(implicit)]
@@ -324,6 +331,7 @@ impl ErrorExt for Error {
             Error::InitMetadata { source, .. } | Error::InitDdlManager { source, .. } => {
                 source.status_code()
             }
+            Error::InitRepartitionManager { source, .. } => source.status_code(),
 
             Error::MissingConfig { .. }
             | Error::LoadLayeredConfig { .. }
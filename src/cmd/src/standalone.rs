@@ -38,6 +38,7 @@ use common_meta::ddl::{DdlContext, NoopRegionFailureDetectorControl};
 use common_meta::ddl_manager::DdlManager;
 use common_meta::key::flow::flow_state::FlowStat;
 use common_meta::key::flow::FlowMetadataManager;
+use common_meta::key::region_balance::RegionMove;
 use common_meta::key::{TableMetadataManager, TableMetadataManagerRef};
 use common_meta::kv_backend::KvBackendRef;
 use common_meta::peer::Peer;
@@ -66,18 +67,21 @@ use flow::{
 use frontend::frontend::{Frontend, FrontendOptions};
 use frontend::instance::builder::FrontendBuilder;
 use frontend::instance::{Instance as FeInstance, StandaloneDatanodeManager};
+use frontend::promql_cache::PromqlCacheOptions;
 use frontend::server::Services;
 use frontend::service_config::{
     InfluxdbOptions, JaegerOptions, MysqlOptions, OpentsdbOptions, PostgresOptions,
     PromStoreOptions,
 };
 use meta_srv::metasrv::{FLOW_ID_SEQ, TABLE_ID_SEQ};
+use meta_srv::procedure::repartition::{DefaultContextFactory, RepartitionManager};
 use mito2::config::MitoConfig;
 use query::options::QueryOptions;
 use serde::{Deserialize, Serialize};
 use servers::export_metrics::{ExportMetricsOption, ExportMetricsTask};
 use servers::grpc::GrpcOptions;
 use servers::http::HttpOptions;
+use servers::prom_rule::RuleOptions;
 use servers::tls::{TlsMode, TlsOption};
 use snafu::ResultExt;
 use tokio::sync::RwLock;
@@ -143,6 +147,8 @@ pub struct StandaloneOptions {
     pub influxdb: InfluxdbOptions,
     pub jaeger: JaegerOptions,
     pub prom_store: PromStoreOptions,
+    pub rule: RuleOptions,
+    pub promql_cache: PromqlCacheOptions,
     pub wal: DatanodeWalConfig,
     pub storage: StorageConfig,
     pub metadata_store: KvBackendConfig,
@@ -175,6 +181,8 @@ impl Default for StandaloneOptions {
             influxdb: InfluxdbOptions::default(),
             jaeger: JaegerOptions::default(),
             prom_store: PromStoreOptions::default(),
+            rule: RuleOptions::default(),
+            promql_cache: PromqlCacheOptions::default(),
             wal: DatanodeWalConfig::default(),
             storage: StorageConfig::default(),
             metadata_store: KvBackendConfig::default(),
@@ -227,6 +235,8 @@ impl StandaloneOptions {
             influxdb: cloned_opts.influxdb,
             jaeger: cloned_opts.jaeger,
             prom_store: cloned_opts.prom_store,
+            rule: cloned_opts.rule,
+            promql_cache: cloned_opts.promql_cache,
             meta_client: None,
             logging: cloned_opts.logging,
             user_provider: cloned_opts.user_provider,
@@ -630,10 +640,11 @@ impl StartCommand {
             flow_id_sequence,
         ));
 
+        let memory_region_keeper = Arc::new(MemoryRegionKeeper::default());
         let ddl_context = DdlContext {
             node_manager: node_manager.clone(),
             cache_invalidator: layered_cache_registry.clone(),
-            memory_region_keeper: Arc::new(MemoryRegionKeeper::default()),
+            memory_region_keeper: memory_region_keeper.clone(),
             leader_region_registry: Arc::new(LeaderRegionRegistry::default()),
             table_metadata_manager: table_metadata_manager.clone(),
             table_metadata_allocator: table_metadata_allocator.clone(),
@@ -642,8 +653,21 @@ impl StartCommand {
             region_failure_detector_controller: Arc::new(NoopRegionFailureDetectorControl),
         };
 
+        let repartition_manager = RepartitionManager::new(
+            procedure_manager.clone(),
+            DefaultContextFactory::new(
+                table_metadata_manager.clone(),
+                node_manager.clone(),
+                memory_region_keeper,
+                layered_cache_registry.clone(),
+            ),
+        );
+        repartition_manager
+            .try_start()
+            .context(error::InitRepartitionManagerSnafu)?;
         let ddl_manager = DdlManager::try_new(ddl_context, procedure_manager.clone(), true)
-            .context(error::InitDdlManagerSnafu)?;
+            .context(error::InitDdlManagerSnafu)?
+            .with_repartition_ddl_manager(Some(Arc::new(repartition_manager)));
         #[cfg(feature = "enterprise")]
         let ddl_manager = {
             let trigger_ddl_manager: Option<common_meta::ddl_manager::TriggerDdlManagerRef> =
@@ -852,6 +876,11 @@ impl InformationExtension for StandaloneInformationExtension {
                 .await,
         ))
     }
+
+    async fn region_moves(&self) -> std::result::Result<Vec<RegionMove>, Self::Error> {
+        // There is no region balancer in standalone mode.
+        Ok(vec![])
+    }
 }
 
 #[cfg(test)]
@@ -237,7 +237,7 @@ fn test_load_flownode_example_config() {
             // to avoid overwhelming the frontend with too many queries
             query: QueryOptions {
                 parallelism: 1,
-                allow_query_fallback: false,
+                ..Default::default()
             },
             meta_client: Some(MetaClientOptions {
                 metasrv_addrs: vec!["127.0.0.1:3002".to_string()],
@@ -104,6 +104,8 @@ pub const INFORMATION_SCHEMA_PROCEDURE_INFO_TABLE_ID: u32 = 34;
 pub const INFORMATION_SCHEMA_REGION_STATISTICS_TABLE_ID: u32 = 35;
 /// id for information_schema.process_list
 pub const INFORMATION_SCHEMA_PROCESS_LIST_TABLE_ID: u32 = 36;
+/// id for information_schema.region_balance_moves
+pub const INFORMATION_SCHEMA_REGION_BALANCE_MOVES_TABLE_ID: u32 = 37;
 
 // ----- End of information_schema tables -----
 
@@ -31,11 +31,13 @@ use crate::scalars::expression::ExpressionFunction;
 use crate::scalars::hll_count::HllCalcFunction;
 use crate::scalars::ip::IpFunctions;
 use crate::scalars::json::JsonFunction;
+use crate::scalars::map::MapFunction;
 use crate::scalars::matches::MatchesFunction;
 use crate::scalars::matches_term::MatchesTermFunction;
 use crate::scalars::math::MathFunction;
 use crate::scalars::timestamp::TimestampFunction;
 use crate::scalars::uddsketch_calc::UddSketchCalcFunction;
+use crate::scalars::uuid::UuidFunctions;
 use crate::scalars::vector::VectorFunction as VectorScalarFunction;
 use crate::system::SystemFunction;
 
@@ -137,6 +139,10 @@ pub static FUNCTION_REGISTRY: Lazy<Arc<FunctionRegistry>> = Lazy::new(|| {
     // Ip functions
     IpFunctions::register(&function_registry);
 
+    // Uuid and map related functions
+    UuidFunctions::register(&function_registry);
+    MapFunction::register(&function_registry);
+
     // Approximate functions
     ApproximateFunction::register(&function_registry);
 
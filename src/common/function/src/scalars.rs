@@ -17,6 +17,7 @@ pub mod expression;
 #[cfg(feature = "geo")]
 pub mod geo;
 pub mod json;
+pub mod map;
 pub mod matches;
 pub mod matches_term;
 pub mod math;
@@ -29,3 +30,4 @@ pub(crate) mod test;
 pub(crate) mod timestamp;
 pub(crate) mod uddsketch_calc;
 pub mod udf;
+pub mod uuid;
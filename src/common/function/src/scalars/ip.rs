@@ -13,11 +13,13 @@
 // limitations under the License.
 
 mod cidr;
+pub mod inet;
 mod ipv4;
 mod ipv6;
 mod range;
 
 use cidr::{Ipv4ToCidr, Ipv6ToCidr};
+use inet::{IpInCidr, IpToString, StringToIp};
 use ipv4::{Ipv4NumToString, Ipv4StringToNum};
 use ipv6::{Ipv6NumToString, Ipv6StringToNum};
 use range::{Ipv4InRange, Ipv6InRange};
@@ -39,5 +41,10 @@ impl IpFunctions {
         registry.register_scalar(Ipv6StringToNum);
         registry.register_scalar(Ipv6ToCidr);
         registry.register_scalar(Ipv6InRange);
+
+        // Register functions of the ip type
+        registry.register_scalar(IpToString);
+        registry.register_scalar(StringToIp);
+        registry.register_scalar(IpInCidr);
     }
 }
@@ -0,0 +1,307 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_query::error::{InvalidFuncArgsSnafu, Result};
+use common_query::prelude::{Signature, TypeSignature};
+use datafusion::logical_expr::Volatility;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::scalars::ScalarVectorBuilder;
+use datatypes::types::{
+    ip_type_value_to_string, parse_cidr_to_ip_type_range, parse_string_to_ip_type_value,
+};
+use datatypes::vectors::{
+    BinaryVectorBuilder, BooleanVectorBuilder, MutableVector, StringVectorBuilder, VectorRef,
+};
+use derive_more::Display;
+use snafu::ensure;
+
+use crate::function::{Function, FunctionContext};
+
+/// Function that checks if an address of the ip type is within a specified CIDR range.
+///
+/// The CIDR range is provided as a string, and can be either an IPv4 or an IPv6 range.
+/// Returns boolean result indicating whether the IP is in the range.
+///
+/// Filters like `ip_in_cidr(client_ip, '10.0.0.0/8')` on an ip column are also used by
+/// the storage engine to prune files and row groups with min/max statistics and indexes.
+///
+/// Examples:
+/// - ip_in_cidr(string_to_ip('10.1.2.3'), '10.0.0.0/8') -> true
+/// - ip_in_cidr(string_to_ip('2001:db8::1'), '2001:db8::/32') -> true
+/// - ip_in_cidr(string_to_ip('10.1.2.3'), '2001:db8::/32') -> false
+#[derive(Clone, Debug, Default, Display)]
+#[display("{}", self.name())]
+pub struct IpInCidr;
+
+impl Function for IpInCidr {
+    fn name(&self) -> &str {
+        "ip_in_cidr"
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::boolean_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::new(
+            TypeSignature::Exact(vec![
+                ConcreteDataType::ip_datatype(),
+                ConcreteDataType::string_datatype(),
+            ]),
+            Volatility::Immutable,
+        )
+    }
+
+    fn eval(&self, _func_ctx: &FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 2,
+            InvalidFuncArgsSnafu {
+                err_msg: format!("Expected 2 arguments, got {}", columns.len())
+            }
+        );
+
+        let ip_vec = &columns[0];
+        let range_vec = &columns[1];
+        let size = ip_vec.len();
+
+        ensure!(
+            range_vec.len() == size,
+            InvalidFuncArgsSnafu {
+                err_msg: "IP addresses and CIDR ranges must have the same number of rows"
+                    .to_string()
+            }
+        );
+
+        let mut results = BooleanVectorBuilder::with_capacity(size);
+
+        for i in 0..size {
+            let ip = ip_vec.get_ref(i);
+            let range = range_vec.get_ref(i);
+
+            let in_range = match (ip.as_binary(), range.as_string()) {
+                (Ok(Some(ip)), Ok(Some(range))) => {
+                    let (first, last) = parse_cidr_to_ip_type_range(range).map_err(|e| {
+                        InvalidFuncArgsSnafu {
+                            err_msg: e.to_string(),
+                        }
+                        .build()
+                    })?;
+                    Some(first.as_slice() <= ip && ip <= last.as_slice())
+                }
+                _ => None,
+            };
+
+            results.push(in_range);
+        }
+
+        Ok(results.to_vector())
+    }
+}
+
+/// Converts an address of the ip type into its string form,
+/// e.g. `10.0.0.1` or `2001:db8::1`.
+#[derive(Clone, Debug, Default, Display)]
+#[display("{}", self.name())]
+pub struct IpToString;
+
+impl Function for IpToString {
+    fn name(&self) -> &str {
+        "ip_to_string"
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::string_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::new(
+            TypeSignature::Exact(vec![ConcreteDataType::ip_datatype()]),
+            Volatility::Immutable,
+        )
+    }
+
+    fn eval(&self, _func_ctx: &FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 1,
+            InvalidFuncArgsSnafu {
+                err_msg: format!("Expected 1 argument, got {}", columns.len())
+            }
+        );
+
+        let ip_vec = &columns[0];
+        let size = ip_vec.len();
+        let mut results = StringVectorBuilder::with_capacity(size);
+
+        for i in 0..size {
+            let ip = ip_vec.get_ref(i);
+            let result = match ip.as_binary() {
+                Ok(Some(ip)) => Some(ip_type_value_to_string(ip).map_err(|e| {
+                    InvalidFuncArgsSnafu {
+                        err_msg: e.to_string(),
+                    }
+                    .build()
+                })?),
+                _ => None,
+            };
+            results.push(result.as_deref());
+        }
+
+        Ok(results.to_vector())
+    }
+}
+
+/// Parses an IPv4 or IPv6 address string into the ip type,
+/// e.g. `string_to_ip('10.0.0.1')`.
+#[derive(Clone, Debug, Default, Display)]
+#[display("{}", self.name())]
+pub struct StringToIp;
+
+impl Function for StringToIp {
+    fn name(&self) -> &str {
+        "string_to_ip"
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::ip_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::new(
+            TypeSignature::Exact(vec![ConcreteDataType::string_datatype()]),
+            Volatility::Immutable,
+        )
+    }
+
+    fn eval(&self, _func_ctx: &FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 1,
+            InvalidFuncArgsSnafu {
+                err_msg: format!("Expected 1 argument, got {}", columns.len())
+            }
+        );
+
+        let string_vec = &columns[0];
+        let size = string_vec.len();
+        let mut results = BinaryVectorBuilder::with_capacity(size);
+
+        for i in 0..size {
+            let s = string_vec.get_ref(i);
+            let result = match s.as_string() {
+                Ok(Some(s)) => Some(parse_string_to_ip_type_value(s).map_err(|e| {
+                    InvalidFuncArgsSnafu {
+                        err_msg: e.to_string(),
+                    }
+                    .build()
+                })?),
+                _ => None,
+            };
+            results.push(result.as_deref());
+        }
+
+        Ok(results.to_vector())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::scalars::ScalarVector;
+    use datatypes::vectors::{BinaryVector, BooleanVector, StringVector};
+
+    use super::*;
+
+    #[test]
+    fn test_ip_string_conversion() {
+        let to_ip = StringToIp;
+        let to_string = IpToString;
+        assert_eq!("string_to_ip", to_ip.name());
+        assert_eq!("ip_to_string", to_string.name());
+        assert_eq!(
+            ConcreteDataType::ip_datatype(),
+            to_ip
+                .return_type(&[ConcreteDataType::string_datatype()])
+                .unwrap()
+        );
+
+        let strings = StringVector::from(vec![Some("10.0.0.1"), None, Some("2001:0db8::0001")]);
+        let args: Vec<VectorRef> = vec![Arc::new(strings)];
+        let ips = to_ip.eval(&FunctionContext::default(), &args).unwrap();
+        assert_eq!(3, ips.len());
+        assert_eq!(16, ips.get_ref(0).as_binary().unwrap().unwrap().len());
+        assert!(ips.is_null(1));
+
+        let strings = to_string.eval(&FunctionContext::default(), &[ips]).unwrap();
+        assert_eq!("10.0.0.1", strings.get_ref(0).as_string().unwrap().unwrap());
+        assert!(strings.is_null(1));
+        assert_eq!(
+            "2001:db8::1",
+            strings.get_ref(2).as_string().unwrap().unwrap()
+        );
+
+        let args: Vec<VectorRef> = vec![Arc::new(StringVector::from(vec!["10.0.0.0/8"]))];
+        assert!(to_ip.eval(&FunctionContext::default(), &args).is_err());
+        let args: Vec<VectorRef> = vec![Arc::new(BinaryVector::from_vec(vec![vec![1u8, 2]]))];
+        assert!(to_string.eval(&FunctionContext::default(), &args).is_err());
+    }
+
+    #[test]
+    fn test_ip_in_cidr() {
+        let func = IpInCidr;
+        assert_eq!("ip_in_cidr", func.name());
+        assert_eq!(
+            ConcreteDataType::boolean_datatype(),
+            func.return_type(&[]).unwrap()
+        );
+
+        let ips = [
+            "10.1.2.3",
+            "11.0.0.1",
+            "2001:db8::1",
+            "2001:db9::1",
+            "10.1.2.3",
+        ]
+        .map(|s| Some(parse_string_to_ip_type_value(s).unwrap()));
+        let ips: VectorRef = Arc::new(BinaryVector::from(
+            ips.into_iter().chain([None]).collect::<Vec<_>>(),
+        ));
+        let ranges: VectorRef = Arc::new(StringVector::from(vec![
+            "10.0.0.0/8",
+            "10.0.0.0/8",
+            "2001:db8::/32",
+            "2001:db8::/32",
+            "2001:db8::/32",
+            "10.0.0.0/8",
+        ]));
+
+        let result = func
+            .eval(&FunctionContext::default(), &[ips.clone(), ranges])
+            .unwrap();
+        let expected = BooleanVector::from(vec![
+            Some(true),
+            Some(false),
+            Some(true),
+            Some(false),
+            Some(false),
+            None,
+        ]);
+        let result = result.as_any().downcast_ref::<BooleanVector>().unwrap();
+        assert_eq!(&expected, result);
+
+        let ranges: VectorRef = Arc::new(StringVector::from(vec!["10.0.0.0/40"; 6]));
+        assert!(func
+            .eval(&FunctionContext::default(), &[ips, ranges])
+            .is_err());
+    }
+}
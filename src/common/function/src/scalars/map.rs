@@ -0,0 +1,168 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{self, Display};
+
+use common_query::error::{InvalidFuncArgsSnafu, Result};
+use common_query::prelude::Signature;
+use datafusion::logical_expr::Volatility;
+use datatypes::data_type::ConcreteDataType;
+use datatypes::prelude::{Value, VectorRef};
+use datatypes::scalars::ScalarVectorBuilder;
+use datatypes::types::decode_map_type_value;
+use datatypes::vectors::{MutableVector, StringVectorBuilder};
+use snafu::ensure;
+
+use crate::function::{Function, FunctionContext};
+use crate::function_registry::FunctionRegistry;
+
+pub(crate) struct MapFunction;
+
+impl MapFunction {
+    pub fn register(registry: &FunctionRegistry) {
+        registry.register_scalar(MapGetFunction);
+    }
+}
+
+/// Gets the value of the given key from a map, e.g. `map_get(attributes, 'service.name')`.
+///
+/// Keys are compared by their string form, and the value is returned as a string.
+/// Returns null if the key doesn't exist in the map.
+#[derive(Clone, Debug, Default)]
+pub struct MapGetFunction;
+
+const NAME: &str = "map_get";
+
+impl Function for MapGetFunction {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::string_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::exact(
+            vec![
+                ConcreteDataType::binary_datatype(),
+                ConcreteDataType::string_datatype(),
+            ],
+            Volatility::Immutable,
+        )
+    }
+
+    fn eval(&self, _func_ctx: &FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 2,
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect exactly two, have: {}",
+                    columns.len()
+                ),
+            }
+        );
+        let maps = &columns[0];
+        let keys = &columns[1];
+
+        let size = maps.len();
+        let mut results = StringVectorBuilder::with_capacity(size);
+        for i in 0..size {
+            let map = maps.get_ref(i);
+            let key = keys.get_ref(i);
+            let result = match (map.as_binary(), key.as_string()) {
+                (Ok(Some(map)), Ok(Some(key))) => {
+                    let (_, entries) = decode_map_type_value(map).map_err(|e| {
+                        InvalidFuncArgsSnafu {
+                            err_msg: e.to_string(),
+                        }
+                        .build()
+                    })?;
+                    entries
+                        .into_iter()
+                        .find(|(k, _)| k.to_string() == key)
+                        .and_then(|(_, v)| match v {
+                            Value::Null => None,
+                            v => Some(v.to_string()),
+                        })
+                }
+                _ => None,
+            };
+            results.push(result.as_deref());
+        }
+
+        Ok(results.to_vector())
+    }
+}
+
+impl Display for MapGetFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MAP_GET")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::types::MapType;
+    use datatypes::vectors::{BinaryVector, StringVector};
+
+    use super::*;
+
+    #[test]
+    fn test_map_get_function() {
+        let map_get = MapGetFunction;
+        assert_eq!("map_get", map_get.name());
+
+        let map_type = MapType::new(
+            ConcreteDataType::string_datatype(),
+            ConcreteDataType::int64_datatype(),
+        );
+        let maps = vec![
+            Some(
+                map_type
+                    .encode_entries([
+                        (Value::from("a"), Value::Int64(1)),
+                        (Value::from("b"), Value::Null),
+                    ])
+                    .unwrap(),
+            ),
+            Some(map_type.encode_entries([]).unwrap()),
+            None,
+        ];
+        let maps: VectorRef = Arc::new(BinaryVector::from(maps));
+
+        for (key, expected) in [
+            ("a", vec![Some("1"), None, None]),
+            ("b", vec![None, None, None]),
+            ("c", vec![None, None, None]),
+        ] {
+            let keys: VectorRef = Arc::new(StringVector::from(vec![key; 3]));
+            let result = map_get
+                .eval(&FunctionContext::default(), &[maps.clone(), keys])
+                .unwrap();
+            let result = (0..result.len())
+                .map(|i| result.get_ref(i).as_string().unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(expected, result);
+        }
+
+        let invalid: VectorRef = Arc::new(BinaryVector::from(vec![Some(b"invalid".to_vec())]));
+        let keys: VectorRef = Arc::new(StringVector::from(vec!["a"]));
+        assert!(map_get
+            .eval(&FunctionContext::default(), &[invalid, keys])
+            .is_err());
+    }
+}
@@ -0,0 +1,32 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod gen_random_uuid;
+mod uuid_string;
+
+use datafusion_expr::ScalarUDF;
+use gen_random_uuid::GenRandomUuidFunction;
+use uuid_string::{StringToUuidFunction, UuidToStringFunction};
+
+use crate::function_registry::FunctionRegistry;
+
+pub(crate) struct UuidFunctions;
+
+impl UuidFunctions {
+    pub fn register(registry: &FunctionRegistry) {
+        registry.register(ScalarUDF::new_from_impl(GenRandomUuidFunction::default()));
+        registry.register_scalar(UuidToStringFunction);
+        registry.register_scalar(StringToUuidFunction);
+    }
+}
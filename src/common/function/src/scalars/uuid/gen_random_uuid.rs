@@ -0,0 +1,136 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use datafusion::arrow::array::BinaryBuilder;
+use datafusion::arrow::datatypes::{DataType as ArrowDataType, Field, FieldRef};
+use datafusion_expr::{
+    ColumnarValue, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDFImpl, Signature, Volatility,
+};
+use datatypes::schema::{ColumnExtType, TYPE_KEY};
+use datatypes::types::UUID_BYTE_SIZE;
+use uuid::Uuid;
+
+const NAME: &str = "gen_random_uuid";
+
+/// Generates a random (version 4) uuid for each row, in the binary form of the uuid type.
+/// The returned field is marked as the uuid type, so the result is shown and stored as uuid.
+///
+/// It's implemented as a native datafusion function rather than a [Function](crate::function::Function),
+/// because it takes no argument but still has to produce a distinct value for every row.
+#[derive(Debug)]
+pub struct GenRandomUuidFunction {
+    signature: Signature,
+}
+
+impl Default for GenRandomUuidFunction {
+    fn default() -> Self {
+        Self {
+            signature: Signature::nullary(Volatility::Volatile),
+        }
+    }
+}
+
+impl ScalarUDFImpl for GenRandomUuidFunction {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(
+        &self,
+        _arg_types: &[ArrowDataType],
+    ) -> datafusion_common::Result<ArrowDataType> {
+        Ok(ArrowDataType::Binary)
+    }
+
+    fn return_field_from_args(
+        &self,
+        _args: ReturnFieldArgs,
+    ) -> datafusion_common::Result<FieldRef> {
+        let field = Field::new(self.name(), ArrowDataType::Binary, false).with_metadata(
+            HashMap::from([(TYPE_KEY.to_string(), ColumnExtType::Uuid.to_string())]),
+        );
+        Ok(Arc::new(field))
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: ScalarFunctionArgs,
+    ) -> datafusion_common::Result<ColumnarValue> {
+        let mut builder =
+            BinaryBuilder::with_capacity(args.number_rows, args.number_rows * UUID_BYTE_SIZE);
+        for _ in 0..args.number_rows {
+            builder.append_value(Uuid::new_v4().as_bytes());
+        }
+        Ok(ColumnarValue::Array(Arc::new(builder.finish())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::array::{Array, BinaryArray};
+    use datafusion_common::config::ConfigOptions;
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::ColumnSchema;
+
+    use super::*;
+
+    #[test]
+    fn test_gen_random_uuid() {
+        let f = GenRandomUuidFunction::default();
+        assert_eq!("gen_random_uuid", f.name());
+        assert_eq!(ArrowDataType::Binary, f.return_type(&[]).unwrap());
+        let return_field = f
+            .return_field_from_args(ReturnFieldArgs {
+                arg_fields: &[],
+                scalar_arguments: &[],
+            })
+            .unwrap();
+        assert_eq!(
+            ConcreteDataType::uuid_datatype(),
+            ColumnSchema::try_from(return_field.as_ref())
+                .unwrap()
+                .data_type
+        );
+
+        let args = ScalarFunctionArgs {
+            args: vec![],
+            arg_fields: vec![],
+            number_rows: 3,
+            return_field: Arc::new(Field::new("x", ArrowDataType::Binary, false)),
+            config_options: Arc::new(ConfigOptions::default()),
+        };
+        let ColumnarValue::Array(array) = f.invoke_with_args(args).unwrap() else {
+            unreachable!()
+        };
+        let array = array.as_any().downcast_ref::<BinaryArray>().unwrap();
+        assert_eq!(3, array.len());
+        for i in 0..array.len() {
+            let uuid = Uuid::from_slice(array.value(i)).unwrap();
+            assert_eq!(Some(uuid::Version::Random), uuid.get_version());
+        }
+        assert_ne!(array.value(0), array.value(1));
+    }
+}
@@ -0,0 +1,200 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{self, Display};
+
+use common_query::error::{InvalidFuncArgsSnafu, Result};
+use common_query::prelude::Signature;
+use datafusion::logical_expr::Volatility;
+use datatypes::data_type::ConcreteDataType;
+use datatypes::prelude::VectorRef;
+use datatypes::scalars::ScalarVectorBuilder;
+use datatypes::types::{parse_string_to_uuid_type_value, uuid_type_value_to_string};
+use datatypes::vectors::{BinaryVectorBuilder, MutableVector, StringVectorBuilder};
+use snafu::ensure;
+
+use crate::function::{Function, FunctionContext};
+
+/// Converts a uuid into its canonical string form, e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+#[derive(Clone, Debug, Default)]
+pub struct UuidToStringFunction;
+
+const UUID_TO_STRING: &str = "uuid_to_string";
+
+impl Function for UuidToStringFunction {
+    fn name(&self) -> &str {
+        UUID_TO_STRING
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::string_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::exact(
+            vec![ConcreteDataType::uuid_datatype()],
+            Volatility::Immutable,
+        )
+    }
+
+    fn eval(&self, _func_ctx: &FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 1,
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect exactly one, have: {}",
+                    columns.len()
+                ),
+            }
+        );
+        let uuids = &columns[0];
+
+        let size = uuids.len();
+        let mut results = StringVectorBuilder::with_capacity(size);
+        for i in 0..size {
+            let uuid = uuids.get_ref(i);
+            let result = match uuid.as_binary() {
+                Ok(Some(uuid)) => Some(uuid_type_value_to_string(uuid).map_err(|e| {
+                    InvalidFuncArgsSnafu {
+                        err_msg: e.to_string(),
+                    }
+                    .build()
+                })?),
+                _ => None,
+            };
+            results.push(result.as_deref());
+        }
+
+        Ok(results.to_vector())
+    }
+}
+
+impl Display for UuidToStringFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "UUID_TO_STRING")
+    }
+}
+
+/// Parses a string into a uuid, accepting the hyphenated, simple, braced and urn forms.
+#[derive(Clone, Debug, Default)]
+pub struct StringToUuidFunction;
+
+const STRING_TO_UUID: &str = "string_to_uuid";
+
+impl Function for StringToUuidFunction {
+    fn name(&self) -> &str {
+        STRING_TO_UUID
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::uuid_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::exact(
+            vec![ConcreteDataType::string_datatype()],
+            Volatility::Immutable,
+        )
+    }
+
+    fn eval(&self, _func_ctx: &FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 1,
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect exactly one, have: {}",
+                    columns.len()
+                ),
+            }
+        );
+        let strings = &columns[0];
+
+        let size = strings.len();
+        let mut results = BinaryVectorBuilder::with_capacity(size);
+        for i in 0..size {
+            let s = strings.get_ref(i);
+            let result = match s.as_string() {
+                Ok(Some(s)) => Some(parse_string_to_uuid_type_value(s).map_err(|e| {
+                    InvalidFuncArgsSnafu {
+                        err_msg: e.to_string(),
+                    }
+                    .build()
+                })?),
+                _ => None,
+            };
+            results.push(result.as_deref());
+        }
+
+        Ok(results.to_vector())
+    }
+}
+
+impl Display for StringToUuidFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "STRING_TO_UUID")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::scalars::ScalarVector;
+    use datatypes::vectors::{BinaryVector, StringVector};
+
+    use super::*;
+
+    #[test]
+    fn test_uuid_string_conversion() {
+        let to_uuid = StringToUuidFunction;
+        let to_string = UuidToStringFunction;
+        assert_eq!("string_to_uuid", to_uuid.name());
+        assert_eq!("uuid_to_string", to_string.name());
+        assert_eq!(
+            ConcreteDataType::uuid_datatype(),
+            to_uuid
+                .return_type(&[ConcreteDataType::string_datatype()])
+                .unwrap()
+        );
+
+        let strings = StringVector::from(vec![
+            Some("67E5504410B1426F9247BB680E5FE0C8"),
+            None,
+            Some("{00000000-0000-0000-0000-000000000000}"),
+        ]);
+        let args: Vec<VectorRef> = vec![Arc::new(strings)];
+        let uuids = to_uuid.eval(&FunctionContext::default(), &args).unwrap();
+        assert_eq!(3, uuids.len());
+        assert_eq!(16, uuids.get_ref(0).as_binary().unwrap().unwrap().len());
+        assert!(uuids.is_null(1));
+
+        let strings = to_string
+            .eval(&FunctionContext::default(), &[uuids])
+            .unwrap();
+        assert_eq!(
+            "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            strings.get_ref(0).as_string().unwrap().unwrap()
+        );
+        assert!(strings.is_null(1));
+        assert_eq!(
+            "00000000-0000-0000-0000-000000000000",
+            strings.get_ref(2).as_string().unwrap().unwrap()
+        );
+
+        let args: Vec<VectorRef> = vec![Arc::new(StringVector::from(vec!["not a uuid"]))];
+        assert!(to_uuid.eval(&FunctionContext::default(), &args).is_err());
+        let args: Vec<VectorRef> = vec![Arc::new(BinaryVector::from_vec(vec![vec![1u8, 2]]))];
+        assert!(to_string.eval(&FunctionContext::default(), &args).is_err());
+    }
+}
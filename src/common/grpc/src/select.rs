@@ -214,7 +214,10 @@ pub fn values(arrays: &[VectorRef]) -> Result<Values> {
             |x| { convert_to_pb_decimal128(x) }
         ),
         (
-            ConcreteDataType::Vector(_),
+            ConcreteDataType::Vector(_)
+                | ConcreteDataType::Uuid(_)
+                | ConcreteDataType::Map(_)
+                | ConcreteDataType::Ip(_),
             BinaryVector,
             binary_values,
             |x| { x.into() }
@@ -97,7 +97,7 @@ where
                     let key: SchemaNameKey = schema_name.into();
                     self.invalidate_key(&key.to_bytes()).await;
                 }
-                CacheIdent::CreateFlow(_) => {
+                CacheIdent::CreateFlow(_) | CacheIdent::AccessControl => {
                     // Do nothing
                 }
                 CacheIdent::DropFlow(DropFlow {
@@ -28,6 +28,7 @@ use crate::error::{
     InvalidRoleSnafu, ParseNumSnafu, Result,
 };
 use crate::key::flow::flow_state::FlowStat;
+use crate::key::region_balance::RegionMove;
 use crate::peer::Peer;
 
 const CLUSTER_NODE_INFO_PREFIX: &str = "__meta_cluster_node_info";
@@ -56,6 +57,9 @@ pub trait ClusterInfo {
     /// List all flow stats in the cluster.
     async fn list_flow_stats(&self) -> std::result::Result<Option<FlowStat>, Self::Error>;
 
+    /// List the planned and in-flight region moves of the region balancer.
+    async fn list_region_moves(&self) -> std::result::Result<Vec<RegionMove>, Self::Error>;
+
     // TODO(jeremy): Other info, like region status, etc.
 }
 
@@ -34,7 +34,9 @@ use strum::AsRefStr;
 use table::metadata::{RawTableInfo, TableId};
 use table::table_reference::TableReference;
 
-use crate::ddl::create_table_template::{build_template, CreateRequestBuilder};
+use crate::ddl::create_table_template::{
+    apply_table_info_column_ids, build_template, CreateRequestBuilder,
+};
 use crate::ddl::utils::raw_table_info::update_table_info_column_ids;
 use crate::ddl::utils::{
     add_peer_context_if_needed, convert_region_routes_to_detecting_regions,
@@ -170,7 +172,8 @@ impl CreateTableProcedure {
         physical_table_id: Option<TableId>,
     ) -> Result<CreateRequestBuilder> {
         let create_table_expr = &self.creator.data.task.create_table;
-        let template = build_template(create_table_expr)?;
+        let mut template = build_template(create_table_expr)?;
+        apply_table_info_column_ids(&mut template, self.table_info());
         Ok(CreateRequestBuilder::new(template, physical_table_id))
     }
 
@@ -68,6 +68,50 @@ pub(crate) fn build_template_from_raw_table_info(
     Ok(template)
 }
 
+/// Builds a [CreateRequest] of a physical table from a [RawTableInfo].
+///
+/// The column ids are taken from the table info, so the created regions share the
+/// same schema as the existing regions of the table.
+pub fn build_template_from_raw_table_info_for_physical_table(
+    raw_table_info: &RawTableInfo,
+) -> Result<CreateRequest> {
+    let meta = &raw_table_info.meta;
+    let primary_key_indices = &meta.primary_key_indices;
+    let column_defs = meta
+        .schema
+        .column_schemas
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let is_primary_key = primary_key_indices.contains(&i);
+            let column_def = try_as_column_def(c, is_primary_key)
+                .context(error::ConvertColumnDefSnafu { column: &c.name })?;
+            let column_id = meta.column_ids.get(i).copied().unwrap_or(i as u32);
+
+            Ok(RegionColumnDef {
+                column_def: Some(column_def),
+                column_id,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let primary_key = primary_key_indices
+        .iter()
+        .map(|i| column_defs[*i].column_id)
+        .collect();
+    let template = CreateRequest {
+        region_id: 0,
+        engine: meta.engine.clone(),
+        column_defs,
+        primary_key,
+        path: String::new(),
+        options: HashMap::from(&meta.options),
+        partition: None,
+    };
+
+    Ok(template)
+}
+
 pub(crate) fn build_template(create_table_expr: &CreateTableExpr) -> Result<CreateRequest> {
     let column_defs = create_table_expr
         .column_defs
@@ -130,6 +174,43 @@ pub(crate) fn build_template(create_table_expr: &CreateTableExpr) -> Result<Crea
     Ok(template)
 }
 
+/// Replaces the column ids of the template by the column ids in the table info.
+///
+/// The column ids of a table are assigned by the order of columns on creation, unless
+/// the table info carries them, e.g., the table is restored from a backup and its regions
+/// must keep the column ids of the backed up regions. It's a no-op if the table info
+/// doesn't have the ids of all columns.
+pub(crate) fn apply_table_info_column_ids(
+    template: &mut CreateRequest,
+    raw_table_info: &RawTableInfo,
+) {
+    let Some(name_to_ids) = raw_table_info.name_to_ids() else {
+        return;
+    };
+    if name_to_ids.is_empty() {
+        return;
+    }
+
+    let mut id_mapping = HashMap::with_capacity(template.column_defs.len());
+    for column_def in &template.column_defs {
+        let Some(column_id) = column_def
+            .column_def
+            .as_ref()
+            .and_then(|c| name_to_ids.get(&c.name))
+        else {
+            return;
+        };
+        id_mapping.insert(column_def.column_id, *column_id);
+    }
+
+    for column_def in &mut template.column_defs {
+        column_def.column_id = id_mapping[&column_def.column_id];
+    }
+    for column_id in &mut template.primary_key {
+        *column_id = id_mapping[column_id];
+    }
+}
+
 /// Builder for [PbCreateRegionRequest].
 pub struct CreateRequestBuilder {
     template: CreateRequest,
@@ -138,7 +219,7 @@ pub struct CreateRequestBuilder {
 }
 
 impl CreateRequestBuilder {
-    pub(crate) fn new(template: CreateRequest, physical_table_id: Option<TableId>) -> Self {
+    pub fn new(template: CreateRequest, physical_table_id: Option<TableId>) -> Self {
         Self {
             template,
             physical_table_id,
@@ -203,6 +284,58 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_apply_table_info_column_ids() {
+        let column_def = |name: &str, column_id| RegionColumnDef {
+            column_def: Some(ColumnDef {
+                name: name.to_string(),
+                ..Default::default()
+            }),
+            column_id,
+        };
+        let mut template = CreateRequest {
+            region_id: 0,
+            engine: "mito".to_string(),
+            column_defs: vec![
+                column_def("host", 0),
+                column_def("ts", 1),
+                column_def("cpu", 2),
+            ],
+            primary_key: vec![0],
+            path: String::new(),
+            options: Default::default(),
+            partition: None,
+        };
+        let mut raw_table_info =
+            crate::ddl::test_util::create_table::test_create_table_task("foo", 1024).table_info;
+
+        // The table info doesn't have the column ids.
+        raw_table_info.meta.column_ids.clear();
+        let expected = template.clone();
+        apply_table_info_column_ids(&mut template, &raw_table_info);
+        assert_eq!(expected, template);
+
+        raw_table_info.meta.schema.column_schemas = ["host", "ts", "cpu"]
+            .into_iter()
+            .map(|name| {
+                datatypes::schema::ColumnSchema::new(
+                    name,
+                    datatypes::data_type::ConcreteDataType::string_datatype(),
+                    true,
+                )
+            })
+            .collect();
+        raw_table_info.meta.column_ids = vec![3, 0, 5];
+        apply_table_info_column_ids(&mut template, &raw_table_info);
+        let column_ids = template
+            .column_defs
+            .iter()
+            .map(|c| c.column_id)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![3, 0, 5], column_ids);
+        assert_eq!(vec![3], template.primary_key);
+    }
+
     #[test]
     fn test_build_one_sets_partition_expr_per_region() {
         // minimal template
@@ -40,7 +40,7 @@ use crate::ddl::{utils, DdlContext};
 use crate::error::{
     EmptyDdlTasksSnafu, ProcedureOutputSnafu, RegisterProcedureLoaderSnafu, Result,
     SubmitProcedureSnafu, TableInfoNotFoundSnafu, TableNotFoundSnafu, TableRouteNotFoundSnafu,
-    UnexpectedLogicalRouteTableSnafu, WaitProcedureSnafu,
+    UnexpectedLogicalRouteTableSnafu, UnsupportedSnafu, WaitProcedureSnafu,
 };
 use crate::key::table_info::TableInfoValue;
 use crate::key::table_name::TableNameKey;
@@ -57,12 +57,12 @@ use crate::rpc::ddl::DdlTask::DropTrigger;
 use crate::rpc::ddl::DdlTask::{
     AlterDatabase, AlterLogicalTables, AlterTable, CreateDatabase, CreateFlow, CreateLogicalTables,
     CreateTable, CreateView, DropDatabase, DropFlow, DropLogicalTables, DropTable, DropView,
-    TruncateTable,
+    RepartitionTable, TruncateTable,
 };
 use crate::rpc::ddl::{
     AlterDatabaseTask, AlterTableTask, CreateDatabaseTask, CreateFlowTask, CreateTableTask,
     CreateViewTask, DropDatabaseTask, DropFlowTask, DropTableTask, DropViewTask, QueryContext,
-    SubmitDdlTaskRequest, SubmitDdlTaskResponse, TruncateTableTask,
+    RepartitionTableTask, SubmitDdlTaskRequest, SubmitDdlTaskResponse, TruncateTableTask,
 };
 use crate::rpc::router::RegionRoute;
 
@@ -77,6 +77,7 @@ pub struct DdlManager {
     procedure_manager: ProcedureManagerRef,
     #[cfg(feature = "enterprise")]
     trigger_ddl_manager: Option<TriggerDdlManagerRef>,
+    repartition_ddl_manager: Option<RepartitionDdlManagerRef>,
 }
 
 /// This trait is responsible for handling DDL tasks about triggers. e.g.,
@@ -106,6 +107,20 @@ pub trait TriggerDdlManager: Send + Sync {
 #[cfg(feature = "enterprise")]
 pub type TriggerDdlManagerRef = Arc<dyn TriggerDdlManager>;
 
+/// This trait is responsible for handling repartition tasks, i.e., splitting
+/// or merging the regions of a table.
+///
+/// The procedure moves region data between datanodes, so it's implemented by the metasrv.
+#[async_trait::async_trait]
+pub trait RepartitionDdlManager: Send + Sync {
+    async fn repartition_table(
+        &self,
+        repartition_table_task: RepartitionTableTask,
+    ) -> Result<SubmitDdlTaskResponse>;
+}
+
+pub type RepartitionDdlManagerRef = Arc<dyn RepartitionDdlManager>;
+
 macro_rules! procedure_loader_entry {
     ($procedure:ident) => {
         (
@@ -140,6 +155,7 @@ impl DdlManager {
             procedure_manager,
             #[cfg(feature = "enterprise")]
             trigger_ddl_manager: None,
+            repartition_ddl_manager: None,
         };
         if register_loaders {
             manager.register_loaders()?;
@@ -156,6 +172,14 @@ impl DdlManager {
         self
     }
 
+    pub fn with_repartition_ddl_manager(
+        mut self,
+        repartition_ddl_manager: Option<RepartitionDdlManagerRef>,
+    ) -> Self {
+        self.repartition_ddl_manager = repartition_ddl_manager;
+        self
+    }
+
     /// Returns the [TableMetadataManagerRef].
     pub fn table_metadata_manager(&self) -> &TableMetadataManagerRef {
         &self.ddl_context.table_metadata_manager
@@ -479,6 +503,9 @@ impl DdlManager {
                     handle_drop_trigger_task(self, drop_trigger_task, request.query_context.into())
                         .await
                 }
+                RepartitionTable(repartition_table_task) => {
+                    handle_repartition_table_task(self, repartition_table_task).await
+                }
             }
         }
         .trace(span)
@@ -517,6 +544,40 @@ async fn handle_truncate_table_task(
     })
 }
 
+async fn handle_repartition_table_task(
+    ddl_manager: &DdlManager,
+    repartition_table_task: RepartitionTableTask,
+) -> Result<SubmitDdlTaskResponse> {
+    let Some(repartition_ddl_manager) = ddl_manager.repartition_ddl_manager.as_ref() else {
+        return UnsupportedSnafu {
+            operation: "repartition table",
+        }
+        .fail();
+    };
+
+    let table_id = repartition_table_task.table_id;
+    let table_route_value = ddl_manager
+        .table_metadata_manager()
+        .table_route_manager()
+        .table_route_storage()
+        .get(table_id)
+        .await?
+        .context(TableRouteNotFoundSnafu { table_id })?;
+    ensure!(
+        table_route_value.is_physical(),
+        UnexpectedLogicalRouteTableSnafu {
+            err_msg: format!(
+                "{:?} is a non-physical TableRouteValue.",
+                repartition_table_task.table_ref()
+            ),
+        }
+    );
+
+    repartition_ddl_manager
+        .repartition_table(repartition_table_task)
+        .await
+}
+
 async fn handle_alter_table_task(
     ddl_manager: &DdlManager,
     alter_table_task: AlterTableTask,
@@ -396,6 +396,34 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("User not found: '{}'", name))]
+    UserNotFound {
+        name: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("User already exists: '{}'", name))]
+    UserAlreadyExists {
+        name: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Role not found: '{}'", name))]
+    RoleNotFound {
+        name: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Role already exists: '{}'", name))]
+    RoleAlreadyExists {
+        name: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
     #[snafu(display("Flow route not found: '{}'", flow_name))]
     FlowRouteNotFound {
         flow_name: String,
@@ -1158,6 +1186,11 @@ impl ErrorExt for Error {
             }
             ViewAlreadyExists { .. } | TableAlreadyExists { .. } => StatusCode::TableAlreadyExists,
 
+            UserNotFound { .. } => StatusCode::UserNotFound,
+            RoleNotFound { .. } | UserAlreadyExists { .. } | RoleAlreadyExists { .. } => {
+                StatusCode::InvalidArguments
+            }
+
             SubmitProcedure { source, .. }
             | QueryProcedure { source, .. }
             | WaitProcedure { source, .. }
@@ -65,6 +65,16 @@
 //! 13. Topic name to region map key `__topic_region/{topic_name}/{region_id}`
 //!     - Mapping {topic_name} to {region_id}
 //!
+//! 14. Access control keys: `__access_control/user/{name}` and `__access_control/role/{name}`
+//!     - The values are [UserValue](crate::key::access_control::UserValue) and
+//!       [RoleValue](crate::key::access_control::RoleValue) structs; they contain the
+//!       privileges granted to users and roles.
+//!
+//! 15. Region balance state key: `__region_balance/state`
+//!     - The value is a [RegionBalanceStateValue] struct; it contains the region moves planned
+//!       by the metasrv region balancer.
+//!     - This key is only stored in the in-memory backend of the metasrv leader.
+//!
 //! All keys have related managers. The managers take care of the serialization and deserialization
 //! of keys and values, and the interaction with the underlying KV store backend.
 //!
@@ -97,10 +107,12 @@
 //!          {flow_id}/
 //!            {partition_id}
 
+pub mod access_control;
 pub mod catalog_name;
 pub mod datanode_table;
 pub mod flow;
 pub mod node_address;
+pub mod region_balance;
 pub mod runtime_switch;
 mod schema_metadata_manager;
 pub mod schema_name;
@@ -144,6 +156,7 @@ use topic_name::TopicNameManager;
 use topic_region::{TopicRegionKey, TopicRegionManager};
 use view_info::{ViewInfoKey, ViewInfoManager, ViewInfoValue};
 
+use self::access_control::{RoleValue, UserValue};
 use self::catalog_name::{CatalogManager, CatalogNameKey, CatalogNameValue};
 use self::datanode_table::RegionInfo;
 use self::flow::flow_info::FlowInfoValue;
@@ -154,6 +167,7 @@ use self::tombstone::TombstoneManager;
 use crate::error::{self, Result, SerdeJsonSnafu};
 use crate::key::flow::flow_state::FlowStateValue;
 use crate::key::node_address::NodeAddressValue;
+use crate::key::region_balance::RegionBalanceStateValue;
 use crate::key::table_route::TableRouteKey;
 use crate::key::topic_region::TopicRegionValue;
 use crate::key::txn_helper::TxnOpGetResponseSet;
@@ -183,6 +197,8 @@ pub const KAFKA_TOPIC_KEY_PREFIX: &str = "__topic_name/kafka";
 // The legacy topic key prefix is used to store the topic name in previous versions.
 pub const LEGACY_TOPIC_KEY_PREFIX: &str = "__created_wal_topics/kafka";
 pub const TOPIC_REGION_PREFIX: &str = "__topic_region";
+pub const ACCESS_CONTROL_KEY_PREFIX: &str = "__access_control";
+pub const REGION_BALANCE_STATE_KEY: &str = "__region_balance/state";
 
 /// The election key.
 pub const ELECTION_KEY: &str = "__metasrv_election";
@@ -1440,7 +1456,10 @@ impl_metadata_value! {
     SchemaNameValue,
     FlowStateValue,
     PoisonValue,
-    TopicRegionValue
+    TopicRegionValue,
+    UserValue,
+    RoleValue,
+    RegionBalanceStateValue
 }
 
 impl_optional_metadata_value! {
@@ -0,0 +1,613 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Display;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, OptionExt, ResultExt};
+use strum::{Display as StrumDisplay, EnumIter, EnumString, IntoEnumIterator};
+
+use crate::cache_invalidator::{CacheInvalidator, Context};
+use crate::error::{
+    self, Result, RoleAlreadyExistsSnafu, RoleNotFoundSnafu, UserAlreadyExistsSnafu,
+    UserNotFoundSnafu,
+};
+use crate::instruction::CacheIdent;
+use crate::key::{MetadataValue, ACCESS_CONTROL_KEY_PREFIX};
+use crate::kv_backend::KvBackendRef;
+use crate::rpc::store::{CompareAndPutRequest, RangeRequest};
+
+const USER_KEY_PREFIX: &str = "user";
+const ROLE_KEY_PREFIX: &str = "role";
+
+/// A privilege that can be granted to users and roles.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    StrumDisplay,
+    EnumString,
+    EnumIter,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "UPPERCASE", ascii_case_insensitive)]
+pub enum Privilege {
+    Select,
+    Insert,
+    Delete,
+    Create,
+    Alter,
+    Drop,
+}
+
+/// The object that privileges are granted on.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum GrantObject {
+    /// All databases.
+    Global,
+    /// A database and all tables in it.
+    Database { catalog: String, schema: String },
+    /// A table.
+    Table {
+        catalog: String,
+        schema: String,
+        table: String,
+    },
+}
+
+impl GrantObject {
+    /// Returns true if privileges on this object also apply to the `other` object.
+    pub fn covers(&self, other: &GrantObject) -> bool {
+        match (self, other) {
+            (GrantObject::Global, _) => true,
+            (
+                GrantObject::Database { catalog, schema },
+                GrantObject::Database {
+                    catalog: other_catalog,
+                    schema: other_schema,
+                }
+                | GrantObject::Table {
+                    catalog: other_catalog,
+                    schema: other_schema,
+                    ..
+                },
+            ) => catalog == other_catalog && schema == other_schema,
+            (GrantObject::Table { .. }, GrantObject::Table { .. }) => self == other,
+            _ => false,
+        }
+    }
+}
+
+impl Display for GrantObject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GrantObject::Global => write!(f, "*.*"),
+            GrantObject::Database { catalog, schema } => write!(f, "{catalog}.{schema}.*"),
+            GrantObject::Table {
+                catalog,
+                schema,
+                table,
+            } => write!(f, "{catalog}.{schema}.{table}"),
+        }
+    }
+}
+
+/// Privileges granted on an object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrivilegeGrant {
+    pub object: GrantObject,
+    pub privileges: BTreeSet<Privilege>,
+}
+
+/// Privileges granted to a user or a role.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Grants(Vec<PrivilegeGrant>);
+
+impl Grants {
+    /// Grants `privileges` on the `object`.
+    pub fn grant(&mut self, object: GrantObject, privileges: &[Privilege]) {
+        match self.0.iter_mut().find(|grant| grant.object == object) {
+            Some(grant) => grant.privileges.extend(privileges),
+            None => self.0.push(PrivilegeGrant {
+                object,
+                privileges: privileges.iter().copied().collect(),
+            }),
+        }
+    }
+
+    /// Revokes `privileges` granted on exactly the `object`.
+    pub fn revoke(&mut self, object: &GrantObject, privileges: &[Privilege]) {
+        for grant in self.0.iter_mut().filter(|grant| &grant.object == object) {
+            grant
+                .privileges
+                .retain(|privilege| !privileges.contains(privilege));
+        }
+        self.0.retain(|grant| !grant.privileges.is_empty());
+    }
+
+    /// Returns true if the `privilege` is granted on an object that covers the `object`.
+    pub fn allows(&self, privilege: Privilege, object: &GrantObject) -> bool {
+        self.0
+            .iter()
+            .any(|grant| grant.privileges.contains(&privilege) && grant.object.covers(object))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PrivilegeGrant> {
+        self.0.iter()
+    }
+}
+
+/// The value of a user created by `CREATE USER`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserValue {
+    /// Hex encoded hash of the password.
+    pub password_hash: Option<String>,
+    pub roles: BTreeSet<String>,
+    pub grants: Grants,
+}
+
+/// The value of a role created by `CREATE ROLE`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleValue {
+    pub grants: Grants,
+}
+
+/// The user key.
+///
+/// The layout: `__access_control/user/{name}`
+fn user_key(name: &str) -> String {
+    format!("{ACCESS_CONTROL_KEY_PREFIX}/{USER_KEY_PREFIX}/{name}")
+}
+
+/// The role key.
+///
+/// The layout: `__access_control/role/{name}`
+fn role_key(name: &str) -> String {
+    format!("{ACCESS_CONTROL_KEY_PREFIX}/{ROLE_KEY_PREFIX}/{name}")
+}
+
+/// All users and roles at a point in time.
+#[derive(Debug, Default)]
+pub struct AccessControlSnapshot {
+    users: HashMap<String, UserValue>,
+    roles: HashMap<String, RoleValue>,
+}
+
+impl AccessControlSnapshot {
+    pub fn user(&self, name: &str) -> Option<&UserValue> {
+        self.users.get(name)
+    }
+
+    /// Returns true if the `privilege` on the `object` is granted to the `user`,
+    /// either directly or through its roles.
+    pub fn is_granted(&self, user: &UserValue, privilege: Privilege, object: &GrantObject) -> bool {
+        user.grants.allows(privilege, object)
+            || user
+                .roles
+                .iter()
+                .filter_map(|role| self.roles.get(role))
+                .any(|role| role.grants.allows(privilege, object))
+    }
+
+    /// Returns true if all privileges on all databases are granted to the `user`.
+    pub fn is_admin(&self, user: &UserValue) -> bool {
+        Privilege::iter().all(|privilege| self.is_granted(user, privilege, &GrantObject::Global))
+    }
+
+    /// Returns true if any privilege on an object in the database is granted to the `user`.
+    pub fn has_any_in_database(&self, user: &UserValue, catalog: &str, schema: &str) -> bool {
+        let in_database = |grants: &Grants| {
+            grants.iter().any(|grant| match &grant.object {
+                GrantObject::Global => true,
+                GrantObject::Database {
+                    catalog: c,
+                    schema: s,
+                }
+                | GrantObject::Table {
+                    catalog: c,
+                    schema: s,
+                    ..
+                } => c == catalog && s == schema,
+            })
+        };
+        in_database(&user.grants)
+            || user
+                .roles
+                .iter()
+                .filter_map(|role| self.roles.get(role))
+                .any(|role| in_database(&role.grants))
+    }
+}
+
+struct CachedSnapshot {
+    snapshot: Arc<AccessControlSnapshot>,
+    loaded_at: Option<Instant>,
+}
+
+pub type AccessControlManagerRef = Arc<AccessControlManager>;
+
+/// The manager of users, roles and their privileges.
+///
+/// It also caches a snapshot of them for permission checks that can't wait for the
+/// kv backend. The snapshot is reloaded after every change made by this manager.
+pub struct AccessControlManager {
+    kv_backend: KvBackendRef,
+    cached: RwLock<CachedSnapshot>,
+}
+
+impl AccessControlManager {
+    pub fn new(kv_backend: KvBackendRef) -> Self {
+        Self {
+            kv_backend,
+            cached: RwLock::new(CachedSnapshot {
+                snapshot: Arc::default(),
+                loaded_at: None,
+            }),
+        }
+    }
+
+    /// Creates a user. Returns false if the user exists and `if_not_exists` is true.
+    pub async fn create_user(
+        &self,
+        name: &str,
+        password_hash: Option<&[u8]>,
+        if_not_exists: bool,
+    ) -> Result<bool> {
+        let value = UserValue {
+            password_hash: password_hash.map(hex::encode),
+            ..Default::default()
+        };
+        let created = self
+            .kv_backend
+            .put_conditionally(user_key(name).into_bytes(), value.try_as_raw_value()?, true)
+            .await?;
+        if !created && !if_not_exists {
+            return UserAlreadyExistsSnafu { name }.fail();
+        }
+        self.refresh().await?;
+        Ok(created)
+    }
+
+    /// Drops a user. Returns false if the user doesn't exist and `if_exists` is true.
+    pub async fn drop_user(&self, name: &str, if_exists: bool) -> Result<bool> {
+        let prev = self
+            .kv_backend
+            .delete(user_key(name).as_bytes(), true)
+            .await?;
+        if prev.is_none() && !if_exists {
+            return UserNotFoundSnafu { name }.fail();
+        }
+        self.refresh().await?;
+        Ok(prev.is_some())
+    }
+
+    /// Creates a role. Returns false if the role exists and `if_not_exists` is true.
+    pub async fn create_role(&self, name: &str, if_not_exists: bool) -> Result<bool> {
+        let created = self
+            .kv_backend
+            .put_conditionally(
+                role_key(name).into_bytes(),
+                RoleValue::default().try_as_raw_value()?,
+                true,
+            )
+            .await?;
+        if !created && !if_not_exists {
+            return RoleAlreadyExistsSnafu { name }.fail();
+        }
+        self.refresh().await?;
+        Ok(created)
+    }
+
+    /// Drops a role. Returns false if the role doesn't exist and `if_exists` is true.
+    ///
+    /// Users that are granted the role lose its privileges.
+    pub async fn drop_role(&self, name: &str, if_exists: bool) -> Result<bool> {
+        let prev = self
+            .kv_backend
+            .delete(role_key(name).as_bytes(), true)
+            .await?;
+        if prev.is_none() && !if_exists {
+            return RoleNotFoundSnafu { name }.fail();
+        }
+        self.refresh().await?;
+        Ok(prev.is_some())
+    }
+
+    /// Grants `privileges` on the `object` to the user or the role named `grantee`.
+    pub async fn grant_privileges(
+        &self,
+        grantee: &str,
+        object: GrantObject,
+        privileges: &[Privilege],
+    ) -> Result<()> {
+        self.update_grants(grantee, |grants| grants.grant(object.clone(), privileges))
+            .await
+    }
+
+    /// Revokes `privileges` on the `object` from the user or the role named `grantee`.
+    pub async fn revoke_privileges(
+        &self,
+        grantee: &str,
+        object: &GrantObject,
+        privileges: &[Privilege],
+    ) -> Result<()> {
+        self.update_grants(grantee, |grants| grants.revoke(object, privileges))
+            .await
+    }
+
+    /// Grants the `role` to the `user`.
+    pub async fn grant_role(&self, role: &str, user: &str) -> Result<()> {
+        let exists = self.kv_backend.exists(role_key(role).as_bytes()).await?;
+        ensure!(exists, RoleNotFoundSnafu { name: role });
+        let updated = self
+            .update_value(&user_key(user), |value: &mut UserValue| {
+                value.roles.insert(role.to_string());
+            })
+            .await?;
+        ensure!(updated, UserNotFoundSnafu { name: user });
+        self.refresh().await
+    }
+
+    /// Revokes the `role` from the `user`.
+    pub async fn revoke_role(&self, role: &str, user: &str) -> Result<()> {
+        let updated = self
+            .update_value(&user_key(user), |value: &mut UserValue| {
+                value.roles.remove(role);
+            })
+            .await?;
+        ensure!(updated, UserNotFoundSnafu { name: user });
+        self.refresh().await
+    }
+
+    /// Returns the user from the kv backend.
+    pub async fn user(&self, name: &str) -> Result<Option<UserValue>> {
+        self.kv_backend
+            .get(user_key(name).as_bytes())
+            .await?
+            .map(|kv| UserValue::try_from_raw_value(&kv.value))
+            .transpose()
+    }
+
+    /// Returns the cached snapshot.
+    pub fn snapshot(&self) -> Arc<AccessControlSnapshot> {
+        self.cached.read().unwrap().snapshot.clone()
+    }
+
+    /// Returns true if the cached snapshot was loaded more than `ttl` ago or never loaded.
+    pub fn is_stale(&self, ttl: Duration) -> bool {
+        self.cached
+            .read()
+            .unwrap()
+            .loaded_at
+            .is_none_or(|loaded_at| loaded_at.elapsed() > ttl)
+    }
+
+    /// Reloads the cached snapshot from the kv backend.
+    pub async fn refresh(&self) -> Result<()> {
+        let loaded_at = Instant::now();
+        let snapshot = Arc::new(self.load_snapshot().await?);
+        let mut cached = self.cached.write().unwrap();
+        // Keeps the newer snapshot if refreshes race.
+        if cached.loaded_at.is_none_or(|prev| prev <= loaded_at) {
+            *cached = CachedSnapshot {
+                snapshot,
+                loaded_at: Some(loaded_at),
+            };
+        }
+        Ok(())
+    }
+
+    async fn load_snapshot(&self) -> Result<AccessControlSnapshot> {
+        let prefix = format!("{ACCESS_CONTROL_KEY_PREFIX}/");
+        let req = RangeRequest::new().with_prefix(prefix.as_bytes());
+        let resp = self.kv_backend.range(req).await?;
+
+        let mut snapshot = AccessControlSnapshot::default();
+        for kv in resp.kvs {
+            let key = std::str::from_utf8(&kv.key).context(error::ConvertRawKeySnafu)?;
+            let (kind, name) =
+                key[prefix.len()..]
+                    .split_once('/')
+                    .context(error::InvalidMetadataSnafu {
+                        err_msg: format!("Illegal access control key: '{key}'"),
+                    })?;
+            match kind {
+                USER_KEY_PREFIX => {
+                    let _ = snapshot
+                        .users
+                        .insert(name.to_string(), UserValue::try_from_raw_value(&kv.value)?);
+                }
+                ROLE_KEY_PREFIX => {
+                    let _ = snapshot
+                        .roles
+                        .insert(name.to_string(), RoleValue::try_from_raw_value(&kv.value)?);
+                }
+                _ => {
+                    return error::InvalidMetadataSnafu {
+                        err_msg: format!("Illegal access control key: '{key}'"),
+                    }
+                    .fail();
+                }
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// Updates grants of the user or the role named `grantee`.
+    async fn update_grants(&self, grantee: &str, f: impl Fn(&mut Grants)) -> Result<()> {
+        let updated = self
+            .update_value(&user_key(grantee), |value: &mut UserValue| {
+                f(&mut value.grants)
+            })
+            .await?
+            || self
+                .update_value(&role_key(grantee), |value: &mut RoleValue| {
+                    f(&mut value.grants)
+                })
+                .await?;
+        ensure!(updated, UserNotFoundSnafu { name: grantee });
+        self.refresh().await
+    }
+
+    /// Updates the value of the `key` with `f`. Returns false if the key doesn't exist.
+    async fn update_value<V: MetadataValue>(&self, key: &str, f: impl Fn(&mut V)) -> Result<bool> {
+        loop {
+            let Some(kv) = self.kv_backend.get(key.as_bytes()).await? else {
+                return Ok(false);
+            };
+            let mut value = V::try_from_raw_value(&kv.value)?;
+            f(&mut value);
+            let req = CompareAndPutRequest::new()
+                .with_key(key)
+                .with_expect(kv.value)
+                .with_value(value.try_as_raw_value()?);
+            if self.kv_backend.compare_and_put(req).await?.success {
+                return Ok(true);
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CacheInvalidator for AccessControlManager {
+    async fn invalidate(&self, _ctx: &Context, caches: &[CacheIdent]) -> Result<()> {
+        if caches.contains(&CacheIdent::AccessControl) {
+            self.refresh().await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kv_backend::memory::MemoryKvBackend;
+
+    fn database(schema: &str) -> GrantObject {
+        GrantObject::Database {
+            catalog: "greptime".to_string(),
+            schema: schema.to_string(),
+        }
+    }
+
+    fn table(schema: &str, table: &str) -> GrantObject {
+        GrantObject::Table {
+            catalog: "greptime".to_string(),
+            schema: schema.to_string(),
+            table: table.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_grants() {
+        let mut grants = Grants::default();
+        grants.grant(database("public"), &[Privilege::Select]);
+        grants.grant(
+            table("metrics", "cpu"),
+            &[Privilege::Insert, Privilege::Select],
+        );
+
+        assert!(grants.allows(Privilege::Select, &database("public")));
+        assert!(grants.allows(Privilege::Select, &table("public", "cpu")));
+        assert!(!grants.allows(Privilege::Insert, &table("public", "cpu")));
+        assert!(grants.allows(Privilege::Insert, &table("metrics", "cpu")));
+        assert!(!grants.allows(Privilege::Insert, &table("metrics", "memory")));
+        assert!(!grants.allows(Privilege::Select, &database("metrics")));
+
+        // Revokes on another object has no effect.
+        grants.revoke(&database("metrics"), &[Privilege::Insert]);
+        assert!(grants.allows(Privilege::Insert, &table("metrics", "cpu")));
+        grants.revoke(
+            &table("metrics", "cpu"),
+            &[Privilege::Insert, Privilege::Select],
+        );
+        assert!(!grants.allows(Privilege::Select, &table("metrics", "cpu")));
+        assert_eq!(1, grants.iter().count());
+
+        grants.grant(GrantObject::Global, &[Privilege::Drop]);
+        assert!(grants.allows(Privilege::Drop, &table("metrics", "cpu")));
+    }
+
+    #[tokio::test]
+    async fn test_access_control_manager() {
+        let manager = AccessControlManager::new(Arc::new(MemoryKvBackend::default()));
+        assert!(manager.is_stale(Duration::from_secs(60)));
+
+        assert!(manager
+            .create_user("alice", Some(b"hash"), false)
+            .await
+            .unwrap());
+        assert!(!manager.create_user("alice", None, true).await.unwrap());
+        manager.create_user("alice", None, false).await.unwrap_err();
+        assert!(manager.create_role("reader", false).await.unwrap());
+        assert!(!manager.is_stale(Duration::from_secs(60)));
+
+        manager
+            .grant_privileges("reader", database("public"), &[Privilege::Select])
+            .await
+            .unwrap();
+        manager
+            .grant_privileges("alice", table("public", "cpu"), &[Privilege::Insert])
+            .await
+            .unwrap();
+        manager
+            .grant_privileges("bob", GrantObject::Global, &[Privilege::Insert])
+            .await
+            .unwrap_err();
+        manager.grant_role("reader", "alice").await.unwrap();
+        manager.grant_role("writer", "alice").await.unwrap_err();
+
+        let snapshot = manager.snapshot();
+        let alice = snapshot.user("alice").unwrap();
+        assert_eq!(Some(hex::encode(b"hash")), alice.password_hash);
+        assert!(snapshot.is_granted(alice, Privilege::Select, &table("public", "memory")));
+        assert!(snapshot.is_granted(alice, Privilege::Insert, &table("public", "cpu")));
+        assert!(!snapshot.is_granted(alice, Privilege::Insert, &table("public", "memory")));
+        assert!(snapshot.has_any_in_database(alice, "greptime", "public"));
+        assert!(!snapshot.has_any_in_database(alice, "greptime", "private"));
+        assert!(!snapshot.is_admin(alice));
+        assert_eq!(alice, &manager.user("alice").await.unwrap().unwrap());
+
+        manager.drop_role("reader", false).await.unwrap();
+        let snapshot = manager.snapshot();
+        let alice = snapshot.user("alice").unwrap();
+        assert!(!snapshot.is_granted(alice, Privilege::Select, &table("public", "memory")));
+
+        manager
+            .grant_privileges(
+                "alice",
+                GrantObject::Global,
+                &Privilege::iter().collect::<Vec<_>>(),
+            )
+            .await
+            .unwrap();
+        let snapshot = manager.snapshot();
+        assert!(snapshot.is_admin(snapshot.user("alice").unwrap()));
+
+        assert!(manager.drop_user("alice", false).await.unwrap());
+        assert!(!manager.drop_user("alice", true).await.unwrap());
+        manager.drop_user("alice", false).await.unwrap_err();
+        assert!(manager.snapshot().user("alice").is_none());
+    }
+}
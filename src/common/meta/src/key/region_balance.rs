@@ -0,0 +1,187 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{self, Display};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use store_api::storage::RegionId;
+
+use crate::error::{self, Result};
+use crate::key::{MetadataKey, MetadataValue, REGION_BALANCE_STATE_KEY};
+use crate::kv_backend::KvBackendRef;
+use crate::peer::Peer;
+use crate::rpc::store::PutRequest;
+
+/// The key of the region balancer state.
+///
+/// The layout: `__region_balance/state`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RegionBalanceStateKey;
+
+impl RegionBalanceStateKey {
+    /// Returns the [RegionBalanceStateKey].
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<'a> MetadataKey<'a, RegionBalanceStateKey> for RegionBalanceStateKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        REGION_BALANCE_STATE_KEY.as_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &'a [u8]) -> Result<RegionBalanceStateKey> {
+        let key = std::str::from_utf8(bytes).map_err(|e| {
+            error::InvalidMetadataSnafu {
+                err_msg: format!(
+                    "RegionBalanceStateKey '{}' is not a valid UTF8 string: {e}",
+                    String::from_utf8_lossy(bytes)
+                ),
+            }
+            .build()
+        })?;
+        if key != REGION_BALANCE_STATE_KEY {
+            return Err(error::InvalidMetadataSnafu {
+                err_msg: format!("Invalid RegionBalanceStateKey '{key}'"),
+            }
+            .build());
+        }
+        Ok(RegionBalanceStateKey)
+    }
+}
+
+/// The state of a region move planned by the region balancer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RegionMoveState {
+    /// The move is planned but not submitted, e.g., the balancer runs in dry-run mode.
+    Planned,
+    /// The region migration procedure of the move is running.
+    Running,
+    /// The move failed to be submitted.
+    Failed,
+}
+
+impl Display for RegionMoveState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegionMoveState::Planned => write!(f, "Planned"),
+            RegionMoveState::Running => write!(f, "Running"),
+            RegionMoveState::Failed => write!(f, "Failed"),
+        }
+    }
+}
+
+/// A region move planned by the region balancer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegionMove {
+    /// The region to move.
+    pub region_id: RegionId,
+    /// The current leader peer of the region.
+    pub from_peer: Peer,
+    /// The target peer of the region.
+    pub to_peer: Peer,
+    /// The load score of the region at planning time.
+    pub load: f64,
+    /// The state of the move.
+    pub state: RegionMoveState,
+    /// The procedure id of the region migration, if submitted.
+    pub procedure_id: Option<String>,
+    /// The error message if the move failed.
+    pub error: Option<String>,
+    /// The time the move was planned in unix timestamp milliseconds.
+    pub planned_at_ms: i64,
+}
+
+/// The value of the region balancer state.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RegionBalanceStateValue {
+    /// Whether the balancer runs in dry-run mode.
+    pub dry_run: bool,
+    /// The planned and in-flight moves.
+    pub moves: Vec<RegionMove>,
+}
+
+pub type RegionBalanceStateManagerRef = Arc<RegionBalanceStateManager>;
+
+/// The manager of [RegionBalanceStateKey]. The state is only meaningful on the
+/// leader metasrv, so it's stored in memory.
+///
+/// The metasrv leader puts the state after each balancing round, and the frontend gets
+/// the latest state to serve `information_schema.region_balance_moves`.
+pub struct RegionBalanceStateManager {
+    in_memory: KvBackendRef,
+}
+
+impl RegionBalanceStateManager {
+    pub fn new(in_memory: KvBackendRef) -> Self {
+        Self { in_memory }
+    }
+
+    pub async fn get(&self) -> Result<Option<RegionBalanceStateValue>> {
+        let key = RegionBalanceStateKey::new().to_bytes();
+        self.in_memory
+            .get(&key)
+            .await?
+            .map(|x| RegionBalanceStateValue::try_from_raw_value(&x.value))
+            .transpose()
+    }
+
+    pub async fn put(&self, value: RegionBalanceStateValue) -> Result<()> {
+        let key = RegionBalanceStateKey::new().to_bytes();
+        let value = value.try_as_raw_value()?;
+        let req = PutRequest::new().with_key(key).with_value(value);
+        self.in_memory.put(req).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kv_backend::memory::MemoryKvBackend;
+
+    #[test]
+    fn test_key_serialization() {
+        let key = RegionBalanceStateKey::new();
+        assert_eq!(b"__region_balance/state".to_vec(), key.to_bytes());
+        assert_eq!(
+            key,
+            RegionBalanceStateKey::from_bytes(&key.to_bytes()).unwrap()
+        );
+        assert!(RegionBalanceStateKey::from_bytes(b"__region_balance/other").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_put_and_get() {
+        let manager = RegionBalanceStateManager::new(Arc::new(MemoryKvBackend::default()));
+        assert!(manager.get().await.unwrap().is_none());
+
+        let value = RegionBalanceStateValue {
+            dry_run: true,
+            moves: vec![RegionMove {
+                region_id: RegionId::new(1024, 1),
+                from_peer: Peer::new(1, "127.0.0.1:4001"),
+                to_peer: Peer::new(2, "127.0.0.1:4002"),
+                load: 0.5,
+                state: RegionMoveState::Planned,
+                procedure_id: None,
+                error: None,
+                planned_at_ms: 1000,
+            }],
+        };
+        manager.put(value.clone()).await.unwrap();
+        assert_eq!(value, manager.get().await.unwrap().unwrap());
+    }
+}
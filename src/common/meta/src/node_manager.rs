@@ -20,10 +20,21 @@ use api::v1::region::{InsertRequests, RegionRequest};
 pub use common_base::AffectedRows;
 use common_query::request::QueryRequest;
 use common_recordbatch::SendableRecordBatchStream;
+use store_api::region_engine::{
+    BackupRegionRequest, BackupRegionResponse, CopyRegionFromRequest, CopyRegionFromResponse,
+    RestoreRegionRequest, RestoreRegionResponse,
+};
 
 use crate::error::Result;
 use crate::peer::Peer;
 
+/// The type of the flight action to backup a region.
+pub const BACKUP_REGION_ACTION: &str = "backup_region";
+/// The type of the flight action to restore a region.
+pub const RESTORE_REGION_ACTION: &str = "restore_region";
+/// The type of the flight action to copy the files of other regions into a region.
+pub const COPY_REGION_FROM_ACTION: &str = "copy_region_from";
+
 /// The trait for handling requests to datanode.
 #[async_trait::async_trait]
 pub trait Datanode: Send + Sync {
@@ -32,6 +43,18 @@ pub trait Datanode: Send + Sync {
 
     /// Handles query requests
     async fn handle_query(&self, request: QueryRequest) -> Result<SendableRecordBatchStream>;
+
+    /// Copies the files of a region to the backup location.
+    async fn backup_region(&self, request: BackupRegionRequest) -> Result<BackupRegionResponse>;
+
+    /// Restores a region from the files of a backup.
+    async fn restore_region(&self, request: RestoreRegionRequest) -> Result<RestoreRegionResponse>;
+
+    /// Copies the files of other regions of the same table into a region.
+    async fn copy_region_from(
+        &self,
+        request: CopyRegionFromRequest,
+    ) -> Result<CopyRegionFromResponse>;
 }
 
 pub type DatanodeRef = Arc<dyn Datanode>;
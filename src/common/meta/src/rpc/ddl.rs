@@ -77,6 +77,7 @@ pub enum DdlTask {
     DropView(DropViewTask),
     #[cfg(feature = "enterprise")]
     CreateTrigger(trigger::CreateTriggerTask),
+    RepartitionTable(RepartitionTableTask),
 }
 
 impl DdlTask {
@@ -192,6 +193,11 @@ impl DdlTask {
         })
     }
 
+    /// Creates a [`DdlTask`] to repartition a table.
+    pub fn new_repartition_table(task: RepartitionTableTask) -> Self {
+        DdlTask::RepartitionTable(task)
+    }
+
     /// Creates a [`DdlTask`] to create a view.
     pub fn new_create_view(create_view: CreateViewExpr, view_info: RawTableInfo) -> Self {
         DdlTask::CreateView(CreateViewTask {
@@ -331,6 +337,12 @@ impl TryFrom<SubmitDdlTaskRequest> for PbDdlTaskRequest {
             DdlTask::CreateTrigger(task) => Task::CreateTriggerTask(task.try_into()?),
             #[cfg(feature = "enterprise")]
             DdlTask::DropTrigger(task) => Task::DropTriggerTask(task.into()),
+            DdlTask::RepartitionTable(_) => {
+                return error::UnsupportedSnafu {
+                    operation: "submitting repartition table task via gRPC",
+                }
+                .fail()
+            }
         };
 
         Ok(Self {
@@ -898,6 +910,31 @@ impl TryFrom<TruncateTableTask> for PbTruncateTableTask {
     }
 }
 
+/// Replaces some partitions of a table with new ones, i.e., splits or merges regions.
+///
+/// The partition exprs are JSON encoded `PartitionExpr`s. Each of `from_partition_exprs`
+/// must match the partition expr of an existing region, and `into_partition_exprs`
+/// must cover exactly the same range as them.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct RepartitionTableTask {
+    pub catalog: String,
+    pub schema: String,
+    pub table: String,
+    pub table_id: TableId,
+    pub from_partition_exprs: Vec<String>,
+    pub into_partition_exprs: Vec<String>,
+}
+
+impl RepartitionTableTask {
+    pub fn table_ref(&self) -> TableReference {
+        TableReference {
+            catalog: &self.catalog,
+            schema: &self.schema,
+            table: &self.table,
+        }
+    }
+}
+
 #[serde_as]
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct CreateDatabaseTask {
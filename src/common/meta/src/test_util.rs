@@ -22,6 +22,10 @@ use common_query::request::QueryRequest;
 use common_recordbatch::SendableRecordBatchStream;
 use common_wal::config::kafka::common::{KafkaConnectionConfig, KafkaTopicConfig};
 use common_wal::config::kafka::MetasrvKafkaConfig;
+use store_api::region_engine::{
+    BackupRegionRequest, BackupRegionResponse, CopyRegionFromRequest, CopyRegionFromResponse,
+    RestoreRegionRequest, RestoreRegionResponse,
+};
 
 use crate::cache_invalidator::DummyCacheInvalidator;
 use crate::ddl::flow_meta::FlowMetadataAllocator;
@@ -117,6 +121,24 @@ impl<T: MockDatanodeHandler> Datanode for MockNode<T> {
     async fn handle_query(&self, request: QueryRequest) -> Result<SendableRecordBatchStream> {
         self.handler.handle_query(&self.peer, request).await
     }
+
+    async fn backup_region(&self, _request: BackupRegionRequest) -> Result<BackupRegionResponse> {
+        unimplemented!()
+    }
+
+    async fn restore_region(
+        &self,
+        _request: RestoreRegionRequest,
+    ) -> Result<RestoreRegionResponse> {
+        unimplemented!()
+    }
+
+    async fn copy_region_from(
+        &self,
+        _request: CopyRegionFromRequest,
+    ) -> Result<CopyRegionFromResponse> {
+        unimplemented!()
+    }
 }
 
 #[async_trait::async_trait]
@@ -268,6 +268,7 @@ pub fn datafusion_status_code<T: ErrorExt + 'static>(
         DataFusionError::Internal(_) => StatusCode::Internal,
         DataFusionError::NotImplemented(_) => StatusCode::Unsupported,
         DataFusionError::Plan(_) => StatusCode::PlanQuery,
+        DataFusionError::ResourcesExhausted(_) => StatusCode::RuntimeResourcesExhausted,
         DataFusionError::External(e) => {
             if let Some(ext) = (*e).downcast_ref::<T>() {
                 ext.status_code()
@@ -212,7 +212,12 @@ impl ErrorExt for Error {
 
             Error::DowncastVector { .. } => StatusCode::Unexpected,
 
-            Error::PollStream { .. } => StatusCode::EngineExecuteQuery,
+            Error::PollStream { error, .. } => match error.find_root() {
+                datafusion::error::DataFusionError::ResourcesExhausted(_) => {
+                    StatusCode::RuntimeResourcesExhausted
+                }
+                _ => StatusCode::EngineExecuteQuery,
+            },
 
             Error::ArrowCompute { .. } => StatusCode::IllegalState,
 
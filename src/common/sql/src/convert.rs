@@ -18,7 +18,10 @@ use common_time::timezone::Timezone;
 use common_time::Timestamp;
 use datatypes::prelude::ConcreteDataType;
 use datatypes::schema::ColumnDefaultConstraint;
-use datatypes::types::{parse_string_to_json_type_value, parse_string_to_vector_type_value};
+use datatypes::types::{
+    parse_string_to_ip_type_value, parse_string_to_json_type_value, parse_string_to_map_type_value,
+    parse_string_to_uuid_type_value, parse_string_to_vector_type_value,
+};
 use datatypes::value::{OrderedF32, OrderedF64, Value};
 use snafu::{ensure, OptionExt, ResultExt};
 pub use sqlparser::ast::{
@@ -306,6 +309,18 @@ pub(crate) fn parse_string_to_value(
             let v = parse_string_to_vector_type_value(&s, Some(d.dim)).context(DatatypeSnafu)?;
             Ok(Value::Binary(v.into()))
         }
+        ConcreteDataType::Uuid(_) => {
+            let v = parse_string_to_uuid_type_value(&s).context(DatatypeSnafu)?;
+            Ok(Value::Binary(v.into()))
+        }
+        ConcreteDataType::Map(m) => {
+            let v = parse_string_to_map_type_value(&s, m).context(DatatypeSnafu)?;
+            Ok(Value::Binary(v.into()))
+        }
+        ConcreteDataType::Ip(_) => {
+            let v = parse_string_to_ip_type_value(&s).context(DatatypeSnafu)?;
+            Ok(Value::Binary(v.into()))
+        }
         _ => ParseSqlValueSnafu {
             msg: format!("Failed to parse {s} to {data_type} value"),
         }
@@ -824,6 +839,105 @@ mod test {
         .is_err())
     }
 
+    #[test]
+    fn test_parse_string_to_uuid_and_map() {
+        let v = parse_string_to_value(
+            "uuid_col",
+            "67e55044-10b1-426f-9247-bb680e5fe0c8".to_string(),
+            &ConcreteDataType::uuid_datatype(),
+            None,
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            Value::Binary(Bytes::from(
+                [
+                    0x67, 0xe5, 0x50, 0x44, 0x10, 0xb1, 0x42, 0x6f, 0x92, 0x47, 0xbb, 0x68, 0x0e,
+                    0x5f, 0xe0, 0xc8
+                ]
+                .as_slice()
+            )),
+            v
+        );
+        assert!(parse_string_to_value(
+            "uuid_col",
+            "not a uuid".to_string(),
+            &ConcreteDataType::uuid_datatype(),
+            None,
+            false,
+        )
+        .is_err());
+
+        let map_type = ConcreteDataType::map_datatype(
+            ConcreteDataType::string_datatype(),
+            ConcreteDataType::int64_datatype(),
+        );
+        let v = parse_string_to_value(
+            "map_col",
+            r#"{"a": 1, "b": 2}"#.to_string(),
+            &map_type,
+            None,
+            false,
+        )
+        .unwrap();
+        let Value::Binary(b) = v else { unreachable!() };
+        let (_, entries) = datatypes::types::decode_map_type_value(&b).unwrap();
+        assert_eq!(
+            vec![
+                (Value::from("a"), Value::Int64(1)),
+                (Value::from("b"), Value::Int64(2)),
+            ],
+            entries
+        );
+        assert!(parse_string_to_value(
+            "map_col",
+            r#"{"a": "b"}"#.to_string(),
+            &map_type,
+            None,
+            false,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_string_to_ip() {
+        let v = parse_string_to_value(
+            "ip_col",
+            "10.0.0.1".to_string(),
+            &ConcreteDataType::ip_datatype(),
+            None,
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            Value::Binary(Bytes::from(
+                [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, 10, 0, 0, 1].as_slice()
+            )),
+            v
+        );
+        let v = parse_string_to_value(
+            "ip_col",
+            "2001:db8::1".to_string(),
+            &ConcreteDataType::ip_datatype(),
+            None,
+            false,
+        )
+        .unwrap();
+        let Value::Binary(b) = v else { unreachable!() };
+        assert_eq!(
+            "2001:db8::1",
+            datatypes::types::ip_type_value_to_string(&b).unwrap()
+        );
+        assert!(parse_string_to_value(
+            "ip_col",
+            "10.0.0.0/8".to_string(),
+            &ConcreteDataType::ip_datatype(),
+            None,
+            false,
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_sql_number_to_value() {
         let v = sql_number_to_value(&ConcreteDataType::float64_datatype(), "3.0").unwrap();
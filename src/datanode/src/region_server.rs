@@ -25,12 +25,15 @@ use api::v1::region::{
     region_request, ListMetadataRequest, RegionResponse as RegionResponseV1, SyncRequest,
 };
 use api::v1::{ResponseHeader, Status};
-use arrow_flight::{FlightData, Ticket};
+use arrow_flight::{Action, FlightData, Ticket};
 use async_trait::async_trait;
 use bytes::Bytes;
 use common_error::ext::{BoxedError, ErrorExt};
 use common_error::status_code::StatusCode;
 use common_meta::datanode::TopicStatsReporter;
+use common_meta::node_manager::{
+    BACKUP_REGION_ACTION, COPY_REGION_FROM_ACTION, RESTORE_REGION_ACTION,
+};
 use common_query::request::QueryRequest;
 use common_query::OutputData;
 use common_recordbatch::SendableRecordBatchStream;
@@ -62,8 +65,9 @@ use store_api::metric_engine_consts::{
     FILE_ENGINE_NAME, LOGICAL_TABLE_METADATA_KEY, METRIC_ENGINE_NAME,
 };
 use store_api::region_engine::{
-    RegionEngineRef, RegionManifestInfo, RegionRole, RegionStatistic, SetRegionRoleStateResponse,
-    SettableRegionRoleState,
+    BackupRegionRequest, BackupRegionResponse, CopyRegionFromRequest, CopyRegionFromResponse,
+    RegionEngineRef, RegionManifestInfo, RegionRole, RegionStatistic, RestoreRegionRequest,
+    RestoreRegionResponse, SetRegionRoleStateResponse, SettableRegionRoleState,
 };
 use store_api::region_request::{
     AffectedRows, BatchRegionDdlRequest, RegionCloseRequest, RegionOpenRequest, RegionRequest,
@@ -570,6 +574,106 @@ impl RegionServer {
             .handle_sync_region(engine_with_status.engine(), region_id, manifest_info)
             .await
     }
+
+    /// Pins the files of the region, copies them to the backup location or releases them.
+    pub async fn backup_region(
+        &self,
+        request: BackupRegionRequest,
+    ) -> Result<BackupRegionResponse> {
+        let region_id = request.region_id();
+        let engine_with_status = self
+            .inner
+            .region_map
+            .get(&region_id)
+            .with_context(|| RegionNotFoundSnafu { region_id })?;
+
+        engine_with_status
+            .engine()
+            .backup_region(request)
+            .await
+            .context(HandleRegionRequestSnafu { region_id })
+    }
+
+    /// Restores the region from the files of a backup.
+    pub async fn restore_region(
+        &self,
+        request: RestoreRegionRequest,
+    ) -> Result<RestoreRegionResponse> {
+        let region_id = request.region_id;
+        let engine_with_status = self
+            .inner
+            .region_map
+            .get(&region_id)
+            .with_context(|| RegionNotFoundSnafu { region_id })?;
+
+        engine_with_status
+            .engine()
+            .restore_region(request)
+            .await
+            .context(HandleRegionRequestSnafu { region_id })
+    }
+
+    /// Copies the files of other regions of the same table into the region.
+    pub async fn copy_region_from(
+        &self,
+        request: CopyRegionFromRequest,
+    ) -> Result<CopyRegionFromResponse> {
+        let region_id = request.region_id;
+        let engine_with_status = self
+            .inner
+            .region_map
+            .get(&region_id)
+            .with_context(|| RegionNotFoundSnafu { region_id })?;
+
+        engine_with_status
+            .engine()
+            .copy_region_from(request)
+            .await
+            .context(HandleRegionRequestSnafu { region_id })
+    }
+
+    /// Handles the Flight action and returns the json body of the result.
+    async fn handle_action(&self, action: Action) -> ServerResult<Vec<u8>> {
+        let body = match action.r#type.as_str() {
+            BACKUP_REGION_ACTION => {
+                let request: BackupRegionRequest =
+                    serde_json::from_slice(&action.body).context(servers_error::ParseJsonSnafu)?;
+                let response = self
+                    .backup_region(request)
+                    .await
+                    .map_err(BoxedError::new)
+                    .context(ExecuteGrpcRequestSnafu)?;
+                serde_json::to_vec(&response).context(servers_error::ToJsonSnafu)?
+            }
+            RESTORE_REGION_ACTION => {
+                let request: RestoreRegionRequest =
+                    serde_json::from_slice(&action.body).context(servers_error::ParseJsonSnafu)?;
+                let response = self
+                    .restore_region(request)
+                    .await
+                    .map_err(BoxedError::new)
+                    .context(ExecuteGrpcRequestSnafu)?;
+                serde_json::to_vec(&response).context(servers_error::ToJsonSnafu)?
+            }
+            COPY_REGION_FROM_ACTION => {
+                let request: CopyRegionFromRequest =
+                    serde_json::from_slice(&action.body).context(servers_error::ParseJsonSnafu)?;
+                let response = self
+                    .copy_region_from(request)
+                    .await
+                    .map_err(BoxedError::new)
+                    .context(ExecuteGrpcRequestSnafu)?;
+                serde_json::to_vec(&response).context(servers_error::ToJsonSnafu)?
+            }
+            other => {
+                return servers_error::InvalidParameterSnafu {
+                    reason: format!("unknown action type: {other}"),
+                }
+                .fail()
+            }
+        };
+        Ok(body)
+    }
 }
 
 #[async_trait]
@@ -641,6 +745,18 @@ impl FlightCraft for RegionServer {
         ));
         Ok(Response::new(stream))
     }
+
+    async fn do_action(
+        &self,
+        request: Request<Action>,
+    ) -> TonicResult<Response<TonicStream<arrow_flight::Result>>> {
+        let body = self.handle_action(request.into_inner()).await?;
+        let stream =
+            futures_util::stream::once(
+                async move { Ok(arrow_flight::Result { body: body.into() }) },
+            );
+        Ok(Response::new(Box::pin(stream)))
+    }
 }
 
 #[derive(Clone)]
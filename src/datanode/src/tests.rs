@@ -32,8 +32,10 @@ use servers::grpc::FlightCompression;
 use session::context::QueryContextRef;
 use store_api::metadata::RegionMetadataRef;
 use store_api::region_engine::{
+    BackupRegionRequest, BackupRegionResponse, CopyRegionFromRequest, CopyRegionFromResponse,
     RegionEngine, RegionManifestInfo, RegionRole, RegionScannerRef, RegionStatistic,
-    SetRegionRoleStateResponse, SettableRegionRoleState, SyncManifestResponse,
+    RestoreRegionRequest, RestoreRegionResponse, SetRegionRoleStateResponse,
+    SettableRegionRoleState, SyncManifestResponse,
 };
 use store_api::region_request::{AffectedRows, RegionRequest};
 use store_api::storage::{RegionId, ScanRequest, SequenceNumber};
@@ -288,6 +290,27 @@ impl RegionEngine for MockRegionEngine {
         unimplemented!()
     }
 
+    async fn backup_region(
+        &self,
+        _request: BackupRegionRequest,
+    ) -> Result<BackupRegionResponse, BoxedError> {
+        unimplemented!()
+    }
+
+    async fn restore_region(
+        &self,
+        _request: RestoreRegionRequest,
+    ) -> Result<RestoreRegionResponse, BoxedError> {
+        unimplemented!()
+    }
+
+    async fn copy_region_from(
+        &self,
+        _request: CopyRegionFromRequest,
+    ) -> Result<CopyRegionFromResponse, BoxedError> {
+        unimplemented!()
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -33,10 +33,11 @@ use crate::types::{
     BinaryType, BooleanType, DateType, Decimal128Type, DictionaryType, DurationMicrosecondType,
     DurationMillisecondType, DurationNanosecondType, DurationSecondType, DurationType, Float32Type,
     Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, IntervalDayTimeType,
-    IntervalMonthDayNanoType, IntervalType, IntervalYearMonthType, JsonType, ListType, NullType,
-    StringType, StructType, TimeMillisecondType, TimeType, TimestampMicrosecondType,
-    TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType, TimestampType,
-    UInt16Type, UInt32Type, UInt64Type, UInt8Type, VectorType,
+    IntervalMonthDayNanoType, IntervalType, IntervalYearMonthType, IpType, JsonType, ListType,
+    MapType, NullType, StringType, StructType, TimeMillisecondType, TimeType,
+    TimestampMicrosecondType, TimestampMillisecondType, TimestampNanosecondType,
+    TimestampSecondType, TimestampType, UInt16Type, UInt32Type, UInt64Type, UInt8Type, UuidType,
+    VectorType,
 };
 use crate::value::Value;
 use crate::vectors::MutableVector;
@@ -87,6 +88,15 @@ pub enum ConcreteDataType {
 
     // Vector type:
     Vector(VectorType),
+
+    // Uuid type:
+    Uuid(UuidType),
+
+    // Map type:
+    Map(MapType),
+
+    // Ip type:
+    Ip(IpType),
 }
 
 impl fmt::Display for ConcreteDataType {
@@ -136,6 +146,9 @@ impl fmt::Display for ConcreteDataType {
             ConcreteDataType::Dictionary(v) => write!(f, "{}", v.name()),
             ConcreteDataType::Json(v) => write!(f, "{}", v.name()),
             ConcreteDataType::Vector(v) => write!(f, "{}", v.name()),
+            ConcreteDataType::Uuid(v) => write!(f, "{}", v.name()),
+            ConcreteDataType::Map(v) => write!(f, "{}", v.name()),
+            ConcreteDataType::Ip(v) => write!(f, "{}", v.name()),
         }
     }
 }
@@ -171,6 +184,9 @@ impl ConcreteDataType {
                 | ConcreteDataType::Binary(_)
                 | ConcreteDataType::Json(_)
                 | ConcreteDataType::Vector(_)
+                | ConcreteDataType::Uuid(_)
+                | ConcreteDataType::Map(_)
+                | ConcreteDataType::Ip(_)
         )
     }
 
@@ -232,6 +248,18 @@ impl ConcreteDataType {
         matches!(self, ConcreteDataType::Vector(_))
     }
 
+    pub fn is_uuid(&self) -> bool {
+        matches!(self, ConcreteDataType::Uuid(_))
+    }
+
+    pub fn is_map(&self) -> bool {
+        matches!(self, ConcreteDataType::Map(_))
+    }
+
+    pub fn is_ip(&self) -> bool {
+        matches!(self, ConcreteDataType::Ip(_))
+    }
+
     pub fn numerics() -> Vec<ConcreteDataType> {
         vec![
             ConcreteDataType::int8_datatype(),
@@ -355,6 +383,14 @@ impl ConcreteDataType {
         }
     }
 
+    /// Try to cast the type as a [`MapType`].
+    pub fn as_map(&self) -> Option<&MapType> {
+        match self {
+            ConcreteDataType::Map(m) => Some(m),
+            _ => None,
+        }
+    }
+
     /// Checks if the data type can cast to another data type.
     pub fn can_arrow_type_cast_to(&self, to_type: &ConcreteDataType) -> bool {
         let array = arrow_array::new_empty_array(&self.as_arrow_type());
@@ -387,7 +423,9 @@ impl ConcreteDataType {
             &ConcreteDataType::Time(_) => "TIME",
             &ConcreteDataType::Interval(_) => "INTERVAL",
             &ConcreteDataType::Decimal128(_) => "NUMERIC",
-            &ConcreteDataType::Json(_) => "JSON",
+            &ConcreteDataType::Json(_) | &ConcreteDataType::Map(_) => "JSON",
+            &ConcreteDataType::Uuid(_) => "UUID",
+            &ConcreteDataType::Ip(_) => "INET",
             ConcreteDataType::List(list) => match list.item_type() {
                 &ConcreteDataType::Null(_) => "UNKNOWN",
                 &ConcreteDataType::Boolean(_) => "_BOOL",
@@ -405,9 +443,12 @@ impl ConcreteDataType {
                 &ConcreteDataType::Interval(_) => "_INTERVAL",
                 &ConcreteDataType::Decimal128(_) => "_NUMERIC",
                 &ConcreteDataType::Json(_) => "_JSON",
+                &ConcreteDataType::Uuid(_) => "_UUID",
+                &ConcreteDataType::Ip(_) => "_INET",
                 &ConcreteDataType::Duration(_)
                 | &ConcreteDataType::Dictionary(_)
                 | &ConcreteDataType::Vector(_)
+                | &ConcreteDataType::Map(_)
                 | &ConcreteDataType::List(_)
                 | &ConcreteDataType::Struct(_) => "UNKNOWN",
             },
@@ -467,6 +508,23 @@ impl TryFrom<&ArrowDataType> for ConcreteDataType {
                 ConcreteDataType::decimal128_datatype(*precision, *scale)
             }
             ArrowDataType::Struct(fields) => ConcreteDataType::Struct(fields.try_into()?),
+            ArrowDataType::Map(field, _) => {
+                let map_type = match field.data_type() {
+                    ArrowDataType::Struct(fields) if fields.len() == 2 => MapType::try_new(
+                        ConcreteDataType::try_from(fields[0].data_type())?,
+                        ConcreteDataType::try_from(fields[1].data_type())?,
+                    )
+                    .ok(),
+                    _ => None,
+                };
+                let Some(map_type) = map_type else {
+                    return error::UnsupportedArrowTypeSnafu {
+                        arrow_type: dt.clone(),
+                    }
+                    .fail();
+                };
+                ConcreteDataType::Map(map_type)
+            }
             ArrowDataType::Float16
             | ArrowDataType::Date64
             | ArrowDataType::FixedSizeBinary(_)
@@ -476,7 +534,6 @@ impl TryFrom<&ArrowDataType> for ConcreteDataType {
             | ArrowDataType::LargeListView(_)
             | ArrowDataType::Union(_, _)
             | ArrowDataType::Decimal256(_, _)
-            | ArrowDataType::Map(_, _)
             | ArrowDataType::RunEndEncoded(_, _)
             | ArrowDataType::Decimal32(_, _)
             | ArrowDataType::Decimal64(_, _) => {
@@ -507,7 +564,7 @@ macro_rules! impl_new_concrete_type_functions {
 
 impl_new_concrete_type_functions!(
     Null, Boolean, UInt8, UInt16, UInt32, UInt64, Int8, Int16, Int32, Int64, Float32, Float64,
-    Binary, Date, String, Json
+    Binary, Date, String, Json, Uuid, Ip
 );
 
 impl ConcreteDataType {
@@ -635,6 +692,13 @@ impl ConcreteDataType {
         ConcreteDataType::List(ListType::new(item_type))
     }
 
+    pub fn map_datatype(
+        key_type: ConcreteDataType,
+        value_type: ConcreteDataType,
+    ) -> ConcreteDataType {
+        ConcreteDataType::Map(MapType::new(key_type, value_type))
+    }
+
     pub fn struct_datatype(fields: StructType) -> ConcreteDataType {
         ConcreteDataType::Struct(fields)
     }
@@ -778,6 +842,41 @@ mod tests {
             ConcreteDataType::from_arrow_type(&ArrowDataType::Date32),
             ConcreteDataType::Date(_)
         ));
+        let entries = Field::new(
+            "entries",
+            ArrowDataType::Struct(
+                vec![
+                    Field::new("keys", ArrowDataType::Utf8, false),
+                    Field::new("values", ArrowDataType::Int64, true),
+                ]
+                .into(),
+            ),
+            false,
+        );
+        assert_eq!(
+            ConcreteDataType::from_arrow_type(&ArrowDataType::Map(
+                Arc::new(entries.clone()),
+                false
+            )),
+            ConcreteDataType::map_datatype(
+                ConcreteDataType::string_datatype(),
+                ConcreteDataType::int64_datatype()
+            )
+        );
+        let unsupported = Field::new(
+            "entries",
+            ArrowDataType::Struct(
+                vec![
+                    Field::new("keys", ArrowDataType::Float64, false),
+                    Field::new("values", ArrowDataType::Int64, true),
+                ]
+                .into(),
+            ),
+            false,
+        );
+        assert!(
+            ConcreteDataType::try_from(&ArrowDataType::Map(Arc::new(unsupported), false)).is_err()
+        );
     }
 
     #[test]
@@ -854,6 +953,13 @@ mod tests {
         assert!(ConcreteDataType::duration_nanosecond_datatype().is_stringifiable());
         assert!(ConcreteDataType::decimal128_datatype(10, 2).is_stringifiable());
         assert!(ConcreteDataType::vector_default_datatype().is_stringifiable());
+        assert!(ConcreteDataType::uuid_datatype().is_stringifiable());
+        assert!(ConcreteDataType::ip_datatype().is_stringifiable());
+        assert!(ConcreteDataType::map_datatype(
+            ConcreteDataType::string_datatype(),
+            ConcreteDataType::string_datatype()
+        )
+        .is_stringifiable());
     }
 
     #[test]
@@ -1008,5 +1114,15 @@ mod tests {
             ConcreteDataType::vector_datatype(3).to_string(),
             "Vector(3)"
         );
+        assert_eq!(ConcreteDataType::uuid_datatype().to_string(), "Uuid");
+        assert_eq!(ConcreteDataType::ip_datatype().to_string(), "Ip");
+        assert_eq!(
+            ConcreteDataType::map_datatype(
+                ConcreteDataType::string_datatype(),
+                ConcreteDataType::uint64_datatype()
+            )
+            .to_string(),
+            "Map<String, UInt64>"
+        );
     }
 }
@@ -203,6 +203,27 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Invalid Uuid: {}", msg))]
+    InvalidUuid {
+        msg: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Invalid Map: {}", msg))]
+    InvalidMap {
+        msg: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Invalid Ip: {}", msg))]
+    InvalidIp {
+        msg: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
     #[snafu(display("Value exceeds the precision {} bound", precision))]
     ValueExceedsPrecision {
         precision: u8,
@@ -258,6 +279,9 @@ impl ErrorExt for Error {
             | InvalidPrecisionOrScale { .. }
             | InvalidJson { .. }
             | InvalidVector { .. }
+            | InvalidUuid { .. }
+            | InvalidMap { .. }
+            | InvalidIp { .. }
             | InvalidFulltextOption { .. }
             | InvalidSkippingIndexOption { .. } => StatusCode::InvalidArguments,
 
@@ -266,10 +266,13 @@ fn collect_fields(column_schemas: &[ColumnSchema]) -> Result<FieldsAndIndices> {
         }
         let mut field = Field::try_from(column_schema)?;
 
-        // Column with type Json or Vector performs the same as binary column in Arrow, so we need to mark it
-        let extype = match column_schema.data_type {
+        // Column with type Json, Vector, Uuid, Map or Ip performs the same as binary column in Arrow, so we need to mark it
+        let extype = match &column_schema.data_type {
             ConcreteDataType::Json(_) => Some(ColumnExtType::Json),
             ConcreteDataType::Vector(d) => Some(ColumnExtType::Vector(d.dim)),
+            ConcreteDataType::Uuid(_) => Some(ColumnExtType::Uuid),
+            ConcreteDataType::Map(m) => Some(ColumnExtType::Map(m.clone())),
+            ConcreteDataType::Ip(_) => Some(ColumnExtType::Ip),
             _ => None,
         };
         if let Some(extype) = extype {
@@ -25,6 +25,7 @@ use crate::data_type::{ConcreteDataType, DataType};
 use crate::error::{self, Error, InvalidFulltextOptionSnafu, ParseExtendedTypeSnafu, Result};
 use crate::schema::constraint::ColumnDefaultConstraint;
 use crate::schema::TYPE_KEY;
+use crate::types::MapType;
 use crate::value::Value;
 use crate::vectors::VectorRef;
 
@@ -401,6 +402,15 @@ pub enum ColumnExtType {
 
     /// Vector type with dimension.
     Vector(u32),
+
+    /// Uuid type.
+    Uuid,
+
+    /// Map type with key and value types.
+    Map(MapType),
+
+    /// Ip type.
+    Ip,
 }
 
 impl fmt::Display for ColumnExtType {
@@ -408,6 +418,9 @@ impl fmt::Display for ColumnExtType {
         match self {
             ColumnExtType::Json => write!(f, "Json"),
             ColumnExtType::Vector(dim) => write!(f, "Vector({})", dim),
+            ColumnExtType::Uuid => write!(f, "Uuid"),
+            ColumnExtType::Map(m) => write!(f, "{}", m.name()),
+            ColumnExtType::Ip => write!(f, "Ip"),
         }
     }
 }
@@ -418,6 +431,11 @@ impl FromStr for ColumnExtType {
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         match s {
             "Json" => Ok(ColumnExtType::Json),
+            "Uuid" => Ok(ColumnExtType::Uuid),
+            "Ip" => Ok(ColumnExtType::Ip),
+            _ if s.starts_with("Map<") => MapType::from_str(s)
+                .map(ColumnExtType::Map)
+                .map_err(|e| e.to_string()),
             _ if s.starts_with("Vector(") && s.ends_with(')') => s[7..s.len() - 1]
                 .parse::<u32>()
                 .map(ColumnExtType::Vector)
@@ -443,6 +461,15 @@ impl TryFrom<&Field> for ColumnSchema {
                 ColumnExtType::Vector(dim) => {
                     data_type = ConcreteDataType::vector_datatype(dim);
                 }
+                ColumnExtType::Uuid => {
+                    data_type = ConcreteDataType::uuid_datatype();
+                }
+                ColumnExtType::Map(m) => {
+                    data_type = ConcreteDataType::Map(m);
+                }
+                ColumnExtType::Ip => {
+                    data_type = ConcreteDataType::ip_datatype();
+                }
             }
         }
         let mut metadata = field.metadata().clone();
@@ -1143,6 +1170,26 @@ mod tests {
             column_schema.metadata.get(TYPE_KEY).unwrap(),
             &ConcreteDataType::vector_datatype(3).name()
         );
+
+        let map_type = ConcreteDataType::map_datatype(
+            ConcreteDataType::string_datatype(),
+            ConcreteDataType::int64_datatype(),
+        );
+        for data_type in [
+            ConcreteDataType::uuid_datatype(),
+            map_type,
+            ConcreteDataType::ip_datatype(),
+        ] {
+            let field = Field::new("test", ArrowDataType::Binary, true);
+            let field =
+                field.with_metadata(Metadata::from([(TYPE_KEY.to_string(), data_type.name())]));
+            let column_schema = ColumnSchema::try_from(&field).unwrap();
+            assert_eq!(data_type, column_schema.data_type);
+            assert_eq!(
+                column_schema.metadata.get(TYPE_KEY).unwrap(),
+                &data_type.name()
+            );
+        }
     }
 
     #[test]
@@ -70,6 +70,11 @@ pub enum LogicalTypeId {
     Json,
 
     Vector,
+
+    Uuid,
+    Map,
+
+    Ip,
 }
 
 impl LogicalTypeId {
@@ -130,6 +135,12 @@ impl LogicalTypeId {
             LogicalTypeId::Decimal128 => ConcreteDataType::decimal128_default_datatype(),
             LogicalTypeId::Json => ConcreteDataType::json_datatype(),
             LogicalTypeId::Vector => ConcreteDataType::vector_default_datatype(),
+            LogicalTypeId::Uuid => ConcreteDataType::uuid_datatype(),
+            LogicalTypeId::Map => ConcreteDataType::map_datatype(
+                ConcreteDataType::string_datatype(),
+                ConcreteDataType::string_datatype(),
+            ),
+            LogicalTypeId::Ip => ConcreteDataType::ip_datatype(),
         }
     }
 }
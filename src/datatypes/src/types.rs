@@ -20,14 +20,17 @@ mod decimal_type;
 mod dictionary_type;
 mod duration_type;
 mod interval_type;
+mod ip_type;
 mod json_type;
 mod list_type;
+mod map_type;
 mod null_type;
 mod primitive_type;
 mod string_type;
 mod struct_type;
 mod time_type;
 mod timestamp_type;
+mod uuid_type;
 mod vector_type;
 
 pub use binary_type::BinaryType;
@@ -43,10 +46,19 @@ pub use duration_type::{
 pub use interval_type::{
     IntervalDayTimeType, IntervalMonthDayNanoType, IntervalType, IntervalYearMonthType,
 };
+pub use ip_type::{
+    ip_addr_to_ip_type_value, ip_type_value_to_ip_addr, ip_type_value_to_string,
+    parse_cidr_to_ip_type_range, parse_string_to_ip_type_value, IpType, IP_BYTE_SIZE,
+};
 pub use json_type::{
-    json_type_value_to_string, parse_string_to_json_type_value, JsonType, JSON_TYPE_NAME,
+    json_type_value_to_string, parse_string_to_json_type_value, JsonFormat, JsonType,
+    JSON_TYPE_NAME,
 };
 pub use list_type::ListType;
+pub use map_type::{
+    decode_map_type_value, map_type_value_to_json, map_type_value_to_string,
+    parse_string_to_map_type_value, MapType,
+};
 pub use null_type::NullType;
 pub use primitive_type::{
     Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, LogicalPrimitiveType,
@@ -61,4 +73,7 @@ pub use timestamp_type::{
     TimestampMicrosecondType, TimestampMillisecondType, TimestampNanosecondType,
     TimestampSecondType, TimestampType,
 };
+pub use uuid_type::{
+    parse_string_to_uuid_type_value, uuid_type_value_to_string, UuidType, UUID_BYTE_SIZE,
+};
 pub use vector_type::{parse_string_to_vector_type_value, vector_type_value_to_string, VectorType};
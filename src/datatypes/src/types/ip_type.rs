@@ -0,0 +1,255 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::{IpAddr, Ipv6Addr};
+
+use arrow::datatypes::DataType as ArrowDataType;
+use common_base::bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::data_type::DataType;
+use crate::error::{InvalidIpSnafu, Result};
+use crate::scalars::ScalarVectorBuilder;
+use crate::type_id::LogicalTypeId;
+use crate::value::Value;
+use crate::vectors::{BinaryVectorBuilder, MutableVector};
+
+/// Size in bytes of an IP address value.
+pub const IP_BYTE_SIZE: usize = 16;
+
+/// `IpType` is a data type for IPv4 and IPv6 addresses.
+///
+/// It is stored as binary data that contains the 16 bytes of the IPv6 address in big-endian
+/// order. IPv4 addresses are stored as IPv4-mapped IPv6 addresses (`::ffff:a.b.c.d`), so the
+/// byte order of two values is the same as the numeric order of the addresses, and a CIDR
+/// block is always a contiguous range of values.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub struct IpType;
+
+impl DataType for IpType {
+    fn name(&self) -> String {
+        "Ip".to_string()
+    }
+
+    fn logical_type_id(&self) -> LogicalTypeId {
+        LogicalTypeId::Ip
+    }
+
+    fn default_value(&self) -> Value {
+        Bytes::from(Ipv6Addr::UNSPECIFIED.octets().as_slice()).into()
+    }
+
+    fn as_arrow_type(&self) -> ArrowDataType {
+        ArrowDataType::Binary
+    }
+
+    fn create_mutable_vector(&self, capacity: usize) -> Box<dyn MutableVector> {
+        Box::new(BinaryVectorBuilder::with_capacity(capacity))
+    }
+
+    fn try_cast(&self, from: Value) -> Option<Value> {
+        match from {
+            Value::Binary(v) if v.len() == IP_BYTE_SIZE => Some(Value::Binary(v)),
+            Value::String(v) => parse_string_to_ip_type_value(v.as_utf8())
+                .ok()
+                .map(Value::from),
+            _ => None,
+        }
+    }
+}
+
+/// Converts an [IpAddr] to an ip type value.
+pub fn ip_addr_to_ip_type_value(addr: IpAddr) -> [u8; IP_BYTE_SIZE] {
+    match addr {
+        IpAddr::V4(v4) => v4.to_ipv6_mapped().octets(),
+        IpAddr::V6(v6) => v6.octets(),
+    }
+}
+
+/// Converts an ip type value to an [IpAddr].
+/// IPv4-mapped values are converted back to IPv4 addresses.
+pub fn ip_type_value_to_ip_addr(val: &[u8]) -> Result<IpAddr> {
+    let octets: [u8; IP_BYTE_SIZE] = val.try_into().map_err(|_| {
+        InvalidIpSnafu {
+            msg: format!(
+                "Failed to convert Ip value: wrong byte size, expected {}, got {}",
+                IP_BYTE_SIZE,
+                val.len()
+            ),
+        }
+        .build()
+    })?;
+    let v6 = Ipv6Addr::from(octets);
+    Ok(match v6.to_ipv4_mapped() {
+        Some(v4) => IpAddr::V4(v4),
+        None => IpAddr::V6(v6),
+    })
+}
+
+/// Converts an ip type value to its string form,
+/// for example: "10.0.0.1" or "2001:db8::1".
+pub fn ip_type_value_to_string(val: &[u8]) -> Result<String> {
+    ip_type_value_to_ip_addr(val).map(|addr| addr.to_string())
+}
+
+/// Parses a string to an ip type value.
+/// Valid input formats are the dotted IPv4 form and the IPv6 forms.
+pub fn parse_string_to_ip_type_value(s: &str) -> Result<Vec<u8>> {
+    let addr = s.trim().parse::<IpAddr>().map_err(|e| {
+        InvalidIpSnafu {
+            msg: format!("Failed to parse {s} to Ip value: {e}"),
+        }
+        .build()
+    })?;
+    Ok(ip_addr_to_ip_type_value(addr).to_vec())
+}
+
+/// Parses a CIDR string, e.g. "10.0.0.0/8" or "2001:db8::/32", into the inclusive range
+/// `(first, last)` of ip type values it covers.
+///
+/// A single address without prefix length is treated as a host, e.g. "10.0.0.1" is "10.0.0.1/32".
+/// Host bits of the address are ignored.
+pub fn parse_cidr_to_ip_type_range(s: &str) -> Result<([u8; IP_BYTE_SIZE], [u8; IP_BYTE_SIZE])> {
+    let s = s.trim();
+    let (addr, prefix) = match s.split_once('/') {
+        Some((addr, prefix)) => (addr, Some(prefix)),
+        None => (s, None),
+    };
+    let addr = addr.parse::<IpAddr>().map_err(|e| {
+        InvalidIpSnafu {
+            msg: format!("Failed to parse {s} to CIDR: {e}"),
+        }
+        .build()
+    })?;
+    let max_prefix = if addr.is_ipv4() { 32 } else { 128 };
+    let prefix = match prefix {
+        Some(prefix) => prefix
+            .parse::<u32>()
+            .ok()
+            .filter(|p| *p <= max_prefix)
+            .ok_or_else(|| {
+                InvalidIpSnafu {
+                    msg: format!("Failed to parse {s} to CIDR: invalid prefix length {prefix}"),
+                }
+                .build()
+            })?,
+        None => max_prefix,
+    };
+    // IPv4 addresses live in the last 32 bits of the IPv4-mapped space.
+    let prefix = prefix + (128 - max_prefix);
+
+    let value = u128::from_be_bytes(ip_addr_to_ip_type_value(addr));
+    let host_mask = u128::MAX.checked_shr(prefix).unwrap_or(0);
+    let first = value & !host_mask;
+    let last = value | host_mask;
+    Ok((first.to_be_bytes(), last.to_be_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion_between_string_and_ip_type_value() {
+        let cases = [
+            ("10.0.0.1", "10.0.0.1"),
+            (" 192.168.1.255 ", "192.168.1.255"),
+            ("::ffff:127.0.0.1", "127.0.0.1"),
+            ("2001:0db8:0000:0000:0000:0000:0000:0001", "2001:db8::1"),
+            ("::", "::"),
+        ];
+
+        for (s, expected) in cases {
+            let val = parse_string_to_ip_type_value(s).unwrap();
+            assert_eq!(IP_BYTE_SIZE, val.len());
+            assert_eq!(expected, ip_type_value_to_string(&val).unwrap());
+        }
+
+        assert_eq!(
+            &[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, 10, 0, 0, 1],
+            parse_string_to_ip_type_value("10.0.0.1")
+                .unwrap()
+                .as_slice()
+        );
+        assert!(parse_string_to_ip_type_value("10.0.0").is_err());
+        assert!(parse_string_to_ip_type_value("10.0.0.0/8").is_err());
+        assert!(ip_type_value_to_string(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_ip_type_value_order() {
+        let values = [
+            "0.0.0.1",
+            "9.255.255.255",
+            "10.0.0.0",
+            "255.255.255.255",
+            "2001:db8::",
+        ]
+        .map(|s| parse_string_to_ip_type_value(s).unwrap());
+        assert!(values.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_parse_cidr_to_ip_type_range() {
+        let cases = [
+            ("10.0.0.0/8", "10.0.0.0", "10.255.255.255"),
+            ("10.1.2.3/16", "10.1.0.0", "10.1.255.255"),
+            ("10.0.0.1", "10.0.0.1", "10.0.0.1"),
+            ("10.0.0.1/32", "10.0.0.1", "10.0.0.1"),
+            ("0.0.0.0/0", "0.0.0.0", "255.255.255.255"),
+            (
+                "2001:db8::/32",
+                "2001:db8::",
+                "2001:db8:ffff:ffff:ffff:ffff:ffff:ffff",
+            ),
+            ("::/0", "::", "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff"),
+            ("2001:db8::1/128", "2001:db8::1", "2001:db8::1"),
+        ];
+
+        for (cidr, first, last) in cases {
+            let (start, end) = parse_cidr_to_ip_type_range(cidr).unwrap();
+            assert_eq!(first, ip_type_value_to_string(&start).unwrap(), "{cidr}");
+            assert_eq!(last, ip_type_value_to_string(&end).unwrap(), "{cidr}");
+        }
+
+        assert!(parse_cidr_to_ip_type_range("10.0.0.0/33").is_err());
+        assert!(parse_cidr_to_ip_type_range("2001:db8::/129").is_err());
+        assert!(parse_cidr_to_ip_type_range("10.0.0.0/x").is_err());
+        assert!(parse_cidr_to_ip_type_range("not a cidr").is_err());
+    }
+
+    #[test]
+    fn test_ip_type_try_cast() {
+        let t = IpType;
+        assert_eq!("Ip", t.name());
+        assert_eq!(ArrowDataType::Binary, t.as_arrow_type());
+
+        let bytes = vec![7u8; IP_BYTE_SIZE];
+        assert_eq!(
+            Some(Value::from(bytes.clone())),
+            t.try_cast(Value::from(bytes))
+        );
+        assert_eq!(None, t.try_cast(Value::from(vec![7u8; 4])));
+        assert_eq!(
+            Some(Value::from(
+                parse_string_to_ip_type_value("10.0.0.1").unwrap()
+            )),
+            t.try_cast(Value::from("10.0.0.1"))
+        );
+        assert_eq!(None, t.try_cast(Value::Int32(1)));
+        assert_eq!(Value::from(vec![0u8; IP_BYTE_SIZE]), t.default_value());
+    }
+}
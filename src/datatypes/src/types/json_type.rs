@@ -28,6 +28,9 @@ pub const JSON_TYPE_NAME: &str = "Json";
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum JsonFormat {
     Jsonb,
+    /// Values are jsonb in memory, but frequently seen paths are shredded into
+    /// typed sub-columns when they are stored in files.
+    Shredded,
 }
 
 impl Default for JsonFormat {
@@ -83,14 +86,14 @@ impl DataType for JsonType {
 /// Converts a json type value to string
 pub fn json_type_value_to_string(val: &[u8], format: &JsonFormat) -> Result<String> {
     match format {
-        JsonFormat::Jsonb => Ok(jsonb::to_string(val)),
+        JsonFormat::Jsonb | JsonFormat::Shredded => Ok(jsonb::to_string(val)),
     }
 }
 
 /// Parses a string to a json type value
 pub fn parse_string_to_json_type_value(s: &str, format: &JsonFormat) -> Result<Vec<u8>> {
     match format {
-        JsonFormat::Jsonb => jsonb::parse_value(s.as_bytes())
+        JsonFormat::Jsonb | JsonFormat::Shredded => jsonb::parse_value(s.as_bytes())
             .map_err(|_| InvalidJsonSnafu { value: s }.build())
             .map(|json| json.to_vec()),
     }
@@ -0,0 +1,607 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use arrow::datatypes::DataType as ArrowDataType;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt};
+
+use crate::data_type::{ConcreteDataType, DataType};
+use crate::error::{Error, InvalidMapSnafu, Result, SerializeSnafu};
+use crate::scalars::ScalarVectorBuilder;
+use crate::type_id::LogicalTypeId;
+use crate::value::Value;
+use crate::vectors::{BinaryVectorBuilder, MutableVector};
+
+/// Size of the header of an encoded map value: key tag, value tag and entry count.
+const MAP_HEADER_SIZE: usize = 6;
+/// The largest type tag of map keys and values.
+const MAX_TYPE_TAG: u8 = 12;
+
+/// `MapType` is a data type for key-value pairs whose keys and values are scalars,
+/// e.g. label-like attributes.
+///
+/// It is stored as binary data in the following layout:
+/// ```text
+/// | key type tag: u8 | value type tag: u8 | entry count: u32 |
+/// | key | value null flag: u8 | value (absent if null) | ...
+/// ```
+/// Numbers are little-endian, strings and binaries are prefixed by their length as u32.
+/// Entries are sorted by key without duplicate keys, so equal maps are encoded to the same bytes.
+/// As the header records the key and value types, a map value can be decoded
+/// without knowing its column type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct MapType {
+    /// The type of the keys.
+    // Use Box to avoid recursive dependency, as enum ConcreteDataType depends on MapType.
+    key_type: Box<ConcreteDataType>,
+    /// The type of the values.
+    value_type: Box<ConcreteDataType>,
+}
+
+impl Default for MapType {
+    fn default() -> Self {
+        MapType::new(
+            ConcreteDataType::string_datatype(),
+            ConcreteDataType::string_datatype(),
+        )
+    }
+}
+
+impl MapType {
+    /// Creates a new `MapType` from the key and value types.
+    pub fn new(key_type: ConcreteDataType, value_type: ConcreteDataType) -> Self {
+        MapType {
+            key_type: Box::new(key_type),
+            value_type: Box::new(value_type),
+        }
+    }
+
+    /// Creates a new `MapType` and checks the key and value types are supported.
+    pub fn try_new(key_type: ConcreteDataType, value_type: ConcreteDataType) -> Result<Self> {
+        ensure!(
+            Self::is_supported_key_type(&key_type),
+            InvalidMapSnafu {
+                msg: format!("Unsupported map key type: {}", key_type),
+            }
+        );
+        ensure!(
+            Self::is_supported_value_type(&value_type),
+            InvalidMapSnafu {
+                msg: format!("Unsupported map value type: {}", value_type),
+            }
+        );
+        Ok(Self::new(key_type, value_type))
+    }
+
+    /// Returns the key data type.
+    #[inline]
+    pub fn key_type(&self) -> &ConcreteDataType {
+        &self.key_type
+    }
+
+    /// Returns the value data type.
+    #[inline]
+    pub fn value_type(&self) -> &ConcreteDataType {
+        &self.value_type
+    }
+
+    /// Returns true if the data type can be used as the key type of a map.
+    /// Floats are not allowed as their equality is not well-defined.
+    pub fn is_supported_key_type(data_type: &ConcreteDataType) -> bool {
+        type_tag(data_type).is_some() && !data_type.is_float()
+    }
+
+    /// Returns true if the data type can be used as the value type of a map.
+    pub fn is_supported_value_type(data_type: &ConcreteDataType) -> bool {
+        type_tag(data_type).is_some()
+    }
+
+    /// Encodes the entries into a map type value.
+    /// Keys and values are cast to the key and value types of the map. Entries are sorted
+    /// by key, and the last entry wins if a key occurs more than once.
+    pub fn encode_entries(
+        &self,
+        entries: impl IntoIterator<Item = (Value, Value)>,
+    ) -> Result<Vec<u8>> {
+        let (Some(key_tag), Some(value_tag)) =
+            (type_tag(&self.key_type), type_tag(&self.value_type))
+        else {
+            return InvalidMapSnafu {
+                msg: format!("Unsupported map type: {}", self.name()),
+            }
+            .fail();
+        };
+
+        let mut normalized = BTreeMap::new();
+        for (key, value) in entries {
+            ensure!(
+                !key.is_null(),
+                InvalidMapSnafu {
+                    msg: "Map key must not be null",
+                }
+            );
+            let key = cast_entry(&self.key_type, key)?;
+            let value = if value.is_null() {
+                Value::Null
+            } else {
+                cast_entry(&self.value_type, value)?
+            };
+            normalized.insert(key, value);
+        }
+
+        let mut buf = Vec::with_capacity(MAP_HEADER_SIZE);
+        buf.extend_from_slice(&[key_tag, value_tag]);
+        let count = u32::try_from(normalized.len()).map_err(|_| {
+            InvalidMapSnafu {
+                msg: format!("Too many map entries: {}", normalized.len()),
+            }
+            .build()
+        })?;
+        buf.extend_from_slice(&count.to_le_bytes());
+        for (key, value) in normalized {
+            encode_scalar(&mut buf, key)?;
+            if value.is_null() {
+                buf.push(0);
+            } else {
+                buf.push(1);
+                encode_scalar(&mut buf, value)?;
+            }
+        }
+
+        Ok(buf)
+    }
+}
+
+impl DataType for MapType {
+    fn name(&self) -> String {
+        format!("Map<{}, {}>", self.key_type.name(), self.value_type.name())
+    }
+
+    fn logical_type_id(&self) -> LogicalTypeId {
+        LogicalTypeId::Map
+    }
+
+    fn default_value(&self) -> Value {
+        self.encode_entries([])
+            .map(Value::from)
+            .unwrap_or(Value::Null)
+    }
+
+    fn as_arrow_type(&self) -> ArrowDataType {
+        ArrowDataType::Binary
+    }
+
+    fn create_mutable_vector(&self, capacity: usize) -> Box<dyn MutableVector> {
+        Box::new(BinaryVectorBuilder::with_capacity(capacity))
+    }
+
+    fn try_cast(&self, from: Value) -> Option<Value> {
+        match from {
+            Value::Binary(v) => {
+                let (map_type, _) = decode_map_type_value(&v).ok()?;
+                (map_type == *self).then_some(Value::Binary(v))
+            }
+            Value::String(v) => parse_string_to_map_type_value(v.as_utf8(), self)
+                .ok()
+                .map(Value::from),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for MapType {
+    type Err = Error;
+
+    /// Parses the name of a map type, e.g. `Map<String, Int64>`.
+    fn from_str(s: &str) -> Result<Self> {
+        let entry_type = |name: &str| {
+            (0..=MAX_TYPE_TAG)
+                .filter_map(|tag| tag_to_type(tag).ok())
+                .find(|t| t.name() == name.trim())
+        };
+        let parsed = s
+            .strip_prefix("Map<")
+            .and_then(|s| s.strip_suffix('>'))
+            .and_then(|s| s.split_once(','))
+            .and_then(|(key, value)| Some((entry_type(key)?, entry_type(value)?)));
+        let Some((key_type, value_type)) = parsed else {
+            return InvalidMapSnafu {
+                msg: format!("Invalid map type name: {s}"),
+            }
+            .fail();
+        };
+        MapType::try_new(key_type, value_type)
+    }
+}
+
+/// Returns the tag of the data type in the encoded map value,
+/// or `None` if the type can't be used in a map.
+fn type_tag(data_type: &ConcreteDataType) -> Option<u8> {
+    let tag = match data_type {
+        ConcreteDataType::Boolean(_) => 0,
+        ConcreteDataType::Int8(_) => 1,
+        ConcreteDataType::Int16(_) => 2,
+        ConcreteDataType::Int32(_) => 3,
+        ConcreteDataType::Int64(_) => 4,
+        ConcreteDataType::UInt8(_) => 5,
+        ConcreteDataType::UInt16(_) => 6,
+        ConcreteDataType::UInt32(_) => 7,
+        ConcreteDataType::UInt64(_) => 8,
+        ConcreteDataType::Float32(_) => 9,
+        ConcreteDataType::Float64(_) => 10,
+        ConcreteDataType::String(_) => 11,
+        ConcreteDataType::Binary(_) => 12,
+        _ => return None,
+    };
+    Some(tag)
+}
+
+fn tag_to_type(tag: u8) -> Result<ConcreteDataType> {
+    let data_type = match tag {
+        0 => ConcreteDataType::boolean_datatype(),
+        1 => ConcreteDataType::int8_datatype(),
+        2 => ConcreteDataType::int16_datatype(),
+        3 => ConcreteDataType::int32_datatype(),
+        4 => ConcreteDataType::int64_datatype(),
+        5 => ConcreteDataType::uint8_datatype(),
+        6 => ConcreteDataType::uint16_datatype(),
+        7 => ConcreteDataType::uint32_datatype(),
+        8 => ConcreteDataType::uint64_datatype(),
+        9 => ConcreteDataType::float32_datatype(),
+        10 => ConcreteDataType::float64_datatype(),
+        11 => ConcreteDataType::string_datatype(),
+        12 => ConcreteDataType::binary_datatype(),
+        _ => {
+            return InvalidMapSnafu {
+                msg: format!("Unknown map entry type tag: {}", tag),
+            }
+            .fail()
+        }
+    };
+    Ok(data_type)
+}
+
+fn cast_entry(data_type: &ConcreteDataType, value: Value) -> Result<Value> {
+    if value.logical_type_id() == data_type.logical_type_id() {
+        return Ok(value);
+    }
+    let msg = format!("Failed to cast {} to map entry type {}", value, data_type);
+    data_type
+        .try_cast(value)
+        .ok_or_else(|| InvalidMapSnafu { msg }.build())
+}
+
+fn encode_len(buf: &mut Vec<u8>, len: usize) -> Result<()> {
+    let len = u32::try_from(len).map_err(|_| {
+        InvalidMapSnafu {
+            msg: format!("Map entry is too large: {} bytes", len),
+        }
+        .build()
+    })?;
+    buf.extend_from_slice(&len.to_le_bytes());
+    Ok(())
+}
+
+fn encode_scalar(buf: &mut Vec<u8>, value: Value) -> Result<()> {
+    match value {
+        Value::Boolean(v) => buf.push(v as u8),
+        Value::Int8(v) => buf.extend_from_slice(&v.to_le_bytes()),
+        Value::Int16(v) => buf.extend_from_slice(&v.to_le_bytes()),
+        Value::Int32(v) => buf.extend_from_slice(&v.to_le_bytes()),
+        Value::Int64(v) => buf.extend_from_slice(&v.to_le_bytes()),
+        Value::UInt8(v) => buf.push(v),
+        Value::UInt16(v) => buf.extend_from_slice(&v.to_le_bytes()),
+        Value::UInt32(v) => buf.extend_from_slice(&v.to_le_bytes()),
+        Value::UInt64(v) => buf.extend_from_slice(&v.to_le_bytes()),
+        Value::Float32(v) => buf.extend_from_slice(&v.0.to_le_bytes()),
+        Value::Float64(v) => buf.extend_from_slice(&v.0.to_le_bytes()),
+        Value::String(v) => {
+            let bytes = v.as_utf8().as_bytes();
+            encode_len(buf, bytes.len())?;
+            buf.extend_from_slice(bytes);
+        }
+        Value::Binary(v) => {
+            encode_len(buf, v.len())?;
+            buf.extend_from_slice(&v);
+        }
+        v => {
+            return InvalidMapSnafu {
+                msg: format!("Unsupported map entry value: {}", v),
+            }
+            .fail()
+        }
+    }
+    Ok(())
+}
+
+/// Reads the encoded map value sequentially.
+struct MapReader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> MapReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        ensure!(
+            self.buf.len() >= len,
+            InvalidMapSnafu {
+                msg: "Unexpected end of map value",
+            }
+        );
+        let (head, tail) = self.buf.split_at(len);
+        self.buf = tail;
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        // `take` returns exactly N bytes so the conversion never fails.
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn take_sized(&mut self) -> Result<&'a [u8]> {
+        let len = u32::from_le_bytes(self.take_array()?) as usize;
+        self.take(len)
+    }
+
+    fn read_scalar(&mut self, tag: u8) -> Result<Value> {
+        let value = match tag {
+            0 => Value::from(self.take_array::<1>()?[0] != 0),
+            1 => Value::from(i8::from_le_bytes(self.take_array()?)),
+            2 => Value::from(i16::from_le_bytes(self.take_array()?)),
+            3 => Value::from(i32::from_le_bytes(self.take_array()?)),
+            4 => Value::from(i64::from_le_bytes(self.take_array()?)),
+            5 => Value::from(u8::from_le_bytes(self.take_array()?)),
+            6 => Value::from(u16::from_le_bytes(self.take_array()?)),
+            7 => Value::from(u32::from_le_bytes(self.take_array()?)),
+            8 => Value::from(u64::from_le_bytes(self.take_array()?)),
+            9 => Value::from(f32::from_le_bytes(self.take_array()?)),
+            10 => Value::from(f64::from_le_bytes(self.take_array()?)),
+            11 => {
+                let s = std::str::from_utf8(self.take_sized()?).map_err(|_| {
+                    InvalidMapSnafu {
+                        msg: "Map entry is not a valid UTF-8 string",
+                    }
+                    .build()
+                })?;
+                Value::from(s)
+            }
+            12 => Value::from(self.take_sized()?),
+            _ => {
+                return InvalidMapSnafu {
+                    msg: format!("Unknown map entry type tag: {}", tag),
+                }
+                .fail()
+            }
+        };
+        Ok(value)
+    }
+}
+
+/// Decodes a map type value into its type and entries.
+pub fn decode_map_type_value(val: &[u8]) -> Result<(MapType, Vec<(Value, Value)>)> {
+    let mut reader = MapReader { buf: val };
+    let [key_tag, value_tag] = reader.take_array()?;
+    let map_type = MapType::new(tag_to_type(key_tag)?, tag_to_type(value_tag)?);
+    let count = u32::from_le_bytes(reader.take_array()?) as usize;
+
+    // Each entry takes at least two bytes, avoid allocating too much for corrupted data.
+    let mut entries = Vec::with_capacity(count.min(val.len() / 2));
+    for _ in 0..count {
+        let key = reader.read_scalar(key_tag)?;
+        let value = if reader.take_array::<1>()?[0] == 0 {
+            Value::Null
+        } else {
+            reader.read_scalar(value_tag)?
+        };
+        entries.push((key, value));
+    }
+    ensure!(
+        reader.buf.is_empty(),
+        InvalidMapSnafu {
+            msg: "Unexpected trailing bytes in map value",
+        }
+    );
+
+    Ok((map_type, entries))
+}
+
+/// Converts a map type value to a JSON object string,
+/// for example: `{"host":"a","region":"b"}`.
+pub fn map_type_value_to_string(val: &[u8]) -> Result<String> {
+    map_type_value_to_json(val).map(|json| json.to_string())
+}
+
+/// Converts a map type value to a JSON object.
+pub fn map_type_value_to_json(val: &[u8]) -> Result<serde_json::Value> {
+    let (_, entries) = decode_map_type_value(val)?;
+    let mut object = serde_json::Map::with_capacity(entries.len());
+    for (key, value) in entries {
+        let value = match value {
+            // Display binary in hex.
+            Value::Binary(_) => serde_json::Value::String(value.to_string()),
+            v => serde_json::Value::try_from(v).context(SerializeSnafu)?,
+        };
+        object.insert(key.to_string(), value);
+    }
+    Ok(serde_json::Value::Object(object))
+}
+
+/// Parses a JSON object string to a map type value,
+/// for example: `{"host": "a", "region": "b"}`.
+pub fn parse_string_to_map_type_value(s: &str, map_type: &MapType) -> Result<Vec<u8>> {
+    let json = serde_json::from_str::<serde_json::Value>(s).map_err(|e| {
+        InvalidMapSnafu {
+            msg: format!("Failed to parse {s} to Map value: {e}"),
+        }
+        .build()
+    })?;
+    let serde_json::Value::Object(object) = json else {
+        return InvalidMapSnafu {
+            msg: format!("Failed to parse {s} to Map value: not a JSON object"),
+        }
+        .fail();
+    };
+
+    let entries = object.into_iter().map(|(key, value)| {
+        let value = match value {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(v) => Value::from(v),
+            serde_json::Value::Number(n) => {
+                if let Some(v) = n.as_i64() {
+                    Value::from(v)
+                } else if let Some(v) = n.as_u64() {
+                    Value::from(v)
+                } else {
+                    Value::from(n.as_f64().unwrap_or_default())
+                }
+            }
+            serde_json::Value::String(v) => Value::from(v),
+            v => Value::from(v.to_string()),
+        };
+        (Value::from(key), value)
+    });
+    map_type.encode_entries(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_type() {
+        let t = MapType::default();
+        assert_eq!("Map<String, String>", t.name());
+        assert_eq!(LogicalTypeId::Map, t.logical_type_id());
+        assert_eq!(ArrowDataType::Binary, t.as_arrow_type());
+        assert_eq!(&ConcreteDataType::string_datatype(), t.key_type());
+        assert_eq!(&ConcreteDataType::string_datatype(), t.value_type());
+
+        let Value::Binary(empty) = t.default_value() else {
+            unreachable!()
+        };
+        let (map_type, entries) = decode_map_type_value(&empty).unwrap();
+        assert_eq!(t, map_type);
+        assert!(entries.is_empty());
+
+        assert!(MapType::try_new(
+            ConcreteDataType::float64_datatype(),
+            ConcreteDataType::string_datatype()
+        )
+        .is_err());
+        assert!(MapType::try_new(
+            ConcreteDataType::string_datatype(),
+            ConcreteDataType::json_datatype()
+        )
+        .is_err());
+
+        let t = MapType::new(
+            ConcreteDataType::uint32_datatype(),
+            ConcreteDataType::binary_datatype(),
+        );
+        assert_eq!(t, MapType::from_str(&t.name()).unwrap());
+        assert!(MapType::from_str("Map<String>").is_err());
+        assert!(MapType::from_str("Map<Float64, String>").is_err());
+        assert!(MapType::from_str("List<String>").is_err());
+    }
+
+    #[test]
+    fn test_encode_decode_map_type_value() {
+        let t = MapType::try_new(
+            ConcreteDataType::string_datatype(),
+            ConcreteDataType::int64_datatype(),
+        )
+        .unwrap();
+        let encoded = t
+            .encode_entries([
+                (Value::from("a"), Value::Int64(1)),
+                (Value::from("b"), Value::Null),
+                // Casts to the value type.
+                (Value::from("c"), Value::Int32(-3)),
+            ])
+            .unwrap();
+        let (map_type, entries) = decode_map_type_value(&encoded).unwrap();
+        assert_eq!(t, map_type);
+        assert_eq!(
+            vec![
+                (Value::from("a"), Value::Int64(1)),
+                (Value::from("b"), Value::Null),
+                (Value::from("c"), Value::Int64(-3)),
+            ],
+            entries
+        );
+
+        // Entries are sorted by key and the last duplicate wins.
+        let normalized = t
+            .encode_entries([
+                (Value::from("c"), Value::Int64(-3)),
+                (Value::from("b"), Value::Int64(2)),
+                (Value::from("a"), Value::Int64(1)),
+                (Value::from("b"), Value::Null),
+            ])
+            .unwrap();
+        assert_eq!(encoded, normalized);
+
+        assert!(t.encode_entries([(Value::Null, Value::Int64(1))]).is_err());
+        assert!(t
+            .encode_entries([(Value::from("a"), Value::from("not a number"))])
+            .is_err());
+
+        assert!(decode_map_type_value(&encoded[..encoded.len() - 1]).is_err());
+        assert!(decode_map_type_value(&[11, 11]).is_err());
+        assert!(decode_map_type_value(&[42, 11, 0, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_conversion_between_string_and_map_type_value() {
+        let t = MapType::default();
+        let val = parse_string_to_map_type_value(r#"{"host": "a", "port": 4000, "up": true}"#, &t)
+            .unwrap();
+        assert_eq!(
+            r#"{"host":"a","port":"4000","up":"true"}"#,
+            map_type_value_to_string(&val).unwrap()
+        );
+
+        let t = MapType::new(
+            ConcreteDataType::uint32_datatype(),
+            ConcreteDataType::float64_datatype(),
+        );
+        let val = parse_string_to_map_type_value(r#"{"1": 1.5, "2": null}"#, &t).unwrap();
+        assert_eq!(
+            r#"{"1":1.5,"2":null}"#,
+            map_type_value_to_string(&val).unwrap()
+        );
+
+        assert!(parse_string_to_map_type_value("[1, 2]", &t).is_err());
+        assert!(parse_string_to_map_type_value("not json", &t).is_err());
+        assert!(parse_string_to_map_type_value(r#"{"a": 1}"#, &t).is_err());
+    }
+
+    #[test]
+    fn test_map_type_try_cast() {
+        let t = MapType::default();
+        let val = t.try_cast(Value::from(r#"{"k": "v"}"#)).unwrap();
+        assert!(matches!(val, Value::Binary(_)));
+        assert_eq!(Some(val.clone()), t.try_cast(val.clone()));
+
+        let other = MapType::new(
+            ConcreteDataType::string_datatype(),
+            ConcreteDataType::int64_datatype(),
+        );
+        assert_eq!(None, other.try_cast(val));
+        assert_eq!(None, t.try_cast(Value::from(vec![1u8, 2, 3])));
+        assert_eq!(None, t.try_cast(Value::Int64(1)));
+    }
+}
@@ -0,0 +1,152 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use arrow::datatypes::DataType as ArrowDataType;
+use common_base::bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::data_type::DataType;
+use crate::error::{InvalidUuidSnafu, Result};
+use crate::scalars::ScalarVectorBuilder;
+use crate::type_id::LogicalTypeId;
+use crate::value::Value;
+use crate::vectors::{BinaryVectorBuilder, MutableVector};
+
+/// Size in bytes of a UUID value.
+pub const UUID_BYTE_SIZE: usize = 16;
+
+/// `UuidType` is a data type for 128-bit universally unique identifiers.
+/// It is stored as binary data that contains the 16 bytes of the UUID in big-endian order.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub struct UuidType;
+
+impl DataType for UuidType {
+    fn name(&self) -> String {
+        "Uuid".to_string()
+    }
+
+    fn logical_type_id(&self) -> LogicalTypeId {
+        LogicalTypeId::Uuid
+    }
+
+    fn default_value(&self) -> Value {
+        Bytes::from(Uuid::nil().as_bytes().as_slice()).into()
+    }
+
+    fn as_arrow_type(&self) -> ArrowDataType {
+        ArrowDataType::Binary
+    }
+
+    fn create_mutable_vector(&self, capacity: usize) -> Box<dyn MutableVector> {
+        Box::new(BinaryVectorBuilder::with_capacity(capacity))
+    }
+
+    fn try_cast(&self, from: Value) -> Option<Value> {
+        match from {
+            Value::Binary(v) if v.len() == UUID_BYTE_SIZE => Some(Value::Binary(v)),
+            Value::String(v) => parse_string_to_uuid_type_value(v.as_utf8())
+                .ok()
+                .map(Value::from),
+            _ => None,
+        }
+    }
+}
+
+/// Converts a uuid type value to its canonical string form,
+/// for example: "67e55044-10b1-426f-9247-bb680e5fe0c8".
+pub fn uuid_type_value_to_string(val: &[u8]) -> Result<String> {
+    let uuid = Uuid::from_slice(val).map_err(|_| {
+        InvalidUuidSnafu {
+            msg: format!(
+                "Failed to convert Uuid value to string: wrong byte size, expected {}, got {}",
+                UUID_BYTE_SIZE,
+                val.len()
+            ),
+        }
+        .build()
+    })?;
+    Ok(uuid.hyphenated().to_string())
+}
+
+/// Parses a string to a uuid type value.
+/// Valid input formats are the hyphenated, simple, braced and urn forms.
+pub fn parse_string_to_uuid_type_value(s: &str) -> Result<Vec<u8>> {
+    let uuid = Uuid::parse_str(s.trim()).map_err(|e| {
+        InvalidUuidSnafu {
+            msg: format!("Failed to parse {s} to Uuid value: {e}"),
+        }
+        .build()
+    })?;
+    Ok(uuid.as_bytes().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion_between_string_and_uuid_type_value() {
+        let cases = [
+            (
+                "67e55044-10b1-426f-9247-bb680e5fe0c8",
+                "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            ),
+            (
+                "67E5504410B1426F9247BB680E5FE0C8",
+                "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            ),
+            (
+                "{67e55044-10b1-426f-9247-bb680e5fe0c8}",
+                "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            ),
+            (
+                "urn:uuid:00000000-0000-0000-0000-000000000000",
+                "00000000-0000-0000-0000-000000000000",
+            ),
+        ];
+
+        for (s, expected) in cases {
+            let val = parse_string_to_uuid_type_value(s).unwrap();
+            assert_eq!(UUID_BYTE_SIZE, val.len());
+            assert_eq!(expected, uuid_type_value_to_string(&val).unwrap());
+        }
+
+        assert!(parse_string_to_uuid_type_value("67e55044-10b1-426f").is_err());
+        assert!(parse_string_to_uuid_type_value("not a uuid").is_err());
+        assert!(uuid_type_value_to_string(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_uuid_type_try_cast() {
+        let t = UuidType;
+        assert_eq!("Uuid", t.name());
+        assert_eq!(ArrowDataType::Binary, t.as_arrow_type());
+
+        let bytes = vec![7u8; UUID_BYTE_SIZE];
+        assert_eq!(
+            Some(Value::from(bytes.clone())),
+            t.try_cast(Value::from(bytes))
+        );
+        assert_eq!(None, t.try_cast(Value::from(vec![7u8; 3])));
+        assert_eq!(
+            Some(Value::from(vec![0u8; UUID_BYTE_SIZE])),
+            t.try_cast(Value::from("00000000-0000-0000-0000-000000000000"))
+        );
+        assert_eq!(None, t.try_cast(Value::Int32(1)));
+        assert_eq!(Value::from(vec![0u8; UUID_BYTE_SIZE]), t.default_value());
+    }
+}
@@ -40,7 +40,10 @@ use crate::error::{self, ConvertArrowArrayToScalarsSnafu, Error, Result, TryFrom
 use crate::prelude::*;
 use crate::schema::ColumnSchema;
 use crate::type_id::LogicalTypeId;
-use crate::types::{IntervalType, ListType};
+use crate::types::{
+    ip_type_value_to_string, map_type_value_to_json, uuid_type_value_to_string, IntervalType,
+    ListType,
+};
 use crate::vectors::ListVector;
 
 pub type OrderedF32 = OrderedFloat<f32>;
@@ -385,8 +388,8 @@ impl Value {
         let value_type_id = self.logical_type_id();
         let output_type_id = output_type.logical_type_id();
         ensure!(
-            // Json type leverage Value(Binary) for storage.
-            output_type_id == value_type_id || self.is_null() || (output_type_id == LogicalTypeId::Json && value_type_id == LogicalTypeId::Binary),
+            // Json, Uuid, Map and Ip types leverage Value(Binary) for storage.
+            output_type_id == value_type_id || self.is_null() || (matches!(output_type_id, LogicalTypeId::Json | LogicalTypeId::Uuid | LogicalTypeId::Map | LogicalTypeId::Ip) && value_type_id == LogicalTypeId::Binary),
             error::ToScalarValueSnafu {
                 reason: format!(
                     "expect value to return output_type {output_type_id:?}, actual: {value_type_id:?}",
@@ -526,9 +529,12 @@ pub fn to_null_scalar_value(output_type: &ConcreteDataType) -> Result<ScalarValu
         ConcreteDataType::UInt64(_) => ScalarValue::UInt64(None),
         ConcreteDataType::Float32(_) => ScalarValue::Float32(None),
         ConcreteDataType::Float64(_) => ScalarValue::Float64(None),
-        ConcreteDataType::Binary(_) | ConcreteDataType::Json(_) | ConcreteDataType::Vector(_) => {
-            ScalarValue::Binary(None)
-        }
+        ConcreteDataType::Binary(_)
+        | ConcreteDataType::Json(_)
+        | ConcreteDataType::Vector(_)
+        | ConcreteDataType::Uuid(_)
+        | ConcreteDataType::Map(_)
+        | ConcreteDataType::Ip(_) => ScalarValue::Binary(None),
         ConcreteDataType::String(_) => ScalarValue::Utf8(None),
         ConcreteDataType::Date(_) => ScalarValue::Date32(None),
         ConcreteDataType::Timestamp(t) => timestamp_to_scalar_value(t.unit(), None),
@@ -1312,19 +1318,37 @@ pub fn transform_value_ref_to_json_value<'a>(
         ValueRef::Float32(v) => serde_json::Value::from(v.0),
         ValueRef::Float64(v) => serde_json::Value::from(v.0),
         ValueRef::String(bytes) => serde_json::Value::String(bytes.to_string()),
-        ValueRef::Binary(bytes) => {
-            if let ConcreteDataType::Json(_) = schema.data_type {
-                match jsonb::from_slice(bytes) {
-                    Ok(json) => json.into(),
-                    Err(e) => {
-                        error!(e; "Failed to parse jsonb");
-                        serde_json::Value::Null
-                    }
+        ValueRef::Binary(bytes) => match schema.data_type {
+            ConcreteDataType::Json(_) => match jsonb::from_slice(bytes) {
+                Ok(json) => json.into(),
+                Err(e) => {
+                    error!(e; "Failed to parse jsonb");
+                    serde_json::Value::Null
                 }
-            } else {
-                serde_json::to_value(bytes)?
-            }
-        }
+            },
+            ConcreteDataType::Uuid(_) => match uuid_type_value_to_string(bytes) {
+                Ok(uuid) => serde_json::Value::String(uuid),
+                Err(e) => {
+                    error!(e; "Failed to parse uuid");
+                    serde_json::Value::Null
+                }
+            },
+            ConcreteDataType::Map(_) => match map_type_value_to_json(bytes) {
+                Ok(map) => map,
+                Err(e) => {
+                    error!(e; "Failed to parse map");
+                    serde_json::Value::Null
+                }
+            },
+            ConcreteDataType::Ip(_) => match ip_type_value_to_string(bytes) {
+                Ok(ip) => serde_json::Value::String(ip),
+                Err(e) => {
+                    error!(e; "Failed to parse ip");
+                    serde_json::Value::Null
+                }
+            },
+            _ => serde_json::to_value(bytes)?,
+        },
         ValueRef::Date(v) => serde_json::Value::Number(v.val().into()),
         ValueRef::List(v) => serde_json::to_value(v)?,
         ValueRef::Timestamp(v) => serde_json::to_value(v.value())?,
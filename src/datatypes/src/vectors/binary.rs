@@ -19,11 +19,17 @@ use arrow::array::{Array, ArrayBuilder, ArrayIter, ArrayRef};
 use snafu::ResultExt;
 
 use crate::arrow_array::{BinaryArray, MutableBinaryArray};
-use crate::data_type::ConcreteDataType;
-use crate::error::{self, InvalidVectorSnafu, Result};
+use crate::data_type::{ConcreteDataType, DataType};
+use crate::error::{
+    self, InvalidIpSnafu, InvalidMapSnafu, InvalidUuidSnafu, InvalidVectorSnafu, Result,
+};
 use crate::scalars::{ScalarVector, ScalarVectorBuilder};
 use crate::serialize::Serializable;
-use crate::types::parse_string_to_vector_type_value;
+use crate::types::{
+    decode_map_type_value, parse_string_to_ip_type_value, parse_string_to_map_type_value,
+    parse_string_to_uuid_type_value, parse_string_to_vector_type_value, MapType, IP_BYTE_SIZE,
+    UUID_BYTE_SIZE,
+};
 use crate::value::{Value, ValueRef};
 use crate::vectors::{self, MutableVector, Validity, Vector, VectorRef};
 
@@ -106,6 +112,92 @@ impl BinaryVector {
         }
         Ok(BinaryVector::from(vector))
     }
+
+    /// Creates a new binary vector of uuids from a binary vector.
+    /// Each value must be either 16 bytes or a uuid string.
+    pub fn convert_binary_to_uuid(&self) -> Result<BinaryVector> {
+        let mut vector = Vec::with_capacity(self.array.len());
+        for binary in self.array.iter() {
+            let Some(binary) = binary else {
+                vector.push(None);
+                continue;
+            };
+
+            if binary.len() == UUID_BYTE_SIZE {
+                vector.push(Some(binary.to_vec()));
+                continue;
+            }
+
+            let s = std::str::from_utf8(binary).map_err(|_| {
+                InvalidUuidSnafu {
+                    msg: format!(
+                        "Unexpected bytes size for uuid value, expected {}, got {}",
+                        UUID_BYTE_SIZE,
+                        binary.len()
+                    ),
+                }
+                .build()
+            })?;
+            vector.push(Some(parse_string_to_uuid_type_value(s)?));
+        }
+        Ok(BinaryVector::from(vector))
+    }
+
+    /// Creates a new binary vector of maps from a binary vector.
+    /// Each value must be either an encoded map of the same type or a JSON object string.
+    pub fn convert_binary_to_map(&self, map_type: &MapType) -> Result<BinaryVector> {
+        let mut vector = Vec::with_capacity(self.array.len());
+        for binary in self.array.iter() {
+            let Some(binary) = binary else {
+                vector.push(None);
+                continue;
+            };
+
+            if decode_map_type_value(binary).is_ok_and(|(t, _)| t == *map_type) {
+                vector.push(Some(binary.to_vec()));
+                continue;
+            }
+
+            let s = std::str::from_utf8(binary).map_err(|_| {
+                InvalidMapSnafu {
+                    msg: format!("Failed to convert binary to {}", map_type.name()),
+                }
+                .build()
+            })?;
+            vector.push(Some(parse_string_to_map_type_value(s, map_type)?));
+        }
+        Ok(BinaryVector::from(vector))
+    }
+
+    /// Creates a new binary vector of ip addresses from a binary vector.
+    /// Each value must be either 16 bytes or an IPv4/IPv6 address string.
+    pub fn convert_binary_to_ip(&self) -> Result<BinaryVector> {
+        let mut vector = Vec::with_capacity(self.array.len());
+        for binary in self.array.iter() {
+            let Some(binary) = binary else {
+                vector.push(None);
+                continue;
+            };
+
+            if binary.len() == IP_BYTE_SIZE {
+                vector.push(Some(binary.to_vec()));
+                continue;
+            }
+
+            let s = std::str::from_utf8(binary).map_err(|_| {
+                InvalidIpSnafu {
+                    msg: format!(
+                        "Unexpected bytes size for ip value, expected {}, got {}",
+                        IP_BYTE_SIZE,
+                        binary.len()
+                    ),
+                }
+                .build()
+            })?;
+            vector.push(Some(parse_string_to_ip_type_value(s)?));
+        }
+        Ok(BinaryVector::from(vector))
+    }
 }
 
 impl From<BinaryArray> for BinaryVector {
@@ -554,4 +646,84 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_binary_vector_conversion_to_uuid() {
+        let vector = BinaryVector::from(vec![
+            Some(b"67e55044-10b1-426f-9247-bb680e5fe0c8".to_vec()),
+            Some(vec![1u8; UUID_BYTE_SIZE]),
+            None,
+        ]);
+        let converted = vector.convert_binary_to_uuid().unwrap();
+        assert_eq!(3, converted.len());
+        assert_eq!(
+            parse_string_to_uuid_type_value("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap(),
+            converted.get_ref(0).as_binary().unwrap().unwrap()
+        );
+        assert_eq!(
+            &[1u8; UUID_BYTE_SIZE],
+            converted.get_ref(1).as_binary().unwrap().unwrap()
+        );
+        assert!(converted.is_null(2));
+
+        let error = BinaryVector::from(vec![Some(b"not a uuid".to_vec())])
+            .convert_binary_to_uuid()
+            .unwrap_err();
+        assert_matches!(error, error::Error::InvalidUuid { .. });
+    }
+
+    #[test]
+    fn test_binary_vector_conversion_to_map() {
+        let map_type = MapType::default();
+        let encoded = parse_string_to_map_type_value(r#"{"a": "b"}"#, &map_type).unwrap();
+        let vector = BinaryVector::from(vec![
+            Some(br#"{"a": "b"}"#.to_vec()),
+            Some(encoded.clone()),
+            None,
+        ]);
+        let converted = vector.convert_binary_to_map(&map_type).unwrap();
+        assert_eq!(3, converted.len());
+        for i in 0..2 {
+            assert_eq!(
+                encoded.as_slice(),
+                converted.get_ref(i).as_binary().unwrap().unwrap()
+            );
+        }
+        assert!(converted.is_null(2));
+
+        let error = BinaryVector::from(vec![Some(b"[1, 2]".to_vec())])
+            .convert_binary_to_map(&map_type)
+            .unwrap_err();
+        assert_matches!(error, error::Error::InvalidMap { .. });
+    }
+
+    #[test]
+    fn test_binary_vector_conversion_to_ip() {
+        let vector = BinaryVector::from(vec![
+            Some(b"10.0.0.1".to_vec()),
+            Some(b"2001:db8::1".to_vec()),
+            Some(vec![1u8; IP_BYTE_SIZE]),
+            None,
+        ]);
+        let converted = vector.convert_binary_to_ip().unwrap();
+        assert_eq!(4, converted.len());
+        assert_eq!(
+            parse_string_to_ip_type_value("10.0.0.1").unwrap(),
+            converted.get_ref(0).as_binary().unwrap().unwrap()
+        );
+        assert_eq!(
+            parse_string_to_ip_type_value("2001:db8::1").unwrap(),
+            converted.get_ref(1).as_binary().unwrap().unwrap()
+        );
+        assert_eq!(
+            &[1u8; IP_BYTE_SIZE],
+            converted.get_ref(2).as_binary().unwrap().unwrap()
+        );
+        assert!(converted.is_null(3));
+
+        let error = BinaryVector::from(vec![Some(b"10.0.0.256".to_vec())])
+            .convert_binary_to_ip()
+            .unwrap_err();
+        assert_matches!(error, error::Error::InvalidIp { .. });
+    }
 }
@@ -21,7 +21,7 @@ use arrow::array::{Array, ArrayRef, StringArray};
 use arrow::compute;
 use arrow::compute::kernels::comparison;
 use arrow::datatypes::{DataType as ArrowDataType, Int64Type, TimeUnit};
-use arrow_array::{DictionaryArray, StructArray};
+use arrow_array::{DictionaryArray, MapArray, StructArray};
 use arrow_schema::IntervalUnit;
 use datafusion_common::ScalarValue;
 use snafu::{OptionExt, ResultExt};
@@ -30,6 +30,7 @@ use crate::data_type::ConcreteDataType;
 use crate::error::{self, ConvertArrowArrayToScalarsSnafu, Result};
 use crate::prelude::DataType;
 use crate::scalars::{Scalar, ScalarVectorBuilder};
+use crate::types::MapType;
 use crate::value::{ListValue, ListValueRef, Value};
 use crate::vectors::struct_vector::StructVector;
 use crate::vectors::{
@@ -371,13 +372,19 @@ impl Helper {
                     .unwrap();
                 Arc::new(StructVector::new(array.clone())?)
             }
+            ArrowDataType::Map(_, _) => {
+                let data_type = ConcreteDataType::try_from(array.as_ref().data_type())?;
+                // Safety: converting from arrow map type always returns a map type.
+                let map_type = data_type.as_map().unwrap();
+                let array = array.as_ref().as_any().downcast_ref::<MapArray>().unwrap();
+                Arc::new(map_array_to_binary_vector(array, map_type)?)
+            }
             ArrowDataType::Float16
             | ArrowDataType::LargeList(_)
             | ArrowDataType::FixedSizeList(_, _)
             | ArrowDataType::Union(_, _)
             | ArrowDataType::Dictionary(_, _)
             | ArrowDataType::Decimal256(_, _)
-            | ArrowDataType::Map(_, _)
             | ArrowDataType::RunEndEncoded(_, _)
             | ArrowDataType::ListView(_)
             | ArrowDataType::LargeListView(_)
@@ -429,6 +436,24 @@ impl Helper {
     }
 }
 
+/// Encodes each map in the arrow map array into a map type value.
+fn map_array_to_binary_vector(array: &MapArray, map_type: &MapType) -> Result<BinaryVector> {
+    let keys = Helper::try_into_vector(array.keys())?;
+    let values = Helper::try_into_vector(array.values())?;
+    let offsets = array.value_offsets();
+    let mut maps = Vec::with_capacity(array.len());
+    for i in 0..array.len() {
+        if array.is_null(i) {
+            maps.push(None);
+            continue;
+        }
+        let entries =
+            (offsets[i] as usize..offsets[i + 1] as usize).map(|j| (keys.get(j), values.get(j)));
+        maps.push(Some(map_type.encode_entries(entries)?));
+    }
+    Ok(BinaryVector::from(maps))
+}
+
 #[cfg(test)]
 mod tests {
     use arrow::array::{
@@ -440,6 +465,7 @@ mod tests {
     };
     use arrow::buffer::Buffer;
     use arrow::datatypes::{Int32Type, IntervalMonthDayNano};
+    use arrow_array::builder::{Int64Builder, MapBuilder, StringBuilder};
     use arrow_array::{BinaryArray, DictionaryArray, FixedSizeBinaryArray, LargeStringArray};
     use arrow_schema::DataType;
     use common_decimal::Decimal128;
@@ -448,6 +474,7 @@ mod tests {
     use common_time::{Date, Duration};
 
     use super::*;
+    use crate::types::decode_map_type_value;
     use crate::value::Value;
     use crate::vectors::ConcreteDataType;
 
@@ -662,6 +689,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_map_array_into_vector() {
+        let mut builder = MapBuilder::new(None, StringBuilder::new(), Int64Builder::new());
+        builder.keys().append_value("a");
+        builder.values().append_value(1);
+        builder.keys().append_value("b");
+        builder.values().append_null();
+        builder.append(true).unwrap();
+        builder.append(false).unwrap();
+        builder.append(true).unwrap();
+        let array: ArrayRef = Arc::new(builder.finish());
+
+        let map_type = ConcreteDataType::map_datatype(
+            ConcreteDataType::string_datatype(),
+            ConcreteDataType::int64_datatype(),
+        );
+        assert_eq!(
+            map_type,
+            ConcreteDataType::try_from(array.data_type()).unwrap()
+        );
+        let vector = Helper::try_into_vector(array).unwrap();
+        assert_eq!(3, vector.len());
+        assert!(vector.is_null(1));
+
+        let (t, entries) =
+            decode_map_type_value(vector.get_ref(0).as_binary().unwrap().unwrap()).unwrap();
+        assert_eq!(map_type.as_map().unwrap(), &t);
+        assert_eq!(
+            vec![
+                (Value::from("a"), Value::Int64(1)),
+                (Value::from("b"), Value::Null)
+            ],
+            entries
+        );
+        let (_, entries) =
+            decode_map_type_value(vector.get_ref(2).as_binary().unwrap().unwrap()).unwrap();
+        assert!(entries.is_empty());
+    }
+
     #[test]
     fn test_large_string_array_into_vector() {
         let input_vec = vec!["a", "b"];
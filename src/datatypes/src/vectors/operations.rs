@@ -76,6 +76,18 @@ macro_rules! impl_scalar_vector_op {
                             let vector = vector.convert_binary_to_vector(d.dim)?;
                             return Ok(Arc::new(vector) as VectorRef);
                         }
+                        ConcreteDataType::Uuid(_) => {
+                            let vector = vector.convert_binary_to_uuid()?;
+                            return Ok(Arc::new(vector) as VectorRef);
+                        }
+                        ConcreteDataType::Map(m) => {
+                            let vector = vector.convert_binary_to_map(m)?;
+                            return Ok(Arc::new(vector) as VectorRef);
+                        }
+                        ConcreteDataType::Ip(_) => {
+                            let vector = vector.convert_binary_to_ip()?;
+                            return Ok(Arc::new(vector) as VectorRef);
+                        }
                         _ => {}
                     }
                 }
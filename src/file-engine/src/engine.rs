@@ -26,9 +26,11 @@ use object_store::ObjectStore;
 use snafu::{ensure, OptionExt};
 use store_api::metadata::RegionMetadataRef;
 use store_api::region_engine::{
+    BackupRegionRequest, BackupRegionResponse, CopyRegionFromRequest, CopyRegionFromResponse,
     RegionEngine, RegionManifestInfo, RegionRole, RegionScannerRef, RegionStatistic,
-    SetRegionRoleStateResponse, SetRegionRoleStateSuccess, SettableRegionRoleState,
-    SinglePartitionScanner, SyncManifestResponse,
+    RestoreRegionRequest, RestoreRegionResponse, SetRegionRoleStateResponse,
+    SetRegionRoleStateSuccess, SettableRegionRoleState, SinglePartitionScanner,
+    SyncManifestResponse,
 };
 use store_api::region_request::{
     AffectedRows, RegionCloseRequest, RegionCreateRequest, RegionDropRequest, RegionOpenRequest,
@@ -150,6 +152,28 @@ impl RegionEngine for FileRegionEngine {
         Ok(SyncManifestResponse::NotSupported)
     }
 
+    async fn backup_region(
+        &self,
+        _request: BackupRegionRequest,
+    ) -> Result<BackupRegionResponse, BoxedError> {
+        // The data of file engine is in external files.
+        Ok(BackupRegionResponse::NotSupported)
+    }
+
+    async fn restore_region(
+        &self,
+        _request: RestoreRegionRequest,
+    ) -> Result<RestoreRegionResponse, BoxedError> {
+        Ok(RestoreRegionResponse::NotSupported)
+    }
+
+    async fn copy_region_from(
+        &self,
+        _request: CopyRegionFromRequest,
+    ) -> Result<CopyRegionFromResponse, BoxedError> {
+        Ok(CopyRegionFromResponse::NotSupported)
+    }
+
     fn role(&self, region_id: RegionId) -> Option<RegionRole> {
         self.inner.state(region_id)
     }
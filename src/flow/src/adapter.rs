@@ -27,7 +27,7 @@ use common_meta::key::TableMetadataManagerRef;
 use common_options::memory::MemoryOptions;
 use common_runtime::JoinHandle;
 use common_telemetry::logging::{LoggingOptions, TracingOptions};
-use common_telemetry::{debug, info, trace};
+use common_telemetry::{debug, info, trace, warn};
 use datatypes::schema::ColumnSchema;
 use datatypes::value::Value;
 use greptime_proto::v1;
@@ -46,6 +46,8 @@ use table::metadata::TableId;
 use tokio::sync::broadcast::error::TryRecvError;
 use tokio::sync::{broadcast, watch, Mutex, RwLock};
 
+pub use crate::adapter::checkpoint::FlowCheckpointOptions;
+use crate::adapter::checkpoint::FlowCheckpointer;
 pub(crate) use crate::adapter::node_context::FlownodeContext;
 use crate::adapter::refill::RefillTask;
 use crate::adapter::table_source::ManagedTableSource;
@@ -60,6 +62,7 @@ use crate::metrics::{METRIC_FLOW_INSERT_ELAPSED, METRIC_FLOW_ROWS, METRIC_FLOW_R
 use crate::repr::{self, DiffRow, RelationDesc, Row, BATCH_SIZE};
 use crate::{CreateFlowArgs, FlowId, TableName};
 
+pub(crate) mod checkpoint;
 pub(crate) mod flownode_impl;
 mod parse_expr;
 pub(crate) mod refill;
@@ -87,6 +90,7 @@ pub const AUTO_CREATED_UPDATE_AT_TS_COL: &str = "update_at";
 pub struct FlowConfig {
     pub num_workers: usize,
     pub batching_mode: BatchingModeOptions,
+    pub checkpoint: FlowCheckpointOptions,
 }
 
 impl Default for FlowConfig {
@@ -94,6 +98,7 @@ impl Default for FlowConfig {
         Self {
             num_workers: (common_config::utils::get_cpus() / 2).max(1),
             batching_mode: BatchingModeOptions::default(),
+            checkpoint: FlowCheckpointOptions::default(),
         }
     }
 }
@@ -130,7 +135,7 @@ impl Default for FlownodeOptions {
             // that it won't use too much cpu or memory
             query: QueryOptions {
                 parallelism: 1,
-                allow_query_fallback: false,
+                ..Default::default()
             },
             user_provider: None,
             memory: MemoryOptions::default(),
@@ -169,6 +174,10 @@ pub struct StreamingEngine {
     node_context: RwLock<FlownodeContext>,
     /// Contains all refill tasks
     refill_tasks: RwLock<BTreeMap<FlowId, RefillTask>>,
+    /// Refill tasks waiting to be started, i.e. replaying source data written after a checkpoint
+    pending_refill_tasks: RwLock<Vec<RefillTask>>,
+    /// Checkpoint state of flows, `None` if checkpoint is disabled
+    checkpointer: Option<Arc<FlowCheckpointer>>,
     flow_err_collectors: RwLock<BTreeMap<FlowId, ErrCollector>>,
     src_send_buf_lens: RwLock<BTreeMap<TableId, watch::Receiver<usize>>>,
     tick_manager: FlowTickManager,
@@ -210,6 +219,8 @@ impl StreamingEngine {
             frontend_invoker: RwLock::new(None),
             node_context: RwLock::new(node_context),
             refill_tasks: Default::default(),
+            pending_refill_tasks: Default::default(),
+            checkpointer: None,
             flow_err_collectors: Default::default(),
             src_send_buf_lens: Default::default(),
             tick_manager,
@@ -224,6 +235,12 @@ impl StreamingEngine {
         self
     }
 
+    /// Periodically checkpoint state of flows with given checkpointer
+    pub(crate) fn with_checkpointer(mut self, checkpointer: FlowCheckpointer) -> Self {
+        self.checkpointer = Some(Arc::new(checkpointer));
+        self
+    }
+
     /// Create a flownode manager with one worker
     pub fn new_with_workers<'s>(
         node_id: Option<u32>,
@@ -268,15 +285,33 @@ impl DiffRequest {
     }
 }
 
+/// Convert batches into requests, consecutive rows with the same kind of diff are in the same
+/// request, so the order of deletes and inserts is kept.
 pub fn batches_to_rows_req(batches: Vec<Batch>) -> Result<Vec<DiffRequest>, Error> {
     let mut reqs = Vec::new();
     for batch in batches {
-        let mut rows = Vec::with_capacity(batch.row_count());
+        let mut inserts = Vec::with_capacity(batch.row_count());
+        let mut deletes = Vec::new();
         for i in 0..batch.row_count() {
-            let row = batch.get_row(i).context(EvalSnafu)?;
-            rows.push((Row::new(row), 0));
+            let row = Row::new(batch.get_row(i).context(EvalSnafu)?);
+            if batch.get_diff(i).context(EvalSnafu)? < 0 {
+                if !inserts.is_empty() {
+                    reqs.push(DiffRequest::Insert(std::mem::take(&mut inserts)));
+                }
+                deletes.push((row, 0));
+            } else {
+                if !deletes.is_empty() {
+                    reqs.push(DiffRequest::Delete(std::mem::take(&mut deletes)));
+                }
+                inserts.push((row, 0));
+            }
+        }
+        if !deletes.is_empty() {
+            reqs.push(DiffRequest::Delete(deletes));
+        }
+        if !inserts.is_empty() {
+            reqs.push(DiffRequest::Insert(inserts));
         }
-        reqs.push(DiffRequest::Insert(rows));
     }
     Ok(reqs)
 }
@@ -335,52 +370,49 @@ impl StreamingEngine {
                 .inc_by(total_rows as u64);
 
             let now = self.tick_manager.tick();
+            // fill the columns that are not in the output rows, and check the row length
+            let to_proto_row = |mut row: Row| -> Result<v1::Row, Error> {
+                // extend `update_at` col if needed
+                // if schema include a millisecond timestamp here, and result row doesn't have it, add it
+                if row.len() < proto_schema.len()
+                    && proto_schema[row.len()].datatype
+                        == greptime_proto::v1::ColumnDataType::TimestampMillisecond as i32
+                {
+                    row.extend([Value::from(common_time::Timestamp::new_millisecond(now))]);
+                }
+                // ts col, if auto create
+                if is_ts_placeholder {
+                    ensure!(
+                        row.len() == schema_len - 1,
+                        InternalSnafu {
+                            reason: format!(
+                                "Row len mismatch, expect {} got {}",
+                                schema_len - 1,
+                                row.len()
+                            )
+                        }
+                    );
+                    row.extend([Value::from(common_time::Timestamp::new_millisecond(0))]);
+                }
+                if row.len() != proto_schema.len() {
+                    UnexpectedSnafu {
+                        reason: format!(
+                            "Flow output row length mismatch, expect {} got {}, the columns in schema are: {:?}",
+                            proto_schema.len(),
+                            row.len(),
+                            proto_schema.iter().map(|c|&c.column_name).collect_vec()
+                        ),
+                    }
+                    .fail()?;
+                }
+                Ok(row.into())
+            };
             for req in reqs {
                 match req {
                     DiffRequest::Insert(insert) => {
                         let rows_proto: Vec<v1::Row> = insert
                             .into_iter()
-                            .map(|(mut row, _ts)| {
-                                // extend `update_at` col if needed
-                                // if schema include a millisecond timestamp here, and result row doesn't have it, add it
-                                if row.len() < proto_schema.len()
-                                    && proto_schema[row.len()].datatype
-                                        == greptime_proto::v1::ColumnDataType::TimestampMillisecond
-                                            as i32
-                                {
-                                    row.extend([Value::from(
-                                        common_time::Timestamp::new_millisecond(now),
-                                    )]);
-                                }
-                                // ts col, if auto create
-                                if is_ts_placeholder {
-                                    ensure!(
-                                        row.len() == schema_len - 1,
-                                        InternalSnafu {
-                                            reason: format!(
-                                                "Row len mismatch, expect {} got {}",
-                                                schema_len - 1,
-                                                row.len()
-                                            )
-                                        }
-                                    );
-                                    row.extend([Value::from(
-                                        common_time::Timestamp::new_millisecond(0),
-                                    )]);
-                                }
-                                if row.len() != proto_schema.len() {
-                                    UnexpectedSnafu {
-                                        reason: format!(
-                                            "Flow output row length mismatch, expect {} got {}, the columns in schema are: {:?}",
-                                            proto_schema.len(),
-                                            row.len(),
-                                            proto_schema.iter().map(|c|&c.column_name).collect_vec()
-                                        ),
-                                    }
-                                    .fail()?;
-                                }
-                                Ok(row.into())
-                            })
+                            .map(|(row, _ts)| to_proto_row(row))
                             .collect::<Result<Vec<_>, Error>>()?;
                         let table_name = table_name.last().unwrap().clone();
                         let req = RowInsertRequest {
@@ -404,16 +436,10 @@ impl StreamingEngine {
                             .with_context(|_| ExternalSnafu {})?;
                     }
                     DiffRequest::Delete(remove) => {
-                        info!("original remove rows={:?}", remove);
                         let rows_proto: Vec<v1::Row> = remove
                             .into_iter()
-                            .map(|(mut row, _ts)| {
-                                row.extend(Some(Value::from(
-                                    common_time::Timestamp::new_millisecond(0),
-                                )));
-                                row.into()
-                            })
-                            .collect::<Vec<_>>();
+                            .map(|(row, _ts)| to_proto_row(row))
+                            .collect::<Result<Vec<_>, Error>>()?;
                         let table_name = table_name.last().unwrap().clone();
                         let req = RowDeleteRequest {
                             table_name,
@@ -583,10 +609,64 @@ impl StreamingEngine {
         info!("Starting flownode manager's background task");
         common_runtime::spawn_global(async move {
             let _state_report_handler = self.clone().start_state_report_handler().await;
+            let checkpoint_task = self.clone().start_checkpoint_task();
             self.run(shutdown).await;
+            if let Some(task) = checkpoint_task {
+                task.abort();
+            }
         })
     }
 
+    /// Start a background task to checkpoint state of flows periodically
+    /// and start refill tasks for flows restored from checkpoint
+    fn start_checkpoint_task(self: Arc<Self>) -> Option<JoinHandle<()>> {
+        let checkpointer = self.checkpointer.clone()?;
+        let handle = common_runtime::spawn_global(async move {
+            let mut last_checkpoint = tokio::time::Instant::now();
+            let mut tick_interval = tokio::time::interval(Duration::from_secs(1));
+            loop {
+                tick_interval.tick().await;
+                if let Err(err) = self.start_pending_refill_tasks().await {
+                    common_telemetry::error!(err; "Failed to start refill tasks");
+                }
+                if last_checkpoint.elapsed() < checkpointer.interval() {
+                    continue;
+                }
+                last_checkpoint = tokio::time::Instant::now();
+                match self.checkpoint(&checkpointer).await {
+                    Ok(cnt) => debug!("Checkpointed {} flows", cnt),
+                    Err(err) => common_telemetry::error!(err; "Failed to checkpoint flows"),
+                }
+            }
+        });
+        Some(handle)
+    }
+
+    /// Start refill tasks waiting for frontend invoker to be available
+    async fn start_pending_refill_tasks(self: &FlowStreamingEngineRef) -> Result<(), Error> {
+        if self.pending_refill_tasks.read().await.is_empty()
+            || self.frontend_invoker.read().await.is_none()
+        {
+            return Ok(());
+        }
+        let tasks = std::mem::take(&mut *self.pending_refill_tasks.write().await);
+        self.starting_refill_flows(tasks).await
+    }
+
+    /// Take snapshots of all stateful flows and persist them, return number of flows persisted
+    ///
+    /// Snapshots are taken before writing back the output, so the persisted state never
+    /// contains an input whose output may be lost
+    async fn checkpoint(&self, checkpointer: &FlowCheckpointer) -> Result<usize, Error> {
+        self.run_available(true).await?;
+        let mut snapshots = BTreeMap::new();
+        for handle in self.worker_handles.iter() {
+            snapshots.extend(handle.snapshot().await?);
+        }
+        self.send_writeback_requests().await?;
+        checkpointer.save(snapshots).await
+    }
+
     /// log all flow errors
     pub async fn log_all_errors(&self) {
         for (f_id, f_err) in self.flow_err_collectors.read().await.iter() {
@@ -757,9 +837,24 @@ impl StreamingEngine {
             }
         }
         self.node_context.write().await.remove_flow(flow_id);
+        if let Some(checkpointer) = &self.checkpointer {
+            checkpointer.unregister_flow(flow_id).await;
+        }
         Ok(())
     }
 
+    /// Remove persisted checkpoint of a dropped flow
+    ///
+    /// Not done in [`Self::remove_flow_inner`] since a flow is also removed from
+    /// this node when migrating to another node, which should restore from the checkpoint
+    pub async fn remove_flow_checkpoint(&self, flow_id: FlowId) {
+        if let Some(checkpointer) = &self.checkpointer {
+            if let Err(err) = checkpointer.remove(flow_id).await {
+                warn!(err; "Failed to remove checkpoint of flow {}", flow_id);
+            }
+        }
+    }
+
     /// Return task id if a new task is created, otherwise return None
     ///
     /// steps to create task:
@@ -881,6 +976,57 @@ impl StreamingEngine {
                     .map(|s| s.get_receiver())
             })
             .collect::<Result<Vec<_>, _>>()?;
+
+        let mut src_time_indexes = vec![None; source_ids.len()];
+        let mut allowed_lateness = 0;
+        let mut snapshot = None;
+        let mut refill_tasks = vec![];
+        if let Some(checkpointer) = &self.checkpointer {
+            let sources = source_table_ids
+                .iter()
+                .copied()
+                .zip(source_ids.iter().copied())
+                .collect_vec();
+            allowed_lateness = checkpointer.allowed_lateness().as_millis() as repr::Duration;
+            let mut time_index_names = Vec::with_capacity(sources.len());
+            for (i, table_id) in source_table_ids.iter().enumerate() {
+                let (idx, col) = self
+                    .table_info_source
+                    .get_time_index_column_from_table_id(*table_id)
+                    .await?;
+                src_time_indexes[i] = Some(idx);
+                time_index_names.push(col.name);
+            }
+
+            let checkpoint = checkpointer.load(flow_id, &sql).await?;
+            if let Some(checkpoint) = checkpoint {
+                // replay source data written after the checkpoint, including rows arrived late
+                // within the allowed lateness, and skip rows already consumed before the checkpoint
+                let now = common_time::Timestamp::new_millisecond(self.tick_manager.tick());
+                for (table_id, time_index_name) in source_table_ids.iter().zip(&time_index_names) {
+                    // no data consumed from this source before checkpoint
+                    let Some(progress) = checkpoint.source_progress.get(table_id) else {
+                        continue;
+                    };
+                    let start = common_time::Timestamp::new_millisecond(progress.replay_from);
+                    let task = RefillTask::create(
+                        flow_id,
+                        *table_id,
+                        Some((start, now)),
+                        time_index_name,
+                        &self.table_info_source,
+                    )
+                    .await?
+                    .with_consumed_rows(progress.consumed.values().flatten().cloned());
+                    refill_tasks.push(task);
+                }
+                snapshot = Some(checkpoint.into_snapshot(&sources));
+            }
+            checkpointer
+                .register_flow(flow_id, sql.clone(), sources)
+                .await;
+        }
+
         let err_collector = ErrCollector::default();
         self.flow_err_collectors
             .write()
@@ -895,13 +1041,32 @@ impl StreamingEngine {
             sink_sender,
             source_ids,
             src_recvs: source_receivers,
+            src_time_indexes,
+            allowed_lateness,
             expire_after,
             or_replace,
             create_if_not_exists,
             err_collector,
+            snapshot,
         };
 
-        handle.create_flow(create_request).await?;
+        let created = match handle.create_flow(create_request).await {
+            Ok(created) => created,
+            Err(err) => {
+                if let Some(checkpointer) = &self.checkpointer {
+                    checkpointer.unregister_flow(flow_id).await;
+                }
+                return Err(err);
+            }
+        };
+        if created.is_some() && !refill_tasks.is_empty() {
+            info!(
+                "Flow with id={} is restored from checkpoint, replaying {} sources",
+                flow_id,
+                refill_tasks.len()
+            );
+            self.pending_refill_tasks.write().await.extend(refill_tasks);
+        }
         info!("Successfully create flow with id={}", flow_id);
         Ok(Some(flow_id))
     }
@@ -0,0 +1,369 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Checkpoint state of streaming flows into object store, so a flow can be restored
+//! after flownode restart or migration and only need to replay source data written after
+//! the checkpoint instead of recomputing from scratch.
+//!
+//! Source data is replayed from `watermark - allowed_lateness` of each source, so rows arrived
+//! late within the allowed lateness are not lost. Rows already consumed in that range are kept
+//! in the checkpoint and skipped when replaying. Rows arrived later than that are not replayed.
+//!
+//! A checkpoint that can't be read or doesn't match the rendered flow fails the flow start,
+//! since starting from empty state would silently lose everything consumed before the
+//! checkpoint. Dropping the flow removes its checkpoint, so it can be recreated from scratch.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use common_config::DEFAULT_DATA_HOME;
+use common_telemetry::{debug, info};
+use object_store::config::ObjectStoreConfig;
+use object_store::factory::new_raw_object_store;
+use object_store::{ErrorKind, ObjectStore};
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use table::metadata::TableId;
+use tokio::sync::RwLock;
+
+use crate::compute::{ArrangeId, DataflowSnapshot, SourceProgress};
+use crate::error::{CheckpointSerdeSnafu, CheckpointStoreSnafu, Error, InitCheckpointStoreSnafu};
+use crate::expr::GlobalId;
+use crate::repr::Timestamp;
+use crate::utils::ArrangementSnapshot;
+use crate::FlowId;
+
+/// Directory in object store to put flow checkpoints in
+const CHECKPOINT_DIR: &str = "flow_checkpoint/";
+
+/// Options for checkpointing state of streaming flows
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FlowCheckpointOptions {
+    /// Whether to checkpoint state of streaming flows, disabled by default
+    pub enable: bool,
+    /// Interval between two checkpoints
+    #[serde(with = "humantime_serde")]
+    pub interval: Duration,
+    /// How late a source row can arrive, compared to the max time index value consumed
+    /// from that source, and still be replayed after restoring from a checkpoint
+    #[serde(with = "humantime_serde")]
+    pub allowed_lateness: Duration,
+    /// Working directory when using local file storage
+    pub data_home: String,
+    #[serde(flatten)]
+    pub store: ObjectStoreConfig,
+}
+
+impl Default for FlowCheckpointOptions {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            interval: Duration::from_secs(5 * 60),
+            allowed_lateness: Duration::from_secs(60),
+            data_home: DEFAULT_DATA_HOME.to_string(),
+            store: ObjectStoreConfig::default(),
+        }
+    }
+}
+
+/// Persisted state of a flow
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct FlowCheckpoint {
+    /// The sql the flow is created with, checkpoint is discarded if the flow is
+    /// recreated with a different sql
+    pub sql: String,
+    pub as_of: Timestamp,
+    pub arrangements: BTreeMap<ArrangeId, ArrangementSnapshot>,
+    /// Progress of each source, keyed by table id since global id
+    /// is only assigned in memory and not stable across restarts
+    pub source_progress: BTreeMap<TableId, SourceProgress>,
+}
+
+impl FlowCheckpoint {
+    fn from_snapshot(
+        sql: String,
+        snapshot: DataflowSnapshot,
+        sources: &[(TableId, GlobalId)],
+    ) -> Self {
+        let mut snapshot_progress = snapshot.source_progress;
+        let source_progress = sources
+            .iter()
+            .filter_map(|(table_id, global_id)| {
+                snapshot_progress
+                    .remove(global_id)
+                    .map(|progress| (*table_id, progress))
+            })
+            .collect();
+        Self {
+            sql,
+            as_of: snapshot.as_of,
+            arrangements: snapshot.arrangements,
+            source_progress,
+        }
+    }
+
+    /// Convert into snapshot with source progress keyed by global ids in `sources`
+    pub fn into_snapshot(self, sources: &[(TableId, GlobalId)]) -> DataflowSnapshot {
+        let source_progress = sources
+            .iter()
+            .filter_map(|(table_id, global_id)| {
+                self.source_progress
+                    .get(table_id)
+                    .map(|progress| (*global_id, progress.clone()))
+            })
+            .collect();
+        DataflowSnapshot {
+            as_of: self.as_of,
+            arrangements: self.arrangements,
+            source_progress,
+        }
+    }
+}
+
+/// What is needed to persist a snapshot of a running flow
+#[derive(Debug, Clone)]
+struct CheckpointMeta {
+    sql: String,
+    sources: Vec<(TableId, GlobalId)>,
+}
+
+/// Read and write checkpoints of streaming flows
+pub(crate) struct FlowCheckpointer {
+    object_store: ObjectStore,
+    interval: Duration,
+    allowed_lateness: Duration,
+    flows: RwLock<BTreeMap<FlowId, CheckpointMeta>>,
+}
+
+impl FlowCheckpointer {
+    /// Create a checkpointer from options, return None if checkpoint is disabled
+    pub async fn try_new(opts: &FlowCheckpointOptions) -> Result<Option<Self>, Error> {
+        if !opts.enable {
+            return Ok(None);
+        }
+        let object_store = new_raw_object_store(&opts.store, &opts.data_home)
+            .await
+            .context(InitCheckpointStoreSnafu)?;
+        info!(
+            "Flow checkpoint enabled with interval={:?}, allowed_lateness={:?}, store={}",
+            opts.interval,
+            opts.allowed_lateness,
+            opts.store.provider_name()
+        );
+        Ok(Some(Self::new(
+            object_store,
+            opts.interval,
+            opts.allowed_lateness,
+        )))
+    }
+
+    pub fn new(object_store: ObjectStore, interval: Duration, allowed_lateness: Duration) -> Self {
+        Self {
+            object_store,
+            interval,
+            allowed_lateness,
+            flows: Default::default(),
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    pub fn allowed_lateness(&self) -> Duration {
+        self.allowed_lateness
+    }
+
+    fn path(flow_id: FlowId) -> String {
+        format!("{CHECKPOINT_DIR}{flow_id}.json")
+    }
+
+    /// Register a running flow so its snapshots can be persisted
+    pub async fn register_flow(
+        &self,
+        flow_id: FlowId,
+        sql: String,
+        sources: Vec<(TableId, GlobalId)>,
+    ) {
+        self.flows
+            .write()
+            .await
+            .insert(flow_id, CheckpointMeta { sql, sources });
+    }
+
+    /// Stop checkpointing a flow, the persisted checkpoint is kept
+    pub async fn unregister_flow(&self, flow_id: FlowId) {
+        self.flows.write().await.remove(&flow_id);
+    }
+
+    /// Load the checkpoint of a flow if it exists and is created with the same sql
+    pub async fn load(&self, flow_id: FlowId, sql: &str) -> Result<Option<FlowCheckpoint>, Error> {
+        let path = Self::path(flow_id);
+        let bytes = match self.object_store.read(&path).await {
+            Ok(bytes) => bytes.to_vec(),
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err).context(CheckpointStoreSnafu { path }),
+        };
+        let checkpoint: FlowCheckpoint =
+            serde_json::from_slice(&bytes).context(CheckpointSerdeSnafu { flow_id })?;
+        if checkpoint.sql != sql {
+            info!(
+                "Discard checkpoint of flow {} since it's created with a different sql",
+                flow_id
+            );
+            return Ok(None);
+        }
+        Ok(Some(checkpoint))
+    }
+
+    /// Persist snapshots of running flows, snapshot of unregistered flow is ignored
+    pub async fn save(
+        &self,
+        snapshots: BTreeMap<FlowId, DataflowSnapshot>,
+    ) -> Result<usize, Error> {
+        let flows = self.flows.read().await;
+        let mut cnt = 0;
+        for (flow_id, snapshot) in snapshots {
+            let Some(meta) = flows.get(&flow_id) else {
+                continue;
+            };
+            let checkpoint =
+                FlowCheckpoint::from_snapshot(meta.sql.clone(), snapshot, &meta.sources);
+            let bytes =
+                serde_json::to_vec(&checkpoint).context(CheckpointSerdeSnafu { flow_id })?;
+            let path = Self::path(flow_id);
+            self.object_store
+                .write(&path, bytes)
+                .await
+                .context(CheckpointStoreSnafu { path })?;
+            debug!("Saved checkpoint of flow {}", flow_id);
+            cnt += 1;
+        }
+        Ok(cnt)
+    }
+
+    /// Remove the checkpoint of a flow, and stop checkpointing it
+    pub async fn remove(&self, flow_id: FlowId) -> Result<(), Error> {
+        self.flows.write().await.remove(&flow_id);
+        let path = Self::path(flow_id);
+        self.object_store
+            .delete(&path)
+            .await
+            .context(CheckpointStoreSnafu { path })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use object_store::services::Memory;
+
+    use super::*;
+    use crate::repr::Row;
+    use crate::utils::Arrangement;
+
+    fn new_checkpointer() -> FlowCheckpointer {
+        let object_store = ObjectStore::new(Memory::default()).unwrap().finish();
+        FlowCheckpointer::new(object_store, Duration::from_secs(1), Duration::from_secs(1))
+    }
+
+    #[tokio::test]
+    async fn test_save_load_checkpoint() {
+        let checkpointer = new_checkpointer();
+        let mut arr = Arrangement::default();
+        arr.apply_updates(0, vec![((Row::new(vec![1i64.into()]), Row::empty()), 1, 1)])
+            .unwrap();
+        let mut progress = SourceProgress::default();
+        progress.consume(
+            vec![
+                (42, Row::new(vec![42i64.into()])),
+                (10, Row::new(vec![10i64.into()])),
+            ],
+            20,
+        );
+        let snapshot = DataflowSnapshot {
+            as_of: 1,
+            arrangements: BTreeMap::from([("reduce/0".to_string(), arr.snapshot())]),
+            source_progress: BTreeMap::from([(GlobalId::User(0), progress.clone())]),
+        };
+
+        // unregistered flow is not saved
+        let saved = checkpointer
+            .save(BTreeMap::from([(1, snapshot.clone())]))
+            .await
+            .unwrap();
+        assert_eq!(saved, 0);
+        assert!(checkpointer.load(1, "sql").await.unwrap().is_none());
+
+        checkpointer
+            .register_flow(1, "sql".to_string(), vec![(1024, GlobalId::User(0))])
+            .await;
+        let saved = checkpointer
+            .save(BTreeMap::from([(1, snapshot.clone())]))
+            .await
+            .unwrap();
+        assert_eq!(saved, 1);
+
+        assert!(checkpointer.load(1, "other sql").await.unwrap().is_none());
+        let checkpoint = checkpointer.load(1, "sql").await.unwrap().unwrap();
+        assert_eq!(
+            checkpoint.source_progress,
+            BTreeMap::from([(1024, progress.clone())])
+        );
+
+        // global id is reassigned after restart
+        let restored = checkpoint.into_snapshot(&[(1024, GlobalId::User(3))]);
+        assert_eq!(
+            restored.source_progress,
+            BTreeMap::from([(GlobalId::User(3), progress)])
+        );
+        assert_eq!(restored.arrangements, snapshot.arrangements);
+
+        checkpointer.remove(1).await.unwrap();
+        assert!(checkpointer.load(1, "sql").await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_source_progress() {
+        let row = |v: i64| Row::new(vec![v.into()]);
+        let mut progress = SourceProgress::default();
+        progress.consume(vec![(100, row(100)), (50, row(50)), (95, row(95))], 10);
+        assert_eq!(progress.watermark, 100);
+        assert_eq!(progress.replay_from, 90);
+        assert_eq!(
+            progress.consumed,
+            BTreeMap::from([(95, vec![row(95)]), (100, vec![row(100)])])
+        );
+
+        // a late row within the allowed lateness is remembered, older ones are not
+        progress.consume(vec![(92, row(92)), (80, row(80))], 10);
+        assert_eq!(progress.watermark, 100);
+        assert_eq!(
+            progress.consumed.keys().copied().collect::<Vec<_>>(),
+            [92, 95, 100]
+        );
+
+        // advancing the watermark forgets rows that will not be replayed
+        progress.consume(vec![(105, row(105)), (105, row(106))], 10);
+        assert_eq!(progress.replay_from, 95);
+        assert_eq!(
+            progress.consumed,
+            BTreeMap::from([
+                (95, vec![row(95)]),
+                (100, vec![row(100)]),
+                (105, vec![row(105), row(106)]),
+            ])
+        );
+    }
+}
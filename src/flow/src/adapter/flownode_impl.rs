@@ -818,6 +818,9 @@ impl common_meta::node_manager::Flownode for FlowDualEngine {
                 self.remove_flow(flow_id.id as u64)
                     .await
                     .map_err(to_meta_err(snafu::location!()))?;
+                self.streaming_engine
+                    .remove_flow_checkpoint(flow_id.id as u64)
+                    .await;
                 METRIC_FLOW_TASK_COUNT.dec();
                 Ok(Default::default())
             }
@@ -14,9 +14,12 @@
 
 //! This module contains the refill flow task, which is used to refill flow with given table id and a time range.
 
-use std::collections::BTreeSet;
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
 
+use arrow::array::BooleanArray;
+use arrow::compute::filter_record_batch;
 use catalog::CatalogManagerRef;
 use common_error::ext::BoxedError;
 use common_meta::key::flow::FlowMetadataManagerRef;
@@ -35,7 +38,7 @@ use crate::adapter::{FlowId, FlowStreamingEngineRef, StreamingEngine};
 use crate::error::{FlowNotFoundSnafu, JoinTaskSnafu, UnexpectedSnafu};
 use crate::expr::error::ExternalSnafu;
 use crate::expr::utils::find_plan_time_window_expr_lower_bound;
-use crate::repr::RelationDesc;
+use crate::repr::{RelationDesc, Row};
 use crate::server::get_all_flow_ids;
 use crate::{Error, FrontendInvoker};
 
@@ -167,9 +170,48 @@ struct TaskData {
     flow_id: FlowId,
     table_id: TableId,
     table_schema: RelationDesc,
+    /// rows already consumed by the flow before a checkpoint, with their count,
+    /// they are skipped so they are not counted twice
+    consumed_rows: BTreeMap<Row, usize>,
 }
 
 impl TaskData {
+    /// Remove rows already consumed by the flow from the record batch
+    fn skip_consumed_rows(&mut self, rb: RecordBatch) -> Result<RecordBatch, Error> {
+        if self.consumed_rows.is_empty() {
+            return Ok(rb);
+        }
+        let mut keep = Vec::with_capacity(rb.num_rows());
+        for idx in 0..rb.num_rows() {
+            let row = Row::new(rb.columns().iter().map(|col| col.get(idx)).collect());
+            keep.push(match self.consumed_rows.entry(row) {
+                Entry::Occupied(mut entry) => {
+                    *entry.get_mut() -= 1;
+                    if *entry.get() == 0 {
+                        entry.remove();
+                    }
+                    false
+                }
+                Entry::Vacant(_) => true,
+            });
+        }
+        if keep.iter().all(|k| *k) {
+            return Ok(rb);
+        }
+
+        let filtered = filter_record_batch(rb.df_record_batch(), &BooleanArray::from(keep))
+            .map_err(|err| {
+                UnexpectedSnafu {
+                    reason: format!("Failed to skip consumed rows: {err}"),
+                }
+                .build()
+            })?;
+        let rb = RecordBatch::try_from_df_record_batch(rb.schema.clone(), filtered)
+            .map_err(BoxedError::new)
+            .context(ExternalSnafu)?;
+        Ok(rb)
+    }
+
     /// validate that incoming batch's schema is the same as table schema(by comparing types&names)
     fn validate_schema(table_schema: &RelationDesc, rb: &RecordBatch) -> Result<(), Error> {
         let rb_schema = &rb.schema;
@@ -269,7 +311,7 @@ impl TaskState<()> {
         manager: FlowStreamingEngineRef,
         mut output_stream: SendableRecordBatchStream,
     ) -> Result<(), Error> {
-        let data = (*task_data).clone();
+        let mut data = (*task_data).clone();
         let handle: JoinHandle<Result<(), Error>> = common_runtime::spawn_global(async move {
             while let Some(rb) = output_stream.next().await {
                 let rb = match rb {
@@ -277,6 +319,10 @@ impl TaskState<()> {
                     Err(err) => Err(BoxedError::new(err)).context(ExternalSnafu)?,
                 };
                 TaskData::validate_schema(&data.table_schema, &rb)?;
+                let rb = data.skip_consumed_rows(rb)?;
+                if rb.num_rows() == 0 {
+                    continue;
+                }
 
                 // send rb into flow node
                 manager
@@ -375,11 +421,20 @@ impl RefillTask {
                 flow_id,
                 table_id,
                 table_schema: table_schema.relation_desc,
+                consumed_rows: BTreeMap::new(),
             },
             state: TaskState::new(sql),
         })
     }
 
+    /// Skip the given rows when refilling, since they are already consumed by the flow
+    pub fn with_consumed_rows(mut self, rows: impl IntoIterator<Item = Row>) -> Self {
+        for row in rows {
+            *self.data.consumed_rows.entry(row).or_default() += 1;
+        }
+        self
+    }
+
     /// Start running the task in background, non-blocking
     pub async fn start_running(
         &mut self,
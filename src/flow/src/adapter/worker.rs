@@ -21,12 +21,14 @@ use std::sync::Arc;
 use common_telemetry::info;
 use dfir_rs::scheduled::graph::Dfir;
 use enum_as_inner::EnumAsInner;
-use snafu::ensure;
+use snafu::{ensure, ResultExt};
 use tokio::sync::{broadcast, mpsc, oneshot, Mutex};
 
 use crate::adapter::FlowId;
-use crate::compute::{Context, DataflowState, ErrCollector};
-use crate::error::{Error, FlowAlreadyExistSnafu, InternalSnafu, UnexpectedSnafu};
+use crate::compute::{Context, DataflowSnapshot, DataflowState, ErrCollector};
+use crate::error::{
+    Error, FlowAlreadyExistSnafu, InternalSnafu, RestoreCheckpointSnafu, UnexpectedSnafu,
+};
 use crate::expr::{Batch, GlobalId};
 use crate::plan::TypedPlan;
 use crate::repr::{self, DiffRow};
@@ -217,6 +219,19 @@ impl WorkerHandle {
         })
     }
 
+    /// take snapshots of all stateful flows in this worker
+    pub async fn snapshot(&self) -> Result<BTreeMap<FlowId, DataflowSnapshot>, Error> {
+        let ret = self.itc_client.call_with_resp(Request::Snapshot).await?;
+        ret.into_snapshot().map_err(|ret| {
+            InternalSnafu {
+                reason: format!(
+                    "Flow Node/Worker itc failed, expect Response::Snapshot, found {ret:?}"
+                ),
+            }
+            .build()
+        })
+    }
+
     pub async fn get_last_exec_time_map(&self) -> Result<BTreeMap<FlowId, i64>, Error> {
         let ret = self
             .itc_client
@@ -264,11 +279,14 @@ impl<'s> Worker<'s> {
         sink_sender: mpsc::UnboundedSender<Batch>,
         source_ids: &[GlobalId],
         src_recvs: Vec<broadcast::Receiver<Batch>>,
+        src_time_indexes: &[Option<usize>],
+        allowed_lateness: repr::Duration,
         // TODO(discord9): set expire duration for all arrangement and compare to sys timestamp instead
         expire_after: Option<repr::Duration>,
         or_replace: bool,
         create_if_not_exists: bool,
         err_collector: ErrCollector,
+        snapshot: Option<DataflowSnapshot>,
     ) -> Result<Option<FlowId>, Error> {
         let already_exist = self.task_states.contains_key(&flow_id);
         match (create_if_not_exists, or_replace, already_exist) {
@@ -291,17 +309,28 @@ impl<'s> Worker<'s> {
             ..Default::default()
         };
         cur_task_state.state.set_expire_after(expire_after);
+        cur_task_state.state.set_allowed_lateness(allowed_lateness);
 
         {
             let mut ctx = cur_task_state.new_ctx(sink_id);
-            for (source_id, src_recv) in source_ids.iter().zip(src_recvs) {
-                let bundle = ctx.render_source_batch(src_recv)?;
+            for (i, (source_id, src_recv)) in source_ids.iter().zip(src_recvs).enumerate() {
+                let time_index = src_time_indexes.get(i).copied().flatten();
+                let bundle = ctx.render_source_batch(src_recv, *source_id, time_index)?;
                 ctx.insert_global_batch(*source_id, bundle);
             }
 
             let rendered = ctx.render_plan_batch(plan)?;
             ctx.render_unbounded_sink_batch(rendered, sink_sender);
         }
+        if let Some(snapshot) = snapshot {
+            // source data before the checkpoint is not replayed, so starting from empty state
+            // would lose it
+            cur_task_state
+                .state
+                .restore(snapshot)
+                .context(RestoreCheckpointSnafu { flow_id })?;
+            info!("Restored state of flow with id={} from snapshot", flow_id);
+        }
         self.task_states.insert(flow_id, cur_task_state);
         Ok(Some(flow_id))
     }
@@ -370,10 +399,13 @@ impl<'s> Worker<'s> {
                 sink_sender,
                 source_ids,
                 src_recvs,
+                src_time_indexes,
+                allowed_lateness,
                 expire_after,
                 or_replace,
                 create_if_not_exists,
                 err_collector,
+                snapshot,
             } => {
                 let task_create_result = self.create_flow(
                     flow_id,
@@ -382,10 +414,13 @@ impl<'s> Worker<'s> {
                     sink_sender,
                     &source_ids,
                     src_recvs,
+                    &src_time_indexes,
+                    allowed_lateness,
                     expire_after,
                     or_replace,
                     create_if_not_exists,
                     err_collector,
+                    snapshot,
                 );
                 Some(Response::Create {
                     result: task_create_result,
@@ -424,6 +459,16 @@ impl<'s> Worker<'s> {
                 }
                 Some(Response::QueryLastExecTimeMap { result: ret })
             }
+            Request::Snapshot => {
+                let ret = self
+                    .task_states
+                    .iter()
+                    .filter_map(|(flow_id, task_state)| {
+                        task_state.state.snapshot().map(|s| (*flow_id, s))
+                    })
+                    .collect();
+                Some(Response::Snapshot { result: ret })
+            }
         };
         Ok(ret)
     }
@@ -438,10 +483,16 @@ pub enum Request {
         sink_sender: mpsc::UnboundedSender<Batch>,
         source_ids: Vec<GlobalId>,
         src_recvs: Vec<broadcast::Receiver<Batch>>,
+        /// time index column of each source, used to track how far each source is consumed
+        src_time_indexes: Vec<Option<usize>>,
+        /// how long consumed source rows are remembered for replaying after restore, in milliseconds
+        allowed_lateness: repr::Duration,
         expire_after: Option<repr::Duration>,
         or_replace: bool,
         create_if_not_exists: bool,
         err_collector: ErrCollector,
+        /// state to restore after the flow is rendered
+        snapshot: Option<DataflowSnapshot>,
     },
     Remove {
         flow_id: FlowId,
@@ -457,6 +508,7 @@ pub enum Request {
     Shutdown,
     QueryStateSize,
     QueryLastExecTimeMap,
+    Snapshot,
 }
 
 #[derive(Debug, EnumAsInner)]
@@ -480,6 +532,10 @@ enum Response {
         /// each flow tasks' last execution time
         result: BTreeMap<FlowId, i64>,
     },
+    Snapshot {
+        /// snapshot of each stateful flow
+        result: BTreeMap<FlowId, DataflowSnapshot>,
+    },
 }
 
 fn create_inter_thread_call() -> (InterThreadCallClient, InterThreadCallServer) {
@@ -593,10 +649,13 @@ mod test {
             sink_sender: sink_tx,
             source_ids: src_ids,
             src_recvs: vec![rx],
+            src_time_indexes: vec![None],
+            allowed_lateness: 0,
             expire_after: None,
             or_replace: false,
             create_if_not_exists: true,
             err_collector: ErrCollector::default(),
+            snapshot: None,
         };
         assert_eq!(
             handle.create_flow(create_reqs).await.unwrap(),
@@ -609,4 +668,50 @@ mod test {
         drop(handle);
         worker_thread_handle.join().unwrap();
     }
+
+    #[tokio::test]
+    async fn test_create_flow_with_mismatched_snapshot() {
+        let (tx, rx) = oneshot::channel();
+        let worker_thread_handle = std::thread::spawn(move || {
+            let (handle, mut worker) = create_worker();
+            tx.send(handle).unwrap();
+            worker.run();
+        });
+        let handle = rx.await.unwrap();
+        let (_tx, rx) = broadcast::channel::<Batch>(1024);
+        let (sink_tx, _sink_rx) = mpsc::unbounded_channel::<Batch>();
+        let snapshot = DataflowSnapshot {
+            as_of: 0,
+            arrangements: BTreeMap::from([(
+                "unknown/0".to_string(),
+                crate::utils::Arrangement::default().snapshot(),
+            )]),
+            source_progress: BTreeMap::new(),
+        };
+        let create_reqs = Request::Create {
+            flow_id: 1,
+            plan: TypedPlan {
+                plan: Plan::Get {
+                    id: Id::Global(GlobalId::User(1)),
+                },
+                schema: RelationType::new(vec![]).into_unnamed(),
+            },
+            sink_id: GlobalId::User(1),
+            sink_sender: sink_tx,
+            source_ids: vec![GlobalId::User(1)],
+            src_recvs: vec![rx],
+            src_time_indexes: vec![None],
+            allowed_lateness: 0,
+            expire_after: None,
+            or_replace: false,
+            create_if_not_exists: true,
+            err_collector: ErrCollector::default(),
+            snapshot: Some(snapshot),
+        };
+        // the flow is not started with empty state
+        assert!(handle.create_flow(create_reqs).await.is_err());
+        assert!(!handle.contains_flow(1).await.unwrap());
+        drop(handle);
+        worker_thread_handle.join().unwrap();
+    }
 }
@@ -19,5 +19,5 @@ mod state;
 mod types;
 
 pub(crate) use render::Context;
-pub(crate) use state::DataflowState;
+pub(crate) use state::{ArrangeId, DataflowSnapshot, DataflowState, SourceProgress};
 pub(crate) use types::ErrCollector;
@@ -17,7 +17,9 @@
 //! And the [`Context`] is the environment for the render process, it contains all the necessary information for the render process
 
 use std::collections::BTreeMap;
+use std::sync::Arc;
 
+use datatypes::vectors::Int64Vector;
 use dfir_rs::scheduled::graph::Dfir;
 use dfir_rs::scheduled::graph_ext::GraphExt;
 use dfir_rs::scheduled::port::{PortCtx, SEND};
@@ -31,6 +33,7 @@ use crate::expr::{self, Batch, GlobalId, LocalId};
 use crate::plan::{Plan, TypedPlan};
 use crate::repr::{self, DiffRow, RelationType};
 
+mod join;
 mod map;
 mod reduce;
 mod src_sink;
@@ -132,10 +135,10 @@ impl Context<'_, '_> {
                 key_val_plan,
                 reduce_plan,
             } => self.render_reduce_batch(input, &key_val_plan, &reduce_plan, &plan.schema.typ),
-            Plan::Join { .. } => NotImplementedSnafu {
-                reason: "Join is still WIP",
-            }
-            .fail(),
+            Plan::Join {
+                inputs,
+                plan: join_plan,
+            } => self.render_join_batch(inputs, join_plan, &plan.schema.typ),
             Plan::Union { .. } => NotImplementedSnafu {
                 reason: "Union is still WIP",
             }
@@ -157,10 +160,7 @@ impl Context<'_, '_> {
                 key_val_plan,
                 reduce_plan,
             } => self.render_reduce(input, key_val_plan, reduce_plan, plan.schema.typ),
-            Plan::Join { .. } => NotImplementedSnafu {
-                reason: "Join is still WIP",
-            }
-            .fail(),
+            Plan::Join { inputs, plan } => self.render_join(inputs, plan),
             Plan::Union { .. } => NotImplementedSnafu {
                 reason: "Union is still WIP",
             }
@@ -203,7 +203,8 @@ impl Context<'_, '_> {
 
                     not_great_than_now.into_iter().for_each(|(_ts, rows)| {
                         err_collector.run(|| {
-                            let rows = rows.into_iter().map(|(row, _ts, _diff)| row).collect();
+                            let (rows, diffs): (Vec<_>, Vec<_>) =
+                                rows.into_iter().map(|(row, _ts, diff)| (row, diff)).unzip();
                             let batch = Batch::try_from_rows_with_types(
                                 rows,
                                 &output_type
@@ -212,6 +213,12 @@ impl Context<'_, '_> {
                                     .map(|ty| ty.scalar_type().clone())
                                     .collect_vec(),
                             )?;
+                            // diffs are only needed if some rows are not a single insert
+                            let batch = if diffs.iter().all(|diff| *diff == 1) {
+                                batch
+                            } else {
+                                batch.with_diffs(Arc::new(Int64Vector::from_vec(diffs)))?
+                            };
                             send_port.give(vec![batch]);
                             Ok(())
                         });
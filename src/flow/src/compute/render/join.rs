@@ -0,0 +1,1068 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Render a [`JoinPlan`] into a sequence of binary join operators.
+//!
+//! Each stage of a [`LinearJoinPlan`] keeps both of its inputs in full arrangements keyed by
+//! `join key ++ row`, so all rows with the same join key can be found with a prefix scan.
+//!
+//! The stream side grows with every input row, so a join needs `EXPIRE AFTER` and the time
+//! index of the stream to expire its rows. The lookup side is usually a table updated by
+//! upserts: if its unique key (the primary key and the time index) is known, an insert of an
+//! existing key retracts the previous version of the row, like the upsert does in the table,
+//! and the rows never expire.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use datatypes::value::Value;
+use datatypes::vectors::Int64Vector;
+use dfir_rs::scheduled::graph_ext::GraphExt;
+use itertools::Itertools;
+use snafu::{ensure, OptionExt};
+
+use crate::compute::render::{Context, SubgraphArg};
+use crate::compute::types::{Collection, CollectionBundle, Toff};
+use crate::error::{Error, InvalidQuerySnafu, PlanSnafu};
+use crate::expr::{Batch, EvalError, ScalarExpr};
+use crate::plan::{JoinFilter, JoinPlan, LinearStagePlan, StageKind, TypedPlan};
+use crate::repr::{self, DiffRow, KeyValDiffRow, RelationType, Row};
+use crate::utils::{ArrangeHandler, KeyExpiryManager};
+
+impl Context<'_, '_> {
+    /// Render a join, start from the source relation and join the lookup relation of each stage
+    /// in order, then apply the final closure.
+    ///
+    /// The join operators only passively receive updates from their inputs, and all output
+    /// updates are emitted with time being `now`.
+    pub fn render_join(
+        &mut self,
+        inputs: Vec<TypedPlan>,
+        plan: JoinPlan,
+    ) -> Result<CollectionBundle, Error> {
+        check_join_inputs(&plan, inputs.len())?;
+
+        let mut rendered = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            let typ = input.schema.typ.clone();
+            rendered.push((self.render_plan(input)?.collection, typ));
+        }
+        let output = self.render_linear_join(rendered, plan)?;
+        Ok(CollectionBundle::from_collection(output))
+    }
+
+    /// Like `render_join` but in batch mode, the diffs of the input and output rows are
+    /// carried by the batches.
+    pub fn render_join_batch(
+        &mut self,
+        inputs: Vec<TypedPlan>,
+        plan: JoinPlan,
+        output_type: &RelationType,
+    ) -> Result<CollectionBundle<Batch>, Error> {
+        check_join_inputs(&plan, inputs.len())?;
+
+        // join operators work on rows, so convert input batches into rows and the output back
+        let mut rendered = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            let typ = input.schema.typ.clone();
+            let batches = self.render_plan_batch(input)?.collection;
+            rendered.push((self.render_batch_to_rows(batches), typ));
+        }
+        let output = self.render_linear_join(rendered, plan)?;
+        let output = self.render_rows_to_batch(output, output_type);
+        Ok(CollectionBundle::from_collection(output))
+    }
+
+    fn render_linear_join(
+        &mut self,
+        inputs: Vec<(Collection<DiffRow>, RelationType)>,
+        plan: JoinPlan,
+    ) -> Result<Collection<DiffRow>, Error> {
+        let JoinPlan::Linear(plan) = plan;
+        let mut inputs = inputs.into_iter().map(Some).collect_vec();
+        let mut take_input = |idx: usize| {
+            inputs
+                .get_mut(idx)
+                .and_then(Option::take)
+                .with_context(|| InvalidQuerySnafu {
+                    reason: format!("Relation {idx} in join plan is not available"),
+                })
+        };
+
+        let (mut stream, mut stream_type) = take_input(plan.source_relation)?;
+
+        if let Some(closure) = plan.initial_closure {
+            stream = self.render_join_closure(stream, closure, "join_initial_closure");
+        }
+
+        for stage in plan.stage_plans {
+            let (lookup, lookup_type) = take_input(stage.lookup_relation)?;
+            stream = self.render_join_stage(stream, &stream_type, lookup, &lookup_type, stage)?;
+            // the lookup columns are appended, so the time index of the stream is kept
+            let time_index = stream_type.time_index;
+            stream_type = RelationType::new(
+                stream_type
+                    .column_types
+                    .into_iter()
+                    .chain(lookup_type.column_types)
+                    .collect(),
+            )
+            .with_time_index(time_index);
+        }
+
+        if let Some(closure) = plan.final_closure {
+            stream = self.render_join_closure(stream, closure, "join_final_closure");
+        }
+
+        Ok(stream)
+    }
+
+    /// Convert batches into rows at `now`, with the diffs of the batches.
+    fn render_batch_to_rows(&mut self, input: Collection<Batch>) -> Collection<DiffRow> {
+        let (out_send_port, out_recv_port) = self.df.make_edge::<_, Toff>("join_batch_to_rows");
+        let now = self.compute_state.current_time_ref();
+        let err_collector = self.err_collector.clone();
+        let scheduler = self.compute_state.get_scheduler();
+
+        let subgraph = self.df.add_subgraph_in_out(
+            "join_batch_to_rows",
+            input.into_inner(),
+            out_send_port,
+            move |_ctx, recv, send| {
+                let now = *now.borrow();
+                let mut rows = vec![];
+                for batch in recv.take_inner().into_iter().flat_map(|v| v.into_iter()) {
+                    err_collector.run(|| {
+                        for idx in 0..batch.row_count() {
+                            rows.push((Row::new(batch.get_row(idx)?), now, batch.get_diff(idx)?));
+                        }
+                        Ok(())
+                    });
+                }
+                send.give(rows);
+            },
+        );
+        scheduler.set_cur_subgraph(subgraph);
+
+        Collection::from_port(out_recv_port)
+    }
+
+    /// Convert rows back into a batch, a row with diff `n` is repeated `|n|` times, with diff
+    /// `1` if `n` is positive, or `-1` if `n` is negative so retractions reach the sink.
+    fn render_rows_to_batch(
+        &mut self,
+        input: Collection<DiffRow>,
+        output_type: &RelationType,
+    ) -> Collection<Batch> {
+        let (out_send_port, out_recv_port) =
+            self.df.make_edge::<_, Toff<Batch>>("join_rows_to_batch");
+        let err_collector = self.err_collector.clone();
+        let scheduler = self.compute_state.get_scheduler();
+        let output_types = output_type
+            .column_types
+            .iter()
+            .map(|ty| ty.scalar_type().clone())
+            .collect_vec();
+
+        let subgraph = self.df.add_subgraph_in_out(
+            "join_rows_to_batch",
+            input.into_inner(),
+            out_send_port,
+            move |_ctx, recv, send| {
+                let (rows, diffs): (Vec<_>, Vec<_>) = recv
+                    .take_inner()
+                    .into_iter()
+                    .flat_map(|v| v.into_iter())
+                    .flat_map(|(row, _ts, diff)| {
+                        std::iter::repeat_n((row, diff.signum()), diff.unsigned_abs() as usize)
+                    })
+                    .unzip();
+                if rows.is_empty() {
+                    return;
+                }
+                let has_retraction = diffs.iter().any(|diff| *diff < 0);
+                if let Some(batch) = err_collector.run(|| {
+                    let batch = Batch::try_from_rows_with_types(rows, &output_types)?;
+                    if has_retraction {
+                        batch.with_diffs(Arc::new(Int64Vector::from_vec(diffs)))
+                    } else {
+                        Ok(batch)
+                    }
+                }) {
+                    send.give(vec![batch]);
+                }
+            },
+        );
+        scheduler.set_cur_subgraph(subgraph);
+
+        Collection::from_port(out_recv_port)
+    }
+
+    /// Apply a [`JoinFilter`] to every row of the collection.
+    fn render_join_closure(
+        &mut self,
+        input: Collection<DiffRow>,
+        closure: JoinFilter,
+        name: &'static str,
+    ) -> Collection<DiffRow> {
+        let (out_send_port, out_recv_port) = self.df.make_edge::<_, Toff>(name);
+        let err_collector = self.err_collector.clone();
+        let scheduler = self.compute_state.get_scheduler();
+
+        let subgraph = self.df.add_subgraph_in_out(
+            name,
+            input.into_inner(),
+            out_send_port,
+            move |_ctx, recv, send| {
+                let output = recv
+                    .take_inner()
+                    .into_iter()
+                    .flat_map(|v| v.into_iter())
+                    .filter_map(|(row, ts, diff)| {
+                        err_collector
+                            .run(|| eval_join_filter(&closure, row.inner))
+                            .flatten()
+                            .map(|row| (row, ts, diff))
+                    })
+                    .collect_vec();
+                send.give(output);
+            },
+        );
+        scheduler.set_cur_subgraph(subgraph);
+
+        Collection::from_port(out_recv_port)
+    }
+
+    /// Render one binary join stage between the stream and the lookup relation.
+    fn render_join_stage(
+        &mut self,
+        stream: Collection<DiffRow>,
+        stream_type: &RelationType,
+        lookup: Collection<DiffRow>,
+        lookup_type: &RelationType,
+        stage: LinearStagePlan,
+    ) -> Result<Collection<DiffRow>, Error> {
+        ensure!(
+            self.compute_state.expire_after().is_some() && stream_type.time_index.is_some(),
+            InvalidQuerySnafu {
+                reason: "A join in a flow needs EXPIRE AFTER and the time index of its stream side to bound the join state",
+            }
+        );
+
+        let (out_send_port, out_recv_port) = self.df.make_edge::<_, Toff>("join");
+
+        // a lookup join also remembers the stream side, so its results can be updated
+        // when the lookup relation changes
+        let stream_arrange = self.new_join_arrange(stream_type, stage.stream_key.len(), true)?;
+        let lookup_versions = match lookup_unique_key(lookup_type) {
+            Some(key) => Some(LookupVersions {
+                key,
+                arrange: self.new_full_arrange()?,
+            }),
+            None => None,
+        };
+        // the rows of a lookup relation with unique key are replaced by upserts instead of expiring
+        let lookup_arrange = self.new_join_arrange(
+            lookup_type,
+            stage.lookup_key.len(),
+            lookup_versions.is_none(),
+        )?;
+        let lookup_arity = lookup_type.column_types.len();
+
+        let now = self.compute_state.current_time_ref();
+        let err_collector = self.err_collector.clone();
+        let scheduler = self.compute_state.get_scheduler();
+        let scheduler_inner = scheduler.clone();
+
+        let subgraph = self.df.add_subgraph_2in_out(
+            "join",
+            stream.into_inner(),
+            lookup.into_inner(),
+            out_send_port,
+            move |_ctx, stream_recv, lookup_recv, send| {
+                let stream_updates = stream_recv
+                    .take_inner()
+                    .into_iter()
+                    .flat_map(|v| v.into_iter())
+                    .collect_vec();
+                let lookup_updates = lookup_recv
+                    .take_inner()
+                    .into_iter()
+                    .flat_map(|v| v.into_iter())
+                    .collect_vec();
+
+                let arg = SubgraphArg {
+                    now: *now.borrow(),
+                    err_collector: &err_collector,
+                    scheduler: &scheduler_inner,
+                    send,
+                };
+                let arranges = JoinArranges {
+                    stream: &stream_arrange,
+                    lookup: &lookup_arrange,
+                    lookup_versions: lookup_versions.as_ref(),
+                    lookup_arity,
+                };
+                join_subgraph(&arranges, &stage, stream_updates, lookup_updates, arg);
+            },
+        );
+        scheduler.set_cur_subgraph(subgraph);
+
+        Ok(Collection::from_port(out_recv_port))
+    }
+
+    /// Create a full arrangement for one side of a join, keyed by `join key ++ row` with empty value.
+    ///
+    /// Rows expire by the time index if `expire` is set.
+    fn new_join_arrange(
+        &mut self,
+        typ: &RelationType,
+        key_arity: usize,
+        expire: bool,
+    ) -> Result<ArrangeHandler, Error> {
+        let arrange_handler = self.compute_state.new_arrange("join");
+
+        // the time index column is shifted by the join key in front of it
+        if let (true, Some(time_index), Some(expire_after)) =
+            (expire, typ.time_index, self.compute_state.expire_after())
+        {
+            let expire_man = KeyExpiryManager::new(
+                Some(expire_after),
+                Some(ScalarExpr::Column(key_arity + time_index)),
+            );
+            arrange_handler.write().set_expire_state(expire_man);
+        }
+
+        // join need full arrangement to be able to look up all rows of a key
+        arrange_handler.clone_full_arrange().context(PlanSnafu {
+            reason: "No write is expected at this point",
+        })
+    }
+
+    /// Create a full arrangement without expiry.
+    fn new_full_arrange(&mut self) -> Result<ArrangeHandler, Error> {
+        self.compute_state
+            .new_arrange("join_lookup_versions")
+            .clone_full_arrange()
+            .context(PlanSnafu {
+                reason: "No write is expected at this point",
+            })
+    }
+}
+
+/// The columns that identify a row of the lookup relation, i.e. the primary key and the
+/// time index of a table, `None` if they are unknown.
+fn lookup_unique_key(typ: &RelationType) -> Option<Vec<usize>> {
+    let mut key = typ.keys.first()?.column_indices.clone();
+    let time_index = typ.time_index?;
+    if !key.contains(&time_index) {
+        key.push(time_index);
+    }
+    Some(key)
+}
+
+/// Check that the join plan uses each of its inputs exactly once.
+fn check_join_inputs(plan: &JoinPlan, input_count: usize) -> Result<(), Error> {
+    let JoinPlan::Linear(plan) = plan;
+    let used = std::iter::once(plan.source_relation)
+        .chain(plan.stage_plans.iter().map(|stage| stage.lookup_relation))
+        .sorted()
+        .collect_vec();
+    ensure!(
+        used == (0..input_count).collect_vec(),
+        InvalidQuerySnafu {
+            reason: format!(
+                "Join plan should use each of its {input_count} inputs exactly once, found {used:?}"
+            ),
+        }
+    );
+    Ok(())
+}
+
+/// The latest version of each row of the lookup relation, by its unique key.
+struct LookupVersions {
+    key: Vec<usize>,
+    /// Arranged by the unique key, the value is the whole row
+    arrange: ArrangeHandler,
+}
+
+/// The arrangements of a join stage
+struct JoinArranges<'a> {
+    stream: &'a ArrangeHandler,
+    lookup: &'a ArrangeHandler,
+    lookup_versions: Option<&'a LookupVersions>,
+    /// Number of columns of the lookup relation, used to pad unmatched rows with nulls
+    lookup_arity: usize,
+}
+
+fn join_subgraph(
+    arranges: &JoinArranges,
+    stage: &LinearStagePlan,
+    stream_updates: Vec<DiffRow>,
+    lookup_updates: Vec<DiffRow>,
+    arg: SubgraphArg,
+) {
+    let SubgraphArg {
+        now,
+        err_collector,
+        send,
+        ..
+    } = arg;
+
+    if stream_updates.is_empty() && lookup_updates.is_empty() {
+        return;
+    }
+
+    let mut output = vec![];
+    let run_join = || {
+        let lookup_updates = match arranges.lookup_versions {
+            Some(versions) => upsert_lookup_updates(versions, lookup_updates, now)?,
+            None => lookup_updates,
+        };
+        match stage.kind {
+            StageKind::Inner => {
+                // The delta of an inner join between two versions is
+                // `d(S ⋈ L) = dS ⋈ L_old + S_new ⋈ dL`
+                for (row, _ts, diff) in &stream_updates {
+                    let Some(key) = eval_join_key(&stage.stream_key, row)? else {
+                        continue;
+                    };
+                    for lookup_row in lookup_rows(arranges.lookup, &key) {
+                        let (lookup_row, lookup_diff) = lookup_row?;
+                        if let Some(out) = eval_stage_closure(&stage.closure, row, &lookup_row)? {
+                            output.push((out, diff * lookup_diff));
+                        }
+                    }
+                }
+                apply_join_updates(arranges.stream, &stage.stream_key, stream_updates, now)?;
+
+                for (lookup_row, _ts, lookup_diff) in &lookup_updates {
+                    let Some(key) = eval_join_key(&stage.lookup_key, lookup_row)? else {
+                        continue;
+                    };
+                    for row in lookup_rows(arranges.stream, &key) {
+                        let (row, diff) = row?;
+                        if let Some(out) = eval_stage_closure(&stage.closure, &row, lookup_row)? {
+                            output.push((out, diff * lookup_diff));
+                        }
+                    }
+                }
+                apply_join_updates(arranges.lookup, &stage.lookup_key, lookup_updates, now)?;
+            }
+            StageKind::Lookup => {
+                // A left join is not linear in the lookup relation, since a stream row is
+                // padded with nulls only when nothing matches. So updates of the stream are
+                // joined with `L_old`, then stream rows with a changed key retract their
+                // results against `L_old` and insert their results against `L_new`.
+                for (row, _ts, diff) in &stream_updates {
+                    left_join_row(arranges, stage, row, *diff, &mut output)?;
+                }
+                apply_join_updates(arranges.stream, &stage.stream_key, stream_updates, now)?;
+
+                let mut changed_keys = BTreeSet::new();
+                for (lookup_row, _ts, _diff) in &lookup_updates {
+                    if let Some(key) = eval_join_key(&stage.lookup_key, lookup_row)? {
+                        changed_keys.insert(key);
+                    }
+                }
+                let affected = changed_keys
+                    .iter()
+                    .flat_map(|key| lookup_rows(arranges.stream, key))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                for (row, diff) in &affected {
+                    left_join_row(arranges, stage, row, -diff, &mut output)?;
+                }
+                apply_join_updates(arranges.lookup, &stage.lookup_key, lookup_updates, now)?;
+                for (row, diff) in &affected {
+                    left_join_row(arranges, stage, row, *diff, &mut output)?;
+                }
+            }
+        }
+        Ok(())
+    };
+    err_collector.run(run_join);
+
+    // retractions and inserts of the same row cancel each other out
+    let mut consolidated: BTreeMap<Row, repr::Diff> = BTreeMap::new();
+    for (row, diff) in output {
+        *consolidated.entry(row).or_default() += diff;
+    }
+    send.give(
+        consolidated
+            .into_iter()
+            .filter(|(_, diff)| *diff != 0)
+            .map(|(row, diff)| (row, now, diff))
+            .collect_vec(),
+    );
+}
+
+/// Turn the updates of the lookup relation into upserts by its unique key: an insert retracts
+/// the previous version of the row with the same key, and a retraction only applies to the
+/// current version.
+fn upsert_lookup_updates(
+    versions: &LookupVersions,
+    updates: Vec<DiffRow>,
+    now: repr::Timestamp,
+) -> Result<Vec<DiffRow>, EvalError> {
+    let mut output = Vec::with_capacity(updates.len());
+    // the latest version of the keys changed by the updates, `None` if deleted
+    let mut changed: BTreeMap<Row, Option<Row>> = BTreeMap::new();
+    {
+        let arrange = versions.arrange.read();
+        for (row, ts, diff) in updates {
+            let key = Row::new(
+                versions
+                    .key
+                    .iter()
+                    .map(|idx| row.inner.get(*idx).cloned().unwrap_or(Value::Null))
+                    .collect(),
+            );
+            let current = match changed.get(&key) {
+                Some(latest) => latest.clone(),
+                None => arrange
+                    .get(now, &key)
+                    .filter(|(_, _, diff)| *diff > 0)
+                    .map(|(row, _, _)| row),
+            };
+            if diff > 0 {
+                if current.as_ref() == Some(&row) {
+                    continue;
+                }
+                if let Some(old) = current {
+                    output.push((old, ts, -1));
+                }
+                output.push((row.clone(), ts, 1));
+                changed.insert(key, Some(row));
+            } else if diff < 0 && current.as_ref() == Some(&row) {
+                output.push((row, ts, -1));
+                changed.insert(key, None);
+            }
+        }
+    }
+
+    let mut arrange = versions.arrange.write();
+    let mut kvs: Vec<KeyValDiffRow> = Vec::with_capacity(changed.len());
+    for (key, latest) in changed {
+        match latest {
+            // a new value overwrites the old value of the key
+            Some(row) => kvs.push(((key, row), now, 1)),
+            None => {
+                if let Some((old, _, diff)) = arrange.get(now, &key) {
+                    kvs.push(((key, old), now, -diff));
+                }
+            }
+        }
+    }
+    arrange.apply_updates(now, kvs)?;
+    arrange.compact_to(now)?;
+    Ok(output)
+}
+
+/// Join a stream row with the current state of the lookup relation as in a left join,
+/// the row is padded with nulls if nothing matches.
+fn left_join_row(
+    arranges: &JoinArranges,
+    stage: &LinearStagePlan,
+    row: &Row,
+    diff: repr::Diff,
+    output: &mut Vec<(Row, repr::Diff)>,
+) -> Result<(), EvalError> {
+    let mut matched = false;
+    if let Some(key) = eval_join_key(&stage.stream_key, row)? {
+        for lookup_row in lookup_rows(arranges.lookup, &key) {
+            let (lookup_row, lookup_diff) = lookup_row?;
+            if let Some(out) = eval_stage_closure(&stage.closure, row, &lookup_row)? {
+                output.push((out, diff * lookup_diff));
+                matched = true;
+            }
+        }
+    }
+    if !matched {
+        let mut padded = row.clone();
+        padded.extend(std::iter::repeat_n(Value::Null, arranges.lookup_arity));
+        output.push((padded, diff));
+    }
+    Ok(())
+}
+
+/// Evaluate the join key of a row, return `None` if any part of the key is null,
+/// since null never equals to anything in an equi-join.
+fn eval_join_key(key: &[ScalarExpr], row: &Row) -> Result<Option<Row>, EvalError> {
+    let mut values = Vec::with_capacity(key.len());
+    for expr in key {
+        let value = expr.eval(&row.inner)?;
+        if value.is_null() {
+            return Ok(None);
+        }
+        values.push(value);
+    }
+    Ok(Some(Row::new(values)))
+}
+
+/// Apply updates of one side of a join into its arrangement, then consolidate it to `now`.
+fn apply_join_updates(
+    arrange: &ArrangeHandler,
+    key: &[ScalarExpr],
+    updates: Vec<DiffRow>,
+    now: repr::Timestamp,
+) -> Result<(), EvalError> {
+    let mut kvs: Vec<KeyValDiffRow> = Vec::with_capacity(updates.len());
+    for (row, _ts, diff) in updates {
+        // rows with null key can never be matched, so there is no need to keep them
+        let Some(mut key_row) = eval_join_key(key, &row)? else {
+            continue;
+        };
+        key_row.extend(row.inner);
+        kvs.push(((key_row, Row::empty()), now, diff));
+    }
+
+    let mut arrange = arrange.write();
+    arrange.apply_updates(now, kvs)?;
+    arrange.compact_to(now)?;
+    arrange.truncate_expired_keys(now);
+    Ok(())
+}
+
+/// Find all rows with join key being `key` in the arrangement, with their multiplicity.
+fn lookup_rows(
+    arrange: &ArrangeHandler,
+    key: &Row,
+) -> impl Iterator<Item = Result<(Row, repr::Diff), EvalError>> {
+    let key_arity = key.len();
+    arrange
+        .read()
+        .get_by_key_prefix(key)
+        .into_iter()
+        .map(move |(key_row, (_val, _ts, diff))| {
+            let row = Row::new(key_row.inner.get(key_arity..).map(|v| v.to_vec()).context(
+                crate::expr::error::InternalSnafu {
+                    reason: "Arranged row is shorter than the join key",
+                },
+            )?);
+            Ok((row, diff))
+        })
+}
+
+/// Evaluate the stage closure on the concatenation of the stream row and the lookup row.
+fn eval_stage_closure(
+    closure: &JoinFilter,
+    row: &Row,
+    lookup_row: &Row,
+) -> Result<Option<Row>, EvalError> {
+    let values = row
+        .inner
+        .iter()
+        .chain(lookup_row.inner.iter())
+        .cloned()
+        .collect_vec();
+    eval_join_filter(closure, values)
+}
+
+/// Check the equivalences of the filter, then apply its map filter project.
+fn eval_join_filter(
+    closure: &JoinFilter,
+    mut values: Vec<Value>,
+) -> Result<Option<Row>, EvalError> {
+    for equivalence in &closure.ready_equivalences {
+        let mut evaluated = equivalence.iter().map(|expr| expr.eval(&values));
+        if let Some(first) = evaluated.next() {
+            let first = first?;
+            for value in evaluated {
+                if value? != first {
+                    return Ok(None);
+                }
+            }
+        }
+    }
+    closure.before.evaluate_into(&mut values, &mut Row::empty())
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+    use std::rc::Rc;
+
+    use datatypes::data_type::ConcreteDataType;
+    use dfir_rs::scheduled::graph::Dfir;
+
+    use super::*;
+    use crate::compute::render::test::{get_output_handle, harness_test_ctx, run_and_check};
+    use crate::compute::state::DataflowState;
+    use crate::expr::{BinaryFunc, GlobalId, MapFilterProject};
+    use crate::plan::{LinearJoinPlan, Plan};
+    use crate::repr::ColumnType;
+
+    fn int_row(values: &[Option<i64>]) -> Row {
+        Row::new(
+            values
+                .iter()
+                .map(|v| v.map(Value::from).unwrap_or(Value::Null))
+                .collect(),
+        )
+    }
+
+    /// `arity` int columns, the second one being the time index
+    fn int_type(arity: usize) -> RelationType {
+        RelationType::new(vec![
+            ColumnType::new_nullable(
+                ConcreteDataType::int64_datatype()
+            );
+            arity
+        ])
+        .with_time_index(Some(1))
+    }
+
+    /// build `Mfp(Join(left, right))` so the output can be read with `get_output_handle`
+    fn join_plan(
+        ctx: &mut Context,
+        left: Vec<DiffRow>,
+        right: Vec<DiffRow>,
+        right_type: RelationType,
+        closure: JoinFilter,
+        kind: StageKind,
+    ) -> TypedPlan {
+        let left = ctx.render_constant(left);
+        ctx.insert_global(GlobalId::User(1), left);
+        let right = ctx.render_constant(right);
+        ctx.insert_global(GlobalId::User(2), right);
+        let get = |id, typ: RelationType| {
+            Plan::Get {
+                id: crate::expr::Id::Global(GlobalId::User(id)),
+            }
+            .with_types(typ.into_unnamed())
+        };
+        let arity = 2 + right_type.column_types.len();
+        let plan = LinearJoinPlan {
+            source_relation: 0,
+            source_key: None,
+            initial_closure: None,
+            stage_plans: vec![LinearStagePlan {
+                lookup_relation: 1,
+                stream_key: vec![ScalarExpr::Column(0)],
+                lookup_key: vec![ScalarExpr::Column(0)],
+                closure,
+                kind,
+            }],
+            final_closure: None,
+        };
+        let join = Plan::Join {
+            inputs: vec![get(1, int_type(2)), get(2, right_type)],
+            plan: JoinPlan::Linear(plan),
+        }
+        .with_types(int_type(arity).into_unnamed());
+        Plan::Mfp {
+            input: Box::new(join),
+            mfp: MapFilterProject::new(arity),
+        }
+        .with_types(int_type(arity).into_unnamed())
+    }
+
+    /// SELECT * FROM l JOIN r ON l.k = r.k
+    #[test]
+    fn test_inner_join() {
+        let mut df = Dfir::new();
+        let mut state = DataflowState::default();
+        state.set_expire_after(Some(1000));
+        let mut ctx = harness_test_ctx(&mut df, &mut state);
+
+        let left = vec![
+            (int_row(&[Some(1), Some(10)]), 1, 1),
+            (int_row(&[None, Some(20)]), 1, 1),
+            (int_row(&[Some(2), Some(30)]), 2, 1),
+            (int_row(&[Some(1), Some(10)]), 4, -1),
+        ];
+        let right = vec![
+            (int_row(&[Some(1), Some(100)]), 2, 1),
+            (int_row(&[Some(2), Some(200)]), 3, 1),
+            (int_row(&[Some(1), Some(101)]), 3, 1),
+        ];
+        let plan = join_plan(
+            &mut ctx,
+            left,
+            right,
+            int_type(2),
+            JoinFilter::identity(4),
+            StageKind::Inner,
+        );
+        let bundle = ctx.render_plan(plan).unwrap();
+        let output = get_output_handle(&mut ctx, bundle);
+        drop(ctx);
+
+        let expected = BTreeMap::from([
+            (
+                2,
+                vec![(int_row(&[Some(1), Some(10), Some(1), Some(100)]), 2, 1)],
+            ),
+            (
+                3,
+                vec![
+                    (int_row(&[Some(1), Some(10), Some(1), Some(101)]), 3, 1),
+                    (int_row(&[Some(2), Some(30), Some(2), Some(200)]), 3, 1),
+                ],
+            ),
+            (
+                4,
+                vec![
+                    (int_row(&[Some(1), Some(10), Some(1), Some(100)]), 4, -1),
+                    (int_row(&[Some(1), Some(10), Some(1), Some(101)]), 4, -1),
+                ],
+            ),
+        ]);
+        run_and_check(&mut state, &mut df, 1..5, expected, output);
+    }
+
+    /// SELECT * FROM l LEFT JOIN r ON l.k = r.k AND l.v < r.v
+    ///
+    /// Rows already emitted are retracted and joined again when `r` changes.
+    #[test]
+    fn test_lookup_join() {
+        let mut df = Dfir::new();
+        let mut state = DataflowState::default();
+        state.set_expire_after(Some(1000));
+        let mut ctx = harness_test_ctx(&mut df, &mut state);
+
+        let left = vec![
+            (int_row(&[Some(1), Some(10)]), 1, 1),
+            (int_row(&[Some(1), Some(10)]), 2, 1),
+            (int_row(&[Some(1), Some(1000)]), 2, 1),
+            (int_row(&[None, Some(20)]), 3, 1),
+        ];
+        let right = vec![
+            (int_row(&[Some(1), Some(100)]), 2, 1),
+            (int_row(&[Some(1), Some(100)]), 4, -1),
+            (int_row(&[Some(1), Some(2000)]), 4, 1),
+        ];
+        let closure = JoinFilter {
+            ready_equivalences: vec![],
+            before: MapFilterProject::new(4)
+                .filter(vec![
+                    ScalarExpr::Column(1).call_binary(ScalarExpr::Column(3), BinaryFunc::Lt)
+                ])
+                .unwrap()
+                .into_safe(),
+        };
+        let plan = join_plan(
+            &mut ctx,
+            left,
+            right,
+            int_type(2),
+            closure,
+            StageKind::Lookup,
+        );
+        let bundle = ctx.render_plan(plan).unwrap();
+        let output = get_output_handle(&mut ctx, bundle);
+        drop(ctx);
+
+        let expected = BTreeMap::from([
+            (1, vec![(int_row(&[Some(1), Some(10), None, None]), 1, 1)]),
+            (
+                2,
+                vec![
+                    (int_row(&[Some(1), Some(10), None, None]), 2, -1),
+                    (int_row(&[Some(1), Some(10), Some(1), Some(100)]), 2, 2),
+                    (int_row(&[Some(1), Some(1000), None, None]), 2, 1),
+                ],
+            ),
+            (3, vec![(int_row(&[None, Some(20), None, None]), 3, 1)]),
+            (
+                4,
+                vec![
+                    (int_row(&[Some(1), Some(10), Some(1), Some(100)]), 4, -2),
+                    (int_row(&[Some(1), Some(10), Some(1), Some(2000)]), 4, 2),
+                    (int_row(&[Some(1), Some(1000), None, None]), 4, -1),
+                    (int_row(&[Some(1), Some(1000), Some(1), Some(2000)]), 4, 1),
+                ],
+            ),
+        ]);
+        run_and_check(&mut state, &mut df, 1..5, expected, output);
+    }
+
+    /// SELECT * FROM l JOIN r ON l.k = r.k, with `r.k` and the time index being the primary key
+    ///
+    /// An insert of an existing key replaces the previous version of the row, as the upsert
+    /// does in the table, and a delete of a stale version is ignored.
+    #[test]
+    fn test_join_lookup_upsert() {
+        let mut df = Dfir::new();
+        let mut state = DataflowState::default();
+        state.set_expire_after(Some(1000));
+        let mut ctx = harness_test_ctx(&mut df, &mut state);
+
+        let left = vec![(int_row(&[Some(1), Some(10)]), 1, 1)];
+        let right = vec![
+            (int_row(&[Some(1), Some(5), Some(100)]), 2, 1),
+            (int_row(&[Some(1), Some(5), Some(200)]), 3, 1),
+            (int_row(&[Some(1), Some(5), Some(100)]), 4, -1),
+            (int_row(&[Some(1), Some(5), Some(200)]), 5, -1),
+        ];
+        let plan = join_plan(
+            &mut ctx,
+            left,
+            right,
+            int_type(3).with_key(vec![0]),
+            JoinFilter::identity(5),
+            StageKind::Inner,
+        );
+        let bundle = ctx.render_plan(plan).unwrap();
+        let output = get_output_handle(&mut ctx, bundle);
+        drop(ctx);
+
+        let expected = BTreeMap::from([
+            (
+                2,
+                vec![(
+                    int_row(&[Some(1), Some(10), Some(1), Some(5), Some(100)]),
+                    2,
+                    1,
+                )],
+            ),
+            (
+                3,
+                vec![
+                    (
+                        int_row(&[Some(1), Some(10), Some(1), Some(5), Some(100)]),
+                        3,
+                        -1,
+                    ),
+                    (
+                        int_row(&[Some(1), Some(10), Some(1), Some(5), Some(200)]),
+                        3,
+                        1,
+                    ),
+                ],
+            ),
+            (
+                5,
+                vec![(
+                    int_row(&[Some(1), Some(10), Some(1), Some(5), Some(200)]),
+                    5,
+                    -1,
+                )],
+            ),
+        ]);
+        run_and_check(&mut state, &mut df, 1..6, expected, output);
+    }
+
+    #[test]
+    fn test_join_without_expire_after() {
+        let mut df = Dfir::new();
+        let mut state = DataflowState::default();
+        let mut ctx = harness_test_ctx(&mut df, &mut state);
+
+        let plan = join_plan(
+            &mut ctx,
+            vec![],
+            vec![],
+            int_type(2),
+            JoinFilter::identity(4),
+            StageKind::Inner,
+        );
+        assert!(ctx.render_plan(plan).is_err());
+    }
+
+    /// Same as `test_inner_join` but in batch mode, the retraction is carried by the diffs
+    /// of the output batch
+    #[test]
+    fn test_inner_join_batch() {
+        let mut df = Dfir::new();
+        let mut state = DataflowState::default();
+        state.set_expire_after(Some(1000));
+        let mut ctx = harness_test_ctx(&mut df, &mut state);
+
+        let left = vec![
+            (int_row(&[Some(1), Some(10)]), 1, 1),
+            (int_row(&[Some(2), Some(30)]), 2, 1),
+            (int_row(&[Some(1), Some(10)]), 4, -1),
+        ];
+        let right = vec![
+            (int_row(&[Some(1), Some(100)]), 2, 1),
+            (int_row(&[Some(1), Some(101)]), 3, 1),
+        ];
+        let constant = |rows| Plan::Constant { rows }.with_types(int_type(2).into_unnamed());
+        let plan = JoinPlan::Linear(LinearJoinPlan {
+            source_relation: 0,
+            source_key: None,
+            initial_closure: None,
+            stage_plans: vec![LinearStagePlan {
+                lookup_relation: 1,
+                stream_key: vec![ScalarExpr::Column(0)],
+                lookup_key: vec![ScalarExpr::Column(0)],
+                closure: JoinFilter::identity(4),
+                kind: StageKind::Inner,
+            }],
+            final_closure: None,
+        });
+        let bundle = ctx
+            .render_join_batch(vec![constant(left), constant(right)], plan, &int_type(4))
+            .unwrap();
+
+        let output = Rc::new(RefCell::new(vec![]));
+        let output_inner = output.clone();
+        ctx.df.add_subgraph_sink(
+            "test_sink",
+            bundle.collection.into_inner(),
+            move |_ctx, recv| {
+                let data = recv.take_inner();
+                output_inner
+                    .borrow_mut()
+                    .extend(data.into_iter().flat_map(|v| v.into_iter()));
+            },
+        );
+        drop(ctx);
+
+        let types = vec![ConcreteDataType::int64_datatype(); 4];
+        let expected = BTreeMap::from([
+            (
+                2,
+                vec![(int_row(&[Some(1), Some(10), Some(1), Some(100)]), 1)],
+            ),
+            (
+                3,
+                vec![(int_row(&[Some(1), Some(10), Some(1), Some(101)]), 1)],
+            ),
+            (
+                4,
+                vec![
+                    (int_row(&[Some(1), Some(10), Some(1), Some(100)]), -1),
+                    (int_row(&[Some(1), Some(10), Some(1), Some(101)]), -1),
+                ],
+            ),
+        ]);
+        for now in 1..5 {
+            state.set_current_ts(now);
+            state.run_available_with_schedule(&mut df);
+            assert!(state.get_err_collector().is_empty());
+            let expected = expected
+                .get(&now)
+                .map(|rows| {
+                    let (rows, diffs): (Vec<_>, Vec<_>) = rows.iter().cloned().unzip();
+                    let batch = Batch::try_from_rows_with_types(rows, &types).unwrap();
+                    // the diffs are only attached if there is a retraction
+                    let batch = if diffs.iter().all(|d| *d == 1) {
+                        batch
+                    } else {
+                        batch
+                            .with_diffs(Arc::new(Int64Vector::from_vec(diffs)))
+                            .unwrap()
+                    };
+                    vec![batch]
+                })
+                .unwrap_or_default();
+            assert_eq!(*output.borrow(), expected, "at ts={}", now);
+            output.borrow_mut().clear();
+        }
+    }
+}
@@ -98,7 +98,7 @@ impl Context<'_, '_> {
 
         // default to have a arrange with only future updates, so it can be empty if no temporal filter is applied
         // as stream only sends current updates and etc.
-        let arrange_handler = self.compute_state.new_arrange(None);
+        let arrange_handler = self.compute_state.new_arrange("mfp");
         let arrange_handler_inner =
             arrange_handler
                 .clone_future_only()
@@ -72,7 +72,7 @@ impl Context<'_, '_> {
         let output_key_arity = key_val_plan.key_plan.output_arity();
 
         // TODO(discord9): config global expire time from self
-        let arrange_handler = self.compute_state.new_arrange(None);
+        let arrange_handler = self.compute_state.new_arrange("reduce_batch");
 
         if let (Some(time_index), Some(expire_after)) =
             (output_type.time_index, self.compute_state.expire_after())
@@ -166,7 +166,7 @@ impl Context<'_, '_> {
         let output_key_arity = key_val_plan.key_plan.output_arity();
 
         // TODO(discord9): config global expire time from self
-        let arrange_handler = self.compute_state.new_arrange(None);
+        let arrange_handler = self.compute_state.new_arrange("reduce");
 
         if let (Some(time_index), Some(expire_after)) =
             (output_type.time_index, self.compute_state.expire_after())
@@ -255,7 +255,7 @@ impl Context<'_, '_> {
             ReducePlan::Accumulable(AccumulablePlan { distinct_aggrs, .. }) => {
                 (!distinct_aggrs.is_empty()).then(|| {
                     std::iter::repeat_with(|| {
-                        let arr = self.compute_state.new_arrange(None);
+                        let arr = self.compute_state.new_arrange("reduce_distinct");
                         arr.set_full_arrangement(true);
                         arr
                     })
@@ -315,6 +315,14 @@ fn batch_split_by_key_val(
 
     if val_batch.row_count() == 0 && val_batch.column_count() == 0 {
         val_batch.set_row_count(row_count);
+        // the diffs of retracted rows are carried by the val batch
+        if let Some(diffs) = batch.diffs() {
+            if let Some(with_diffs) =
+                err_collector.run(|| val_batch.clone().with_diffs(diffs.clone()))
+            {
+                val_batch = with_diffs;
+            }
+        }
     }
 
     (key_batch, val_batch)
@@ -553,7 +561,8 @@ fn reduce_batch_subgraph(
                         .cloned()
                         .unwrap_or_else(|| Arc::new(NullVector::new(val_batch.row_count())));
                     let len = cur_input.len();
-                    cur_accum.update_batch(&expr.func, VectorDiff::from(cur_input))?;
+                    let input = VectorDiff::try_new(cur_input, val_batch.diffs().cloned())?;
+                    cur_accum.update_batch(&expr.func, input)?;
 
                     trace!("Reduce accum after take {} rows: {:?}", len, cur_accum);
                 }
@@ -27,15 +27,22 @@ use crate::compute::render::Context;
 use crate::compute::types::{Arranged, Collection, CollectionBundle, Toff};
 use crate::error::{Error, PlanSnafu};
 use crate::expr::error::InternalSnafu;
-use crate::expr::{Batch, EvalError};
-use crate::repr::{DiffRow, Row};
+use crate::expr::{Batch, EvalError, GlobalId};
+use crate::repr::{value_to_internal_ts, DiffRow, Row};
 
 #[allow(clippy::mutable_key_type)]
 impl Context<'_, '_> {
     /// simply send the batch to downstream, without fancy features like buffering
+    ///
+    /// if `time_index` is given, consumed rows are recorded in the source's progress
+    /// in dataflow state, see [`DataflowState::snapshot`]
+    ///
+    /// [`DataflowState::snapshot`]: crate::compute::DataflowState::snapshot
     pub fn render_source_batch(
         &mut self,
         mut src_recv: broadcast::Receiver<Batch>,
+        source_id: GlobalId,
+        time_index: Option<usize>,
     ) -> Result<CollectionBundle<Batch>, Error> {
         debug!("Rendering Source Batch");
         let (send_port, recv_port) = self.df.make_edge::<_, Toff<Batch>>("source_batch");
@@ -43,6 +50,8 @@ impl Context<'_, '_> {
         let schd = self.compute_state.get_scheduler();
         let inner_schd = schd.clone();
         let now = self.compute_state.current_time_ref();
+        let progress = self.compute_state.source_progress_ref();
+        let allowed_lateness = self.compute_state.allowed_lateness();
         let err_collector = self.err_collector.clone();
 
         let sub = self
@@ -86,6 +95,29 @@ impl Context<'_, '_> {
                     total_row_count,
                     total_batches.len()
                 );
+                if let Some(time_index) = time_index {
+                    err_collector.run(|| -> Result<(), EvalError> {
+                        let mut rows = Vec::with_capacity(total_row_count);
+                        for batch in &total_batches {
+                            for idx in 0..batch.row_count() {
+                                let row = batch.get_row(idx)?;
+                                let Some(ts) = row
+                                    .get(time_index)
+                                    .and_then(|v| value_to_internal_ts(v.clone()).ok())
+                                else {
+                                    continue;
+                                };
+                                rows.push((ts, Row::new(row)));
+                            }
+                        }
+                        progress
+                            .borrow_mut()
+                            .entry(source_id)
+                            .or_default()
+                            .consume(rows, allowed_lateness);
+                        Ok(())
+                    });
+                }
                 send.give(total_batches);
 
                 let now = *now.borrow();
@@ -106,7 +138,7 @@ impl Context<'_, '_> {
     ) -> Result<CollectionBundle, Error> {
         debug!("Rendering Source");
         let (send_port, recv_port) = self.df.make_edge::<_, Toff>("source");
-        let arrange_handler = self.compute_state.new_arrange(None);
+        let arrange_handler = self.compute_state.new_arrange("source");
         let arrange_handler_inner =
             arrange_handler
                 .clone_future_only()
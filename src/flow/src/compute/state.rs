@@ -13,16 +13,20 @@
 // limitations under the License.
 
 use std::cell::RefCell;
-use std::collections::{BTreeMap, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::rc::Rc;
 
 use dfir_rs::scheduled::graph::Dfir;
 use dfir_rs::scheduled::SubgraphId;
 use get_size2::GetSize;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
 
 use crate::compute::types::ErrCollector;
-use crate::repr::{self, Timestamp};
-use crate::utils::{ArrangeHandler, Arrangement};
+use crate::error::{Error, InternalSnafu};
+use crate::expr::GlobalId;
+use crate::repr::{self, Row, Timestamp};
+use crate::utils::{ArrangeHandler, Arrangement, ArrangementSnapshot};
 
 /// input/output of a dataflow
 /// One `ComputeState` manage the input/output/schedule of one `Dfir`
@@ -42,23 +46,99 @@ pub struct DataflowState {
     err_collector: ErrCollector,
     /// save all used arrange in this dataflow, since usually there is no delete operation
     /// we can just keep track of all used arrange and schedule subgraph when they need to be updated
-    arrange_used: Vec<ArrangeHandler>,
+    arrange_used: Vec<(ArrangeId, ArrangeHandler)>,
     /// the time arrangement need to be expired after a certain time in milliseconds
     expire_after: Option<Timestamp>,
     /// the last time each subgraph executed
     last_exec_time: Option<Timestamp>,
+    /// how far each source is consumed, used to know from where to replay source data
+    /// after restoring from a snapshot
+    source_progress: Rc<RefCell<BTreeMap<GlobalId, SourceProgress>>>,
+    /// how long a source row can arrive after rows with greater time index value,
+    /// and still be replayed after restoring from a snapshot
+    allowed_lateness: Timestamp,
+}
+
+/// Identify an arrangement in a dataflow by the operator it's created for and the number of
+/// arrangements created for the same operator before it, e.g. `reduce/0`
+///
+/// It's stable as long as the dataflow is rendered from the same plan, and a snapshot taken
+/// from a different plan is detected when restoring instead of put into wrong arrangements.
+pub type ArrangeId = String;
+
+/// A serializable copy of the state of a dataflow, see [`DataflowState::snapshot`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataflowSnapshot {
+    pub as_of: Timestamp,
+    /// state of each arrangement by its id
+    pub arrangements: BTreeMap<ArrangeId, ArrangementSnapshot>,
+    pub source_progress: BTreeMap<GlobalId, SourceProgress>,
+}
+
+/// How far a source is consumed by a dataflow
+///
+/// Source data is replayed from `replay_from` after restoring from a snapshot, the rows
+/// already consumed in that range are kept in `consumed` so they are not counted twice.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceProgress {
+    /// the max time index value consumed from the source
+    pub watermark: Timestamp,
+    /// `watermark - allowed_lateness`, rows with smaller time index value are not replayed
+    pub replay_from: Timestamp,
+    /// rows consumed with time index value not less than `replay_from`
+    pub consumed: BTreeMap<Timestamp, Vec<Row>>,
+}
+
+impl Default for SourceProgress {
+    fn default() -> Self {
+        Self {
+            watermark: Timestamp::MIN,
+            replay_from: Timestamp::MIN,
+            consumed: BTreeMap::new(),
+        }
+    }
+}
+
+impl SourceProgress {
+    /// Record rows consumed from the source, each with its time index value
+    pub fn consume(
+        &mut self,
+        rows: impl IntoIterator<Item = (Timestamp, Row)>,
+        allowed_lateness: Timestamp,
+    ) {
+        for (ts, row) in rows {
+            // older rows are never replayed, so there is no need to remember them
+            if ts >= self.replay_from {
+                self.consumed.entry(ts).or_default().push(row);
+            }
+            self.watermark = self.watermark.max(ts);
+        }
+        let replay_from = self.watermark.saturating_sub(allowed_lateness);
+        if replay_from > self.replay_from {
+            self.consumed = self.consumed.split_off(&replay_from);
+            self.replay_from = replay_from;
+        }
+    }
 }
 
 impl DataflowState {
-    pub fn new_arrange(&mut self, name: Option<Vec<String>>) -> ArrangeHandler {
-        let arrange = name.map(Arrangement::new_with_name).unwrap_or_default();
+    /// Create an arrangement for an operator, see [`ArrangeId`] for how it's identified
+    pub fn new_arrange(&mut self, operator: &str) -> ArrangeHandler {
+        let seq = self
+            .arrange_used
+            .iter()
+            .filter(|(id, _)| id.rsplit_once('/').map(|(op, _)| op) == Some(operator))
+            .count();
+        let id = format!("{operator}/{seq}");
+        let arrange = Arrangement::new_with_name(vec![id.clone()]);
 
         let arr = ArrangeHandler::from(arrange);
         // mark this arrange as used in this dataflow
-        self.arrange_used.push(
+        self.arrange_used.push((
+            id,
             arr.clone_future_only()
                 .expect("No write happening at this point"),
-        );
+        ));
         arr
     }
 
@@ -115,7 +195,10 @@ impl DataflowState {
     }
 
     pub fn get_state_size(&self) -> usize {
-        self.arrange_used.iter().map(|x| x.read().get_size()).sum()
+        self.arrange_used
+            .iter()
+            .map(|(_, x)| x.read().get_size())
+            .sum()
     }
 
     pub fn set_last_exec_time(&mut self, time: Timestamp) {
@@ -125,6 +208,70 @@ impl DataflowState {
     pub fn last_exec_time(&self) -> Option<Timestamp> {
         self.last_exec_time
     }
+
+    /// return a handle to the source progress, sources should update it when consuming rows
+    pub fn source_progress_ref(&self) -> Rc<RefCell<BTreeMap<GlobalId, SourceProgress>>> {
+        self.source_progress.clone()
+    }
+
+    pub fn set_allowed_lateness(&mut self, allowed_lateness: repr::Duration) {
+        self.allowed_lateness = allowed_lateness;
+    }
+
+    pub fn allowed_lateness(&self) -> Timestamp {
+        self.allowed_lateness
+    }
+
+    /// Take a snapshot of all arrangements and source progress
+    ///
+    /// Return None if this dataflow is stateless, i.e. there is nothing to restore
+    pub fn snapshot(&self) -> Option<DataflowSnapshot> {
+        if self.arrange_used.is_empty() {
+            return None;
+        }
+        Some(DataflowSnapshot {
+            as_of: self.current_ts(),
+            arrangements: self
+                .arrange_used
+                .iter()
+                .map(|(id, arr)| (id.clone(), arr.read().snapshot()))
+                .collect(),
+            source_progress: self.source_progress.borrow().clone(),
+        })
+    }
+
+    /// Restore state from a snapshot taken from a dataflow rendered from the same plan
+    ///
+    /// Nothing is restored if the arrangements of the snapshot and this dataflow differ.
+    pub fn restore(&mut self, mut snapshot: DataflowSnapshot) -> Result<(), Error> {
+        let expected = self
+            .arrange_used
+            .iter()
+            .map(|(id, _)| id.as_str())
+            .collect::<BTreeSet<_>>();
+        let actual = snapshot
+            .arrangements
+            .keys()
+            .map(|id| id.as_str())
+            .collect::<BTreeSet<_>>();
+        ensure!(
+            expected == actual,
+            InternalSnafu {
+                reason: format!(
+                    "Snapshot has arrangements {:?}, but dataflow has {:?}",
+                    actual, expected
+                ),
+            }
+        );
+        for (id, arr) in &self.arrange_used {
+            if let Some(arr_snapshot) = snapshot.arrangements.remove(id) {
+                arr.write().restore(arr_snapshot);
+            }
+        }
+        self.set_current_ts(snapshot.as_of);
+        self.source_progress.replace(snapshot.source_progress);
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -297,6 +297,41 @@ pub enum Error {
         location: Location,
         source: common_grpc::error::Error,
     },
+    #[snafu(display("Failed to init object store for flow checkpoint"))]
+    InitCheckpointStore {
+        #[snafu(implicit)]
+        location: Location,
+        source: object_store::error::Error,
+    },
+
+    #[snafu(display("Failed to access flow checkpoint at path: {path}"))]
+    CheckpointStore {
+        path: String,
+        #[snafu(source)]
+        error: object_store::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Failed to encode or decode checkpoint of flow {flow_id}"))]
+    CheckpointSerde {
+        flow_id: FlowId,
+        #[snafu(source)]
+        error: serde_json::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Failed to restore flow {flow_id} from its checkpoint, drop and recreate the flow to start from scratch"
+    ))]
+    RestoreCheckpoint {
+        flow_id: FlowId,
+        #[snafu(source(from(Error, Box::new)))]
+        source: Box<Error>,
+        #[snafu(implicit)]
+        location: Location,
+    },
 }
 
 /// the outer message is the full error stack, and inner message in header is the last error message that can be show directly to user
@@ -356,6 +391,11 @@ impl ErrorExt for Error {
             Error::SubstraitEncodeLogicalPlan { source, .. } => source.status_code(),
 
             Error::ConvertColumnSchema { source, .. } => source.status_code(),
+
+            Error::InitCheckpointStore { source, .. } => source.status_code(),
+            Error::CheckpointStore { .. } => StatusCode::StorageUnavailable,
+            Error::CheckpointSerde { .. } => StatusCode::Unexpected,
+            Error::RestoreCheckpoint { source, .. } => source.status_code(),
         }
     }
 
@@ -24,10 +24,12 @@ mod scalar;
 mod signature;
 pub(crate) mod utils;
 
+use std::sync::Arc;
+
 use arrow::compute::FilterBuilder;
 use datatypes::prelude::{ConcreteDataType, DataType};
 use datatypes::value::Value;
-use datatypes::vectors::{BooleanVector, Helper, VectorRef};
+use datatypes::vectors::{BooleanVector, Helper, Int64Vector, VectorRef};
 pub(crate) use df_func::{DfScalarFunction, RawDfScalarFn};
 pub(crate) use error::{EvalError, InvalidArgumentSnafu};
 pub(crate) use func::{BinaryFunc, UnaryFunc, UnmaterializableFunc, VariadicFunc};
@@ -164,6 +166,40 @@ impl Batch {
         }
     }
 
+    /// Set the diff of each row, `1` means insert and `-1` means delete
+    pub fn with_diffs(mut self, diffs: VectorRef) -> Result<Self, EvalError> {
+        ensure!(
+            diffs.len() == self.row_count,
+            InvalidArgumentSnafu {
+                reason: format!(
+                    "Expect {} diffs for the batch, found {}",
+                    self.row_count,
+                    diffs.len()
+                )
+            }
+        );
+        self.diffs = Some(diffs);
+        Ok(self)
+    }
+
+    /// The diff of each row, `None` means all rows are insert
+    pub fn diffs(&self) -> Option<&VectorRef> {
+        self.diffs.as_ref()
+    }
+
+    /// Get the diff of the row at `idx`
+    pub fn get_diff(&self, idx: usize) -> Result<Diff, EvalError> {
+        let Some(diffs) = &self.diffs else {
+            return Ok(1);
+        };
+        diffs.get(idx).try_into().map_err(|_| {
+            InvalidArgumentSnafu {
+                reason: format!("Invalid diff value at index {}", idx),
+            }
+            .build()
+        })
+    }
+
     pub fn batch(&self) -> &[VectorRef] {
         &self.batch
     }
@@ -206,7 +242,11 @@ impl Batch {
             .iter()
             .map(|v| v.slice(offset, length))
             .collect_vec();
-        Batch::try_new(batch, length)
+        let sliced = Batch::try_new(batch, length)?;
+        match &self.diffs {
+            Some(diffs) => sliced.with_diffs(diffs.slice(offset, length)),
+            None => Ok(sliced),
+        }
     }
 
     /// append another batch to self
@@ -229,11 +269,22 @@ impl Batch {
         if self.batch.is_empty() {
             self.batch = other.batch;
             self.row_count = other.row_count;
+            self.diffs = other.diffs;
             return Ok(());
         } else if other.batch.is_empty() {
             return Ok(());
         }
 
+        let diffs = if self.diffs.is_some() || other.diffs.is_some() {
+            let diffs = (0..self.row_count())
+                .map(|idx| self.get_diff(idx))
+                .chain((0..other.row_count()).map(|idx| other.get_diff(idx)))
+                .collect::<Result<Vec<_>, _>>()?;
+            Some(Arc::new(Int64Vector::from_vec(diffs)) as VectorRef)
+        } else {
+            None
+        };
+
         let dts = {
             let max_len = self.batch.len().max(other.batch.len());
             let mut dts = Vec::with_capacity(max_len);
@@ -278,6 +329,7 @@ impl Batch {
         }
         self.batch = result;
         self.row_count = self_row_count + other_row_count;
+        self.diffs = diffs;
         Ok(())
     }
 
@@ -297,7 +349,21 @@ impl Batch {
         let res_vector = Helper::try_into_vectors(&filtered).context(DataTypeSnafu {
             msg: "can't convert arrow array to vector",
         })?;
-        Self::try_new(res_vector, len)
+        let res = Self::try_new(res_vector, len)?;
+        match &self.diffs {
+            Some(diffs) => {
+                let filtered = filter_pred
+                    .filter(diffs.to_arrow_array().as_ref())
+                    .context(ArrowSnafu {
+                        context: "Failed to filter diffs",
+                    })?;
+                let diffs = Helper::try_into_vector(filtered).context(DataTypeSnafu {
+                    msg: "can't convert arrow array to vector",
+                })?;
+                res.with_diffs(diffs)
+            }
+            None => Ok(res),
+        }
     }
 }
 
@@ -318,7 +384,7 @@ impl VectorDiff {
         self.vector.len()
     }
 
-    fn try_new(vector: VectorRef, diff: Option<VectorRef>) -> Result<Self, EvalError> {
+    pub(crate) fn try_new(vector: VectorRef, diff: Option<VectorRef>) -> Result<Self, EvalError> {
         ensure!(
             diff.as_ref().is_none_or(|diff| diff.len() == vector.len()),
             InvalidArgumentSnafu {
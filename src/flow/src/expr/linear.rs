@@ -532,7 +532,21 @@ impl SafeMfpPlan {
             .collect_vec();
         let row_count = pred.count();
 
-        Batch::try_new(projected, row_count)
+        let res = Batch::try_new(projected, row_count)?;
+        match batch.diffs() {
+            Some(diffs) => {
+                let filtered = pred
+                    .filter(diffs.to_arrow_array().as_ref())
+                    .with_context(|_| ArrowSnafu {
+                        context: format!("failed to filter diffs for mfp operator {:?}", self),
+                    })?;
+                let diffs = Helper::try_into_vector(filtered).context(DataTypeSnafu {
+                    msg: "Failed to convert arrow array to vector",
+                })?;
+                res.with_diffs(diffs)
+            }
+            None => Ok(res),
+        }
     }
 
     /// similar to [`MapFilterProject::evaluate_into`], just in batch.
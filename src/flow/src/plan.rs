@@ -22,7 +22,9 @@ use std::collections::BTreeSet;
 
 use crate::error::Error;
 use crate::expr::{GlobalId, Id, LocalId, MapFilterProject, SafeMfpPlan, ScalarExpr, TypedExpr};
-use crate::plan::join::JoinPlan;
+pub(crate) use crate::plan::join::{
+    JoinFilter, JoinPlan, LinearJoinPlan, LinearStagePlan, StageKind,
+};
 pub(crate) use crate::plan::reduce::{AccumulablePlan, AggrWithIndex, KeyValPlan, ReducePlan};
 use crate::repr::{DiffRow, RelationDesc};
 
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::expr::ScalarExpr;
+use crate::expr::{MapFilterProject, ScalarExpr};
 use crate::plan::SafeMfpPlan;
 
 /// TODO(discord9): consider impl more join strategies
@@ -31,6 +31,16 @@ pub struct JoinFilter {
     pub before: SafeMfpPlan,
 }
 
+impl JoinFilter {
+    /// A filter that keep every row of given arity as is
+    pub fn identity(arity: usize) -> Self {
+        Self {
+            ready_equivalences: vec![],
+            before: MapFilterProject::new(arity).into_safe(),
+        }
+    }
+}
+
 /// A plan for the execution of a linear join.
 ///
 /// A linear join is a sequence of stages, each of which introduces
@@ -64,13 +74,28 @@ pub struct LinearStagePlan {
     pub lookup_relation: usize,
     /// The key expressions to use for the stream relation.
     pub stream_key: Vec<ScalarExpr>,
-    /// Columns to retain from the stream relation.
-    /// These columns are those that are not redundant with `stream_key`,
-    /// and cannot be read out of the key component of an arrangement.
-    pub stream_thinning: Vec<usize>,
     /// The key expressions to use for the lookup relation.
     pub lookup_key: Vec<ScalarExpr>,
-    /// The closure to apply to the concatenation of the key columns,
-    /// the stream value columns, and the lookup value colunms.
+    /// The closure to apply to the concatenation of the stream row and the lookup row.
+    ///
+    /// For [`StageKind::Lookup`] the closure must keep the arity of its input,
+    /// so unmatched stream rows can be padded with nulls.
     pub closure: JoinFilter,
+    /// How the stream and the lookup relation are joined in this stage.
+    pub kind: StageKind,
+}
+
+/// The kind of a join stage.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum StageKind {
+    /// Inner equi-join, both the stream and the lookup relation are kept in arrangements
+    /// so that updates from either side produce new join results.
+    Inner,
+    /// Left outer join against a dimension table.
+    ///
+    /// Updates of the stream are matched against the current state of the lookup relation
+    /// and padded with nulls if nothing matches. The stream is kept in an arrangement too,
+    /// so when the lookup relation changes, the rows already emitted for the affected keys
+    /// are retracted and the stream rows are joined again.
+    Lookup,
 }
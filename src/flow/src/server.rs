@@ -52,6 +52,7 @@ use tokio::sync::{broadcast, oneshot, Mutex};
 use tonic::codec::CompressionEncoding;
 use tonic::{Request, Response, Status};
 
+use crate::adapter::checkpoint::FlowCheckpointer;
 use crate::adapter::flownode_impl::{FlowDualEngine, FlowDualEngineRef};
 use crate::adapter::{create_worker, FlowStreamingEngineRef};
 use crate::batching_mode::engine::BatchingEngine;
@@ -420,6 +421,9 @@ impl FlownodeBuilder {
         if let Some(handler) = self.state_report_handler.take() {
             man = man.with_state_report_handler(handler).await;
         }
+        if let Some(checkpointer) = FlowCheckpointer::try_new(&self.opts.flow.checkpoint).await? {
+            man = man.with_checkpointer(checkpointer);
+        }
         info!("Flow Node Manager started");
         Ok(man)
     }
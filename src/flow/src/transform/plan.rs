@@ -12,19 +12,22 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
 use itertools::Itertools;
 use snafu::OptionExt;
 use substrait::substrait_proto_df::proto::{FilterRel, ReadRel};
 use substrait_proto::proto::expression::MaskExpression;
+use substrait_proto::proto::join_rel::JoinType;
 use substrait_proto::proto::read_rel::ReadType;
 use substrait_proto::proto::rel::RelType;
-use substrait_proto::proto::{plan_rel, Plan as SubPlan, ProjectRel, Rel};
+use substrait_proto::proto::{plan_rel, JoinRel, Plan as SubPlan, ProjectRel, Rel};
 
 use crate::error::{Error, InvalidQuerySnafu, NotImplementedSnafu, PlanSnafu, UnexpectedSnafu};
-use crate::expr::{MapFilterProject, TypedExpr};
-use crate::plan::{Plan, TypedPlan};
+use crate::expr::{BinaryFunc, MapFilterProject, ScalarExpr, TypedExpr, VariadicFunc};
+use crate::plan::{
+    JoinFilter, JoinPlan, LinearJoinPlan, LinearStagePlan, Plan, StageKind, TypedPlan,
+};
 use crate::repr::{self, RelationType};
 use crate::transform::{substrait_proto, FlownodeContext, FunctionExtensions};
 
@@ -147,6 +150,102 @@ impl TypedPlan {
         input.filter(expr)
     }
 
+    /// Convert a join into a single stage [`LinearJoinPlan`], with the left input as the stream
+    /// and the right input as the lookup relation.
+    ///
+    /// Equalities between the two inputs in the join condition become the join key,
+    /// other predicates are evaluated on each pair of matched rows.
+    ///
+    /// Both inputs are kept in arrangements when rendered, so a left join against a dimension
+    /// table retracts and re-emits its results when the dimension table changes.
+    #[async_recursion::async_recursion]
+    pub async fn from_substrait_join(
+        ctx: &mut FlownodeContext,
+        join: &JoinRel,
+        extensions: &FunctionExtensions,
+    ) -> Result<TypedPlan, Error> {
+        let (Some(left), Some(right)) = (join.left.as_ref(), join.right.as_ref()) else {
+            return not_impl_err!("Join without both inputs is not supported");
+        };
+        let left = TypedPlan::from_substrait_rel(ctx, left, extensions).await?;
+        let right = TypedPlan::from_substrait_rel(ctx, right, extensions).await?;
+
+        let kind = match join.r#type() {
+            JoinType::Inner => StageKind::Inner,
+            JoinType::Left => StageKind::Lookup,
+            other => return not_impl_err!("Unsupported join type: {:?}", other),
+        };
+
+        let left_arity = left.schema.len()?;
+        let right_arity = right.schema.len()?;
+        let mut right_schema = right.schema.clone();
+        if kind == StageKind::Lookup {
+            // unmatched rows are padded with nulls
+            for column in right_schema.typ.column_types.iter_mut() {
+                column.nullable = true;
+            }
+        }
+        let mut schema = left.schema.clone().concat(right_schema);
+        // a row can be joined with many rows from the other side, so keys of either side no longer hold
+        schema.typ.keys.clear();
+        schema.typ.auto_columns = left
+            .schema
+            .typ
+            .auto_columns
+            .iter()
+            .copied()
+            .chain(right.schema.typ.auto_columns.iter().map(|c| c + left_arity))
+            .collect();
+
+        let mut stream_key = vec![];
+        let mut lookup_key = vec![];
+        let mut predicates = vec![];
+        if let Some(condition) = join.expression.as_ref() {
+            let condition = TypedExpr::from_substrait_rex(condition, &schema, extensions).await?;
+            for conjunct in split_conjunction(condition.expr) {
+                match as_equi_key(&conjunct, left_arity)? {
+                    Some((stream, lookup)) => {
+                        stream_key.push(stream);
+                        lookup_key.push(lookup);
+                    }
+                    None => predicates.push(conjunct),
+                }
+            }
+        }
+        let closure = JoinFilter {
+            ready_equivalences: vec![],
+            before: MapFilterProject::new(left_arity + right_arity)
+                .filter(predicates)?
+                .into_safe(),
+        };
+
+        let plan = LinearJoinPlan {
+            source_relation: 0,
+            source_key: None,
+            initial_closure: None,
+            stage_plans: vec![LinearStagePlan {
+                lookup_relation: 1,
+                stream_key,
+                lookup_key,
+                closure,
+                kind,
+            }],
+            final_closure: None,
+        };
+        let ret = Plan::Join {
+            inputs: vec![left, right],
+            plan: JoinPlan::Linear(plan),
+        }
+        .with_types(schema);
+
+        if let Some(filter) = join.post_join_filter.as_ref() {
+            let expr = TypedExpr::from_substrait_rex(filter, &ret.schema, extensions).await?;
+            ret.filter(expr)
+        } else {
+            Ok(ret)
+        }
+    }
+
     pub async fn from_substrait_read(
         ctx: &mut FlownodeContext,
         read: &ReadRel,
@@ -226,11 +325,59 @@ impl TypedPlan {
             Some(RelType::Aggregate(agg)) => {
                 Self::from_substrait_agg_rel(ctx, agg, extensions).await
             }
+            Some(RelType::Join(join)) => Self::from_substrait_join(ctx, join, extensions).await,
             _ => not_impl_err!("Unsupported relation type: {:?}", rel.rel_type),
         }
     }
 }
 
+/// Split a predicate into its `AND`-ed parts
+fn split_conjunction(expr: ScalarExpr) -> Vec<ScalarExpr> {
+    match expr {
+        ScalarExpr::CallVariadic {
+            func: VariadicFunc::And,
+            exprs,
+        } => exprs.into_iter().flat_map(split_conjunction).collect(),
+        expr => vec![expr],
+    }
+}
+
+/// If `expr` is an equality between an expression on the left input and one on the right input,
+/// return both sides, with the right one rewritten to refer to the columns of the right input.
+fn as_equi_key(
+    expr: &ScalarExpr,
+    left_arity: usize,
+) -> Result<Option<(ScalarExpr, ScalarExpr)>, Error> {
+    let ScalarExpr::CallBinary {
+        func: BinaryFunc::Eq,
+        expr1,
+        expr2,
+    } = expr
+    else {
+        return Ok(None);
+    };
+    let refs_only = |e: &ScalarExpr, left: bool| {
+        let columns = e.get_all_ref_columns();
+        !columns.is_empty() && columns.iter().all(|c| (*c < left_arity) == left)
+    };
+    let (stream, lookup) = if refs_only(expr1, true) && refs_only(expr2, false) {
+        (expr1, expr2)
+    } else if refs_only(expr2, true) && refs_only(expr1, false) {
+        (expr2, expr1)
+    } else {
+        return Ok(None);
+    };
+
+    let mut lookup = lookup.as_ref().clone();
+    let shift: BTreeMap<usize, usize> = lookup
+        .get_all_ref_columns()
+        .into_iter()
+        .map(|c| (c, c - left_arity))
+        .collect();
+    lookup.permute_map(&shift)?;
+    Ok(Some((stream.as_ref().clone(), lookup)))
+}
+
 #[cfg(test)]
 mod test {
     use datatypes::prelude::ConcreteDataType;
@@ -274,4 +421,44 @@ mod test {
 
         assert_eq!(flow_plan.unwrap(), expected);
     }
+
+    #[tokio::test]
+    async fn test_join() {
+        let engine = create_test_query_engine();
+        let sql = "SELECT numbers_with_ts.number, numbers_with_ts.ts FROM numbers_with_ts LEFT JOIN numbers ON numbers_with_ts.number = numbers.number";
+        let plan = sql_to_substrait(engine.clone(), sql).await;
+
+        let mut ctx = create_test_ctx();
+        let flow_plan = TypedPlan::from_substrait_plan(&mut ctx, &plan)
+            .await
+            .unwrap();
+
+        let mut cur = Some(&flow_plan);
+        while let Some(plan) = cur
+            && !matches!(plan.plan, Plan::Join { .. })
+        {
+            cur = plan.plan.get_first_input_plan();
+        }
+        let Some(Plan::Join {
+            inputs,
+            plan: JoinPlan::Linear(join),
+        }) = cur.map(|p| &p.plan)
+        else {
+            panic!("Expect a join in plan: {flow_plan:?}");
+        };
+
+        assert_eq!(inputs.len(), 2);
+        assert_eq!(join.stage_plans.len(), 1);
+        let stage = &join.stage_plans[0];
+        assert_eq!(stage.kind, StageKind::Lookup);
+        assert_eq!(stage.stream_key.len(), 1);
+        assert_eq!(stage.lookup_key.len(), 1);
+        // columns from the lookup side are nullable after a left join
+        let schema = &cur.unwrap().schema.typ;
+        assert!(
+            schema.column_types[inputs[0].schema.typ.column_types.len()..]
+                .iter()
+                .all(|c| c.nullable)
+        );
+    }
 }
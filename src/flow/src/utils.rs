@@ -22,6 +22,7 @@ use common_meta::key::flow::flow_state::FlowStat;
 use common_telemetry::trace;
 use datatypes::value::Value;
 use get_size2::GetSize;
+use serde::{Deserialize, Serialize};
 use smallvec::{smallvec, SmallVec};
 use tokio::sync::{mpsc, oneshot, RwLock};
 use tokio::time::Instant;
@@ -278,6 +279,70 @@ pub struct Arrangement {
     size_update_interval: tokio::time::Duration,
 }
 
+/// A serializable copy of the state of an [`Arrangement`], used for checkpointing.
+///
+/// Only data is kept, configs like expire duration are set again when the dataflow is rendered.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArrangementSnapshot {
+    spine: Vec<(Timestamp, Vec<(Row, Vec<DiffRow>)>)>,
+    last_compaction_time: Option<Timestamp>,
+    event_ts_to_key: Vec<(Timestamp, Vec<Row>)>,
+}
+
+impl Arrangement {
+    /// Take a snapshot of the current state
+    pub fn snapshot(&self) -> ArrangementSnapshot {
+        let spine = self
+            .spine
+            .iter()
+            .map(|(ts, batch)| {
+                let batch = batch
+                    .iter()
+                    .map(|(key, updates)| (key.clone(), updates.to_vec()))
+                    .collect();
+                (*ts, batch)
+            })
+            .collect();
+        let event_ts_to_key = self
+            .expire_state
+            .iter()
+            .flat_map(|s| s.event_ts_to_key.iter())
+            .map(|(ts, keys)| (*ts, keys.iter().cloned().collect()))
+            .collect();
+        ArrangementSnapshot {
+            spine,
+            last_compaction_time: self.last_compaction_time,
+            event_ts_to_key,
+        }
+    }
+
+    /// Replace the current state with the snapshot
+    pub fn restore(&mut self, snapshot: ArrangementSnapshot) {
+        self.spine = snapshot
+            .spine
+            .into_iter()
+            .map(|(ts, batch)| {
+                let batch = batch
+                    .into_iter()
+                    .map(|(key, updates)| (key, SmallVec::from_vec(updates)))
+                    .collect();
+                (ts, batch)
+            })
+            .collect();
+        self.last_compaction_time = snapshot.last_compaction_time;
+        if let Some(s) = &mut self.expire_state {
+            s.event_ts_to_key = snapshot
+                .event_ts_to_key
+                .into_iter()
+                .map(|(ts, keys)| (ts, keys.into_iter().collect()))
+                .collect();
+        }
+        self.is_written = self.is_written || !self.spine.is_empty();
+        self.estimated_size = self.compute_size();
+        self.last_size_update = Instant::now();
+    }
+}
+
 impl Arrangement {
     fn compute_size(&self) -> usize {
         self.spine
@@ -626,6 +691,22 @@ impl Arrangement {
         }
         final_val
     }
+
+    /// Get current state of all keys that start with `prefix`.
+    ///
+    /// Only read the consolidated state at last compaction time, so it's only meaningful
+    /// for full arrangement right after `compact_to`.
+    /// Useful for join operator to find all rows with the same join key.
+    pub fn get_by_key_prefix(&self, prefix: &Row) -> Vec<(Row, DiffRow)> {
+        let Some(batch) = self.last_compaction_time.and_then(|ts| self.spine.get(&ts)) else {
+            return vec![];
+        };
+        batch
+            .range(prefix.clone()..)
+            .take_while(|(key, _)| key.inner.starts_with(&prefix.inner))
+            .filter_map(|(key, updates)| updates.first().map(|u| (key.clone(), u.clone())))
+            .collect()
+    }
 }
 
 fn compact_diff_row(old_row: Option<DiffRow>, new_row: &DiffRow) -> Option<DiffRow> {
@@ -1041,4 +1122,55 @@ mod test {
             Some((lit("y"), 1, 1)) /* fast path */
         );
     }
+
+    #[test]
+    fn test_get_by_key_prefix() {
+        let mut arr = Arrangement::default();
+        arr.full_arrangement = true;
+        let key = |k: i64, v: &str| Row::new(vec![k.into(), v.into()]);
+        let updates = vec![
+            (kv(key(1, "a"), Row::empty()), 1, 1),
+            (kv(key(1, "b"), Row::empty()), 1, 2),
+            (kv(key(2, "a"), Row::empty()), 1, 1),
+            (kv(key(1, "a"), Row::empty()), 2, -1),
+        ];
+        arr.apply_updates(0, updates).unwrap();
+        assert!(arr.get_by_key_prefix(&lit(1i64)).is_empty());
+
+        arr.compact_to(2).unwrap();
+        assert_eq!(
+            arr.get_by_key_prefix(&lit(1i64)),
+            vec![(key(1, "b"), (Row::empty(), 1, 2))]
+        );
+        assert_eq!(arr.get_by_key_prefix(&Row::empty()).len(), 2);
+        assert!(arr.get_by_key_prefix(&lit(3i64)).is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let mut arr = Arrangement::default();
+        arr.full_arrangement = true;
+        arr.set_expire_state(KeyExpiryManager::new(Some(10), Some(ScalarExpr::Column(0))));
+        let updates = vec![
+            (kv(lit(1i64), lit("x")), 1, 1),
+            (kv(lit(5i64), lit("y")), 5, 1),
+            (kv(lit(5i64), lit("y")), 7, -1),
+        ];
+        arr.apply_updates(5, updates).unwrap();
+        arr.compact_to(5).unwrap();
+
+        let snapshot = arr.snapshot();
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let snapshot: ArrangementSnapshot = serde_json::from_str(&json).unwrap();
+
+        let mut restored = Arrangement::default();
+        restored.full_arrangement = true;
+        restored.set_expire_state(KeyExpiryManager::new(Some(10), Some(ScalarExpr::Column(0))));
+        restored.restore(snapshot);
+        assert_eq!(restored.spine, arr.spine);
+        assert_eq!(restored.last_compaction_time(), Some(5));
+        assert_eq!(restored.expire_state, arr.expire_state);
+        assert_eq!(restored.get(5, &lit(5i64)), Some((lit("y"), 5, 1)));
+        assert_eq!(restored.get(7, &lit(5i64)), None);
+    }
 }
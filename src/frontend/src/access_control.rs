@@ -0,0 +1,1191 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Role-based access control for users created by `CREATE USER`.
+//!
+//! Users from other user providers, and the default user when authentication is
+//! disabled, are not restricted.
+
+use std::any::Any;
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use api::v1::ddl_request::Expr as DdlExpr;
+use api::v1::greptime_request::Request;
+use api::v1::query_request::Query;
+use async_trait::async_trait;
+use auth::error::{
+    AccessDeniedSnafu, AuthBackendSnafu, IllegalParamSnafu, InternalStateSnafu,
+    UnsupportedPasswordTypeSnafu, UserPasswordMismatchSnafu,
+};
+use auth::{
+    auth_mysql_with_hash, mysql_password_hash, Identity, Password, PermissionChecker,
+    PermissionReq, PermissionResp, PermissionScope, UserInfo, UserInfoRef, UserProvider,
+    UserProviderRef,
+};
+use catalog::information_schema::PROCESS_LIST;
+use common_base::secrets::ExposeSecret;
+use common_catalog::consts::INFORMATION_SCHEMA_NAME;
+use common_error::ext::BoxedError;
+use common_meta::key::access_control::{
+    AccessControlManagerRef, AccessControlSnapshot, GrantObject, Privilege, UserValue,
+};
+use common_telemetry::warn;
+use promql_parser::label::{MatchOp, Matchers, METRIC_NAME};
+use promql_parser::parser::{
+    AggregateExpr, BinaryExpr, Call, Expr as PromqlExpr, MatrixSelector, ParenExpr, SubqueryExpr,
+    UnaryExpr, VectorSelector,
+};
+use servers::prom_store::{DATABASE_LABEL, SCHEMA_LABEL};
+use snafu::{ensure, ResultExt};
+use sql::ast::ObjectNamePartExt;
+use sql::statements::copy::{Copy, CopyDatabase, CopyTable};
+use sql::statements::create::SqlOrTql;
+use sql::statements::statement::Statement;
+use sql::statements::tql::Tql;
+use sqlparser::ast::{
+    FromTable, ObjectName, Query as SqlQuery, Statement as SqlStatement, TableFactor, Visit,
+    Visitor,
+};
+
+/// How long the permission checker trusts the cached users and roles.
+const SNAPSHOT_TTL: Duration = Duration::from_secs(10);
+
+/// The [UserInfo] of users authenticated by the [AccessControlUserProvider].
+#[derive(Debug)]
+pub struct AccessControlUserInfo {
+    username: String,
+}
+
+impl UserInfo for AccessControlUserInfo {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn username(&self) -> &str {
+        &self.username
+    }
+}
+
+/// A [UserProvider] that authenticates users created by `CREATE USER` and
+/// delegates other users to the `inner` provider.
+pub struct AccessControlUserProvider {
+    inner: UserProviderRef,
+    manager: AccessControlManagerRef,
+}
+
+impl AccessControlUserProvider {
+    pub fn new(inner: UserProviderRef, manager: AccessControlManagerRef) -> Self {
+        Self { inner, manager }
+    }
+}
+
+#[async_trait]
+impl UserProvider for AccessControlUserProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn authenticate(
+        &self,
+        id: Identity<'_>,
+        password: Password<'_>,
+    ) -> auth::error::Result<UserInfoRef> {
+        let Identity::UserId(username, _) = id;
+        let user = self
+            .manager
+            .user(username)
+            .await
+            .map_err(BoxedError::new)
+            .context(AuthBackendSnafu)?;
+        let Some(user) = user else {
+            return self.inner.authenticate(id, password).await;
+        };
+        verify_password(username, &user, password)?;
+
+        // The permission checker can't load the user by itself.
+        if self.manager.snapshot().user(username).is_none() {
+            self.manager
+                .refresh()
+                .await
+                .map_err(BoxedError::new)
+                .context(AuthBackendSnafu)?;
+        }
+        Ok(Arc::new(AccessControlUserInfo {
+            username: username.to_string(),
+        }))
+    }
+
+    async fn authorize(
+        &self,
+        catalog: &str,
+        schema: &str,
+        user_info: &UserInfoRef,
+    ) -> auth::error::Result<()> {
+        if user_info
+            .as_any()
+            .downcast_ref::<AccessControlUserInfo>()
+            .is_none()
+        {
+            return self.inner.authorize(catalog, schema, user_info).await;
+        }
+
+        let snapshot = self.manager.snapshot();
+        let allowed = snapshot.user(user_info.username()).is_some_and(|user| {
+            schema == INFORMATION_SCHEMA_NAME || snapshot.has_any_in_database(user, catalog, schema)
+        });
+        ensure!(
+            allowed,
+            AccessDeniedSnafu {
+                catalog,
+                schema,
+                username: user_info.username(),
+            }
+        );
+        Ok(())
+    }
+
+    fn external(&self) -> bool {
+        self.inner.external()
+    }
+}
+
+fn verify_password(
+    username: &str,
+    user: &UserValue,
+    password: Password<'_>,
+) -> auth::error::Result<()> {
+    let hash = user
+        .password_hash
+        .as_deref()
+        .map(hex::decode)
+        .transpose()
+        .map_err(|_| {
+            InternalStateSnafu {
+                msg: format!("Invalid password hash of user {username}"),
+            }
+            .build()
+        })?;
+
+    let matched = match (password, hash) {
+        (Password::PlainText(pwd), hash) => {
+            let pwd = pwd.expose_secret();
+            match hash {
+                Some(hash) => {
+                    ensure!(
+                        !pwd.is_empty(),
+                        IllegalParamSnafu {
+                            msg: "blank password"
+                        }
+                    );
+                    mysql_password_hash(pwd.as_bytes()) == hash
+                }
+                None => pwd.is_empty(),
+            }
+        }
+        (Password::MysqlNativePassword(auth_data, salt), Some(hash)) => {
+            return auth_mysql_with_hash(auth_data, salt, username, &hash);
+        }
+        (Password::MysqlNativePassword(auth_data, _), None) => auth_data.is_empty(),
+        (Password::PgMD5(_, _), _) => {
+            return UnsupportedPasswordTypeSnafu {
+                password_type: "pg_md5",
+            }
+            .fail();
+        }
+    };
+    ensure!(matched, UserPasswordMismatchSnafu { username });
+    Ok(())
+}
+
+/// A [PermissionChecker] that enforces the privileges granted to users created by
+/// `CREATE USER`.
+///
+/// Checks are made against the cached users and roles, which are reloaded in the
+/// background once they are older than [SNAPSHOT_TTL].
+pub struct AccessControlPermissionChecker {
+    manager: AccessControlManagerRef,
+    refreshing: Arc<AtomicBool>,
+}
+
+impl AccessControlPermissionChecker {
+    pub fn new(manager: AccessControlManagerRef) -> Self {
+        Self {
+            manager,
+            refreshing: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn refresh_if_stale(&self) {
+        if !self.manager.is_stale(SNAPSHOT_TTL) || self.refreshing.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let manager = self.manager.clone();
+        let refreshing = self.refreshing.clone();
+        let _ = common_runtime::spawn_global(async move {
+            if let Err(e) = manager.refresh().await {
+                warn!(e; "Failed to refresh users and roles");
+            }
+            refreshing.store(false, Ordering::Release);
+        });
+    }
+}
+
+impl PermissionChecker for AccessControlPermissionChecker {
+    fn check_permission(
+        &self,
+        user_info: UserInfoRef,
+        req: PermissionReq,
+        scope: PermissionScope,
+    ) -> auth::error::Result<PermissionResp> {
+        self.refresh_if_stale();
+
+        let snapshot = self.manager.snapshot();
+        let user = snapshot.user(user_info.username());
+        if user_info
+            .as_any()
+            .downcast_ref::<AccessControlUserInfo>()
+            .is_none()
+        {
+            // Users created by `CREATE USER` are only authenticated by the
+            // [AccessControlUserProvider], other user infos of them are denied.
+            return if user.is_some() {
+                Ok(PermissionResp::Reject)
+            } else {
+                Ok(PermissionResp::Allow)
+            };
+        }
+        // The user may be dropped after login.
+        let Some(user) = user else {
+            return Ok(PermissionResp::Reject);
+        };
+        if required_privileges(&req, scope).is_satisfied(&snapshot, user) {
+            Ok(PermissionResp::Allow)
+        } else {
+            Ok(PermissionResp::Reject)
+        }
+    }
+}
+
+/// Privileges required by a [PermissionReq].
+#[derive(Debug, PartialEq, Eq)]
+enum Required {
+    /// No privilege is required.
+    Nothing,
+    /// Any privilege on an object in the database.
+    AnyInDatabase { catalog: String, schema: String },
+    /// All the privileges.
+    Privileges(Vec<(Privilege, GrantObject)>),
+    /// All privileges on all databases.
+    Admin,
+}
+
+impl Required {
+    fn is_satisfied(&self, snapshot: &AccessControlSnapshot, user: &UserValue) -> bool {
+        match self {
+            Required::Nothing => true,
+            Required::AnyInDatabase { catalog, schema } => {
+                schema == INFORMATION_SCHEMA_NAME
+                    || snapshot.has_any_in_database(user, catalog, schema)
+            }
+            Required::Privileges(privileges) => privileges.iter().all(|(privilege, object)| {
+                is_information_schema(object) || snapshot.is_granted(user, *privilege, object)
+            }),
+            Required::Admin => snapshot.is_admin(user),
+        }
+    }
+}
+
+fn is_information_schema(object: &GrantObject) -> bool {
+    match object {
+        GrantObject::Global => false,
+        GrantObject::Database { schema, .. } => schema == INFORMATION_SCHEMA_NAME,
+        // The process list exposes the queries of all users.
+        GrantObject::Table { schema, table, .. } => {
+            schema == INFORMATION_SCHEMA_NAME && !table.eq_ignore_ascii_case(PROCESS_LIST)
+        }
+    }
+}
+
+fn required_privileges(req: &PermissionReq, scope: PermissionScope) -> Required {
+    let database = || database_object(scope.catalog, scope.schema);
+    match req {
+        PermissionReq::SqlStatement(stmt) => statement_privileges(stmt, scope),
+        PermissionReq::GrpcRequest(request) => grpc_privileges(request, scope),
+        PermissionReq::PromQuery(query) => Required::Privileges(promql_privileges(query, scope)),
+        PermissionReq::LogicalPlan { scans, insert_into } => {
+            let table = |name: &api::v1::TableName| GrantObject::Table {
+                catalog: non_empty_or(&name.catalog_name, scope.catalog).to_string(),
+                schema: non_empty_or(&name.schema_name, scope.schema).to_string(),
+                table: name.table_name.clone(),
+            };
+            let mut privileges = scans
+                .iter()
+                .map(|name| (Privilege::Select, table(name)))
+                .collect::<Vec<_>>();
+            if let Some(name) = insert_into {
+                privileges.push((Privilege::Insert, table(name)));
+            }
+            Required::Privileges(privileges)
+        }
+        PermissionReq::LogQuery | PermissionReq::PromStoreRead => {
+            Required::Privileges(vec![(Privilege::Select, database())])
+        }
+        PermissionReq::Opentsdb
+        | PermissionReq::LineProtocol
+        | PermissionReq::PromStoreWrite
+        | PermissionReq::Otlp
+        | PermissionReq::LogWrite
+        | PermissionReq::BulkInsert => Required::Privileges(vec![(Privilege::Insert, database())]),
+    }
+}
+
+fn grpc_privileges(request: &Request, scope: PermissionScope) -> Required {
+    let table = |table: &str| GrantObject::Table {
+        catalog: scope.catalog.to_string(),
+        schema: scope.schema.to_string(),
+        table: table.to_string(),
+    };
+    let table_name = |name: &api::v1::TableName| GrantObject::Table {
+        catalog: non_empty_or(&name.catalog_name, scope.catalog).to_string(),
+        schema: non_empty_or(&name.schema_name, scope.schema).to_string(),
+        table: name.table_name.clone(),
+    };
+    let database = |catalog: &str, schema: &str| {
+        database_object(
+            non_empty_or(catalog, scope.catalog),
+            non_empty_or(schema, scope.schema),
+        )
+    };
+
+    let privileges = match request {
+        Request::Inserts(requests) => requests
+            .inserts
+            .iter()
+            .map(|request| (Privilege::Insert, table(&request.table_name)))
+            .collect(),
+        Request::RowInserts(requests) => requests
+            .inserts
+            .iter()
+            .map(|request| (Privilege::Insert, table(&request.table_name)))
+            .collect(),
+        Request::Deletes(requests) => requests
+            .deletes
+            .iter()
+            .map(|request| (Privilege::Delete, table(&request.table_name)))
+            .collect(),
+        Request::RowDeletes(requests) => requests
+            .deletes
+            .iter()
+            .map(|request| (Privilege::Delete, table(&request.table_name)))
+            .collect(),
+        Request::Query(request) => match &request.query {
+            // SQL is checked statement by statement later, and plans are checked
+            // table by table once they are decoded.
+            Some(Query::Sql(_))
+            | Some(Query::LogicalPlan(_))
+            | Some(Query::InsertIntoPlan(_))
+            | None => return Required::Nothing,
+            Some(Query::PromRangeQuery(query)) => promql_privileges(&query.query, scope),
+        },
+        Request::Ddl(request) => match &request.expr {
+            Some(DdlExpr::CreateDatabase(expr)) => {
+                vec![(
+                    Privilege::Create,
+                    database(&expr.catalog_name, &expr.schema_name),
+                )]
+            }
+            Some(DdlExpr::AlterDatabase(expr)) => {
+                vec![(
+                    Privilege::Alter,
+                    database(&expr.catalog_name, &expr.schema_name),
+                )]
+            }
+            Some(DdlExpr::CreateTable(expr)) => {
+                vec![(
+                    Privilege::Create,
+                    database(&expr.catalog_name, &expr.schema_name),
+                )]
+            }
+            Some(DdlExpr::AlterTable(expr)) => {
+                vec![(
+                    Privilege::Alter,
+                    database(&expr.catalog_name, &expr.schema_name),
+                )]
+            }
+            Some(DdlExpr::DropTable(expr)) => {
+                vec![(
+                    Privilege::Drop,
+                    database(&expr.catalog_name, &expr.schema_name),
+                )]
+            }
+            Some(DdlExpr::TruncateTable(expr)) => {
+                vec![(
+                    Privilege::Delete,
+                    database(&expr.catalog_name, &expr.schema_name),
+                )]
+            }
+            Some(DdlExpr::CreateView(expr)) => {
+                vec![(
+                    Privilege::Create,
+                    database(&expr.catalog_name, &expr.schema_name),
+                )]
+            }
+            Some(DdlExpr::DropView(expr)) => {
+                vec![(
+                    Privilege::Drop,
+                    database(&expr.catalog_name, &expr.schema_name),
+                )]
+            }
+            Some(DdlExpr::CreateFlow(expr)) => {
+                let mut privileges = expr
+                    .source_table_names
+                    .iter()
+                    .map(|name| (Privilege::Select, table_name(name)))
+                    .collect::<Vec<_>>();
+                let sink = match &expr.sink_table_name {
+                    Some(name) => table_name(name),
+                    None => database(&expr.catalog_name, ""),
+                };
+                privileges.push((Privilege::Create, sink));
+                privileges
+            }
+            Some(DdlExpr::DropFlow(_)) => vec![(Privilege::Drop, database("", ""))],
+            None => return Required::Nothing,
+        },
+    };
+    Required::Privileges(privileges)
+}
+
+fn statement_privileges(stmt: &Statement, scope: PermissionScope) -> Required {
+    let table = |name: &ObjectName| table_object(name, scope);
+    let database = |name: &ObjectName| database_name_object(name, scope);
+    let current_database = || database_object(scope.catalog, scope.schema);
+    let any_in = |database: Option<&String>| Required::AnyInDatabase {
+        catalog: scope.catalog.to_string(),
+        schema: database
+            .map(String::as_str)
+            .unwrap_or(scope.schema)
+            .to_string(),
+    };
+
+    let privileges = match stmt {
+        Statement::Query(_)
+        | Statement::Explain(_)
+        | Statement::DeclareCursor(_)
+        | Statement::Copy(Copy::CopyQueryTo(_)) => read_privileges(stmt, None, scope),
+        Statement::Tql(tql) => promql_privileges(tql_query(tql), scope),
+        Statement::Insert(insert) => {
+            let Ok(name) = insert.table_name() else {
+                return Required::Admin;
+            };
+            let mut privileges = read_privileges(stmt, Some(name), scope);
+            privileges.push((Privilege::Insert, table(name)));
+            privileges
+        }
+        Statement::Update(update) => {
+            // Rows to update are read from the target table.
+            let name = update.table_name();
+            let mut privileges = read_privileges(stmt, Some(name), scope);
+            privileges.push((Privilege::Select, table(name)));
+            privileges.push((Privilege::Insert, table(name)));
+            privileges
+        }
+        Statement::Delete(delete) => {
+            let Some(name) = delete_table_name(&delete.inner) else {
+                return Required::Admin;
+            };
+            let mut privileges = read_privileges(&delete.inner, Some(name), scope);
+            privileges.push((Privilege::Delete, table(name)));
+            privileges
+        }
+        Statement::TruncateTable(stmt) => vec![(Privilege::Delete, table(stmt.table_name()))],
+
+        Statement::CreateDatabase(stmt) => vec![(Privilege::Create, database(&stmt.name))],
+        Statement::CreateTable(stmt) => vec![(Privilege::Create, table(&stmt.name))],
+        Statement::CreateExternalTable(stmt) => vec![(Privilege::Create, table(&stmt.name))],
+        Statement::CreateTableLike(stmt) => vec![
+            (Privilege::Create, table(&stmt.table_name)),
+            (Privilege::Select, table(&stmt.source_name)),
+        ],
+        Statement::CreateView(stmt) => {
+            let mut privileges = read_privileges(stmt.query.as_ref(), None, scope);
+            privileges.push((Privilege::Create, table(&stmt.name)));
+            privileges
+        }
+        Statement::CreateFlow(stmt) => {
+            let mut privileges = match stmt.query.as_ref() {
+                SqlOrTql::Sql(..) => read_privileges(stmt.query.as_ref(), None, scope),
+                SqlOrTql::Tql(tql, _) => promql_privileges(tql_query(tql), scope),
+            };
+            privileges.push((Privilege::Create, table(&stmt.sink_table_name)));
+            privileges
+        }
+        Statement::AlterDatabase(stmt) => vec![(Privilege::Alter, database(stmt.database_name()))],
+        Statement::AlterTable(stmt) => vec![(Privilege::Alter, table(stmt.table_name()))],
+        Statement::DropDatabase(stmt) => vec![(Privilege::Drop, database(stmt.name()))],
+        Statement::DropTable(stmt) => stmt
+            .table_names()
+            .iter()
+            .map(|name| (Privilege::Drop, table(name)))
+            .collect(),
+        Statement::DropView(stmt) => vec![(Privilege::Drop, table(&stmt.view_name))],
+        Statement::DropFlow(_) => vec![(Privilege::Drop, current_database())],
+
+        Statement::Copy(Copy::CopyTable(CopyTable::To(stmt))) => {
+            vec![(Privilege::Select, table(&stmt.table_name))]
+        }
+        Statement::Copy(Copy::CopyTable(CopyTable::From(stmt))) => {
+            vec![(Privilege::Insert, table(&stmt.table_name))]
+        }
+        Statement::Copy(Copy::CopyDatabase(CopyDatabase::To(stmt))) => {
+            vec![(Privilege::Select, database(&stmt.database_name))]
+        }
+        Statement::Copy(Copy::CopyDatabase(CopyDatabase::From(stmt))) => {
+            vec![(Privilege::Insert, database(&stmt.database_name))]
+        }
+        Statement::BackupDatabase(stmt) => {
+            vec![(Privilege::Select, database(&stmt.database_name))]
+        }
+        Statement::RestoreDatabase(stmt) => vec![
+            (Privilege::Create, database(&stmt.database_name)),
+            (Privilege::Insert, database(&stmt.database_name)),
+        ],
+
+        Statement::DescribeTable(stmt) => vec![(Privilege::Select, table(stmt.name()))],
+        Statement::ShowCreateTable(stmt) => vec![(Privilege::Select, table(&stmt.table_name))],
+        Statement::ShowCreateView(stmt) => vec![(Privilege::Select, table(&stmt.view_name))],
+        Statement::ShowCreateFlow(_) => vec![(Privilege::Select, current_database())],
+        Statement::ShowCreateDatabase(stmt) => {
+            return match database(&stmt.database_name) {
+                GrantObject::Database { catalog, schema } => {
+                    Required::AnyInDatabase { catalog, schema }
+                }
+                _ => Required::Admin,
+            };
+        }
+        Statement::ShowTables(stmt) => return any_in(stmt.database.as_ref()),
+        Statement::ShowTableStatus(stmt) => return any_in(stmt.database.as_ref()),
+        Statement::ShowColumns(stmt) => return any_in(stmt.database.as_ref()),
+        Statement::ShowIndex(stmt) => return any_in(stmt.database.as_ref()),
+        Statement::ShowRegion(stmt) => return any_in(stmt.database.as_ref()),
+        Statement::ShowViews(stmt) => return any_in(stmt.database.as_ref()),
+        Statement::ShowFlows(stmt) => return any_in(stmt.database.as_ref()),
+        Statement::Use(db) => return any_in(Some(db)),
+
+        // Statements in the session, or only about the user itself.
+        Statement::ShowDatabases(_)
+        | Statement::ShowStatus(_)
+        | Statement::ShowSearchPath(_)
+        | Statement::ShowVariables(_)
+        | Statement::ShowCharset(_)
+        | Statement::ShowCollation(_)
+        | Statement::SetVariables(_)
+        | Statement::FetchCursor(_)
+        | Statement::CloseCursor(_) => return Required::Nothing,
+
+        // Processes of all users are visible and can be killed.
+        Statement::ShowProcesslist(_)
+        | Statement::Kill(_)
+        | Statement::Admin(_)
+        | Statement::CreateUser(_)
+        | Statement::DropUser(_)
+        | Statement::CreateRole(_)
+        | Statement::DropRole(_)
+        | Statement::Grant(_)
+        | Statement::Revoke(_) => return Required::Admin,
+        #[cfg(feature = "enterprise")]
+        Statement::CreateTrigger(_)
+        | Statement::DropTrigger(_)
+        | Statement::AlterTrigger(_)
+        | Statement::ShowTriggers(_) => return Required::Admin,
+    };
+    Required::Privileges(privileges)
+}
+
+/// Returns the select privileges on all relations in the `visitable` except the `target`
+/// and the references to CTEs.
+fn read_privileges<'a, V: Visit>(
+    visitable: &V,
+    target: Option<&'a ObjectName>,
+    scope: PermissionScope<'a>,
+) -> Vec<(Privilege, GrantObject)> {
+    let mut visitor = RelationVisitor {
+        target,
+        scope,
+        ctes: vec![],
+        privileges: vec![],
+    };
+    let _ = visitable.visit(&mut visitor);
+    visitor.privileges
+}
+
+/// The CTEs defined by the `WITH` clause of a query.
+#[derive(Default)]
+struct CteScope {
+    names: Vec<String>,
+    /// The queries of the CTEs, to find out which CTEs are visible in them.
+    queries: Vec<*const SqlQuery>,
+    recursive: bool,
+    /// The number of CTEs visible in the part of the query being visited.
+    visible: usize,
+}
+
+struct RelationVisitor<'a> {
+    target: Option<&'a ObjectName>,
+    scope: PermissionScope<'a>,
+    /// The CTEs of the queries being visited, from the outermost one.
+    ctes: Vec<CteScope>,
+    privileges: Vec<(Privilege, GrantObject)>,
+}
+
+impl RelationVisitor<'_> {
+    fn is_cte(&self, relation: &ObjectName) -> bool {
+        let [name] = &relation.0[..] else {
+            return false;
+        };
+        let name = name.to_string_unquoted();
+        self.ctes
+            .iter()
+            .any(|scope| scope.names[..scope.visible].contains(&name))
+    }
+
+    /// Returns the position of the `query` in the CTEs of the innermost query.
+    fn cte_position(&self, query: &SqlQuery) -> Option<usize> {
+        self.ctes
+            .last()?
+            .queries
+            .iter()
+            .position(|cte| std::ptr::eq(*cte, query))
+    }
+}
+
+impl Visitor for RelationVisitor<'_> {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, query: &SqlQuery) -> ControlFlow<Self::Break> {
+        if let Some(i) = self.cte_position(query) {
+            if let Some(scope) = self.ctes.last_mut() {
+                // A CTE only sees the CTEs before it, unless the `WITH` is recursive.
+                scope.visible = if scope.recursive {
+                    scope.names.len()
+                } else {
+                    i
+                };
+            }
+        }
+
+        let scope = match &query.with {
+            Some(with) => CteScope {
+                names: with
+                    .cte_tables
+                    .iter()
+                    .map(|cte| cte.alias.name.value.clone())
+                    .collect(),
+                queries: with
+                    .cte_tables
+                    .iter()
+                    .map(|cte| cte.query.as_ref() as *const SqlQuery)
+                    .collect(),
+                recursive: with.recursive,
+                visible: 0,
+            },
+            None => CteScope::default(),
+        };
+        self.ctes.push(scope);
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_query(&mut self, query: &SqlQuery) -> ControlFlow<Self::Break> {
+        let _ = self.ctes.pop();
+        if let Some(i) = self.cte_position(query) {
+            if let Some(scope) = self.ctes.last_mut() {
+                // The CTE is visible in the following ones and the body.
+                scope.visible = scope.visible.max(i + 1);
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_relation(&mut self, relation: &ObjectName) -> ControlFlow<Self::Break> {
+        if self.target != Some(relation) && !self.is_cte(relation) {
+            self.privileges
+                .push((Privilege::Select, table_object(relation, self.scope)));
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// Returns the table a `DELETE` statement deletes from.
+fn delete_table_name(stmt: &SqlStatement) -> Option<&ObjectName> {
+    let SqlStatement::Delete(delete) = stmt else {
+        return None;
+    };
+    let (FromTable::WithFromKeyword(tables) | FromTable::WithoutKeyword(tables)) = &delete.from;
+    match &tables.first()?.relation {
+        TableFactor::Table { name, .. } => Some(name),
+        _ => None,
+    }
+}
+
+fn tql_query(tql: &Tql) -> &str {
+    match tql {
+        Tql::Eval(eval) => &eval.query,
+        Tql::Explain(explain) => &explain.query,
+        Tql::Analyze(analyze) => &analyze.query,
+    }
+}
+
+/// Returns the select privileges on the tables read by the PromQL `query`.
+///
+/// Selectors read the table of their metric name in the database in the `__schema__`
+/// or `__database__` matcher, or the current database if there is no such matcher.
+/// Selectors without a metric name may read any table in the database.
+fn promql_privileges(query: &str, scope: PermissionScope) -> Vec<(Privilege, GrantObject)> {
+    let Ok(expr) = promql_parser::parser::parse(query) else {
+        // The query is rejected on planning anyway.
+        return vec![(
+            Privilege::Select,
+            database_object(scope.catalog, scope.schema),
+        )];
+    };
+    let mut selectors = vec![];
+    collect_promql_selectors(&expr, &mut selectors);
+
+    let mut privileges: Vec<(Privilege, GrantObject)> = vec![];
+    for (schema, metric) in selectors {
+        let schema = schema.as_deref().unwrap_or(scope.schema);
+        let object = match metric {
+            Some(table) => GrantObject::Table {
+                catalog: scope.catalog.to_string(),
+                schema: schema.to_string(),
+                table,
+            },
+            None => database_object(scope.catalog, schema),
+        };
+        if !privileges.iter().any(|(_, existing)| *existing == object) {
+            privileges.push((Privilege::Select, object));
+        }
+    }
+    privileges
+}
+
+/// Collects the schemas and the metric names of all selectors in the `expr`, `None`
+/// for the current schema or an unknown metric.
+fn collect_promql_selectors(
+    expr: &PromqlExpr,
+    selectors: &mut Vec<(Option<String>, Option<String>)>,
+) {
+    match expr {
+        PromqlExpr::Aggregate(AggregateExpr { expr, param, .. }) => {
+            collect_promql_selectors(expr, selectors);
+            if let Some(param) = param {
+                collect_promql_selectors(param, selectors);
+            }
+        }
+        PromqlExpr::Unary(UnaryExpr { expr })
+        | PromqlExpr::Paren(ParenExpr { expr })
+        | PromqlExpr::Subquery(SubqueryExpr { expr, .. }) => {
+            collect_promql_selectors(expr, selectors)
+        }
+        PromqlExpr::Binary(BinaryExpr { lhs, rhs, .. }) => {
+            collect_promql_selectors(lhs, selectors);
+            collect_promql_selectors(rhs, selectors);
+        }
+        PromqlExpr::VectorSelector(selector)
+        | PromqlExpr::MatrixSelector(MatrixSelector { vs: selector, .. }) => selectors.push((
+            selector_schema(&selector.matchers),
+            selector_metric(selector),
+        )),
+        PromqlExpr::Call(Call { args, .. }) => args
+            .args
+            .iter()
+            .for_each(|arg| collect_promql_selectors(arg, selectors)),
+        PromqlExpr::NumberLiteral(_) | PromqlExpr::StringLiteral(_) | PromqlExpr::Extension(_) => {}
+    }
+}
+
+/// Returns the schema a selector reads, the planner takes the last matcher.
+fn selector_schema(matchers: &Matchers) -> Option<String> {
+    matchers
+        .matchers
+        .iter()
+        .filter(|matcher| matcher.name == SCHEMA_LABEL || matcher.name == DATABASE_LABEL)
+        .next_back()
+        .map(|matcher| matcher.value.clone())
+}
+
+/// Returns the metric name of a selector if it's a single table.
+fn selector_metric(selector: &VectorSelector) -> Option<String> {
+    selector.name.clone().or_else(|| {
+        selector
+            .matchers
+            .matchers
+            .iter()
+            .find(|matcher| matcher.name == METRIC_NAME && matcher.op == MatchOp::Equal)
+            .map(|matcher| matcher.value.clone())
+    })
+}
+
+fn non_empty_or<'a>(value: &'a str, default: &'a str) -> &'a str {
+    if value.is_empty() {
+        default
+    } else {
+        value
+    }
+}
+
+fn database_object(catalog: &str, schema: &str) -> GrantObject {
+    GrantObject::Database {
+        catalog: catalog.to_string(),
+        schema: schema.to_string(),
+    }
+}
+
+fn database_name_object(name: &ObjectName, scope: PermissionScope) -> GrantObject {
+    match &name.0[..] {
+        [schema] => database_object(scope.catalog, &schema.to_string_unquoted()),
+        [catalog, schema] => {
+            database_object(&catalog.to_string_unquoted(), &schema.to_string_unquoted())
+        }
+        // Invalid names are rejected unless the user can access everything.
+        _ => GrantObject::Global,
+    }
+}
+
+fn table_object(name: &ObjectName, scope: PermissionScope) -> GrantObject {
+    let (catalog, schema, table) = match &name.0[..] {
+        [table] => (
+            scope.catalog.to_string(),
+            scope.schema.to_string(),
+            table.to_string_unquoted(),
+        ),
+        [schema, table] => (
+            scope.catalog.to_string(),
+            schema.to_string_unquoted(),
+            table.to_string_unquoted(),
+        ),
+        [catalog, schema, table] => (
+            catalog.to_string_unquoted(),
+            schema.to_string_unquoted(),
+            table.to_string_unquoted(),
+        ),
+        _ => return GrantObject::Global,
+    };
+    GrantObject::Table {
+        catalog,
+        schema,
+        table,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_meta::key::access_control::AccessControlManager;
+    use common_meta::kv_backend::memory::MemoryKvBackend;
+    use sql::dialect::GreptimeDbDialect;
+    use sql::parser::{ParseOptions, ParserContext};
+
+    use super::*;
+
+    fn parse(sql: &str) -> Statement {
+        ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+            .unwrap()
+            .remove(0)
+    }
+
+    fn table(schema: &str, table: &str) -> GrantObject {
+        GrantObject::Table {
+            catalog: "greptime".to_string(),
+            schema: schema.to_string(),
+            table: table.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_statement_privileges() {
+        let scope = PermissionScope::new("greptime", "public");
+        let required = |sql: &str| statement_privileges(&parse(sql), scope);
+
+        assert_eq!(
+            Required::Privileges(vec![
+                (Privilege::Select, table("public", "a")),
+                (Privilege::Select, table("test", "b")),
+            ]),
+            required("SELECT * FROM a JOIN test.b ON a.ts = b.ts")
+        );
+        assert_eq!(
+            Required::Privileges(vec![
+                (Privilege::Select, table("public", "b")),
+                (Privilege::Insert, table("public", "a")),
+            ]),
+            required("INSERT INTO a SELECT * FROM b")
+        );
+        assert_eq!(
+            Required::Privileges(vec![(Privilege::Delete, table("public", "a"))]),
+            required("DELETE FROM a WHERE ts > 0")
+        );
+        assert_eq!(
+            Required::Privileges(vec![
+                (Privilege::Select, table("test", "b")),
+                (Privilege::Delete, table("public", "a")),
+            ]),
+            required("DELETE FROM a WHERE ts IN (SELECT ts FROM test.b)")
+        );
+        assert_eq!(
+            Required::Privileges(vec![
+                (Privilege::Select, table("public", "a")),
+                (Privilege::Insert, table("public", "a")),
+            ]),
+            required("UPDATE a SET v = 1 WHERE ts > 0")
+        );
+        assert_eq!(
+            Required::Privileges(vec![(Privilege::Drop, table("test", "a"))]),
+            required("DROP TABLE test.a")
+        );
+        assert_eq!(
+            Required::Privileges(vec![(
+                Privilege::Create,
+                database_object("greptime", "test")
+            )]),
+            required("CREATE DATABASE test")
+        );
+        assert_eq!(
+            Required::AnyInDatabase {
+                catalog: "greptime".to_string(),
+                schema: "test".to_string(),
+            },
+            required("SHOW TABLES FROM test")
+        );
+        assert_eq!(Required::Nothing, required("SHOW DATABASES"));
+        assert_eq!(Required::Admin, required("CREATE USER alice"));
+        assert_eq!(Required::Admin, required("GRANT SELECT ON *.* TO alice"));
+        assert_eq!(Required::Admin, required("SHOW PROCESSLIST"));
+        assert_eq!(Required::Admin, required("KILL 'process-123'"));
+    }
+
+    #[test]
+    fn test_cte_privileges() {
+        let scope = PermissionScope::new("greptime", "public");
+        let required = |sql: &str| statement_privileges(&parse(sql), scope);
+
+        assert_eq!(
+            Required::Privileges(vec![(Privilege::Select, table("test", "b"))]),
+            required("WITH a AS (SELECT * FROM test.b) SELECT * FROM a")
+        );
+        // `b` in the first CTE is a table.
+        assert_eq!(
+            Required::Privileges(vec![
+                (Privilege::Select, table("public", "b")),
+                (Privilege::Select, table("public", "c")),
+            ]),
+            required("WITH a AS (SELECT * FROM b), b AS (SELECT * FROM c) SELECT * FROM a, b")
+        );
+        // CTEs are only visible in the query defining them.
+        assert_eq!(
+            Required::Privileges(vec![
+                (Privilege::Select, table("public", "b")),
+                (Privilege::Select, table("public", "a")),
+            ]),
+            required("SELECT * FROM (WITH a AS (SELECT * FROM b) SELECT * FROM a) AS t, a")
+        );
+    }
+
+    #[test]
+    fn test_promql_privileges() {
+        let scope = PermissionScope::new("greptime", "public");
+        let required = |sql: &str| statement_privileges(&parse(sql), scope);
+
+        assert_eq!(
+            Required::Privileges(vec![
+                (Privilege::Select, table("test", "http_requests")),
+                (Privilege::Select, table("public", "http_requests")),
+            ]),
+            required(
+                "TQL EVAL (0, 10, '5s') sum(rate(http_requests{__schema__=\"test\"}[1m])) / http_requests"
+            )
+        );
+        assert_eq!(
+            Required::Privileges(vec![(Privilege::Select, table("test", "http_requests"))]),
+            required("TQL EVAL (0, 10, '5s') {__name__=\"http_requests\", __database__=\"test\"}")
+        );
+        // Selectors without a metric name may read any table.
+        assert_eq!(
+            Required::Privileges(vec![(
+                Privilege::Select,
+                database_object("greptime", "public")
+            )]),
+            required("TQL EVAL (0, 10, '5s') {__name__=~\"http_.*\"}")
+        );
+        assert_eq!(
+            Required::Privileges(vec![
+                (Privilege::Select, table("test", "a")),
+                (Privilege::Select, table("other", "b")),
+            ]),
+            required_privileges(
+                &PermissionReq::PromQuery("a{__schema__=\"test\"} + b{__database__=\"other\"}"),
+                scope
+            )
+        );
+    }
+
+    #[test]
+    fn test_create_flow_privileges() {
+        let scope = PermissionScope::new("greptime", "public");
+        let required = |sql: &str| statement_privileges(&parse(sql), scope);
+
+        assert_eq!(
+            Required::Privileges(vec![
+                (Privilege::Select, table("test", "source")),
+                (Privilege::Create, table("public", "sink")),
+            ]),
+            required("CREATE FLOW f SINK TO sink AS SELECT count(*) FROM test.source")
+        );
+        assert_eq!(
+            Required::Privileges(vec![
+                (Privilege::Select, table("test", "http_requests")),
+                (Privilege::Create, table("public", "sink")),
+            ]),
+            required("CREATE FLOW f SINK TO sink AS TQL EVAL (now() - '1m'::interval, now(), '5s') count(http_requests{__schema__=\"test\"})")
+        );
+    }
+
+    #[test]
+    fn test_logical_plan_privileges() {
+        let scope = PermissionScope::new("greptime", "public");
+        let table_name = |schema: &str, table: &str| api::v1::TableName {
+            catalog_name: String::new(),
+            schema_name: schema.to_string(),
+            table_name: table.to_string(),
+        };
+        let scans = [table_name("", "a"), table_name("test", "b")];
+        let sink = table_name("", "c");
+
+        assert_eq!(
+            Required::Privileges(vec![
+                (Privilege::Select, table("public", "a")),
+                (Privilege::Select, table("test", "b")),
+                (Privilege::Insert, table("public", "c")),
+            ]),
+            required_privileges(
+                &PermissionReq::LogicalPlan {
+                    scans: &scans,
+                    insert_into: Some(&sink),
+                },
+                scope
+            )
+        );
+        let request = Request::Query(api::v1::QueryRequest {
+            query: Some(Query::LogicalPlan(vec![])),
+        });
+        assert_eq!(Required::Nothing, grpc_privileges(&request, scope));
+        let request = Request::Query(api::v1::QueryRequest {
+            query: Some(Query::PromRangeQuery(api::v1::PromRangeQuery {
+                query: "http_requests".to_string(),
+                ..Default::default()
+            })),
+        });
+        assert_eq!(
+            Required::Privileges(vec![(Privilege::Select, table("public", "http_requests"))]),
+            grpc_privileges(&request, scope)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_permission_checker() {
+        let manager = Arc::new(AccessControlManager::new(Arc::new(
+            MemoryKvBackend::default(),
+        )));
+        manager.create_user("alice", None, false).await.unwrap();
+        manager
+            .grant_privileges(
+                "alice",
+                database_object("greptime", "public"),
+                &[Privilege::Select],
+            )
+            .await
+            .unwrap();
+        let checker = AccessControlPermissionChecker::new(manager.clone());
+        let scope = PermissionScope::new("greptime", "public");
+        let alice: UserInfoRef = Arc::new(AccessControlUserInfo {
+            username: "alice".to_string(),
+        });
+        let check = |user: UserInfoRef, sql: &str| {
+            let stmt = parse(sql);
+            checker
+                .check_permission(user, PermissionReq::SqlStatement(&stmt), scope)
+                .unwrap()
+        };
+
+        assert!(matches!(
+            check(alice.clone(), "SELECT * FROM monitor"),
+            PermissionResp::Allow
+        ));
+        assert!(matches!(
+            check(alice.clone(), "SELECT * FROM test.monitor"),
+            PermissionResp::Reject
+        ));
+        assert!(matches!(
+            check(alice.clone(), "INSERT INTO monitor VALUES (1)"),
+            PermissionResp::Reject
+        ));
+        assert!(matches!(
+            check(alice.clone(), "SELECT * FROM information_schema.tables"),
+            PermissionResp::Allow
+        ));
+        assert!(matches!(
+            check(
+                alice.clone(),
+                "SELECT * FROM information_schema.process_list"
+            ),
+            PermissionResp::Reject
+        ));
+        // Other users are not restricted.
+        assert!(matches!(
+            check(auth::userinfo_by_name(None), "DROP TABLE test.monitor"),
+            PermissionResp::Allow
+        ));
+        // Users created by `CREATE USER` must be authenticated by the provider.
+        assert!(matches!(
+            check(
+                auth::userinfo_by_name(Some("alice".to_string())),
+                "SELECT * FROM monitor"
+            ),
+            PermissionResp::Reject
+        ));
+
+        manager.drop_user("alice", false).await.unwrap();
+        assert!(matches!(
+            check(alice, "SELECT * FROM monitor"),
+            PermissionResp::Reject
+        ));
+    }
+
+    #[test]
+    fn test_verify_password() {
+        let user = UserValue {
+            password_hash: Some(hex::encode(mysql_password_hash(b"secret"))),
+            ..Default::default()
+        };
+        let secret = "secret".to_string().into();
+        assert!(verify_password("alice", &user, Password::PlainText(secret)).is_ok());
+        let wrong = "wrong".to_string().into();
+        assert!(verify_password("alice", &user, Password::PlainText(wrong)).is_err());
+
+        let user = UserValue::default();
+        assert!(verify_password("alice", &user, Password::PlainText(String::new().into())).is_ok());
+    }
+}
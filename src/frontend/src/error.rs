@@ -321,6 +321,13 @@ pub enum Error {
         name: String,
     },
 
+    #[snafu(display("Failed to load Prometheus rules"))]
+    LoadPromRules {
+        #[snafu(implicit)]
+        location: Location,
+        source: servers::error::Error,
+    },
+
     #[snafu(display("Invalid tls config"))]
     InvalidTlsConfig {
         #[snafu(source)]
@@ -410,6 +417,7 @@ impl ErrorExt for Error {
 
             Error::SqlExecIntercepted { source, .. } => source.status_code(),
             Error::StartServer { source, .. } => source.status_code(),
+            Error::LoadPromRules { source, .. } => source.status_code(),
             Error::ShutdownServer { source, .. } => source.status_code(),
 
             Error::ParseSql { source, .. } => source.status_code(),
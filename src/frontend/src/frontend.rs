@@ -27,14 +27,17 @@ use servers::export_metrics::{ExportMetricsOption, ExportMetricsTask};
 use servers::grpc::GrpcOptions;
 use servers::heartbeat_options::HeartbeatOptions;
 use servers::http::HttpOptions;
+use servers::prom_rule::RuleOptions;
 use servers::server::ServerHandlers;
 use snafu::ResultExt;
 
 use crate::error;
 use crate::error::Result;
 use crate::heartbeat::HeartbeatTask;
+use crate::instance::prom_rule::KvRuleLeaderElection;
 use crate::instance::prom_store::ExportMetricHandler;
 use crate::instance::Instance;
+use crate::promql_cache::PromqlCacheOptions;
 use crate::service_config::{
     InfluxdbOptions, JaegerOptions, MysqlOptions, OpentsdbOptions, OtlpOptions, PostgresOptions,
     PromStoreOptions,
@@ -56,6 +59,10 @@ pub struct FrontendOptions {
     pub opentsdb: OpentsdbOptions,
     pub influxdb: InfluxdbOptions,
     pub prom_store: PromStoreOptions,
+    /// The Prometheus recording and alerting rules options.
+    pub rule: RuleOptions,
+    /// The result cache options of PromQL range queries.
+    pub promql_cache: PromqlCacheOptions,
     pub jaeger: JaegerOptions,
     pub otlp: OtlpOptions,
     pub meta_client: Option<MetaClientOptions>,
@@ -87,6 +94,8 @@ impl Default for FrontendOptions {
             influxdb: InfluxdbOptions::default(),
             jaeger: JaegerOptions::default(),
             prom_store: PromStoreOptions::default(),
+            rule: RuleOptions::default(),
+            promql_cache: PromqlCacheOptions::default(),
             otlp: OtlpOptions::default(),
             meta_client: None,
             logging: LoggingOptions::default(),
@@ -124,6 +133,22 @@ impl Frontend {
             t.start().await?;
         }
 
+        if let Some(manager) = self.instance.rule_manager() {
+            let handler = ExportMetricHandler::new_handler(
+                self.instance.inserter().clone(),
+                self.instance.statement_executor().clone(),
+            );
+            let election = Arc::new(KvRuleLeaderElection::new(
+                self.instance.table_metadata_manager().kv_backend().clone(),
+            ));
+            manager.start(
+                self.instance.clone(),
+                handler,
+                self.instance.clone(),
+                election,
+            );
+        }
+
         if let Some(t) = self.export_metrics_task.as_ref() {
             if t.send_by_handler {
                 let inserter = self.instance.inserter().clone();
@@ -142,6 +167,10 @@ impl Frontend {
     }
 
     pub async fn shutdown(&mut self) -> Result<()> {
+        if let Some(manager) = self.instance.rule_manager() {
+            manager.stop();
+        }
+
         self.servers
             .shutdown_all()
             .await
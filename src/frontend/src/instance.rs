@@ -20,6 +20,8 @@ mod log_handler;
 mod logs;
 mod opentsdb;
 mod otlp;
+mod postgres_copy;
+pub mod prom_rule;
 pub mod prom_store;
 mod promql;
 mod region_query;
@@ -31,7 +33,7 @@ use std::time::{Duration, SystemTime};
 
 use async_stream::stream;
 use async_trait::async_trait;
-use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq};
+use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq, PermissionScope};
 use catalog::process_manager::{
     ProcessManagerRef, QueryStatement as CatalogQueryStatement, SlowQueryTimer,
 };
@@ -77,12 +79,11 @@ use query::query_engine::DescribeResult;
 use query::QueryEngineRef;
 use servers::error::{
     self as server_error, AuthSnafu, CommonMetaSnafu, ExecuteQuerySnafu,
-    OtlpMetricModeIncompatibleSnafu, ParsePromQLSnafu, UnexpectedResultSnafu,
-};
-use servers::interceptor::{
-    PromQueryInterceptor, PromQueryInterceptorRef, SqlQueryInterceptor, SqlQueryInterceptorRef,
+    OtlpMetricModeIncompatibleSnafu, ParsePromQLSnafu,
 };
+use servers::interceptor::{SqlQueryInterceptor, SqlQueryInterceptorRef};
 use servers::otlp::metrics::legacy_normalize_otlp_name;
+use servers::prom_rule::RuleManagerRef;
 use servers::prometheus_handler::PrometheusHandler;
 use servers::query_handler::sql::SqlQueryHandler;
 use session::context::{Channel, QueryContextRef};
@@ -91,6 +92,7 @@ use snafu::prelude::*;
 use sql::ast::ObjectNamePartExt;
 use sql::dialect::Dialect;
 use sql::parser::{ParseOptions, ParserContext};
+use sql::statements::access_control::{Grant, Grantable, PrivilegeLevel, Revoke};
 use sql::statements::copy::{CopyDatabase, CopyTable};
 use sql::statements::statement::Statement;
 use sql::statements::tql::Tql;
@@ -104,6 +106,7 @@ use crate::error::{
     StatementTimeoutSnafu, TableOperationSnafu,
 };
 use crate::limiter::LimiterRef;
+use crate::promql_cache::PromqlResultCacheRef;
 use crate::stream_wrapper::CancellableStreamWrapper;
 
 lazy_static! {
@@ -127,6 +130,8 @@ pub struct Instance {
     limiter: Option<LimiterRef>,
     process_manager: ProcessManagerRef,
     slow_query_options: SlowQueryOptions,
+    rule_manager: Option<RuleManagerRef>,
+    promql_cache: Option<PromqlResultCacheRef>,
 
     // cache for otlp metrics
     // first layer key: db-string
@@ -198,6 +203,10 @@ impl Instance {
         &self.process_manager
     }
 
+    pub fn rule_manager(&self) -> Option<&RuleManagerRef> {
+        self.rule_manager.as_ref()
+    }
+
     pub fn node_manager(&self) -> &NodeManagerRef {
         self.inserter.node_manager()
     }
@@ -560,6 +569,10 @@ impl SqlQueryHandler for Instance {
                         .check_permission(
                             query_ctx.current_user(),
                             PermissionReq::SqlStatement(&stmt),
+                            PermissionScope::new(
+                                query_ctx.current_catalog(),
+                                &query_ctx.current_schema(),
+                            ),
                         )
                         .context(PermissionSnafu)
                     {
@@ -680,7 +693,11 @@ impl SqlQueryHandler for Instance {
             self.plugins
                 .get::<PermissionCheckerRef>()
                 .as_ref()
-                .check_permission(query_ctx.current_user(), PermissionReq::SqlStatement(&stmt))
+                .check_permission(
+                    query_ctx.current_user(),
+                    PermissionReq::SqlStatement(&stmt),
+                    PermissionScope::new(query_ctx.current_catalog(), &query_ctx.current_schema()),
+                )
                 .context(PermissionSnafu)?;
 
             let plan = self
@@ -728,14 +745,14 @@ impl PrometheusHandler for Instance {
         query: &PromQuery,
         query_ctx: QueryContextRef,
     ) -> server_error::Result<Output> {
-        let interceptor = self
-            .plugins
-            .get::<PromQueryInterceptorRef<server_error::Error>>();
-
         self.plugins
             .get::<PermissionCheckerRef>()
             .as_ref()
-            .check_permission(query_ctx.current_user(), PermissionReq::PromQuery)
+            .check_permission(
+                query_ctx.current_user(),
+                PermissionReq::PromQuery(&query.query),
+                PermissionScope::new(query_ctx.current_catalog(), &query_ctx.current_schema()),
+            )
             .context(AuthSnafu)?;
 
         let stmt = QueryLanguageParser::parse_promql(query, &query_ctx).with_context(|_| {
@@ -744,70 +761,17 @@ impl PrometheusHandler for Instance {
             }
         })?;
 
-        let plan = self
-            .statement_executor
-            .plan(&stmt, query_ctx.clone())
-            .await
-            .map_err(BoxedError::new)
-            .context(ExecuteQuerySnafu)?;
-
-        interceptor.pre_execute(query, Some(&plan), query_ctx.clone())?;
-
-        // Take the EvalStmt from the original QueryStatement and use it to create the CatalogQueryStatement.
-        let query_statement = if let QueryStatement::Promql(eval_stmt) = stmt {
-            CatalogQueryStatement::Promql(eval_stmt)
-        } else {
-            // It should not happen since the query is already parsed successfully.
-            return UnexpectedResultSnafu {
-                reason: "The query should always be promql.".to_string(),
+        if let (Some(cache), QueryStatement::Promql(eval_stmt)) = (&self.promql_cache, &stmt) {
+            if let Some(split) =
+                cache.split(query_ctx.get_db_string(), eval_stmt, SystemTime::now())
+            {
+                return self
+                    .do_cached_range_query(query, cache, eval_stmt, split, query_ctx)
+                    .await;
             }
-            .fail();
-        };
-        let query = query_statement.to_string();
-
-        let slow_query_timer = self
-            .slow_query_options
-            .enable
-            .then(|| self.event_recorder.clone())
-            .flatten()
-            .map(|event_recorder| {
-                SlowQueryTimer::new(
-                    query_statement,
-                    self.slow_query_options.threshold,
-                    self.slow_query_options.sample_ratio,
-                    self.slow_query_options.record_type,
-                    event_recorder,
-                )
-            });
-
-        let ticket = self.process_manager.register_query(
-            query_ctx.current_catalog().to_string(),
-            vec![query_ctx.current_schema()],
-            query,
-            query_ctx.conn_info().to_string(),
-            Some(query_ctx.process_id()),
-            slow_query_timer,
-        );
-
-        let query_fut = self.statement_executor.exec_plan(plan, query_ctx.clone());
-
-        let output = CancellableFuture::new(query_fut, ticket.cancellation_handle.clone())
-            .await
-            .map_err(|_| servers::error::CancelledSnafu.build())?
-            .map(|output| {
-                let Output { meta, data } = output;
-                let data = match data {
-                    OutputData::Stream(stream) => {
-                        OutputData::Stream(Box::pin(CancellableStreamWrapper::new(stream, ticket)))
-                    }
-                    other => other,
-                };
-                Output { data, meta }
-            })
-            .map_err(BoxedError::new)
-            .context(ExecuteQuerySnafu)?;
+        }
 
-        Ok(interceptor.post_execute(output, query_ctx)?)
+        self.exec_promql_statement(query, stmt, query_ctx).await
     }
 
     async fn query_metric_names(
@@ -926,6 +890,9 @@ pub fn check_permission(
             let name = insert.table_name().context(ParseSqlSnafu)?;
             validate_param(name, query_ctx)?;
         }
+        Statement::Update(update) => {
+            validate_param(update.table_name(), query_ctx)?;
+        }
         Statement::CreateTable(stmt) => {
             validate_param(&stmt.name, query_ctx)?;
         }
@@ -984,6 +951,8 @@ pub fn check_permission(
                 CopyDatabase::From(stmt) => validate_database(&stmt.database_name, query_ctx)?,
             }
         }
+        Statement::BackupDatabase(stmt) => validate_database(&stmt.database_name, query_ctx)?,
+        Statement::RestoreDatabase(stmt) => validate_database(&stmt.database_name, query_ctx)?,
         Statement::TruncateTable(stmt) => {
             validate_param(stmt.table_name(), query_ctx)?;
         }
@@ -993,6 +962,20 @@ pub fn check_permission(
         Statement::Kill(_) => {}
         // SHOW PROCESSLIST
         Statement::ShowProcesslist(_) => {}
+        // users and roles are not in any catalog
+        Statement::CreateUser(_)
+        | Statement::DropUser(_)
+        | Statement::CreateRole(_)
+        | Statement::DropRole(_) => {}
+        Statement::Grant(Grant { grantable, .. }) | Statement::Revoke(Revoke { grantable, .. }) => {
+            if let Grantable::Privileges {
+                level: PrivilegeLevel::Table(name),
+                ..
+            } = grantable
+            {
+                validate_param(name, query_ctx)?;
+            }
+        }
     }
     Ok(())
 }
@@ -14,6 +14,7 @@
 
 use std::sync::Arc;
 
+use auth::{PermissionCheckerRef, UserProviderRef};
 use cache::{TABLE_FLOWNODE_SET_CACHE_NAME, TABLE_ROUTE_CACHE_NAME};
 use catalog::process_manager::ProcessManagerRef;
 use catalog::CatalogManagerRef;
@@ -21,6 +22,7 @@ use common_base::Plugins;
 use common_event_recorder::EventRecorderImpl;
 use common_meta::cache::{LayeredCacheRegistryRef, TableRouteCacheRef};
 use common_meta::cache_invalidator::{CacheInvalidatorRef, DummyCacheInvalidator};
+use common_meta::key::access_control::AccessControlManagerRef;
 use common_meta::key::flow::FlowMetadataManager;
 use common_meta::key::TableMetadataManager;
 use common_meta::kv_backend::KvBackendRef;
@@ -38,14 +40,17 @@ use partition::manager::PartitionRuleManager;
 use pipeline::pipeline_operator::PipelineOperator;
 use query::region_query::RegionQueryHandlerFactoryRef;
 use query::QueryEngineFactory;
-use snafu::OptionExt;
+use servers::prom_rule::RuleManager;
+use snafu::{OptionExt, ResultExt};
 
+use crate::access_control::{AccessControlPermissionChecker, AccessControlUserProvider};
 use crate::error::{self, Result};
 use crate::events::EventHandlerImpl;
 use crate::frontend::FrontendOptions;
 use crate::instance::region_query::FrontendRegionQueryHandler;
 use crate::instance::Instance;
 use crate::limiter::Limiter;
+use crate::promql_cache::PromqlResultCache;
 
 /// The frontend [`Instance`] builder.
 pub struct FrontendBuilder {
@@ -132,12 +137,17 @@ impl FrontendBuilder {
                     name: TABLE_FLOWNODE_SET_CACHE_NAME,
                 })?;
 
-        let inserter = Arc::new(Inserter::new(
+        let promql_cache = PromqlResultCache::try_new(&self.options.promql_cache).map(Arc::new);
+        let mut inserter = Inserter::new(
             self.catalog_manager.clone(),
             partition_manager.clone(),
             node_manager.clone(),
             table_flownode_cache,
-        ));
+        );
+        if let Some(promql_cache) = &promql_cache {
+            inserter = inserter.with_insert_listener(promql_cache.clone());
+        }
+        let inserter = Arc::new(inserter);
         let deleter = Arc::new(Deleter::new(
             self.catalog_manager.clone(),
             partition_manager.clone(),
@@ -176,7 +186,7 @@ impl FrontendBuilder {
         )
         .query_engine();
 
-        let statement_executor = Arc::new(StatementExecutor::new(
+        let mut statement_executor = StatementExecutor::new(
             self.catalog_manager.clone(),
             query_engine.clone(),
             self.procedure_executor,
@@ -185,7 +195,12 @@ impl FrontendBuilder {
             inserter.clone(),
             table_route_cache,
             Some(process_manager.clone()),
-        ));
+        );
+        // Shares the manager with the cache registry, so invalidations reach its snapshot.
+        if let Some(manager) = self.layered_cache_registry.get::<AccessControlManagerRef>() {
+            statement_executor = statement_executor.with_access_control_manager(manager);
+        }
+        let statement_executor = Arc::new(statement_executor);
 
         let pipeline_operator = Arc::new(PipelineOperator::new(
             inserter.clone(),
@@ -196,6 +211,20 @@ impl FrontendBuilder {
 
         plugins.insert::<StatementExecutorRef>(statement_executor.clone());
 
+        // Users created by `CREATE USER` can only log in if authentication is enabled.
+        let access_control_manager = statement_executor.access_control_manager().clone();
+        if let Some(user_provider) = plugins.get::<UserProviderRef>() {
+            plugins.insert::<UserProviderRef>(Arc::new(AccessControlUserProvider::new(
+                user_provider,
+                access_control_manager.clone(),
+            )));
+            if plugins.get::<PermissionCheckerRef>().is_none() {
+                plugins.insert::<PermissionCheckerRef>(Arc::new(
+                    AccessControlPermissionChecker::new(access_control_manager),
+                ));
+            }
+        }
+
         let event_recorder = Arc::new(EventRecorderImpl::new(Box::new(EventHandlerImpl::new(
             statement_executor.clone(),
             self.options.slow_query.ttl,
@@ -210,6 +239,10 @@ impl FrontendBuilder {
                 Arc::new(Limiter::new(max_in_flight_write_bytes.as_bytes()))
             });
 
+        let rule_manager = RuleManager::try_new(&self.options.rule)
+            .context(error::LoadPromRulesSnafu)?
+            .map(Arc::new);
+
         Ok(Instance {
             catalog_manager: self.catalog_manager,
             pipeline_operator,
@@ -224,6 +257,8 @@ impl FrontendBuilder {
             process_manager,
             otlp_metrics_table_legacy_cache: DashMap::new(),
             slow_query_options: self.options.slow_query.clone(),
+            rule_manager,
+            promql_cache,
         })
     }
 }
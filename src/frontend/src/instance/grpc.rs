@@ -23,7 +23,7 @@ use api::v1::{
     RowInsertRequests,
 };
 use async_trait::async_trait;
-use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq};
+use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq, PermissionScope};
 use common_base::AffectedRows;
 use common_error::ext::BoxedError;
 use common_grpc::flight::FlightDecoder;
@@ -31,7 +31,10 @@ use common_grpc::FlightData;
 use common_query::logical_plan::add_insert_to_logical_plan;
 use common_query::Output;
 use common_telemetry::tracing::{self};
+use datafusion::common::tree_node::{TreeNode, TreeNodeRecursion};
+use datafusion::common::TableReference;
 use datafusion::datasource::DefaultTableSource;
+use datafusion_expr::LogicalPlan;
 use query::parser::PromQuery;
 use servers::interceptor::{GrpcQueryInterceptor, GrpcQueryInterceptorRef};
 use servers::query_handler::grpc::GrpcQueryHandler;
@@ -64,7 +67,11 @@ impl GrpcQueryHandler for Instance {
         self.plugins
             .get::<PermissionCheckerRef>()
             .as_ref()
-            .check_permission(ctx.current_user(), PermissionReq::GrpcRequest(&request))
+            .check_permission(
+                ctx.current_user(),
+                PermissionReq::GrpcRequest(&request),
+                PermissionScope::new(ctx.current_catalog(), &ctx.current_schema()),
+            )
             .context(PermissionSnafu)?;
 
         let _guard = if let Some(limiter) = &self.limiter {
@@ -122,6 +129,7 @@ impl GrpcQueryHandler for Instance {
                             .decode(bytes::Bytes::from(plan), dummy_catalog_list, true)
                             .await
                             .context(SubstraitDecodeLogicalPlanSnafu)?;
+                        self.check_plan_permission(&logical_plan, None, &ctx)?;
                         let output =
                             SqlQueryHandler::do_exec_plan(self, None, logical_plan, ctx.clone())
                                 .await?;
@@ -276,7 +284,11 @@ impl GrpcQueryHandler for Instance {
         self.plugins
             .get::<PermissionCheckerRef>()
             .as_ref()
-            .check_permission(ctx.current_user(), PermissionReq::BulkInsert)
+            .check_permission(
+                ctx.current_user(),
+                PermissionReq::BulkInsert,
+                PermissionScope::new(ctx.current_catalog(), &ctx.current_schema()),
+            )
             .context(PermissionSnafu)?;
 
         // do we check limit for bulk insert?
@@ -337,6 +349,30 @@ fn fill_catalog_and_schema_from_context(ddl_expr: &mut DdlExpr, ctx: &QueryConte
 }
 
 impl Instance {
+    /// Checks the permission to read the tables scanned by the `plan`, and to insert
+    /// into the `insert_into` table.
+    fn check_plan_permission(
+        &self,
+        plan: &LogicalPlan,
+        insert_into: Option<&api::v1::TableName>,
+        ctx: &QueryContextRef,
+    ) -> Result<()> {
+        let scans = plan_table_scans(plan, ctx);
+        self.plugins
+            .get::<PermissionCheckerRef>()
+            .as_ref()
+            .check_permission(
+                ctx.current_user(),
+                PermissionReq::LogicalPlan {
+                    scans: &scans,
+                    insert_into,
+                },
+                PermissionScope::new(ctx.current_catalog(), &ctx.current_schema()),
+            )
+            .context(PermissionSnafu)?;
+        Ok(())
+    }
+
     async fn handle_insert_plan(
         &self,
         insert: InsertIntoPlan,
@@ -368,6 +404,7 @@ impl Instance {
             )
             .await
             .context(SubstraitDecodeLogicalPlanSnafu)?;
+        self.check_plan_permission(&logical_plan, Some(&table_name), &ctx)?;
 
         let table = self
             .catalog_manager()
@@ -500,3 +537,31 @@ impl Instance {
             .context(TableOperationSnafu)
     }
 }
+
+/// Returns the tables scanned by the `plan`, including the ones in subqueries.
+fn plan_table_scans(plan: &LogicalPlan, ctx: &QueryContextRef) -> Vec<api::v1::TableName> {
+    let mut scans = vec![];
+    // The visitor never fails.
+    let _ = plan.apply_with_subqueries(|node| {
+        if let LogicalPlan::TableScan(scan) = node {
+            let (catalog, schema) = match &scan.table_name {
+                TableReference::Full {
+                    catalog, schema, ..
+                } => (catalog.to_string(), schema.to_string()),
+                TableReference::Partial { schema, .. } => {
+                    (ctx.current_catalog().to_string(), schema.to_string())
+                }
+                TableReference::Bare { .. } => {
+                    (ctx.current_catalog().to_string(), ctx.current_schema())
+                }
+            };
+            scans.push(api::v1::TableName {
+                catalog_name: catalog,
+                schema_name: schema,
+                table_name: scan.table_name.table().to_string(),
+            });
+        }
+        Ok(TreeNodeRecursion::Continue)
+    });
+    scans
+}
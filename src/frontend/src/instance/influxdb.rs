@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use async_trait::async_trait;
-use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq};
+use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq, PermissionScope};
 use client::Output;
 use common_error::ext::BoxedError;
 use servers::error::{AuthSnafu, Error, InFlightWriteBytesExceededSnafu};
@@ -35,7 +35,11 @@ impl InfluxdbLineProtocolHandler for Instance {
         self.plugins
             .get::<PermissionCheckerRef>()
             .as_ref()
-            .check_permission(ctx.current_user(), PermissionReq::LineProtocol)
+            .check_permission(
+                ctx.current_user(),
+                PermissionReq::LineProtocol,
+                PermissionScope::new(ctx.current_catalog(), &ctx.current_schema()),
+            )
             .context(AuthSnafu)?;
 
         let interceptor_ref = self.plugins.get::<LineProtocolInterceptorRef<Error>>();
@@ -16,7 +16,7 @@ use std::sync::Arc;
 
 use api::v1::RowInsertRequests;
 use async_trait::async_trait;
-use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq};
+use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq, PermissionScope};
 use client::Output;
 use common_error::ext::BoxedError;
 use datatypes::timestamp::TimestampNanosecond;
@@ -40,7 +40,11 @@ impl PipelineHandler for Instance {
         self.plugins
             .get::<PermissionCheckerRef>()
             .as_ref()
-            .check_permission(ctx.current_user(), PermissionReq::LogWrite)
+            .check_permission(
+                ctx.current_user(),
+                PermissionReq::LogWrite,
+                PermissionScope::new(ctx.current_catalog(), &ctx.current_schema()),
+            )
             .context(AuthSnafu)?;
 
         let log = self
@@ -116,6 +120,42 @@ impl PipelineHandler for Instance {
             .await
             .context(PipelineSnafu)
     }
+
+    async fn list_pipeline_versions(
+        &self,
+        name: &str,
+        query_ctx: QueryContextRef,
+    ) -> ServerResult<Vec<TimestampNanosecond>> {
+        self.pipeline_operator
+            .list_pipeline_versions(name, query_ctx)
+            .await
+            .context(PipelineSnafu)
+    }
+
+    async fn diff_pipeline(
+        &self,
+        name: &str,
+        from: PipelineVersion,
+        to: PipelineVersion,
+        query_ctx: QueryContextRef,
+    ) -> ServerResult<String> {
+        self.pipeline_operator
+            .diff_pipeline(name, from, to, query_ctx)
+            .await
+            .context(PipelineSnafu)
+    }
+
+    async fn rollback_pipeline(
+        &self,
+        name: &str,
+        version: PipelineVersion,
+        query_ctx: QueryContextRef,
+    ) -> ServerResult<PipelineInfo> {
+        self.pipeline_operator
+            .rollback_pipeline(name, version, query_ctx)
+            .await
+            .context(PipelineSnafu)
+    }
 }
 
 impl Instance {
@@ -14,7 +14,7 @@
 
 use std::ops::Deref;
 
-use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq};
+use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq, PermissionScope};
 use client::Output;
 use common_error::ext::BoxedError;
 use log_query::LogQuery;
@@ -38,7 +38,11 @@ impl LogQueryHandler for Instance {
         self.plugins
             .get::<PermissionCheckerRef>()
             .as_ref()
-            .check_permission(ctx.current_user(), PermissionReq::LogQuery)
+            .check_permission(
+                ctx.current_user(),
+                PermissionReq::LogQuery,
+                PermissionScope::new(ctx.current_catalog(), &ctx.current_schema()),
+            )
             .context(AuthSnafu)?;
 
         interceptor.as_ref().pre_query(&request, ctx.clone())?;
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use async_trait::async_trait;
-use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq};
+use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq, PermissionScope};
 use common_error::ext::BoxedError;
 use common_telemetry::tracing;
 use servers::error as server_error;
@@ -37,7 +37,11 @@ impl OpentsdbProtocolHandler for Instance {
         self.plugins
             .get::<PermissionCheckerRef>()
             .as_ref()
-            .check_permission(ctx.current_user(), PermissionReq::Opentsdb)
+            .check_permission(
+                ctx.current_user(),
+                PermissionReq::Opentsdb,
+                PermissionScope::new(ctx.current_catalog(), &ctx.current_schema()),
+            )
             .context(AuthSnafu)?;
 
         let (requests, _) = data_point_to_grpc_row_insert_requests(data_points)?;
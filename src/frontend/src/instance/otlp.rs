@@ -15,7 +15,7 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq};
+use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq, PermissionScope};
 use client::Output;
 use common_error::ext::BoxedError;
 use common_query::prelude::GREPTIME_PHYSICAL_TABLE;
@@ -47,7 +47,11 @@ impl OpenTelemetryProtocolHandler for Instance {
         self.plugins
             .get::<PermissionCheckerRef>()
             .as_ref()
-            .check_permission(ctx.current_user(), PermissionReq::Otlp)
+            .check_permission(
+                ctx.current_user(),
+                PermissionReq::Otlp,
+                PermissionScope::new(ctx.current_catalog(), &ctx.current_schema()),
+            )
             .context(AuthSnafu)?;
 
         let interceptor_ref = self
@@ -124,7 +128,11 @@ impl OpenTelemetryProtocolHandler for Instance {
         self.plugins
             .get::<PermissionCheckerRef>()
             .as_ref()
-            .check_permission(ctx.current_user(), PermissionReq::Otlp)
+            .check_permission(
+                ctx.current_user(),
+                PermissionReq::Otlp,
+                PermissionScope::new(ctx.current_catalog(), &ctx.current_schema()),
+            )
             .context(AuthSnafu)?;
 
         let interceptor_ref = self
@@ -171,7 +179,11 @@ impl OpenTelemetryProtocolHandler for Instance {
         self.plugins
             .get::<PermissionCheckerRef>()
             .as_ref()
-            .check_permission(ctx.current_user(), PermissionReq::Otlp)
+            .check_permission(
+                ctx.current_user(),
+                PermissionReq::Otlp,
+                PermissionScope::new(ctx.current_catalog(), &ctx.current_schema()),
+            )
             .context(AuthSnafu)?;
 
         let interceptor_ref = self
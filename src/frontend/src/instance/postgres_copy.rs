@@ -0,0 +1,101 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq, PermissionScope};
+use common_base::AffectedRows;
+use common_error::ext::BoxedError;
+use common_query::OutputData;
+use common_telemetry::tracing;
+use query::query_engine::options::validate_catalog_and_schema;
+use servers::error as server_error;
+use servers::error::{
+    AuthSnafu, CatalogSnafu, InFlightWriteBytesExceededSnafu, ValidateTableSnafu,
+};
+use servers::query_handler::PostgresCopyHandler;
+use session::context::QueryContextRef;
+use snafu::prelude::*;
+use table::requests::InsertRequest as TableInsertRequest;
+use table::table_name::TableName;
+use table::TableRef;
+
+use crate::instance::Instance;
+
+#[async_trait]
+impl PostgresCopyHandler for Instance {
+    async fn get_table(
+        &self,
+        table_name: &TableName,
+        ctx: QueryContextRef,
+    ) -> server_error::Result<Option<TableRef>> {
+        validate_catalog_and_schema(&table_name.catalog_name, &table_name.schema_name, &ctx)
+            .with_context(|_| ValidateTableSnafu {
+                table: table_name.to_string(),
+            })?;
+
+        self.catalog_manager
+            .table(
+                &table_name.catalog_name,
+                &table_name.schema_name,
+                &table_name.table_name,
+                Some(&ctx),
+            )
+            .await
+            .context(CatalogSnafu)
+    }
+
+    #[tracing::instrument(skip_all, fields(protocol = "postgres"))]
+    async fn insert(
+        &self,
+        request: TableInsertRequest,
+        ctx: QueryContextRef,
+    ) -> server_error::Result<AffectedRows> {
+        self.plugins
+            .get::<PermissionCheckerRef>()
+            .as_ref()
+            .check_permission(
+                ctx.current_user(),
+                PermissionReq::BulkInsert,
+                PermissionScope::new(&request.catalog_name, &request.schema_name),
+            )
+            .context(AuthSnafu)?;
+
+        let _guard = if let Some(limiter) = &self.limiter {
+            let size = request
+                .columns_values
+                .values()
+                .map(|vector| vector.memory_size())
+                .sum::<usize>();
+            let result = limiter.limit_in_flight_write_bytes(size as u64);
+            if result.is_none() {
+                return InFlightWriteBytesExceededSnafu.fail();
+            }
+            result
+        } else {
+            None
+        };
+
+        let output = self
+            .inserter
+            .handle_table_insert(request, ctx)
+            .await
+            .map_err(BoxedError::new)
+            .context(server_error::ExecuteGrpcQuerySnafu)?;
+
+        Ok(match output.data {
+            OutputData::AffectedRows(rows) => rows,
+            _ => 0,
+        })
+    }
+}
@@ -0,0 +1,298 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use api::v1::value::ValueData;
+use api::v1::{
+    ColumnDataType, ColumnSchema, Row, RowInsertRequest, RowInsertRequests, Rows, SemanticType,
+};
+use async_trait::async_trait;
+use client::OutputData;
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_PRIVATE_SCHEMA_NAME};
+use common_error::ext::BoxedError;
+use common_meta::kv_backend::KvBackendRef;
+use common_meta::rpc::store::CompareAndPutRequest;
+use common_recordbatch::util;
+use common_time::util::current_time_millis;
+use datatypes::prelude::Value;
+use serde::{Deserialize, Serialize};
+use servers::error::{
+    CampaignRuleLeaderSnafu, LoadAlertStateSnafu, PersistAlertStateSnafu, Result as ServerResult,
+};
+use servers::prom_rule::{AlertStateStore, RuleLeaderElection, RULE_LEADER_CAMPAIGN_INTERVAL};
+use session::context::{QueryContextBuilder, QueryContextRef};
+use snafu::ResultExt;
+
+use crate::error::{
+    CatalogSnafu, CollectRecordbatchSnafu, ExecLogicalPlanSnafu, ReadTableSnafu, Result,
+};
+use crate::instance::Instance;
+
+/// The table in `greptime_private` to persist the alerts of alerting rules.
+pub const ALERT_STATE_TABLE_NAME: &str = "prom_alert_states";
+const GROUP_COLUMN_NAME: &str = "group_name";
+const RULE_COLUMN_NAME: &str = "rule_name";
+const ALERTS_COLUMN_NAME: &str = "alerts";
+const TIMESTAMP_COLUMN_NAME: &str = "greptime_timestamp";
+
+/// The key of the rule evaluation leader lease in the kv backend.
+const RULE_LEADER_KEY: &str = "__prom_rule_leader";
+/// The leader lease expires if not renewed in a few campaign intervals.
+const RULE_LEADER_LEASE: Duration =
+    Duration::from_secs(RULE_LEADER_CAMPAIGN_INTERVAL.as_secs() * 3);
+
+fn alert_state_ctx() -> QueryContextRef {
+    QueryContextBuilder::default()
+        .current_catalog(DEFAULT_CATALOG_NAME.to_string())
+        .current_schema(DEFAULT_PRIVATE_SCHEMA_NAME.to_string())
+        .build()
+        .into()
+}
+
+impl Instance {
+    async fn load_alert_states(&self) -> Result<Vec<(String, String, String)>> {
+        let ctx = alert_state_ctx();
+        let Some(table) = self
+            .catalog_manager
+            .table(
+                DEFAULT_CATALOG_NAME,
+                DEFAULT_PRIVATE_SCHEMA_NAME,
+                ALERT_STATE_TABLE_NAME,
+                Some(&ctx),
+            )
+            .await
+            .context(CatalogSnafu)?
+        else {
+            // Nothing was persisted yet.
+            return Ok(vec![]);
+        };
+
+        let dataframe = self
+            .query_engine
+            .read_table(table)
+            .with_context(|_| ReadTableSnafu {
+                table_name: ALERT_STATE_TABLE_NAME,
+            })?;
+        let output = self
+            .query_engine
+            .execute(dataframe.into_logical_plan(), ctx)
+            .await
+            .context(ExecLogicalPlanSnafu)?;
+        let batches = match output.data {
+            OutputData::Stream(stream) => util::collect(stream)
+                .await
+                .context(CollectRecordbatchSnafu)?,
+            OutputData::RecordBatches(rbs) => rbs.take(),
+            OutputData::AffectedRows(_) => vec![],
+        };
+
+        let mut states = Vec::new();
+        for batch in batches {
+            let (Some(groups), Some(rules), Some(alerts)) = (
+                batch.column_by_name(GROUP_COLUMN_NAME),
+                batch.column_by_name(RULE_COLUMN_NAME),
+                batch.column_by_name(ALERTS_COLUMN_NAME),
+            ) else {
+                continue;
+            };
+            for i in 0..batch.num_rows() {
+                if let (Value::String(group), Value::String(rule), Value::String(alerts)) =
+                    (groups.get(i), rules.get(i), alerts.get(i))
+                {
+                    states.push((
+                        group.into_string(),
+                        rule.into_string(),
+                        alerts.into_string(),
+                    ));
+                }
+            }
+        }
+        Ok(states)
+    }
+
+    async fn save_alert_states(&self, group: &str, rule: &str, alerts: String) -> Result<()> {
+        let schema = vec![
+            ColumnSchema {
+                column_name: GROUP_COLUMN_NAME.to_string(),
+                datatype: ColumnDataType::String.into(),
+                semantic_type: SemanticType::Tag.into(),
+                ..Default::default()
+            },
+            ColumnSchema {
+                column_name: RULE_COLUMN_NAME.to_string(),
+                datatype: ColumnDataType::String.into(),
+                semantic_type: SemanticType::Tag.into(),
+                ..Default::default()
+            },
+            ColumnSchema {
+                column_name: ALERTS_COLUMN_NAME.to_string(),
+                datatype: ColumnDataType::String.into(),
+                semantic_type: SemanticType::Field.into(),
+                ..Default::default()
+            },
+            ColumnSchema {
+                column_name: TIMESTAMP_COLUMN_NAME.to_string(),
+                datatype: ColumnDataType::TimestampMillisecond.into(),
+                semantic_type: SemanticType::Timestamp.into(),
+                ..Default::default()
+            },
+        ];
+        // Every rule has a single row at timestamp zero, so the latest
+        // alerts replace the previous ones on deduplication.
+        let row = Row {
+            values: vec![
+                ValueData::StringValue(group.to_string()).into(),
+                ValueData::StringValue(rule.to_string()).into(),
+                ValueData::StringValue(alerts).into(),
+                ValueData::TimestampMillisecondValue(0).into(),
+            ],
+        };
+        let requests = RowInsertRequests {
+            inserts: vec![RowInsertRequest {
+                table_name: ALERT_STATE_TABLE_NAME.to_string(),
+                rows: Some(Rows {
+                    schema,
+                    rows: vec![row],
+                }),
+            }],
+        };
+
+        let _ = self
+            .handle_row_inserts(requests, alert_state_ctx(), false, false)
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AlertStateStore for Instance {
+    async fn load(&self) -> ServerResult<Vec<(String, String, String)>> {
+        self.load_alert_states()
+            .await
+            .map_err(BoxedError::new)
+            .context(LoadAlertStateSnafu)
+    }
+
+    async fn save(&self, group: &str, rule: &str, alerts: String) -> ServerResult<()> {
+        self.save_alert_states(group, rule, alerts)
+            .await
+            .map_err(BoxedError::new)
+            .context(PersistAlertStateSnafu { rule })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RuleLeaderLease {
+    node: String,
+    /// The time in milliseconds the lease expires.
+    expire_at: i64,
+}
+
+/// Elects the rule evaluation leader by a lease in the kv backend shared by all frontends.
+pub struct KvRuleLeaderElection {
+    kv_backend: KvBackendRef,
+    /// The unique id of this node in the election.
+    node: String,
+}
+
+impl KvRuleLeaderElection {
+    pub fn new(kv_backend: KvBackendRef) -> Self {
+        Self {
+            kv_backend,
+            node: uuid::Uuid::new_v4().to_string(),
+        }
+    }
+
+    /// Acquires the lease if it's absent, expired or held by this node.
+    async fn try_acquire(&self) -> common_meta::error::Result<bool> {
+        let now = current_time_millis();
+        let expect = match self.kv_backend.get(RULE_LEADER_KEY.as_bytes()).await? {
+            Some(kv) => {
+                let held_by_other = serde_json::from_slice::<RuleLeaderLease>(&kv.value)
+                    .is_ok_and(|lease| lease.node != self.node && lease.expire_at > now);
+                if held_by_other {
+                    return Ok(false);
+                }
+                kv.value
+            }
+            None => vec![],
+        };
+
+        let lease = RuleLeaderLease {
+            node: self.node.clone(),
+            expire_at: now + RULE_LEADER_LEASE.as_millis() as i64,
+        };
+        // Serializing the lease never fails.
+        let value = serde_json::to_vec(&lease).unwrap_or_default();
+        let resp = self
+            .kv_backend
+            .compare_and_put(
+                CompareAndPutRequest::new()
+                    .with_key(RULE_LEADER_KEY.as_bytes().to_vec())
+                    .with_expect(expect)
+                    .with_value(value),
+            )
+            .await?;
+        Ok(resp.success)
+    }
+}
+
+#[async_trait]
+impl RuleLeaderElection for KvRuleLeaderElection {
+    async fn campaign(&self) -> ServerResult<bool> {
+        self.try_acquire()
+            .await
+            .map_err(BoxedError::new)
+            .context(CampaignRuleLeaderSnafu)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_meta::kv_backend::memory::MemoryKvBackend;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_rule_leader_election() {
+        let kv_backend: KvBackendRef = Arc::new(MemoryKvBackend::new());
+        let node1 = KvRuleLeaderElection::new(kv_backend.clone());
+        let node2 = KvRuleLeaderElection::new(kv_backend.clone());
+
+        assert!(node1.campaign().await.unwrap());
+        assert!(!node2.campaign().await.unwrap());
+        // Renews the lease.
+        assert!(node1.campaign().await.unwrap());
+        assert!(!node2.campaign().await.unwrap());
+
+        // Takes over the expired lease.
+        let expired = RuleLeaderLease {
+            node: node1.node.clone(),
+            expire_at: current_time_millis() - 1,
+        };
+        kv_backend
+            .put(
+                common_meta::rpc::store::PutRequest::new()
+                    .with_key(RULE_LEADER_KEY.as_bytes().to_vec())
+                    .with_value(serde_json::to_vec(&expired).unwrap()),
+            )
+            .await
+            .unwrap();
+        assert!(node2.campaign().await.unwrap());
+        assert!(!node1.campaign().await.unwrap());
+    }
+}
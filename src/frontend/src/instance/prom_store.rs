@@ -19,7 +19,7 @@ use api::prom_store::remote::read_request::ResponseType;
 use api::prom_store::remote::{Query, QueryResult, ReadRequest, ReadResponse};
 use api::v1::RowInsertRequests;
 use async_trait::async_trait;
-use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq};
+use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq, PermissionScope};
 use client::OutputData;
 use common_catalog::format_full_table_name;
 use common_error::ext::BoxedError;
@@ -168,7 +168,11 @@ impl PromStoreProtocolHandler for Instance {
         self.plugins
             .get::<PermissionCheckerRef>()
             .as_ref()
-            .check_permission(ctx.current_user(), PermissionReq::PromStoreWrite)
+            .check_permission(
+                ctx.current_user(),
+                PermissionReq::PromStoreWrite,
+                PermissionScope::new(ctx.current_catalog(), &ctx.current_schema()),
+            )
             .context(AuthSnafu)?;
         let interceptor_ref = self
             .plugins
@@ -212,7 +216,11 @@ impl PromStoreProtocolHandler for Instance {
         self.plugins
             .get::<PermissionCheckerRef>()
             .as_ref()
-            .check_permission(ctx.current_user(), PermissionReq::PromStoreRead)
+            .check_permission(
+                ctx.current_user(),
+                PermissionReq::PromStoreRead,
+                PermissionScope::new(ctx.current_catalog(), &ctx.current_schema()),
+            )
             .context(AuthSnafu)?;
         let interceptor_ref = self
             .plugins
@@ -12,18 +12,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use catalog::information_schema::TABLES;
+use catalog::process_manager::{QueryStatement as CatalogQueryStatement, SlowQueryTimer};
 use client::OutputData;
+use common_base::cancellation::CancellableFuture;
 use common_catalog::consts::INFORMATION_SCHEMA_NAME;
 use common_catalog::format_full_table_name;
-use common_recordbatch::util;
+use common_error::ext::BoxedError;
+use common_query::Output;
+use common_recordbatch::{util, RecordBatches};
 use common_telemetry::tracing;
 use datatypes::prelude::Value;
 use promql_parser::label::{MatchOp, Matcher, Matchers};
+use promql_parser::parser::EvalStmt;
+use query::parser::{PromQuery, QueryStatement};
 use query::promql;
 use query::promql::planner::PromPlanner;
+use servers::error::{self as server_error, ExecuteQuerySnafu, UnexpectedResultSnafu};
+use servers::interceptor::{PromQueryInterceptor, PromQueryInterceptorRef};
 use servers::prom_store::{DATABASE_LABEL, SCHEMA_LABEL};
 use servers::prometheus;
 use session::context::QueryContextRef;
@@ -35,6 +43,8 @@ use crate::error::{
     Result, TableNotFoundSnafu,
 };
 use crate::instance::Instance;
+use crate::promql_cache::{PromqlResultCacheRef, SplitRangeQuery};
+use crate::stream_wrapper::CancellableStreamWrapper;
 
 impl Instance {
     /// Handles metric names query request, returns the names.
@@ -182,4 +192,160 @@ impl Instance {
 
         Ok(results)
     }
+
+    /// Executes the PromQL statement `stmt` parsed from `query`.
+    pub(crate) async fn exec_promql_statement(
+        &self,
+        query: &PromQuery,
+        stmt: QueryStatement,
+        query_ctx: QueryContextRef,
+    ) -> server_error::Result<Output> {
+        let interceptor = self
+            .plugins
+            .get::<PromQueryInterceptorRef<server_error::Error>>();
+
+        let plan = self
+            .statement_executor
+            .plan(&stmt, query_ctx.clone())
+            .await
+            .map_err(BoxedError::new)
+            .context(ExecuteQuerySnafu)?;
+
+        interceptor.pre_execute(query, Some(&plan), query_ctx.clone())?;
+
+        // Take the EvalStmt from the original QueryStatement and use it to create the CatalogQueryStatement.
+        let query_statement = if let QueryStatement::Promql(eval_stmt) = stmt {
+            CatalogQueryStatement::Promql(eval_stmt)
+        } else {
+            // It should not happen since the query is already parsed successfully.
+            return UnexpectedResultSnafu {
+                reason: "The query should always be promql.".to_string(),
+            }
+            .fail();
+        };
+        let query = query_statement.to_string();
+
+        let slow_query_timer = self
+            .slow_query_options
+            .enable
+            .then(|| self.event_recorder.clone())
+            .flatten()
+            .map(|event_recorder| {
+                SlowQueryTimer::new(
+                    query_statement,
+                    self.slow_query_options.threshold,
+                    self.slow_query_options.sample_ratio,
+                    self.slow_query_options.record_type,
+                    event_recorder,
+                )
+            });
+
+        let ticket = self.process_manager.register_query(
+            query_ctx.current_catalog().to_string(),
+            vec![query_ctx.current_schema()],
+            query,
+            query_ctx.conn_info().to_string(),
+            Some(query_ctx.process_id()),
+            slow_query_timer,
+        );
+
+        let query_fut = self.statement_executor.exec_plan(plan, query_ctx.clone());
+
+        let output = CancellableFuture::new(query_fut, ticket.cancellation_handle.clone())
+            .await
+            .map_err(|_| servers::error::CancelledSnafu.build())?
+            .map(|output| {
+                let Output { meta, data } = output;
+                let data = match data {
+                    OutputData::Stream(stream) => {
+                        OutputData::Stream(Box::pin(CancellableStreamWrapper::new(stream, ticket)))
+                    }
+                    other => other,
+                };
+                Output { data, meta }
+            })
+            .map_err(BoxedError::new)
+            .context(ExecuteQuerySnafu)?;
+
+        Ok(interceptor.post_execute(output, query_ctx)?)
+    }
+
+    /// Executes the range query with the result `cache`. Results of cacheable chunks
+    /// are read from the cache if present, other chunks are executed and then cached.
+    pub(crate) async fn do_cached_range_query(
+        &self,
+        query: &PromQuery,
+        cache: &PromqlResultCacheRef,
+        eval_stmt: &EvalStmt,
+        split: SplitRangeQuery,
+        query_ctx: QueryContextRef,
+    ) -> server_error::Result<Output> {
+        let mut schema = None;
+        let mut batches = Vec::new();
+        let mut cached_chunks = Vec::new();
+        for chunk in &split.chunks {
+            let cached = if chunk.cacheable {
+                cache.get(&split, chunk)
+            } else {
+                None
+            };
+            let result = match cached {
+                Some(result) => {
+                    cached_chunks.push(*chunk);
+                    result
+                }
+                None => {
+                    let mut stmt = eval_stmt.clone();
+                    stmt.start = UNIX_EPOCH + Duration::from_millis(chunk.start as u64);
+                    stmt.end = UNIX_EPOCH + Duration::from_millis(chunk.end as u64);
+                    let output = self
+                        .exec_promql_statement(
+                            query,
+                            QueryStatement::Promql(stmt),
+                            query_ctx.clone(),
+                        )
+                        .await?;
+                    let result = match output.data {
+                        OutputData::Stream(stream) => util::collect_batches(stream)
+                            .await
+                            .context(server_error::CollectRecordbatchSnafu)?,
+                        OutputData::RecordBatches(batches) => batches,
+                        OutputData::AffectedRows(_) => {
+                            return UnexpectedResultSnafu {
+                                reason: "PromQL query should not return affected rows",
+                            }
+                            .fail();
+                        }
+                    };
+                    if chunk.cacheable {
+                        cache.put(&split, chunk, &result);
+                    }
+                    result
+                }
+            };
+
+            let schema = schema.get_or_insert_with(|| result.schema());
+            if *schema != result.schema() {
+                // The cached results are outdated, e.g. the table is altered.
+                for chunk in &cached_chunks {
+                    cache.remove(&split, chunk);
+                }
+                return self
+                    .exec_promql_statement(
+                        query,
+                        QueryStatement::Promql(eval_stmt.clone()),
+                        query_ctx,
+                    )
+                    .await;
+            }
+            batches.extend(result.take());
+        }
+
+        let schema = schema.context(UnexpectedResultSnafu {
+            reason: "range query is split into no chunks",
+        })?;
+        let batches = RecordBatches::try_new(schema, batches)
+            .context(server_error::CollectRecordbatchSnafu)?;
+        Ok(Output::new_with_record_batches(batches))
+    }
 }
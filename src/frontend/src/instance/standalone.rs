@@ -31,6 +31,10 @@ use common_telemetry::tracing_context::{FutureExt, TracingContext};
 use datanode::region_server::RegionServer;
 use servers::grpc::region_server::RegionServerHandler;
 use snafu::{OptionExt, ResultExt};
+use store_api::region_engine::{
+    BackupRegionRequest, BackupRegionResponse, CopyRegionFromRequest, CopyRegionFromResponse,
+    RestoreRegionRequest, RestoreRegionResponse,
+};
 
 use crate::error::{InvalidRegionRequestSnafu, InvokeRegionServerSnafu, Result};
 
@@ -110,4 +114,37 @@ impl Datanode for RegionInvoker {
             .map_err(BoxedError::new)
             .context(meta_error::ExternalSnafu)
     }
+
+    async fn backup_region(
+        &self,
+        request: BackupRegionRequest,
+    ) -> MetaResult<BackupRegionResponse> {
+        self.region_server
+            .backup_region(request)
+            .await
+            .map_err(BoxedError::new)
+            .context(meta_error::ExternalSnafu)
+    }
+
+    async fn restore_region(
+        &self,
+        request: RestoreRegionRequest,
+    ) -> MetaResult<RestoreRegionResponse> {
+        self.region_server
+            .restore_region(request)
+            .await
+            .map_err(BoxedError::new)
+            .context(meta_error::ExternalSnafu)
+    }
+
+    async fn copy_region_from(
+        &self,
+        request: CopyRegionFromRequest,
+    ) -> MetaResult<CopyRegionFromResponse> {
+        self.region_server
+            .copy_region_from(request)
+            .await
+            .map_err(BoxedError::new)
+            .context(meta_error::ExternalSnafu)
+    }
 }
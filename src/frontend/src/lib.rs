@@ -14,6 +14,7 @@
 
 #![feature(assert_matches)]
 
+pub mod access_control;
 pub mod error;
 pub mod events;
 pub mod frontend;
@@ -21,6 +22,7 @@ pub mod heartbeat;
 pub mod instance;
 pub(crate) mod limiter;
 pub(crate) mod metrics;
+pub mod promql_cache;
 pub mod server;
 pub mod service_config;
 mod stream_wrapper;
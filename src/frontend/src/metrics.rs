@@ -72,4 +72,16 @@ lazy_static! {
         &["result"]
     )
     .unwrap();
+    /// The number of PromQL range query chunks whose results are read from the cache.
+    pub static ref PROMQL_CACHE_HIT: IntCounter = register_int_counter!(
+        "greptime_frontend_promql_cache_hit",
+        "frontend promql result cache hit",
+    )
+    .unwrap();
+    /// The number of PromQL range query chunks whose results are not in the cache.
+    pub static ref PROMQL_CACHE_MISS: IntCounter = register_int_counter!(
+        "greptime_frontend_promql_cache_miss",
+        "frontend promql result cache miss",
+    )
+    .unwrap();
 }
@@ -0,0 +1,657 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Result cache of PromQL range queries.
+//!
+//! A range query is split into chunks whose evaluation timestamps are aligned to the
+//! split interval. Results of chunks older than `now - freshness_lag` are immutable unless
+//! late writes arrive, so they are cached, and the fresh tail is always executed.
+//! Writes through this frontend with timestamps older than the lag invalidate the cached
+//! results that read the written table. Other changes, e.g. writes through other frontends,
+//! deletes or truncates, are not tracked, so cached results expire after the time to live.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use common_base::readable_size::ReadableSize;
+use common_catalog::build_db_string;
+use common_recordbatch::RecordBatches;
+use common_telemetry::debug;
+use dashmap::DashMap;
+use moka::sync::Cache;
+use operator::insert::InsertListener;
+use promql_parser::label::{MatchOp, METRIC_NAME};
+use promql_parser::parser::{
+    AggregateExpr, BinaryExpr, Call, EvalStmt, Expr as PromExpr, MatrixSelector, Offset, ParenExpr,
+    SubqueryExpr, UnaryExpr, VectorSelector,
+};
+use serde::{Deserialize, Serialize};
+use servers::prom_store::{DATABASE_LABEL, SCHEMA_LABEL};
+use table::metadata::TableInfo;
+
+use crate::metrics::{PROMQL_CACHE_HIT, PROMQL_CACHE_MISS};
+
+/// Suffixes of the tables that histogram functions may read instead of the
+/// table in the selector.
+const HISTOGRAM_SUFFIXES: [&str; 3] = ["_bucket", "_count", "_sum"];
+
+/// Options of the result cache of PromQL range queries.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct PromqlCacheOptions {
+    /// Whether to cache the results of range queries.
+    pub enable: bool,
+    /// Capacity of the cache.
+    pub cache_size: ReadableSize,
+    /// Range queries are split into chunks aligned to this interval.
+    #[serde(with = "humantime_serde")]
+    pub split_interval: Duration,
+    /// Results newer than `now - freshness_lag` are not cached. Writes older than it
+    /// invalidate the cached results of the written table.
+    #[serde(with = "humantime_serde")]
+    pub freshness_lag: Duration,
+    /// Cached results expire after this duration.
+    #[serde(with = "humantime_serde")]
+    pub time_to_live: Duration,
+}
+
+impl Default for PromqlCacheOptions {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            cache_size: ReadableSize::mb(256),
+            split_interval: Duration::from_secs(60 * 60),
+            freshness_lag: Duration::from_secs(10 * 60),
+            time_to_live: Duration::from_secs(10 * 60),
+        }
+    }
+}
+
+/// Chunk of a range query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct QueryChunk {
+    /// Start of the chunk in milliseconds.
+    pub(crate) start: i64,
+    /// End of the chunk in milliseconds, inclusive.
+    pub(crate) end: i64,
+    /// Whether the result of the chunk can be cached.
+    pub(crate) cacheable: bool,
+}
+
+/// A range query split into chunks.
+#[derive(Debug)]
+pub(crate) struct SplitRangeQuery {
+    db: String,
+    /// The normalized query.
+    query: String,
+    step: i64,
+    lookback: i64,
+    /// Tables read by the query, `None` if unknown.
+    tables: Option<Arc<HashSet<String>>>,
+    /// Invalidation sequence when the query is split.
+    sequence: u64,
+    pub(crate) chunks: Vec<QueryChunk>,
+}
+
+impl SplitRangeQuery {
+    fn key(&self, chunk: &QueryChunk) -> CacheKey {
+        CacheKey {
+            db: self.db.clone(),
+            query: self.query.clone(),
+            step: self.step,
+            lookback: self.lookback,
+            start: chunk.start,
+            end: chunk.end,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    db: String,
+    query: String,
+    step: i64,
+    lookback: i64,
+    start: i64,
+    end: i64,
+}
+
+#[derive(Debug)]
+struct CachedResult {
+    batches: RecordBatches,
+    /// Tables read by the query, `None` if unknown.
+    tables: Option<Arc<HashSet<String>>>,
+    /// Invalidation sequence when the query that produces the result starts.
+    sequence: u64,
+}
+
+pub type PromqlResultCacheRef = Arc<PromqlResultCache>;
+
+/// Cache of the results of range query chunks.
+pub struct PromqlResultCache {
+    split_interval: i64,
+    freshness_lag: i64,
+    cache: Cache<CacheKey, Arc<CachedResult>>,
+    /// Sequence of invalidations.
+    sequence: AtomicU64,
+    /// Sequence of the last invalidation of tables, keyed by the db and the table name.
+    table_invalidations: DashMap<(String, String), u64>,
+    /// Sequence of the last invalidation of any table in the db.
+    db_invalidations: DashMap<String, u64>,
+}
+
+impl PromqlResultCache {
+    /// Creates the cache, returns `None` if the cache is disabled.
+    pub fn try_new(options: &PromqlCacheOptions) -> Option<Self> {
+        if !options.enable || options.cache_size.as_bytes() == 0 || options.time_to_live.is_zero() {
+            return None;
+        }
+        let split_interval = options.split_interval.as_millis() as i64;
+        if split_interval <= 0 {
+            return None;
+        }
+
+        let cache = Cache::builder()
+            .max_capacity(options.cache_size.as_bytes())
+            .weigher(cached_result_weight)
+            .time_to_live(options.time_to_live)
+            .build();
+        Some(Self {
+            split_interval,
+            freshness_lag: options.freshness_lag.as_millis() as i64,
+            cache,
+            sequence: AtomicU64::new(0),
+            table_invalidations: DashMap::new(),
+            db_invalidations: DashMap::new(),
+        })
+    }
+
+    /// Splits the range query `stmt` in `db` into chunks at `now`.
+    ///
+    /// Returns `None` if the query can't use the cache, e.g. it's an instant query,
+    /// or it contains `@` modifiers or negative offsets.
+    pub(crate) fn split(
+        &self,
+        db: String,
+        stmt: &EvalStmt,
+        now: SystemTime,
+    ) -> Option<SplitRangeQuery> {
+        let start = to_millis(stmt.start)?;
+        let end = to_millis(stmt.end)?;
+        let step = stmt.interval.as_millis() as i64;
+        if start >= end || step <= 0 || step > self.split_interval {
+            return None;
+        }
+
+        let mut tables = ExprTables::default();
+        if !tables.collect(&stmt.expr) {
+            return None;
+        }
+
+        let cacheable_before = to_millis(now)? - self.freshness_lag;
+        let chunks = split_range(start, end, step, self.split_interval, cacheable_before);
+        if !chunks.iter().any(|chunk| chunk.cacheable) {
+            return None;
+        }
+
+        Some(SplitRangeQuery {
+            db,
+            query: stmt.expr.to_string(),
+            step,
+            lookback: stmt.lookback_delta.as_millis() as i64,
+            tables: (!tables.unknown).then(|| Arc::new(tables.names)),
+            sequence: self.sequence.load(Ordering::Acquire),
+            chunks,
+        })
+    }
+
+    /// Gets the cached result of the `chunk`.
+    pub(crate) fn get(&self, query: &SplitRangeQuery, chunk: &QueryChunk) -> Option<RecordBatches> {
+        let key = query.key(chunk);
+        let Some(result) = self.cache.get(&key) else {
+            PROMQL_CACHE_MISS.inc();
+            return None;
+        };
+        if self.is_invalidated(&query.db, &result) {
+            self.cache.invalidate(&key);
+            PROMQL_CACHE_MISS.inc();
+            return None;
+        }
+
+        PROMQL_CACHE_HIT.inc();
+        Some(clone_batches(&result.batches))
+    }
+
+    /// Caches the result of the `chunk`.
+    pub(crate) fn put(&self, query: &SplitRangeQuery, chunk: &QueryChunk, batches: &RecordBatches) {
+        let result = CachedResult {
+            batches: clone_batches(batches),
+            tables: query.tables.clone(),
+            sequence: query.sequence,
+        };
+        // The result may miss rows written before the query finishes.
+        if self.is_invalidated(&query.db, &result) {
+            return;
+        }
+        self.cache.insert(query.key(chunk), Arc::new(result));
+    }
+
+    /// Removes the cached result of the `chunk`.
+    pub(crate) fn remove(&self, query: &SplitRangeQuery, chunk: &QueryChunk) {
+        self.cache.invalidate(&query.key(chunk));
+    }
+
+    /// Invalidates the cached results that read the `table` in `db`.
+    pub(crate) fn invalidate(&self, db: &str, table: &str) {
+        let sequence = self.sequence.fetch_add(1, Ordering::AcqRel) + 1;
+        self.table_invalidations
+            .insert((db.to_string(), table.to_string()), sequence);
+        self.db_invalidations.insert(db.to_string(), sequence);
+        debug!("Invalidate PromQL results of table {} in {}", table, db);
+    }
+
+    fn is_invalidated(&self, db: &str, result: &CachedResult) -> bool {
+        let invalidated_after =
+            |sequence: Option<u64>| sequence.is_some_and(|sequence| sequence > result.sequence);
+        match &result.tables {
+            Some(tables) => tables.iter().any(|table| {
+                invalidated_after(
+                    self.table_invalidations
+                        .get(&(db.to_string(), table.clone()))
+                        .map(|s| *s),
+                )
+            }),
+            None => invalidated_after(self.db_invalidations.get(db).map(|s| *s)),
+        }
+    }
+}
+
+impl InsertListener for PromqlResultCache {
+    fn on_inserted(&self, table: &TableInfo, min_timestamp: i64) {
+        let Some(now) = to_millis(SystemTime::now()) else {
+            return;
+        };
+        if min_timestamp < now - self.freshness_lag {
+            self.invalidate(
+                &build_db_string(&table.catalog_name, &table.schema_name),
+                &table.name,
+            );
+        }
+    }
+}
+
+fn cached_result_weight(key: &CacheKey, value: &Arc<CachedResult>) -> u32 {
+    let batches_size = value
+        .batches
+        .iter()
+        .map(|batch| batch.df_record_batch().get_array_memory_size())
+        .sum::<usize>();
+    (key.db.len() + key.query.len() + batches_size)
+        .try_into()
+        .unwrap_or(u32::MAX)
+}
+
+fn clone_batches(batches: &RecordBatches) -> RecordBatches {
+    // Safety: all batches have the same schema.
+    RecordBatches::try_new(batches.schema(), batches.iter().cloned().collect()).unwrap()
+}
+
+fn to_millis(time: SystemTime) -> Option<i64> {
+    time.duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_millis() as i64)
+}
+
+/// Splits the evaluation timestamps `start + k * step` in `[start, end]` into chunks
+/// aligned to the `interval`. Chunks that end before `cacheable_before` are cacheable
+/// and the remaining chunks are merged into one.
+fn split_range(
+    start: i64,
+    end: i64,
+    step: i64,
+    interval: i64,
+    cacheable_before: i64,
+) -> Vec<QueryChunk> {
+    let mut chunks: Vec<QueryChunk> = Vec::new();
+    let mut chunk_start = start;
+    while chunk_start <= end {
+        let boundary = (chunk_start.div_euclid(interval) + 1) * interval;
+        let last = (boundary - 1).min(end);
+        let chunk_end = chunk_start + (last - chunk_start) / step * step;
+        let cacheable = chunk_end < cacheable_before;
+        match chunks.last_mut() {
+            Some(prev) if !prev.cacheable && !cacheable => prev.end = chunk_end,
+            _ => chunks.push(QueryChunk {
+                start: chunk_start,
+                end: chunk_end,
+                cacheable,
+            }),
+        }
+        chunk_start = chunk_end + step;
+    }
+    chunks
+}
+
+/// Tables read by a PromQL expression.
+#[derive(Debug, Default)]
+struct ExprTables {
+    names: HashSet<String>,
+    /// Whether the expression reads tables not in `names`.
+    unknown: bool,
+}
+
+impl ExprTables {
+    /// Collects tables read by `expr`, returns false if the result of `expr`
+    /// can't be cached.
+    fn collect(&mut self, expr: &PromExpr) -> bool {
+        match expr {
+            PromExpr::Aggregate(AggregateExpr { expr, param, .. }) => {
+                self.collect(expr) && param.as_ref().is_none_or(|param| self.collect(param))
+            }
+            PromExpr::Unary(UnaryExpr { expr }) | PromExpr::Paren(ParenExpr { expr }) => {
+                self.collect(expr)
+            }
+            PromExpr::Binary(BinaryExpr { lhs, rhs, .. }) => self.collect(lhs) && self.collect(rhs),
+            PromExpr::Subquery(SubqueryExpr {
+                expr, offset, at, ..
+            }) => at.is_none() && !matches!(offset, Some(Offset::Neg(_))) && self.collect(expr),
+            PromExpr::VectorSelector(vs) | PromExpr::MatrixSelector(MatrixSelector { vs, .. }) => {
+                self.collect_selector(vs)
+            }
+            PromExpr::Call(Call { args, .. }) => args.args.iter().all(|arg| self.collect(arg)),
+            PromExpr::NumberLiteral(_) | PromExpr::StringLiteral(_) => true,
+            PromExpr::Extension(_) => false,
+        }
+    }
+
+    fn collect_selector(&mut self, vs: &VectorSelector) -> bool {
+        // The result depends on the range of the query, or the query reads data
+        // newer than the evaluation timestamps.
+        if vs.at.is_some() || matches!(vs.offset, Some(Offset::Neg(_))) {
+            return false;
+        }
+        // The selector reads tables in another db.
+        if vs
+            .matchers
+            .matchers
+            .iter()
+            .any(|m| m.name == SCHEMA_LABEL || m.name == DATABASE_LABEL)
+        {
+            return false;
+        }
+
+        let name = vs.name.clone().or_else(|| {
+            vs.matchers
+                .matchers
+                .iter()
+                .find(|m| m.name == METRIC_NAME && m.op == MatchOp::Equal)
+                .map(|m| m.value.clone())
+        });
+        match name {
+            Some(name) if vs.matchers.or_matchers.is_empty() => {
+                for suffix in HISTOGRAM_SUFFIXES {
+                    self.names.insert(format!("{name}{suffix}"));
+                }
+                self.names.insert(name);
+            }
+            _ => self.unknown = true,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::Float64Vector;
+    use table::metadata::{TableInfoBuilder, TableMetaBuilder};
+
+    use super::*;
+
+    fn new_cache() -> PromqlResultCache {
+        PromqlResultCache::try_new(&PromqlCacheOptions {
+            enable: true,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    fn eval_stmt(query: &str, start: u64, end: u64, step: u64) -> EvalStmt {
+        EvalStmt {
+            expr: promql_parser::parser::parse(query).unwrap(),
+            start: UNIX_EPOCH + Duration::from_secs(start),
+            end: UNIX_EPOCH + Duration::from_secs(end),
+            interval: Duration::from_secs(step),
+            lookback_delta: Duration::from_secs(300),
+        }
+    }
+
+    fn new_batches() -> RecordBatches {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "v",
+            ConcreteDataType::float64_datatype(),
+            true,
+        )]));
+        RecordBatches::try_from_columns(
+            schema,
+            vec![Arc::new(Float64Vector::from_slice([1.0, 2.0])) as _],
+        )
+        .unwrap()
+    }
+
+    fn new_table_info(name: &str) -> TableInfo {
+        let schema = Arc::new(Schema::new(vec![]));
+        let meta = TableMetaBuilder::new_external_table()
+            .schema(schema)
+            .build()
+            .unwrap();
+        TableInfoBuilder::default()
+            .name(name)
+            .meta(meta)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_split_range() {
+        // Step aligned to the interval.
+        assert_eq!(
+            vec![
+                QueryChunk {
+                    start: 50,
+                    end: 90,
+                    cacheable: true,
+                },
+                QueryChunk {
+                    start: 100,
+                    end: 190,
+                    cacheable: true,
+                },
+                QueryChunk {
+                    start: 200,
+                    end: 250,
+                    cacheable: false,
+                },
+            ],
+            split_range(50, 250, 10, 100, 200)
+        );
+        // Step not aligned to the interval.
+        assert_eq!(
+            vec![
+                QueryChunk {
+                    start: 5,
+                    end: 89,
+                    cacheable: true,
+                },
+                QueryChunk {
+                    start: 110,
+                    end: 194,
+                    cacheable: true,
+                },
+                QueryChunk {
+                    start: 215,
+                    end: 299,
+                    cacheable: true,
+                },
+            ],
+            split_range(5, 300, 21, 100, 1000)
+        );
+        // Fresh chunks are merged.
+        assert_eq!(
+            vec![
+                QueryChunk {
+                    start: 0,
+                    end: 90,
+                    cacheable: true,
+                },
+                QueryChunk {
+                    start: 100,
+                    end: 300,
+                    cacheable: false,
+                },
+            ],
+            split_range(0, 300, 10, 100, 150)
+        );
+    }
+
+    #[test]
+    fn test_split_query() {
+        let cache = new_cache();
+        let now = UNIX_EPOCH + Duration::from_secs(4 * 3600 + 2000);
+
+        let split = cache
+            .split(
+                "public".to_string(),
+                &eval_stmt("sum(rate(http_requests[5m]))", 1800, 4 * 3600 + 1800, 60),
+                now,
+            )
+            .unwrap();
+        assert_eq!(5, split.chunks.len());
+        assert!(split.chunks[..4].iter().all(|c| c.cacheable));
+        assert!(!split.chunks[4].cacheable);
+        assert_eq!(3600 * 1000, split.chunks[1].start);
+        let tables = split.tables.as_ref().unwrap();
+        assert!(tables.contains("http_requests"));
+        assert!(tables.contains("http_requests_bucket"));
+
+        let split = cache
+            .split(
+                "public".to_string(),
+                &eval_stmt("{__name__=~\"http_.*\"}", 1800, 4 * 3600, 60),
+                now,
+            )
+            .unwrap();
+        assert!(split.tables.is_none());
+
+        for query in [
+            "http_requests @ 100",
+            "http_requests offset -5m",
+            "http_requests{__schema__=\"other\"}",
+            "rate(http_requests[5m] @ end())",
+        ] {
+            assert!(cache
+                .split(
+                    "public".to_string(),
+                    &eval_stmt(query, 1800, 4 * 3600, 60),
+                    now
+                )
+                .is_none());
+        }
+        // Instant query.
+        assert!(cache
+            .split(
+                "public".to_string(),
+                &eval_stmt("http_requests", 1800, 1800, 60),
+                now
+            )
+            .is_none());
+        // All chunks are fresh.
+        assert!(cache
+            .split(
+                "public".to_string(),
+                &eval_stmt("http_requests", 5 * 3600 - 300, 5 * 3600, 60),
+                now
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn test_cache_invalidation() {
+        let cache = new_cache();
+        let now = SystemTime::now();
+        let start = now - Duration::from_secs(6 * 3600);
+        let stmt = EvalStmt {
+            start,
+            end: now,
+            ..eval_stmt("http_requests", 0, 0, 60)
+        };
+        let split = cache.split("public".to_string(), &stmt, now).unwrap();
+        let chunk = split.chunks[0];
+        assert!(chunk.cacheable);
+
+        assert!(cache.get(&split, &chunk).is_none());
+        cache.put(&split, &chunk, &new_batches());
+        assert_eq!(new_batches(), cache.get(&split, &chunk).unwrap());
+
+        // Writes to other tables or fresh writes don't invalidate the result.
+        let now_millis = to_millis(SystemTime::now()).unwrap();
+        cache.on_inserted(&new_table_info("other"), 0);
+        cache.on_inserted(&new_table_info("http_requests"), now_millis);
+        assert!(cache.get(&split, &chunk).is_some());
+
+        // Late writes invalidate the result.
+        cache.on_inserted(&new_table_info("http_requests"), 0);
+        assert!(cache.get(&split, &chunk).is_none());
+
+        // Results of queries started before the invalidation are not cached.
+        cache.put(&split, &chunk, &new_batches());
+        assert!(cache.get(&split, &chunk).is_none());
+        let split = cache.split("public".to_string(), &stmt, now).unwrap();
+        cache.put(&split, &chunk, &new_batches());
+        assert!(cache.get(&split, &chunk).is_some());
+    }
+
+    #[test]
+    fn test_cache_expiration() {
+        let cache = PromqlResultCache::try_new(&PromqlCacheOptions {
+            enable: true,
+            time_to_live: Duration::from_millis(100),
+            ..Default::default()
+        })
+        .unwrap();
+        let now = SystemTime::now();
+        let stmt = EvalStmt {
+            start: now - Duration::from_secs(6 * 3600),
+            end: now,
+            ..eval_stmt("http_requests", 0, 0, 60)
+        };
+        let split = cache.split("public".to_string(), &stmt, now).unwrap();
+        let chunk = split.chunks[0];
+        cache.put(&split, &chunk, &new_batches());
+        assert!(cache.get(&split, &chunk).is_some());
+
+        std::thread::sleep(Duration::from_millis(200));
+        assert!(cache.get(&split, &chunk).is_none());
+
+        assert!(PromqlResultCache::try_new(&PromqlCacheOptions {
+            enable: true,
+            time_to_live: Duration::ZERO,
+            ..Default::default()
+        })
+        .is_none());
+    }
+}
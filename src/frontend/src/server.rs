@@ -106,6 +106,10 @@ where
                 .with_prometheus_handler(self.instance.clone());
         }
 
+        if let Some(manager) = self.instance.rule_manager() {
+            builder = builder.with_prometheus_rule_handler(manager.clone());
+        }
+
         if opts.otlp.enable {
             builder = builder
                 .with_otlp_handler(self.instance.clone(), opts.prom_store.with_metric_engine);
@@ -284,6 +288,7 @@ where
 
             let pg_server = Box::new(PostgresServer::new(
                 ServerSqlQueryHandlerAdapter::arc(instance.clone()),
+                Some(instance.clone()),
                 opts.tls.should_force_tls(),
                 tls_server_config,
                 opts.keep_alive.as_secs(),
@@ -36,6 +36,12 @@ pub enum Error {
 
     #[snafu(display("End time {end} is before start time {start}"))]
     EndBeforeStart { start: String, end: String },
+
+    #[snafu(display("Failed to parse LogQL at position {position}: {reason}"))]
+    ParseLogQL { position: usize, reason: String },
+
+    #[snafu(display("Unsupported LogQL feature: {feature}"))]
+    UnsupportedLogQL { feature: String },
 }
 
 impl ErrorExt for Error {
@@ -48,7 +54,9 @@ impl ErrorExt for Error {
             Error::InvalidTimeFilter { .. }
             | Error::InvalidDateFormat { .. }
             | Error::InvalidSpanFormat { .. }
-            | Error::EndBeforeStart { .. } => StatusCode::InvalidArguments,
+            | Error::EndBeforeStart { .. }
+            | Error::ParseLogQL { .. } => StatusCode::InvalidArguments,
+            Error::UnsupportedLogQL { .. } => StatusCode::Unsupported,
         }
     }
 }
@@ -21,6 +21,9 @@ use crate::error::{
     Result,
 };
 
+/// Name of the group key column generated for [`LogExpr::AggrFunc`] with a `range`.
+pub const RANGE_BUCKET_COLUMN: &str = "__range_bucket";
+
 /// GreptimeDB's log query request.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LogQuery {
@@ -31,6 +34,12 @@ pub struct LogQuery {
     pub time_filter: TimeFilter,
     /// Controls row skipping and fetch on the result set.
     pub limit: Limit,
+    /// Sorts the rows by the time index in this direction before applying `limit`,
+    /// so the limit keeps the earliest or the latest rows.
+    ///
+    /// The order of the rows is unspecified if this is `None`.
+    #[serde(default)]
+    pub direction: Option<Direction>,
     /// Columns to return in the result set.
     ///
     /// The columns can be either from the original log or derived from processing exprs.
@@ -107,6 +116,9 @@ pub enum LogExpr {
         name: String,
         args: Vec<LogExpr>,
         /// Optional range function parameter. Stands for the time range for both step and align.
+        ///
+        /// When presents, the time index is bucketed by this range (an interval string like
+        /// "5 minutes") and the bucket is added to the group keys as [`RANGE_BUCKET_COLUMN`].
         range: Option<String>,
         by: Vec<LogExpr>,
         alias: Option<String>,
@@ -139,6 +151,7 @@ impl Default for LogQuery {
             time_filter: Default::default(),
             filters: Filters::And(vec![]),
             limit: Limit::default(),
+            direction: None,
             context: Default::default(),
             columns: vec![],
             exprs: vec![],
@@ -456,6 +469,17 @@ pub enum Context {
     Seconds(usize, usize),
 }
 
+/// Order of log rows by the time index.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    /// From the earliest to the latest.
+    Forward,
+    /// From the latest to the earliest.
+    #[default]
+    Backward,
+}
+
 /// Represents limit and offset parameters for query pagination.
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Limit {
@@ -0,0 +1,1209 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A subset of [LogQL] that can be lowered into [`LogQuery`].
+//!
+//! Supported syntax:
+//! - Stream selectors: `{app="foo", env!="dev", pod=~"api-.*", ns!~"kube-.*"}`
+//! - Line filters: `|= "text"`, `!= "text"`, `|~ "regex"`, `!~ "regex"`
+//! - Parsers: `| json`, `| logfmt`, followed by label filters like `| level="error"`
+//! - Range aggregations: `count_over_time`, `rate`, `bytes_over_time`, `bytes_rate`
+//! - Vector aggregations: `sum`, `avg`, `min`, `max`, `count` with an optional `by (...)`
+//!
+//! [LogQL]: https://grafana.com/docs/loki/latest/query/
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use table::table_name::TableName;
+
+use crate::error::{ParseLogQLSnafu, Result, UnsupportedLogQLSnafu};
+use crate::{
+    ColumnFilters, ContentFilter, Direction, EqualValue, Filters, Limit, LogExpr, LogQuery,
+    TimeFilter, RANGE_BUCKET_COLUMN,
+};
+
+/// The column that stores the log line.
+pub const LINE_COLUMN: &str = "line";
+/// Alias of the value column produced by metric queries.
+pub const METRIC_VALUE_COLUMN: &str = "__logql_value";
+
+/// A parsed LogQL query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogQLExpr {
+    /// Log query that returns log lines.
+    Log(LogSelector),
+    /// Metric query that returns samples.
+    Metric(MetricExpr),
+}
+
+/// Stream selector with its log pipeline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogSelector {
+    pub matchers: Vec<LabelMatcher>,
+    pub stages: Vec<PipelineStage>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelMatcher {
+    pub name: String,
+    pub op: MatchOp,
+    pub value: String,
+}
+
+/// Match operators shared by label matchers and line filters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchOp {
+    /// `=` for labels, `|=` for lines.
+    Equal,
+    /// `!=` for both labels and lines.
+    NotEqual,
+    /// `=~` for labels, `|~` for lines.
+    Regex,
+    /// `!~` for both labels and lines.
+    NotRegex,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PipelineStage {
+    LineFilter { op: MatchOp, value: String },
+    Parser(LogParser),
+    LabelFilter(LabelMatcher),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogParser {
+    Json,
+    Logfmt,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetricExpr {
+    Range {
+        func: RangeFunction,
+        selector: LogSelector,
+        range: Duration,
+    },
+    Vector {
+        op: VectorOp,
+        grouping: Vec<String>,
+        inner: Box<MetricExpr>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeFunction {
+    CountOverTime,
+    Rate,
+    BytesOverTime,
+    BytesRate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorOp {
+    Sum,
+    Avg,
+    Min,
+    Max,
+    Count,
+}
+
+impl RangeFunction {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "count_over_time" => Some(Self::CountOverTime),
+            "rate" => Some(Self::Rate),
+            "bytes_over_time" => Some(Self::BytesOverTime),
+            "bytes_rate" => Some(Self::BytesRate),
+            _ => None,
+        }
+    }
+
+    fn is_rate(&self) -> bool {
+        matches!(self, Self::Rate | Self::BytesRate)
+    }
+}
+
+impl VectorOp {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "sum" => Some(Self::Sum),
+            "avg" => Some(Self::Avg),
+            "min" => Some(Self::Min),
+            "max" => Some(Self::Max),
+            "count" => Some(Self::Count),
+            _ => None,
+        }
+    }
+
+    /// Aggregates the values of a group at an evaluation time, `values` is never empty.
+    fn apply(&self, values: &[f64]) -> f64 {
+        match self {
+            Self::Sum => values.iter().sum(),
+            Self::Avg => values.iter().sum::<f64>() / values.len() as f64,
+            Self::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+            Self::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Self::Count => values.len() as f64,
+        }
+    }
+}
+
+impl MetricExpr {
+    /// Returns the range of the innermost range aggregation.
+    pub fn range(&self) -> Duration {
+        self.range_aggregation().2
+    }
+
+    /// Returns the innermost range aggregation.
+    fn range_aggregation(&self) -> (RangeFunction, &LogSelector, Duration) {
+        match self {
+            MetricExpr::Range {
+                func,
+                selector,
+                range,
+            } => (*func, selector, *range),
+            MetricExpr::Vector { inner, .. } => inner.range_aggregation(),
+        }
+    }
+
+    /// Returns the labels the range aggregation must group by, which are the stream
+    /// labels and every label vector aggregations group by.
+    fn range_labels(&self, stream_labels: &[String]) -> Vec<String> {
+        let mut labels = stream_labels.to_vec();
+        let mut expr = self;
+        while let MetricExpr::Vector {
+            grouping, inner, ..
+        } = expr
+        {
+            for label in grouping {
+                if !labels.contains(label) {
+                    labels.push(label.clone());
+                }
+            }
+            expr = inner;
+        }
+        labels
+    }
+
+    /// Returns the width of the buckets the range aggregation is evaluated on.
+    ///
+    /// Both the step and the range are multiples of the width, so every window
+    /// `(t - range, t]` of an evaluation time `t` is made of whole buckets.
+    pub fn bucket_width(&self, params: &EvalParams) -> Duration {
+        let range = self.range().as_millis().max(1);
+        let step = params.step.as_millis().max(1);
+        Duration::from_millis(gcd(range, step) as u64)
+    }
+
+    /// Returns the end (inclusive) of the bucket that starts at `bucket_start`, in nanoseconds.
+    ///
+    /// Buckets are aligned to the evaluation times, each of them covers `(end - width, end]`.
+    /// `bucket_start` may be truncated to the precision of the time index.
+    pub fn bucket_end(&self, params: &EvalParams, bucket_start: i64) -> i64 {
+        let width = self.bucket_width(params).as_nanos() as i64;
+        let origin = params.start_nanos() + 1;
+        let offset = bucket_start - origin;
+        let mut index = offset.div_euclid(width);
+        if offset.rem_euclid(width) != 0 {
+            index += 1;
+        }
+        params.start_nanos() + (index + 1) * width
+    }
+
+    /// Lowers the range aggregation into expressions that aggregate each stream
+    /// in buckets of [`MetricExpr::bucket_width`].
+    ///
+    /// The buckets are merged into windows and vector aggregations are applied by [`MetricExpr::eval`].
+    fn lower(
+        &self,
+        params: &EvalParams,
+        time_index: &str,
+        stream_labels: &[String],
+    ) -> Result<(Filters, Vec<LogExpr>)> {
+        let (func, selector, _) = self.range_aggregation();
+        let filters = selector.to_filters()?;
+        let (name, arg) = match func {
+            RangeFunction::CountOverTime | RangeFunction::Rate => {
+                ("count", LogExpr::NamedIdent(LINE_COLUMN.to_string()))
+            }
+            RangeFunction::BytesOverTime | RangeFunction::BytesRate => (
+                "sum",
+                LogExpr::ScalarFunc {
+                    name: "octet_length".to_string(),
+                    args: vec![LogExpr::NamedIdent(LINE_COLUMN.to_string())],
+                    alias: None,
+                },
+            ),
+        };
+        // Buckets start one nanosecond after an evaluation time, so they are left-open
+        // and right-closed like the windows.
+        let origin = params.start + chrono::Duration::nanoseconds(1);
+        let bucket = LogExpr::ScalarFunc {
+            name: "date_bin".to_string(),
+            args: vec![
+                LogExpr::Literal(format!(
+                    "{} milliseconds",
+                    self.bucket_width(params).as_millis()
+                )),
+                LogExpr::NamedIdent(time_index.to_string()),
+                LogExpr::Literal(origin.to_rfc3339_opts(SecondsFormat::Nanos, true)),
+            ],
+            alias: Some(RANGE_BUCKET_COLUMN.to_string()),
+        };
+        let by = std::iter::once(bucket)
+            .chain(
+                self.range_labels(stream_labels)
+                    .into_iter()
+                    .map(LogExpr::NamedIdent),
+            )
+            .collect();
+        let expr = LogExpr::AggrFunc {
+            name: name.to_string(),
+            args: vec![arg],
+            range: None,
+            by,
+            alias: Some(METRIC_VALUE_COLUMN.to_string()),
+        };
+        Ok((filters, vec![expr]))
+    }
+
+    /// Evaluates this expression at every evaluation time of `params`.
+    ///
+    /// `buckets` are the values of the range aggregation of each stream, keyed by
+    /// [`MetricExpr::bucket_end`]. Returns the samples of each output series.
+    pub fn eval(&self, params: &EvalParams, buckets: &SeriesSamples) -> SeriesSamples {
+        match self {
+            MetricExpr::Range { func, range, .. } => {
+                let range = range.as_nanos() as i64;
+                let divisor = if func.is_rate() {
+                    range as f64 / 1_000_000_000.0
+                } else {
+                    1.0
+                };
+                buckets
+                    .iter()
+                    .filter_map(|(labels, values)| {
+                        let samples = params
+                            .eval_times()
+                            .filter_map(|t| {
+                                let mut window = values.range(t - range + 1..=t).peekable();
+                                window.peek()?;
+                                Some((t, window.map(|(_, v)| v).sum::<f64>() / divisor))
+                            })
+                            .collect::<BTreeMap<_, _>>();
+                        (!samples.is_empty()).then(|| (labels.clone(), samples))
+                    })
+                    .collect()
+            }
+            MetricExpr::Vector {
+                op,
+                grouping,
+                inner,
+            } => {
+                let mut groups: BTreeMap<_, BTreeMap<i64, Vec<f64>>> = BTreeMap::new();
+                for (labels, samples) in inner.eval(params, buckets) {
+                    let group = labels
+                        .into_iter()
+                        .filter(|(name, _)| grouping.contains(name))
+                        .collect::<BTreeMap<_, _>>();
+                    let group = groups.entry(group).or_default();
+                    for (t, value) in samples {
+                        group.entry(t).or_default().push(value);
+                    }
+                }
+                groups
+                    .into_iter()
+                    .map(|(labels, samples)| {
+                        let samples = samples
+                            .into_iter()
+                            .map(|(t, values)| (t, op.apply(&values)))
+                            .collect();
+                        (labels, samples)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+impl LogSelector {
+    /// Returns the filters of the stream selector and its line and label filters.
+    pub fn to_filters(&self) -> Result<Filters> {
+        let mut filters = self
+            .matchers
+            .iter()
+            .map(|matcher| {
+                matcher_to_filters(
+                    LogExpr::NamedIdent(matcher.name.clone()),
+                    matcher.op,
+                    matcher.value.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut parser = None;
+        for stage in &self.stages {
+            match stage {
+                PipelineStage::LineFilter { op, value } => {
+                    let line = LogExpr::NamedIdent(LINE_COLUMN.to_string());
+                    let filter = match op {
+                        MatchOp::Equal | MatchOp::NotEqual => {
+                            column_filters(line, ContentFilter::Contains(value.clone()))
+                        }
+                        MatchOp::Regex | MatchOp::NotRegex => {
+                            column_filters(line, ContentFilter::Regex(value.clone()))
+                        }
+                    };
+                    filters.push(negate_if(filter, *op));
+                }
+                PipelineStage::Parser(p) => parser = Some(*p),
+                PipelineStage::LabelFilter(matcher) => {
+                    filters.push(label_filter_after_parser(parser, matcher)?);
+                }
+            }
+        }
+
+        Ok(Filters::And(filters))
+    }
+}
+
+impl LogQLExpr {
+    /// Parses a LogQL query.
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut parser = Parser::new(input);
+        let expr = parser.parse_expr()?;
+        parser.skip_whitespace();
+        if !parser.is_eof() {
+            return parser.error("unexpected trailing characters");
+        }
+        Ok(expr)
+    }
+
+    pub fn is_metric(&self) -> bool {
+        matches!(self, LogQLExpr::Metric(_))
+    }
+
+    /// Lowers this query into a [`LogQuery`] over `table`.
+    ///
+    /// `time_index` is the time index column and `stream_labels` are the label columns of
+    /// the table. Range aggregations group by them to produce one series per stream.
+    pub fn to_log_query(
+        &self,
+        table: TableName,
+        params: &EvalParams,
+        time_index: &str,
+        stream_labels: &[String],
+    ) -> Result<LogQuery> {
+        match self {
+            LogQLExpr::Log(selector) => Ok(LogQuery {
+                table,
+                time_filter: time_filter(params.start, params.end),
+                limit: Limit {
+                    skip: None,
+                    fetch: Some(params.limit),
+                },
+                direction: Some(params.direction),
+                filters: selector.to_filters()?,
+                ..Default::default()
+            }),
+            LogQLExpr::Metric(metric) => {
+                let (filters, exprs) = metric.lower(params, time_index, stream_labels)?;
+                // The first window covers `(start - range, start]`.
+                let start = params.start
+                    - chrono::Duration::from_std(metric.range()).unwrap_or_default()
+                    + chrono::Duration::nanoseconds(1);
+                Ok(LogQuery {
+                    table,
+                    time_filter: time_filter(start, params.end),
+                    // The limit is applied before aggregations, every row must be kept.
+                    limit: Limit {
+                        skip: None,
+                        fetch: Some(usize::MAX),
+                    },
+                    filters,
+                    exprs,
+                    ..Default::default()
+                })
+            }
+        }
+    }
+}
+
+/// Samples of series, from the labels of a series to its values keyed by nanosecond timestamps.
+pub type SeriesSamples = BTreeMap<BTreeMap<String, String>, BTreeMap<i64, f64>>;
+
+/// Parameters to evaluate a [`LogQLExpr`].
+#[derive(Debug, Clone)]
+pub struct EvalParams {
+    /// Start of the logs to return, or the first evaluation time of a metric query.
+    pub start: DateTime<Utc>,
+    /// End (inclusive) of the logs to return, or the last evaluation time of a metric query.
+    pub end: DateTime<Utc>,
+    /// Interval between two evaluation times of a metric query.
+    pub step: Duration,
+    /// Max number of log lines to return.
+    pub limit: usize,
+    /// Order of the log lines to return.
+    pub direction: Direction,
+}
+
+impl EvalParams {
+    fn start_nanos(&self) -> i64 {
+        self.start.timestamp_nanos_opt().unwrap_or_default()
+    }
+
+    /// Returns the evaluation times in nanoseconds, from `start` to `end` by `step`.
+    pub fn eval_times(&self) -> impl Iterator<Item = i64> {
+        let start = self.start_nanos();
+        let end = self.end.timestamp_nanos_opt().unwrap_or_default();
+        let step = (self.step.as_nanos() as i64).max(1);
+        (0..)
+            .map(move |i| start + i * step)
+            .take_while(move |t| *t <= end)
+    }
+}
+
+fn time_filter(start: DateTime<Utc>, end: DateTime<Utc>) -> TimeFilter {
+    TimeFilter {
+        start: Some(start.to_rfc3339_opts(SecondsFormat::Nanos, true)),
+        end: Some(end.to_rfc3339_opts(SecondsFormat::Nanos, true)),
+        span: None,
+    }
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn column_filters(expr: LogExpr, filter: ContentFilter) -> Filters {
+    Filters::Single(ColumnFilters {
+        expr: Box::new(expr),
+        filters: vec![filter],
+    })
+}
+
+fn negate_if(filters: Filters, op: MatchOp) -> Filters {
+    match op {
+        MatchOp::NotEqual | MatchOp::NotRegex => Filters::Not(Box::new(filters)),
+        MatchOp::Equal | MatchOp::Regex => filters,
+    }
+}
+
+/// Builds the filter of a label matcher. Label regexes are fully anchored in LogQL.
+fn matcher_to_filters(expr: LogExpr, op: MatchOp, value: String) -> Filters {
+    let filter = match op {
+        MatchOp::Equal | MatchOp::NotEqual => ContentFilter::Equal(EqualValue::String(value)),
+        MatchOp::Regex | MatchOp::NotRegex => ContentFilter::Regex(format!("^(?:{value})$")),
+    };
+    negate_if(column_filters(expr, filter), op)
+}
+
+/// Builds the filter of a label filter stage.
+///
+/// Without a parser the label refers to a stream label column. After `| json`
+/// the label is read from the line with `json_get_string`, and after `| logfmt`
+/// only equality is supported and is matched against the raw line.
+fn label_filter_after_parser(parser: Option<LogParser>, matcher: &LabelMatcher) -> Result<Filters> {
+    match parser {
+        None => Ok(matcher_to_filters(
+            LogExpr::NamedIdent(matcher.name.clone()),
+            matcher.op,
+            matcher.value.clone(),
+        )),
+        Some(LogParser::Json) => {
+            let expr = LogExpr::ScalarFunc {
+                name: "json_get_string".to_string(),
+                args: vec![
+                    LogExpr::ScalarFunc {
+                        name: "parse_json".to_string(),
+                        args: vec![LogExpr::NamedIdent(LINE_COLUMN.to_string())],
+                        alias: None,
+                    },
+                    LogExpr::Literal(matcher.name.clone()),
+                ],
+                alias: None,
+            };
+            Ok(matcher_to_filters(expr, matcher.op, matcher.value.clone()))
+        }
+        Some(LogParser::Logfmt) => match matcher.op {
+            MatchOp::Equal | MatchOp::NotEqual => {
+                let key = escape_regex(&matcher.name);
+                let value = escape_regex(&matcher.value);
+                let pattern = format!(r#"(^|\s){key}=("{value}"|{value})(\s|$)"#);
+                Ok(negate_if(
+                    column_filters(
+                        LogExpr::NamedIdent(LINE_COLUMN.to_string()),
+                        ContentFilter::Regex(pattern),
+                    ),
+                    matcher.op,
+                ))
+            }
+            MatchOp::Regex | MatchOp::NotRegex => UnsupportedLogQLSnafu {
+                feature: "regex label filter after logfmt parser",
+            }
+            .fail(),
+        },
+    }
+}
+
+fn escape_regex(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Parses a Prometheus style duration like `5m`, `1h30m` or `500ms`.
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    let mut total = Duration::ZERO;
+    let mut rest = s;
+    if rest.is_empty() {
+        return None;
+    }
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if digits == 0 {
+            return None;
+        }
+        let value: u64 = rest[..digits].parse().ok()?;
+        rest = &rest[digits..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let unit = match &rest[..unit_len] {
+            "ms" => Duration::from_millis(1),
+            "s" => Duration::from_secs(1),
+            "m" => Duration::from_secs(60),
+            "h" => Duration::from_secs(60 * 60),
+            "d" => Duration::from_secs(24 * 60 * 60),
+            "w" => Duration::from_secs(7 * 24 * 60 * 60),
+            "y" => Duration::from_secs(365 * 24 * 60 * 60),
+            _ => return None,
+        };
+        total += unit.checked_mul(u32::try_from(value).ok()?)?;
+        rest = &rest[unit_len..];
+    }
+    Some(total)
+}
+
+/// A hand-written recursive descent parser for LogQL.
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    fn error<T>(&self, reason: &str) -> Result<T> {
+        ParseLogQLSnafu {
+            position: self.pos,
+            reason,
+        }
+        .fail()
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn is_eof(&self) -> bool {
+        self.pos >= self.input.len()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let trimmed = self.rest().trim_start();
+        self.pos = self.input.len() - trimmed.len();
+    }
+
+    /// Consumes `token` if the remaining input starts with it.
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<()> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            self.error(&format!("expected `{token}`"))
+        }
+    }
+
+    fn peek_ident(&mut self) -> Option<&'a str> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        if len == 0 || rest.starts_with(|c: char| c.is_ascii_digit()) {
+            None
+        } else {
+            Some(&rest[..len])
+        }
+    }
+
+    fn parse_ident(&mut self) -> Result<String> {
+        match self.peek_ident() {
+            Some(ident) => {
+                self.pos += ident.len();
+                Ok(ident.to_string())
+            }
+            None => self.error("expected identifier"),
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String> {
+        self.skip_whitespace();
+        let mut chars = self.rest().char_indices();
+        let quote = match chars.next() {
+            Some((_, c)) if c == '"' || c == '`' => c,
+            _ => return self.error("expected string literal"),
+        };
+
+        let mut value = String::new();
+        let mut escaped = false;
+        for (idx, c) in chars {
+            if escaped {
+                match c {
+                    'n' => value.push('\n'),
+                    't' => value.push('\t'),
+                    'r' => value.push('\r'),
+                    // Keep the backslash for regex escapes like `\d`.
+                    '"' | '\\' => value.push(c),
+                    _ => {
+                        value.push('\\');
+                        value.push(c);
+                    }
+                }
+                escaped = false;
+            } else if c == '\\' && quote == '"' {
+                escaped = true;
+            } else if c == quote {
+                self.pos += idx + c.len_utf8();
+                return Ok(value);
+            } else {
+                value.push(c);
+            }
+        }
+        self.error("unterminated string literal")
+    }
+
+    fn parse_expr(&mut self) -> Result<LogQLExpr> {
+        self.skip_whitespace();
+        if self.rest().starts_with('{') {
+            return Ok(LogQLExpr::Log(self.parse_selector()?));
+        }
+        Ok(LogQLExpr::Metric(self.parse_metric()?))
+    }
+
+    fn parse_metric(&mut self) -> Result<MetricExpr> {
+        let name = self.parse_ident()?;
+        if let Some(func) = RangeFunction::from_name(&name) {
+            self.expect("(")?;
+            let selector = self.parse_selector()?;
+            self.expect("[")?;
+            let end = match self.rest().find(']') {
+                Some(end) => end,
+                None => return self.error("expected `]`"),
+            };
+            let range_str = self.rest()[..end].trim();
+            let Some(range) = parse_duration(range_str) else {
+                return self.error(&format!("invalid range `{range_str}`"));
+            };
+            self.pos += end + 1;
+            // Pipeline stages are also allowed after the range.
+            let mut selector = selector;
+            selector.stages.extend(self.parse_stages()?);
+            self.expect(")")?;
+            return Ok(MetricExpr::Range {
+                func,
+                selector,
+                range,
+            });
+        }
+
+        if let Some(op) = VectorOp::from_name(&name) {
+            let mut grouping = self.parse_grouping()?;
+            self.expect("(")?;
+            let inner = self.parse_metric()?;
+            self.expect(")")?;
+            if grouping.is_none() {
+                grouping = self.parse_grouping()?;
+            }
+            return Ok(MetricExpr::Vector {
+                op,
+                grouping: grouping.unwrap_or_default(),
+                inner: Box::new(inner),
+            });
+        }
+
+        UnsupportedLogQLSnafu {
+            feature: format!("function `{name}`"),
+        }
+        .fail()
+    }
+
+    fn parse_grouping(&mut self) -> Result<Option<Vec<String>>> {
+        match self.peek_ident() {
+            Some("by") => {
+                self.pos += 2;
+                self.expect("(")?;
+                let mut labels = vec![];
+                if !self.eat(")") {
+                    loop {
+                        labels.push(self.parse_ident()?);
+                        if self.eat(")") {
+                            break;
+                        }
+                        self.expect(",")?;
+                    }
+                }
+                Ok(Some(labels))
+            }
+            Some("without") => UnsupportedLogQLSnafu {
+                feature: "`without` grouping",
+            }
+            .fail(),
+            _ => Ok(None),
+        }
+    }
+
+    fn parse_selector(&mut self) -> Result<LogSelector> {
+        self.expect("{")?;
+        let mut matchers = vec![];
+        if !self.eat("}") {
+            loop {
+                matchers.push(self.parse_matcher()?);
+                if self.eat("}") {
+                    break;
+                }
+                self.expect(",")?;
+            }
+        }
+        if matchers.is_empty() {
+            return self.error("stream selector must contain at least one matcher");
+        }
+        let stages = self.parse_stages()?;
+        Ok(LogSelector { matchers, stages })
+    }
+
+    fn parse_match_op(&mut self) -> Result<MatchOp> {
+        if self.eat("=~") {
+            Ok(MatchOp::Regex)
+        } else if self.eat("!~") {
+            Ok(MatchOp::NotRegex)
+        } else if self.eat("!=") {
+            Ok(MatchOp::NotEqual)
+        } else if self.eat("=") {
+            Ok(MatchOp::Equal)
+        } else {
+            self.error("expected one of `=`, `!=`, `=~`, `!~`")
+        }
+    }
+
+    fn parse_matcher(&mut self) -> Result<LabelMatcher> {
+        let name = self.parse_ident()?;
+        let op = self.parse_match_op()?;
+        let value = self.parse_string()?;
+        Ok(LabelMatcher { name, op, value })
+    }
+
+    fn parse_stages(&mut self) -> Result<Vec<PipelineStage>> {
+        let mut stages = vec![];
+        loop {
+            let op = if self.eat("|=") {
+                MatchOp::Equal
+            } else if self.eat("|~") {
+                MatchOp::Regex
+            } else if self.eat("!=") {
+                MatchOp::NotEqual
+            } else if self.eat("!~") {
+                MatchOp::NotRegex
+            } else if self.eat("|") {
+                stages.push(self.parse_pipe_stage()?);
+                continue;
+            } else {
+                return Ok(stages);
+            };
+            let value = self.parse_string()?;
+            stages.push(PipelineStage::LineFilter { op, value });
+        }
+    }
+
+    fn parse_pipe_stage(&mut self) -> Result<PipelineStage> {
+        let name = self.parse_ident()?;
+        match name.as_str() {
+            "json" => Ok(PipelineStage::Parser(LogParser::Json)),
+            "logfmt" => Ok(PipelineStage::Parser(LogParser::Logfmt)),
+            "line_format" | "label_format" | "unwrap" | "pattern" | "regexp" | "unpack"
+            | "decolorize" | "drop" | "keep" => UnsupportedLogQLSnafu {
+                feature: format!("`{name}` stage"),
+            }
+            .fail(),
+            _ => {
+                let op = self.parse_match_op()?;
+                let value = self.parse_string()?;
+                Ok(PipelineStage::LabelFilter(LabelMatcher { name, op, value }))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    fn selector(matchers: Vec<LabelMatcher>, stages: Vec<PipelineStage>) -> LogSelector {
+        LogSelector { matchers, stages }
+    }
+
+    fn matcher(name: &str, op: MatchOp, value: &str) -> LabelMatcher {
+        LabelMatcher {
+            name: name.to_string(),
+            op,
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("5m"), Some(Duration::from_secs(300)));
+        assert_eq!(parse_duration("1h30m"), Some(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("500ms"), Some(Duration::from_millis(500)));
+        assert_eq!(parse_duration("1d"), Some(Duration::from_secs(86400)));
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("5"), None);
+        assert_eq!(parse_duration("m"), None);
+        assert_eq!(parse_duration("5x"), None);
+    }
+
+    #[test]
+    fn test_parse_log_query() {
+        let expr = LogQLExpr::parse(
+            r#"{app="foo", env!="dev", pod=~"api-.*"} |= "error" != `timeout` |~ "code=\\d+" | json | level="warn""#,
+        )
+        .unwrap();
+        assert_eq!(
+            expr,
+            LogQLExpr::Log(selector(
+                vec![
+                    matcher("app", MatchOp::Equal, "foo"),
+                    matcher("env", MatchOp::NotEqual, "dev"),
+                    matcher("pod", MatchOp::Regex, "api-.*"),
+                ],
+                vec![
+                    PipelineStage::LineFilter {
+                        op: MatchOp::Equal,
+                        value: "error".to_string()
+                    },
+                    PipelineStage::LineFilter {
+                        op: MatchOp::NotEqual,
+                        value: "timeout".to_string()
+                    },
+                    PipelineStage::LineFilter {
+                        op: MatchOp::Regex,
+                        value: r"code=\d+".to_string()
+                    },
+                    PipelineStage::Parser(LogParser::Json),
+                    PipelineStage::LabelFilter(matcher("level", MatchOp::Equal, "warn")),
+                ]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_metric_query() {
+        let expr =
+            LogQLExpr::parse(r#"sum by (app) (rate({app=~"foo|bar"} |= "error" [5m]))"#).unwrap();
+        assert_eq!(
+            expr,
+            LogQLExpr::Metric(MetricExpr::Vector {
+                op: VectorOp::Sum,
+                grouping: vec!["app".to_string()],
+                inner: Box::new(MetricExpr::Range {
+                    func: RangeFunction::Rate,
+                    selector: selector(
+                        vec![matcher("app", MatchOp::Regex, "foo|bar")],
+                        vec![PipelineStage::LineFilter {
+                            op: MatchOp::Equal,
+                            value: "error".to_string()
+                        }]
+                    ),
+                    range: Duration::from_secs(300),
+                }),
+            })
+        );
+
+        // grouping after the inner expression
+        let expr = LogQLExpr::parse(r#"count(count_over_time({app="foo"}[1m])) by (env)"#).unwrap();
+        let LogQLExpr::Metric(MetricExpr::Vector { op, grouping, .. }) = expr else {
+            unreachable!()
+        };
+        assert_eq!(op, VectorOp::Count);
+        assert_eq!(grouping, vec!["env".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert!(matches!(
+            LogQLExpr::parse(r#"{app="foo""#),
+            Err(Error::ParseLogQL { .. })
+        ));
+        assert!(matches!(
+            LogQLExpr::parse(r#"{}"#),
+            Err(Error::ParseLogQL { .. })
+        ));
+        assert!(matches!(
+            LogQLExpr::parse(r#"rate({app="foo"}[5x])"#),
+            Err(Error::ParseLogQL { .. })
+        ));
+        assert!(matches!(
+            LogQLExpr::parse(r#"quantile_over_time(0.9, {app="foo"}[5m])"#),
+            Err(Error::UnsupportedLogQL { .. })
+        ));
+        assert!(matches!(
+            LogQLExpr::parse(r#"sum without (app) (rate({app="foo"}[5m]))"#),
+            Err(Error::UnsupportedLogQL { .. })
+        ));
+        assert!(matches!(
+            LogQLExpr::parse(r#"{app="foo"} | line_format "{{.msg}}""#),
+            Err(Error::UnsupportedLogQL { .. })
+        ));
+    }
+
+    #[test]
+    fn test_lower_log_query() {
+        let expr =
+            LogQLExpr::parse(r#"{app="foo", pod!~"api"} |= "error" | logfmt | level="warn""#)
+                .unwrap();
+        let params = EvalParams {
+            limit: 100,
+            direction: Direction::Forward,
+            ..eval_params(0, 3600, 60)
+        };
+        let query = expr
+            .to_log_query(
+                TableName::new("greptime", "public", "loki_logs"),
+                &params,
+                "ts",
+                &[],
+            )
+            .unwrap();
+        assert_eq!(query.limit.fetch, Some(100));
+        assert_eq!(query.direction, Some(Direction::Forward));
+        assert_eq!(
+            query.time_filter.start.as_deref(),
+            Some("1970-01-01T00:00:00.000000000Z")
+        );
+        assert_eq!(
+            query.time_filter.end.as_deref(),
+            Some("1970-01-01T01:00:00.000000000Z")
+        );
+        assert!(query.exprs.is_empty());
+        let Filters::And(filters) = query.filters else {
+            unreachable!()
+        };
+        assert_eq!(filters.len(), 4);
+        assert!(matches!(
+            &filters[0],
+            Filters::Single(ColumnFilters { filters, .. })
+                if matches!(&filters[0], ContentFilter::Equal(EqualValue::String(v)) if v == "foo")
+        ));
+        assert!(matches!(
+            &filters[1],
+            Filters::Not(inner)
+                if matches!(inner.as_ref(), Filters::Single(ColumnFilters { filters, .. })
+                    if matches!(&filters[0], ContentFilter::Regex(v) if v == "^(?:api)$"))
+        ));
+        assert!(matches!(
+            &filters[2],
+            Filters::Single(ColumnFilters { filters, .. })
+                if matches!(&filters[0], ContentFilter::Contains(v) if v == "error")
+        ));
+        assert!(matches!(
+            &filters[3],
+            Filters::Single(ColumnFilters { filters, .. })
+                if matches!(&filters[0], ContentFilter::Regex(v) if v == r#"(^|\s)level=("warn"|warn)(\s|$)"#)
+        ));
+    }
+
+    #[test]
+    fn test_lower_label_filter_regex() {
+        let expr = LogQLExpr::parse(r#"{app="foo"} | level=~"err" | json | code!~"5.." "#).unwrap();
+        let selector = match &expr {
+            LogQLExpr::Log(selector) => selector,
+            _ => unreachable!(),
+        };
+        let Filters::And(filters) = selector.to_filters().unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(filters.len(), 3);
+        assert!(matches!(
+            &filters[1],
+            Filters::Single(ColumnFilters { filters, .. })
+                if matches!(&filters[0], ContentFilter::Regex(v) if v == "^(?:err)$")
+        ));
+        assert!(matches!(
+            &filters[2],
+            Filters::Not(inner)
+                if matches!(inner.as_ref(), Filters::Single(ColumnFilters { filters, .. })
+                    if matches!(&filters[0], ContentFilter::Regex(v) if v == "^(?:5..)$"))
+        ));
+    }
+
+    #[test]
+    fn test_lower_metric_query() {
+        let expr = LogQLExpr::parse(r#"sum by (app) (rate({app="foo"}[5m]))"#).unwrap();
+        let params = eval_params(3600, 7200, 60);
+        let query = expr
+            .to_log_query(
+                TableName::new("greptime", "public", "loki_logs"),
+                &params,
+                "ts",
+                &["env".to_string()],
+            )
+            .unwrap();
+        assert_eq!(query.limit.fetch, Some(usize::MAX));
+        assert_eq!(query.direction, None);
+        // The first window covers `(start - range, start]`.
+        assert_eq!(
+            query.time_filter.start.as_deref(),
+            Some("1970-01-01T00:55:00.000000001Z")
+        );
+        assert_eq!(query.exprs.len(), 1);
+        let LogExpr::AggrFunc {
+            name, range, by, ..
+        } = &query.exprs[0]
+        else {
+            unreachable!()
+        };
+        assert_eq!(name, "count");
+        assert!(range.is_none());
+        let [LogExpr::ScalarFunc { name, args, alias }, LogExpr::NamedIdent(a), LogExpr::NamedIdent(b)] =
+            by.as_slice()
+        else {
+            unreachable!("{by:?}")
+        };
+        assert_eq!(name, "date_bin");
+        assert_eq!(alias.as_deref(), Some(RANGE_BUCKET_COLUMN));
+        assert!(matches!(
+            args.as_slice(),
+            [LogExpr::Literal(width), LogExpr::NamedIdent(ts), LogExpr::Literal(origin)]
+                if width == "60000 milliseconds" && ts == "ts" && origin == "1970-01-01T01:00:00.000000001Z"
+        ));
+        assert_eq!((a.as_str(), b.as_str()), ("env", "app"));
+    }
+
+    fn eval_params(start_secs: i64, end_secs: i64, step_secs: u64) -> EvalParams {
+        EvalParams {
+            start: DateTime::from_timestamp(start_secs, 0).unwrap(),
+            end: DateTime::from_timestamp(end_secs, 0).unwrap(),
+            step: Duration::from_secs(step_secs),
+            limit: 100,
+            direction: Direction::Backward,
+        }
+    }
+
+    fn labels(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    const SEC: i64 = 1_000_000_000;
+
+    #[test]
+    fn test_bucket_end() {
+        let LogQLExpr::Metric(metric) =
+            LogQLExpr::parse(r#"count_over_time({app="foo"}[90s])"#).unwrap()
+        else {
+            unreachable!()
+        };
+        // Buckets of 30s aligned to the start at 100s.
+        let params = eval_params(100, 400, 60);
+        assert_eq!(metric.bucket_width(&params), Duration::from_secs(30));
+        // A bucket covers `(end - 30s, end]`, the start of (70s, 100s] is 70s + 1ns.
+        assert_eq!(metric.bucket_end(&params, 70 * SEC + 1), 100 * SEC);
+        assert_eq!(metric.bucket_end(&params, 100 * SEC + 1), 130 * SEC);
+        // The start may be truncated by a millisecond time index.
+        assert_eq!(metric.bucket_end(&params, 100 * SEC), 130 * SEC);
+        assert_eq!(metric.bucket_end(&params, 40 * SEC), 70 * SEC);
+    }
+
+    #[test]
+    fn test_eval_metric_query() {
+        let LogQLExpr::Metric(metric) =
+            LogQLExpr::parse(r#"sum by (app) (rate({app="foo"}[2m]))"#).unwrap()
+        else {
+            unreachable!()
+        };
+        // Evaluated at 120s, 180s and 240s, every window has two buckets of a minute.
+        let params = eval_params(120, 240, 60);
+        assert_eq!(params.eval_times().count(), 3);
+        let buckets = SeriesSamples::from([
+            (
+                labels(&[("app", "foo"), ("pod", "a")]),
+                BTreeMap::from([(60 * SEC, 60.0), (120 * SEC, 120.0), (180 * SEC, 60.0)]),
+            ),
+            (
+                labels(&[("app", "foo"), ("pod", "b")]),
+                BTreeMap::from([(240 * SEC, 120.0)]),
+            ),
+        ]);
+
+        let samples = metric.eval(&params, &buckets);
+        assert_eq!(
+            samples,
+            SeriesSamples::from([(
+                labels(&[("app", "foo")]),
+                BTreeMap::from([(120 * SEC, 1.5), (180 * SEC, 1.5), (240 * SEC, 1.5)]),
+            )])
+        );
+
+        let LogQLExpr::Metric(metric) =
+            LogQLExpr::parse(r#"max(count_over_time({app="foo"}[1m]))"#).unwrap()
+        else {
+            unreachable!()
+        };
+        let samples = metric.eval(&params, &buckets);
+        assert_eq!(
+            samples,
+            SeriesSamples::from([(
+                labels(&[]),
+                BTreeMap::from([(120 * SEC, 120.0), (180 * SEC, 60.0), (240 * SEC, 120.0)]),
+            )])
+        );
+    }
+}
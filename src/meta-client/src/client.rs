@@ -38,6 +38,7 @@ use common_meta::error::{
     self as meta_error, ExternalSnafu, Result as MetaResult, UnsupportedSnafu,
 };
 use common_meta::key::flow::flow_state::{FlowStat, FlowStateManager};
+use common_meta::key::region_balance::{RegionBalanceStateManager, RegionMove};
 use common_meta::kv_backend::KvBackendRef;
 use common_meta::procedure_executor::{ExecutorContext, ProcedureExecutor};
 use common_meta::range_stream::PaginationStream;
@@ -61,8 +62,8 @@ use store::Client as StoreClient;
 
 pub use self::heartbeat::{HeartbeatSender, HeartbeatStream};
 use crate::error::{
-    ConvertMetaRequestSnafu, ConvertMetaResponseSnafu, Error, GetFlowStatSnafu, NotStartedSnafu,
-    Result,
+    ConvertMetaRequestSnafu, ConvertMetaResponseSnafu, Error, GetFlowStatSnafu,
+    GetRegionBalanceStateSnafu, NotStartedSnafu, Result,
 };
 
 pub type Id = u64;
@@ -426,6 +427,18 @@ impl ClusterInfo for MetaClient {
 
         Ok(res.map(|r| r.into()))
     }
+
+    async fn list_region_moves(&self) -> Result<Vec<RegionMove>> {
+        let cluster_backend = ClusterKvBackend::new(Arc::new(self.cluster_client()?));
+        let cluster_backend = Arc::new(cluster_backend) as KvBackendRef;
+        let region_balance_state_manager = RegionBalanceStateManager::new(cluster_backend);
+        let res = region_balance_state_manager
+            .get()
+            .await
+            .context(GetRegionBalanceStateSnafu)?;
+
+        Ok(res.map(|r| r.moves).unwrap_or_default())
+    }
 }
 
 fn decode_stats(kv: KeyValue) -> MetaResult<DatanodeStatValue> {
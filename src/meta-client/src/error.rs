@@ -107,6 +107,13 @@ pub enum Error {
         source: common_meta::error::Error,
     },
 
+    #[snafu(display("Failed to get region balance state"))]
+    GetRegionBalanceState {
+        #[snafu(implicit)]
+        location: Location,
+        source: common_meta::error::Error,
+    },
+
     #[snafu(display("Retry exceeded max times({}), message: {}", times, msg))]
     RetryTimesExceeded { times: usize, msg: String },
 
@@ -143,7 +150,8 @@ impl ErrorExt for Error {
             Error::InvalidResponseHeader { source, .. }
             | Error::ConvertMetaRequest { source, .. }
             | Error::ConvertMetaResponse { source, .. }
-            | Error::GetFlowStat { source, .. } => source.status_code(),
+            | Error::GetFlowStat { source, .. }
+            | Error::GetRegionBalanceState { source, .. } => source.status_code(),
         }
     }
 }
@@ -960,6 +960,42 @@ pub enum Error {
         #[snafu(source)]
         source: common_meta::error::Error,
     },
+
+    #[snafu(display("Failed to operate region: {}", region_id))]
+    OperateRegion {
+        region_id: RegionId,
+        #[snafu(implicit)]
+        location: Location,
+        source: common_meta::error::Error,
+    },
+
+    #[snafu(display("Invalid partition expr: {}", expr))]
+    InvalidPartitionExpr {
+        expr: String,
+        #[snafu(implicit)]
+        location: Location,
+        source: partition::error::Error,
+    },
+
+    #[snafu(display(
+        "Failed to copy data from regions {:?} to region {}",
+        from_region_ids,
+        to_region_id
+    ))]
+    CopyRegionData {
+        from_region_ids: Vec<RegionId>,
+        to_region_id: RegionId,
+        #[snafu(implicit)]
+        location: Location,
+        source: common_meta::error::Error,
+    },
+
+    #[snafu(display("The engine of region {} doesn't support copying data", region_id))]
+    CopyRegionDataNotSupported {
+        region_id: RegionId,
+        #[snafu(implicit)]
+        location: Location,
+    },
 }
 
 impl Error {
@@ -1093,6 +1129,10 @@ impl ErrorExt for Error {
             | Error::InitReconciliationManager { source, .. } => source.status_code(),
 
             Error::Other { source, .. } => source.status_code(),
+            Error::OperateRegion { source, .. } => source.status_code(),
+            Error::InvalidPartitionExpr { source, .. } => source.status_code(),
+            Error::CopyRegionData { source, .. } => source.status_code(),
+            Error::CopyRegionDataNotSupported { .. } => StatusCode::Unsupported,
             Error::NoEnoughAvailableNode { .. } => StatusCode::RuntimeResourcesExhausted,
 
             #[cfg(feature = "pg_kvbackend")]
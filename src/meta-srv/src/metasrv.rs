@@ -72,6 +72,7 @@ use crate::procedure::region_migration::manager::RegionMigrationManagerRef;
 use crate::procedure::wal_prune::manager::WalPruneTickerRef;
 use crate::procedure::ProcedureManagerListenerAdapter;
 use crate::pubsub::{PublisherRef, SubscriptionManagerRef};
+use crate::region::balancer::RegionBalancerTickerRef;
 use crate::region::flush_trigger::RegionFlushTickerRef;
 use crate::region::supervisor::RegionSupervisorTickerRef;
 use crate::selector::{RegionStatAwareSelector, Selector, SelectorType};
@@ -120,6 +121,49 @@ impl Default for StatsPersistenceOptions {
     }
 }
 
+/// Configuration options for the region balancer.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RegionBalancerOptions {
+    /// Whether to enable the region balancer.
+    pub enable: bool,
+    /// Whether to only plan the region moves without migrating any region.
+    pub dry_run: bool,
+    /// The interval of each balancing round.
+    #[serde(with = "humantime_serde")]
+    pub interval: Duration,
+    /// The maximum number of in-flight region migrations triggered by the balancer.
+    pub max_concurrent_moves: usize,
+    /// The interval during which a region moved by the balancer won't be moved again.
+    #[serde(with = "humantime_serde")]
+    pub move_cooldown: Duration,
+    /// A datanode is considered overloaded if its load exceeds the average load
+    /// of all datanodes by this ratio.
+    pub imbalance_threshold: f64,
+    /// The weight of the region write rate in the region load.
+    pub write_rate_weight: f64,
+    /// The weight of the region SST size in the region load.
+    pub sst_size_weight: f64,
+    /// The weight of the region CPU usage (read and write capacity units) in the region load.
+    pub cpu_weight: f64,
+}
+
+impl Default for RegionBalancerOptions {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            dry_run: true,
+            interval: Duration::from_secs(5 * 60),
+            max_concurrent_moves: 1,
+            move_cooldown: Duration::from_secs(30 * 60),
+            imbalance_threshold: 0.2,
+            write_rate_weight: 1.0,
+            sst_size_weight: 1.0,
+            cpu_weight: 1.0,
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct MetasrvOptions {
@@ -209,6 +253,8 @@ pub struct MetasrvOptions {
     pub event_recorder: EventRecorderOptions,
     /// The stats persistence options.
     pub stats_persistence: StatsPersistenceOptions,
+    /// The region balancer options.
+    pub region_balancer: RegionBalancerOptions,
 }
 
 impl fmt::Debug for MetasrvOptions {
@@ -240,7 +286,8 @@ impl fmt::Debug for MetasrvOptions {
             .field("tracing", &self.tracing)
             .field("backend", &self.backend)
             .field("event_recorder", &self.event_recorder)
-            .field("stats_persistence", &self.stats_persistence);
+            .field("stats_persistence", &self.stats_persistence)
+            .field("region_balancer", &self.region_balancer);
 
         #[cfg(any(feature = "pg_kvbackend", feature = "mysql_kvbackend"))]
         debug_struct.field("meta_table_name", &self.meta_table_name);
@@ -307,6 +354,7 @@ impl Default for MetasrvOptions {
             node_max_idle_time: Duration::from_secs(24 * 60 * 60),
             event_recorder: EventRecorderOptions::default(),
             stats_persistence: StatsPersistenceOptions::default(),
+            region_balancer: RegionBalancerOptions::default(),
         }
     }
 }
@@ -496,6 +544,7 @@ pub struct Metasrv {
     topic_stats_registry: TopicStatsRegistryRef,
     wal_prune_ticker: Option<WalPruneTickerRef>,
     region_flush_ticker: Option<RegionFlushTickerRef>,
+    region_balancer_ticker: Option<RegionBalancerTickerRef>,
     table_id_sequence: SequenceRef,
     reconciliation_manager: ReconciliationManagerRef,
 
@@ -558,6 +607,9 @@ impl Metasrv {
             if let Some(region_flush_trigger) = &self.region_flush_ticker {
                 leadership_change_notifier.add_listener(region_flush_trigger.clone() as _);
             }
+            if let Some(region_balancer_ticker) = &self.region_balancer_ticker {
+                leadership_change_notifier.add_listener(region_balancer_ticker.clone() as _);
+            }
             if let Some(customizer) = self.plugins.get::<LeadershipChangeNotifierCustomizerRef>() {
                 customizer.customize(&mut leadership_change_notifier);
             }
@@ -32,6 +32,7 @@ use common_meta::ddl_manager::DdlManager;
 use common_meta::distributed_time_constants::{self};
 use common_meta::key::flow::flow_state::FlowStateManager;
 use common_meta::key::flow::FlowMetadataManager;
+use common_meta::key::region_balance::RegionBalanceStateManager;
 use common_meta::key::runtime_switch::{RuntimeSwitchManager, RuntimeSwitchManagerRef};
 use common_meta::key::TableMetadataManager;
 use common_meta::kv_backend::memory::MemoryKvBackend;
@@ -67,8 +68,12 @@ use crate::metasrv::{
 };
 use crate::procedure::region_migration::manager::RegionMigrationManager;
 use crate::procedure::region_migration::DefaultContextFactory;
+use crate::procedure::repartition::{
+    DefaultContextFactory as RepartitionContextFactory, RepartitionManager,
+};
 use crate::procedure::wal_prune::manager::{WalPruneManager, WalPruneTicker};
 use crate::procedure::wal_prune::Context as WalPruneContext;
+use crate::region::balancer::RegionBalancer;
 use crate::region::flush_trigger::RegionFlushTrigger;
 use crate::region::supervisor::{
     HeartbeatAcceptor, RegionFailureDetectorControl, RegionSupervisor, RegionSupervisorSelector,
@@ -401,9 +406,23 @@ impl MetasrvBuilder {
             flow_metadata_allocator: flow_metadata_allocator.clone(),
             region_failure_detector_controller,
         };
+        // repartition manager
+        let repartition_manager = RepartitionManager::new(
+            procedure_manager.clone(),
+            RepartitionContextFactory::new(
+                table_metadata_manager.clone(),
+                node_manager.clone(),
+                memory_region_keeper.clone(),
+                cache_invalidator.clone(),
+                mailbox.clone(),
+                options.grpc.server_addr.clone(),
+            ),
+        );
+        repartition_manager.try_start()?;
         let procedure_manager_c = procedure_manager.clone();
         let ddl_manager = DdlManager::try_new(ddl_context, procedure_manager_c, true)
-            .context(error::InitDdlManagerSnafu)?;
+            .context(error::InitDdlManagerSnafu)?
+            .with_repartition_ddl_manager(Some(Arc::new(repartition_manager)));
         #[cfg(feature = "enterprise")]
         let ddl_manager = {
             let trigger_ddl_manager = plugins.as_ref().and_then(|plugins| {
@@ -431,6 +450,21 @@ impl MetasrvBuilder {
             None
         };
 
+        let region_balancer_ticker = if options.region_balancer.enable {
+            let (region_balancer, region_balancer_ticker) = RegionBalancer::new(
+                meta_peer_client.clone(),
+                region_migration_manager.clone(),
+                runtime_switch_manager.clone(),
+                RegionBalanceStateManager::new(in_memory.clone().as_kv_backend_ref()),
+                options.region_balancer.clone(),
+            );
+            region_balancer.try_start()?;
+
+            Some(Arc::new(region_balancer_ticker))
+        } else {
+            None
+        };
+
         // remote WAL prune ticker and manager
         let wal_prune_ticker = if is_remote_wal && options.wal.enable_active_wal_pruning() {
             let (tx, rx) = WalPruneManager::channel();
@@ -558,6 +592,7 @@ impl MetasrvBuilder {
             leader_region_registry,
             wal_prune_ticker,
             region_flush_ticker,
+            region_balancer_ticker,
             table_id_sequence,
             reconciliation_manager,
             topic_stats_registry,
@@ -90,4 +90,13 @@ lazy_static! {
     /// The topic estimated replay size.
     pub static ref METRIC_META_TOPIC_ESTIMATED_REPLAY_SIZE: IntGaugeVec =
         register_int_gauge_vec!("meta_topic_estimated_replay_size", "meta topic estimated replay size", &["topic_name"]).unwrap();
+    /// The repartition execute histogram.
+    pub static ref METRIC_META_REPARTITION_EXECUTE: HistogramVec =
+        register_histogram_vec!("greptime_meta_repartition_execute", "meta repartition execute", &["state"]).unwrap();
+    /// The repartition error counter.
+    pub static ref METRIC_META_REPARTITION_ERROR: IntCounterVec =
+        register_int_counter_vec!("greptime_meta_repartition_error", "meta repartition error", &["state", "error_type"]).unwrap();
+    /// The region balancer moves counter.
+    pub static ref METRIC_META_REGION_BALANCER_MOVES: IntCounterVec =
+        register_int_counter_vec!("greptime_meta_region_balancer_moves", "meta region balancer moves", &["state"]).unwrap();
 }
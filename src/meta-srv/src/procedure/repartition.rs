@@ -0,0 +1,607 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The procedure to repartition a table, i.e., split a region into several regions
+//! or merge several regions into one.
+//!
+//! The procedure goes through the following states:
+//! - [RepartitionStart](repartition_start::RepartitionStart): validates the request and allocates the target regions.
+//! - [StageRegions](stage_regions::StageRegions): creates the target regions on datanodes.
+//! - [FenceSourceRegions](fence_source_regions::FenceSourceRegions): downgrades the source regions
+//!   to reject writes and flushes them.
+//! - [CopyRegionData](copy_region_data::CopyRegionData): copies the SST files of the source regions
+//!   into the target regions on the datanodes of the target regions.
+//! - [UpdateMetadata](update_metadata::UpdateMetadata): switches the table route to the target regions.
+//! - [DropSourceRegions](drop_source_regions::DropSourceRegions): drops the source regions.
+//!
+//! Writes to the table's source regions are rejected from the fencing until the table route is switched.
+
+pub(crate) mod copy_region_data;
+pub(crate) mod drop_source_regions;
+pub(crate) mod fence_source_regions;
+pub(crate) mod manager;
+pub(crate) mod repartition_end;
+pub(crate) mod repartition_start;
+pub(crate) mod stage_regions;
+pub(crate) mod update_metadata;
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use api::region::RegionResponse;
+use api::v1::region::{region_request, DropRequest, RegionRequest, RegionRequestHeader};
+use common_error::ext::{BoxedError, ErrorExt};
+use common_error::status_code::StatusCode;
+use common_meta::cache_invalidator::CacheInvalidatorRef;
+use common_meta::ddl::utils::add_peer_context_if_needed;
+use common_meta::instruction::CacheIdent;
+use common_meta::key::table_info::TableInfoValue;
+use common_meta::key::table_route::TableRouteValue;
+use common_meta::key::{DeserializedValueWithBytes, TableMetadataManagerRef};
+use common_meta::lock_key::{CatalogLock, SchemaLock, TableLock};
+use common_meta::node_manager::NodeManagerRef;
+use common_meta::peer::Peer;
+use common_meta::region_keeper::{MemoryRegionKeeperRef, OperatingRegionGuard};
+use common_meta::rpc::router::{LeaderState, RegionRoute};
+use common_procedure::error::{
+    Error as ProcedureError, FromJsonSnafu, Result as ProcedureResult, ToJsonSnafu,
+};
+use common_procedure::{Context as ProcedureContext, LockKey, Procedure, Status, StringKey};
+use common_telemetry::tracing_context::TracingContext;
+use common_telemetry::{error, info};
+pub use manager::RepartitionManager;
+use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, ResultExt};
+use store_api::storage::{RegionId, RegionNumber, TableId};
+
+use self::repartition_start::RepartitionStart;
+use crate::error::{self, Result};
+use crate::metrics::{METRIC_META_REPARTITION_ERROR, METRIC_META_REPARTITION_EXECUTE};
+use crate::service::mailbox::MailboxRef;
+
+/// The regions to be replaced and the regions replacing them.
+///
+/// It's allocated by the [RepartitionStart] state.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RepartitionPlan {
+    /// The routes of the regions to be replaced.
+    pub(crate) source_regions: Vec<RegionRoute>,
+    /// The routes of the new regions.
+    pub(crate) target_regions: Vec<RegionRoute>,
+    /// The wal options of the new regions.
+    pub(crate) target_region_wal_options: HashMap<RegionNumber, String>,
+}
+
+/// It's shared in each step and available even after recovering.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PersistentContext {
+    /// The table catalog.
+    pub(crate) catalog: String,
+    /// The table schema.
+    pub(crate) schema: String,
+    /// The table name.
+    pub(crate) table: String,
+    /// The table id.
+    pub(crate) table_id: TableId,
+    /// The JSON encoded partition exprs of the regions to be replaced.
+    pub(crate) from_partition_exprs: Vec<String>,
+    /// The JSON encoded partition exprs of the new regions.
+    pub(crate) into_partition_exprs: Vec<String>,
+    /// The plan allocated by the [RepartitionStart] state.
+    #[serde(default)]
+    pub(crate) plan: Option<RepartitionPlan>,
+    /// The target regions whose data has been copied by the
+    /// [CopyRegionData](copy_region_data::CopyRegionData) state.
+    #[serde(default)]
+    pub(crate) copied_regions: Vec<RegionId>,
+}
+
+impl PersistentContext {
+    pub fn lock_key(&self) -> Vec<StringKey> {
+        vec![
+            CatalogLock::Read(&self.catalog).into(),
+            SchemaLock::read(&self.catalog, &self.schema).into(),
+            TableLock::Write(self.table_id).into(),
+        ]
+    }
+}
+
+/// It's shared in each step and available in executing (including retrying).
+///
+/// It will be dropped if the procedure runner crashes.
+#[derive(Debug, Default)]
+pub struct VolatileContext {
+    /// `operating_region_guards` will be set in the [StageRegions](stage_regions::StageRegions) state.
+    ///
+    /// They should be consumed after the target regions were written into the table route.
+    operating_region_guards: Vec<OperatingRegionGuard>,
+    /// `table_route` is stored via previous steps for future use.
+    table_route: Option<DeserializedValueWithBytes<TableRouteValue>>,
+    /// `table_info` is stored via previous steps for future use.
+    table_info: Option<DeserializedValueWithBytes<TableInfoValue>>,
+}
+
+/// Used to generate new [Context].
+#[derive(Clone)]
+pub struct DefaultContextFactory {
+    table_metadata_manager: TableMetadataManagerRef,
+    node_manager: NodeManagerRef,
+    memory_region_keeper: MemoryRegionKeeperRef,
+    cache_invalidator: CacheInvalidatorRef,
+    mailbox: MailboxRef,
+    server_addr: String,
+}
+
+impl DefaultContextFactory {
+    /// Returns an [`DefaultContextFactory`].
+    pub fn new(
+        table_metadata_manager: TableMetadataManagerRef,
+        node_manager: NodeManagerRef,
+        memory_region_keeper: MemoryRegionKeeperRef,
+        cache_invalidator: CacheInvalidatorRef,
+        mailbox: MailboxRef,
+        server_addr: String,
+    ) -> Self {
+        Self {
+            table_metadata_manager,
+            node_manager,
+            memory_region_keeper,
+            cache_invalidator,
+            mailbox,
+            server_addr,
+        }
+    }
+
+    fn new_context(self, persistent_ctx: PersistentContext) -> Context {
+        Context {
+            persistent_ctx,
+            volatile_ctx: VolatileContext::default(),
+            table_metadata_manager: self.table_metadata_manager,
+            node_manager: self.node_manager,
+            memory_region_keeper: self.memory_region_keeper,
+            cache_invalidator: self.cache_invalidator,
+            mailbox: self.mailbox,
+            server_addr: self.server_addr,
+        }
+    }
+}
+
+/// The context of procedure execution.
+pub struct Context {
+    persistent_ctx: PersistentContext,
+    volatile_ctx: VolatileContext,
+    table_metadata_manager: TableMetadataManagerRef,
+    node_manager: NodeManagerRef,
+    memory_region_keeper: MemoryRegionKeeperRef,
+    cache_invalidator: CacheInvalidatorRef,
+    mailbox: MailboxRef,
+    server_addr: String,
+}
+
+impl Context {
+    /// Returns the table id.
+    pub fn table_id(&self) -> TableId {
+        self.persistent_ctx.table_id
+    }
+
+    /// Returns the [RepartitionPlan].
+    ///
+    /// Abort(non-retry):
+    /// - The plan hasn't been allocated.
+    pub fn plan(&self) -> Result<&RepartitionPlan> {
+        self.persistent_ctx
+            .plan
+            .as_ref()
+            .context(error::UnexpectedSnafu {
+                violated: "Repartition plan is not allocated",
+            })
+    }
+
+    /// Returns the `table_route` of [VolatileContext] if any.
+    /// Otherwise, returns the value retrieved from remote.
+    ///
+    /// Retry:
+    /// - Failed to retrieve the metadata of table.
+    pub async fn get_table_route_value(
+        &mut self,
+    ) -> Result<&DeserializedValueWithBytes<TableRouteValue>> {
+        let table_route_value = &mut self.volatile_ctx.table_route;
+
+        if table_route_value.is_none() {
+            let table_id = self.persistent_ctx.table_id;
+            let table_route = self
+                .table_metadata_manager
+                .table_route_manager()
+                .table_route_storage()
+                .get_with_raw_bytes(table_id)
+                .await
+                .context(error::TableMetadataManagerSnafu)
+                .map_err(BoxedError::new)
+                .with_context(|_| error::RetryLaterWithSourceSnafu {
+                    reason: format!("Failed to get TableRoute: {table_id}"),
+                })?
+                .context(error::TableRouteNotFoundSnafu { table_id })?;
+
+            *table_route_value = Some(table_route);
+        }
+
+        Ok(table_route_value.as_ref().unwrap())
+    }
+
+    /// Removes the `table_route` of [VolatileContext], returns true if any.
+    pub fn remove_table_route_value(&mut self) -> bool {
+        let value = self.volatile_ctx.table_route.take();
+        value.is_some()
+    }
+
+    /// Returns the `table_info` of [VolatileContext] if any.
+    /// Otherwise, returns the value retrieved from remote.
+    ///
+    /// Retry:
+    /// - Failed to retrieve the metadata of table.
+    pub async fn get_table_info_value(
+        &mut self,
+    ) -> Result<&DeserializedValueWithBytes<TableInfoValue>> {
+        let table_info_value = &mut self.volatile_ctx.table_info;
+
+        if table_info_value.is_none() {
+            let table_id = self.persistent_ctx.table_id;
+            let table_info = self
+                .table_metadata_manager
+                .table_info_manager()
+                .get(table_id)
+                .await
+                .context(error::TableMetadataManagerSnafu)
+                .map_err(BoxedError::new)
+                .with_context(|_| error::RetryLaterWithSourceSnafu {
+                    reason: format!("Failed to get TableInfo: {table_id}"),
+                })?
+                .context(error::TableInfoNotFoundSnafu { table_id })?;
+
+            *table_info_value = Some(table_info);
+        }
+
+        Ok(table_info_value.as_ref().unwrap())
+    }
+
+    /// Removes the `table_info` of [VolatileContext], returns true if any.
+    pub fn remove_table_info_value(&mut self) -> bool {
+        let value = self.volatile_ctx.table_info.take();
+        value.is_some()
+    }
+
+    /// Sends the region request to the datanode.
+    pub async fn send_region_request(
+        &self,
+        peer: &Peer,
+        region_id: RegionId,
+        body: region_request::Body,
+    ) -> Result<RegionResponse> {
+        let request = RegionRequest {
+            header: Some(RegionRequestHeader {
+                tracing_context: TracingContext::from_current_span().to_w3c(),
+                ..Default::default()
+            }),
+            body: Some(body),
+        };
+
+        self.node_manager
+            .datanode(peer)
+            .await
+            .handle(request)
+            .await
+            .map_err(add_peer_context_if_needed(peer.clone()))
+            .context(error::OperateRegionSnafu { region_id })
+    }
+
+    /// Drops the regions on datanodes, ignores the regions that don't exist.
+    pub async fn drop_regions(&self, region_routes: &[RegionRoute]) -> Result<()> {
+        for route in region_routes {
+            let Some(peer) = &route.leader_peer else {
+                continue;
+            };
+            let region_id = route.region.id;
+            let body = region_request::Body::Drop(DropRequest {
+                region_id: region_id.as_u64(),
+                fast_path: false,
+            });
+            if let Err(err) = self.send_region_request(peer, region_id, body).await {
+                if err.status_code() != StatusCode::RegionNotFound {
+                    return Err(err);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets the leader state of the source regions in the table route.
+    ///
+    /// Retry:
+    /// - Failed to retrieve the metadata of table.
+    /// - Failed to update the table route.
+    pub async fn set_source_leader_state(&mut self, state: Option<LeaderState>) -> Result<()> {
+        let table_id = self.table_id();
+        let source_regions = self
+            .plan()?
+            .source_regions
+            .iter()
+            .map(|route| route.region.id)
+            .collect::<Vec<_>>();
+        self.remove_table_route_value();
+        let table_route_value = self.get_table_route_value().await?.clone();
+        let result = self
+            .table_metadata_manager
+            .update_leader_region_status(table_id, &table_route_value, |route| {
+                source_regions.contains(&route.region.id).then_some(state)
+            })
+            .await;
+        self.remove_table_route_value();
+        result
+            .context(error::TableMetadataManagerSnafu)
+            .map_err(BoxedError::new)
+            .with_context(|_| error::RetryLaterWithSourceSnafu {
+                reason: format!(
+                    "Failed to update the leader state of the source regions of table: {table_id}"
+                ),
+            })
+    }
+
+    /// Broadcasts the invalidate table cache message.
+    pub async fn invalidate_table_cache(&self) -> Result<()> {
+        let table_id = self.table_id();
+        // ignore the result
+        let ctx = common_meta::cache_invalidator::Context::default();
+        let _ = self
+            .cache_invalidator
+            .invalidate(&ctx, &[CacheIdent::TableId(table_id)])
+            .await;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(tag = "repartition_state")]
+pub(crate) trait State: Sync + Send + Debug {
+    fn name(&self) -> &'static str {
+        let type_name = std::any::type_name::<Self>();
+        // short name
+        type_name.split("::").last().unwrap_or(type_name)
+    }
+
+    /// Yields the next [State] and [Status].
+    async fn next(
+        &mut self,
+        ctx: &mut Context,
+        procedure_ctx: &ProcedureContext,
+    ) -> Result<(Box<dyn State>, Status)>;
+
+    /// Returns as [Any](std::any::Any).
+    fn as_any(&self) -> &dyn Any;
+}
+
+/// Persistent data of [RepartitionProcedure].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RepartitionDataOwned {
+    persistent_ctx: PersistentContext,
+    state: Box<dyn State>,
+}
+
+/// Persistent data of [RepartitionProcedure].
+#[derive(Debug, Serialize)]
+pub struct RepartitionData<'a> {
+    persistent_ctx: &'a PersistentContext,
+    state: &'a dyn State,
+}
+
+pub(crate) struct RepartitionProcedure {
+    state: Box<dyn State>,
+    context: Context,
+}
+
+impl RepartitionProcedure {
+    const TYPE_NAME: &'static str = "metasrv-procedure::Repartition";
+
+    pub fn new(
+        persistent_context: PersistentContext,
+        context_factory: DefaultContextFactory,
+    ) -> Self {
+        Self {
+            state: Box::new(RepartitionStart),
+            context: context_factory.new_context(persistent_context),
+        }
+    }
+
+    fn from_json(json: &str, context_factory: DefaultContextFactory) -> ProcedureResult<Self> {
+        let RepartitionDataOwned {
+            persistent_ctx,
+            state,
+        } = serde_json::from_str(json).context(FromJsonSnafu)?;
+
+        Ok(Self {
+            state,
+            context: context_factory.new_context(persistent_ctx),
+        })
+    }
+
+    /// Drops the target regions if the table route hasn't been switched to them.
+    async fn rollback_inner(&mut self) -> Result<()> {
+        let _timer = METRIC_META_REPARTITION_EXECUTE
+            .with_label_values(&["rollback"])
+            .start_timer();
+
+        let Some(plan) = self.context.persistent_ctx.plan.clone() else {
+            return Ok(());
+        };
+
+        self.context.remove_table_route_value();
+        let table_route = self.context.get_table_route_value().await?;
+        // Safety: It must be a physical table route.
+        let switched = table_route.region_routes().unwrap().iter().any(|route| {
+            plan.target_regions
+                .iter()
+                .any(|target| target.region.id == route.region.id)
+        });
+        if switched {
+            return Ok(());
+        }
+
+        info!(
+            "Rollbacking repartition of table: {}, dropping target regions: {:?}",
+            self.context.table_id(),
+            plan.target_regions
+                .iter()
+                .map(|route| route.region.id)
+                .collect::<Vec<_>>()
+        );
+        self.context
+            .drop_regions(&plan.target_regions)
+            .await
+            .map_err(BoxedError::new)
+            .context(error::RetryLaterWithSourceSnafu {
+                reason: "Failed to drop the target regions during the rollback",
+            })?;
+        self.context.volatile_ctx.operating_region_guards.clear();
+        // The source regions are upgraded by the region lease once they aren't downgrading in the table route.
+        self.context.set_source_leader_state(None).await?;
+        self.context.invalidate_table_cache().await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Procedure for RepartitionProcedure {
+    fn type_name(&self) -> &str {
+        Self::TYPE_NAME
+    }
+
+    async fn rollback(&mut self, _ctx: &ProcedureContext) -> ProcedureResult<()> {
+        self.rollback_inner()
+            .await
+            .map_err(ProcedureError::external)
+    }
+
+    fn rollback_supported(&self) -> bool {
+        true
+    }
+
+    async fn execute(&mut self, ctx: &ProcedureContext) -> ProcedureResult<Status> {
+        let state = &mut self.state;
+
+        let name = state.name();
+        let _timer = METRIC_META_REPARTITION_EXECUTE
+            .with_label_values(&[name])
+            .start_timer();
+        match state.next(&mut self.context, ctx).await {
+            Ok((next, status)) => {
+                *state = next;
+                Ok(status)
+            }
+            Err(e) => {
+                if e.is_retryable() {
+                    METRIC_META_REPARTITION_ERROR
+                        .with_label_values(&[name, "retryable"])
+                        .inc();
+                    Err(ProcedureError::retry_later(e))
+                } else {
+                    error!(
+                        e;
+                        "Repartition procedure failed, table_id: {}, state: {}",
+                        self.context.table_id(),
+                        name,
+                    );
+                    METRIC_META_REPARTITION_ERROR
+                        .with_label_values(&[name, "external"])
+                        .inc();
+                    Err(ProcedureError::external(e))
+                }
+            }
+        }
+    }
+
+    fn dump(&self) -> ProcedureResult<String> {
+        let data = RepartitionData {
+            state: self.state.as_ref(),
+            persistent_ctx: &self.context.persistent_ctx,
+        };
+        serde_json::to_string(&data).context(ToJsonSnafu)
+    }
+
+    fn lock_key(&self) -> LockKey {
+        LockKey::new(self.context.persistent_ctx.lock_key())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_meta::rpc::router::Region;
+
+    use super::*;
+    use crate::procedure::repartition::update_metadata::UpdateMetadata;
+
+    fn new_persistent_context() -> PersistentContext {
+        PersistentContext {
+            catalog: "greptime".to_string(),
+            schema: "public".to_string(),
+            table: "foo".to_string(),
+            table_id: 1024,
+            from_partition_exprs: vec!["a".to_string()],
+            into_partition_exprs: vec!["b".to_string(), "c".to_string()],
+            plan: Some(RepartitionPlan {
+                source_regions: vec![RegionRoute {
+                    region: Region::new_test(RegionId::new(1024, 1)),
+                    leader_peer: Some(Peer::new(1, "127.0.0.1:4001")),
+                    ..Default::default()
+                }],
+                target_regions: vec![],
+                target_region_wal_options: HashMap::new(),
+            }),
+            copied_regions: vec![],
+        }
+    }
+
+    #[test]
+    fn test_lock_key() {
+        let persistent_context = new_persistent_context();
+        let keys = persistent_context.lock_key();
+
+        let expected_keys = vec![
+            CatalogLock::Read("greptime").into(),
+            SchemaLock::read("greptime", "public").into(),
+            TableLock::Write(1024).into(),
+        ];
+
+        assert_eq!(expected_keys, keys);
+    }
+
+    #[test]
+    fn test_data_serialization() {
+        let persistent_ctx = new_persistent_context();
+        let state: Box<dyn State> = Box::new(UpdateMetadata);
+        let data = RepartitionData {
+            persistent_ctx: &persistent_ctx,
+            state: state.as_ref(),
+        };
+        let serialized = serde_json::to_string(&data).unwrap();
+
+        let RepartitionDataOwned {
+            persistent_ctx: deserialized,
+            state,
+        } = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(persistent_ctx, deserialized);
+        assert!(state.as_any().downcast_ref::<UpdateMetadata>().is_some());
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+
+use common_meta::rpc::router::RegionRoute;
+use common_procedure::{Context as ProcedureContext, Status};
+use common_telemetry::info;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use store_api::region_engine::{CopyRegionFromRequest, CopyRegionFromResponse};
+
+use crate::error::{self, Result};
+use crate::procedure::repartition::update_metadata::UpdateMetadata;
+use crate::procedure::repartition::{Context, RepartitionPlan, State};
+
+/// Copies the SST files of the source regions into the target regions.
+///
+/// The datanode of each target region copies the files from the object store, the
+/// files are rewritten with the rows matching the partition expr of the target region
+/// if the source regions are split. The copied target regions are persisted, and the
+/// datanode skips a target region that already has files, so the copy is idempotent.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CopyRegionData;
+
+#[async_trait::async_trait]
+#[typetag::serde]
+impl State for CopyRegionData {
+    async fn next(
+        &mut self,
+        ctx: &mut Context,
+        _procedure_ctx: &ProcedureContext,
+    ) -> Result<(Box<dyn State>, Status)> {
+        let plan = ctx.plan()?;
+        let Some(target) = plan
+            .target_regions
+            .iter()
+            .find(|route| !ctx.persistent_ctx.copied_regions.contains(&route.region.id))
+        else {
+            return Ok((Box::new(UpdateMetadata), Status::executing(true)));
+        };
+
+        let request = build_copy_request(plan, target);
+        let region_id = request.region_id;
+        let source_region_ids = request.source_region_ids.clone();
+        // Safety: The target regions are always allocated with a leader peer.
+        let peer = target.leader_peer.as_ref().unwrap();
+        let response = ctx
+            .node_manager
+            .datanode(peer)
+            .await
+            .copy_region_from(request)
+            .await
+            .context(error::CopyRegionDataSnafu {
+                from_region_ids: source_region_ids.clone(),
+                to_region_id: region_id,
+            })?;
+        let CopyRegionFromResponse::Copied { copied_files } = response else {
+            return error::CopyRegionDataNotSupportedSnafu { region_id }.fail();
+        };
+        info!(
+            "Copied {copied_files} files from regions {:?} to region {region_id}",
+            source_region_ids
+        );
+
+        // Persists the progress before copying the next target region.
+        ctx.persistent_ctx.copied_regions.push(region_id);
+        Ok((Box::new(CopyRegionData), Status::executing(true)))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Builds the request to copy the files of all source regions into the `target` region.
+///
+/// The rows are filtered by the partition expr of the target only if the source regions
+/// are split into several regions, a merged region owns all the rows of the source regions.
+fn build_copy_request(plan: &RepartitionPlan, target: &RegionRoute) -> CopyRegionFromRequest {
+    let partition_expr = (plan.target_regions.len() > 1).then(|| target.region.partition_expr());
+    CopyRegionFromRequest {
+        region_id: target.region.id,
+        source_region_ids: plan
+            .source_regions
+            .iter()
+            .map(|route| route.region.id)
+            .collect(),
+        partition_expr,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use common_meta::rpc::router::Region;
+    use store_api::storage::RegionId;
+
+    use super::*;
+
+    fn new_route(region_id: RegionId, partition_expr: &str) -> RegionRoute {
+        let mut region = Region::new_test(region_id);
+        region.partition_expr = partition_expr.to_string();
+        RegionRoute {
+            region,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_build_copy_request() {
+        let source = new_route(RegionId::new(1024, 1), "a");
+        let targets = vec![
+            new_route(RegionId::new(1024, 2), "b"),
+            new_route(RegionId::new(1024, 3), "c"),
+        ];
+
+        // Split: the rows are filtered by the partition expr of the target.
+        let plan = RepartitionPlan {
+            source_regions: vec![source.clone()],
+            target_regions: targets.clone(),
+            target_region_wal_options: HashMap::new(),
+        };
+        let request = build_copy_request(&plan, &targets[1]);
+        assert_eq!(
+            CopyRegionFromRequest {
+                region_id: RegionId::new(1024, 3),
+                source_region_ids: vec![RegionId::new(1024, 1)],
+                partition_expr: Some("c".to_string()),
+            },
+            request
+        );
+
+        // Merge: the files are copied as is.
+        let plan = RepartitionPlan {
+            source_regions: targets,
+            target_regions: vec![source.clone()],
+            target_region_wal_options: HashMap::new(),
+        };
+        let request = build_copy_request(&plan, &source);
+        assert_eq!(
+            CopyRegionFromRequest {
+                region_id: RegionId::new(1024, 1),
+                source_region_ids: vec![RegionId::new(1024, 2), RegionId::new(1024, 3)],
+                partition_expr: None,
+            },
+            request
+        );
+    }
+}
@@ -0,0 +1,54 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+
+use common_error::ext::BoxedError;
+use common_procedure::{Context as ProcedureContext, Status};
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+
+use crate::error::{self, Result};
+use crate::procedure::repartition::repartition_end::RepartitionEnd;
+use crate::procedure::repartition::{Context, State};
+
+/// Drops the source regions, which are no longer in the table route.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DropSourceRegions;
+
+#[async_trait::async_trait]
+#[typetag::serde]
+impl State for DropSourceRegions {
+    /// Retry:
+    /// - Failed to drop the source regions.
+    async fn next(
+        &mut self,
+        ctx: &mut Context,
+        _procedure_ctx: &ProcedureContext,
+    ) -> Result<(Box<dyn State>, Status)> {
+        let source_regions = ctx.plan()?.source_regions.clone();
+        ctx.drop_regions(&source_regions)
+            .await
+            .map_err(BoxedError::new)
+            .context(error::RetryLaterWithSourceSnafu {
+                reason: "Failed to drop the source regions",
+            })?;
+
+        Ok((Box::new(RepartitionEnd), Status::executing(false)))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -0,0 +1,153 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::time::Duration;
+
+use api::v1::meta::MailboxMessage;
+use common_meta::instruction::{
+    DowngradeRegion, DowngradeRegionReply, Instruction, InstructionReply,
+};
+use common_meta::peer::Peer;
+use common_meta::rpc::router::LeaderState;
+use common_procedure::{Context as ProcedureContext, Status};
+use common_telemetry::{info, warn};
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use store_api::storage::RegionId;
+use tokio::time::Instant;
+
+use crate::error::{self, Result};
+use crate::handler::HeartbeatMailbox;
+use crate::procedure::repartition::copy_region_data::CopyRegionData;
+use crate::procedure::repartition::{Context, State};
+use crate::service::mailbox::Channel;
+
+/// The timeout to downgrade a source region, including flushing it.
+const DOWNGRADE_REGION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Fences the writes to the source regions.
+///
+/// It marks the source regions as downgrading in the table route, so their leases
+/// won't be renewed as leaders, then downgrades them on datanodes. A downgraded region
+/// rejects writes and has flushed all its rows into SST files.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FenceSourceRegions;
+
+#[async_trait::async_trait]
+#[typetag::serde]
+impl State for FenceSourceRegions {
+    async fn next(
+        &mut self,
+        ctx: &mut Context,
+        _procedure_ctx: &ProcedureContext,
+    ) -> Result<(Box<dyn State>, Status)> {
+        ctx.set_source_leader_state(Some(LeaderState::Downgrading))
+            .await?;
+        ctx.invalidate_table_cache().await?;
+
+        let source_regions = ctx.plan()?.source_regions.clone();
+        for source in &source_regions {
+            // Safety: It's checked in the `RepartitionStart` state.
+            let peer = source.leader_peer.as_ref().unwrap();
+            downgrade_region(ctx, peer, source.region.id).await?;
+        }
+
+        Ok((Box::new(CopyRegionData), Status::executing(true)))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Downgrades the region on the datanode, it's a no-op if the region has been downgraded.
+///
+/// Retry:
+/// - [MailboxTimeout](error::Error::MailboxTimeout), Timeout.
+/// - Failed to downgrade region on the Datanode.
+///
+/// Abort:
+/// - [PusherNotFound](error::Error::PusherNotFound), The datanode is unreachable.
+/// - [PushMessage](error::Error::PushMessage), The receiver is dropped.
+/// - [UnexpectedInstructionReply](error::Error::UnexpectedInstructionReply).
+async fn downgrade_region(ctx: &Context, peer: &Peer, region_id: RegionId) -> Result<()> {
+    let instruction = Instruction::DowngradeRegion(DowngradeRegion {
+        region_id,
+        flush_timeout: Some(DOWNGRADE_REGION_TIMEOUT),
+    });
+    let msg = MailboxMessage::json_message(
+        &format!("Downgrade region for repartition: {}", region_id),
+        &format!("Metasrv@{}", ctx.server_addr),
+        &format!("Datanode-{}@{}", peer.id, peer.addr),
+        common_time::util::current_time_millis(),
+        &instruction,
+    )
+    .with_context(|_| error::SerializeToJsonSnafu {
+        input: instruction.to_string(),
+    })?;
+
+    let ch = Channel::Datanode(peer.id);
+    let now = Instant::now();
+    let receiver = ctx.mailbox.send(&ch, msg, DOWNGRADE_REGION_TIMEOUT).await?;
+
+    match receiver.await {
+        Ok(msg) => {
+            let reply = HeartbeatMailbox::json_reply(&msg)?;
+            let InstructionReply::DowngradeRegion(DowngradeRegionReply { exists, error, .. }) =
+                reply
+            else {
+                return error::UnexpectedInstructionReplySnafu {
+                    mailbox_message: msg.to_string(),
+                    reason: "expect downgrade region reply",
+                }
+                .fail();
+            };
+
+            if error.is_some() {
+                return error::RetryLaterSnafu {
+                    reason: format!(
+                        "Failed to downgrade the region {} on datanode {:?}, error: {:?}, elapsed: {:?}",
+                        region_id, peer, error, now.elapsed()
+                    ),
+                }
+                .fail();
+            }
+            if exists {
+                info!(
+                    "Region {} is downgraded on datanode {:?} for repartition, elapsed: {:?}",
+                    region_id,
+                    peer,
+                    now.elapsed()
+                );
+            } else {
+                warn!(
+                    "Trying to downgrade the region {} on datanode {:?} for repartition, but region doesn't exist!",
+                    region_id, peer
+                );
+            }
+
+            Ok(())
+        }
+        Err(error::Error::MailboxTimeout { .. }) => error::RetryLaterSnafu {
+            reason: format!(
+                "Mailbox received timeout for downgrade region {region_id} on datanode {:?}, elapsed: {:?}",
+                peer,
+                now.elapsed()
+            ),
+        }
+        .fail(),
+        Err(err) => Err(err),
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_meta::ddl_manager::RepartitionDdlManager;
+use common_meta::error::{
+    Result as MetaResult, SubmitProcedureSnafu as MetaSubmitProcedureSnafu,
+    WaitProcedureSnafu as MetaWaitProcedureSnafu,
+};
+use common_meta::rpc::ddl::{RepartitionTableTask, SubmitDdlTaskResponse};
+use common_procedure::{watcher, ProcedureManagerRef, ProcedureWithId};
+use common_telemetry::info;
+use snafu::ResultExt;
+
+use crate::error::{self, Result};
+use crate::procedure::repartition::{
+    DefaultContextFactory, PersistentContext, RepartitionProcedure,
+};
+
+pub type RepartitionManagerRef = Arc<RepartitionManager>;
+
+/// Submits the [RepartitionProcedure]s.
+pub struct RepartitionManager {
+    procedure_manager: ProcedureManagerRef,
+    context_factory: DefaultContextFactory,
+}
+
+impl RepartitionManager {
+    /// Returns new [`RepartitionManager`].
+    pub fn new(
+        procedure_manager: ProcedureManagerRef,
+        context_factory: DefaultContextFactory,
+    ) -> Self {
+        Self {
+            procedure_manager,
+            context_factory,
+        }
+    }
+
+    /// Registers the loader of [RepartitionProcedure] to the `ProcedureManager`.
+    pub fn try_start(&self) -> Result<()> {
+        let context_factory = self.context_factory.clone();
+        self.procedure_manager
+            .register_loader(
+                RepartitionProcedure::TYPE_NAME,
+                Box::new(move |json| {
+                    let context_factory = context_factory.clone();
+                    RepartitionProcedure::from_json(json, context_factory).map(|p| Box::new(p) as _)
+                }),
+            )
+            .context(error::RegisterProcedureLoaderSnafu {
+                type_name: RepartitionProcedure::TYPE_NAME,
+            })
+    }
+}
+
+#[async_trait::async_trait]
+impl RepartitionDdlManager for RepartitionManager {
+    async fn repartition_table(
+        &self,
+        task: RepartitionTableTask,
+    ) -> MetaResult<SubmitDdlTaskResponse> {
+        let RepartitionTableTask {
+            catalog,
+            schema,
+            table,
+            table_id,
+            from_partition_exprs,
+            into_partition_exprs,
+        } = task;
+        let persistent_ctx = PersistentContext {
+            catalog,
+            schema,
+            table,
+            table_id,
+            from_partition_exprs,
+            into_partition_exprs,
+            plan: None,
+            copied_regions: vec![],
+        };
+        let procedure = RepartitionProcedure::new(persistent_ctx, self.context_factory.clone());
+        let procedure_with_id = ProcedureWithId::with_random_id(Box::new(procedure));
+        let procedure_id = procedure_with_id.id;
+        info!("Starting repartition procedure {procedure_id} for table: {table_id}");
+
+        let mut watcher = self
+            .procedure_manager
+            .submit(procedure_with_id)
+            .await
+            .context(MetaSubmitProcedureSnafu)?;
+        watcher::wait(&mut watcher)
+            .await
+            .context(MetaWaitProcedureSnafu)?;
+
+        Ok(SubmitDdlTaskResponse {
+            key: procedure_id.to_string().into(),
+            ..Default::default()
+        })
+    }
+}
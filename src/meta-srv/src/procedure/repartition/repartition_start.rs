@@ -0,0 +1,233 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::collections::HashMap;
+
+use common_catalog::consts::MITO_ENGINE;
+use common_error::ext::BoxedError;
+use common_meta::key::datanode_table::DatanodeTableKey;
+use common_meta::rpc::router::{Region, RegionRoute};
+use common_procedure::{Context as ProcedureContext, Status};
+use common_telemetry::info;
+use partition::expr::PartitionExpr;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, OptionExt, ResultExt};
+use store_api::storage::RegionId;
+
+use crate::error::{self, Result};
+use crate::procedure::repartition::stage_regions::StageRegions;
+use crate::procedure::repartition::{Context, RepartitionPlan, State};
+
+/// Validates the request and allocates the [RepartitionPlan].
+///
+/// The new regions are placed on the leader peer of the first region to be replaced.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RepartitionStart;
+
+#[async_trait::async_trait]
+#[typetag::serde]
+impl State for RepartitionStart {
+    async fn next(
+        &mut self,
+        ctx: &mut Context,
+        _procedure_ctx: &ProcedureContext,
+    ) -> Result<(Box<dyn State>, Status)> {
+        if ctx.persistent_ctx.plan.is_none() {
+            let plan = self.allocate_plan(ctx).await?;
+            info!(
+                "Repartitioning table: {}, source regions: {:?}, target regions: {:?}",
+                ctx.table_id(),
+                plan.source_regions
+                    .iter()
+                    .map(|route| route.region.id)
+                    .collect::<Vec<_>>(),
+                plan.target_regions
+                    .iter()
+                    .map(|route| route.region.id)
+                    .collect::<Vec<_>>(),
+            );
+            ctx.persistent_ctx.plan = Some(plan);
+        }
+
+        Ok((Box::new(StageRegions), Status::executing(true)))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl RepartitionStart {
+    /// Allocates the [RepartitionPlan].
+    ///
+    /// Abort(non-retry):
+    /// - The table isn't a mito table.
+    /// - Any partition expr is invalid.
+    /// - Any source partition expr doesn't match an existing region.
+    /// - The source region has no leader.
+    ///
+    /// Retry:
+    /// - Failed to retrieve the metadata of table.
+    async fn allocate_plan(&self, ctx: &mut Context) -> Result<RepartitionPlan> {
+        let table_id = ctx.table_id();
+        let engine = ctx
+            .get_table_info_value()
+            .await?
+            .table_info
+            .meta
+            .engine
+            .clone();
+        ensure!(
+            engine == MITO_ENGINE,
+            error::UnsupportedSnafu {
+                operation: format!("repartitioning table with engine {engine}"),
+            }
+        );
+
+        let from_exprs = parse_partition_exprs(&ctx.persistent_ctx.from_partition_exprs)?;
+        let into_exprs = parse_partition_exprs(&ctx.persistent_ctx.into_partition_exprs)?;
+        ensure!(
+            !from_exprs.is_empty() && !into_exprs.is_empty(),
+            error::InvalidArgumentsSnafu {
+                err_msg: "The partition exprs to repartition can't be empty",
+            }
+        );
+
+        // Safety: The table route must be a physical table route, it was checked before submitting.
+        let region_routes = ctx
+            .get_table_route_value()
+            .await?
+            .region_routes()
+            .unwrap()
+            .clone();
+
+        let mut source_regions = Vec::with_capacity(from_exprs.len());
+        for (expr, raw) in from_exprs
+            .iter()
+            .zip(&ctx.persistent_ctx.from_partition_exprs)
+        {
+            let route = region_routes
+                .iter()
+                .find(|route| {
+                    PartitionExpr::from_json_str(&route.region.partition_expr())
+                        .ok()
+                        .flatten()
+                        .as_ref()
+                        == Some(expr)
+                })
+                .with_context(|| error::InvalidArgumentsSnafu {
+                    err_msg: format!("No region of table {table_id} is partitioned by {raw}"),
+                })?;
+            ensure!(
+                route.leader_peer.is_some(),
+                error::UnexpectedSnafu {
+                    violated: format!("Region {} has no leader", route.region.id),
+                }
+            );
+            source_regions.push(route.clone());
+        }
+
+        // Safety: checked above.
+        let peer = source_regions[0].leader_peer.clone().unwrap();
+        let next_region_number = region_routes
+            .iter()
+            .map(|route| route.region.id.region_number())
+            .max()
+            .map(|n| n + 1)
+            .unwrap_or_default();
+        let target_regions = ctx
+            .persistent_ctx
+            .into_partition_exprs
+            .iter()
+            .enumerate()
+            .map(|(i, expr)| RegionRoute {
+                region: Region {
+                    id: RegionId::new(table_id, next_region_number + i as u32),
+                    partition_expr: expr.clone(),
+                    ..Default::default()
+                },
+                leader_peer: Some(peer.clone()),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+
+        // The new regions share the wal options of the first source region.
+        let datanode_table = ctx
+            .table_metadata_manager
+            .datanode_table_manager()
+            .get(&DatanodeTableKey::new(peer.id, table_id))
+            .await
+            .context(error::TableMetadataManagerSnafu)
+            .map_err(BoxedError::new)
+            .with_context(|_| error::RetryLaterWithSourceSnafu {
+                reason: format!("Failed to get DatanodeTable: ({},{table_id})", peer.id),
+            })?
+            .context(error::DatanodeTableNotFoundSnafu {
+                table_id,
+                datanode_id: peer.id,
+            })?;
+        let target_region_wal_options = match datanode_table
+            .region_info
+            .region_wal_options
+            .get(&source_regions[0].region.id.region_number())
+        {
+            Some(wal_options) => target_regions
+                .iter()
+                .map(|route| (route.region.id.region_number(), wal_options.clone()))
+                .collect(),
+            None => HashMap::new(),
+        };
+
+        Ok(RepartitionPlan {
+            source_regions,
+            target_regions,
+            target_region_wal_options,
+        })
+    }
+}
+
+fn parse_partition_exprs(exprs: &[String]) -> Result<Vec<PartitionExpr>> {
+    exprs
+        .iter()
+        .map(|expr| {
+            PartitionExpr::from_json_str(expr)
+                .context(error::InvalidPartitionExprSnafu { expr })?
+                .with_context(|| error::InvalidArgumentsSnafu {
+                    err_msg: "The partition expr to repartition can't be empty",
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use partition::expr::col;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_partition_exprs() {
+        let expr = col("a").lt(datatypes::value::Value::Int32(10));
+        let json = expr.as_json_str().unwrap();
+        let parsed = parse_partition_exprs(&[json]).unwrap();
+        assert_eq!(vec![expr], parsed);
+
+        let err = parse_partition_exprs(&["".to_string()]).unwrap_err();
+        assert!(matches!(err, error::Error::InvalidArguments { .. }));
+
+        let err = parse_partition_exprs(&["{".to_string()]).unwrap_err();
+        assert!(matches!(err, error::Error::InvalidPartitionExpr { .. }));
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::collections::HashMap;
+
+use api::v1::region::region_request;
+use common_meta::ddl::utils::region_storage_path;
+use common_meta::ddl::{
+    build_template_from_raw_table_info_for_physical_table, CreateRequestBuilder,
+};
+use common_procedure::{Context as ProcedureContext, Status};
+use common_telemetry::info;
+use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, ResultExt};
+
+use crate::error::{self, Result};
+use crate::procedure::repartition::fence_source_regions::FenceSourceRegions;
+use crate::procedure::repartition::{Context, State};
+
+/// Creates the target regions on datanodes.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StageRegions;
+
+#[async_trait::async_trait]
+#[typetag::serde]
+impl State for StageRegions {
+    async fn next(
+        &mut self,
+        ctx: &mut Context,
+        _procedure_ctx: &ProcedureContext,
+    ) -> Result<(Box<dyn State>, Status)> {
+        self.register_operating_regions(ctx)?;
+        self.create_target_regions(ctx).await?;
+
+        Ok((Box::new(FenceSourceRegions), Status::executing(true)))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl StageRegions {
+    /// Registers the target regions as operating regions, so that they won't be
+    /// considered as unknown regions before they are written into the table route.
+    ///
+    /// Abort(non-retry):
+    /// - Another procedure is operating the target regions.
+    fn register_operating_regions(&self, ctx: &mut Context) -> Result<()> {
+        if !ctx.volatile_ctx.operating_region_guards.is_empty() {
+            return Ok(());
+        }
+
+        let mut guards = Vec::new();
+        for route in &ctx.plan()?.target_regions {
+            // Safety: The target regions are always allocated with a leader peer.
+            let peer_id = route.leader_peer.as_ref().unwrap().id;
+            let region_id = route.region.id;
+            let guard = ctx
+                .memory_region_keeper
+                .register(peer_id, region_id)
+                .context(error::RegionOpeningRaceSnafu { peer_id, region_id })?;
+            guards.push(guard);
+        }
+        ctx.volatile_ctx.operating_region_guards = guards;
+
+        Ok(())
+    }
+
+    /// Creates the target regions.
+    ///
+    /// Abort(non-retry):
+    /// - Failed to create the target regions.
+    ///
+    /// Retry:
+    /// - Failed to retrieve the metadata of table.
+    async fn create_target_regions(&self, ctx: &mut Context) -> Result<()> {
+        let table_info = ctx.get_table_info_value().await?.table_info.clone();
+        let template = build_template_from_raw_table_info_for_physical_table(&table_info)
+            .context(error::TableMetadataManagerSnafu)?;
+        let request_builder = CreateRequestBuilder::new(template, None);
+        let storage_path =
+            region_storage_path(&ctx.persistent_ctx.catalog, &ctx.persistent_ctx.schema);
+
+        let plan = ctx.plan()?;
+        let partition_exprs = plan
+            .target_regions
+            .iter()
+            .map(|route| {
+                (
+                    route.region.id.region_number(),
+                    route.region.partition_expr(),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+        for route in &plan.target_regions {
+            // Safety: The target regions are always allocated with a leader peer.
+            let peer = route.leader_peer.as_ref().unwrap();
+            let region_id = route.region.id;
+            let request = request_builder.build_one(
+                region_id,
+                storage_path.clone(),
+                &plan.target_region_wal_options,
+                &partition_exprs,
+            );
+            info!("Creating region {region_id} on peer {peer} for repartition");
+            ctx.send_region_request(peer, region_id, region_request::Body::Create(request))
+                .await?;
+        }
+
+        Ok(())
+    }
+}
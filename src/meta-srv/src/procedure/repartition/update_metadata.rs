@@ -0,0 +1,223 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+
+use common_error::ext::BoxedError;
+use common_meta::key::datanode_table::DatanodeTableKey;
+use common_meta::rpc::router::RegionRoute;
+use common_procedure::{Context as ProcedureContext, Status};
+use common_telemetry::info;
+use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, ResultExt};
+
+use crate::error::{self, Result};
+use crate::procedure::repartition::drop_source_regions::DropSourceRegions;
+use crate::procedure::repartition::{Context, RepartitionPlan, State};
+
+/// Switches the table route and table info from the source regions to the target regions.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateMetadata;
+
+#[async_trait::async_trait]
+#[typetag::serde]
+impl State for UpdateMetadata {
+    async fn next(
+        &mut self,
+        ctx: &mut Context,
+        _procedure_ctx: &ProcedureContext,
+    ) -> Result<(Box<dyn State>, Status)> {
+        let plan = ctx.plan()?.clone();
+        self.update_table_route(ctx, &plan).await?;
+        self.update_table_info(ctx).await?;
+        // The target regions have been written into the table route.
+        ctx.volatile_ctx.operating_region_guards.clear();
+        ctx.invalidate_table_cache().await?;
+
+        Ok((Box::new(DropSourceRegions), Status::executing(true)))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Returns the region routes replacing the source regions with the target regions.
+fn build_new_region_routes(
+    current_region_routes: &[RegionRoute],
+    plan: &RepartitionPlan,
+) -> Vec<RegionRoute> {
+    current_region_routes
+        .iter()
+        .filter(|route| {
+            !plan
+                .source_regions
+                .iter()
+                .any(|source| source.region.id == route.region.id)
+        })
+        .chain(plan.target_regions.iter())
+        .cloned()
+        .collect()
+}
+
+impl UpdateMetadata {
+    /// Replaces the source regions with the target regions in the table route.
+    ///
+    /// Retry:
+    /// - Failed to retrieve the metadata of table or datanode.
+    /// - Failed to update the table route.
+    async fn update_table_route(&self, ctx: &mut Context, plan: &RepartitionPlan) -> Result<()> {
+        let table_id = ctx.table_id();
+        ctx.remove_table_route_value();
+        let table_route_value = ctx.get_table_route_value().await?.clone();
+        // Safety: The table route must be a physical table route.
+        let current_region_routes = table_route_value.region_routes().unwrap();
+        let switched = current_region_routes.iter().any(|route| {
+            plan.target_regions
+                .iter()
+                .any(|target| target.region.id == route.region.id)
+        });
+        if switched {
+            return Ok(());
+        }
+
+        // Safety: The target regions are always allocated with a leader peer.
+        let datanode_id = plan.target_regions[0].leader_peer.as_ref().unwrap().id;
+        let region_info = ctx
+            .table_metadata_manager
+            .datanode_table_manager()
+            .get(&DatanodeTableKey::new(datanode_id, table_id))
+            .await
+            .context(error::TableMetadataManagerSnafu)
+            .map_err(BoxedError::new)
+            .with_context(|_| error::RetryLaterWithSourceSnafu {
+                reason: format!("Failed to get DatanodeTable: ({datanode_id},{table_id})"),
+            })?
+            .context(error::DatanodeTableNotFoundSnafu {
+                table_id,
+                datanode_id,
+            })?
+            .region_info;
+
+        let mut new_region_wal_options = region_info.region_wal_options.clone();
+        for source in &plan.source_regions {
+            new_region_wal_options.remove(&source.region.id.region_number());
+        }
+        new_region_wal_options.extend(plan.target_region_wal_options.clone());
+        let new_region_routes = build_new_region_routes(current_region_routes, plan);
+        let region_options = region_info.region_options.clone();
+
+        info!(
+            "Switching table route of table {table_id} for repartition, new region routes: {:?}",
+            new_region_routes
+        );
+        ctx.table_metadata_manager
+            .update_table_route(
+                table_id,
+                region_info,
+                &table_route_value,
+                new_region_routes,
+                &region_options,
+                &new_region_wal_options,
+            )
+            .await
+            .context(error::TableMetadataManagerSnafu)
+            .map_err(BoxedError::new)
+            .with_context(|_| error::RetryLaterWithSourceSnafu {
+                reason: format!("Failed to update the table route of table: {table_id}"),
+            })?;
+        ctx.remove_table_route_value();
+
+        Ok(())
+    }
+
+    /// Updates the region numbers in the table info.
+    ///
+    /// Retry:
+    /// - Failed to retrieve the metadata of table.
+    /// - Failed to update the table info.
+    async fn update_table_info(&self, ctx: &mut Context) -> Result<()> {
+        let table_id = ctx.table_id();
+        // Safety: The table route must be a physical table route.
+        let mut region_numbers = ctx
+            .get_table_route_value()
+            .await?
+            .region_routes()
+            .unwrap()
+            .iter()
+            .map(|route| route.region.id.region_number())
+            .collect::<Vec<_>>();
+        region_numbers.sort_unstable();
+
+        ctx.remove_table_info_value();
+        let table_info_value = ctx.get_table_info_value().await?.clone();
+        if table_info_value.table_info.meta.region_numbers == region_numbers {
+            return Ok(());
+        }
+
+        let mut new_table_info = table_info_value.table_info.clone();
+        new_table_info.meta.region_numbers = region_numbers;
+        ctx.table_metadata_manager
+            .update_table_info(&table_info_value, None, new_table_info)
+            .await
+            .context(error::TableMetadataManagerSnafu)
+            .map_err(BoxedError::new)
+            .with_context(|_| error::RetryLaterWithSourceSnafu {
+                reason: format!("Failed to update the table info of table: {table_id}"),
+            })?;
+        ctx.remove_table_info_value();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use common_meta::peer::Peer;
+    use common_meta::rpc::router::Region;
+    use store_api::storage::RegionId;
+
+    use super::*;
+
+    fn new_region_route(region_id: RegionId) -> RegionRoute {
+        RegionRoute {
+            region: Region::new_test(region_id),
+            leader_peer: Some(Peer::new(1, "127.0.0.1:4001")),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_build_new_region_routes() {
+        let current = (0..3)
+            .map(|n| new_region_route(RegionId::new(1024, n)))
+            .collect::<Vec<_>>();
+        let plan = RepartitionPlan {
+            source_regions: vec![current[1].clone()],
+            target_regions: vec![
+                new_region_route(RegionId::new(1024, 3)),
+                new_region_route(RegionId::new(1024, 4)),
+            ],
+            target_region_wal_options: HashMap::new(),
+        };
+
+        let region_ids = build_new_region_routes(&current, &plan)
+            .into_iter()
+            .map(|route| route.region.id.region_number())
+            .collect::<Vec<_>>();
+        assert_eq!(vec![0, 2, 3, 4], region_ids);
+    }
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod balancer;
 pub mod failure_detector;
 pub mod flush_trigger;
 pub mod lease_keeper;
@@ -0,0 +1,738 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use common_meta::datanode::{DatanodeStatKey, DatanodeStatValue};
+use common_meta::distributed_time_constants;
+use common_meta::key::region_balance::{
+    RegionBalanceStateManager, RegionBalanceStateValue, RegionMove, RegionMoveState,
+};
+use common_meta::key::runtime_switch::RuntimeSwitchManagerRef;
+use common_meta::peer::Peer;
+use common_telemetry::{debug, error, info};
+use common_time::util::current_time_millis;
+use snafu::ResultExt;
+use store_api::region_engine::RegionRole;
+use store_api::storage::RegionId;
+use tokio::sync::mpsc::{Receiver, Sender};
+
+use crate::cluster::MetaPeerClientRef;
+use crate::error::{self, Result};
+use crate::metasrv::RegionBalancerOptions;
+use crate::procedure::region_migration::manager::{
+    RegionMigrationManagerRef, RegionMigrationProcedureTask, RegionMigrationTriggerReason,
+};
+use crate::procedure::region_migration::DEFAULT_REGION_MIGRATION_TIMEOUT;
+use crate::{define_ticker, lease, metrics};
+
+/// [`Event`] represents various types of events that can be processed by the region balancer.
+///
+/// Variants:
+/// - `Tick`: This event is used to trigger a balancing round periodically.
+pub(crate) enum Event {
+    Tick,
+}
+
+pub(crate) type RegionBalancerTickerRef = Arc<RegionBalancerTicker>;
+
+define_ticker!(
+    /// [RegionBalancerTicker] is used to trigger the region balancer periodically.
+    RegionBalancerTicker,
+    event_type = Event,
+    event_value = Event::Tick
+);
+
+/// The load of a leader region.
+#[derive(Debug, Clone, PartialEq)]
+struct RegionLoad {
+    region_id: RegionId,
+    /// The write rate in bytes per second.
+    write_rate: f64,
+    /// The size of the SST data files in bytes.
+    sst_size: u64,
+    /// The read and write capacity units during the last heartbeat period.
+    cpu: f64,
+    /// The weighted load score, see [`score_regions`].
+    score: f64,
+}
+
+/// The load of a datanode.
+#[derive(Debug, Clone)]
+struct DatanodeLoad {
+    peer: Peer,
+    regions: Vec<RegionLoad>,
+}
+
+impl DatanodeLoad {
+    fn load(&self) -> f64 {
+        self.regions.iter().map(|r| r.score).sum()
+    }
+}
+
+/// A region move planned by [`plan_moves`].
+#[derive(Debug, Clone, PartialEq)]
+struct PlannedMove {
+    region_id: RegionId,
+    from_peer: Peer,
+    to_peer: Peer,
+    load: f64,
+}
+
+/// [`MoveCooldown`] tracks the regions moved by the balancer recently.
+///
+/// A region is cooling down within [`RegionBalancerOptions::move_cooldown`] after
+/// its move finished, and it won't be planned to move again, so the balancer won't
+/// bounce a region between datanodes while the load statistics are catching up.
+#[derive(Debug)]
+struct MoveCooldown {
+    cooldown: Duration,
+    /// The time each region finished its last move.
+    moved_at: HashMap<RegionId, Instant>,
+}
+
+impl MoveCooldown {
+    fn new(cooldown: Duration) -> Self {
+        Self {
+            cooldown,
+            moved_at: HashMap::new(),
+        }
+    }
+
+    /// Records that the region finished a move at `now`.
+    fn record(&mut self, region_id: RegionId, now: Instant) {
+        self.moved_at.insert(region_id, now);
+    }
+
+    /// Removes the regions whose cooldown has expired at `now`.
+    fn expire(&mut self, now: Instant) {
+        let cooldown = self.cooldown;
+        self.moved_at
+            .retain(|_, moved_at| now.saturating_duration_since(*moved_at) < cooldown);
+    }
+
+    /// Returns the regions that are still cooling down.
+    fn regions(&self) -> impl Iterator<Item = RegionId> + '_ {
+        self.moved_at.keys().copied()
+    }
+}
+
+/// [`RegionBalancer`] moves leader regions from overloaded datanodes to underloaded
+/// datanodes after placement.
+///
+/// In each round, the balancer reads the region statistics collected from datanode
+/// heartbeats, scores every leader region by its write rate, SST size and CPU usage,
+/// and plans region migrations that even out the load of datanodes. A datanode is
+/// considered overloaded if its load exceeds the average load by
+/// [`RegionBalancerOptions::imbalance_threshold`]. A region moved by the balancer
+/// isn't moved again until [`RegionBalancerOptions::move_cooldown`] elapses.
+///
+/// In dry-run mode, the planned moves are only published without being executed.
+/// The planned and in-flight moves are exposed by `information_schema.region_balance_moves`.
+pub struct RegionBalancer {
+    /// The meta peer client.
+    meta_peer_client: MetaPeerClientRef,
+    /// The region migration manager.
+    region_migration_manager: RegionMigrationManagerRef,
+    /// The runtime switch manager.
+    runtime_switch_manager: RuntimeSwitchManagerRef,
+    /// The manager to publish the balancer state.
+    region_balance_state_manager: RegionBalanceStateManager,
+    /// The balancer options.
+    options: RegionBalancerOptions,
+    /// The moves submitted by the balancer whose procedures are still running.
+    inflight_moves: HashMap<RegionId, RegionMove>,
+    /// The regions whose moves finished recently.
+    move_cooldown: MoveCooldown,
+    /// The receiver of events.
+    receiver: Receiver<Event>,
+}
+
+impl RegionBalancer {
+    /// Creates a new [`RegionBalancer`].
+    pub(crate) fn new(
+        meta_peer_client: MetaPeerClientRef,
+        region_migration_manager: RegionMigrationManagerRef,
+        runtime_switch_manager: RuntimeSwitchManagerRef,
+        region_balance_state_manager: RegionBalanceStateManager,
+        options: RegionBalancerOptions,
+    ) -> (Self, RegionBalancerTicker) {
+        let (tx, rx) = Self::channel();
+        let region_balancer_ticker = RegionBalancerTicker::new(options.interval, tx);
+        let move_cooldown = MoveCooldown::new(options.move_cooldown);
+        let region_balancer = Self {
+            meta_peer_client,
+            region_migration_manager,
+            runtime_switch_manager,
+            region_balance_state_manager,
+            options,
+            inflight_moves: HashMap::new(),
+            move_cooldown,
+            receiver: rx,
+        };
+        (region_balancer, region_balancer_ticker)
+    }
+
+    fn channel() -> (Sender<Event>, Receiver<Event>) {
+        tokio::sync::mpsc::channel(8)
+    }
+
+    /// Starts the region balancer.
+    pub fn try_start(mut self) -> Result<()> {
+        common_runtime::spawn_global(async move { self.run().await });
+        info!(
+            "Region balancer started, dry_run: {}, interval: {:?}",
+            self.options.dry_run, self.options.interval
+        );
+        Ok(())
+    }
+
+    async fn run(&mut self) {
+        while let Some(event) = self.receiver.recv().await {
+            match event {
+                Event::Tick => self.handle_tick().await,
+            }
+        }
+    }
+
+    async fn handle_tick(&mut self) {
+        if let Err(e) = self.balance().await {
+            error!(e; "Failed to balance regions");
+        }
+    }
+
+    async fn balance(&mut self) -> Result<()> {
+        let now = Instant::now();
+        // Removes the moves whose procedures have finished, and starts their cooldown.
+        let tracker = self.region_migration_manager.tracker();
+        let move_cooldown = &mut self.move_cooldown;
+        self.inflight_moves.retain(|region_id, _| {
+            let running = tracker.contains(*region_id);
+            if !running {
+                move_cooldown.record(*region_id, now);
+            }
+            running
+        });
+        move_cooldown.expire(now);
+
+        let is_maintenance_mode = self
+            .runtime_switch_manager
+            .maintenance_mode()
+            .await
+            .context(error::RuntimeSwitchManagerSnafu)?;
+        if is_maintenance_mode {
+            info!("Maintenance mode is enabled, skip balancing regions");
+            return self.publish_state(vec![]).await;
+        }
+
+        let mut datanode_loads = self.collect_datanode_loads().await?;
+        score_regions(&mut datanode_loads, &self.options);
+
+        // Skips the regions that are migrating, including those triggered by others,
+        // and the regions that are cooling down.
+        let excluded_regions = datanode_loads
+            .iter()
+            .flat_map(|d| d.regions.iter())
+            .map(|r| r.region_id)
+            .filter(|region_id| tracker.contains(*region_id))
+            .chain(self.move_cooldown.regions())
+            .collect::<HashSet<_>>();
+        let max_moves = self
+            .options
+            .max_concurrent_moves
+            .saturating_sub(self.inflight_moves.len());
+        let planned_moves = plan_moves(
+            &datanode_loads,
+            self.options.imbalance_threshold,
+            max_moves,
+            &excluded_regions,
+        );
+
+        let planned_at_ms = current_time_millis();
+        let mut moves = Vec::with_capacity(planned_moves.len());
+        for planned_move in planned_moves {
+            let region_move = if self.options.dry_run {
+                new_region_move(planned_move, planned_at_ms, RegionMoveState::Planned)
+            } else {
+                self.submit_move(planned_move, planned_at_ms).await
+            };
+            metrics::METRIC_META_REGION_BALANCER_MOVES
+                .with_label_values(&[&region_move.state.to_string()])
+                .inc();
+            moves.push(region_move);
+        }
+
+        debug!(
+            "Balanced regions on {} datanodes in {:?}, planned moves: {}, inflight moves: {}",
+            datanode_loads.len(),
+            now.elapsed(),
+            moves.len(),
+            self.inflight_moves.len()
+        );
+
+        self.publish_state(moves).await
+    }
+
+    /// Collects the loads of alive datanodes that accept ingest workload.
+    async fn collect_datanode_loads(&self) -> Result<Vec<DatanodeLoad>> {
+        let lease_kvs = lease::alive_datanodes(
+            &self.meta_peer_client,
+            Duration::from_secs(distributed_time_constants::DATANODE_LEASE_SECS),
+        )
+        .with_condition(lease::is_datanode_accept_ingest_workload)
+        .await?;
+        let stat_keys = lease_kvs.keys().map(|k| k.into()).collect();
+        let stat_kvs = self.meta_peer_client.get_dn_stat_kvs(stat_keys).await?;
+
+        let datanode_loads = lease_kvs
+            .into_iter()
+            .map(|(lease_key, lease_value)| {
+                let stat_key: DatanodeStatKey = (&lease_key).into();
+                let peer = Peer::new(lease_key.node_id, lease_value.node_addr);
+                let regions = stat_kvs
+                    .get(&stat_key)
+                    .map(region_loads)
+                    .unwrap_or_default();
+                DatanodeLoad { peer, regions }
+            })
+            .collect();
+
+        Ok(datanode_loads)
+    }
+
+    async fn submit_move(&mut self, planned_move: PlannedMove, planned_at_ms: i64) -> RegionMove {
+        let task = RegionMigrationProcedureTask::new(
+            planned_move.region_id,
+            planned_move.from_peer.clone(),
+            planned_move.to_peer.clone(),
+            DEFAULT_REGION_MIGRATION_TIMEOUT,
+            RegionMigrationTriggerReason::AutoRebalance,
+        );
+        info!("Submitting region migration for balancing: {task}");
+
+        match self.region_migration_manager.submit_procedure(task).await {
+            Ok(procedure_id) => {
+                let mut region_move =
+                    new_region_move(planned_move, planned_at_ms, RegionMoveState::Running);
+                region_move.procedure_id = procedure_id.map(|id| id.to_string());
+                self.inflight_moves
+                    .insert(region_move.region_id, region_move.clone());
+                region_move
+            }
+            Err(e) => {
+                error!(e; "Failed to submit region migration for region: {}", planned_move.region_id);
+                let mut region_move =
+                    new_region_move(planned_move, planned_at_ms, RegionMoveState::Failed);
+                region_move.error = Some(e.to_string());
+                region_move
+            }
+        }
+    }
+
+    /// Publishes the moves of this round together with the in-flight moves.
+    async fn publish_state(&self, moves: Vec<RegionMove>) -> Result<()> {
+        let new_regions = moves.iter().map(|m| m.region_id).collect::<HashSet<_>>();
+        let mut all_moves = self
+            .inflight_moves
+            .values()
+            .filter(|m| !new_regions.contains(&m.region_id))
+            .cloned()
+            .collect::<Vec<_>>();
+        all_moves.extend(moves);
+        all_moves.sort_unstable_by_key(|m| m.region_id);
+
+        self.region_balance_state_manager
+            .put(RegionBalanceStateValue {
+                dry_run: self.options.dry_run,
+                moves: all_moves,
+            })
+            .await
+            .context(error::KvBackendSnafu)
+    }
+}
+
+fn new_region_move(
+    planned_move: PlannedMove,
+    planned_at_ms: i64,
+    state: RegionMoveState,
+) -> RegionMove {
+    RegionMove {
+        region_id: planned_move.region_id,
+        from_peer: planned_move.from_peer,
+        to_peer: planned_move.to_peer,
+        load: planned_move.load,
+        state,
+        procedure_id: None,
+        error: None,
+        planned_at_ms,
+    }
+}
+
+/// Returns the loads of leader regions in the latest stat of a datanode.
+///
+/// The write rate is calculated from the written bytes between the oldest and
+/// the latest stat, and it's zero if there is only one stat.
+fn region_loads(stat_value: &DatanodeStatValue) -> Vec<RegionLoad> {
+    let (Some(first), Some(last)) = (stat_value.stats.first(), stat_value.stats.last()) else {
+        return vec![];
+    };
+    let elapsed_secs = (last.timestamp_millis - first.timestamp_millis) as f64 / 1000.0;
+    let first_write_bytes = first
+        .region_stats
+        .iter()
+        .map(|r| (r.id, r.write_bytes))
+        .collect::<HashMap<_, _>>();
+
+    last.region_stats
+        .iter()
+        .filter(|r| r.role == RegionRole::Leader)
+        .map(|r| {
+            let write_rate = match first_write_bytes.get(&r.id) {
+                Some(write_bytes) if elapsed_secs > 0.0 => {
+                    r.write_bytes.saturating_sub(*write_bytes) as f64 / elapsed_secs
+                }
+                _ => 0.0,
+            };
+            RegionLoad {
+                region_id: r.id,
+                write_rate,
+                sst_size: r.sst_size,
+                cpu: (r.rcus + r.wcus).max(0) as f64,
+                score: 0.0,
+            }
+        })
+        .collect()
+}
+
+/// Scores the regions by their weighted share of the cluster-wide write rate,
+/// SST size and CPU usage.
+fn score_regions(datanode_loads: &mut [DatanodeLoad], options: &RegionBalancerOptions) {
+    let regions = || datanode_loads.iter().flat_map(|d| d.regions.iter());
+    let total_write_rate: f64 = regions().map(|r| r.write_rate).sum();
+    let total_sst_size: f64 = regions().map(|r| r.sst_size as f64).sum();
+    let total_cpu: f64 = regions().map(|r| r.cpu).sum();
+
+    let share = |value: f64, total: f64| if total > 0.0 { value / total } else { 0.0 };
+    for region in datanode_loads.iter_mut().flat_map(|d| d.regions.iter_mut()) {
+        region.score = options.write_rate_weight * share(region.write_rate, total_write_rate)
+            + options.sst_size_weight * share(region.sst_size as f64, total_sst_size)
+            + options.cpu_weight * share(region.cpu, total_cpu);
+    }
+}
+
+/// Plans at most `max_moves` region moves to even out the load of datanodes.
+///
+/// The `excluded_regions`, i.e., the migrating and cooling down regions, are never moved.
+/// Each step moves a region from the most loaded datanode to the least loaded datanode,
+/// choosing the region that brings the two datanodes closest to each other. It stops
+/// when no datanode exceeds the average load by `imbalance_threshold` or no region
+/// can reduce the gap.
+fn plan_moves(
+    datanode_loads: &[DatanodeLoad],
+    imbalance_threshold: f64,
+    max_moves: usize,
+    excluded_regions: &HashSet<RegionId>,
+) -> Vec<PlannedMove> {
+    if datanode_loads.len() < 2 || max_moves == 0 {
+        return vec![];
+    }
+
+    let mut loads = datanode_loads.iter().map(|d| d.load()).collect::<Vec<_>>();
+    let avg_load = loads.iter().sum::<f64>() / loads.len() as f64;
+    if avg_load <= 0.0 {
+        return vec![];
+    }
+
+    let mut moved_regions = HashSet::new();
+    let mut moves = Vec::new();
+    while moves.len() < max_moves {
+        // Safety: there are at least two datanodes.
+        let (max_idx, max_load) = loads
+            .iter()
+            .copied()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap();
+        let (min_idx, min_load) = loads
+            .iter()
+            .copied()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap();
+        if max_load <= avg_load * (1.0 + imbalance_threshold) {
+            break;
+        }
+
+        let gap = max_load - min_load;
+        let Some(region) = datanode_loads[max_idx]
+            .regions
+            .iter()
+            .filter(|r| r.score > 0.0 && r.score < gap)
+            .filter(|r| !excluded_regions.contains(&r.region_id))
+            .filter(|r| !moved_regions.contains(&r.region_id))
+            .min_by(|a, b| {
+                (gap - 2.0 * a.score)
+                    .abs()
+                    .total_cmp(&(gap - 2.0 * b.score).abs())
+            })
+        else {
+            break;
+        };
+
+        loads[max_idx] -= region.score;
+        loads[min_idx] += region.score;
+        moved_regions.insert(region.region_id);
+        moves.push(PlannedMove {
+            region_id: region.region_id,
+            from_peer: datanode_loads[max_idx].peer.clone(),
+            to_peer: datanode_loads[min_idx].peer.clone(),
+            load: region.score,
+        });
+    }
+
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use common_meta::datanode::{RegionManifestInfo, RegionStat, Stat};
+
+    use super::*;
+
+    fn region_load(table: u32, region: u32, score: f64) -> RegionLoad {
+        RegionLoad {
+            region_id: RegionId::new(table, region),
+            write_rate: 0.0,
+            sst_size: 0,
+            cpu: 0.0,
+            score,
+        }
+    }
+
+    fn datanode_load(id: u64, regions: Vec<RegionLoad>) -> DatanodeLoad {
+        DatanodeLoad {
+            peer: Peer::empty(id),
+            regions,
+        }
+    }
+
+    fn region_stat(region_id: RegionId, role: RegionRole, write_bytes: u64) -> RegionStat {
+        RegionStat {
+            id: region_id,
+            role,
+            rcus: 1,
+            wcus: 2,
+            approximate_bytes: 0,
+            engine: String::new(),
+            num_rows: 0,
+            memtable_size: 0,
+            manifest_size: 0,
+            sst_size: 100,
+            sst_num: 0,
+            index_size: 0,
+            region_manifest: RegionManifestInfo::Mito {
+                manifest_version: 0,
+                flushed_entry_id: 0,
+            },
+            data_topic_latest_entry_id: 0,
+            metadata_topic_latest_entry_id: 0,
+            write_bytes,
+        }
+    }
+
+    #[test]
+    fn test_region_loads() {
+        let leader = RegionId::new(1024, 1);
+        let follower = RegionId::new(1024, 2);
+        let new_leader = RegionId::new(1024, 3);
+        let stat_value = DatanodeStatValue {
+            stats: vec![
+                Stat {
+                    timestamp_millis: 1000,
+                    region_stats: vec![region_stat(leader, RegionRole::Leader, 100)],
+                    ..Default::default()
+                },
+                Stat {
+                    timestamp_millis: 3000,
+                    region_stats: vec![
+                        region_stat(leader, RegionRole::Leader, 300),
+                        region_stat(follower, RegionRole::Follower, 300),
+                        region_stat(new_leader, RegionRole::Leader, 300),
+                    ],
+                    ..Default::default()
+                },
+            ],
+        };
+
+        let loads = region_loads(&stat_value);
+        assert_eq!(2, loads.len());
+        assert_eq!(leader, loads[0].region_id);
+        assert_eq!(100.0, loads[0].write_rate);
+        assert_eq!(100, loads[0].sst_size);
+        assert_eq!(3.0, loads[0].cpu);
+        // The region is absent in the oldest stat.
+        assert_eq!(new_leader, loads[1].region_id);
+        assert_eq!(0.0, loads[1].write_rate);
+
+        assert!(region_loads(&DatanodeStatValue { stats: vec![] }).is_empty());
+    }
+
+    #[test]
+    fn test_score_regions() {
+        let mut datanode_loads = vec![
+            datanode_load(
+                1,
+                vec![RegionLoad {
+                    write_rate: 300.0,
+                    sst_size: 100,
+                    cpu: 0.0,
+                    ..region_load(1024, 1, 0.0)
+                }],
+            ),
+            datanode_load(
+                2,
+                vec![RegionLoad {
+                    write_rate: 100.0,
+                    sst_size: 300,
+                    cpu: 0.0,
+                    ..region_load(1024, 2, 0.0)
+                }],
+            ),
+        ];
+        let options = RegionBalancerOptions {
+            write_rate_weight: 2.0,
+            ..Default::default()
+        };
+        score_regions(&mut datanode_loads, &options);
+        // 2.0 * 0.75 + 1.0 * 0.25, the CPU usage is ignored since the total is zero.
+        assert_eq!(1.75, datanode_loads[0].load());
+        // 2.0 * 0.25 + 1.0 * 0.75
+        assert_eq!(1.25, datanode_loads[1].load());
+    }
+
+    #[test]
+    fn test_plan_moves_balanced() {
+        let datanode_loads = vec![
+            datanode_load(1, vec![region_load(1024, 1, 0.5)]),
+            datanode_load(2, vec![region_load(1024, 2, 0.5)]),
+        ];
+        assert!(plan_moves(&datanode_loads, 0.2, 10, &HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn test_plan_moves_skewed() {
+        let datanode_loads = vec![
+            datanode_load(
+                1,
+                vec![
+                    region_load(1024, 1, 0.6),
+                    region_load(1024, 2, 0.3),
+                    region_load(1024, 3, 0.1),
+                ],
+            ),
+            datanode_load(2, vec![region_load(1024, 4, 0.2)]),
+            datanode_load(3, vec![region_load(1024, 5, 0.2)]),
+        ];
+
+        let moves = plan_moves(&datanode_loads, 0.2, 10, &HashSet::new());
+        assert_eq!(
+            moves,
+            vec![
+                PlannedMove {
+                    region_id: RegionId::new(1024, 2),
+                    from_peer: Peer::empty(1),
+                    to_peer: Peer::empty(2),
+                    load: 0.3,
+                },
+                PlannedMove {
+                    region_id: RegionId::new(1024, 3),
+                    from_peer: Peer::empty(1),
+                    to_peer: Peer::empty(3),
+                    load: 0.1,
+                },
+            ]
+        );
+
+        // Respects the max moves.
+        let moves = plan_moves(&datanode_loads, 0.2, 1, &HashSet::new());
+        assert_eq!(1, moves.len());
+        assert!(plan_moves(&datanode_loads, 0.2, 0, &HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn test_plan_moves_skip_excluded_regions() {
+        let datanode_loads = vec![
+            datanode_load(
+                1,
+                vec![region_load(1024, 1, 0.5), region_load(1024, 2, 0.3)],
+            ),
+            datanode_load(2, vec![]),
+        ];
+
+        let excluded = HashSet::from([RegionId::new(1024, 1)]);
+        let moves = plan_moves(&datanode_loads, 0.2, 10, &excluded);
+        assert_eq!(1, moves.len());
+        assert_eq!(RegionId::new(1024, 2), moves[0].region_id);
+
+        let excluded = HashSet::from([RegionId::new(1024, 1), RegionId::new(1024, 2)]);
+        assert!(plan_moves(&datanode_loads, 0.2, 10, &excluded).is_empty());
+    }
+
+    #[test]
+    fn test_move_cooldown() {
+        let now = Instant::now();
+        let mut cooldown = MoveCooldown::new(Duration::from_secs(60));
+        cooldown.record(RegionId::new(1024, 1), now);
+        cooldown.record(RegionId::new(1024, 2), now + Duration::from_secs(30));
+
+        cooldown.expire(now + Duration::from_secs(59));
+        let regions = cooldown.regions().collect::<HashSet<_>>();
+        assert_eq!(
+            HashSet::from([RegionId::new(1024, 1), RegionId::new(1024, 2)]),
+            regions
+        );
+
+        // The cooldown of the first region expires.
+        cooldown.expire(now + Duration::from_secs(60));
+        let regions = cooldown.regions().collect::<HashSet<_>>();
+        assert_eq!(HashSet::from([RegionId::new(1024, 2)]), regions);
+
+        // The cooling down region isn't moved again.
+        let datanode_loads = vec![
+            datanode_load(
+                1,
+                vec![region_load(1024, 1, 0.5), region_load(1024, 2, 0.3)],
+            ),
+            datanode_load(2, vec![]),
+        ];
+        let moves = plan_moves(&datanode_loads, 0.2, 10, &regions);
+        assert_eq!(1, moves.len());
+        assert_eq!(RegionId::new(1024, 1), moves[0].region_id);
+
+        cooldown.expire(now + Duration::from_secs(90));
+        assert_eq!(0, cooldown.regions().count());
+    }
+
+    #[test]
+    fn test_plan_moves_single_region() {
+        // Moving the only region doesn't reduce the gap.
+        let datanode_loads = vec![
+            datanode_load(1, vec![region_load(1024, 1, 1.0)]),
+            datanode_load(2, vec![]),
+        ];
+        assert!(plan_moves(&datanode_loads, 0.2, 10, &HashSet::new()).is_empty());
+    }
+}
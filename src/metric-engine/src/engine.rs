@@ -42,9 +42,10 @@ pub(crate) use state::MetricEngineState;
 use store_api::metadata::RegionMetadataRef;
 use store_api::metric_engine_consts::METRIC_ENGINE_NAME;
 use store_api::region_engine::{
-    BatchResponses, RegionEngine, RegionManifestInfo, RegionRole, RegionScannerRef,
-    RegionStatistic, SetRegionRoleStateResponse, SetRegionRoleStateSuccess,
-    SettableRegionRoleState, SyncManifestResponse,
+    BackupRegionRequest, BackupRegionResponse, BatchResponses, CopyRegionFromRequest,
+    CopyRegionFromResponse, RegionEngine, RegionManifestInfo, RegionRole, RegionScannerRef,
+    RegionStatistic, RestoreRegionRequest, RestoreRegionResponse, SetRegionRoleStateResponse,
+    SetRegionRoleStateSuccess, SettableRegionRoleState, SyncManifestResponse,
 };
 use store_api::region_request::{BatchRegionDdlRequest, RegionOpenRequest, RegionRequest};
 use store_api::storage::{RegionId, ScanRequest, SequenceNumber};
@@ -318,6 +319,29 @@ impl RegionEngine for MetricEngine {
             .map_err(BoxedError::new)
     }
 
+    async fn backup_region(
+        &self,
+        _request: BackupRegionRequest,
+    ) -> Result<BackupRegionResponse, BoxedError> {
+        // The rows of logical regions are keyed by their table ids in the data region,
+        // which are reallocated on restoring.
+        Ok(BackupRegionResponse::NotSupported)
+    }
+
+    async fn restore_region(
+        &self,
+        _request: RestoreRegionRequest,
+    ) -> Result<RestoreRegionResponse, BoxedError> {
+        Ok(RestoreRegionResponse::NotSupported)
+    }
+
+    async fn copy_region_from(
+        &self,
+        _request: CopyRegionFromRequest,
+    ) -> Result<CopyRegionFromResponse, BoxedError> {
+        Ok(CopyRegionFromResponse::NotSupported)
+    }
+
     async fn set_region_role_state_gracefully(
         &self,
         region_id: RegionId,
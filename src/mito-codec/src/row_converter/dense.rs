@@ -68,7 +68,10 @@ impl SortField {
             ConcreteDataType::Float64(_) => 9,
             ConcreteDataType::Binary(_)
             | ConcreteDataType::Json(_)
-            | ConcreteDataType::Vector(_) => 11,
+            | ConcreteDataType::Vector(_)
+            | ConcreteDataType::Uuid(_)
+            | ConcreteDataType::Map(_)
+            | ConcreteDataType::Ip(_) => 11,
             ConcreteDataType::String(_) => 11, // a non-empty string takes at least 11 bytes.
             ConcreteDataType::Date(_) => 5,
             ConcreteDataType::Timestamp(_) => 10,
@@ -164,7 +167,10 @@ impl SortField {
             Duration, duration,
             Decimal128, decimal128,
             Json, binary,
-            Vector, binary
+            Vector, binary,
+            Uuid, binary,
+            Map, binary,
+            Ip, binary
         );
 
         Ok(())
@@ -187,7 +193,7 @@ impl SortField {
                             Ok(Value::from(Option::<$f>::deserialize(deserializer).context(error::DeserializeFieldSnafu)?))
                         }
                     )*
-                    ConcreteDataType::Binary(_) | ConcreteDataType::Json(_) | ConcreteDataType::Vector(_) => Ok(Value::from(
+                    ConcreteDataType::Binary(_) | ConcreteDataType::Json(_) | ConcreteDataType::Vector(_) | ConcreteDataType::Uuid(_) | ConcreteDataType::Map(_) | ConcreteDataType::Ip(_) => Ok(Value::from(
                         Option::<Vec<u8>>::deserialize(deserializer)
                             .context(error::DeserializeFieldSnafu)?
                             .map(Bytes::from),
@@ -277,7 +283,10 @@ impl SortField {
             ConcreteDataType::Float64(_) => 9,
             ConcreteDataType::Binary(_)
             | ConcreteDataType::Json(_)
-            | ConcreteDataType::Vector(_) => {
+            | ConcreteDataType::Vector(_)
+            | ConcreteDataType::Uuid(_)
+            | ConcreteDataType::Map(_)
+            | ConcreteDataType::Ip(_) => {
                 // Now the encoder encode binary as a list of bytes so we can't use
                 // skip bytes.
                 let pos_before = deserializer.position();
@@ -0,0 +1,395 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Backup and restore of regions.
+//!
+//! A backup first pins a version of the region, then copies the SST and index files
+//! referenced by the version to an external object store, and returns a [RegionSnapshot]
+//! that lists them. Files are named by their file ids, so backups of the same region can
+//! share a directory and an incremental backup only copies the files that are not in the
+//! snapshot of its base backup.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use common_datasource::object_store::build_backend;
+use common_telemetry::{info, warn};
+use futures::AsyncWriteExt;
+use object_store::ObjectStore;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, OptionExt, ResultExt};
+use store_api::metadata::RegionMetadataRef;
+use store_api::region_engine::{BackupRegionResponse, RestoreRegionRequest};
+use store_api::storage::{RegionId, SequenceNumber};
+use store_api::ManifestVersion;
+
+use crate::error::{
+    BuildBackupStoreSnafu, CopyBackupFileSnafu, InvalidRequestSnafu, OpenDalSnafu, Result,
+    SerdeJsonSnafu,
+};
+use crate::manifest::action::RegionEdit;
+use crate::region::version::VersionRef;
+use crate::region::MitoRegionRef;
+use crate::sst::file::{FileId, FileMeta};
+use crate::sst::location;
+
+/// Pins that are not copied or released within the duration are dropped, in case
+/// the backup is aborted without releasing them.
+const PIN_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The files of a region pinned by a backup.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegionSnapshot {
+    /// The region that was backed up.
+    pub region_id: RegionId,
+    /// The manifest version of the region when the files were pinned.
+    pub manifest_version: ManifestVersion,
+    /// The metadata of the region.
+    pub metadata: RegionMetadataRef,
+    /// Inclusive max sequence of the rows in the files.
+    pub flushed_sequence: SequenceNumber,
+    /// The SST files of the region.
+    pub files: Vec<FileMeta>,
+}
+
+impl RegionSnapshot {
+    fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).context(SerdeJsonSnafu)
+    }
+
+    fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).context(SerdeJsonSnafu)
+    }
+}
+
+/// A version of a region pinned by a backup.
+///
+/// Holding the version keeps its files from being purged.
+struct PinnedVersion {
+    manifest_version: ManifestVersion,
+    version: VersionRef,
+    pinned_at: Instant,
+}
+
+/// The versions of regions pinned by backups, keyed by the backup name and the region id.
+#[derive(Default)]
+pub(crate) struct BackupPins {
+    pins: Mutex<HashMap<(String, RegionId), PinnedVersion>>,
+}
+
+impl BackupPins {
+    /// Pins the current version of the region for the backup.
+    ///
+    /// It returns the version pinned before if the region has been pinned by the backup.
+    pub(crate) async fn pin(
+        &self,
+        region: &MitoRegionRef,
+        backup_name: &str,
+    ) -> BackupRegionResponse {
+        // The version control is updated while holding the manifest manager lock, reads both
+        // under the lock to get a consistent pair.
+        let (manifest_version, version) = {
+            let manager = region.manifest_ctx.manifest_manager.read().await;
+            (manager.manifest().manifest_version, region.version())
+        };
+
+        let mut pins = self.pins.lock().unwrap();
+        pins.retain(|(name, region_id), pin| {
+            let expired = pin.pinned_at.elapsed() > PIN_TTL;
+            if expired {
+                warn!(
+                    "Drop the expired pin of region {} held by backup {}",
+                    region_id, name
+                );
+            }
+            !expired
+        });
+        let pin = pins
+            .entry((backup_name.to_string(), region.region_id))
+            .or_insert_with(|| PinnedVersion {
+                manifest_version,
+                version,
+                pinned_at: Instant::now(),
+            });
+        info!(
+            "Pinned region {} at manifest version {} for backup {}",
+            region.region_id, pin.manifest_version, backup_name
+        );
+
+        BackupRegionResponse::Pinned {
+            manifest_version: pin.manifest_version,
+            column_metadatas: pin.version.metadata.column_metadatas.clone(),
+        }
+    }
+
+    /// Releases the pin of the region held by the backup.
+    pub(crate) fn release(&self, region_id: RegionId, backup_name: &str) {
+        self.pins
+            .lock()
+            .unwrap()
+            .remove(&(backup_name.to_string(), region_id));
+    }
+
+    /// Returns the pinned version of the region.
+    fn get(&self, region_id: RegionId, backup_name: &str) -> Result<(ManifestVersion, VersionRef)> {
+        self.pins
+            .lock()
+            .unwrap()
+            .get(&(backup_name.to_string(), region_id))
+            .map(|pin| (pin.manifest_version, pin.version.clone()))
+            .with_context(|| InvalidRequestSnafu {
+                region_id,
+                reason: format!("region is not pinned by backup {backup_name}"),
+            })
+    }
+}
+
+/// Copies the files referenced by the version of the region pinned by the backup
+/// to the backup location, and releases the pin.
+pub(crate) async fn backup_region(
+    pins: &BackupPins,
+    region: &MitoRegionRef,
+    backup_name: &str,
+    location: &str,
+    connection: &HashMap<String, String>,
+    base_snapshot: Option<&str>,
+) -> Result<BackupRegionResponse> {
+    let region_id = region.region_id;
+    let base_files = match base_snapshot {
+        Some(base) => RegionSnapshot::from_json(base)?
+            .files
+            .into_iter()
+            .map(|file| file.file_id)
+            .collect(),
+        None => HashSet::new(),
+    };
+    let target = build_backup_store(location, connection)?;
+
+    let (manifest_version, version) = pins.get(region_id, backup_name)?;
+    let files = version
+        .ssts
+        .levels()
+        .iter()
+        .flat_map(|level| level.files())
+        .map(|file| file.meta_ref().clone())
+        .collect::<Vec<_>>();
+
+    let layer = &region.access_layer;
+    let mut copied_files = 0;
+    let mut copied_bytes = 0;
+    for file in files
+        .iter()
+        .filter(|file| !base_files.contains(&file.file_id))
+    {
+        let sst_path =
+            location::sst_file_path(layer.table_dir(), file.file_id(), layer.path_type());
+        copied_bytes += copy_file(
+            layer.object_store(),
+            &sst_path,
+            &target,
+            &backup_sst_path(file.file_id),
+        )
+        .await?;
+        if file.exists_index() {
+            let index_path =
+                location::index_file_path(layer.table_dir(), file.file_id(), layer.path_type());
+            copied_bytes += copy_file(
+                layer.object_store(),
+                &index_path,
+                &target,
+                &backup_index_path(file.file_id),
+            )
+            .await?;
+        }
+        copied_files += 1;
+    }
+
+    info!(
+        "Backed up region {} at manifest version {}, files: {}, copied files: {}, copied bytes: {}",
+        region_id,
+        manifest_version,
+        files.len(),
+        copied_files,
+        copied_bytes
+    );
+
+    let snapshot = RegionSnapshot {
+        region_id,
+        manifest_version,
+        metadata: version.metadata.clone(),
+        flushed_sequence: version.flushed_sequence,
+        files,
+    };
+    let snapshot = snapshot.to_json()?;
+    pins.release(region_id, backup_name);
+
+    Ok(BackupRegionResponse::Backup {
+        manifest_version,
+        copied_files,
+        copied_bytes,
+        snapshot,
+    })
+}
+
+/// Copies the files of the backup into the empty region.
+///
+/// Returns the [RegionEdit] to add the files to the region.
+pub(crate) async fn restore_region_files(
+    region: &MitoRegionRef,
+    request: &RestoreRegionRequest,
+) -> Result<RegionEdit> {
+    let region_id = region.region_id;
+    let snapshot = RegionSnapshot::from_json(&request.snapshot)?;
+    let version = region.version();
+    ensure!(
+        version.ssts.num_files() == 0 && version.memtables.is_empty(),
+        InvalidRequestSnafu {
+            region_id,
+            reason: "region to restore is not empty",
+        }
+    );
+    ensure_compatible_metadata(region_id, &snapshot.metadata, &version.metadata)?;
+
+    let source = build_backup_store(&request.location, &request.connection)?;
+    let layer = &region.access_layer;
+    let mut files_to_add = Vec::with_capacity(snapshot.files.len());
+    for mut file in snapshot.files {
+        // The files are placed under the directory of the region to restore.
+        file.region_id = region_id;
+        let sst_path =
+            location::sst_file_path(layer.table_dir(), file.file_id(), layer.path_type());
+        copy_file(
+            &source,
+            &backup_sst_path(file.file_id),
+            layer.object_store(),
+            &sst_path,
+        )
+        .await?;
+        if file.exists_index() {
+            let index_path =
+                location::index_file_path(layer.table_dir(), file.file_id(), layer.path_type());
+            copy_file(
+                &source,
+                &backup_index_path(file.file_id),
+                layer.object_store(),
+                &index_path,
+            )
+            .await?;
+        }
+        files_to_add.push(file);
+    }
+
+    info!(
+        "Restored {} files of region {} to region {}",
+        files_to_add.len(),
+        snapshot.region_id,
+        region_id
+    );
+
+    Ok(RegionEdit {
+        files_to_add,
+        files_to_remove: vec![],
+        timestamp_ms: None,
+        compaction_time_window: None,
+        flushed_entry_id: None,
+        // Rows in the files keep their sequences, new rows must have larger sequences.
+        flushed_sequence: Some(snapshot.flushed_sequence),
+    })
+}
+
+/// Ensures the columns of the backup have the same ids in the region to restore,
+/// as the SST files reference columns by ids.
+pub(crate) fn ensure_compatible_metadata(
+    region_id: RegionId,
+    backup: &RegionMetadataRef,
+    current: &RegionMetadataRef,
+) -> Result<()> {
+    for column in &backup.column_metadatas {
+        let name = &column.column_schema.name;
+        let compatible = current.column_by_name(name).is_some_and(|c| {
+            c.column_id == column.column_id
+                && c.semantic_type == column.semantic_type
+                && c.column_schema.data_type == column.column_schema.data_type
+        });
+        ensure!(
+            compatible,
+            InvalidRequestSnafu {
+                region_id,
+                reason: format!("column {name} of the backup is incompatible with the region"),
+            }
+        );
+    }
+    ensure!(
+        backup.primary_key == current.primary_key
+            && backup.primary_key_encoding == current.primary_key_encoding,
+        InvalidRequestSnafu {
+            region_id,
+            reason: "primary key of the backup is incompatible with the region",
+        }
+    );
+
+    Ok(())
+}
+
+fn build_backup_store(url: &str, connection: &HashMap<String, String>) -> Result<ObjectStore> {
+    build_backend(url, connection).context(BuildBackupStoreSnafu { url })
+}
+
+/// Returns the path of the SST file in the backup directory.
+fn backup_sst_path(file_id: FileId) -> String {
+    format!("{file_id}.parquet")
+}
+
+/// Returns the path of the index file in the backup directory.
+fn backup_index_path(file_id: FileId) -> String {
+    format!("index/{file_id}.puffin")
+}
+
+/// Copies a file between object stores, returns the number of bytes copied.
+pub(crate) async fn copy_file(
+    from_store: &ObjectStore,
+    from: &str,
+    to_store: &ObjectStore,
+    to: &str,
+) -> Result<u64> {
+    let file_size = from_store
+        .stat(from)
+        .await
+        .context(OpenDalSnafu)?
+        .content_length();
+    let reader = from_store
+        .reader(from)
+        .await
+        .context(OpenDalSnafu)?
+        .into_futures_async_read(0..file_size)
+        .await
+        .context(OpenDalSnafu)?;
+    let mut writer = to_store
+        .writer(to)
+        .await
+        .context(OpenDalSnafu)?
+        .into_futures_async_write();
+
+    let bytes_copied = futures::io::copy(reader, &mut writer)
+        .await
+        .context(CopyBackupFileSnafu { from, to })?;
+    // Must close to flush all data.
+    writer
+        .close()
+        .await
+        .context(CopyBackupFileSnafu { from, to })?;
+
+    Ok(bytes_copied)
+}
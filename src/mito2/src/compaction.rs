@@ -15,7 +15,9 @@
 mod buckets;
 pub mod compactor;
 pub mod picker;
+mod rollup;
 pub mod run;
+mod size_tiered;
 mod task;
 #[cfg(test)]
 mod test_util;
@@ -39,7 +41,7 @@ use datafusion_expr::Expr;
 use serde::{Deserialize, Serialize};
 use snafu::{OptionExt, ResultExt};
 use store_api::metadata::RegionMetadataRef;
-use store_api::storage::{RegionId, TableId};
+use store_api::storage::{RegionId, RollupAggregator, TableId};
 use task::MAX_PARALLEL_COMPACTION;
 use tokio::sync::mpsc::{self, Sender};
 
@@ -56,6 +58,7 @@ use crate::error::{
 };
 use crate::metrics::{COMPACTION_STAGE_ELAPSED, INFLIGHT_COMPACTION_COUNT};
 use crate::read::projection::ProjectionMapper;
+use crate::read::rollup::RollupReader;
 use crate::read::scan_region::{PredicateGroup, ScanInput};
 use crate::read::seq_scan::SeqScan;
 use crate::read::BoxedBatchReader;
@@ -67,7 +70,7 @@ use crate::schedule::remote_job_scheduler::{
     CompactionJob, DefaultNotifier, RemoteJob, RemoteJobSchedulerRef,
 };
 use crate::schedule::scheduler::SchedulerRef;
-use crate::sst::file::{FileHandle, FileMeta, Level};
+use crate::sst::file::{FileHandle, FileMeta, Level, RollupMeta};
 use crate::sst::version::LevelMeta;
 use crate::worker::WorkerListener;
 
@@ -304,6 +307,7 @@ impl CompactionScheduler {
         let picker = new_picker(
             &options,
             &request.current_version.options.compaction,
+            request.current_version.options.rollup.as_ref(),
             request.current_version.options.append_mode,
         );
         let region_id = request.region_id();
@@ -612,6 +616,8 @@ pub struct CompactionOutput {
     pub filter_deleted: bool,
     /// Compaction output time range. Only windowed compaction specifies output time range.
     pub output_time_range: Option<TimestampRange>,
+    /// Rollup tier of the output. The inputs are aggregated into this tier if present.
+    pub rollup: Option<RollupMeta>,
 }
 
 /// SerializedCompactionOutput is a serialized version of [CompactionOutput] by replacing [FileHandle] with [FileMeta].
@@ -621,9 +627,12 @@ pub struct SerializedCompactionOutput {
     inputs: Vec<FileMeta>,
     filter_deleted: bool,
     output_time_range: Option<TimestampRange>,
+    #[serde(default)]
+    rollup: Option<RollupMeta>,
 }
 
 /// Builders to create [BoxedBatchReader] for compaction.
+#[derive(Clone)]
 struct CompactionSstReaderBuilder<'a> {
     metadata: RegionMetadataRef,
     sst_layer: AccessLayerRef,
@@ -662,6 +671,61 @@ impl CompactionSstReaderBuilder<'_> {
             .build_reader_for_compaction()
             .await
     }
+
+    /// Builds [BoxedBatchReader] that rolls up raw files of the inputs into the `rollup` tier.
+    ///
+    /// Input files rolled up into the same tier before are merged into the output. Averages
+    /// are merged with input files of the count aggregator in the same interval.
+    async fn build_rollup_reader(self, rollup: RollupMeta) -> Result<BoxedBatchReader> {
+        let files_of = |meta: Option<RollupMeta>| {
+            self.inputs
+                .iter()
+                .filter(|f| f.rollup() == meta)
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        let raw = files_of(None);
+        let rolled = files_of(Some(rollup));
+        let counts = if rollup.aggregator == RollupAggregator::Avg {
+            files_of(Some(RollupMeta {
+                interval_secs: rollup.interval_secs,
+                aggregator: RollupAggregator::Count,
+            }))
+        } else {
+            Vec::new()
+        };
+
+        let reader = CompactionSstReaderBuilder {
+            inputs: &raw,
+            ..self.clone()
+        }
+        .build_sst_reader()
+        .await?;
+        let mut reader = RollupReader::new(reader, rollup);
+        if !rolled.is_empty() {
+            let rolled_reader = CompactionSstReaderBuilder {
+                inputs: &rolled,
+                ..self.clone()
+            }
+            .build_sst_reader()
+            .await?;
+            let counts_reader = if counts.is_empty() {
+                None
+            } else {
+                Some(
+                    CompactionSstReaderBuilder {
+                        inputs: &counts,
+                        ..self
+                    }
+                    .build_sst_reader()
+                    .await?,
+                )
+            };
+            reader = reader.with_rolled(rolled_reader, counts_reader);
+        }
+
+        Ok(Box::new(reader))
+    }
 }
 
 /// Converts time range to predicates so that rows outside the range will be filtered.
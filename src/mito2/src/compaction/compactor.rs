@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::num::NonZero;
 use std::sync::Arc;
 use std::time::Duration;
@@ -309,11 +310,20 @@ impl Compactor for DefaultCompactor {
             Vec::with_capacity(picker_output.outputs.iter().map(|o| o.inputs.len()).sum());
         let internal_parallelism = compaction_region.max_parallelism.max(1);
 
+        let mut compacted_file_ids = HashSet::new();
         for output in picker_output.outputs.drain(..) {
-            compacted_inputs.extend(output.inputs.iter().map(|f| f.meta_ref().clone()));
+            // Outputs of different rollup tiers may share the same inputs.
+            compacted_inputs.extend(
+                output
+                    .inputs
+                    .iter()
+                    .filter(|f| compacted_file_ids.insert(f.file_id()))
+                    .map(|f| f.meta_ref().clone()),
+            );
             let write_opts = WriteOptions {
                 write_buffer_size: compaction_region.engine_config.sst_write_buffer_size,
                 max_file_size: picker_output.max_file_size,
+                json_format: compaction_region.current_version.options.json_format,
                 ..Default::default()
             };
 
@@ -345,7 +355,7 @@ impl Compactor for DefaultCompactor {
                     .iter()
                     .map(|f| f.file_id().to_string())
                     .join(",");
-                let reader = CompactionSstReaderBuilder {
+                let builder = CompactionSstReaderBuilder {
                     metadata: region_metadata.clone(),
                     sst_layer: sst_layer.clone(),
                     cache: cache_manager.clone(),
@@ -354,9 +364,11 @@ impl Compactor for DefaultCompactor {
                     filter_deleted: output.filter_deleted,
                     time_range: output.output_time_range,
                     merge_mode,
-                }
-                .build_sst_reader()
-                .await?;
+                };
+                let reader = match output.rollup {
+                    Some(rollup) => builder.build_rollup_reader(rollup).await?,
+                    None => builder.build_sst_reader().await?,
+                };
                 let (sst_infos, metrics) = sst_layer
                     .write_sst(
                         SstWriteRequest {
@@ -388,6 +400,7 @@ impl Compactor for DefaultCompactor {
                         num_rows: sst_info.num_rows as u64,
                         num_row_groups: sst_info.num_row_groups,
                         sequence: max_sequence,
+                        rollup: output.rollup,
                     })
                     .collect::<Vec<_>>();
                 let output_file_names =
@@ -470,6 +483,7 @@ impl Compactor for DefaultCompactor {
             let picker_output = new_picker(
                 &compact_request_options,
                 &compaction_region.region_options.compaction,
+                compaction_region.region_options.rollup.as_ref(),
                 compaction_region.region_options.append_mode,
             )
             .pick(compaction_region);
@@ -19,10 +19,12 @@ use api::v1::region::compact_request;
 use serde::{Deserialize, Serialize};
 
 use crate::compaction::compactor::CompactionRegion;
+use crate::compaction::rollup::RollupPicker;
+use crate::compaction::size_tiered::SizeTieredPicker;
 use crate::compaction::twcs::TwcsPicker;
 use crate::compaction::window::WindowedCompactionPicker;
 use crate::compaction::{CompactionOutput, SerializedCompactionOutput};
-use crate::region::options::CompactionOptions;
+use crate::region::options::{CompactionOptions, RollupOptions};
 use crate::sst::file::{FileHandle, FileMeta};
 use crate::sst::file_purger::FilePurger;
 
@@ -68,6 +70,7 @@ impl From<&PickerOutput> for SerializedPickerOutput {
                 inputs: output.inputs.iter().map(|s| s.meta_ref().clone()).collect(),
                 filter_deleted: output.filter_deleted,
                 output_time_range: output.output_time_range,
+                rollup: output.rollup,
             })
             .collect();
         let expired_ssts = input
@@ -102,6 +105,7 @@ impl PickerOutput {
                     .collect(),
                 filter_deleted: output.filter_deleted,
                 output_time_range: output.output_time_range,
+                rollup: output.rollup,
             })
             .collect();
 
@@ -124,6 +128,7 @@ impl PickerOutput {
 pub fn new_picker(
     compact_request_options: &compact_request::Options,
     compaction_options: &CompactionOptions,
+    rollup_options: Option<&RollupOptions>,
     append_mode: bool,
 ) -> Arc<dyn Picker> {
     if let compact_request::Options::StrictWindow(window) = compact_request_options {
@@ -134,13 +139,30 @@ pub fn new_picker(
         };
         Arc::new(WindowedCompactionPicker::new(window)) as Arc<_>
     } else {
-        match compaction_options {
+        let picker = match compaction_options {
             CompactionOptions::Twcs(twcs_opts) => Arc::new(TwcsPicker {
                 trigger_file_num: twcs_opts.trigger_file_num,
                 time_window_seconds: twcs_opts.time_window_seconds(),
                 max_output_file_size: twcs_opts.max_output_file_size.map(|r| r.as_bytes()),
                 append_mode,
             }) as Arc<_>,
+            CompactionOptions::SizeTiered(size_tiered_opts) => Arc::new(SizeTieredPicker {
+                min_threshold: size_tiered_opts.min_threshold,
+                max_threshold: size_tiered_opts.max_threshold,
+                min_file_size: size_tiered_opts
+                    .min_file_size
+                    .map(|r| r.as_bytes())
+                    .unwrap_or_default(),
+                time_window_seconds: size_tiered_opts.time_window_seconds(),
+                max_output_file_size: size_tiered_opts.max_output_file_size.map(|r| r.as_bytes()),
+            }) as Arc<_>,
+        };
+        match rollup_options {
+            Some(options) => Arc::new(RollupPicker {
+                inner: picker,
+                options: options.clone(),
+            }) as Arc<_>,
+            None => picker,
         }
     }
 }
@@ -171,12 +193,14 @@ mod tests {
                     inputs: inputs_file_handle.clone(),
                     filter_deleted: false,
                     output_time_range: None,
+                    rollup: None,
                 },
                 CompactionOutput {
                     output_level: 0,
                     inputs: inputs_file_handle.clone(),
                     filter_deleted: false,
                     output_time_range: None,
+                    rollup: None,
                 },
             ],
             expired_ssts: expired_ssts_file_handle.clone(),
@@ -0,0 +1,336 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use common_telemetry::info;
+use common_time::timestamp::TimeUnit;
+use common_time::Timestamp;
+use store_api::storage::RollupAggregator;
+
+use crate::compaction::buckets::infer_time_bucket;
+use crate::compaction::compactor::CompactionRegion;
+use crate::compaction::picker::{Picker, PickerOutput};
+use crate::compaction::{get_expired_ssts, CompactionOutput};
+use crate::region::options::RollupOptions;
+use crate::sst::file::{FileHandle, Level, RollupMeta};
+use crate::sst::version::LevelMeta;
+
+const LEVEL_COMPACTED: Level = 1;
+
+/// `RollupPicker` rolls up raw files older than the rollup threshold into the
+/// rollup tiers of the region. Each aggregator of each tier is written to its
+/// own files and the raw files are removed.
+///
+/// Rolled up files overlapping intervals of the raw files are compacted with them,
+/// so rows arriving after their intervals were rolled up are merged into the existing
+/// aggregates. Tiers with the average aggregator also keep row counts to merge
+/// averages, even if they don't declare the count aggregator.
+///
+/// Other files are picked by the inner picker once there is nothing to roll up.
+#[derive(Debug)]
+pub struct RollupPicker {
+    /// Picker for files that are not rolled up.
+    pub inner: Arc<dyn Picker>,
+    /// Rollup options of the region.
+    pub options: RollupOptions,
+}
+
+impl RollupPicker {
+    /// Builds one output for each aggregator of each tier from raw `inputs` and the
+    /// `rolled` files of the tier overlapping them.
+    fn build_output(&self, inputs: &[FileHandle], rolled: &[FileHandle]) -> Vec<CompactionOutput> {
+        let mut outputs = Vec::new();
+        for tier in &self.options.tiers {
+            let interval_secs = tier.interval.as_secs();
+            let (start, end) = interval_range(inputs, interval_secs);
+            let count = RollupMeta {
+                interval_secs,
+                aggregator: RollupAggregator::Count,
+            };
+            let mut aggregators = tier.aggregators.clone();
+            if aggregators.contains(&RollupAggregator::Avg)
+                && !aggregators.contains(&RollupAggregator::Count)
+            {
+                aggregators.push(RollupAggregator::Count);
+            }
+
+            for aggregator in aggregators {
+                let rollup = RollupMeta {
+                    interval_secs,
+                    aggregator,
+                };
+                let mut output_inputs = inputs.to_vec();
+                output_inputs.extend(
+                    rolled
+                        .iter()
+                        .filter(|f| {
+                            f.rollup() == Some(rollup)
+                                || (aggregator == RollupAggregator::Avg
+                                    && f.rollup() == Some(count))
+                        })
+                        .filter(|f| {
+                            let (file_start, file_end) = millis_range(f);
+                            file_start < end && file_end >= start
+                        })
+                        .cloned(),
+                );
+                outputs.push(CompactionOutput {
+                    output_level: LEVEL_COMPACTED,
+                    inputs: output_inputs,
+                    filter_deleted: true,
+                    output_time_range: None,
+                    rollup: Some(rollup),
+                });
+            }
+        }
+        outputs
+    }
+}
+
+/// Returns the time range `[start, end)` in milliseconds of the intervals
+/// containing rows of the `files`.
+fn interval_range(files: &[FileHandle], interval_secs: u64) -> (i64, i64) {
+    let interval = (interval_secs as i64).saturating_mul(1000).max(1);
+    let start = files
+        .iter()
+        .map(|f| millis_range(f).0)
+        .min()
+        .unwrap_or_default();
+    let end = files
+        .iter()
+        .map(|f| millis_range(f).1)
+        .max()
+        .unwrap_or_default();
+    (
+        start.div_euclid(interval) * interval,
+        (end.div_euclid(interval) + 1) * interval,
+    )
+}
+
+/// Returns the time range of the file in milliseconds.
+fn millis_range(file: &FileHandle) -> (i64, i64) {
+    let (start, end) = file.time_range();
+    let to_millis = |ts: Timestamp| {
+        ts.convert_to(TimeUnit::Millisecond)
+            .map(|ts| ts.value())
+            .unwrap_or(ts.value())
+    };
+    (to_millis(start), to_millis(end))
+}
+
+impl Picker for RollupPicker {
+    fn pick(&self, compaction_region: &CompactionRegion) -> Option<PickerOutput> {
+        let region_id = compaction_region.region_id;
+        let levels = compaction_region.current_version.ssts.levels();
+        let now = Timestamp::current_millis();
+        let Ok(threshold) = now.sub_duration(self.options.after) else {
+            return self.inner.pick(compaction_region);
+        };
+
+        let expired_ssts = get_expired_ssts(levels, compaction_region.ttl, now);
+        let inputs = find_rollup_inputs(levels, &expired_ssts, threshold);
+        if inputs.is_empty() {
+            return self.inner.pick(compaction_region);
+        }
+        if !expired_ssts.is_empty() {
+            info!("Expired SSTs in region {}: {:?}", region_id, expired_ssts);
+            // here we mark expired SSTs as compacting to avoid them being picked.
+            expired_ssts.iter().for_each(|f| f.set_compacting(true));
+        }
+        info!(
+            "Region ({:?}) rollup pick result: threshold: {}, input files: {:?}",
+            region_id,
+            threshold.to_iso8601_string(),
+            inputs.iter().map(|f| f.file_id()).collect::<Vec<_>>()
+        );
+
+        let time_window_size = compaction_region
+            .current_version
+            .compaction_time_window
+            .or(compaction_region
+                .current_version
+                .options
+                .compaction
+                .time_window())
+            .map(|window| window.as_secs() as i64)
+            .unwrap_or_else(|| infer_time_bucket(levels[0].files()));
+
+        Some(PickerOutput {
+            outputs: self.build_output(&inputs, &find_rolled_files(levels, &expired_ssts)),
+            expired_ssts,
+            time_window_size,
+            max_file_size: None,
+        })
+    }
+}
+
+/// Finds raw files whose data are all older than the `threshold`.
+fn find_rollup_inputs(
+    levels: &[LevelMeta],
+    expired_ssts: &[FileHandle],
+    threshold: Timestamp,
+) -> Vec<FileHandle> {
+    find_files(levels, expired_ssts, |f| {
+        f.rollup().is_none() && f.time_range().1 < threshold
+    })
+}
+
+/// Finds rolled up files that are not expired.
+fn find_rolled_files(levels: &[LevelMeta], expired_ssts: &[FileHandle]) -> Vec<FileHandle> {
+    find_files(levels, expired_ssts, |f| f.rollup().is_some())
+}
+
+/// Finds files that are not compacting or expired and match the `predicate`.
+fn find_files(
+    levels: &[LevelMeta],
+    expired_ssts: &[FileHandle],
+    predicate: impl Fn(&FileHandle) -> bool,
+) -> Vec<FileHandle> {
+    let expired = expired_ssts
+        .iter()
+        .map(|f| f.file_id())
+        .collect::<HashSet<_>>();
+    levels
+        .iter()
+        .flat_map(LevelMeta::files)
+        .filter(|f| !f.compacting() && !expired.contains(&f.file_id()) && predicate(f))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::compaction::test_util::new_file_handle;
+    use crate::compaction::twcs::TwcsPicker;
+    use crate::region::options::RollupTier;
+    use crate::sst::file::{FileId, FileMeta};
+    use crate::test_util::new_noop_file_purger;
+    use crate::test_util::version_util::new_level_metas;
+
+    fn new_rolled_file(
+        start: i64,
+        end: i64,
+        interval_secs: u64,
+        aggregator: RollupAggregator,
+    ) -> FileHandle {
+        let file = new_file_handle(FileId::random(), start, end, 1);
+        FileHandle::new(
+            FileMeta {
+                rollup: Some(RollupMeta {
+                    interval_secs,
+                    aggregator,
+                }),
+                ..file.meta_ref().clone()
+            },
+            new_noop_file_purger(),
+        )
+    }
+
+    #[test]
+    fn test_find_rollup_inputs() {
+        let files = [
+            new_file_handle(FileId::random(), 0, 999, 0),
+            new_file_handle(FileId::random(), 500, 2000, 1),
+            new_file_handle(FileId::random(), 0, 999, 1),
+            new_file_handle(FileId::random(), 0, 500, 0),
+        ];
+        files[2].set_compacting(true);
+        let levels = new_level_metas(&files);
+
+        let inputs = find_rollup_inputs(&levels, &files[3..], Timestamp::new_millisecond(1000));
+        assert_eq!(1, inputs.len());
+        assert_eq!(files[0].file_id(), inputs[0].file_id());
+    }
+
+    #[test]
+    fn test_build_output() {
+        let picker = RollupPicker {
+            inner: Arc::new(TwcsPicker {
+                trigger_file_num: 4,
+                time_window_seconds: None,
+                max_output_file_size: None,
+                append_mode: true,
+            }),
+            options: RollupOptions {
+                tiers: vec![
+                    RollupTier {
+                        interval: Duration::from_secs(300),
+                        aggregators: vec![RollupAggregator::Avg, RollupAggregator::Max],
+                    },
+                    RollupTier {
+                        interval: Duration::from_secs(3600),
+                        aggregators: vec![RollupAggregator::Avg],
+                    },
+                ],
+                after: Duration::from_secs(3600),
+            },
+        };
+        let inputs = [
+            new_file_handle(FileId::random(), 310_000, 320_000, 0),
+            new_file_handle(FileId::random(), 400_000, 500_000, 1),
+        ];
+        let rolled = [
+            new_rolled_file(0, 0, 300, RollupAggregator::Avg),
+            new_rolled_file(0, 300_000, 300, RollupAggregator::Avg),
+            new_rolled_file(300_000, 300_000, 300, RollupAggregator::Count),
+            new_rolled_file(600_000, 900_000, 300, RollupAggregator::Max),
+            new_rolled_file(0, 0, 3600, RollupAggregator::Avg),
+        ];
+
+        let outputs = picker.build_output(&inputs, &rolled);
+        let rollups = outputs
+            .iter()
+            .map(|output| {
+                let rollup = output.rollup.unwrap();
+                let rolled_inputs = output
+                    .inputs
+                    .iter()
+                    .skip(2)
+                    .map(|f| {
+                        rolled
+                            .iter()
+                            .position(|r| r.file_id() == f.file_id())
+                            .unwrap()
+                    })
+                    .collect::<Vec<_>>();
+                (rollup.interval_secs, rollup.aggregator, rolled_inputs)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (300, RollupAggregator::Avg, vec![1, 2]),
+                (300, RollupAggregator::Max, vec![]),
+                (300, RollupAggregator::Count, vec![2]),
+                (3600, RollupAggregator::Avg, vec![4]),
+                (3600, RollupAggregator::Count, vec![]),
+            ],
+            rollups
+        );
+    }
+
+    #[test]
+    fn test_interval_range() {
+        let files = [
+            new_file_handle(FileId::random(), 310_000, 320_000, 0),
+            new_file_handle(FileId::random(), -1000, 500_000, 1),
+        ];
+        assert_eq!((-300_000, 600_000), interval_range(&files, 300));
+        assert_eq!((-3_600_000, 3_600_000), interval_range(&files, 3600));
+    }
+}
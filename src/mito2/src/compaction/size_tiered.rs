@@ -0,0 +1,360 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap};
+use std::num::NonZeroU64;
+
+use common_base::readable_size::ReadableSize;
+use common_telemetry::info;
+use common_time::timestamp::TimeUnit;
+use common_time::timestamp_millis::BucketAligned;
+use common_time::Timestamp;
+use store_api::storage::RegionId;
+
+use crate::compaction::buckets::infer_time_bucket;
+use crate::compaction::compactor::CompactionRegion;
+use crate::compaction::picker::{Picker, PickerOutput};
+use crate::compaction::run::{FileGroup, Item};
+use crate::compaction::{get_expired_ssts, CompactionOutput};
+use crate::sst::file::{FileHandle, Level};
+use crate::sst::version::LevelMeta;
+
+const LEVEL_COMPACTED: Level = 1;
+
+/// A file belongs to a tier if its size is no larger than `BUCKET_HIGH` times the
+/// average size of the tier.
+const BUCKET_HIGH: f64 = 1.5;
+
+/// `SizeTieredPicker` groups files in the same time window into tiers of similar size
+/// and picks a tier as compaction candidates once it has enough files.
+///
+/// Unlike TWCS, it never merges small files into a large one, so each row is rewritten
+/// only once per tier. This bounds the write amplification of append-only tables that
+/// flush many small files.
+#[derive(Debug)]
+pub struct SizeTieredPicker {
+    /// Minimum file num in a tier to trigger a compaction.
+    pub min_threshold: usize,
+    /// Maximum file num to compact in a tier at once.
+    pub max_threshold: usize,
+    /// Files smaller than this size are considered in the same tier.
+    pub min_file_size: u64,
+    /// Compaction time window in seconds.
+    pub time_window_seconds: Option<i64>,
+    /// Max allowed compaction output file size. Larger files are not compacted.
+    pub max_output_file_size: Option<u64>,
+}
+
+impl SizeTieredPicker {
+    /// Builds compaction output from file groups in each window.
+    fn build_output(
+        &self,
+        region_id: RegionId,
+        windows: BTreeMap<i64, Vec<FileGroup>>,
+    ) -> Vec<CompactionOutput> {
+        // A tier with only one file can't be compacted.
+        let min_threshold = self.min_threshold.max(2);
+        let max_threshold = self.max_threshold.max(min_threshold);
+
+        let mut output = vec![];
+        for (window, groups) in windows {
+            for tier in self.find_tiers(groups) {
+                if tier.groups.len() < min_threshold {
+                    continue;
+                }
+                let inputs = tier
+                    .groups
+                    .into_iter()
+                    .take(max_threshold)
+                    .collect::<Vec<_>>();
+                log_pick_result(region_id, window, self.max_output_file_size, &inputs);
+                output.push(CompactionOutput {
+                    output_level: LEVEL_COMPACTED,
+                    inputs: inputs.into_iter().flat_map(|fg| fg.into_files()).collect(),
+                    // Other tiers in the window may still overlap with the output.
+                    filter_deleted: false,
+                    output_time_range: None,
+                    rollup: None,
+                });
+            }
+        }
+        output
+    }
+
+    /// Groups files into tiers by their sizes. Files in each tier are sorted by size.
+    fn find_tiers(&self, groups: Vec<FileGroup>) -> Vec<Tier> {
+        let mut groups = groups
+            .into_iter()
+            .filter(|group| {
+                self.max_output_file_size
+                    .is_none_or(|max_size| (group.size() as u64) < max_size)
+            })
+            .collect::<Vec<_>>();
+        groups.sort_unstable_by_key(|group| group.size());
+
+        let mut tiers: Vec<Tier> = vec![];
+        for group in groups {
+            match tiers.last_mut() {
+                Some(tier) if tier.accepts(group.size() as u64, self.min_file_size) => {
+                    tier.add(group);
+                }
+                _ => tiers.push(Tier::new(group)),
+            }
+        }
+        tiers
+    }
+}
+
+fn log_pick_result(
+    region_id: RegionId,
+    window: i64,
+    max_output_file_size: Option<u64>,
+    inputs: &[FileGroup],
+) {
+    let input_file_str: Vec<String> = inputs
+        .iter()
+        .map(|f| {
+            format!(
+                "FileGroup{{id: {:?}, size: {}, num rows: {} }}",
+                f.file_ids(),
+                ReadableSize(f.size() as u64),
+                f.num_rows()
+            )
+        })
+        .collect();
+    let window_str = Timestamp::new_second(window).to_iso8601_string();
+    let max_output_file_size = max_output_file_size.map(|size| ReadableSize(size).to_string());
+    info!(
+        "Region ({:?}) size-tiered compaction pick result: current window: {}, \
+            max output file size: {:?}, input files: {:?}",
+        region_id, window_str, max_output_file_size, input_file_str
+    );
+}
+
+impl Picker for SizeTieredPicker {
+    fn pick(&self, compaction_region: &CompactionRegion) -> Option<PickerOutput> {
+        let region_id = compaction_region.region_id;
+        let levels = compaction_region.current_version.ssts.levels();
+
+        let expired_ssts =
+            get_expired_ssts(levels, compaction_region.ttl, Timestamp::current_millis());
+        if !expired_ssts.is_empty() {
+            info!("Expired SSTs in region {}: {:?}", region_id, expired_ssts);
+            // here we mark expired SSTs as compacting to avoid them being picked.
+            expired_ssts.iter().for_each(|f| f.set_compacting(true));
+        }
+
+        let compaction_time_window = compaction_region
+            .current_version
+            .compaction_time_window
+            .map(|window| window.as_secs() as i64);
+        let time_window_size = compaction_time_window
+            .or(self.time_window_seconds)
+            .unwrap_or_else(|| {
+                let inferred = infer_time_bucket(levels[0].files());
+                info!(
+                    "Compaction window for region {} is not present, inferring from files: {:?}",
+                    region_id, inferred
+                );
+                inferred
+            });
+
+        let windows = assign_to_windows(levels.iter().flat_map(LevelMeta::files), time_window_size);
+        let outputs = self.build_output(region_id, windows);
+
+        if outputs.is_empty() && expired_ssts.is_empty() {
+            return None;
+        }
+
+        let max_file_size = self.max_output_file_size.map(|v| v as usize);
+        Some(PickerOutput {
+            outputs,
+            expired_ssts,
+            time_window_size,
+            max_file_size,
+        })
+    }
+}
+
+/// Files with similar sizes.
+struct Tier {
+    groups: Vec<FileGroup>,
+    total_size: u64,
+}
+
+impl Tier {
+    fn new(group: FileGroup) -> Self {
+        let total_size = group.size() as u64;
+        Self {
+            groups: vec![group],
+            total_size,
+        }
+    }
+
+    fn add(&mut self, group: FileGroup) {
+        self.total_size += group.size() as u64;
+        self.groups.push(group);
+    }
+
+    /// Returns whether a file of `size` belongs to this tier. Files are added in
+    /// ascending order of size so the `size` is never smaller than the average size.
+    fn accepts(&self, size: u64, min_file_size: u64) -> bool {
+        let avg_size = self.total_size / self.groups.len() as u64;
+        if avg_size < min_file_size && size < min_file_size {
+            return true;
+        }
+        size as f64 <= avg_size as f64 * BUCKET_HIGH
+    }
+}
+
+/// Assigns files to windows by their max timestamps. Files in each window are grouped
+/// by their sequences, as files with the same sequence are created by the same compaction.
+fn assign_to_windows<'a>(
+    files: impl Iterator<Item = &'a FileHandle>,
+    time_window_size: i64,
+) -> BTreeMap<i64, Vec<FileGroup>> {
+    let mut windows: BTreeMap<i64, HashMap<Option<NonZeroU64>, FileGroup>> = BTreeMap::new();
+    for f in files {
+        if f.compacting() || f.rollup().is_some() {
+            continue;
+        }
+        let (_, end) = f.time_range();
+        let time_window = end
+            .convert_to(TimeUnit::Second)
+            .unwrap()
+            .value()
+            .align_to_ceil_by_bucket(time_window_size)
+            .unwrap_or(i64::MIN);
+
+        windows
+            .entry(time_window)
+            .or_default()
+            .entry(f.meta_ref().sequence)
+            .and_modify(|group| group.add_file(f.clone()))
+            .or_insert_with(|| FileGroup::new_with_file(f.clone()));
+    }
+
+    windows
+        .into_iter()
+        .map(|(window, groups)| (window, groups.into_values().collect()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compaction::test_util::new_file_handle_with_size_and_sequence;
+    use crate::sst::file::FileId;
+
+    fn new_picker() -> SizeTieredPicker {
+        SizeTieredPicker {
+            min_threshold: 3,
+            max_threshold: 4,
+            min_file_size: ReadableSize::kb(10).as_bytes(),
+            time_window_seconds: Some(3600),
+            max_output_file_size: Some(ReadableSize::mb(100).as_bytes()),
+        }
+    }
+
+    /// Creates files in window [0, 3600) with given sizes in KiB.
+    fn new_files(sizes_kb: &[u64]) -> Vec<FileHandle> {
+        sizes_kb
+            .iter()
+            .enumerate()
+            .map(|(idx, size)| {
+                new_file_handle_with_size_and_sequence(
+                    FileId::random(),
+                    0,
+                    3_599_000,
+                    0,
+                    idx as u64 + 1,
+                    ReadableSize::kb(*size).as_bytes(),
+                )
+            })
+            .collect()
+    }
+
+    fn output_sizes_kb(output: &CompactionOutput) -> Vec<u64> {
+        let mut sizes = output
+            .inputs
+            .iter()
+            .map(|f| f.size() / 1024)
+            .collect::<Vec<_>>();
+        sizes.sort_unstable();
+        sizes
+    }
+
+    #[test]
+    fn test_find_tiers() {
+        let picker = new_picker();
+        let files = new_files(&[1, 5, 9, 100, 120, 140, 1000, 3000]);
+        let windows = assign_to_windows(files.iter(), 3600);
+        assert_eq!(1, windows.len());
+
+        let tiers = picker.find_tiers(windows.into_values().next().unwrap());
+        let tier_sizes = tiers
+            .iter()
+            .map(|tier| {
+                tier.groups
+                    .iter()
+                    .map(|g| g.size() as u64 / 1024)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![vec![1, 5, 9], vec![100, 120, 140], vec![1000], vec![3000]],
+            tier_sizes
+        );
+    }
+
+    #[test]
+    fn test_build_output() {
+        let picker = new_picker();
+        // Files larger than the max output file size are never picked.
+        let mut files = new_files(&[1, 2, 3, 4, 5, 500, 600, 200 * 1024, 300 * 1024]);
+        files[3].set_compacting(true);
+        let windows = assign_to_windows(files.iter(), 3600);
+        let output = picker.build_output(RegionId::new(1, 1), windows);
+
+        // The tier of [500, 600] doesn't reach the min threshold.
+        assert_eq!(1, output.len());
+        assert_eq!(vec![1, 2, 3, 5], output_sizes_kb(&output[0]));
+        assert_eq!(LEVEL_COMPACTED, output[0].output_level);
+        assert!(!output[0].filter_deleted);
+
+        // Picks at most max threshold files in a tier.
+        let files = new_files(&[1, 1, 1, 1, 1, 1]);
+        let windows = assign_to_windows(files.iter(), 3600);
+        let output = picker.build_output(RegionId::new(1, 1), windows);
+        assert_eq!(1, output.len());
+        assert_eq!(4, output[0].inputs.len());
+    }
+
+    #[test]
+    fn test_assign_to_windows() {
+        let files = [
+            new_file_handle_with_size_and_sequence(FileId::random(), 0, 999, 0, 1, 10),
+            new_file_handle_with_size_and_sequence(FileId::random(), 0, 999, 0, 1, 20),
+            new_file_handle_with_size_and_sequence(FileId::random(), 0, 999, 0, 2, 30),
+            new_file_handle_with_size_and_sequence(FileId::random(), 3000, 4999, 0, 3, 40),
+        ];
+        let windows = assign_to_windows(files.iter(), 3);
+        assert_eq!(vec![&0, &6], windows.keys().collect::<Vec<_>>());
+
+        let mut sizes = windows[&0].iter().map(|g| g.size()).collect::<Vec<_>>();
+        sizes.sort_unstable();
+        assert_eq!(vec![30, 30], sizes);
+        assert_eq!(40, windows[&6][0].size());
+    }
+}
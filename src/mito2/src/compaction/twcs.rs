@@ -97,6 +97,7 @@ impl TwcsPicker {
                     inputs: inputs.into_iter().flat_map(|fg| fg.into_files()).collect(),
                     filter_deleted,
                     output_time_range: None, // we do not enforce output time range in twcs compactions.
+                    rollup: None,
                 });
             }
         }
@@ -257,7 +258,8 @@ fn assign_to_windows<'a>(
     let mut windows: HashMap<i64, Window> = HashMap::new();
     // Iterates all files and assign to time windows according to max timestamp
     for f in files {
-        if f.compacting() {
+        // Rollup files are never merged with raw files.
+        if f.compacting() || f.rollup().is_some() {
             continue;
         }
         let (_, end) = f.time_range();
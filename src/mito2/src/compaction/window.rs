@@ -137,6 +137,7 @@ fn build_output(windows: BTreeMap<i64, (i64, Vec<FileHandle>)>) -> Vec<Compactio
             inputs: files,
             filter_deleted: false,
             output_time_range,
+            rollup: None,
         };
         outputs.push(output);
     }
@@ -154,7 +155,8 @@ fn assign_files_to_time_windows<'a>(
     let mut buckets = BTreeMap::new();
 
     for file in files {
-        if file.compacting() {
+        // Skips rollup files as they don't contain raw data.
+        if file.compacting() || file.rollup().is_some() {
             continue;
         }
         let (start, end) = file.time_range();
@@ -250,6 +252,7 @@ mod tests {
                 index_options: Default::default(),
                 memtable: None,
                 merge_mode: None,
+                rollup: None,
             },
             compaction_time_window: None,
         }
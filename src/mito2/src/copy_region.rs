@@ -0,0 +1,314 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Copies the SST files of other regions of the same table into a region.
+//!
+//! It's used to repartition a table. The source regions must be fenced and flushed
+//! before copying, so their files don't change while they are copied. A file is copied
+//! as is if all its rows belong to the region, otherwise it's rewritten with the rows
+//! matching the partition expr of the region.
+//!
+//! All copied files are added to the region by a single [RegionEdit], so the region
+//! is either empty or has all the files. A non-empty region is not copied again.
+
+use std::num::NonZero;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use common_telemetry::info;
+use datafusion::physical_plan::PhysicalExpr;
+use datatypes::arrow::array::{Array, BooleanArray};
+use datatypes::vectors::BooleanVector;
+use partition::expr::PartitionExpr;
+use snafu::{OptionExt, ResultExt};
+use store_api::region_engine::CopyRegionFromRequest;
+use store_api::storage::RegionId;
+
+use crate::access_layer::{OperationType, SstWriteRequest, WriteType};
+use crate::backup::{copy_file, ensure_compatible_metadata};
+use crate::cache::{CacheManagerRef, CacheStrategy};
+use crate::config::MitoConfig;
+use crate::error::{
+    EmptyRegionDirSnafu, EvalPartitionExprSnafu, InvalidPartitionExprSnafu, RecordBatchSnafu,
+    Result, UnexpectedSnafu,
+};
+use crate::manifest::action::{RegionEdit, RegionManifest};
+use crate::manifest::manager::{RegionManifestManager, RegionManifestOptions, RemoveFileOptions};
+use crate::manifest::storage::manifest_compress_type;
+use crate::read::projection::ProjectionMapper;
+use crate::read::scan_region::ScanInput;
+use crate::read::seq_scan::SeqScan;
+use crate::read::{Batch, BatchReader, BoxedBatchReader, Source};
+use crate::region::opener::new_manifest_dir;
+use crate::region::MitoRegionRef;
+use crate::sst::file::{FileHandle, FileMeta};
+use crate::sst::location::{self, region_dir_from_table_dir};
+use crate::sst::parquet::WriteOptions;
+
+/// Copies the files of the source regions into the region.
+///
+/// Returns the [RegionEdit] to add the files to the region, or `None` if the region
+/// isn't empty, i.e., the files were copied before.
+pub(crate) async fn copy_region_files(
+    region: &MitoRegionRef,
+    config: &MitoConfig,
+    cache_manager: &CacheManagerRef,
+    request: &CopyRegionFromRequest,
+) -> Result<Option<RegionEdit>> {
+    let region_id = region.region_id;
+    let version = region.version();
+    if version.ssts.num_files() > 0 || !version.memtables.is_empty() {
+        info!(
+            "Skips copying files from regions {:?} to region {}, the region isn't empty",
+            request.source_region_ids, region_id
+        );
+        return Ok(None);
+    }
+
+    let filter = match &request.partition_expr {
+        Some(expr) => {
+            let partition_expr = PartitionExpr::from_json_str(expr)
+                .context(InvalidPartitionExprSnafu { expr })?
+                .context(UnexpectedSnafu {
+                    reason: format!("Empty partition expr to copy region {region_id}"),
+                })?;
+            let mapper = ProjectionMapper::all(&version.metadata, false)?;
+            let physical_expr = partition_expr
+                .try_as_physical_expr(mapper.output_schema().arrow_schema())
+                .context(InvalidPartitionExprSnafu { expr })?;
+            Some(physical_expr)
+        }
+        None => None,
+    };
+
+    let layer = &region.access_layer;
+    let mut files_to_add = Vec::new();
+    let mut flushed_sequence = 0;
+    for source_region_id in &request.source_region_ids {
+        let manifest = load_manifest(region, config, *source_region_id).await?;
+        ensure_compatible_metadata(region_id, &manifest.metadata, &version.metadata)?;
+        flushed_sequence = flushed_sequence.max(manifest.flushed_sequence);
+
+        for file in manifest.files.values() {
+            match &filter {
+                None => {
+                    let mut target = file.clone();
+                    // The files are placed under the directory of the region.
+                    target.region_id = region_id;
+                    copy_file(
+                        layer.object_store(),
+                        &location::sst_file_path(
+                            layer.table_dir(),
+                            file.file_id(),
+                            layer.path_type(),
+                        ),
+                        layer.object_store(),
+                        &location::sst_file_path(
+                            layer.table_dir(),
+                            target.file_id(),
+                            layer.path_type(),
+                        ),
+                    )
+                    .await?;
+                    if file.exists_index() {
+                        copy_file(
+                            layer.object_store(),
+                            &location::index_file_path(
+                                layer.table_dir(),
+                                file.file_id(),
+                                layer.path_type(),
+                            ),
+                            layer.object_store(),
+                            &location::index_file_path(
+                                layer.table_dir(),
+                                target.file_id(),
+                                layer.path_type(),
+                            ),
+                        )
+                        .await?;
+                    }
+                    files_to_add.push(target);
+                }
+                Some(physical_expr) => {
+                    files_to_add.extend(
+                        rewrite_file(region, config, cache_manager, file, physical_expr.clone())
+                            .await?,
+                    );
+                }
+            }
+        }
+    }
+
+    info!(
+        "Copied {} files from regions {:?} to region {}, filtered: {}",
+        files_to_add.len(),
+        request.source_region_ids,
+        region_id,
+        filter.is_some()
+    );
+
+    Ok(Some(RegionEdit {
+        files_to_add,
+        files_to_remove: vec![],
+        timestamp_ms: None,
+        compaction_time_window: None,
+        flushed_entry_id: None,
+        // Rows in the files keep their sequences, new rows must have larger sequences.
+        flushed_sequence: Some(flushed_sequence),
+    }))
+}
+
+/// Loads the manifest of another region of the same table from the object store.
+async fn load_manifest(
+    region: &MitoRegionRef,
+    config: &MitoConfig,
+    source_region_id: RegionId,
+) -> Result<RegionManifest> {
+    let layer = &region.access_layer;
+    let region_dir =
+        region_dir_from_table_dir(layer.table_dir(), source_region_id, layer.path_type());
+    let options = RegionManifestOptions {
+        manifest_dir: new_manifest_dir(&region_dir),
+        object_store: layer.object_store().clone(),
+        compress_type: manifest_compress_type(config.compress_manifest),
+        // The manifest is only read.
+        checkpoint_distance: 0,
+        remove_file_options: RemoveFileOptions {
+            keep_count: config.experimental_manifest_keep_removed_file_count,
+            keep_ttl: config.experimental_manifest_keep_removed_file_ttl,
+        },
+    };
+    let manager = RegionManifestManager::open(options, Default::default(), Default::default())
+        .await?
+        .context(EmptyRegionDirSnafu {
+            region_id: source_region_id,
+            region_dir: &region_dir,
+        })?;
+
+    Ok(manager.manifest().as_ref().clone())
+}
+
+/// Rewrites the rows of the `file` matching the partition expr into new files of the region.
+async fn rewrite_file(
+    region: &MitoRegionRef,
+    config: &MitoConfig,
+    cache_manager: &CacheManagerRef,
+    file: &FileMeta,
+    physical_expr: Arc<dyn PhysicalExpr>,
+) -> Result<Vec<FileMeta>> {
+    let version = region.version();
+    let metadata = version.metadata.clone();
+    let layer = region.access_layer.clone();
+    // Reads the file as the file of the region, the compat layer fills the missing columns.
+    let file_handle = FileHandle::new(file.clone(), region.file_purger.clone());
+    let scan_input = ScanInput::new(layer.clone(), ProjectionMapper::all(&metadata, false)?)
+        .with_files(vec![file_handle])
+        .with_append_mode(true)
+        .with_cache(CacheStrategy::Compaction(cache_manager.clone()))
+        .with_filter_deleted(false)
+        .with_merge_mode(version.options.merge_mode());
+    let reader = SeqScan::new(scan_input, true)
+        .build_reader_for_compaction()
+        .await?;
+    let reader = PartitionFilterReader {
+        region_id: region.region_id,
+        reader,
+        mapper: ProjectionMapper::all(&metadata, false)?,
+        physical_expr,
+    };
+
+    let (sst_infos, _) = layer
+        .write_sst(
+            SstWriteRequest {
+                op_type: OperationType::Compact,
+                metadata,
+                source: Source::Reader(Box::new(reader)),
+                cache_manager: cache_manager.clone(),
+                storage: version.options.storage.clone(),
+                max_sequence: file.sequence.map(NonZero::get),
+                index_options: version.options.index_options.clone(),
+                inverted_index_config: config.inverted_index.clone(),
+                fulltext_index_config: config.fulltext_index.clone(),
+                bloom_filter_index_config: config.bloom_filter_index.clone(),
+            },
+            &WriteOptions {
+                write_buffer_size: config.sst_write_buffer_size,
+                json_format: version.options.json_format,
+                ..Default::default()
+            },
+            WriteType::Compaction,
+        )
+        .await?;
+
+    Ok(sst_infos
+        .into_iter()
+        .map(|sst_info| FileMeta {
+            region_id: region.region_id,
+            file_id: sst_info.file_id,
+            time_range: sst_info.time_range,
+            level: file.level,
+            file_size: sst_info.file_size,
+            available_indexes: sst_info.index_metadata.build_available_indexes(),
+            index_file_size: sst_info.index_metadata.file_size,
+            num_rows: sst_info.num_rows as u64,
+            num_row_groups: sst_info.num_row_groups,
+            sequence: file.sequence,
+            rollup: file.rollup,
+        })
+        .collect())
+}
+
+/// A [BatchReader] that only yields the rows matching the partition expr.
+struct PartitionFilterReader {
+    region_id: RegionId,
+    reader: BoxedBatchReader,
+    /// Converts the batches to record batches to evaluate the expr.
+    mapper: ProjectionMapper,
+    physical_expr: Arc<dyn PhysicalExpr>,
+}
+
+impl PartitionFilterReader {
+    fn filter(&self, batch: &mut Batch) -> Result<()> {
+        // Safety: The mapper is built with the primary key format.
+        let mapper = self.mapper.as_primary_key().unwrap();
+        let record_batch = mapper
+            .convert(batch, &CacheStrategy::Disabled)
+            .context(RecordBatchSnafu)?;
+        let num_rows = record_batch.num_rows();
+        let mask = self
+            .physical_expr
+            .evaluate(record_batch.df_record_batch())
+            .and_then(|value| value.into_array(num_rows))
+            .context(EvalPartitionExprSnafu {
+                region_id: self.region_id,
+            })?;
+        // Safety: The partition expr is a predicate.
+        let mask = mask.as_any().downcast_ref::<BooleanArray>().unwrap();
+        batch.filter(&BooleanVector::from(mask.clone()))
+    }
+}
+
+#[async_trait]
+impl BatchReader for PartitionFilterReader {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        while let Some(mut batch) = self.reader.next_batch().await? {
+            self.filter(&mut batch)?;
+            if !batch.is_empty() {
+                return Ok(Some(batch));
+            }
+        }
+
+        Ok(None)
+    }
+}
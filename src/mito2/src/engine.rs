@@ -19,6 +19,8 @@ mod alter_test;
 #[cfg(test)]
 mod append_mode_test;
 #[cfg(test)]
+mod backup_test;
+#[cfg(test)]
 mod basic_test;
 #[cfg(test)]
 mod batch_open_test;
@@ -29,6 +31,8 @@ mod close_test;
 #[cfg(test)]
 mod compaction_test;
 #[cfg(test)]
+mod copy_region_test;
+#[cfg(test)]
 mod create_test;
 #[cfg(test)]
 mod drop_test;
@@ -51,6 +55,8 @@ mod projection_test;
 #[cfg(test)]
 mod prune_test;
 #[cfg(test)]
+mod rollup_test;
+#[cfg(test)]
 mod row_selector_test;
 #[cfg(test)]
 mod scan_test;
@@ -88,8 +94,10 @@ use store_api::metric_engine_consts::{
     MANIFEST_INFO_EXTENSION_KEY, TABLE_COLUMN_METADATA_EXTENSION_KEY,
 };
 use store_api::region_engine::{
-    BatchResponses, RegionEngine, RegionManifestInfo, RegionRole, RegionScannerRef,
-    RegionStatistic, SetRegionRoleStateResponse, SettableRegionRoleState, SyncManifestResponse,
+    BackupRegionRequest, BackupRegionResponse, BatchResponses, CopyRegionFromRequest,
+    CopyRegionFromResponse, RegionEngine, RegionManifestInfo, RegionRole, RegionScannerRef,
+    RegionStatistic, RestoreRegionRequest, RestoreRegionResponse, SetRegionRoleStateResponse,
+    SettableRegionRoleState, SyncManifestResponse,
 };
 use store_api::region_request::{AffectedRows, RegionOpenRequest, RegionRequest};
 use store_api::sst_entry::{ManifestSstEntry, StorageSstEntry};
@@ -97,6 +105,7 @@ use store_api::storage::{RegionId, ScanRequest, SequenceNumber};
 use store_api::ManifestVersion;
 use tokio::sync::{oneshot, Semaphore};
 
+use crate::backup::BackupPins;
 use crate::cache::CacheStrategy;
 use crate::config::MitoConfig;
 use crate::error::{
@@ -118,6 +127,7 @@ use crate::wal::entry_distributor::{
 };
 use crate::wal::raw_entry_reader::{LogStoreRawEntryReader, RawEntryReader};
 use crate::worker::WorkerGroup;
+use crate::{backup, copy_region};
 
 pub const MITO_ENGINE_NAME: &str = "mito";
 
@@ -182,6 +192,7 @@ impl<'a, S: LogStore> MitoEngineBuilder<'a, S> {
             workers,
             config,
             wal_raw_entry_reader,
+            backup_pins: BackupPins::default(),
             #[cfg(feature = "enterprise")]
             extension_range_provider_factory: None,
         };
@@ -301,6 +312,11 @@ impl MitoEngine {
             }
         );
 
+        self.submit_region_edit(region_id, edit).await
+    }
+
+    /// Submits the [RegionEdit] to the worker of the region without validating it.
+    async fn submit_region_edit(&self, region_id: RegionId, edit: RegionEdit) -> Result<()> {
         let (tx, rx) = oneshot::channel();
         let request = WorkerRequest::EditRegion(RegionEditRequest {
             region_id,
@@ -437,6 +453,8 @@ struct EngineInner {
     config: Arc<MitoConfig>,
     /// The Wal raw entry reader.
     wal_raw_entry_reader: Arc<dyn RawEntryReader>,
+    /// The versions of regions pinned by backups.
+    backup_pins: BackupPins,
     #[cfg(feature = "enterprise")]
     extension_range_provider_factory: Option<BoxedExtensionRangeProviderFactory>,
 }
@@ -852,6 +870,104 @@ impl RegionEngine for MitoEngine {
         Ok(SyncManifestResponse::Mito { synced })
     }
 
+    async fn backup_region(
+        &self,
+        request: BackupRegionRequest,
+    ) -> Result<BackupRegionResponse, BoxedError> {
+        let _timer = HANDLE_REQUEST_ELAPSED
+            .with_label_values(&["backup_region"])
+            .start_timer();
+
+        let pins = &self.inner.backup_pins;
+        match request {
+            BackupRegionRequest::Pin {
+                region_id,
+                backup_name,
+            } => {
+                let region = self.inner.find_region(region_id).map_err(BoxedError::new)?;
+                Ok(pins.pin(&region, &backup_name).await)
+            }
+            BackupRegionRequest::Copy {
+                region_id,
+                backup_name,
+                location,
+                connection,
+                base_snapshot,
+            } => {
+                let region = self.inner.find_region(region_id).map_err(BoxedError::new)?;
+                backup::backup_region(
+                    pins,
+                    &region,
+                    &backup_name,
+                    &location,
+                    &connection,
+                    base_snapshot.as_deref(),
+                )
+                .await
+                .map_err(BoxedError::new)
+            }
+            BackupRegionRequest::Release {
+                region_id,
+                backup_name,
+            } => {
+                // The region may have been closed, its pin is released anyway.
+                pins.release(region_id, &backup_name);
+                Ok(BackupRegionResponse::Released)
+            }
+        }
+    }
+
+    async fn restore_region(
+        &self,
+        request: RestoreRegionRequest,
+    ) -> Result<RestoreRegionResponse, BoxedError> {
+        let _timer = HANDLE_REQUEST_ELAPSED
+            .with_label_values(&["restore_region"])
+            .start_timer();
+
+        let region_id = request.region_id;
+        let region = self.inner.find_region(region_id).map_err(BoxedError::new)?;
+        let edit = backup::restore_region_files(&region, &request)
+            .await
+            .map_err(BoxedError::new)?;
+        let restored_files = edit.files_to_add.len();
+        if restored_files > 0 {
+            self.submit_region_edit(region_id, edit)
+                .await
+                .map_err(BoxedError::new)?;
+        }
+
+        Ok(RestoreRegionResponse::Restored { restored_files })
+    }
+
+    async fn copy_region_from(
+        &self,
+        request: CopyRegionFromRequest,
+    ) -> Result<CopyRegionFromResponse, BoxedError> {
+        let _timer = HANDLE_REQUEST_ELAPSED
+            .with_label_values(&["copy_region_from"])
+            .start_timer();
+
+        let region_id = request.region_id;
+        let region = self.inner.find_region(region_id).map_err(BoxedError::new)?;
+        let cache_manager = self.inner.workers.cache_manager();
+        let edit =
+            copy_region::copy_region_files(&region, &self.inner.config, &cache_manager, &request)
+                .await
+                .map_err(BoxedError::new)?;
+        let Some(edit) = edit else {
+            return Ok(CopyRegionFromResponse::Copied { copied_files: 0 });
+        };
+        let copied_files = edit.files_to_add.len();
+        if copied_files > 0 {
+            self.submit_region_edit(region_id, edit)
+                .await
+                .map_err(BoxedError::new)?;
+        }
+
+        Ok(CopyRegionFromResponse::Copied { copied_files })
+    }
+
     fn role(&self, region_id: RegionId) -> Option<RegionRole> {
         self.inner.role(region_id)
     }
@@ -943,6 +1059,7 @@ impl MitoEngine {
                 .await?,
                 config,
                 wal_raw_entry_reader,
+                backup_pins: BackupPins::default(),
                 #[cfg(feature = "enterprise")]
                 extension_range_provider_factory: None,
             }),
@@ -0,0 +1,388 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use api::v1::Rows;
+use common_recordbatch::RecordBatches;
+use common_test_util::temp_dir::create_temp_dir;
+use store_api::metadata::ColumnMetadata;
+use store_api::region_engine::{
+    BackupRegionRequest, BackupRegionResponse, RegionEngine, RestoreRegionRequest,
+    RestoreRegionResponse,
+};
+use store_api::region_request::{AlterKind, RegionAlterRequest, RegionRequest};
+use store_api::storage::{RegionId, ScanRequest};
+
+use crate::config::MitoConfig;
+use crate::engine::MitoEngine;
+use crate::test_util::{
+    build_rows, build_rows_with_fields, flush_region, put_rows, rows_schema, CreateRequestBuilder,
+    TestEnv,
+};
+
+const BACKUP_NAME: &str = "test_backup";
+
+async fn pin(engine: &MitoEngine, region_id: RegionId) -> Vec<ColumnMetadata> {
+    let response = engine
+        .backup_region(BackupRegionRequest::Pin {
+            region_id,
+            backup_name: BACKUP_NAME.to_string(),
+        })
+        .await
+        .unwrap();
+    let BackupRegionResponse::Pinned {
+        column_metadatas, ..
+    } = response
+    else {
+        unreachable!()
+    };
+    column_metadatas
+}
+
+async fn backup(
+    engine: &MitoEngine,
+    region_id: RegionId,
+    location: &str,
+    base_snapshot: Option<String>,
+) -> (usize, String) {
+    pin(engine, region_id).await;
+    let response = engine
+        .backup_region(BackupRegionRequest::Copy {
+            region_id,
+            backup_name: BACKUP_NAME.to_string(),
+            location: location.to_string(),
+            connection: HashMap::new(),
+            base_snapshot,
+        })
+        .await
+        .unwrap();
+    let BackupRegionResponse::Backup {
+        copied_files,
+        snapshot,
+        ..
+    } = response
+    else {
+        unreachable!()
+    };
+    (copied_files, snapshot)
+}
+
+#[tokio::test]
+async fn test_backup_and_restore_region() {
+    let mut env = TestEnv::new().await;
+    let engine = env.create_engine(MitoConfig::default()).await;
+    let backup_dir = create_temp_dir("backup");
+    let location = format!("{}/", backup_dir.path().to_str().unwrap());
+
+    let source_region_id = RegionId::new(1, 1);
+    let target_region_id = RegionId::new(2, 1);
+    for region_id in [source_region_id, target_region_id] {
+        env.get_schema_metadata_manager()
+            .register_region_table_info(
+                region_id.table_id(),
+                "test_table",
+                "test_catalog",
+                "test_schema",
+                None,
+                env.get_kv_backend(),
+            )
+            .await;
+    }
+
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(source_region_id, RegionRequest::Create(request.clone()))
+        .await
+        .unwrap();
+    engine
+        .handle_request(target_region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(0, 3),
+    };
+    put_rows(&engine, source_region_id, rows).await;
+    flush_region(&engine, source_region_id, None).await;
+    let (copied_files, base_snapshot) = backup(&engine, source_region_id, &location, None).await;
+    assert_eq!(1, copied_files);
+
+    // The incremental backup only copies the new file.
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(3, 5),
+    };
+    put_rows(&engine, source_region_id, rows).await;
+    flush_region(&engine, source_region_id, None).await;
+    let (copied_files, snapshot) =
+        backup(&engine, source_region_id, &location, Some(base_snapshot)).await;
+    assert_eq!(1, copied_files);
+
+    let response = engine
+        .restore_region(RestoreRegionRequest {
+            region_id: target_region_id,
+            location: location.clone(),
+            connection: HashMap::new(),
+            snapshot: snapshot.clone(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        RestoreRegionResponse::Restored { restored_files: 2 },
+        response
+    );
+
+    let stream = engine
+        .scan_to_stream(target_region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 0     | 0.0     | 1970-01-01T00:00:00 |
+| 1     | 1.0     | 1970-01-01T00:00:01 |
+| 2     | 2.0     | 1970-01-01T00:00:02 |
+| 3     | 3.0     | 1970-01-01T00:00:03 |
+| 4     | 4.0     | 1970-01-01T00:00:04 |
++-------+---------+---------------------+";
+    assert_eq!(expected, batches.pretty_print().unwrap());
+
+    // The region to restore must be empty.
+    engine
+        .restore_region(RestoreRegionRequest {
+            region_id: target_region_id,
+            location,
+            connection: HashMap::new(),
+            snapshot,
+        })
+        .await
+        .unwrap_err();
+}
+
+#[tokio::test]
+async fn test_backup_copy_pinned_files() {
+    let mut env = TestEnv::new().await;
+    let engine = env.create_engine(MitoConfig::default()).await;
+    let backup_dir = create_temp_dir("backup");
+    let location = format!("{}/", backup_dir.path().to_str().unwrap());
+
+    let region_id = RegionId::new(1, 1);
+    env.get_schema_metadata_manager()
+        .register_region_table_info(
+            region_id.table_id(),
+            "test_table",
+            "test_catalog",
+            "test_schema",
+            None,
+            env.get_kv_backend(),
+        )
+        .await;
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    let copy_request = BackupRegionRequest::Copy {
+        region_id,
+        backup_name: BACKUP_NAME.to_string(),
+        location,
+        connection: HashMap::new(),
+        base_snapshot: None,
+    };
+
+    // The region must be pinned before copying.
+    engine
+        .backup_region(copy_request.clone())
+        .await
+        .unwrap_err();
+
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(0, 3),
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+    pin(&engine, region_id).await;
+
+    // Files flushed after the pin are not copied.
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(3, 5),
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+    let response = engine.backup_region(copy_request.clone()).await.unwrap();
+    let BackupRegionResponse::Backup { copied_files, .. } = response else {
+        unreachable!()
+    };
+    assert_eq!(1, copied_files);
+
+    // The pin is released after copying.
+    engine
+        .backup_region(copy_request.clone())
+        .await
+        .unwrap_err();
+
+    pin(&engine, region_id).await;
+    let response = engine
+        .backup_region(BackupRegionRequest::Release {
+            region_id,
+            backup_name: BACKUP_NAME.to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(BackupRegionResponse::Released, response);
+    engine.backup_region(copy_request).await.unwrap_err();
+}
+
+#[tokio::test]
+async fn test_backup_and_restore_altered_region() {
+    let mut env = TestEnv::new().await;
+    let engine = env.create_engine(MitoConfig::default()).await;
+    let backup_dir = create_temp_dir("backup");
+    let location = format!("{}/", backup_dir.path().to_str().unwrap());
+
+    let source_region_id = RegionId::new(1, 1);
+    let target_region_id = RegionId::new(2, 1);
+    let incompatible_region_id = RegionId::new(3, 1);
+    for region_id in [source_region_id, target_region_id, incompatible_region_id] {
+        env.get_schema_metadata_manager()
+            .register_region_table_info(
+                region_id.table_id(),
+                "test_table",
+                "test_catalog",
+                "test_schema",
+                None,
+                env.get_kv_backend(),
+            )
+            .await;
+    }
+
+    // Columns: tag_0 (0), field_0 (1), field_1 (2), ts (3).
+    let request = CreateRequestBuilder::new().field_num(2).build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(source_region_id, RegionRequest::Create(request.clone()))
+        .await
+        .unwrap();
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows_with_fields("a", &[0, 1], &[(Some(0), Some(10)), (Some(1), Some(11))]),
+    };
+    put_rows(&engine, source_region_id, rows).await;
+    flush_region(&engine, source_region_id, None).await;
+    engine
+        .handle_request(
+            source_region_id,
+            RegionRequest::Alter(RegionAlterRequest {
+                kind: AlterKind::DropColumns {
+                    names: vec!["field_0".to_string()],
+                },
+            }),
+        )
+        .await
+        .unwrap();
+
+    let column_metadatas = pin(&engine, source_region_id).await;
+    let column_ids = column_metadatas
+        .iter()
+        .map(|c| (c.column_schema.name.as_str(), c.column_id))
+        .collect::<Vec<_>>();
+    assert_eq!(vec![("tag_0", 0), ("field_1", 2), ("ts", 3)], column_ids);
+    let response = engine
+        .backup_region(BackupRegionRequest::Copy {
+            region_id: source_region_id,
+            backup_name: BACKUP_NAME.to_string(),
+            location: location.clone(),
+            connection: HashMap::new(),
+            base_snapshot: None,
+        })
+        .await
+        .unwrap();
+    let BackupRegionResponse::Backup { snapshot, .. } = response else {
+        unreachable!()
+    };
+
+    // A region with the same columns but ids assigned by the order of columns
+    // can't be restored.
+    let mut incompatible_request = request.clone();
+    incompatible_request.column_metadatas = column_metadatas
+        .iter()
+        .cloned()
+        .enumerate()
+        .map(|(i, mut column)| {
+            column.column_id = i as u32;
+            column
+        })
+        .collect();
+    incompatible_request.primary_key = vec![0];
+    engine
+        .handle_request(
+            incompatible_region_id,
+            RegionRequest::Create(incompatible_request),
+        )
+        .await
+        .unwrap();
+    engine
+        .restore_region(RestoreRegionRequest {
+            region_id: incompatible_region_id,
+            location: location.clone(),
+            connection: HashMap::new(),
+            snapshot: snapshot.clone(),
+        })
+        .await
+        .unwrap_err();
+
+    // Recreates the region with the column ids of the backup.
+    let mut target_request = request;
+    target_request.column_metadatas = column_metadatas;
+    target_request.primary_key = vec![0];
+    engine
+        .handle_request(target_region_id, RegionRequest::Create(target_request))
+        .await
+        .unwrap();
+    let response = engine
+        .restore_region(RestoreRegionRequest {
+            region_id: target_region_id,
+            location,
+            connection: HashMap::new(),
+            snapshot,
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        RestoreRegionResponse::Restored { restored_files: 1 },
+        response
+    );
+
+    let stream = engine
+        .scan_to_stream(target_region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_1 | ts                  |
++-------+---------+---------------------+
+| a     | 10.0    | 1970-01-01T00:00:00 |
+| a     | 11.0    | 1970-01-01T00:00:01 |
++-------+---------+---------------------+";
+    assert_eq!(expected, batches.pretty_print().unwrap());
+}
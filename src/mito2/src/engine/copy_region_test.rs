@@ -0,0 +1,150 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::Rows;
+use common_recordbatch::RecordBatches;
+use datatypes::value::Value;
+use partition::expr::col;
+use store_api::region_engine::{CopyRegionFromRequest, CopyRegionFromResponse, RegionEngine};
+use store_api::region_request::RegionRequest;
+use store_api::storage::{RegionId, ScanRequest};
+
+use crate::config::MitoConfig;
+use crate::engine::MitoEngine;
+use crate::test_util::{
+    build_rows, flush_region, put_rows, rows_schema, CreateRequestBuilder, TestEnv,
+};
+
+async fn copy(
+    engine: &MitoEngine,
+    region_id: RegionId,
+    source_region_ids: Vec<RegionId>,
+    partition_expr: Option<String>,
+) -> usize {
+    let response = engine
+        .copy_region_from(CopyRegionFromRequest {
+            region_id,
+            source_region_ids,
+            partition_expr,
+        })
+        .await
+        .unwrap();
+    let CopyRegionFromResponse::Copied { copied_files } = response else {
+        unreachable!()
+    };
+    copied_files
+}
+
+async fn scan(engine: &MitoEngine, region_id: RegionId) -> String {
+    let stream = engine
+        .scan_to_stream(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    batches.pretty_print().unwrap()
+}
+
+#[tokio::test]
+async fn test_copy_region_from() {
+    let mut env = TestEnv::new().await;
+    let engine = env.create_engine(MitoConfig::default()).await;
+    env.get_schema_metadata_manager()
+        .register_region_table_info(
+            1,
+            "test_table",
+            "test_catalog",
+            "test_schema",
+            None,
+            env.get_kv_backend(),
+        )
+        .await;
+
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = rows_schema(&request);
+    let source_region_ids = [RegionId::new(1, 1), RegionId::new(1, 2)];
+    for (i, region_id) in source_region_ids.iter().enumerate() {
+        engine
+            .handle_request(*region_id, RegionRequest::Create(request.clone()))
+            .await
+            .unwrap();
+        let rows = Rows {
+            schema: column_schemas.clone(),
+            rows: build_rows(i * 2, i * 2 + 2),
+        };
+        put_rows(&engine, *region_id, rows).await;
+        flush_region(&engine, *region_id, None).await;
+    }
+
+    // Merges the source regions, the files are copied as is.
+    let merged_region_id = RegionId::new(1, 3);
+    engine
+        .handle_request(merged_region_id, RegionRequest::Create(request.clone()))
+        .await
+        .unwrap();
+    let copied_files = copy(&engine, merged_region_id, source_region_ids.to_vec(), None).await;
+    assert_eq!(2, copied_files);
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 0     | 0.0     | 1970-01-01T00:00:00 |
+| 1     | 1.0     | 1970-01-01T00:00:01 |
+| 2     | 2.0     | 1970-01-01T00:00:02 |
+| 3     | 3.0     | 1970-01-01T00:00:03 |
++-------+---------+---------------------+";
+    assert_eq!(expected, scan(&engine, merged_region_id).await);
+
+    // Copying again is a no-op.
+    let copied_files = copy(&engine, merged_region_id, source_region_ids.to_vec(), None).await;
+    assert_eq!(0, copied_files);
+    assert_eq!(expected, scan(&engine, merged_region_id).await);
+
+    // Splits the merged region, the files are rewritten with the rows of each target.
+    let exprs = [
+        col("tag_0").lt(Value::from("1")),
+        col("tag_0").gt_eq(Value::from("1")),
+    ];
+    let split_region_ids = [RegionId::new(1, 4), RegionId::new(1, 5)];
+    for (region_id, expr) in split_region_ids.iter().zip(exprs) {
+        let expr = expr.as_json_str().unwrap();
+        engine
+            .handle_request(
+                *region_id,
+                RegionRequest::Create(
+                    CreateRequestBuilder::new()
+                        .partition_expr_json(Some(expr.clone()))
+                        .build(),
+                ),
+            )
+            .await
+            .unwrap();
+        copy(&engine, *region_id, vec![merged_region_id], Some(expr)).await;
+    }
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 0     | 0.0     | 1970-01-01T00:00:00 |
++-------+---------+---------------------+";
+    assert_eq!(expected, scan(&engine, split_region_ids[0]).await);
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 1     | 1.0     | 1970-01-01T00:00:01 |
+| 2     | 2.0     | 1970-01-01T00:00:02 |
+| 3     | 3.0     | 1970-01-01T00:00:03 |
++-------+---------+---------------------+";
+    assert_eq!(expected, scan(&engine, split_region_ids[1]).await);
+}
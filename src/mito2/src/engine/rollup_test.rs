@@ -0,0 +1,216 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for rollup tiers.
+
+use api::v1::Rows;
+use common_recordbatch::RecordBatches;
+use datafusion_common::ScalarValue;
+use datafusion_expr::{col, lit};
+use store_api::region_engine::RegionEngine;
+use store_api::region_request::{RegionCompactRequest, RegionRequest};
+use store_api::storage::{RegionId, RollupAggregator, RollupHint, ScanRequest};
+
+use crate::config::MitoConfig;
+use crate::test_util::batch_util::sort_batches_and_print;
+use crate::test_util::{
+    build_rows_for_key, flush_region, put_rows, rows_schema, CreateRequestBuilder, TestEnv,
+};
+
+fn rollup_request(step_millis: i64, aggregator: Option<RollupAggregator>) -> ScanRequest {
+    ScanRequest {
+        rollup: Some(RollupHint {
+            step_millis,
+            aggregator,
+        }),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_scan_rolled_up_data() {
+    common_telemetry::init_default_ut_logging();
+
+    let mut env = TestEnv::new().await;
+    let engine = env.create_engine(MitoConfig::default()).await;
+    let region_id = RegionId::new(1, 1);
+
+    env.get_schema_metadata_manager()
+        .register_region_table_info(
+            region_id.table_id(),
+            "test_table",
+            "test_catalog",
+            "test_schema",
+            None,
+            env.get_kv_backend(),
+        )
+        .await;
+
+    let request = CreateRequestBuilder::new()
+        .insert_option("rollup", "1m:avg,max")
+        .insert_option("rollup.after", "1h")
+        .build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // Rows in the first two minutes, which are rolled up.
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows_for_key("a", 0, 120, 0),
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+    engine
+        .handle_request(
+            region_id,
+            RegionRequest::Compact(RegionCompactRequest::default()),
+        )
+        .await
+        .unwrap();
+
+    let scanner = engine
+        .scanner(
+            region_id,
+            rollup_request(60_000, Some(RollupAggregator::Avg)),
+        )
+        .await
+        .unwrap();
+    assert_eq!(1, scanner.num_files());
+    let stream = engine
+        .scan_to_stream(
+            region_id,
+            rollup_request(60_000, Some(RollupAggregator::Avg)),
+        )
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| a     | 29.5    | 1970-01-01T00:00:00 |
+| a     | 89.5    | 1970-01-01T00:01:00 |
++-------+---------+---------------------+";
+    assert_eq!(expected, sort_batches_and_print(&batches, &["tag_0", "ts"]));
+
+    let stream = engine
+        .scan_to_stream(
+            region_id,
+            rollup_request(300_000, Some(RollupAggregator::Max)),
+        )
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| a     | 59.0    | 1970-01-01T00:00:00 |
+| a     | 119.0   | 1970-01-01T00:01:00 |
++-------+---------+---------------------+";
+    assert_eq!(expected, sort_batches_and_print(&batches, &["tag_0", "ts"]));
+
+    // The raw rows are removed, so queries that don't match a tier read the default tier.
+    for request in [
+        ScanRequest::default(),
+        rollup_request(60_000, None),
+        rollup_request(60_000, Some(RollupAggregator::Sum)),
+        rollup_request(1_000, Some(RollupAggregator::Avg)),
+    ] {
+        let stream = engine.scan_to_stream(region_id, request).await.unwrap();
+        let batches = RecordBatches::try_collect(stream).await.unwrap();
+        let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| a     | 29.5    | 1970-01-01T00:00:00 |
+| a     | 89.5    | 1970-01-01T00:01:00 |
++-------+---------+---------------------+";
+        assert_eq!(expected, sort_batches_and_print(&batches, &["tag_0", "ts"]));
+    }
+
+    // Queries out of the rolled up range are not affected.
+    let filter = col("ts").gt_eq(lit(ScalarValue::TimestampMillisecond(Some(120_000), None)));
+    let request = ScanRequest {
+        filters: vec![filter],
+        ..Default::default()
+    };
+    let stream = engine.scan_to_stream(region_id, request).await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    assert_eq!(0, batches.iter().map(|b| b.num_rows()).sum::<usize>());
+
+    // Late rows are merged into the rolled up intervals.
+    let mut rows = build_rows_for_key("a", 90, 91, 730);
+    rows.extend(build_rows_for_key("b", 0, 1, 5));
+    let rows = Rows {
+        schema: column_schemas,
+        rows,
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+    engine
+        .handle_request(
+            region_id,
+            RegionRequest::Compact(RegionCompactRequest::default()),
+        )
+        .await
+        .unwrap();
+
+    let scanner = engine
+        .scanner(
+            region_id,
+            rollup_request(60_000, Some(RollupAggregator::Avg)),
+        )
+        .await
+        .unwrap();
+    assert_eq!(1, scanner.num_files());
+    let stream = engine
+        .scan_to_stream(
+            region_id,
+            rollup_request(60_000, Some(RollupAggregator::Avg)),
+        )
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| a     | 29.5    | 1970-01-01T00:00:00 |
+| a     | 100.0   | 1970-01-01T00:01:00 |
+| b     | 5.0     | 1970-01-01T00:00:00 |
++-------+---------+---------------------+";
+    assert_eq!(expected, sort_batches_and_print(&batches, &["tag_0", "ts"]));
+
+    let stream = engine
+        .scan_to_stream(
+            region_id,
+            rollup_request(60_000, Some(RollupAggregator::Max)),
+        )
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| a     | 59.0    | 1970-01-01T00:00:00 |
+| a     | 730.0   | 1970-01-01T00:01:00 |
+| b     | 5.0     | 1970-01-01T00:00:00 |
++-------+---------+---------------------+";
+    assert_eq!(expected, sort_batches_and_print(&batches, &["tag_0", "ts"]));
+}
@@ -663,6 +663,41 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Failed to build the object store of backup: {}", url))]
+    BuildBackupStore {
+        url: String,
+        source: common_datasource::error::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Failed to copy backup file from {} to {}", from, to))]
+    CopyBackupFile {
+        from: String,
+        to: String,
+        #[snafu(source)]
+        error: std::io::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Invalid partition expr: {}", expr))]
+    InvalidPartitionExpr {
+        expr: String,
+        #[snafu(implicit)]
+        location: Location,
+        source: partition::error::Error,
+    },
+
+    #[snafu(display("Failed to evaluate partition expr of region {}", region_id))]
+    EvalPartitionExpr {
+        region_id: RegionId,
+        #[snafu(source)]
+        error: datafusion_common::DataFusionError,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
     #[snafu(display("Failed to create directory {}", dir))]
     CreateDir {
         dir: String,
@@ -1172,7 +1207,12 @@ impl ErrorExt for Error {
 
             RecordBatch { source, .. } => source.status_code(),
 
-            Download { .. } | Upload { .. } => StatusCode::StorageUnavailable,
+            Download { .. } | Upload { .. } | CopyBackupFile { .. } => {
+                StatusCode::StorageUnavailable
+            }
+            BuildBackupStore { source, .. } => source.status_code(),
+            InvalidPartitionExpr { .. } => StatusCode::InvalidArguments,
+            EvalPartitionExpr { .. } => StatusCode::Unexpected,
             ChecksumMismatch { .. } => StatusCode::Unexpected,
             RegionStopped { .. } => StatusCode::RegionNotReady,
             TimeRangePredicateOverflow { .. } => StatusCode::InvalidArguments,
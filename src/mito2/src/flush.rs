@@ -335,6 +335,7 @@ impl RegionFlushTask {
 
         let mut write_opts = WriteOptions {
             write_buffer_size: self.engine_config.sst_write_buffer_size,
+            json_format: version.options.json_format,
             ..Default::default()
         };
         if let Some(row_group_size) = self.row_group_size {
@@ -423,6 +424,7 @@ impl RegionFlushTask {
                     num_rows: sst_info.num_rows as u64,
                     num_row_groups: sst_info.num_row_groups,
                     sequence: NonZeroU64::new(max_sequence),
+                    rollup: None,
                 }
             }));
         }
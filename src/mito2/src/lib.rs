@@ -28,9 +28,11 @@
 pub mod test_util;
 
 pub mod access_layer;
+pub mod backup;
 pub mod cache;
 pub mod compaction;
 pub mod config;
+mod copy_region;
 pub mod engine;
 pub mod error;
 #[cfg(feature = "enterprise")]
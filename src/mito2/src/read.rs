@@ -25,6 +25,7 @@ pub mod plain_batch;
 pub mod projection;
 pub(crate) mod prune;
 pub mod range;
+pub(crate) mod rollup;
 pub mod scan_region;
 pub mod scan_util;
 pub(crate) mod seq_scan;
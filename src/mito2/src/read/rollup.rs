@@ -0,0 +1,607 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Utilities to aggregate rows of each time series into rollup intervals.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use api::v1::OpType;
+use async_trait::async_trait;
+use common_time::Timestamp;
+use datatypes::prelude::{ConcreteDataType, DataType};
+use datatypes::value::Value;
+use datatypes::vectors::{UInt64Vector, UInt8Vector, Vector};
+use snafu::ResultExt;
+use store_api::storage::{ColumnId, RollupAggregator};
+
+use crate::error::{ComputeVectorSnafu, Result};
+use crate::read::{Batch, BatchColumn, BatchReader, BoxedBatchReader};
+use crate::sst::file::RollupMeta;
+
+/// Reader to aggregate rows of each time series into rollup intervals.
+/// It assumes that batches from the input readers are sorted.
+///
+/// Each output row has the start of its interval as timestamp. Numeric fields are
+/// aggregated by the aggregator of the tier and then cast to the type of the field.
+/// Other fields keep the last non-null value in the interval.
+///
+/// Rows rolled up into the tier before are merged with the raw rows of the same
+/// intervals, so late rows update the existing aggregates. Averages are merged with
+/// the row counts of the intervals, and raw rows replace the previous value of `last`.
+pub(crate) struct RollupReader {
+    /// Reader of raw rows.
+    reader: KeyReader,
+    /// Rows rolled up into the tier before.
+    rolled: Option<KeyReader>,
+    /// Row counts of the rolled up rows, to merge averages.
+    rolled_counts: Option<KeyReader>,
+    /// Rollup tier to build.
+    rollup: RollupMeta,
+}
+
+impl RollupReader {
+    /// Creates a new `RollupReader` that aggregates raw rows from the `reader`.
+    pub(crate) fn new(reader: BoxedBatchReader, rollup: RollupMeta) -> Self {
+        Self {
+            reader: KeyReader::new(reader),
+            rolled: None,
+            rolled_counts: None,
+            rollup,
+        }
+    }
+
+    /// Merges rows rolled up into the tier before from the `rolled` reader.
+    ///
+    /// `rolled_counts` reads the row counts of the same intervals. It's required to
+    /// merge averages, otherwise each rolled up average counts as one row.
+    pub(crate) fn with_rolled(
+        mut self,
+        rolled: BoxedBatchReader,
+        rolled_counts: Option<BoxedBatchReader>,
+    ) -> Self {
+        self.rolled = Some(KeyReader::new(rolled));
+        self.rolled_counts = rolled_counts.map(KeyReader::new);
+        self
+    }
+
+    /// Returns the aggregated rows of the next time series.
+    async fn next_series(&mut self) -> Result<Option<Batch>> {
+        let raw_key = self.reader.peek_key().await?.map(<[u8]>::to_vec);
+        let rolled_key = match &mut self.rolled {
+            Some(rolled) => rolled.peek_key().await?.map(<[u8]>::to_vec),
+            None => None,
+        };
+        let key = match (raw_key, rolled_key) {
+            (Some(raw), Some(rolled)) => raw.min(rolled),
+            (Some(key), None) | (None, Some(key)) => key,
+            (None, None) => return Ok(None),
+        };
+
+        let mut series: Option<SeriesRollup> = None;
+        for batch in self.reader.take_key(&key).await? {
+            series
+                .get_or_insert_with(|| SeriesRollup::new(&batch, self.rollup))
+                .push(&batch);
+        }
+        if let Some(rolled) = &mut self.rolled {
+            let mut counts = HashMap::new();
+            if let Some(rolled_counts) = &mut self.rolled_counts {
+                for batch in rolled_counts.take_key(&key).await? {
+                    collect_counts(&batch, &mut counts);
+                }
+            }
+            for batch in rolled.take_key(&key).await? {
+                series
+                    .get_or_insert_with(|| SeriesRollup::new(&batch, self.rollup))
+                    .merge_rolled(&batch, &counts);
+            }
+        }
+
+        series.map(SeriesRollup::finish).transpose()
+    }
+}
+
+#[async_trait]
+impl BatchReader for RollupReader {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        self.next_series().await
+    }
+}
+
+/// Reader that returns batches of a primary key at a time, skipping deleted rows.
+struct KeyReader {
+    reader: BoxedBatchReader,
+    peeked: Option<Batch>,
+}
+
+impl KeyReader {
+    fn new(reader: BoxedBatchReader) -> Self {
+        Self {
+            reader,
+            peeked: None,
+        }
+    }
+
+    /// Returns the primary key of the next batch.
+    async fn peek_key(&mut self) -> Result<Option<&[u8]>> {
+        if self.peeked.is_none() {
+            while let Some(mut batch) = self.reader.next_batch().await? {
+                batch.filter_deleted()?;
+                if !batch.is_empty() {
+                    self.peeked = Some(batch);
+                    break;
+                }
+            }
+        }
+        Ok(self.peeked.as_ref().map(Batch::primary_key))
+    }
+
+    /// Takes all batches of the primary `key`.
+    async fn take_key(&mut self, key: &[u8]) -> Result<Vec<Batch>> {
+        let mut batches = Vec::new();
+        while self.peek_key().await? == Some(key) {
+            // Safety: the batch is peeked.
+            batches.push(self.peeked.take().unwrap());
+        }
+        Ok(batches)
+    }
+}
+
+/// Collects row counts of fields in the rolled up `batch` of the count aggregator,
+/// keyed by the start of intervals.
+fn collect_counts(batch: &Batch, counts: &mut HashMap<i64, Vec<Option<u64>>>) {
+    // Safety: the batch is not empty.
+    let timestamps = batch.timestamps_native().unwrap();
+    for (row, ts) in timestamps.iter().enumerate() {
+        let row_counts = batch
+            .fields()
+            .iter()
+            .map(|field| field.data.get(row).as_f64_lossy().map(|v| v as u64))
+            .collect();
+        counts.insert(*ts, row_counts);
+    }
+}
+
+/// Aggregated rows of a time series.
+struct SeriesRollup {
+    primary_key: Vec<u8>,
+    aggregator: RollupAggregator,
+    /// Interval in the unit of the time index.
+    interval: i64,
+    /// Data type of the time index.
+    timestamp_type: ConcreteDataType,
+    /// Column ids and data types of fields.
+    field_types: Vec<(ColumnId, ConcreteDataType)>,
+    /// Intervals keyed by their start timestamps.
+    intervals: BTreeMap<i64, Interval>,
+}
+
+/// The interval being aggregated.
+struct Interval {
+    sequence: u64,
+    accumulators: Vec<Accumulator>,
+}
+
+/// Accumulated state of a field in an interval.
+#[derive(Default)]
+struct Accumulator {
+    sum: f64,
+    count: u64,
+    min: Option<f64>,
+    max: Option<f64>,
+    /// The last non-null value.
+    last: Option<Value>,
+}
+
+impl Accumulator {
+    fn update(&mut self, value: Value) {
+        if value.is_null() {
+            return;
+        }
+        if let Some(v) = value.as_f64_lossy() {
+            self.sum += v;
+            self.count += 1;
+            self.update_min_max(v);
+        }
+        self.last = Some(value);
+    }
+
+    /// Merges a `value` rolled up by the `aggregator` before. `count` is the number of
+    /// rows aggregated into the value if known.
+    fn merge_rolled(&mut self, aggregator: RollupAggregator, value: Value, count: Option<u64>) {
+        if value.is_null() {
+            return;
+        }
+        if let Some(v) = value.as_f64_lossy() {
+            match aggregator {
+                RollupAggregator::Avg => {
+                    let count = count.unwrap_or(1);
+                    self.sum += v * count as f64;
+                    self.count += count;
+                }
+                RollupAggregator::Count => self.count += v as u64,
+                _ => {
+                    self.sum += v;
+                    self.count += 1;
+                }
+            }
+            self.update_min_max(v);
+        }
+        // Raw rows are newer than the rolled up value.
+        if self.last.is_none() {
+            self.last = Some(value);
+        }
+    }
+
+    fn update_min_max(&mut self, v: f64) {
+        self.min = Some(self.min.map_or(v, |min| min.min(v)));
+        self.max = Some(self.max.map_or(v, |max| max.max(v)));
+    }
+
+    /// Returns the aggregated value of a field of `data_type`.
+    fn finish(self, aggregator: RollupAggregator, data_type: &ConcreteDataType) -> Value {
+        if !data_type.is_numeric() {
+            return self.last.unwrap_or(Value::Null);
+        }
+        let value = match aggregator {
+            RollupAggregator::Avg if self.count > 0 => Some(self.sum / self.count as f64),
+            RollupAggregator::Sum if self.count > 0 => Some(self.sum),
+            RollupAggregator::Count => Some(self.count as f64),
+            RollupAggregator::Min => self.min,
+            RollupAggregator::Max => self.max,
+            RollupAggregator::Last => return self.last.unwrap_or(Value::Null),
+            _ => None,
+        };
+        value.map_or(Value::Null, |v| f64_to_value(v, data_type))
+    }
+}
+
+impl SeriesRollup {
+    fn new(batch: &Batch, rollup: RollupMeta) -> Self {
+        let timestamp_type = batch.timestamps().data_type();
+        // Safety: the time index is a timestamp and the interval is in seconds.
+        let unit = timestamp_type.as_timestamp().unwrap().unit();
+        let interval = Timestamp::new_second(rollup.interval_secs as i64)
+            .convert_to(unit)
+            .unwrap()
+            .value();
+        let field_types = batch
+            .fields()
+            .iter()
+            .map(|field| (field.column_id, field.data.data_type()))
+            .collect::<Vec<_>>();
+
+        Self {
+            primary_key: batch.primary_key().to_vec(),
+            aggregator: rollup.aggregator,
+            interval,
+            timestamp_type,
+            field_types,
+            intervals: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the interval that starts at `start`.
+    fn interval(&mut self, start: i64) -> &mut Interval {
+        let num_fields = self.field_types.len();
+        self.intervals.entry(start).or_insert_with(|| Interval {
+            sequence: 0,
+            accumulators: (0..num_fields).map(|_| Accumulator::default()).collect(),
+        })
+    }
+
+    /// Pushes raw rows of the `batch` into intervals.
+    fn push(&mut self, batch: &Batch) {
+        // Safety: the batch is not empty.
+        let timestamps = batch.timestamps_native().unwrap();
+        for (row, ts) in timestamps.iter().enumerate() {
+            let start = ts.div_euclid(self.interval) * self.interval;
+            let interval = self.interval(start);
+            interval.sequence = interval.sequence.max(batch.get_sequence(row));
+            for (accumulator, field) in interval.accumulators.iter_mut().zip(batch.fields()) {
+                accumulator.update(field.data.get(row));
+            }
+        }
+    }
+
+    /// Merges rows of the `batch` rolled up into the tier before. `counts` are row
+    /// counts of fields keyed by the start of intervals.
+    fn merge_rolled(&mut self, batch: &Batch, counts: &HashMap<i64, Vec<Option<u64>>>) {
+        let aggregator = self.aggregator;
+        // Safety: the batch is not empty.
+        let timestamps = batch.timestamps_native().unwrap();
+        for (row, ts) in timestamps.iter().enumerate() {
+            let row_counts = counts.get(ts);
+            let interval = self.interval(*ts);
+            interval.sequence = interval.sequence.max(batch.get_sequence(row));
+            for (i, (accumulator, field)) in interval
+                .accumulators
+                .iter_mut()
+                .zip(batch.fields())
+                .enumerate()
+            {
+                let count = row_counts.and_then(|counts| counts.get(i).copied().flatten());
+                accumulator.merge_rolled(aggregator, field.data.get(row), count);
+            }
+        }
+    }
+
+    /// Builds the aggregated rows into a batch.
+    fn finish(self) -> Result<Batch> {
+        let num_rows = self.intervals.len();
+        let mut timestamps = self.timestamp_type.create_mutable_vector(num_rows);
+        let mut sequences = Vec::with_capacity(num_rows);
+        let mut fields = self
+            .field_types
+            .iter()
+            .map(|(_, data_type)| data_type.create_mutable_vector(num_rows))
+            .collect::<Vec<_>>();
+        // Safety: the data type is timestamp.
+        let unit = self.timestamp_type.as_timestamp().unwrap().unit();
+        for (start, interval) in self.intervals {
+            timestamps
+                .try_push_value_ref(Value::Timestamp(Timestamp::new(start, unit)).as_value_ref())
+                .context(ComputeVectorSnafu)?;
+            sequences.push(interval.sequence);
+            for ((accumulator, (_, data_type)), builder) in interval
+                .accumulators
+                .into_iter()
+                .zip(&self.field_types)
+                .zip(&mut fields)
+            {
+                builder
+                    .try_push_value_ref(
+                        accumulator
+                            .finish(self.aggregator, data_type)
+                            .as_value_ref(),
+                    )
+                    .context(ComputeVectorSnafu)?;
+            }
+        }
+
+        let fields = self
+            .field_types
+            .iter()
+            .zip(fields)
+            .map(|((column_id, _), mut builder)| BatchColumn {
+                column_id: *column_id,
+                data: builder.to_vector(),
+            })
+            .collect();
+        Batch::new(
+            self.primary_key,
+            timestamps.to_vector(),
+            Arc::new(UInt64Vector::from_vec(sequences)),
+            Arc::new(UInt8Vector::from_vec(vec![OpType::Put as u8; num_rows])),
+            fields,
+        )
+    }
+}
+
+/// Converts an aggregated value to a value of numeric `data_type`.
+fn f64_to_value(v: f64, data_type: &ConcreteDataType) -> Value {
+    match data_type {
+        ConcreteDataType::Int8(_) => Value::Int8(v as i8),
+        ConcreteDataType::Int16(_) => Value::Int16(v as i16),
+        ConcreteDataType::Int32(_) => Value::Int32(v as i32),
+        ConcreteDataType::Int64(_) => Value::Int64(v as i64),
+        ConcreteDataType::UInt8(_) => Value::UInt8(v as u8),
+        ConcreteDataType::UInt16(_) => Value::UInt16(v as u16),
+        ConcreteDataType::UInt32(_) => Value::UInt32(v as u32),
+        ConcreteDataType::UInt64(_) => Value::UInt64(v as u64),
+        ConcreteDataType::Float32(_) => Value::Float32((v as f32).into()),
+        _ => Value::Float64(v.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{check_reader_result, new_batch, VecBatchReader};
+
+    fn new_reader(batches: &[Batch], aggregator: RollupAggregator) -> RollupReader {
+        RollupReader::new(
+            Box::new(VecBatchReader::new(batches)),
+            RollupMeta {
+                interval_secs: 10,
+                aggregator,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_rollup_reader() {
+        let input = [
+            new_batch(
+                b"k1",
+                &[1000, 9000, 10000],
+                &[11, 12, 13],
+                &[OpType::Put, OpType::Put, OpType::Put],
+                &[3, 5, 7],
+            ),
+            new_batch(
+                b"k1",
+                &[15000, 25000],
+                &[14, 10],
+                &[OpType::Put, OpType::Delete],
+                &[1, 100],
+            ),
+            new_batch(
+                b"k2",
+                &[-1000, 0],
+                &[21, 22],
+                &[OpType::Put, OpType::Put],
+                &[8, 9],
+            ),
+        ];
+
+        let mut reader = new_reader(&input, RollupAggregator::Max);
+        check_reader_result(
+            &mut reader,
+            &[
+                new_batch(
+                    b"k1",
+                    &[0, 10000],
+                    &[12, 14],
+                    &[OpType::Put, OpType::Put],
+                    &[5, 7],
+                ),
+                new_batch(
+                    b"k2",
+                    &[-10000, 0],
+                    &[21, 22],
+                    &[OpType::Put, OpType::Put],
+                    &[8, 9],
+                ),
+            ],
+        )
+        .await;
+
+        let mut reader = new_reader(&input, RollupAggregator::Sum);
+        check_reader_result(
+            &mut reader,
+            &[
+                new_batch(
+                    b"k1",
+                    &[0, 10000],
+                    &[12, 14],
+                    &[OpType::Put, OpType::Put],
+                    &[8, 8],
+                ),
+                new_batch(
+                    b"k2",
+                    &[-10000, 0],
+                    &[21, 22],
+                    &[OpType::Put, OpType::Put],
+                    &[8, 9],
+                ),
+            ],
+        )
+        .await;
+
+        let mut reader = new_reader(&input[..2], RollupAggregator::Count);
+        check_reader_result(
+            &mut reader,
+            &[new_batch(
+                b"k1",
+                &[0, 10000],
+                &[12, 14],
+                &[OpType::Put, OpType::Put],
+                &[2, 2],
+            )],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_rollup_reader_merge_rolled() {
+        let raw = [new_batch(
+            b"k1",
+            &[2000, 21000],
+            &[30, 31],
+            &[OpType::Put, OpType::Put],
+            &[6, 2],
+        )];
+        let rolled = [
+            new_batch(b"k0", &[0], &[4], &[OpType::Put], &[4]),
+            new_batch(
+                b"k1",
+                &[0, 10000],
+                &[5, 6],
+                &[OpType::Put, OpType::Put],
+                &[3, 7],
+            ),
+        ];
+        let counts = [new_batch(b"k1", &[0], &[5], &[OpType::Put], &[2])];
+
+        let mut reader = new_reader(&raw, RollupAggregator::Avg).with_rolled(
+            Box::new(VecBatchReader::new(&rolled)),
+            Some(Box::new(VecBatchReader::new(&counts))),
+        );
+        check_reader_result(
+            &mut reader,
+            &[
+                new_batch(b"k0", &[0], &[4], &[OpType::Put], &[4]),
+                new_batch(
+                    b"k1",
+                    &[0, 10000, 20000],
+                    &[30, 6, 31],
+                    &[OpType::Put, OpType::Put, OpType::Put],
+                    &[4, 7, 2],
+                ),
+            ],
+        )
+        .await;
+
+        let mut reader = new_reader(&raw, RollupAggregator::Max)
+            .with_rolled(Box::new(VecBatchReader::new(&rolled)), None);
+        check_reader_result(
+            &mut reader,
+            &[
+                new_batch(b"k0", &[0], &[4], &[OpType::Put], &[4]),
+                new_batch(
+                    b"k1",
+                    &[0, 10000, 20000],
+                    &[30, 6, 31],
+                    &[OpType::Put, OpType::Put, OpType::Put],
+                    &[6, 7, 2],
+                ),
+            ],
+        )
+        .await;
+    }
+
+    #[test]
+    fn test_accumulator() {
+        let mut accumulator = Accumulator::default();
+        for value in [Value::Int64(1), Value::Null, Value::Int64(4)] {
+            accumulator.update(value);
+        }
+        assert_eq!(
+            Value::Float64(2.5.into()),
+            accumulator.finish(RollupAggregator::Avg, &ConcreteDataType::float64_datatype())
+        );
+
+        let mut accumulator = Accumulator::default();
+        accumulator.update(Value::String("a".into()));
+        accumulator.update(Value::String("b".into()));
+        assert_eq!(
+            Value::String("b".into()),
+            accumulator.finish(RollupAggregator::Avg, &ConcreteDataType::string_datatype())
+        );
+
+        let accumulator = Accumulator::default();
+        assert_eq!(
+            Value::Null,
+            accumulator.finish(RollupAggregator::Sum, &ConcreteDataType::int64_datatype())
+        );
+
+        let mut accumulator = Accumulator::default();
+        accumulator.update(Value::Int64(5));
+        accumulator.merge_rolled(RollupAggregator::Last, Value::Int64(1), None);
+        assert_eq!(
+            Value::Int64(5),
+            accumulator.finish(RollupAggregator::Last, &ConcreteDataType::int64_datatype())
+        );
+
+        let mut accumulator = Accumulator::default();
+        accumulator.update(Value::Int64(1));
+        accumulator.merge_rolled(RollupAggregator::Count, Value::Int64(3), None);
+        assert_eq!(
+            Value::Int64(4),
+            accumulator.finish(RollupAggregator::Count, &ConcreteDataType::int64_datatype())
+        );
+    }
+}
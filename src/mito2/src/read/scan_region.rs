@@ -55,7 +55,7 @@ use crate::read::unordered_scan::UnorderedScan;
 use crate::read::{Batch, Source};
 use crate::region::options::MergeMode;
 use crate::region::version::VersionRef;
-use crate::sst::file::FileHandle;
+use crate::sst::file::{FileHandle, RollupMeta};
 use crate::sst::index::bloom_filter::applier::{
     BloomFilterIndexApplierBuilder, BloomFilterIndexApplierRef,
 };
@@ -63,6 +63,8 @@ use crate::sst::index::fulltext_index::applier::builder::FulltextIndexApplierBui
 use crate::sst::index::fulltext_index::applier::FulltextIndexApplierRef;
 use crate::sst::index::inverted_index::applier::builder::InvertedIndexApplierBuilder;
 use crate::sst::index::inverted_index::applier::InvertedIndexApplierRef;
+use crate::sst::index::ip_cidr::derive_ip_cidr_filters;
+use crate::sst::parquet::json_shred::{parse_path, JsonPaths};
 use crate::sst::parquet::reader::ReaderMetrics;
 
 /// A scanner scans a region and returns a [SendableRecordBatchStream].
@@ -368,6 +370,7 @@ impl ScanRegion {
 
     /// Creates a scan input.
     async fn scan_input(mut self) -> Result<ScanInput> {
+        self.add_ip_cidr_filters();
         let sst_min_sequence = self.request.sst_min_sequence.and_then(NonZeroU64::new);
         let time_range = self.build_time_range_predicate();
         let predicate = PredicateGroup::new(&self.version.metadata, &self.request.filters);
@@ -379,9 +382,23 @@ impl ScanRegion {
         };
 
         let ssts = &self.version.ssts;
+        let rollup = self
+            .version
+            .options
+            .rollup
+            .as_ref()
+            .and_then(|options| options.select_tier(self.request.rollup.as_ref()))
+            .map(|(interval, aggregator)| RollupMeta {
+                interval_secs: interval.as_secs(),
+                aggregator,
+            });
         let mut files = Vec::new();
         for level in ssts.levels() {
             for file in level.files.values() {
+                // Reads rollup files of the selected tier only.
+                if file.rollup().is_some() && file.rollup() != rollup {
+                    continue;
+                }
                 let exceed_min_sequence = match (sst_min_sequence, file.meta_ref().sequence) {
                     (Some(min_sequence), Some(file_sequence)) => file_sequence > min_sequence,
                     // If the file's sequence is None (or actually is zero), it could mean the file
@@ -448,6 +465,7 @@ impl ScanRegion {
         let inverted_index_applier = self.build_invereted_index_applier();
         let bloom_filter_applier = self.build_bloom_filter_applier();
         let fulltext_index_applier = self.build_fulltext_index_applier();
+        let json_paths = self.build_json_paths();
         let predicate = PredicateGroup::new(&self.version.metadata, &self.request.filters);
         // The mapper always computes projected column ids as the schema of SSTs may change.
         let mapper = match &self.request.projection {
@@ -471,7 +489,8 @@ impl ScanRegion {
             .with_filter_deleted(self.filter_deleted)
             .with_merge_mode(self.version.options.merge_mode())
             .with_series_row_selector(self.request.series_row_selector)
-            .with_distribution(self.request.distribution);
+            .with_distribution(self.request.distribution)
+            .with_json_paths(json_paths);
 
         #[cfg(feature = "enterprise")]
         let input = if let Some(provider) = self.extension_range_provider {
@@ -502,6 +521,13 @@ impl ScanRegion {
         build_time_range_predicate(&time_index.column_schema.name, unit, &self.request.filters)
     }
 
+    /// Adds range filters derived from CIDR filters on ip columns so that
+    /// min/max statistics and indexes can prune with them.
+    fn add_ip_cidr_filters(&mut self) {
+        let derived = derive_ip_cidr_filters(&self.version.metadata, &self.request.filters);
+        self.request.filters.extend(derived);
+    }
+
     /// Remove field filters if the merge mode is [MergeMode::LastNonNull].
     fn maybe_remove_field_filters(&mut self) {
         if self.version.options.merge_mode() != MergeMode::LastNonNull {
@@ -622,6 +648,31 @@ impl ScanRegion {
         .flatten()
         .map(Arc::new)
     }
+
+    /// Resolves paths of JSON columns in the request.
+    ///
+    /// Ignores a column if any of its paths can't be read from sub-columns.
+    fn build_json_paths(&self) -> Option<Arc<JsonPaths>> {
+        let hint = self.request.json_paths.as_ref()?;
+        let metadata = &self.version.metadata;
+        let json_paths = hint
+            .paths
+            .iter()
+            .filter_map(|(name, paths)| {
+                let column = metadata.column_by_name(name)?;
+                if !column.column_schema.data_type.is_json() {
+                    return None;
+                }
+                let paths = paths
+                    .iter()
+                    .map(|path| parse_path(path))
+                    .collect::<Option<Vec<_>>>()?;
+                Some((column.column_id, paths))
+            })
+            .collect::<JsonPaths>();
+
+        (!json_paths.is_empty()).then(|| Arc::new(json_paths))
+    }
 }
 
 /// Returns true if the time range of a SST `file` matches the `predicate`.
@@ -673,6 +724,8 @@ pub struct ScanInput {
     pub(crate) series_row_selector: Option<TimeSeriesRowSelector>,
     /// Hint for the required distribution of the scanner.
     pub(crate) distribution: Option<TimeSeriesDistribution>,
+    /// Paths of JSON columns the query accesses.
+    json_paths: Option<Arc<JsonPaths>>,
     #[cfg(feature = "enterprise")]
     extension_ranges: Vec<BoxedExtensionRange>,
 }
@@ -701,6 +754,7 @@ impl ScanInput {
             merge_mode: MergeMode::default(),
             series_row_selector: None,
             distribution: None,
+            json_paths: None,
             #[cfg(feature = "enterprise")]
             extension_ranges: Vec::new(),
         }
@@ -845,6 +899,13 @@ impl ScanInput {
         self
     }
 
+    /// Sets paths of JSON columns the query accesses.
+    #[must_use]
+    pub(crate) fn with_json_paths(mut self, json_paths: Option<Arc<JsonPaths>>) -> Self {
+        self.json_paths = json_paths;
+        self
+    }
+
     /// Scans sources in parallel.
     ///
     /// # Panics if the input doesn't allow parallel scan.
@@ -884,6 +945,13 @@ impl ScanInput {
         file: &FileHandle,
         reader_metrics: &mut ReaderMetrics,
     ) -> Result<FileRangeBuilder> {
+        // Results of the selector are cached by projection, so we always read whole
+        // JSON documents to avoid caching documents that only have some paths.
+        let json_paths = if self.series_row_selector.is_none() {
+            self.json_paths.clone()
+        } else {
+            None
+        };
         let res = self
             .access_layer
             .read_sst(file.clone())
@@ -894,6 +962,7 @@ impl ScanInput {
             .bloom_filter_index_applier(self.bloom_filter_index_applier.clone())
             .fulltext_index_applier(self.fulltext_index_applier.clone())
             .expected_metadata(Some(self.mapper.metadata().clone()))
+            .json_paths(json_paths)
             .build_reader_input(reader_metrics)
             .await;
         let (mut file_range_ctx, selection) = match res {
@@ -22,19 +22,23 @@ use std::time::Duration;
 use common_base::readable_size::ReadableSize;
 use common_time::TimeToLive;
 use common_wal::options::{WalOptions, WAL_OPTIONS_KEY};
+use datatypes::types::JsonFormat;
+use humantime_serde::re::humantime;
 use serde::de::Error as _;
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 use serde_with::{serde_as, with_prefix, DisplayFromStr, NoneAsEmptyString};
-use snafu::{ensure, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 use store_api::codec::PrimaryKeyEncoding;
-use store_api::storage::ColumnId;
+use store_api::storage::{ColumnId, RollupAggregator, RollupHint};
 use strum::EnumString;
 
 use crate::error::{Error, InvalidRegionOptionsSnafu, JsonOptionsSnafu, Result};
 use crate::memtable::partition_tree::{DEFAULT_FREEZE_THRESHOLD, DEFAULT_MAX_KEYS_PER_SHARD};
 
 const DEFAULT_INDEX_SEGMENT_ROW_COUNT: usize = 1024;
+/// Default age of data to roll up.
+const DEFAULT_ROLLUP_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
 
 /// Mode to handle duplicate rows while merging.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumString)]
@@ -73,6 +77,10 @@ pub struct RegionOptions {
     /// The mode to merge duplicate rows.
     /// Only takes effect when `append_mode` is `false`.
     pub merge_mode: Option<MergeMode>,
+    /// Rollup tiers to downsample old data.
+    pub rollup: Option<RollupOptions>,
+    /// Format to store JSON columns in SSTs.
+    pub json_format: JsonFormat,
 }
 
 impl RegionOptions {
@@ -142,6 +150,34 @@ impl TryFrom<&HashMap<String, String>> for RegionOptions {
             None
         };
 
+        let rollup = match options.rollup {
+            Some(tiers) => Some(RollupOptions::try_new(
+                &tiers,
+                options.rollup_after.unwrap_or(DEFAULT_ROLLUP_AFTER),
+            )?),
+            None => {
+                ensure!(
+                    options.rollup_after.is_none(),
+                    InvalidRegionOptionsSnafu {
+                        reason: "rollup.after is not allowed without rollup",
+                    }
+                );
+                None
+            }
+        };
+
+        let json_format = match options.json_format.as_deref() {
+            None => JsonFormat::default(),
+            Some(format) if format.eq_ignore_ascii_case("jsonb") => JsonFormat::Jsonb,
+            Some(format) if format.eq_ignore_ascii_case("shredded") => JsonFormat::Shredded,
+            Some(format) => {
+                return InvalidRegionOptionsSnafu {
+                    reason: format!("unknown json.format `{format}`, expect `jsonb` or `shredded`"),
+                }
+                .fail();
+            }
+        };
+
         let opts = RegionOptions {
             ttl: options.ttl,
             compaction,
@@ -151,6 +187,8 @@ impl TryFrom<&HashMap<String, String>> for RegionOptions {
             index_options,
             memtable,
             merge_mode: options.merge_mode,
+            rollup,
+            json_format,
         };
         opts.validate()?;
 
@@ -166,24 +204,30 @@ pub enum CompactionOptions {
     /// Time window compaction strategy.
     #[serde(with = "prefix_twcs")]
     Twcs(TwcsOptions),
+    /// Size-tiered compaction strategy.
+    #[serde(with = "prefix_size_tiered")]
+    SizeTiered(SizeTieredOptions),
 }
 
 impl CompactionOptions {
     pub(crate) fn time_window(&self) -> Option<Duration> {
         match self {
             CompactionOptions::Twcs(opts) => opts.time_window,
+            CompactionOptions::SizeTiered(opts) => opts.time_window,
         }
     }
 
     pub(crate) fn remote_compaction(&self) -> bool {
         match self {
             CompactionOptions::Twcs(opts) => opts.remote_compaction,
+            CompactionOptions::SizeTiered(opts) => opts.remote_compaction,
         }
     }
 
     pub(crate) fn fallback_to_local(&self) -> bool {
         match self {
             CompactionOptions::Twcs(opts) => opts.fallback_to_local,
+            CompactionOptions::SizeTiered(opts) => opts.fallback_to_local,
         }
     }
 }
@@ -220,14 +264,7 @@ with_prefix!(prefix_twcs "compaction.twcs.");
 impl TwcsOptions {
     /// Returns time window in second resolution.
     pub fn time_window_seconds(&self) -> Option<i64> {
-        self.time_window.and_then(|window| {
-            let window_secs = window.as_secs();
-            if window_secs == 0 {
-                None
-            } else {
-                window_secs.try_into().ok()
-            }
-        })
+        time_window_seconds(self.time_window)
     }
 }
 
@@ -243,6 +280,67 @@ impl Default for TwcsOptions {
     }
 }
 
+/// Size-tiered compaction options.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SizeTieredOptions {
+    /// Minimum number of files with similar size in a tier to trigger a compaction.
+    #[serde_as(as = "DisplayFromStr")]
+    pub min_threshold: usize,
+    /// Maximum number of files to compact in a tier at once.
+    #[serde_as(as = "DisplayFromStr")]
+    pub max_threshold: usize,
+    /// Files smaller than this size are put into the same tier.
+    pub min_file_size: Option<ReadableSize>,
+    /// Compaction time window defined when creating tables.
+    #[serde(with = "humantime_serde")]
+    pub time_window: Option<Duration>,
+    /// Max allowed compaction output file size. Files larger than it are never compacted again.
+    pub max_output_file_size: Option<ReadableSize>,
+    /// Whether to use remote compaction.
+    #[serde_as(as = "DisplayFromStr")]
+    pub remote_compaction: bool,
+    /// Whether to fall back to local compaction if remote compaction fails.
+    #[serde_as(as = "DisplayFromStr")]
+    pub fallback_to_local: bool,
+}
+
+with_prefix!(prefix_size_tiered "compaction.size_tiered.");
+
+impl SizeTieredOptions {
+    /// Returns time window in second resolution.
+    pub fn time_window_seconds(&self) -> Option<i64> {
+        time_window_seconds(self.time_window)
+    }
+}
+
+impl Default for SizeTieredOptions {
+    fn default() -> Self {
+        Self {
+            min_threshold: 4,
+            max_threshold: 32,
+            min_file_size: Some(ReadableSize::mb(50)),
+            time_window: None,
+            max_output_file_size: Some(ReadableSize::gb(2)),
+            remote_compaction: false,
+            fallback_to_local: true,
+        }
+    }
+}
+
+/// Converts the time window to seconds, returns `None` if the window is zero.
+fn time_window_seconds(time_window: Option<Duration>) -> Option<i64> {
+    time_window.and_then(|window| {
+        let window_secs = window.as_secs();
+        if window_secs == 0 {
+            None
+        } else {
+            window_secs.try_into().ok()
+        }
+    })
+}
+
 /// We need to define a new struct without enum fields as `#[serde(default)]` does not
 /// support external tagging.
 #[serde_as]
@@ -256,6 +354,13 @@ struct RegionOptionsWithoutEnum {
     append_mode: bool,
     #[serde_as(as = "NoneAsEmptyString")]
     merge_mode: Option<MergeMode>,
+    #[serde_as(as = "NoneAsEmptyString")]
+    rollup: Option<String>,
+    #[serde(rename = "rollup.after", with = "humantime_serde")]
+    rollup_after: Option<Duration>,
+    #[serde(rename = "json.format")]
+    #[serde_as(as = "NoneAsEmptyString")]
+    json_format: Option<String>,
 }
 
 impl Default for RegionOptionsWithoutEnum {
@@ -266,10 +371,124 @@ impl Default for RegionOptionsWithoutEnum {
             storage: options.storage,
             append_mode: options.append_mode,
             merge_mode: options.merge_mode,
+            rollup: None,
+            rollup_after: None,
+            json_format: None,
         }
     }
 }
 
+/// Options to roll up data older than `after` into tiers of aggregated data.
+///
+/// The rollup is declared as tiers separated by `;`. Each tier has an interval
+/// and the aggregators to apply, e.g. `5m:avg,max;1h:avg`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RollupOptions {
+    /// Tiers sorted by interval in ascending order.
+    pub tiers: Vec<RollupTier>,
+    /// Age of data to roll up.
+    #[serde(with = "humantime_serde")]
+    pub after: Duration,
+}
+
+/// A tier of rollup data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RollupTier {
+    /// Interval of rows in the tier.
+    #[serde(with = "humantime_serde")]
+    pub interval: Duration,
+    /// Aggregators of the tier. Each aggregator is stored in its own SSTs.
+    pub aggregators: Vec<RollupAggregator>,
+}
+
+impl RollupOptions {
+    /// Parses the rollup `tiers` and builds the options.
+    pub fn try_new(tiers: &str, after: Duration) -> Result<Self> {
+        let mut parsed = Vec::new();
+        for tier in tiers.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+            let (interval, aggregators) =
+                tier.split_once(':')
+                    .with_context(|| InvalidRegionOptionsSnafu {
+                        reason: format!(
+                            "invalid rollup tier `{tier}`, expect `<interval>:<aggregators>`"
+                        ),
+                    })?;
+            let interval = humantime::parse_duration(interval.trim()).map_err(|e| {
+                InvalidRegionOptionsSnafu {
+                    reason: format!("invalid rollup interval `{interval}`: {e}"),
+                }
+                .build()
+            })?;
+            ensure!(
+                interval.as_secs() > 0 && interval.subsec_nanos() == 0,
+                InvalidRegionOptionsSnafu {
+                    reason: format!("rollup interval {interval:?} must be whole seconds"),
+                }
+            );
+
+            let mut tier_aggregators = Vec::new();
+            for aggregator in aggregators.split(',').map(str::trim) {
+                let aggregator = aggregator.parse::<RollupAggregator>().map_err(|_| {
+                    InvalidRegionOptionsSnafu {
+                        reason: format!("unknown rollup aggregator `{aggregator}`"),
+                    }
+                    .build()
+                })?;
+                if !tier_aggregators.contains(&aggregator) {
+                    tier_aggregators.push(aggregator);
+                }
+            }
+            parsed.push(RollupTier {
+                interval,
+                aggregators: tier_aggregators,
+            });
+        }
+
+        ensure!(
+            !parsed.is_empty(),
+            InvalidRegionOptionsSnafu {
+                reason: "rollup requires at least one tier",
+            }
+        );
+        parsed.sort_unstable_by_key(|tier| tier.interval);
+        ensure!(
+            parsed.windows(2).all(|w| w[0].interval < w[1].interval),
+            InvalidRegionOptionsSnafu {
+                reason: "rollup intervals must be distinct",
+            }
+        );
+
+        Ok(Self {
+            tiers: parsed,
+            after,
+        })
+    }
+
+    /// Selects the tier interval and the aggregator to read for the `hint`.
+    ///
+    /// Returns the coarsest tier whose interval is no larger than the step of the
+    /// hint and has the aggregator of the hint. Raw data older than `after` is
+    /// removed once it's rolled up, so queries without a matching tier, e.g. queries
+    /// without an aggregator, read the first aggregator of the finest tier instead.
+    pub fn select_tier(&self, hint: Option<&RollupHint>) -> Option<(Duration, RollupAggregator)> {
+        let matched = hint.and_then(|hint| {
+            let aggregator = hint.aggregator?;
+            self.tiers
+                .iter()
+                .rev()
+                .find(|tier| {
+                    tier.interval.as_millis() <= hint.step_millis as u128
+                        && tier.aggregators.contains(&aggregator)
+                })
+                .map(|tier| (tier.interval, aggregator))
+        });
+        matched.or_else(|| {
+            let tier = self.tiers.first()?;
+            Some((tier.interval, *tier.aggregators.first()?))
+        })
+    }
+}
+
 with_prefix!(prefix_inverted_index "index.inverted_index.");
 
 /// Options for index.
@@ -514,6 +733,114 @@ mod tests {
         assert_eq!(expect, options);
     }
 
+    #[test]
+    fn test_with_size_tiered_compaction() {
+        let map = make_map(&[
+            ("compaction.type", "size_tiered"),
+            ("compaction.size_tiered.min_threshold", "6"),
+            ("compaction.size_tiered.max_threshold", "16"),
+            ("compaction.size_tiered.min_file_size", "8MB"),
+            ("compaction.size_tiered.time_window", "1d"),
+            ("compaction.size_tiered.max_output_file_size", "1GB"),
+            ("append_mode", "true"),
+        ]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        let expect = RegionOptions {
+            compaction: CompactionOptions::SizeTiered(SizeTieredOptions {
+                min_threshold: 6,
+                max_threshold: 16,
+                min_file_size: Some(ReadableSize::mb(8)),
+                time_window: Some(Duration::from_secs(3600 * 24)),
+                max_output_file_size: Some(ReadableSize::gb(1)),
+                ..Default::default()
+            }),
+            append_mode: true,
+            ..Default::default()
+        };
+        assert_eq!(expect, options);
+    }
+
+    #[test]
+    fn test_with_rollup() {
+        let map = make_map(&[("rollup", "1h:avg; 5m:avg,max,avg"), ("rollup.after", "7d")]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        let expect = RollupOptions {
+            tiers: vec![
+                RollupTier {
+                    interval: Duration::from_secs(300),
+                    aggregators: vec![RollupAggregator::Avg, RollupAggregator::Max],
+                },
+                RollupTier {
+                    interval: Duration::from_secs(3600),
+                    aggregators: vec![RollupAggregator::Avg],
+                },
+            ],
+            after: Duration::from_secs(7 * 24 * 3600),
+        };
+        assert_eq!(Some(&expect), options.rollup.as_ref());
+
+        let map = make_map(&[("rollup", "5m:max")]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        assert_eq!(DEFAULT_ROLLUP_AFTER, options.rollup.unwrap().after);
+
+        let options = expect;
+        let hint = |step_millis, aggregator| RollupHint {
+            step_millis,
+            aggregator,
+        };
+        let five_minutes = Duration::from_secs(300);
+        let one_hour = Duration::from_secs(3600);
+        let default = Some((five_minutes, RollupAggregator::Avg));
+        assert_eq!(default, options.select_tier(None));
+        assert_eq!(
+            Some((one_hour, RollupAggregator::Avg)),
+            options.select_tier(Some(&hint(3_600_000, Some(RollupAggregator::Avg))))
+        );
+        assert_eq!(
+            Some((five_minutes, RollupAggregator::Max)),
+            options.select_tier(Some(&hint(3_600_000, Some(RollupAggregator::Max))))
+        );
+        // The step is finer than all tiers.
+        assert_eq!(
+            default,
+            options.select_tier(Some(&hint(60_000, Some(RollupAggregator::Max))))
+        );
+        // The aggregator is not rolled up.
+        assert_eq!(
+            default,
+            options.select_tier(Some(&hint(3_600_000, Some(RollupAggregator::Sum))))
+        );
+        assert_eq!(default, options.select_tier(Some(&hint(3_600_000, None))));
+
+        for invalid in [
+            vec![("rollup.after", "1d")],
+            vec![("rollup", "5m")],
+            vec![("rollup", "5m:median")],
+            vec![("rollup", "500ms:avg")],
+            vec![("rollup", "5m:avg;300s:max")],
+            vec![("rollup", ";")],
+        ] {
+            let map = make_map(&invalid);
+            let err = RegionOptions::try_from(&map).unwrap_err();
+            assert_eq!(StatusCode::InvalidArguments, err.status_code());
+        }
+    }
+
+    #[test]
+    fn test_with_json_format() {
+        let map = make_map(&[]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        assert_eq!(JsonFormat::Jsonb, options.json_format);
+
+        let map = make_map(&[("json.format", "Shredded")]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        assert_eq!(JsonFormat::Shredded, options.json_format);
+
+        let map = make_map(&[("json.format", "bson")]);
+        let err = RegionOptions::try_from(&map).unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+    }
+
     fn test_with_wal_options(wal_options: &WalOptions) -> bool {
         let encoded_wal_options = serde_json::to_string(&wal_options).unwrap();
         let map = make_map(&[(WAL_OPTIONS_KEY, &encoded_wal_options)]);
@@ -652,6 +979,8 @@ mod tests {
                 primary_key_encoding: PrimaryKeyEncoding::Dense,
             })),
             merge_mode: Some(MergeMode::LastNonNull),
+            rollup: None,
+            json_format: JsonFormat::Jsonb,
         };
         assert_eq!(expect, options);
     }
@@ -685,6 +1014,8 @@ mod tests {
                 primary_key_encoding: PrimaryKeyEncoding::Dense,
             })),
             merge_mode: Some(MergeMode::LastNonNull),
+            rollup: None,
+            json_format: JsonFormat::Jsonb,
         };
         let region_options_json_str = serde_json::to_string(&options).unwrap();
         let got: RegionOptions = serde_json::from_str(&region_options_json_str).unwrap();
@@ -748,6 +1079,8 @@ mod tests {
                 primary_key_encoding: PrimaryKeyEncoding::Dense,
             })),
             merge_mode: Some(MergeMode::LastNonNull),
+            rollup: None,
+            json_format: JsonFormat::Jsonb,
         };
         assert_eq!(options, got);
     }
@@ -27,7 +27,7 @@ use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use snafu::{ResultExt, Snafu};
 use store_api::region_request::PathType;
-use store_api::storage::RegionId;
+use store_api::storage::{RegionId, RollupAggregator};
 use uuid::Uuid;
 
 use crate::sst::file_purger::{FilePurgerRef, PurgeRequest};
@@ -167,6 +167,9 @@ pub struct FileMeta {
     /// This sequence is the only sequence in this file. And it's retrieved from the max
     /// sequence of the rows on generating this file.
     pub sequence: Option<NonZeroU64>,
+    /// Rollup tier of the file. `None` if the file contains raw data.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rollup: Option<RollupMeta>,
 }
 
 impl Debug for FileMeta {
@@ -200,11 +203,23 @@ impl Debug for FileMeta {
                 Some(seq) => {
                     write!(f, "{}", seq)
                 }
-            })
-            .finish()
+            });
+        if let Some(rollup) = &self.rollup {
+            debug_struct.field("rollup", rollup);
+        }
+        debug_struct.finish()
     }
 }
 
+/// Rollup tier of a SST file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RollupMeta {
+    /// Interval of rows in seconds.
+    pub interval_secs: u64,
+    /// Aggregator applied to rows in each interval.
+    pub aggregator: RollupAggregator,
+}
+
 /// Type of index.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum IndexType {
@@ -319,6 +334,11 @@ impl FileHandle {
     pub fn num_rows(&self) -> usize {
         self.inner.meta.num_rows as usize
     }
+
+    /// Returns the rollup tier of the file, `None` if the file contains raw data.
+    pub fn rollup(&self) -> Option<RollupMeta> {
+        self.inner.meta.rollup
+    }
 }
 
 /// Inner data of [FileHandle].
@@ -390,6 +410,7 @@ mod tests {
             num_rows: 0,
             num_row_groups: 0,
             sequence: None,
+            rollup: None,
         }
     }
 
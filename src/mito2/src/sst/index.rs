@@ -17,6 +17,7 @@ pub(crate) mod fulltext_index;
 mod indexer;
 pub mod intermediate;
 pub(crate) mod inverted_index;
+pub(crate) mod ip_cidr;
 pub mod puffin_manager;
 mod statistics;
 pub(crate) mod store;
@@ -0,0 +1,355 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Derives pruning filters from CIDR predicates on ip columns.
+//!
+//! Ip values are stored as 16 bytes in big-endian order, so a CIDR block is a contiguous
+//! range of values. A filter `ip_in_cidr(col, '10.0.0.0/8')` implies
+//! `col BETWEEN <first address> AND <last address>`, which min/max statistics and the
+//! inverted index can prune with. Small blocks additionally imply `col IN (<all addresses>)`,
+//! which the bloom filter index can prune with.
+//!
+//! A disjunction of CIDR blocks on the same column implies the union of the blocks.
+
+use common_telemetry::debug;
+use datafusion_common::{Column, ScalarValue};
+use datafusion_expr::expr::InList;
+use datafusion_expr::utils::split_conjunction;
+use datafusion_expr::{Between, BinaryExpr, Expr, Operator};
+use datatypes::types::{parse_cidr_to_ip_type_range, IP_BYTE_SIZE};
+use store_api::metadata::RegionMetadata;
+
+/// Name of the function that checks whether an ip is in a CIDR block.
+const IP_IN_CIDR: &str = "ip_in_cidr";
+
+/// Max number of addresses in a CIDR block to expand it to an `IN` list.
+const MAX_CIDR_IN_LIST_SIZE: u128 = 16;
+
+/// Returns filters implied by the `ip_in_cidr` filters on ip columns in `exprs`.
+///
+/// Each conjunct of the filters is checked, a disjunction of `ip_in_cidr` on the same column
+/// implies the union of the blocks, and a conjunction implies the blocks of any of its sides.
+/// Filters mixing `ip_in_cidr` with other predicates in a disjunction are not derived from.
+///
+/// The returned filters don't change the result of the query as they are always
+/// weaker than the original ones, so callers can add them to the filters only for pruning.
+pub(crate) fn derive_ip_cidr_filters(metadata: &RegionMetadata, exprs: &[Expr]) -> Vec<Expr> {
+    let mut derived = Vec::new();
+    for expr in exprs.iter().flat_map(|expr| split_conjunction(expr)) {
+        let Some((column, ranges)) = cidr_ranges(metadata, expr) else {
+            continue;
+        };
+        let column_expr = Expr::Column(column);
+        let between = |(first, last): (u128, u128)| {
+            Expr::Between(Between::new(
+                Box::new(column_expr.clone()),
+                false,
+                Box::new(ip_lit(first)),
+                Box::new(ip_lit(last)),
+            ))
+        };
+
+        // The ranges are sorted, so the first and the last one bound all of them.
+        let (lower, upper) = (ranges[0].0, ranges[ranges.len() - 1].1);
+        derived.push(between((lower, upper)));
+        if ranges.len() > 1 {
+            let disjunction = ranges[1..]
+                .iter()
+                .fold(between(ranges[0]), |expr, range| expr.or(between(*range)));
+            derived.push(disjunction);
+        }
+
+        let size = ranges.iter().fold(0u128, |size, (first, last)| {
+            size.saturating_add(last - first + 1)
+        });
+        if size <= MAX_CIDR_IN_LIST_SIZE {
+            let list = ranges
+                .iter()
+                .flat_map(|(first, last)| *first..=*last)
+                .map(ip_lit)
+                .collect();
+            derived.push(Expr::InList(InList::new(
+                Box::new(column_expr),
+                list,
+                false,
+            )));
+        }
+    }
+
+    derived
+}
+
+/// Returns the ip column and the sorted, disjoint ranges of ips that `expr` can be true for,
+/// or `None` if `expr` doesn't restrict a single ip column by CIDR blocks.
+fn cidr_ranges(metadata: &RegionMetadata, expr: &Expr) -> Option<(Column, Vec<(u128, u128)>)> {
+    match expr {
+        Expr::ScalarFunction(func) => {
+            if func.name() != IP_IN_CIDR || func.args.len() != 2 {
+                return None;
+            }
+            let Expr::Column(column) = &func.args[0] else {
+                return None;
+            };
+            let column_meta = metadata.column_by_name(&column.name)?;
+            if !column_meta.column_schema.data_type.is_ip() {
+                return None;
+            }
+            let Expr::Literal(
+                ScalarValue::Utf8(Some(cidr))
+                | ScalarValue::LargeUtf8(Some(cidr))
+                | ScalarValue::Utf8View(Some(cidr)),
+                _,
+            ) = &func.args[1]
+            else {
+                return None;
+            };
+            match parse_cidr_to_ip_type_range(cidr) {
+                Ok((first, last)) => Some((
+                    column.clone(),
+                    vec![(u128::from_be_bytes(first), u128::from_be_bytes(last))],
+                )),
+                Err(e) => {
+                    // The query will fail while evaluating the function, so we don't derive any filter.
+                    debug!("Skip deriving filters from invalid CIDR {cidr}: {e}");
+                    None
+                }
+            }
+        }
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::Or,
+            right,
+        }) => {
+            let (column, mut ranges) = cidr_ranges(metadata, left)?;
+            let (right_column, right_ranges) = cidr_ranges(metadata, right)?;
+            if column != right_column {
+                return None;
+            }
+            ranges.extend(right_ranges);
+            Some((column, merge_ranges(ranges)))
+        }
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::And,
+            right,
+        }) => cidr_ranges(metadata, left).or_else(|| cidr_ranges(metadata, right)),
+        _ => None,
+    }
+}
+
+/// Sorts the ranges and merges the overlapping or adjacent ones.
+fn merge_ranges(mut ranges: Vec<(u128, u128)>) -> Vec<(u128, u128)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(u128, u128)> = Vec::with_capacity(ranges.len());
+    for (first, last) in ranges {
+        match merged.last_mut() {
+            Some((_, prev_last)) if first <= prev_last.saturating_add(1) => {
+                *prev_last = (*prev_last).max(last);
+            }
+            _ => merged.push((first, last)),
+        }
+    }
+    merged
+}
+
+fn ip_lit(value: u128) -> Expr {
+    let value: [u8; IP_BYTE_SIZE] = value.to_be_bytes();
+    Expr::Literal(ScalarValue::Binary(Some(value.to_vec())), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use api::v1::SemanticType;
+    use common_function::function::FunctionRef;
+    use common_function::function_factory::ScalarFunctionFactory;
+    use common_function::scalars::ip::inet::IpInCidr;
+    use datafusion_expr::expr::ScalarFunction;
+    use datafusion_expr::{col, lit};
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::ColumnSchema;
+    use datatypes::types::parse_string_to_ip_type_value;
+    use store_api::metadata::{ColumnMetadata, RegionMetadataBuilder};
+    use store_api::storage::RegionId;
+
+    use super::*;
+
+    fn test_region_metadata() -> RegionMetadata {
+        let mut builder = RegionMetadataBuilder::new(RegionId::new(1234, 5678));
+        builder
+            .push_column_metadata(ColumnMetadata {
+                column_schema: ColumnSchema::new(
+                    "client_ip",
+                    ConcreteDataType::ip_datatype(),
+                    true,
+                ),
+                semantic_type: SemanticType::Field,
+                column_id: 1,
+            })
+            .push_column_metadata(ColumnMetadata {
+                column_schema: ColumnSchema::new(
+                    "binary",
+                    ConcreteDataType::binary_datatype(),
+                    true,
+                ),
+                semantic_type: SemanticType::Field,
+                column_id: 2,
+            })
+            .push_column_metadata(ColumnMetadata {
+                column_schema: ColumnSchema::new(
+                    "ts",
+                    ConcreteDataType::timestamp_millisecond_datatype(),
+                    false,
+                ),
+                semantic_type: SemanticType::Timestamp,
+                column_id: 3,
+            });
+        builder.build().unwrap()
+    }
+
+    fn ip_in_cidr(column: &str, cidr: &str) -> Expr {
+        let func = Arc::new(
+            ScalarFunctionFactory::from(Arc::new(IpInCidr) as FunctionRef)
+                .provide(Default::default()),
+        );
+        Expr::ScalarFunction(ScalarFunction {
+            func,
+            args: vec![Expr::Column(Column::from_name(column)), lit(cidr)],
+        })
+    }
+
+    fn ip(s: &str) -> Expr {
+        Expr::Literal(
+            ScalarValue::Binary(Some(parse_string_to_ip_type_value(s).unwrap())),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_derive_ip_cidr_filters() {
+        let metadata = test_region_metadata();
+
+        let derived = derive_ip_cidr_filters(&metadata, &[ip_in_cidr("client_ip", "10.0.0.0/8")]);
+        assert_eq!(
+            vec![col("client_ip").between(ip("10.0.0.0"), ip("10.255.255.255"))],
+            derived
+        );
+
+        let derived = derive_ip_cidr_filters(
+            &metadata,
+            &[
+                ip_in_cidr("client_ip", "2001:db8::/126"),
+                col("client_ip").is_not_null(),
+            ],
+        );
+        assert_eq!(
+            vec![
+                col("client_ip").between(ip("2001:db8::"), ip("2001:db8::3")),
+                col("client_ip").in_list(
+                    vec![
+                        ip("2001:db8::"),
+                        ip("2001:db8::1"),
+                        ip("2001:db8::2"),
+                        ip("2001:db8::3"),
+                    ],
+                    false
+                ),
+            ],
+            derived
+        );
+
+        let derived = derive_ip_cidr_filters(&metadata, &[ip_in_cidr("client_ip", "10.0.0.1")]);
+        assert_eq!(
+            vec![
+                col("client_ip").between(ip("10.0.0.1"), ip("10.0.0.1")),
+                col("client_ip").in_list(vec![ip("10.0.0.1")], false),
+            ],
+            derived
+        );
+
+        // Not an ip column.
+        assert!(
+            derive_ip_cidr_filters(&metadata, &[ip_in_cidr("binary", "10.0.0.0/8")]).is_empty()
+        );
+        // Nonexistent column.
+        assert!(derive_ip_cidr_filters(&metadata, &[ip_in_cidr("foo", "10.0.0.0/8")]).is_empty());
+        // Invalid CIDR.
+        assert!(
+            derive_ip_cidr_filters(&metadata, &[ip_in_cidr("client_ip", "10.0.0.0/40")]).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_derive_ip_cidr_filters_from_compound_exprs() {
+        let metadata = test_region_metadata();
+
+        // Conjunctions are walked.
+        let derived = derive_ip_cidr_filters(
+            &metadata,
+            &[col("ts")
+                .gt(lit(0i64))
+                .and(ip_in_cidr("client_ip", "10.0.0.0/8"))],
+        );
+        assert_eq!(
+            vec![col("client_ip").between(ip("10.0.0.0"), ip("10.255.255.255"))],
+            derived
+        );
+
+        // Adjacent blocks are merged.
+        let derived = derive_ip_cidr_filters(
+            &metadata,
+            &[ip_in_cidr("client_ip", "10.0.0.4/30").or(ip_in_cidr("client_ip", "10.0.0.0/30"))],
+        );
+        assert_eq!(
+            vec![
+                col("client_ip").between(ip("10.0.0.0"), ip("10.0.0.7")),
+                col("client_ip")
+                    .in_list((0..8).map(|i| ip(&format!("10.0.0.{i}"))).collect(), false),
+            ],
+            derived
+        );
+
+        // Disjoint blocks are bounded together and kept as a disjunction.
+        let derived = derive_ip_cidr_filters(
+            &metadata,
+            &[ip_in_cidr("client_ip", "10.0.1.0/31").or(ip_in_cidr("client_ip", "10.0.0.0/31"))],
+        );
+        assert_eq!(
+            vec![
+                col("client_ip").between(ip("10.0.0.0"), ip("10.0.1.1")),
+                col("client_ip")
+                    .between(ip("10.0.0.0"), ip("10.0.0.1"))
+                    .or(col("client_ip").between(ip("10.0.1.0"), ip("10.0.1.1"))),
+                col("client_ip").in_list(
+                    vec![
+                        ip("10.0.0.0"),
+                        ip("10.0.0.1"),
+                        ip("10.0.1.0"),
+                        ip("10.0.1.1"),
+                    ],
+                    false
+                ),
+            ],
+            derived
+        );
+
+        // A disjunction with other predicates doesn't restrict the ip column.
+        assert!(derive_ip_cidr_filters(
+            &metadata,
+            &[ip_in_cidr("client_ip", "10.0.0.0/8").or(col("ts").gt(lit(0i64)))],
+        )
+        .is_empty());
+    }
+}
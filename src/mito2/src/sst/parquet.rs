@@ -17,6 +17,7 @@
 use std::sync::Arc;
 
 use common_base::readable_size::ReadableSize;
+use datatypes::types::JsonFormat;
 use parquet::file::metadata::ParquetMetaData;
 
 use crate::sst::file::{FileId, FileTimeRange};
@@ -27,6 +28,7 @@ pub(crate) mod file_range;
 pub mod flat_format;
 pub mod format;
 pub(crate) mod helper;
+pub(crate) mod json_shred;
 pub(crate) mod metadata;
 pub mod reader;
 pub mod row_group;
@@ -53,6 +55,8 @@ pub struct WriteOptions {
     /// Note: This is not a hard limit as we can only observe the file size when
     /// ArrowWrite writes to underlying writers.
     pub max_file_size: Option<usize>,
+    /// Format to store JSON columns.
+    pub json_format: JsonFormat,
 }
 
 impl Default for WriteOptions {
@@ -61,6 +65,7 @@ impl Default for WriteOptions {
             write_buffer_size: DEFAULT_WRITE_BUFFER_SIZE,
             row_group_size: DEFAULT_ROW_GROUP_SIZE,
             max_file_size: None,
+            json_format: JsonFormat::Jsonb,
         }
     }
 }
@@ -747,6 +752,7 @@ mod tests {
                 num_row_groups: info.num_row_groups,
                 num_rows: info.num_rows as u64,
                 sequence: None,
+                rollup: None,
             },
             Arc::new(NoopFilePurger),
         );
@@ -0,0 +1,1242 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Shredded JSON columns in parquet SSTs.
+//!
+//! If a region stores JSON in the [JsonFormat::Shredded](datatypes::types::JsonFormat::Shredded)
+//! format, the writer collects [JsonPathStats] from every batch it writes, finds paths frequently
+//! seen in documents of each JSON field and moves their scalar values into typed sub-columns.
+//! The sub-columns of a SST are fixed once the SST is created, so the writer starts a new SST
+//! when more paths become frequent. The JSON column keeps the residual documents.
+//! Sub-columns are appended after internal columns so other columns keep their positions:
+//! ```text
+//! field 0, ..., field N, time index, primary key, sequence, op type, sub-column 0, ..., sub-column M
+//! ```
+//!
+//! The [JsonShredLayout] of a SST is stored in the key value metadata of the file.
+//!
+//! The reader reassembles documents from the residual and the sub-columns. If a query only
+//! accesses shredded paths of a JSON column, the reader skips the residual and builds the
+//! documents from sub-columns. Filters on path accessors are rewritten to read sub-columns
+//! so row groups can be pruned by statistics of sub-columns.
+
+use std::borrow::{Borrow, Cow};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::{Column, ScalarValue};
+use datafusion_expr::expr::Cast;
+use datafusion_expr::Expr;
+use datatypes::arrow::array::{
+    Array, ArrayRef, BinaryArray, BinaryBuilder, BooleanArray, BooleanBuilder, Float64Array,
+    Float64Builder, Int64Array, Int64Builder, StringArray, StringBuilder, UInt64Array,
+    UInt64Builder,
+};
+use datatypes::arrow::datatypes::{
+    DataType as ArrowDataType, Field, FieldRef, Fields, Schema, SchemaRef,
+};
+use datatypes::arrow::record_batch::RecordBatch;
+use jsonb::{Number, Object, Value};
+use parquet::file::metadata::{KeyValue, RowGroupMetaData};
+use parquet::file::statistics::Statistics;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, OptionExt, ResultExt};
+use store_api::metadata::RegionMetadata;
+use store_api::storage::ColumnId;
+
+use crate::error::{
+    InvalidParquetSnafu, InvalidRecordBatchSnafu, NewRecordBatchSnafu, Result, SerdeJsonSnafu,
+};
+
+/// Key of the shredded JSON layout in parquet SST.
+pub(crate) const JSON_SHRED_METADATA_KEY: &str = "greptime:json_shred";
+
+/// Prefix of names of sub-columns.
+const SUB_COLUMN_PREFIX: &str = "__json_";
+/// Minimal ratio of documents containing a path to shred the path.
+const MIN_PATH_RATIO: f64 = 0.5;
+/// Maximum number of paths to shred in a JSON column.
+const MAX_PATHS_PER_COLUMN: usize = 32;
+/// Maximum depth of objects to find paths.
+const MAX_PATH_DEPTH: usize = 8;
+/// Maximum number of distinct paths and types to count in a JSON column.
+const MAX_COUNTED_PATHS: usize = 4096;
+
+/// Paths of JSON columns a query accesses, keyed by column id.
+pub(crate) type JsonPaths = HashMap<ColumnId, Vec<Vec<String>>>;
+
+/// Type of values in a sub-column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ShreddedType {
+    Boolean,
+    Int64,
+    UInt64,
+    Float64,
+    String,
+}
+
+impl ShreddedType {
+    /// Returns the type of the scalar `value`, or `None` if it can't be shredded.
+    fn of(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bool(_) => Some(ShreddedType::Boolean),
+            Value::Number(Number::Int64(_)) => Some(ShreddedType::Int64),
+            Value::Number(Number::UInt64(_)) => Some(ShreddedType::UInt64),
+            Value::Number(Number::Float64(_)) => Some(ShreddedType::Float64),
+            Value::String(_) => Some(ShreddedType::String),
+            _ => None,
+        }
+    }
+
+    fn arrow_type(&self) -> ArrowDataType {
+        match self {
+            ShreddedType::Boolean => ArrowDataType::Boolean,
+            ShreddedType::Int64 => ArrowDataType::Int64,
+            ShreddedType::UInt64 => ArrowDataType::UInt64,
+            ShreddedType::Float64 => ArrowDataType::Float64,
+            ShreddedType::String => ArrowDataType::Utf8,
+        }
+    }
+
+    /// Returns true if the path accessor function `name` returns values of this
+    /// type as they are.
+    fn is_returned_by(&self, name: &str) -> bool {
+        matches!(
+            (self, name),
+            (ShreddedType::Boolean, "json_get_bool")
+                | (ShreddedType::Int64, "json_get_int")
+                | (ShreddedType::UInt64, "json_get_int")
+                | (ShreddedType::Float64, "json_get_float")
+                | (ShreddedType::String, "json_get_string")
+        )
+    }
+}
+
+/// A path shredded into a sub-column.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ShreddedPath {
+    /// Keys of objects from the root of documents to the value.
+    pub(crate) keys: Vec<String>,
+    /// Type of values in the sub-column.
+    pub(crate) data_type: ShreddedType,
+    /// Index of the sub-column in the SST.
+    pub(crate) index: usize,
+    /// Whether all values at the path are in the sub-column. It's false if some
+    /// documents have a value of another type at the path, which is kept in the residual.
+    pub(crate) exact: bool,
+}
+
+/// A JSON column with shredded paths.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ShreddedColumn {
+    pub(crate) column_id: ColumnId,
+    /// Index of the column in the SST.
+    pub(crate) index: usize,
+    pub(crate) paths: Vec<ShreddedPath>,
+}
+
+/// Layout of shredded JSON columns in a SST.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct JsonShredLayout {
+    pub(crate) columns: Vec<ShreddedColumn>,
+}
+
+impl JsonShredLayout {
+    /// Decodes the layout from the key value metadata of a SST whose schema without
+    /// sub-columns has `num_columns` columns.
+    ///
+    /// Returns `None` if the SST has no shredded column.
+    pub(crate) fn decode(
+        file_path: &str,
+        key_value_meta: Option<&Vec<KeyValue>>,
+        num_columns: usize,
+    ) -> Result<Option<Self>> {
+        let Some(value) = key_value_meta
+            .and_then(|key_values| {
+                key_values
+                    .iter()
+                    .find(|kv| kv.key == JSON_SHRED_METADATA_KEY)
+            })
+            .and_then(|kv| kv.value.as_ref())
+        else {
+            return Ok(None);
+        };
+        let layout: JsonShredLayout = serde_json::from_str(value).map_err(|e| {
+            InvalidParquetSnafu {
+                file: file_path,
+                reason: format!("invalid {}: {}", JSON_SHRED_METADATA_KEY, e),
+            }
+            .build()
+        })?;
+
+        // Sub-columns must follow other columns in order.
+        let valid = layout
+            .columns
+            .iter()
+            .all(|column| column.index < num_columns)
+            && layout
+                .paths()
+                .enumerate()
+                .all(|(i, path)| path.index == num_columns + i && !path.keys.is_empty());
+        ensure!(
+            valid,
+            InvalidParquetSnafu {
+                file: file_path,
+                reason: format!(
+                    "{} doesn't match the schema with {} columns",
+                    JSON_SHRED_METADATA_KEY, num_columns
+                ),
+            }
+        );
+
+        Ok(Some(layout))
+    }
+
+    /// Encodes the layout into key value metadata.
+    pub(crate) fn to_key_value(&self) -> Result<KeyValue> {
+        let json = serde_json::to_string(self).context(SerdeJsonSnafu)?;
+        Ok(KeyValue::new(JSON_SHRED_METADATA_KEY.to_string(), json))
+    }
+
+    /// Returns fields of the SST from `fields` without sub-columns.
+    pub(crate) fn file_fields(&self, fields: &Fields) -> Fields {
+        fields
+            .iter()
+            .cloned()
+            .chain(self.paths().map(|path| Arc::new(sub_column_field(path))))
+            .collect()
+    }
+
+    /// Rewrites path accessors of exactly shredded paths in `exprs` to read
+    /// sub-columns, so row groups can be pruned by statistics of sub-columns.
+    ///
+    /// `metadata` resolves names of columns in `exprs`. Returns rewritten exprs and
+    /// fields of sub-columns they read, or `None` if nothing is rewritten.
+    pub(crate) fn rewrite_filters(
+        &self,
+        exprs: &[Expr],
+        metadata: &RegionMetadata,
+    ) -> Option<(Vec<Expr>, Vec<FieldRef>)> {
+        let mut fields = Vec::new();
+        let exprs = exprs
+            .iter()
+            .map(|expr| {
+                expr.clone()
+                    .transform_up(|expr| {
+                        let Some(path) = self.accessed_path(&expr, metadata) else {
+                            return Ok(Transformed::no(expr));
+                        };
+                        let field = sub_column_field(path);
+                        let mut column = Expr::Column(Column::from_name(field.name()));
+                        if path.data_type == ShreddedType::UInt64 {
+                            // `json_get_int` returns unsigned values as signed integers.
+                            column = Expr::Cast(Cast::new(Box::new(column), ArrowDataType::Int64));
+                        }
+                        if !fields.iter().any(|f: &FieldRef| f.name() == field.name()) {
+                            fields.push(Arc::new(field));
+                        }
+                        Ok(Transformed::yes(column))
+                    })
+                    .map(|transformed| transformed.data)
+                    .unwrap_or_else(|_| expr.clone())
+            })
+            .collect();
+
+        (!fields.is_empty()).then_some((exprs, fields))
+    }
+
+    /// Returns the exactly shredded path of a sub-column by its name.
+    pub(crate) fn exact_path_by_name(&self, name: &str) -> Option<&ShreddedPath> {
+        let index = name
+            .strip_prefix(SUB_COLUMN_PREFIX)?
+            .parse::<usize>()
+            .ok()?;
+        self.paths().find(|path| path.index == index && path.exact)
+    }
+
+    /// Returns the path `expr` reads if it's a path accessor of an exactly shredded path.
+    fn accessed_path(&self, expr: &Expr, metadata: &RegionMetadata) -> Option<&ShreddedPath> {
+        let Expr::ScalarFunction(func) = expr else {
+            return None;
+        };
+        let [Expr::Column(column), Expr::Literal(ScalarValue::Utf8(Some(path)), _)] =
+            func.args.as_slice()
+        else {
+            return None;
+        };
+        let column_id = metadata.column_by_name(&column.name)?.column_id;
+        let keys = parse_path(path)?;
+        self.columns
+            .iter()
+            .find(|column| column.column_id == column_id)?
+            .paths
+            .iter()
+            .find(|path| {
+                path.exact && path.keys == keys && path.data_type.is_returned_by(func.name())
+            })
+    }
+
+    fn paths(&self) -> impl Iterator<Item = &ShreddedPath> {
+        self.columns.iter().flat_map(|column| column.paths.iter())
+    }
+}
+
+fn sub_column_field(path: &ShreddedPath) -> Field {
+    Field::new(
+        format!("{}{}", SUB_COLUMN_PREFIX, path.index),
+        path.data_type.arrow_type(),
+        true,
+    )
+}
+
+/// Returns min or max values of the sub-column of `path` in row groups.
+pub(crate) fn sub_column_values(
+    row_groups: &[impl Borrow<RowGroupMetaData>],
+    path: &ShreddedPath,
+    is_min: bool,
+) -> Option<ArrayRef> {
+    let null_scalar: ScalarValue = (&path.data_type.arrow_type()).try_into().ok()?;
+    let scalar_values = row_groups.iter().map(|meta| {
+        let stats = meta.borrow().column(path.index).statistics()?;
+        let value = match (path.data_type, stats) {
+            (ShreddedType::Boolean, Statistics::Boolean(s)) => {
+                ScalarValue::Boolean(Some(*if is_min { s.min_opt() } else { s.max_opt() }?))
+            }
+            (ShreddedType::Int64, Statistics::Int64(s)) => {
+                ScalarValue::Int64(Some(*if is_min { s.min_opt() } else { s.max_opt() }?))
+            }
+            // Parquet stores unsigned integers as signed integers.
+            (ShreddedType::UInt64, Statistics::Int64(s)) => {
+                ScalarValue::UInt64(Some(*if is_min { s.min_opt() } else { s.max_opt() }? as u64))
+            }
+            (ShreddedType::Float64, Statistics::Double(s)) => {
+                ScalarValue::Float64(Some(*if is_min { s.min_opt() } else { s.max_opt() }?))
+            }
+            (ShreddedType::String, Statistics::ByteArray(s)) => {
+                let bytes = if is_min {
+                    s.min_bytes_opt()?
+                } else {
+                    s.max_bytes_opt()?
+                };
+                ScalarValue::Utf8(Some(String::from_utf8(bytes.to_vec()).ok()?))
+            }
+            _ => return None,
+        };
+        Some(value)
+    });
+    let scalar_values = scalar_values
+        .map(|maybe_scalar| maybe_scalar.unwrap_or_else(|| null_scalar.clone()))
+        .collect::<Vec<_>>();
+    ScalarValue::iter_to_array(scalar_values).ok()
+}
+
+/// Parses the path of a path accessor like `a.b` or `$.a.b` into keys.
+///
+/// Returns `None` if the path has anything other than keys of objects.
+pub(crate) fn parse_path(path: &str) -> Option<Vec<String>> {
+    let path = path.strip_prefix('$').unwrap_or(path);
+    let path = path.strip_prefix('.').unwrap_or(path);
+    path.split('.')
+        .map(|key| is_simple_key(key).then(|| key.to_string()))
+        .collect()
+}
+
+/// Returns true if `key` can be accessed by a path without quotes.
+fn is_simple_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Returns ids and indices of JSON fields in the SST of `metadata`.
+pub(crate) fn json_field_columns(metadata: &RegionMetadata) -> Vec<(ColumnId, usize)> {
+    metadata
+        .field_columns()
+        .enumerate()
+        .filter(|(_, column)| column.column_schema.data_type.is_json())
+        .map(|(index, column)| (column.column_id, index))
+        .collect()
+}
+
+/// Counts of scalar paths in JSON documents of JSON columns, collected from every
+/// batch written to SSTs.
+#[derive(Debug, Default)]
+pub(crate) struct JsonPathStats {
+    columns: HashMap<ColumnId, PathCounts>,
+}
+
+impl JsonPathStats {
+    /// Counts paths in documents of `json_columns` in `batch`.
+    ///
+    /// `json_columns` are ids and indices of JSON columns in the batch.
+    pub(crate) fn update(&mut self, json_columns: &[(ColumnId, usize)], batch: &RecordBatch) {
+        for (column_id, index) in json_columns {
+            let Some(array) = batch.column(*index).as_any().downcast_ref::<BinaryArray>() else {
+                continue;
+            };
+            self.columns.entry(*column_id).or_default().update(array);
+        }
+    }
+
+    /// Returns paths to shred in the JSON column `column_id`.
+    fn paths(&self, column_id: ColumnId) -> Vec<(Vec<String>, ShreddedType)> {
+        self.columns
+            .get(&column_id)
+            .map(PathCounts::discover)
+            .unwrap_or_default()
+    }
+
+    /// Returns true if there is a path to shred not in `layout`.
+    pub(crate) fn has_new_paths(&self, layout: Option<&JsonShredLayout>) -> bool {
+        self.columns.keys().any(|column_id| {
+            let shredded = layout.and_then(|layout| {
+                layout
+                    .columns
+                    .iter()
+                    .find(|column| column.column_id == *column_id)
+            });
+            self.paths(*column_id).iter().any(|(keys, data_type)| {
+                !shredded.is_some_and(|column| {
+                    column
+                        .paths
+                        .iter()
+                        .any(|path| path.keys == *keys && path.data_type == *data_type)
+                })
+            })
+        })
+    }
+}
+
+/// Counts of scalar paths with types in documents of a JSON column.
+#[derive(Debug, Default)]
+struct PathCounts {
+    num_docs: usize,
+    counts: HashMap<(Vec<String>, ShreddedType), usize>,
+}
+
+impl PathCounts {
+    fn update(&mut self, array: &BinaryArray) {
+        for bytes in array.iter().flatten() {
+            let Ok(value) = jsonb::from_slice(bytes) else {
+                continue;
+            };
+            self.num_docs += 1;
+            collect_scalar_paths(&value, &mut Vec::new(), &mut self.counts);
+        }
+    }
+
+    /// Finds scalar paths in at least [MIN_PATH_RATIO] of documents.
+    ///
+    /// A path is shredded with the type of most values at the path.
+    fn discover(&self) -> Vec<(Vec<String>, ShreddedType)> {
+        let min_count = ((self.num_docs as f64 * MIN_PATH_RATIO).ceil() as usize).max(1);
+        let mut best: HashMap<&Vec<String>, (ShreddedType, usize)> = HashMap::new();
+        for ((keys, data_type), count) in &self.counts {
+            if *count < min_count {
+                continue;
+            }
+            let entry = best.entry(keys).or_insert((*data_type, *count));
+            if *count > entry.1 || (*count == entry.1 && *data_type < entry.0) {
+                *entry = (*data_type, *count);
+            }
+        }
+        let mut paths = best.into_iter().collect::<Vec<_>>();
+        paths.sort_unstable_by(|(a_keys, (_, a)), (b_keys, (_, b))| {
+            b.cmp(a).then_with(|| a_keys.cmp(b_keys))
+        });
+        paths.truncate(MAX_PATHS_PER_COLUMN);
+        paths
+            .into_iter()
+            .map(|(keys, (data_type, _))| (keys.clone(), data_type))
+            .collect()
+    }
+}
+
+fn collect_scalar_paths(
+    value: &Value,
+    keys: &mut Vec<String>,
+    counts: &mut HashMap<(Vec<String>, ShreddedType), usize>,
+) {
+    let Value::Object(object) = value else {
+        return;
+    };
+    if keys.len() >= MAX_PATH_DEPTH {
+        return;
+    }
+    for (key, value) in object {
+        if !is_simple_key(key) {
+            continue;
+        }
+        keys.push(key.clone());
+        if let Some(data_type) = ShreddedType::of(value) {
+            let key = (keys.clone(), data_type);
+            // Only counts paths seen before once there are too many paths.
+            if let Some(count) = counts.get_mut(&key) {
+                *count += 1;
+            } else if counts.len() < MAX_COUNTED_PATHS {
+                counts.insert(key, 1);
+            }
+        } else {
+            collect_scalar_paths(value, keys, counts);
+        }
+        keys.pop();
+    }
+}
+
+/// Shreds JSON columns of record batches to write into a SST.
+pub(crate) struct JsonShredder {
+    layout: JsonShredLayout,
+    /// Schema of the SST with sub-columns.
+    arrow_schema: SchemaRef,
+}
+
+impl JsonShredder {
+    /// Creates a shredder for `json_columns` with paths to shred in `stats`.
+    ///
+    /// `json_columns` are ids and indices of JSON columns in `sst_schema`.
+    /// Returns `None` if there is no path to shred.
+    pub(crate) fn try_new(
+        json_columns: &[(ColumnId, usize)],
+        sst_schema: &SchemaRef,
+        stats: &JsonPathStats,
+    ) -> Option<Self> {
+        let mut next_index = sst_schema.fields().len();
+        let mut columns = Vec::new();
+        for (column_id, index) in json_columns {
+            let paths = stats
+                .paths(*column_id)
+                .into_iter()
+                .map(|(keys, data_type)| {
+                    next_index += 1;
+                    ShreddedPath {
+                        keys,
+                        data_type,
+                        index: next_index - 1,
+                        exact: true,
+                    }
+                })
+                .collect::<Vec<_>>();
+            if !paths.is_empty() {
+                columns.push(ShreddedColumn {
+                    column_id: *column_id,
+                    index: *index,
+                    paths,
+                });
+            }
+        }
+        if columns.is_empty() {
+            return None;
+        }
+
+        let layout = JsonShredLayout { columns };
+        let arrow_schema = Arc::new(Schema::new_with_metadata(
+            layout.file_fields(sst_schema.fields()),
+            sst_schema.metadata().clone(),
+        ));
+        Some(Self {
+            layout,
+            arrow_schema,
+        })
+    }
+
+    /// Returns the schema of the SST with sub-columns.
+    pub(crate) fn arrow_schema(&self) -> &SchemaRef {
+        &self.arrow_schema
+    }
+
+    /// Returns the layout of values shredded so far.
+    pub(crate) fn layout(&self) -> &JsonShredLayout {
+        &self.layout
+    }
+
+    /// Replaces JSON columns of `batch` with residuals and appends their sub-columns.
+    pub(crate) fn shred(&mut self, batch: &RecordBatch) -> Result<RecordBatch> {
+        let mut columns = batch.columns().to_vec();
+        let mut sub_columns = Vec::new();
+        for column in &mut self.layout.columns {
+            let array = columns[column.index]
+                .as_any()
+                .downcast_ref::<BinaryArray>()
+                .with_context(|| InvalidRecordBatchSnafu {
+                    reason: format!(
+                        "JSON column {} should not be {:?}",
+                        column.column_id,
+                        columns[column.index].data_type()
+                    ),
+                })?;
+            let residual = shred_array(array, &mut column.paths, &mut sub_columns);
+            columns[column.index] = residual;
+        }
+        columns.extend(sub_columns);
+
+        RecordBatch::try_new(self.arrow_schema.clone(), columns).context(NewRecordBatchSnafu)
+    }
+}
+
+/// Moves values at `paths` of documents in `array` into sub-columns.
+/// Returns the residual documents.
+fn shred_array(
+    array: &BinaryArray,
+    paths: &mut [ShreddedPath],
+    sub_columns: &mut Vec<ArrayRef>,
+) -> ArrayRef {
+    let mut residual = BinaryBuilder::with_capacity(array.len(), array.value_data().len());
+    let mut builders = paths
+        .iter()
+        .map(|path| SubColumnBuilder::new(path.data_type, array.len()))
+        .collect::<Vec<_>>();
+    for bytes in array.iter() {
+        let value = bytes.map(|bytes| (bytes, jsonb::from_slice(bytes)));
+        let mut value = match value {
+            Some((_, Ok(value))) => value,
+            Some((bytes, Err(_))) => {
+                residual.append_value(bytes);
+                builders.iter_mut().for_each(SubColumnBuilder::append_null);
+                continue;
+            }
+            None => {
+                residual.append_null();
+                builders.iter_mut().for_each(SubColumnBuilder::append_null);
+                continue;
+            }
+        };
+
+        for (path, builder) in paths.iter_mut().zip(builders.iter_mut()) {
+            match take_value(&mut value, &path.keys, path.data_type) {
+                TakenValue::Value(value) => builder.append(&value),
+                TakenValue::Missing => builder.append_null(),
+                TakenValue::Mismatch => {
+                    path.exact = false;
+                    builder.append_null();
+                }
+            }
+        }
+        residual.append_value(value.to_vec());
+    }
+
+    sub_columns.extend(builders.iter_mut().map(SubColumnBuilder::finish));
+    Arc::new(residual.finish())
+}
+
+/// Result of taking a value from a document.
+enum TakenValue<'a> {
+    Value(Value<'a>),
+    /// The document has no value at the path.
+    Missing,
+    /// The document has a value of another type at the path, or the path goes
+    /// through a value other than an object.
+    Mismatch,
+}
+
+/// Removes the value at `keys` from `doc` if it's of `data_type`.
+fn take_value<'a>(doc: &mut Value<'a>, keys: &[String], data_type: ShreddedType) -> TakenValue<'a> {
+    // Safety: keys of paths are not empty.
+    let (last, parents) = keys.split_last().unwrap();
+    let mut current = doc;
+    for key in parents {
+        current = match current {
+            Value::Object(object) => match object.get_mut(key) {
+                Some(child) => child,
+                None => return TakenValue::Missing,
+            },
+            Value::Null => return TakenValue::Missing,
+            _ => return TakenValue::Mismatch,
+        };
+    }
+    let object = match current {
+        Value::Object(object) => object,
+        Value::Null => return TakenValue::Missing,
+        _ => return TakenValue::Mismatch,
+    };
+    let matched = match object.get(last) {
+        None | Some(Value::Null) => return TakenValue::Missing,
+        Some(value) => ShreddedType::of(value) == Some(data_type),
+    };
+    if matched {
+        // Safety: the key exists.
+        TakenValue::Value(object.remove(last).unwrap())
+    } else {
+        TakenValue::Mismatch
+    }
+}
+
+/// Puts `value` at `keys` into `doc`, creates objects on the path if absent.
+fn put_value<'a>(doc: &mut Value<'a>, keys: &[String], value: Value<'a>) {
+    // Safety: keys of paths are not empty.
+    let (last, parents) = keys.split_last().unwrap();
+    let mut current = doc;
+    for key in parents {
+        current = match current {
+            Value::Object(object) => object
+                .entry(key.clone())
+                .or_insert_with(|| Value::Object(Object::new())),
+            // Values are only shredded from objects.
+            _ => return,
+        };
+    }
+    if let Value::Object(object) = current {
+        object.insert(last.clone(), value);
+    }
+}
+
+enum SubColumnBuilder {
+    Boolean(BooleanBuilder),
+    Int64(Int64Builder),
+    UInt64(UInt64Builder),
+    Float64(Float64Builder),
+    String(StringBuilder),
+}
+
+impl SubColumnBuilder {
+    fn new(data_type: ShreddedType, capacity: usize) -> Self {
+        match data_type {
+            ShreddedType::Boolean => Self::Boolean(BooleanBuilder::with_capacity(capacity)),
+            ShreddedType::Int64 => Self::Int64(Int64Builder::with_capacity(capacity)),
+            ShreddedType::UInt64 => Self::UInt64(UInt64Builder::with_capacity(capacity)),
+            ShreddedType::Float64 => Self::Float64(Float64Builder::with_capacity(capacity)),
+            ShreddedType::String => Self::String(StringBuilder::with_capacity(capacity, 0)),
+        }
+    }
+
+    /// Appends `value`, or a null if `value` isn't of the type of the builder.
+    fn append(&mut self, value: &Value) {
+        match (self, value) {
+            (Self::Boolean(builder), Value::Bool(v)) => builder.append_value(*v),
+            (Self::Int64(builder), Value::Number(Number::Int64(v))) => builder.append_value(*v),
+            (Self::UInt64(builder), Value::Number(Number::UInt64(v))) => builder.append_value(*v),
+            (Self::Float64(builder), Value::Number(Number::Float64(v))) => builder.append_value(*v),
+            (Self::String(builder), Value::String(v)) => builder.append_value(v),
+            (builder, _) => builder.append_null(),
+        }
+    }
+
+    fn append_null(&mut self) {
+        match self {
+            Self::Boolean(builder) => builder.append_null(),
+            Self::Int64(builder) => builder.append_null(),
+            Self::UInt64(builder) => builder.append_null(),
+            Self::Float64(builder) => builder.append_null(),
+            Self::String(builder) => builder.append_null(),
+        }
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        match self {
+            Self::Boolean(builder) => Arc::new(builder.finish()),
+            Self::Int64(builder) => Arc::new(builder.finish()),
+            Self::UInt64(builder) => Arc::new(builder.finish()),
+            Self::Float64(builder) => Arc::new(builder.finish()),
+            Self::String(builder) => Arc::new(builder.finish()),
+        }
+    }
+}
+
+/// A sub-column read from the SST.
+enum SubColumnArray<'a> {
+    Boolean(&'a BooleanArray),
+    Int64(&'a Int64Array),
+    UInt64(&'a UInt64Array),
+    Float64(&'a Float64Array),
+    String(&'a StringArray),
+}
+
+impl<'a> SubColumnArray<'a> {
+    fn try_new(array: &'a ArrayRef, data_type: ShreddedType) -> Result<Self> {
+        let any = array.as_any();
+        let sub_array = match data_type {
+            ShreddedType::Boolean => any.downcast_ref().map(Self::Boolean),
+            ShreddedType::Int64 => any.downcast_ref().map(Self::Int64),
+            ShreddedType::UInt64 => any.downcast_ref().map(Self::UInt64),
+            ShreddedType::Float64 => any.downcast_ref().map(Self::Float64),
+            ShreddedType::String => any.downcast_ref().map(Self::String),
+        };
+        sub_array.with_context(|| InvalidRecordBatchSnafu {
+            reason: format!(
+                "sub-column of {:?} should not be {:?}",
+                data_type,
+                array.data_type()
+            ),
+        })
+    }
+
+    fn value(&self, row: usize) -> Option<Value<'a>> {
+        match self {
+            Self::Boolean(array) => array.is_valid(row).then(|| Value::Bool(array.value(row))),
+            Self::Int64(array) => array
+                .is_valid(row)
+                .then(|| Value::Number(Number::Int64(array.value(row)))),
+            Self::UInt64(array) => array
+                .is_valid(row)
+                .then(|| Value::Number(Number::UInt64(array.value(row)))),
+            Self::Float64(array) => array
+                .is_valid(row)
+                .then(|| Value::Number(Number::Float64(array.value(row)))),
+            Self::String(array) => array
+                .is_valid(row)
+                .then(|| Value::String(Cow::Borrowed(array.value(row)))),
+        }
+    }
+}
+
+/// Column to output when reading a SST with shredded JSON columns.
+#[derive(Debug)]
+enum OutputColumn {
+    /// Column read from the SST at the index.
+    Plain(usize),
+    /// JSON column built from the residual and sub-columns at indices.
+    /// Documents only contain values in sub-columns if the residual is `None`.
+    Json {
+        residual: Option<usize>,
+        paths: Vec<ShreddedPath>,
+    },
+}
+
+/// Projection to read a SST with shredded JSON columns.
+///
+/// It reads the residual and sub-columns of projected JSON columns and rebuilds
+/// record batches in the same schema as SSTs without shredded columns.
+#[derive(Debug)]
+pub(crate) struct JsonShredProjection {
+    /// Sorted indices of columns to read from the SST.
+    read_indices: Vec<usize>,
+    /// Columns to output, indices are positions in record batches read.
+    columns: Vec<OutputColumn>,
+    /// Schema of record batches to output.
+    output_schema: SchemaRef,
+}
+
+impl JsonShredProjection {
+    /// Creates a projection to read `projection_indices` of a SST whose schema without
+    /// sub-columns is `sst_schema`.
+    ///
+    /// A JSON column only reads sub-columns if all of its `json_paths` are exactly shredded.
+    /// Returns `None` if no shredded column is projected.
+    pub(crate) fn new(
+        layout: &JsonShredLayout,
+        sst_schema: &SchemaRef,
+        projection_indices: &[usize],
+        json_paths: Option<&JsonPaths>,
+    ) -> Option<Self> {
+        let mut read_indices = Vec::with_capacity(projection_indices.len());
+        let mut columns = Vec::with_capacity(projection_indices.len());
+        for index in projection_indices {
+            let Some(shredded) = layout.columns.iter().find(|c| c.index == *index) else {
+                read_indices.push(*index);
+                columns.push(OutputColumn::Plain(*index));
+                continue;
+            };
+
+            let accessed_paths = json_paths
+                .and_then(|json_paths| json_paths.get(&shredded.column_id))
+                .and_then(|keys| {
+                    keys.iter()
+                        .map(|keys| {
+                            shredded
+                                .paths
+                                .iter()
+                                .find(|path| path.exact && path.keys == *keys)
+                                .cloned()
+                        })
+                        .collect::<Option<Vec<_>>>()
+                });
+            let (residual, mut paths) = match accessed_paths {
+                Some(paths) => (None, paths),
+                None => {
+                    read_indices.push(*index);
+                    (Some(*index), shredded.paths.clone())
+                }
+            };
+            // Different paths in the hint may have the same keys.
+            paths.sort_unstable_by_key(|path| path.index);
+            paths.dedup_by_key(|path| path.index);
+            read_indices.extend(paths.iter().map(|path| path.index));
+            columns.push(OutputColumn::Json { residual, paths });
+        }
+        if columns
+            .iter()
+            .all(|column| matches!(column, OutputColumn::Plain(_)))
+        {
+            return None;
+        }
+
+        read_indices.sort_unstable();
+        read_indices.dedup();
+        // Converts indices in the SST to positions in record batches read.
+        let position = |index: usize| read_indices.binary_search(&index).unwrap();
+        for column in &mut columns {
+            match column {
+                OutputColumn::Plain(index) => *index = position(*index),
+                OutputColumn::Json { residual, paths } => {
+                    if let Some(index) = residual {
+                        *index = position(*index);
+                    }
+                    for path in paths {
+                        path.index = position(path.index);
+                    }
+                }
+            }
+        }
+        // Safety: projection indices are in the schema.
+        let output_schema = Arc::new(sst_schema.project(projection_indices).unwrap());
+
+        Some(Self {
+            read_indices,
+            columns,
+            output_schema,
+        })
+    }
+
+    /// Returns sorted indices of columns to read from the SST.
+    pub(crate) fn read_indices(&self) -> &[usize] {
+        &self.read_indices
+    }
+
+    /// Rebuilds JSON columns in the record batch read from the SST.
+    pub(crate) fn reassemble(&self, batch: RecordBatch) -> Result<RecordBatch> {
+        let columns = self
+            .columns
+            .iter()
+            .map(|column| match column {
+                OutputColumn::Plain(pos) => Ok(batch.column(*pos).clone()),
+                OutputColumn::Json { residual, paths } => {
+                    reassemble_json(&batch, residual.as_ref(), paths)
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        RecordBatch::try_new(self.output_schema.clone(), columns).context(NewRecordBatchSnafu)
+    }
+}
+
+/// Puts values of sub-columns at `paths` back to documents of the `residual`.
+fn reassemble_json(
+    batch: &RecordBatch,
+    residual: Option<&usize>,
+    paths: &[ShreddedPath],
+) -> Result<ArrayRef> {
+    let residual = residual
+        .map(|pos| {
+            let array = batch.column(*pos);
+            array
+                .as_any()
+                .downcast_ref::<BinaryArray>()
+                .with_context(|| InvalidRecordBatchSnafu {
+                    reason: format!("residual JSON should not be {:?}", array.data_type()),
+                })
+        })
+        .transpose()?;
+    let sub_arrays = paths
+        .iter()
+        .map(|path| SubColumnArray::try_new(batch.column(path.index), path.data_type))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut builder = BinaryBuilder::with_capacity(
+        batch.num_rows(),
+        residual.map(|array| array.value_data().len()).unwrap_or(0),
+    );
+    for row in 0..batch.num_rows() {
+        let mut values = paths
+            .iter()
+            .zip(&sub_arrays)
+            .filter_map(|(path, array)| array.value(row).map(|value| (&path.keys, value)))
+            .peekable();
+        let mut doc = match residual {
+            Some(residual) if residual.is_null(row) => {
+                builder.append_null();
+                continue;
+            }
+            Some(residual) if values.peek().is_none() => {
+                builder.append_value(residual.value(row));
+                continue;
+            }
+            Some(residual) => jsonb::from_slice(residual.value(row)).map_err(|e| {
+                InvalidRecordBatchSnafu {
+                    reason: format!("invalid residual JSON: {}", e),
+                }
+                .build()
+            })?,
+            None => Value::Object(Object::new()),
+        };
+        for (keys, value) in values {
+            put_value(&mut doc, keys, value);
+        }
+        builder.append_value(doc.to_vec());
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+#[cfg(test)]
+mod tests {
+    use common_function::function::FunctionRef;
+    use common_function::function_factory::ScalarFunctionFactory;
+    use common_function::scalars::json::json_get::{JsonGetInt, JsonGetString};
+    use datafusion_expr::expr::ScalarFunction;
+    use datafusion_expr::{col, lit};
+    use datatypes::arrow::array::UInt32Array;
+
+    use super::*;
+    use crate::test_util::sst_util::sst_region_metadata;
+
+    fn new_json_array(docs: &[Option<&str>]) -> ArrayRef {
+        Arc::new(BinaryArray::from_iter(docs.iter().map(|doc| {
+            doc.map(|doc| jsonb::parse_value(doc.as_bytes()).unwrap().to_vec())
+        })))
+    }
+
+    fn new_batch(docs: &[Option<&str>]) -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("j", ArrowDataType::Binary, true),
+            Field::new("v", ArrowDataType::UInt32, true),
+        ]));
+        let values = Arc::new(UInt32Array::from_iter_values(0..docs.len() as u32));
+        RecordBatch::try_new(schema, vec![new_json_array(docs), values]).unwrap()
+    }
+
+    fn new_shredder(batches: &[&RecordBatch]) -> Option<JsonShredder> {
+        let mut stats = JsonPathStats::default();
+        for batch in batches {
+            stats.update(&[(1, 0)], batch);
+        }
+        JsonShredder::try_new(&[(1, 0)], batches[0].schema_ref(), &stats)
+    }
+
+    fn json_strings(array: &ArrayRef) -> Vec<Option<String>> {
+        array
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .unwrap()
+            .iter()
+            .map(|bytes| bytes.map(jsonb::to_string))
+            .collect()
+    }
+
+    const DOCS: [Option<&str>; 4] = [
+        Some(r#"{"a": 1, "b": {"c": "x"}, "d": [1, 2]}"#),
+        Some(r#"{"a": 2, "b": {"c": "y", "e": true}}"#),
+        Some(r#"{"a": "s", "f": 1.5}"#),
+        None,
+    ];
+
+    #[test]
+    fn test_parse_path() {
+        let keys = |keys: &[&str]| Some(keys.iter().map(|k| k.to_string()).collect::<Vec<_>>());
+        assert_eq!(keys(&["a", "b"]), parse_path("$.a.b"));
+        assert_eq!(keys(&["a", "b"]), parse_path("a.b"));
+        assert_eq!(keys(&["_a1"]), parse_path("$._a1"));
+        assert_eq!(None, parse_path("$"));
+        assert_eq!(None, parse_path("$.a[0]"));
+        assert_eq!(None, parse_path("$.a..b"));
+        assert_eq!(None, parse_path("$.\"a b\""));
+    }
+
+    #[test]
+    fn test_shred_and_reassemble() {
+        let batch = new_batch(&DOCS);
+        let mut shredder = new_shredder(&[&batch]).unwrap();
+        let shredded = shredder.shred(&batch).unwrap();
+
+        let layout = shredder.layout().clone();
+        let paths = &layout.columns[0].paths;
+        assert_eq!(2, paths.len());
+        assert_eq!(vec!["a".to_string()], paths[0].keys);
+        assert_eq!(ShreddedType::UInt64, paths[0].data_type);
+        assert_eq!(2, paths[0].index);
+        // A document has a string at `a`.
+        assert!(!paths[0].exact);
+        assert_eq!(vec!["b".to_string(), "c".to_string()], paths[1].keys);
+        assert_eq!(ShreddedType::String, paths[1].data_type);
+        assert_eq!(3, paths[1].index);
+        assert!(paths[1].exact);
+
+        assert_eq!(4, shredded.num_columns());
+        assert_eq!(
+            vec![
+                Some(r#"{"b":{},"d":[1,2]}"#.to_string()),
+                Some(r#"{"b":{"e":true}}"#.to_string()),
+                Some(r#"{"a":"s","f":1.5}"#.to_string()),
+                None,
+            ],
+            json_strings(shredded.column(0))
+        );
+
+        let key_value = layout.to_key_value().unwrap();
+        let decoded = JsonShredLayout::decode("test", Some(&vec![key_value]), 2)
+            .unwrap()
+            .unwrap();
+        assert_eq!(layout, decoded);
+        assert!(JsonShredLayout::decode("test", Some(&vec![]), 2)
+            .unwrap()
+            .is_none());
+        let key_value = layout.to_key_value().unwrap();
+        assert!(JsonShredLayout::decode("test", Some(&vec![key_value]), 3).is_err());
+
+        // Reads all columns.
+        let projection =
+            JsonShredProjection::new(&layout, batch.schema_ref(), &[0, 1], None).unwrap();
+        assert_eq!(&[0, 1, 2, 3], projection.read_indices());
+        let reassembled = projection.reassemble(shredded.clone()).unwrap();
+        assert_eq!(batch.schema(), reassembled.schema());
+        assert_eq!(
+            json_strings(batch.column(0)),
+            json_strings(reassembled.column(0))
+        );
+        assert_eq!(batch.column(1), reassembled.column(1));
+
+        // Doesn't read shredded columns.
+        assert!(JsonShredProjection::new(&layout, batch.schema_ref(), &[1], None).is_none());
+
+        // Only reads the sub-column of an exact path.
+        let json_paths = JsonPaths::from([(1, vec![vec!["b".to_string(), "c".to_string()]])]);
+        let projection =
+            JsonShredProjection::new(&layout, batch.schema_ref(), &[0, 1], Some(&json_paths))
+                .unwrap();
+        assert_eq!(&[1, 3], projection.read_indices());
+        let read = RecordBatch::try_new(
+            Arc::new(shredded.schema().project(&[1, 3]).unwrap()),
+            vec![shredded.column(1).clone(), shredded.column(3).clone()],
+        )
+        .unwrap();
+        let reassembled = projection.reassemble(read).unwrap();
+        assert_eq!(
+            vec![
+                Some(r#"{"b":{"c":"x"}}"#.to_string()),
+                Some(r#"{"b":{"c":"y"}}"#.to_string()),
+                Some("{}".to_string()),
+                Some("{}".to_string()),
+            ],
+            json_strings(reassembled.column(0))
+        );
+
+        // Reads the residual if a path is not exact.
+        let json_paths = JsonPaths::from([(1, vec![vec!["a".to_string()]])]);
+        let projection =
+            JsonShredProjection::new(&layout, batch.schema_ref(), &[0, 1], Some(&json_paths))
+                .unwrap();
+        assert_eq!(&[0, 1, 2, 3], projection.read_indices());
+    }
+
+    #[test]
+    fn test_no_path_to_shred() {
+        let batch = new_batch(&[Some("[1, 2]"), Some(r#"{"a": 1}"#), Some(r#"{"b": 1}"#)]);
+        assert!(new_shredder(&[&batch]).is_none());
+        let batch = new_batch(&[None, None]);
+        assert!(new_shredder(&[&batch]).is_none());
+    }
+
+    #[test]
+    fn test_discover_paths_from_every_batch() {
+        let first = new_batch(&[Some(r#"{"a": 1}"#)]);
+        let rest = new_batch(&[
+            Some(r#"{"b": "x"}"#),
+            Some(r#"{"b": "y"}"#),
+            Some(r#"{"a": 2, "b": "z"}"#),
+        ]);
+        let keys = |shredder: &JsonShredder| {
+            shredder.layout().columns[0]
+                .paths
+                .iter()
+                .map(|path| path.keys.join("."))
+                .collect::<Vec<_>>()
+        };
+
+        let shredder = new_shredder(&[&first]).unwrap();
+        assert_eq!(vec!["a"], keys(&shredder));
+
+        // `b` is in 3 of 4 documents, `a` in 2 of 4.
+        let mut stats = JsonPathStats::default();
+        stats.update(&[(1, 0)], &first);
+        assert!(!stats.has_new_paths(Some(shredder.layout())));
+        stats.update(&[(1, 0)], &rest);
+        assert!(stats.has_new_paths(Some(shredder.layout())));
+        assert!(stats.has_new_paths(None));
+
+        let shredder = JsonShredder::try_new(&[(1, 0)], first.schema_ref(), &stats).unwrap();
+        assert_eq!(vec!["b", "a"], keys(&shredder));
+        assert!(!stats.has_new_paths(Some(shredder.layout())));
+    }
+
+    fn accessor(func: FunctionRef, column: &str, path: &str) -> Expr {
+        let udf = ScalarFunctionFactory::from(func).provide(Default::default());
+        Expr::ScalarFunction(ScalarFunction::new_udf(
+            Arc::new(udf),
+            vec![col(column), lit(path)],
+        ))
+    }
+
+    #[test]
+    fn test_rewrite_filters() {
+        let metadata = sst_region_metadata();
+        let field_id = metadata.field_columns().next().unwrap().column_id;
+        let field_name = metadata
+            .field_columns()
+            .next()
+            .unwrap()
+            .column_schema
+            .name
+            .clone();
+        let layout = JsonShredLayout {
+            columns: vec![ShreddedColumn {
+                column_id: field_id,
+                index: 0,
+                paths: vec![
+                    ShreddedPath {
+                        keys: vec!["a".to_string()],
+                        data_type: ShreddedType::UInt64,
+                        index: 5,
+                        exact: true,
+                    },
+                    ShreddedPath {
+                        keys: vec!["b".to_string()],
+                        data_type: ShreddedType::String,
+                        index: 6,
+                        exact: false,
+                    },
+                ],
+            }],
+        };
+
+        let exprs = vec![
+            accessor(Arc::new(JsonGetInt), &field_name, "$.a").gt(lit(10i64)),
+            accessor(Arc::new(JsonGetString), &field_name, "b").eq(lit("x")),
+            accessor(Arc::new(JsonGetString), &field_name, "a").eq(lit("x")),
+        ];
+        let (rewritten, fields) = layout.rewrite_filters(&exprs, &metadata).unwrap();
+        assert_eq!(
+            vec![Arc::new(Field::new(
+                "__json_5",
+                ArrowDataType::UInt64,
+                true
+            ))],
+            fields
+        );
+        assert_eq!(
+            Expr::Cast(Cast::new(Box::new(col("__json_5")), ArrowDataType::Int64)).gt(lit(10i64)),
+            rewritten[0]
+        );
+        // Not exact.
+        assert_eq!(exprs[1], rewritten[1]);
+        // Type mismatch.
+        assert_eq!(exprs[2], rewritten[2]);
+        assert!(layout.rewrite_filters(&exprs[1..], &metadata).is_none());
+
+        assert!(layout.exact_path_by_name("__json_5").is_some());
+        assert!(layout.exact_path_by_name("__json_6").is_none());
+        assert!(layout.exact_path_by_name("__json_7").is_none());
+    }
+}
@@ -24,6 +24,7 @@ use common_recordbatch::filter::SimpleFilterEvaluator;
 use common_telemetry::{debug, warn};
 use datafusion_expr::Expr;
 use datatypes::arrow::array::ArrayRef;
+use datatypes::arrow::datatypes::{Fields, Schema};
 use datatypes::arrow::error::ArrowError;
 use datatypes::arrow::record_batch::RecordBatch;
 use datatypes::data_type::ConcreteDataType;
@@ -57,6 +58,7 @@ use crate::sst::index::fulltext_index::applier::FulltextIndexApplierRef;
 use crate::sst::index::inverted_index::applier::InvertedIndexApplierRef;
 use crate::sst::parquet::file_range::{FileRangeContext, FileRangeContextRef};
 use crate::sst::parquet::format::{need_override_sequence, PrimaryKeyReadFormat, ReadFormat};
+use crate::sst::parquet::json_shred::{JsonPaths, JsonShredLayout, JsonShredProjection};
 use crate::sst::parquet::metadata::MetadataLoader;
 use crate::sst::parquet::row_group::InMemoryRowGroup;
 use crate::sst::parquet::row_selection::RowGroupSelection;
@@ -113,6 +115,8 @@ pub struct ParquetReaderBuilder {
     /// This is usually the latest metadata of the region. The reader use
     /// it get the correct column id of a column by name.
     expected_metadata: Option<RegionMetadataRef>,
+    /// Paths of JSON columns to read.
+    json_paths: Option<Arc<JsonPaths>>,
 }
 
 impl ParquetReaderBuilder {
@@ -135,6 +139,7 @@ impl ParquetReaderBuilder {
             bloom_filter_index_applier: None,
             fulltext_index_applier: None,
             expected_metadata: None,
+            json_paths: None,
         }
     }
 
@@ -198,6 +203,16 @@ impl ParquetReaderBuilder {
         self
     }
 
+    /// Attaches paths of JSON columns the query accesses to the builder.
+    ///
+    /// The reader only reads sub-columns of a shredded JSON column if all its
+    /// paths are exactly shredded. The returned documents only contain these paths.
+    #[must_use]
+    pub(crate) fn json_paths(mut self, json_paths: Option<Arc<JsonPaths>>) -> Self {
+        self.json_paths = json_paths;
+        self
+    }
+
     /// Builds a [ParquetReader].
     ///
     /// This needs to perform IO operation.
@@ -245,20 +260,43 @@ impl ParquetReaderBuilder {
         }
         let read_format = ReadFormat::PrimaryKey(read_format);
 
+        let sst_schema = read_format.arrow_schema();
+        let json_shred =
+            JsonShredLayout::decode(&file_path, key_value_meta, sst_schema.fields().len())?;
+        let json_projection = json_shred.as_ref().and_then(|layout| {
+            JsonShredProjection::new(
+                layout,
+                sst_schema,
+                read_format.projection_indices(),
+                self.json_paths.as_deref(),
+            )
+        });
+
         // Computes the projection mask.
         let parquet_schema_desc = parquet_meta.file_metadata().schema_descr();
-        let indices = read_format.projection_indices();
+        let indices = json_projection
+            .as_ref()
+            .map(|projection| projection.read_indices())
+            .unwrap_or_else(|| read_format.projection_indices());
         // Now we assumes we don't have nested schemas.
-        // TODO(yingwen): Revisit this if we introduce nested types such as JSON type.
+        // Shredded JSON columns are stored as primitive sub-columns.
         let projection_mask = ProjectionMask::roots(parquet_schema_desc, indices.iter().copied());
 
         // Computes the field levels.
-        let hint = Some(read_format.arrow_schema().fields());
+        let file_fields = json_shred
+            .as_ref()
+            .map(|layout| layout.file_fields(sst_schema.fields()));
+        let hint = Some(file_fields.as_ref().unwrap_or(sst_schema.fields()));
         let field_levels =
             parquet_to_arrow_field_levels(parquet_schema_desc, projection_mask.clone(), hint)
                 .context(ReadDataPartSnafu)?;
         let selection = self
-            .row_groups_to_read(&read_format, &parquet_meta, &mut metrics.filter_metrics)
+            .row_groups_to_read(
+                &read_format,
+                &parquet_meta,
+                json_shred.as_ref(),
+                &mut metrics.filter_metrics,
+            )
             .await;
 
         let reader_builder = RowGroupReaderBuilder {
@@ -268,6 +306,7 @@ impl ParquetReaderBuilder {
             object_store: self.object_store.clone(),
             projection: projection_mask,
             field_levels,
+            json_projection,
             cache_strategy: self.cache_strategy.clone(),
         };
 
@@ -355,6 +394,7 @@ impl ParquetReaderBuilder {
         &self,
         read_format: &ReadFormat,
         parquet_meta: &ParquetMetaData,
+        json_shred: Option<&JsonShredLayout>,
         metrics: &mut ReaderFilterMetrics,
     ) -> RowGroupSelection {
         let num_row_groups = parquet_meta.num_row_groups();
@@ -375,7 +415,13 @@ impl ParquetReaderBuilder {
 
         let mut output = RowGroupSelection::new(row_group_size, num_rows as _);
 
-        self.prune_row_groups_by_minmax(read_format, parquet_meta, &mut output, metrics);
+        self.prune_row_groups_by_minmax(
+            read_format,
+            parquet_meta,
+            json_shred,
+            &mut output,
+            metrics,
+        );
         if output.is_empty() {
             return output;
         }
@@ -676,6 +722,7 @@ impl ParquetReaderBuilder {
         &self,
         read_format: &ReadFormat,
         parquet_meta: &ParquetMetaData,
+        json_shred: Option<&JsonShredLayout>,
         output: &mut RowGroupSelection,
         metrics: &mut ReaderFilterMetrics,
     ) -> bool {
@@ -688,13 +735,34 @@ impl ParquetReaderBuilder {
         let region_meta = read_format.metadata();
         let row_groups = parquet_meta.row_groups();
         let stats =
-            RowGroupPruningStats::new(row_groups, read_format, self.expected_metadata.clone());
+            RowGroupPruningStats::new(row_groups, read_format, self.expected_metadata.clone())
+                .with_json_shred(json_shred);
         let prune_schema = self
             .expected_metadata
             .as_ref()
             .map(|meta| meta.schema.arrow_schema())
             .unwrap_or_else(|| region_meta.schema.arrow_schema());
 
+        // Rewrites path accessors of shredded JSON columns to read sub-columns.
+        let rewritten = json_shred.and_then(|layout| {
+            let metadata = self.expected_metadata.as_ref().unwrap_or(region_meta);
+            let (exprs, fields) = layout.rewrite_filters(predicate.exprs(), metadata)?;
+            let schema = Schema::new_with_metadata(
+                prune_schema
+                    .fields()
+                    .iter()
+                    .cloned()
+                    .chain(fields)
+                    .collect::<Fields>(),
+                prune_schema.metadata().clone(),
+            );
+            Some((Predicate::new(exprs), Arc::new(schema)))
+        });
+        let (predicate, prune_schema) = match &rewritten {
+            Some((predicate, schema)) => (predicate, schema),
+            None => (predicate, prune_schema),
+        };
+
         // Here we use the schema of the SST to build the physical expression. If the column
         // in the SST doesn't have the same column id as the column in the expected metadata,
         // we will get a None statistics for that column.
@@ -907,6 +975,8 @@ pub(crate) struct RowGroupReaderBuilder {
     projection: ProjectionMask,
     /// Field levels to read.
     field_levels: FieldLevels,
+    /// Projection to rebuild shredded JSON columns.
+    json_projection: Option<JsonShredProjection>,
     /// Cache.
     cache_strategy: CacheStrategy,
 }
@@ -930,6 +1000,14 @@ impl RowGroupReaderBuilder {
         &self.cache_strategy
     }
 
+    /// Rebuilds shredded JSON columns in the record batch read from the SST.
+    pub(crate) fn reassemble_json(&self, record_batch: RecordBatch) -> Result<RecordBatch> {
+        match &self.json_projection {
+            Some(projection) => projection.reassemble(record_batch),
+            None => Ok(record_batch),
+        }
+    }
+
     /// Builds a [ParquetRecordBatchReader] to read the row group at `row_group_idx`.
     pub(crate) async fn build(
         &self,
@@ -1217,9 +1295,12 @@ impl RowGroupReaderContext for FileRangeContextRef {
         &self,
         result: std::result::Result<Option<RecordBatch>, ArrowError>,
     ) -> Result<Option<RecordBatch>> {
-        result.context(ArrowReaderSnafu {
+        let record_batch = result.context(ArrowReaderSnafu {
             path: self.file_path(),
-        })
+        })?;
+        record_batch
+            .map(|batch| self.reader_builder().reassemble_json(batch))
+            .transpose()
     }
 
     fn read_format(&self) -> &ReadFormat {
@@ -1365,6 +1446,10 @@ impl FlatRowGroupReader {
                 let record_batch = batch_result.context(ArrowReaderSnafu {
                     path: self.context.file_path(),
                 })?;
+                let record_batch = self
+                    .context
+                    .reader_builder()
+                    .reassemble_json(record_batch)?;
 
                 // Apply override sequence if needed
                 if let (Some(flat_format), Some(override_array)) = (
@@ -26,6 +26,7 @@ use store_api::metadata::RegionMetadataRef;
 use store_api::storage::ColumnId;
 
 use crate::sst::parquet::format::{ReadFormat, StatValues};
+use crate::sst::parquet::json_shred::{sub_column_values, JsonShredLayout, ShreddedPath};
 
 /// Statistics for pruning row groups.
 pub(crate) struct RowGroupPruningStats<'a, T> {
@@ -38,6 +39,8 @@ pub(crate) struct RowGroupPruningStats<'a, T> {
     /// of the metadata in the SST to get the column id of a column as the SST may have
     /// different columns.
     expected_metadata: Option<RegionMetadataRef>,
+    /// Layout of shredded JSON columns in the SST.
+    json_shred: Option<&'a JsonShredLayout>,
 }
 
 impl<'a, T> RowGroupPruningStats<'a, T> {
@@ -51,9 +54,22 @@ impl<'a, T> RowGroupPruningStats<'a, T> {
             row_groups,
             read_format,
             expected_metadata,
+            json_shred: None,
         }
     }
 
+    /// Attaches the layout of shredded JSON columns so filters on sub-columns
+    /// can be pruned.
+    pub(crate) fn with_json_shred(mut self, json_shred: Option<&'a JsonShredLayout>) -> Self {
+        self.json_shred = json_shred;
+        self
+    }
+
+    /// Returns the exactly shredded path of the sub-column `name`.
+    fn shredded_path(&self, name: &str) -> Option<&'a ShreddedPath> {
+        self.json_shred?.exact_path_by_name(name)
+    }
+
     /// Returns the column id of specific column name if we need to read it.
     /// Prefers the column id in the expected metadata if it exists.
     fn column_id_to_prune(&self, name: &str) -> Option<ColumnId> {
@@ -98,6 +114,9 @@ impl<T: Borrow<RowGroupMetaData>> RowGroupPruningStats<'_, T> {
 
 impl<T: Borrow<RowGroupMetaData>> PruningStatistics for RowGroupPruningStats<'_, T> {
     fn min_values(&self, column: &Column) -> Option<ArrayRef> {
+        if let Some(path) = self.shredded_path(&column.name) {
+            return sub_column_values(self.row_groups, path, true);
+        }
         let column_id = self.column_id_to_prune(&column.name)?;
         match self.read_format.min_values(self.row_groups, column_id) {
             StatValues::Values(values) => Some(values),
@@ -107,6 +126,9 @@ impl<T: Borrow<RowGroupMetaData>> PruningStatistics for RowGroupPruningStats<'_,
     }
 
     fn max_values(&self, column: &Column) -> Option<ArrayRef> {
+        if let Some(path) = self.shredded_path(&column.name) {
+            return sub_column_values(self.row_groups, path, false);
+        }
         let column_id = self.column_id_to_prune(&column.name)?;
         match self.read_format.max_values(self.row_groups, column_id) {
             StatValues::Values(values) => Some(values),
@@ -120,6 +142,9 @@ impl<T: Borrow<RowGroupMetaData>> PruningStatistics for RowGroupPruningStats<'_,
     }
 
     fn null_counts(&self, column: &Column) -> Option<ArrayRef> {
+        if let Some(path) = self.shredded_path(&column.name) {
+            return ReadFormat::column_null_counts(self.row_groups, path.index);
+        }
         let column_id = self.column_id_to_prune(&column.name)?;
         match self.read_format.null_counts(self.row_groups, column_id) {
             StatValues::Values(values) => Some(values),
@@ -25,6 +25,7 @@ use std::time::Instant;
 use common_telemetry::debug;
 use common_time::Timestamp;
 use datatypes::arrow::datatypes::SchemaRef;
+use datatypes::types::JsonFormat;
 use object_store::{FuturesAsyncWriter, ObjectStore};
 use parquet::arrow::AsyncArrowWriter;
 use parquet::basic::{Compression, Encoding, ZstdLevel};
@@ -46,6 +47,7 @@ use crate::sst::file::{FileId, RegionFileId};
 use crate::sst::index::{Indexer, IndexerBuilder};
 use crate::sst::parquet::format::PrimaryKeyWriteFormat;
 use crate::sst::parquet::helper::parse_parquet_metadata;
+use crate::sst::parquet::json_shred::{json_field_columns, JsonPathStats, JsonShredder};
 use crate::sst::parquet::{SstInfo, WriteOptions, PARQUET_METADATA_KEY};
 use crate::sst::{DEFAULT_WRITE_BUFFER_SIZE, DEFAULT_WRITE_CONCURRENCY};
 
@@ -68,6 +70,10 @@ pub struct ParquetWriter<F: WriterFactory, I: IndexerBuilder, P: FilePathProvide
     file_cleaner: Option<TempFileCleaner>,
     /// Write metrics
     metrics: Metrics,
+    /// Shredder of JSON columns in the current file.
+    json_shredder: Option<JsonShredder>,
+    /// Paths in JSON columns of all batches written by the writer.
+    json_stats: JsonPathStats,
 }
 
 pub trait WriterFactory {
@@ -149,6 +155,8 @@ where
             bytes_written: Arc::new(AtomicUsize::new(0)),
             file_cleaner: None,
             metrics,
+            json_shredder: None,
+            json_stats: JsonPathStats::default(),
         }
     }
 
@@ -174,6 +182,11 @@ where
             // Finish indexer and writer.
             // safety: writer and index can only be both present or not.
             let index_output = self.current_indexer.as_mut().unwrap().finish().await;
+            // Values of some paths may have mismatched types, so we store the layout
+            // after all batches are shredded.
+            if let Some(shredder) = self.json_shredder.take() {
+                current_writer.append_key_value_metadata(shredder.layout().to_key_value()?);
+            }
             current_writer.flush().await.context(WriteParquetSnafu)?;
 
             let file_meta = current_writer.close().await.context(WriteParquetSnafu)?;
@@ -253,6 +266,9 @@ where
                         && self.bytes_written.load(Ordering::Relaxed) > max_file_size
                     {
                         self.finish_current_file(&mut results, &mut stats).await?;
+                    } else if self.json_layout_outdated(opts, stats.num_rows, batch.num_rows()) {
+                        // Starts a new file to shred paths found after creating the current file.
+                        self.finish_current_file(&mut results, &mut stats).await?;
                     }
                 }
                 Err(e) => {
@@ -270,6 +286,28 @@ where
         Ok(results)
     }
 
+    /// Returns true if the current file has finished a row group after writing a batch of
+    /// `batch_rows` rows and some frequent JSON paths are not shredded in the file.
+    fn json_layout_outdated(
+        &self,
+        opts: &WriteOptions,
+        num_rows: usize,
+        batch_rows: usize,
+    ) -> bool {
+        if opts.json_format != JsonFormat::Shredded {
+            return false;
+        }
+        let row_group_size = opts.row_group_size.max(1);
+        if (num_rows - batch_rows) / row_group_size == num_rows / row_group_size {
+            return false;
+        }
+        self.json_stats.has_new_paths(
+            self.json_shredder
+                .as_ref()
+                .map(|shredder| shredder.layout()),
+        )
+    }
+
     /// Customizes per-column config according to schema and maybe column cardinality.
     fn customize_column_config(
         builder: WriterPropertiesBuilder,
@@ -301,10 +339,29 @@ where
         };
         self.metrics.iter_source += start.elapsed();
 
-        let arrow_batch = write_format.convert_batch(&batch)?;
+        let mut arrow_batch = write_format.convert_batch(&batch)?;
+        if opts.json_format == JsonFormat::Shredded {
+            let json_columns = json_field_columns(&self.metadata);
+            self.json_stats.update(&json_columns, &arrow_batch);
+            if self.writer.is_none() {
+                // Shreds paths found in all batches so far in the new file.
+                self.json_shredder = JsonShredder::try_new(
+                    &json_columns,
+                    write_format.arrow_schema(),
+                    &self.json_stats,
+                );
+            }
+        }
+        let mut schema = write_format.arrow_schema();
+        if let Some(shredder) = &mut self.json_shredder {
+            arrow_batch = shredder.shred(&arrow_batch)?;
+            schema = shredder.arrow_schema();
+        }
+        // Clones the schema as initializing the writer borrows self mutably.
+        let schema = schema.clone();
 
         let start = Instant::now();
-        self.maybe_init_writer(write_format.arrow_schema(), opts)
+        self.maybe_init_writer(&schema, opts)
             .await?
             .write(&arrow_batch)
             .await
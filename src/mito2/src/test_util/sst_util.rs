@@ -128,6 +128,7 @@ pub fn sst_file_handle_with_file_id(file_id: FileId, start_ms: i64, end_ms: i64)
             num_rows: 0,
             num_row_groups: 0,
             sequence: None,
+            rollup: None,
         },
         file_purger,
     )
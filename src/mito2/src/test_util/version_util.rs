@@ -106,6 +106,7 @@ impl VersionControlBuilder {
                 num_rows: 0,
                 num_row_groups: 0,
                 sequence: NonZeroU64::new(start_ms as u64),
+                rollup: None,
             },
         );
         self
@@ -189,6 +190,7 @@ pub(crate) fn apply_edit(
                 num_rows: 0,
                 num_row_groups: 0,
                 sequence: NonZeroU64::new(*start_ms as u64),
+                rollup: None,
             }
         })
         .collect();
@@ -177,7 +177,9 @@ impl<S> RegionWorkerLoop<S> {
                     current_options.ttl = new_ttl;
                 }
                 SetRegionOption::Twsc(key, value) => {
-                    let Twcs(options) = &mut current_options.compaction;
+                    let Twcs(options) = &mut current_options.compaction else {
+                        return InvalidSetRegionOptionRequestSnafu { key, value }.fail();
+                    };
                     set_twcs_options(
                         options,
                         &TwcsOptions::default(),
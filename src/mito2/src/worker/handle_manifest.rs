@@ -261,10 +261,20 @@ impl<S> RegionWorkerLoop<S> {
             edit_result.result.is_ok() && !edit_result.edit.files_to_add.is_empty();
 
         if edit_result.result.is_ok() {
+            let flushed_sequence = edit_result.edit.flushed_sequence;
             // Applies the edit to the region.
             region
                 .version_control
                 .apply_edit(edit_result.edit, &[], region.file_purger.clone());
+            // The added files may contain rows with larger sequences, e.g., files restored from a backup.
+            if let Some(sequence) = flushed_sequence {
+                let data = region.version_control.current();
+                if sequence > data.committed_sequence {
+                    region
+                        .version_control
+                        .set_sequence_and_entry_id(sequence, data.last_entry_id);
+                }
+            }
         }
 
         // Sets the region as writable.
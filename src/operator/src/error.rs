@@ -23,6 +23,7 @@ use datafusion::parquet;
 use datafusion_common::DataFusionError;
 use datatypes::arrow::error::ArrowError;
 use snafu::{Location, Snafu};
+use store_api::storage::RegionId;
 use table::metadata::TableType;
 
 #[derive(Snafu)]
@@ -222,6 +223,13 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Invalid UPDATE statement, reason: {}", reason))]
+    InvalidUpdate {
+        reason: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
     #[snafu(display("Table not found: {}", table_name))]
     TableNotFound { table_name: String },
 
@@ -617,6 +625,39 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Invalid backup, reason: {}", reason))]
+    InvalidBackup {
+        reason: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Failed to backup or restore region {}", region_id))]
+    BackupRegion {
+        region_id: RegionId,
+        #[snafu(implicit)]
+        location: Location,
+        source: common_meta::error::Error,
+    },
+
+    #[snafu(display("Failed to write object to path: {}", path))]
+    WriteObject {
+        path: String,
+        #[snafu(implicit)]
+        location: Location,
+        #[snafu(source)]
+        error: object_store::Error,
+    },
+
+    #[snafu(display("Failed to decode backup manifest in path: {}", path))]
+    DecodeBackupManifest {
+        path: String,
+        #[snafu(implicit)]
+        location: Location,
+        #[snafu(source)]
+        error: serde_json::error::Error,
+    },
+
     #[snafu(display("Table metadata manager error"))]
     TableMetadataManager {
         source: common_meta::error::Error,
@@ -624,6 +665,13 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Access control error"))]
+    AccessControl {
+        source: common_meta::error::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
     #[snafu(display("Missing insert body"))]
     MissingInsertBody {
         source: sql::error::Error,
@@ -874,6 +922,7 @@ impl ErrorExt for Error {
             | Error::InvalidConfigValue { .. }
             | Error::InvalidInsertRequest { .. }
             | Error::InvalidDeleteRequest { .. }
+            | Error::InvalidUpdate { .. }
             | Error::IllegalPrimaryKeysDef { .. }
             | Error::SchemaNotFound { .. }
             | Error::SchemaExists { .. }
@@ -917,7 +966,9 @@ impl ErrorExt for Error {
             Error::NotSupported { .. }
             | Error::ShowCreateTableBaseOnly { .. }
             | Error::SchemaReadOnly { .. } => StatusCode::Unsupported,
-            Error::TableMetadataManager { source, .. } => source.status_code(),
+            Error::TableMetadataManager { source, .. } | Error::AccessControl { source, .. } => {
+                source.status_code()
+            }
             Error::ParseSql { source, .. } => source.status_code(),
             Error::InvalidateTableCache { source, .. } => source.status_code(),
             Error::ParseFileFormat { source, .. } | Error::InferSchema { source, .. } => {
@@ -977,6 +1028,10 @@ impl ErrorExt for Error {
             Error::InvalidCopyParameter { .. } | Error::InvalidCopyDatabasePath { .. } => {
                 StatusCode::InvalidArguments
             }
+            Error::InvalidBackup { .. } => StatusCode::InvalidArguments,
+            Error::BackupRegion { source, .. } => source.status_code(),
+            Error::WriteObject { .. } => StatusCode::StorageUnavailable,
+            Error::DecodeBackupManifest { .. } => StatusCode::Unexpected,
             Error::ColumnDefaultValue { source, .. } => source.status_code(),
             Error::EmptyDdlExpr { .. }
             | Error::InvalidPartitionRule { .. }
@@ -512,6 +512,13 @@ pub(crate) fn to_alter_table_expr(
             }
             .fail();
         }
+        AlterTableOperation::SplitPartition { .. } | AlterTableOperation::MergePartition { .. } => {
+            // Repartition is submitted as a dedicated DDL task instead of an alter table expr.
+            return NotSupportedSnafu {
+                feat: "SPLIT or MERGE PARTITION in alter table expr",
+            }
+            .fail();
+        }
         AlterTableOperation::AddColumns { add_columns } => AlterTableKind::AddColumns(AddColumns {
             add_columns: add_columns
                 .into_iter()
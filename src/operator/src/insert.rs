@@ -15,6 +15,7 @@
 use std::sync::Arc;
 
 use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
+use api::helper::pb_value_to_value_ref;
 use api::v1::alter_table_expr::Kind;
 use api::v1::column_def::options_from_skipping;
 use api::v1::region::{
@@ -39,7 +40,9 @@ use common_query::prelude::{GREPTIME_TIMESTAMP, GREPTIME_VALUE};
 use common_query::Output;
 use common_telemetry::tracing_context::TracingContext;
 use common_telemetry::{error, info, warn};
+use common_time::timestamp::TimeUnit;
 use datatypes::schema::SkippingIndexOptions;
+use datatypes::value::ValueRef;
 use futures_util::future;
 use meter_macros::write_meter;
 use partition::manager::PartitionRuleManagerRef;
@@ -81,10 +84,21 @@ pub struct Inserter {
     pub(crate) partition_manager: PartitionRuleManagerRef,
     pub(crate) node_manager: NodeManagerRef,
     pub(crate) table_flownode_set_cache: TableFlownodeSetCacheRef,
+    insert_listener: Option<InsertListenerRef>,
 }
 
 pub type InserterRef = Arc<Inserter>;
 
+/// Listener of the rows written by the [Inserter].
+pub trait InsertListener: Send + Sync {
+    /// Called after rows are written to the `table`.
+    ///
+    /// `min_timestamp` is the minimum timestamp of the written rows in milliseconds.
+    fn on_inserted(&self, table: &TableInfo, min_timestamp: i64);
+}
+
+pub type InsertListenerRef = Arc<dyn InsertListener>;
+
 /// Hint for the table type to create automatically.
 #[derive(Clone)]
 pub enum AutoCreateTableType {
@@ -139,6 +153,15 @@ impl Inserter {
             partition_manager,
             node_manager,
             table_flownode_set_cache,
+            insert_listener: None,
+        }
+    }
+
+    /// Sets the listener to notify after rows are written.
+    pub fn with_insert_listener(self, insert_listener: InsertListenerRef) -> Self {
+        Self {
+            insert_listener: Some(insert_listener),
+            ..self
         }
     }
 
@@ -402,6 +425,11 @@ impl Inserter {
         .await?;
         flow_mirror_task.detach(self.node_manager.clone())?;
 
+        let written_timestamps = self
+            .insert_listener
+            .as_ref()
+            .map(|_| min_timestamps_by_table(&normal_requests));
+
         // Write requests to datanode and wait for response
         let write_tasks = self
             .group_requests_by_peer(normal_requests)
@@ -422,6 +450,16 @@ impl Inserter {
         let results = future::try_join_all(write_tasks)
             .await
             .context(JoinTaskSnafu)?;
+        // Notifies the listener even if some requests fail since the rows may be partially written.
+        if let (Some(listener), Some(written_timestamps)) =
+            (&self.insert_listener, written_timestamps)
+        {
+            for (table_id, min_timestamp) in written_timestamps {
+                if let Some(table_info) = table_infos.get(&table_id) {
+                    listener.on_inserted(table_info, min_timestamp);
+                }
+            }
+        }
         let affected_rows = results
             .into_iter()
             .map(|resp| resp.map(|r| r.affected_rows))
@@ -973,6 +1011,44 @@ impl Inserter {
     }
 }
 
+/// Returns the minimum timestamp in milliseconds of rows written to each table.
+fn min_timestamps_by_table(requests: &RegionInsertRequests) -> HashMap<TableId, i64> {
+    let mut timestamps = HashMap::new();
+    for request in &requests.requests {
+        let Some(rows) = &request.rows else {
+            continue;
+        };
+        let Some(ts_index) = rows
+            .schema
+            .iter()
+            .position(|column| column.semantic_type == SemanticType::Timestamp as i32)
+        else {
+            continue;
+        };
+        let datatype_ext = &rows.schema[ts_index].datatype_extension;
+        let min_timestamp = rows
+            .rows
+            .iter()
+            .filter_map(|row| {
+                let ValueRef::Timestamp(ts) =
+                    pb_value_to_value_ref(row.values.get(ts_index)?, datatype_ext)
+                else {
+                    return None;
+                };
+                ts.convert_to(TimeUnit::Millisecond).map(|ts| ts.value())
+            })
+            .min();
+        if let Some(min_timestamp) = min_timestamp {
+            let table_id = RegionId::from_u64(request.region_id).table_id();
+            timestamps
+                .entry(table_id)
+                .and_modify(|ts: &mut i64| *ts = (*ts).min(min_timestamp))
+                .or_insert(min_timestamp);
+        }
+    }
+    timestamps
+}
+
 fn validate_column_count_match(requests: &RowInsertRequests) -> Result<()> {
     for request in &requests.inserts {
         let rows = request.rows.as_ref().unwrap();
@@ -1175,6 +1251,7 @@ mod tests {
     use std::sync::Arc;
 
     use api::v1::helper::{field_column_schema, time_index_column_schema};
+    use api::v1::value::ValueData;
     use api::v1::{RowInsertRequest, Rows, Value};
     use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
     use common_meta::cache::new_table_flownode_set_cache;
@@ -1280,4 +1357,55 @@ mod tests {
         assert_eq!(req_schema[0].column_name, ts_name);
         assert_eq!(req_schema[1].column_name, field_name);
     }
+
+    #[test]
+    fn test_min_timestamps_by_table() {
+        let request = |region_id: RegionId, datatype, values: Vec<ValueData>| RegionInsertRequest {
+            region_id: region_id.as_u64(),
+            rows: Some(Rows {
+                schema: vec![
+                    field_column_schema("value", ColumnDataType::Float64),
+                    time_index_column_schema("ts", datatype),
+                ],
+                rows: values
+                    .into_iter()
+                    .map(|v| api::v1::Row {
+                        values: vec![
+                            Value::default(),
+                            Value {
+                                value_data: Some(v),
+                            },
+                        ],
+                    })
+                    .collect(),
+            }),
+        };
+        let requests = RegionInsertRequests {
+            requests: vec![
+                request(
+                    RegionId::new(1, 0),
+                    ColumnDataType::TimestampMillisecond,
+                    vec![
+                        ValueData::TimestampMillisecondValue(3000),
+                        ValueData::TimestampMillisecondValue(1000),
+                    ],
+                ),
+                request(
+                    RegionId::new(1, 1),
+                    ColumnDataType::TimestampMillisecond,
+                    vec![ValueData::TimestampMillisecondValue(2000)],
+                ),
+                request(
+                    RegionId::new(2, 0),
+                    ColumnDataType::TimestampSecond,
+                    vec![ValueData::TimestampSecondValue(5)],
+                ),
+            ],
+        };
+
+        let timestamps = min_timestamps_by_table(&requests);
+        assert_eq!(2, timestamps.len());
+        assert_eq!(Some(&1000), timestamps.get(&1));
+        assert_eq!(Some(&5000), timestamps.get(&2));
+    }
 }
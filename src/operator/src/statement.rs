@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod access_control;
 mod admin;
+mod backup;
 mod copy_database;
 mod copy_query_to;
 mod copy_table_from;
@@ -39,6 +41,7 @@ use client::RecordBatches;
 use common_error::ext::BoxedError;
 use common_meta::cache::TableRouteCacheRef;
 use common_meta::cache_invalidator::CacheInvalidatorRef;
+use common_meta::key::access_control::{AccessControlManager, AccessControlManagerRef};
 use common_meta::key::flow::{FlowMetadataManager, FlowMetadataManagerRef};
 use common_meta::key::schema_name::SchemaNameKey;
 use common_meta::key::view_info::{ViewInfoManager, ViewInfoManagerRef};
@@ -99,6 +102,7 @@ pub struct StatementExecutor {
     cache_invalidator: CacheInvalidatorRef,
     inserter: InserterRef,
     process_manager: Option<ProcessManagerRef>,
+    access_control_manager: AccessControlManagerRef,
 }
 
 pub type StatementExecutorRef = Arc<StatementExecutor>;
@@ -122,6 +126,7 @@ impl StatementExecutor {
             table_metadata_manager: Arc::new(TableMetadataManager::new(kv_backend.clone())),
             flow_metadata_manager: Arc::new(FlowMetadataManager::new(kv_backend.clone())),
             view_info_manager: Arc::new(ViewInfoManager::new(kv_backend.clone())),
+            access_control_manager: Arc::new(AccessControlManager::new(kv_backend.clone())),
             partition_manager: Arc::new(PartitionRuleManager::new(kv_backend, table_route_cache)),
             cache_invalidator,
             inserter,
@@ -160,6 +165,8 @@ impl StatementExecutor {
 
             Statement::Insert(insert) => self.insert(insert, query_ctx).await,
 
+            Statement::Update(update) => self.update(update, query_ctx).await,
+
             Statement::Tql(tql) => self.execute_tql(tql, query_ctx).await,
 
             Statement::DescribeTable(stmt) => self.describe_table(stmt, query_ctx).await,
@@ -392,6 +399,14 @@ impl StatementExecutor {
             Statement::Admin(admin) => self.execute_admin_command(admin, query_ctx).await,
             Statement::Kill(kill) => self.execute_kill(query_ctx, kill).await,
             Statement::ShowProcesslist(show) => self.show_processlist(show, query_ctx).await,
+            Statement::CreateUser(stmt) => self.create_user(stmt).await,
+            Statement::DropUser(stmt) => self.drop_user(stmt).await,
+            Statement::CreateRole(stmt) => self.create_role(stmt).await,
+            Statement::DropRole(stmt) => self.drop_role(stmt).await,
+            Statement::Grant(stmt) => self.grant(stmt, query_ctx).await,
+            Statement::Revoke(stmt) => self.revoke(stmt, query_ctx).await,
+            Statement::BackupDatabase(stmt) => self.backup_database(stmt, query_ctx).await,
+            Statement::RestoreDatabase(stmt) => self.restore_database(stmt, query_ctx).await,
         }
     }
 
@@ -542,6 +557,18 @@ impl StatementExecutor {
         &self.cache_invalidator
     }
 
+    /// Uses the `manager` to manage users and roles, e.g. the one in the cache registry.
+    pub fn with_access_control_manager(self, manager: AccessControlManagerRef) -> Self {
+        Self {
+            access_control_manager: manager,
+            ..self
+        }
+    }
+
+    pub fn access_control_manager(&self) -> &AccessControlManagerRef {
+        &self.access_control_manager
+    }
+
     /// Convert truncate time ranges for the given table from sql values to timestamps
     ///
     pub async fn convert_truncate_time_ranges(
@@ -0,0 +1,161 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_error::ext::BoxedError;
+use common_meta::cache_invalidator::Context;
+use common_meta::instruction::CacheIdent;
+use common_meta::key::access_control::{GrantObject, Privilege};
+use common_query::Output;
+use session::context::QueryContextRef;
+use session::table_name::table_idents_to_full_name;
+use snafu::ResultExt;
+use sql::statements::access_control::{
+    CreateRole, CreateUser, DropRole, DropUser, Grant, Grantable, Privilege as SqlPrivilege,
+    PrivilegeLevel, Revoke,
+};
+
+use crate::error::{AccessControlSnafu, ExternalSnafu, InvalidateTableCacheSnafu, Result};
+use crate::statement::StatementExecutor;
+
+impl StatementExecutor {
+    pub async fn create_user(&self, stmt: CreateUser) -> Result<Output> {
+        let password_hash = stmt
+            .password
+            .map(|password| auth::mysql_password_hash(password.as_bytes()));
+        let created = self
+            .access_control_manager
+            .create_user(
+                &stmt.name.value,
+                password_hash.as_deref(),
+                stmt.if_not_exists,
+            )
+            .await
+            .context(AccessControlSnafu)?;
+        Ok(Output::new_with_affected_rows(created as usize))
+    }
+
+    pub async fn drop_user(&self, stmt: DropUser) -> Result<Output> {
+        let dropped = self
+            .access_control_manager
+            .drop_user(&stmt.name.value, stmt.if_exists)
+            .await
+            .context(AccessControlSnafu)?;
+        self.invalidate_access_control().await?;
+        Ok(Output::new_with_affected_rows(dropped as usize))
+    }
+
+    pub async fn create_role(&self, stmt: CreateRole) -> Result<Output> {
+        let created = self
+            .access_control_manager
+            .create_role(&stmt.name.value, stmt.if_not_exists)
+            .await
+            .context(AccessControlSnafu)?;
+        Ok(Output::new_with_affected_rows(created as usize))
+    }
+
+    pub async fn drop_role(&self, stmt: DropRole) -> Result<Output> {
+        let dropped = self
+            .access_control_manager
+            .drop_role(&stmt.name.value, stmt.if_exists)
+            .await
+            .context(AccessControlSnafu)?;
+        self.invalidate_access_control().await?;
+        Ok(Output::new_with_affected_rows(dropped as usize))
+    }
+
+    pub async fn grant(&self, stmt: Grant, query_ctx: QueryContextRef) -> Result<Output> {
+        let grantee = &stmt.grantee.value;
+        match stmt.grantable {
+            Grantable::Privileges { privileges, level } => {
+                let object = to_grant_object(&level, &query_ctx)?;
+                self.access_control_manager
+                    .grant_privileges(grantee, object, &to_privileges(&privileges))
+                    .await
+            }
+            Grantable::Role(role) => {
+                self.access_control_manager
+                    .grant_role(&role.value, grantee)
+                    .await
+            }
+        }
+        .context(AccessControlSnafu)?;
+        self.invalidate_access_control().await?;
+        Ok(Output::new_with_affected_rows(0))
+    }
+
+    pub async fn revoke(&self, stmt: Revoke, query_ctx: QueryContextRef) -> Result<Output> {
+        let grantee = &stmt.grantee.value;
+        match stmt.grantable {
+            Grantable::Privileges { privileges, level } => {
+                let object = to_grant_object(&level, &query_ctx)?;
+                self.access_control_manager
+                    .revoke_privileges(grantee, &object, &to_privileges(&privileges))
+                    .await
+            }
+            Grantable::Role(role) => {
+                self.access_control_manager
+                    .revoke_role(&role.value, grantee)
+                    .await
+            }
+        }
+        .context(AccessControlSnafu)?;
+        self.invalidate_access_control().await?;
+        Ok(Output::new_with_affected_rows(0))
+    }
+
+    /// Invalidates the cached users and roles, so privileges revoked or dropped
+    /// take effect immediately.
+    async fn invalidate_access_control(&self) -> Result<()> {
+        self.cache_invalidator
+            .invalidate(&Context::default(), &[CacheIdent::AccessControl])
+            .await
+            .context(InvalidateTableCacheSnafu)
+    }
+}
+
+/// Resolves the privilege level to an object in the current catalog.
+fn to_grant_object(level: &PrivilegeLevel, query_ctx: &QueryContextRef) -> Result<GrantObject> {
+    let object = match level {
+        PrivilegeLevel::Global => GrantObject::Global,
+        PrivilegeLevel::Database(schema) => GrantObject::Database {
+            catalog: query_ctx.current_catalog().to_string(),
+            schema: schema.value.clone(),
+        },
+        PrivilegeLevel::Table(name) => {
+            let (catalog, schema, table) = table_idents_to_full_name(name, query_ctx)
+                .map_err(BoxedError::new)
+                .context(ExternalSnafu)?;
+            GrantObject::Table {
+                catalog,
+                schema,
+                table,
+            }
+        }
+    };
+    Ok(object)
+}
+
+fn to_privileges(privileges: &[SqlPrivilege]) -> Vec<Privilege> {
+    privileges
+        .iter()
+        .map(|privilege| match privilege {
+            SqlPrivilege::Select => Privilege::Select,
+            SqlPrivilege::Insert => Privilege::Insert,
+            SqlPrivilege::Delete => Privilege::Delete,
+            SqlPrivilege::Create => Privilege::Create,
+            SqlPrivilege::Alter => Privilege::Alter,
+            SqlPrivilege::Drop => Privilege::Drop,
+        })
+        .collect()
+}
@@ -0,0 +1,735 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! BACKUP DATABASE and RESTORE DATABASE.
+//!
+//! A backup repository is a directory in an object store with the layout:
+//!
+//! ```text
+//! <repository>/
+//!   <backup_name>/backup.json           -- the [BackupManifest] of a backup
+//!   data/<table_id>_<region_number>/    -- SST and index files of a region
+//! ```
+//!
+//! Backups of the same region share a data directory, so an incremental backup
+//! only copies the files that are not in its base backup.
+//!
+//! A backup takes a snapshot of the database before copying any file: it flushes all
+//! regions at once, then pins the files and manifest versions of all regions at once.
+//! The copy reads the pinned versions, so it doesn't widen the time window of the backup
+//! however long it takes. The backup contains all rows written before its `snapshot_at_ms`,
+//! rows written while the regions are flushed and pinned may or may not be included.
+//!
+//! Tables of the metric engine are not supported, they are skipped with a warning and
+//! listed in the `skipped_tables` of the manifest.
+//!
+//! A restore recreates the tables with the column ids of the backed up regions, since the
+//! SST files reference columns by ids. It either restores all tables or drops the tables
+//! it has created.
+
+use std::collections::HashMap;
+
+use api::v1::region::region_request::Body as RegionRequestBody;
+use api::v1::region::{FlushRequest, RegionRequestHeader};
+use client::Output;
+use common_catalog::consts::MITO_ENGINE;
+use common_catalog::{build_db_string, format_full_table_name};
+use common_datasource::object_store::build_backend;
+use common_meta::peer::Peer;
+use common_telemetry::tracing_context::TracingContext;
+use common_telemetry::{error, info, tracing, warn};
+use common_time::util::current_time_millis;
+use futures::future;
+use object_store::ObjectStore;
+use partition::expr::PartitionExpr;
+use partition::manager::PartitionInfo;
+use query::sql::create_table_stmt;
+use serde::{Deserialize, Serialize};
+use session::context::QueryContextRef;
+use snafu::{ensure, OptionExt, ResultExt};
+use sql::dialect::GreptimeDbDialect;
+use sql::parser::{ParseOptions, ParserContext};
+use sql::statements::backup::{BackupDatabase, RestoreDatabase};
+use sql::statements::statement::Statement;
+use sql::statements::OptionMap;
+use sqlparser::ast::{Ident, ObjectName};
+use store_api::region_engine::{
+    BackupRegionRequest, BackupRegionResponse, RestoreRegionRequest, RestoreRegionResponse,
+};
+use store_api::storage::{ColumnId, RegionId, TableId};
+use table::metadata::TableType;
+use table::table_name::TableName;
+use table::table_reference::TableReference;
+
+use crate::error::{
+    BackupRegionSnafu, BuildBackendSnafu, CatalogSnafu, DecodeBackupManifestSnafu, EncodeJsonSnafu,
+    FindRegionLeaderSnafu, FindTablePartitionRuleSnafu, InvalidBackupSnafu, ParseQuerySnafu,
+    ParseSqlSnafu, ReadObjectSnafu, RequestRegionSnafu, Result, SchemaNotFoundSnafu,
+    TableAlreadyExistsSnafu, WriteObjectSnafu,
+};
+use crate::expr_helper;
+use crate::region_req_factory::RegionRequestFactory;
+use crate::statement::show::create_partitions_stmt;
+use crate::statement::{idents_to_full_database_name, StatementExecutor};
+
+/// The option of the backup name.
+const BACKUP_NAME_KEY: &str = "name";
+/// The option of the base backup of an incremental backup.
+const BACKUP_BASE_KEY: &str = "base";
+const BACKUP_MANIFEST_FILE: &str = "backup.json";
+
+/// The manifest of a backup, which is written after all files are copied.
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupManifest {
+    name: String,
+    catalog: String,
+    schema: String,
+    /// The name of the base backup if it's an incremental backup.
+    base: Option<String>,
+    /// The time before the regions are flushed, rows written before it are in the backup.
+    #[serde(default)]
+    snapshot_at_ms: i64,
+    created_at_ms: i64,
+    tables: Vec<TableBackup>,
+    /// The tables that are not backed up as their engines are not supported.
+    #[serde(default)]
+    skipped_tables: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TableBackup {
+    table_name: String,
+    table_id: TableId,
+    /// The CREATE TABLE statement to recreate the table, including the partition rule.
+    create_table_sql: String,
+    /// The ids of the columns in the backed up regions.
+    column_ids: HashMap<String, ColumnId>,
+    regions: Vec<RegionBackup>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RegionBackup {
+    region_id: RegionId,
+    partition_expr: Option<PartitionExpr>,
+    manifest_version: u64,
+    /// The snapshot returned by the region engine.
+    snapshot: String,
+}
+
+impl StatementExecutor {
+    /// Backs up all mito tables in the database, returns the number of tables backed up.
+    /// Tables of other engines are skipped.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn backup_database(
+        &self,
+        stmt: BackupDatabase,
+        ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let (catalog, schema) = idents_to_full_database_name(&stmt.database_name, &ctx)?;
+        self.ensure_schema_exists(&catalog, &schema, &ctx).await?;
+        ensure_repository_location(&stmt.location)?;
+        let name = backup_name(&stmt.with)?;
+        let base = stmt.with.get(BACKUP_BASE_KEY).cloned();
+        let connection = stmt.connection.into_map();
+        let repository = build_backend(&stmt.location, &connection).context(BuildBackendSnafu)?;
+
+        let path = manifest_path(&name);
+        ensure!(
+            !repository
+                .exists(&path)
+                .await
+                .context(ReadObjectSnafu { path: &path })?,
+            InvalidBackupSnafu {
+                reason: format!("backup {name} already exists"),
+            }
+        );
+        let base_snapshots = match &base {
+            Some(base) => read_manifest(&repository, base)
+                .await?
+                .tables
+                .into_iter()
+                .flat_map(|table| table.regions)
+                .map(|region| (region.region_id, region.snapshot))
+                .collect(),
+            None => HashMap::new(),
+        };
+
+        info!(
+            "Backup database {}.{} to {}, name: {}, base: {:?}",
+            catalog, schema, stmt.location, name, base
+        );
+        let table_names = self
+            .catalog_manager
+            .table_names(&catalog, &schema, Some(&ctx))
+            .await
+            .context(CatalogSnafu)?;
+        let mut tables = Vec::with_capacity(table_names.len());
+        let mut skipped_tables = Vec::new();
+        for table_name in table_names {
+            let table = self
+                .get_table(&TableReference {
+                    catalog: &catalog,
+                    schema: &schema,
+                    table: &table_name,
+                })
+                .await?;
+            // Only base tables, ignores views and temporary tables.
+            if table.table_type() != TableType::Base {
+                continue;
+            }
+            let table_info = table.table_info();
+            if table_info.meta.engine != MITO_ENGINE {
+                warn!(
+                    "Skip backing up table {}.{}.{}, engine {} is not supported",
+                    catalog, schema, table_name, table_info.meta.engine
+                );
+                skipped_tables.push(table_name);
+                continue;
+            }
+
+            let partitions = self
+                .partition_manager
+                .find_table_partitions(table_info.table_id())
+                .await
+                .context(FindTablePartitionRuleSnafu {
+                    table_name: &table_name,
+                })?;
+            let mut create_stmt =
+                create_table_stmt(&table_info, None, ctx.quote_style()).context(ParseQuerySnafu)?;
+            create_stmt.partitions = create_partitions_stmt(&table_info, partitions.clone())?
+                .and_then(|mut partitions| {
+                    if !partitions.column_list.is_empty() {
+                        partitions.set_quote(ctx.quote_style());
+                        Some(partitions)
+                    } else {
+                        None
+                    }
+                });
+
+            tables.push(TableBackup {
+                table_name,
+                table_id: table_info.table_id(),
+                create_table_sql: create_stmt.to_string(),
+                column_ids: HashMap::new(),
+                regions: partitions
+                    .into_iter()
+                    .map(|partition| RegionBackup {
+                        region_id: partition.id,
+                        partition_expr: partition.partition_expr,
+                        manifest_version: 0,
+                        snapshot: String::new(),
+                    })
+                    .collect(),
+            });
+        }
+
+        // Flushes all regions before pinning their files so the backup contains
+        // the rows written before the snapshot.
+        let region_ids = tables
+            .iter()
+            .flat_map(|table| table.regions.iter().map(|region| region.region_id))
+            .collect::<Vec<_>>();
+        let db_string = build_db_string(&catalog, &schema);
+        let snapshot_at_ms = current_time_millis();
+        future::try_join_all(
+            region_ids
+                .iter()
+                .map(|region_id| self.flush_region(*region_id, &db_string)),
+        )
+        .await?;
+
+        // Pins all regions before copying any file.
+        let mut pinned_regions = Vec::with_capacity(region_ids.len());
+        let mut result = self
+            .pin_regions(&name, &mut tables, &mut pinned_regions)
+            .await;
+        if result.is_ok() {
+            result = self
+                .copy_regions(
+                    &name,
+                    &stmt.location,
+                    &connection,
+                    &base_snapshots,
+                    &mut tables,
+                    &mut pinned_regions,
+                )
+                .await;
+        }
+        if let Err(e) = result {
+            self.release_regions(&name, &pinned_regions).await;
+            return Err(e);
+        }
+
+        let num_tables = tables.len();
+        let manifest = BackupManifest {
+            name,
+            catalog,
+            schema,
+            base,
+            snapshot_at_ms,
+            created_at_ms: current_time_millis(),
+            tables,
+            skipped_tables,
+        };
+        let body = serde_json::to_vec(&manifest).context(EncodeJsonSnafu)?;
+        repository
+            .write(&path, body)
+            .await
+            .context(WriteObjectSnafu { path: &path })?;
+
+        Ok(Output::new_with_affected_rows(num_tables))
+    }
+
+    /// Recreates the tables of a backup in the database and restores their regions,
+    /// returns the number of tables restored.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn restore_database(
+        &self,
+        stmt: RestoreDatabase,
+        ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let (catalog, schema) = idents_to_full_database_name(&stmt.database_name, &ctx)?;
+        self.ensure_schema_exists(&catalog, &schema, &ctx).await?;
+        ensure_repository_location(&stmt.location)?;
+        let name = backup_name(&stmt.with)?;
+        let connection = stmt.connection.into_map();
+        let repository = build_backend(&stmt.location, &connection).context(BuildBackendSnafu)?;
+        let manifest = read_manifest(&repository, &name).await?;
+
+        info!(
+            "Restore database {}.{} from {}, name: {}, source database: {}.{}",
+            catalog, schema, stmt.location, name, manifest.catalog, manifest.schema
+        );
+        // Checks all tables before creating any of them.
+        for table_backup in &manifest.tables {
+            let table = self
+                .catalog_manager
+                .table(&catalog, &schema, &table_backup.table_name, Some(&ctx))
+                .await
+                .context(CatalogSnafu)?;
+            ensure!(
+                table.is_none(),
+                TableAlreadyExistsSnafu {
+                    table: format_full_table_name(&catalog, &schema, &table_backup.table_name),
+                }
+            );
+        }
+
+        let mut created_tables = Vec::with_capacity(manifest.tables.len());
+        let result = self
+            .restore_tables(
+                &catalog,
+                &schema,
+                &stmt.location,
+                &connection,
+                &manifest.tables,
+                &mut created_tables,
+                &ctx,
+            )
+            .await;
+        if let Err(e) = result {
+            // Drops the created tables, so the restore either restores all tables or none.
+            if !created_tables.is_empty() {
+                if let Err(drop_err) = self.drop_tables(&created_tables, true, ctx.clone()).await {
+                    error!(drop_err; "Failed to drop tables {:?} after failing to restore backup {}", created_tables, name);
+                }
+            }
+            return Err(e);
+        }
+
+        Ok(Output::new_with_affected_rows(manifest.tables.len()))
+    }
+
+    /// Pins the files of all regions at once, and records the column ids of the tables.
+    async fn pin_regions(
+        &self,
+        backup_name: &str,
+        tables: &mut [TableBackup],
+        pinned_regions: &mut Vec<(RegionId, Peer)>,
+    ) -> Result<()> {
+        let region_ids = tables
+            .iter()
+            .flat_map(|table| table.regions.iter().map(|region| region.region_id))
+            .collect::<Vec<_>>();
+        let responses = future::join_all(
+            region_ids
+                .iter()
+                .map(|region_id| self.pin_region(backup_name, *region_id)),
+        )
+        .await;
+
+        // Records all pinned regions before checking the responses, so they are
+        // released if any region fails.
+        let mut pins = HashMap::with_capacity(region_ids.len());
+        let mut first_error = None;
+        for (region_id, response) in region_ids.into_iter().zip(responses) {
+            match response {
+                Ok((
+                    peer,
+                    BackupRegionResponse::Pinned {
+                        manifest_version,
+                        column_metadatas,
+                    },
+                )) => {
+                    pinned_regions.push((region_id, peer));
+                    let _ = pins.insert(region_id, (manifest_version, column_metadatas));
+                }
+                Ok(_) => {
+                    let _ = first_error.get_or_insert(
+                        InvalidBackupSnafu {
+                            reason: format!("region {region_id} doesn't support backup"),
+                        }
+                        .build(),
+                    );
+                }
+                Err(e) => {
+                    let _ = first_error.get_or_insert(e);
+                }
+            }
+        }
+        if let Some(e) = first_error {
+            return Err(e);
+        }
+
+        for table in tables.iter_mut() {
+            for region in table.regions.iter_mut() {
+                let region_id = region.region_id;
+                let Some((manifest_version, column_metadatas)) = pins.remove(&region_id) else {
+                    continue;
+                };
+                region.manifest_version = manifest_version;
+
+                let column_ids = column_metadatas
+                    .into_iter()
+                    .map(|column| (column.column_schema.name, column.column_id))
+                    .collect::<HashMap<_, _>>();
+                // All regions of the restored table are created with the same column ids.
+                if table.column_ids.is_empty() {
+                    table.column_ids = column_ids;
+                } else {
+                    ensure!(
+                        table.column_ids == column_ids,
+                        InvalidBackupSnafu {
+                            reason: format!(
+                                "columns of region {} differ from other regions of table {}",
+                                region_id, table.table_name
+                            ),
+                        }
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn pin_region(
+        &self,
+        backup_name: &str,
+        region_id: RegionId,
+    ) -> Result<(Peer, BackupRegionResponse)> {
+        let peer = self.find_region_leader(region_id).await?;
+        let response = self
+            .inserter
+            .node_manager
+            .datanode(&peer)
+            .await
+            .backup_region(BackupRegionRequest::Pin {
+                region_id,
+                backup_name: backup_name.to_string(),
+            })
+            .await
+            .context(BackupRegionSnafu { region_id })?;
+        Ok((peer, response))
+    }
+
+    /// Copies the pinned files of all regions, the pin of a region is released after its
+    /// files are copied.
+    async fn copy_regions(
+        &self,
+        backup_name: &str,
+        location: &str,
+        connection: &HashMap<String, String>,
+        base_snapshots: &HashMap<RegionId, String>,
+        tables: &mut [TableBackup],
+        pinned_regions: &mut Vec<(RegionId, Peer)>,
+    ) -> Result<()> {
+        for region in tables.iter_mut().flat_map(|table| table.regions.iter_mut()) {
+            let region_id = region.region_id;
+            // Copies from the datanode that holds the pin.
+            let Some(index) = pinned_regions.iter().position(|(id, _)| *id == region_id) else {
+                continue;
+            };
+            let peer = pinned_regions[index].1.clone();
+            let response = self
+                .inserter
+                .node_manager
+                .datanode(&peer)
+                .await
+                .backup_region(BackupRegionRequest::Copy {
+                    region_id,
+                    backup_name: backup_name.to_string(),
+                    location: region_location(location, region_id),
+                    connection: connection.clone(),
+                    base_snapshot: base_snapshots.get(&region_id).cloned(),
+                })
+                .await
+                .context(BackupRegionSnafu { region_id })?;
+            pinned_regions.swap_remove(index);
+            let BackupRegionResponse::Backup {
+                manifest_version,
+                copied_files,
+                copied_bytes,
+                snapshot,
+            } = response
+            else {
+                return InvalidBackupSnafu {
+                    reason: format!("region {region_id} doesn't support backup"),
+                }
+                .fail();
+            };
+            info!(
+                "Backed up region {} at manifest version {}, copied files: {}, copied bytes: {}",
+                region_id, manifest_version, copied_files, copied_bytes
+            );
+            region.manifest_version = manifest_version;
+            region.snapshot = snapshot;
+        }
+
+        Ok(())
+    }
+
+    /// Releases the pins of the regions, the errors are only logged.
+    async fn release_regions(&self, backup_name: &str, pinned_regions: &[(RegionId, Peer)]) {
+        for (region_id, peer) in pinned_regions {
+            if let Err(e) = self
+                .inserter
+                .node_manager
+                .datanode(peer)
+                .await
+                .backup_region(BackupRegionRequest::Release {
+                    region_id: *region_id,
+                    backup_name: backup_name.to_string(),
+                })
+                .await
+            {
+                warn!(e; "Failed to release the pin of region {} held by backup {}", region_id, backup_name);
+            }
+        }
+    }
+
+    /// Recreates the tables and restores their regions, the created tables are pushed
+    /// to `created_tables`.
+    #[allow(clippy::too_many_arguments)]
+    async fn restore_tables(
+        &self,
+        catalog: &str,
+        schema: &str,
+        location: &str,
+        connection: &HashMap<String, String>,
+        tables: &[TableBackup],
+        created_tables: &mut Vec<TableName>,
+        ctx: &QueryContextRef,
+    ) -> Result<()> {
+        for table_backup in tables {
+            let mut create_stmt = parse_create_table(&table_backup.create_table_sql)?;
+            create_stmt.name = ObjectName::from(vec![
+                Ident::new(catalog),
+                Ident::new(schema),
+                Ident::new(&table_backup.table_name),
+            ]);
+            let partitions = create_stmt.partitions.take();
+            let mut create_expr = expr_helper::create_to_expr(&create_stmt, ctx)?;
+            let table = self
+                .create_non_logic_table_with_column_ids(
+                    &mut create_expr,
+                    partitions,
+                    Some(&table_backup.column_ids),
+                    ctx.clone(),
+                )
+                .await?;
+            created_tables.push(TableName::new(catalog, schema, &table_backup.table_name));
+            let table_id = table.table_info().table_id();
+            let partitions = self
+                .partition_manager
+                .find_table_partitions(table_id)
+                .await
+                .context(FindTablePartitionRuleSnafu {
+                    table_name: &table_backup.table_name,
+                })?;
+
+            for region in &table_backup.regions {
+                let region_id = find_restored_region(&partitions, region)?;
+                let peer = self.find_region_leader(region_id).await?;
+                let response = self
+                    .inserter
+                    .node_manager
+                    .datanode(&peer)
+                    .await
+                    .restore_region(RestoreRegionRequest {
+                        region_id,
+                        location: region_location(location, region.region_id),
+                        connection: connection.clone(),
+                        snapshot: region.snapshot.clone(),
+                    })
+                    .await
+                    .context(BackupRegionSnafu { region_id })?;
+                let RestoreRegionResponse::Restored { restored_files } = response else {
+                    return InvalidBackupSnafu {
+                        reason: format!("region {region_id} doesn't support restore"),
+                    }
+                    .fail();
+                };
+                info!(
+                    "Restored region {} from region {}, restored files: {}",
+                    region_id, region.region_id, restored_files
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn ensure_schema_exists(
+        &self,
+        catalog: &str,
+        schema: &str,
+        ctx: &QueryContextRef,
+    ) -> Result<()> {
+        ensure!(
+            self.catalog_manager
+                .schema_exists(catalog, schema, Some(ctx))
+                .await
+                .context(CatalogSnafu)?,
+            SchemaNotFoundSnafu {
+                schema_info: build_db_string(catalog, schema),
+            }
+        );
+        Ok(())
+    }
+
+    async fn find_region_leader(&self, region_id: RegionId) -> Result<Peer> {
+        self.partition_manager
+            .find_region_leader(region_id)
+            .await
+            .context(FindRegionLeaderSnafu)
+    }
+
+    async fn flush_region(&self, region_id: RegionId, db_string: &str) -> Result<()> {
+        let request_factory = RegionRequestFactory::new(RegionRequestHeader {
+            tracing_context: TracingContext::from_current_span().to_w3c(),
+            dbname: db_string.to_string(),
+            ..Default::default()
+        });
+        let request = request_factory.build_request(RegionRequestBody::Flush(FlushRequest {
+            region_id: region_id.as_u64(),
+        }));
+        let peer = self.find_region_leader(region_id).await?;
+        let _ = self
+            .inserter
+            .node_manager
+            .datanode(&peer)
+            .await
+            .handle(request)
+            .await
+            .context(RequestRegionSnafu)?;
+        Ok(())
+    }
+}
+
+fn ensure_repository_location(location: &str) -> Result<()> {
+    ensure!(
+        location.ends_with('/'),
+        InvalidBackupSnafu {
+            reason: format!("backup location must end with '/': {location}"),
+        }
+    );
+    Ok(())
+}
+
+fn backup_name(with: &OptionMap) -> Result<String> {
+    let name = with.get(BACKUP_NAME_KEY).context(InvalidBackupSnafu {
+        reason: "backup name is required, specify it by WITH (name = '...')",
+    })?;
+    ensure!(
+        !name.is_empty() && !name.contains('/') && name != "data",
+        InvalidBackupSnafu {
+            reason: format!("invalid backup name: {name}"),
+        }
+    );
+    Ok(name.clone())
+}
+
+fn manifest_path(name: &str) -> String {
+    format!("{name}/{BACKUP_MANIFEST_FILE}")
+}
+
+/// Returns the location of the files of the region in the repository.
+fn region_location(repository: &str, region_id: RegionId) -> String {
+    format!(
+        "{repository}data/{}_{}/",
+        region_id.table_id(),
+        region_id.region_number()
+    )
+}
+
+async fn read_manifest(repository: &ObjectStore, name: &str) -> Result<BackupManifest> {
+    let path = manifest_path(name);
+    ensure!(
+        repository
+            .exists(&path)
+            .await
+            .context(ReadObjectSnafu { path: &path })?,
+        InvalidBackupSnafu {
+            reason: format!("backup {name} not found"),
+        }
+    );
+    let body = repository
+        .read(&path)
+        .await
+        .context(ReadObjectSnafu { path: &path })?
+        .to_vec();
+    serde_json::from_slice(&body).context(DecodeBackupManifestSnafu { path })
+}
+
+fn parse_create_table(sql: &str) -> Result<sql::statements::create::CreateTable> {
+    let mut statements =
+        ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+            .context(ParseSqlSnafu)?;
+    match statements.pop() {
+        Some(Statement::CreateTable(create_stmt)) if statements.is_empty() => Ok(create_stmt),
+        _ => InvalidBackupSnafu {
+            reason: format!("invalid CREATE TABLE statement in backup: {sql}"),
+        }
+        .fail(),
+    }
+}
+
+/// Finds the region of the restored table with the same partition rule as the backed up region.
+fn find_restored_region(partitions: &[PartitionInfo], region: &RegionBackup) -> Result<RegionId> {
+    if let [partition] = partitions {
+        return Ok(partition.id);
+    }
+
+    partitions
+        .iter()
+        .find(|partition| partition.partition_expr == region.partition_expr)
+        .map(|partition| partition.id)
+        .with_context(|| InvalidBackupSnafu {
+            reason: format!(
+                "no region of the restored table matches the partition of region {}",
+                region.region_id
+            ),
+        })
+}
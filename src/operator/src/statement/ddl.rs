@@ -40,8 +40,8 @@ use common_meta::rpc::ddl::trigger::CreateTriggerTask;
 #[cfg(feature = "enterprise")]
 use common_meta::rpc::ddl::trigger::DropTriggerTask;
 use common_meta::rpc::ddl::{
-    CreateFlowTask, DdlTask, DropFlowTask, DropViewTask, SubmitDdlTaskRequest,
-    SubmitDdlTaskResponse,
+    CreateFlowTask, DdlTask, DropFlowTask, DropViewTask, RepartitionTableTask,
+    SubmitDdlTaskRequest, SubmitDdlTaskResponse,
 };
 use common_query::Output;
 use common_sql::convert::sql_value_to_value;
@@ -66,7 +66,7 @@ use snafu::{ensure, OptionExt, ResultExt};
 use sql::parser::{ParseOptions, ParserContext};
 #[cfg(feature = "enterprise")]
 use sql::statements::alter::trigger::AlterTrigger;
-use sql::statements::alter::{AlterDatabase, AlterTable};
+use sql::statements::alter::{AlterDatabase, AlterTable, AlterTableOperation};
 #[cfg(feature = "enterprise")]
 use sql::statements::create::trigger::CreateTrigger;
 use sql::statements::create::{
@@ -75,6 +75,7 @@ use sql::statements::create::{
 use sql::statements::statement::Statement;
 use sqlparser::ast::{Expr, Ident, UnaryOperator, Value as ParserValue};
 use store_api::metric_engine_consts::{LOGICAL_TABLE_METADATA_KEY, METRIC_ENGINE_NAME};
+use store_api::storage::ColumnId;
 use substrait::{DFLogicalSubstraitConvertor, SubstraitPlan};
 use table::dist_table::DistTable;
 use table::metadata::{self, RawTableInfo, RawTableMeta, TableId, TableInfo, TableType};
@@ -249,6 +250,19 @@ impl StatementExecutor {
         create_table: &mut CreateTableExpr,
         partitions: Option<Partitions>,
         query_ctx: QueryContextRef,
+    ) -> Result<TableRef> {
+        self.create_non_logic_table_with_column_ids(create_table, partitions, None, query_ctx)
+            .await
+    }
+
+    /// Creates a non-logical table whose columns have the given ids, or ids assigned
+    /// by the order of columns if `column_ids` is `None`.
+    pub(crate) async fn create_non_logic_table_with_column_ids(
+        &self,
+        create_table: &mut CreateTableExpr,
+        partitions: Option<Partitions>,
+        column_ids: Option<&HashMap<String, ColumnId>>,
+        query_ctx: QueryContextRef,
     ) -> Result<TableRef> {
         let _timer = crate::metrics::DIST_CREATE_TABLE.start_timer();
 
@@ -310,6 +324,9 @@ impl StatementExecutor {
 
         let (partitions, partition_cols) = parse_partitions(create_table, partitions, &query_ctx)?;
         let mut table_info = create_table_info(create_table, partition_cols)?;
+        if let Some(column_ids) = column_ids {
+            set_table_info_column_ids(&mut table_info, column_ids)?;
+        }
 
         let resp = self
             .create_table_procedure(
@@ -1187,10 +1204,179 @@ impl StatementExecutor {
         alter_table: AlterTable,
         query_context: QueryContextRef,
     ) -> Result<Output> {
+        if matches!(
+            alter_table.alter_operation(),
+            AlterTableOperation::SplitPartition { .. } | AlterTableOperation::MergePartition { .. }
+        ) {
+            return self.repartition_table(alter_table, query_context).await;
+        }
+
         let expr = expr_helper::to_alter_table_expr(alter_table, &query_context)?;
         self.alter_table_inner(expr, query_context).await
     }
 
+    /// Splits a partition of the table into several partitions, or merges
+    /// several partitions into one.
+    #[tracing::instrument(skip_all)]
+    async fn repartition_table(
+        &self,
+        alter_table: AlterTable,
+        query_context: QueryContextRef,
+    ) -> Result<Output> {
+        let (catalog_name, schema_name, table_name) =
+            table_idents_to_full_name(alter_table.table_name(), &query_context)
+                .map_err(BoxedError::new)
+                .context(error::ExternalSnafu)?;
+        ensure!(
+            !is_readonly_schema(&schema_name),
+            SchemaReadOnlySnafu {
+                name: schema_name.clone()
+            }
+        );
+
+        let table = self
+            .catalog_manager
+            .table(
+                &catalog_name,
+                &schema_name,
+                &table_name,
+                Some(&query_context),
+            )
+            .await
+            .context(CatalogSnafu)?
+            .with_context(|| TableNotFoundSnafu {
+                table_name: format_full_table_name(&catalog_name, &schema_name, &table_name),
+            })?;
+        let table_info = table.table_info();
+        let table_id = table_info.table_id();
+
+        let column_schemas = table_info.meta.schema.column_schemas();
+        let partition_columns = table_info
+            .meta
+            .partition_key_indices
+            .iter()
+            .map(|i| column_schemas[*i].name.clone())
+            .collect::<Vec<_>>();
+        ensure!(
+            !partition_columns.is_empty(),
+            InvalidPartitionRuleSnafu {
+                reason: format!("table {table_name} is not partitioned"),
+            }
+        );
+        let column_name_and_type = table_info
+            .meta
+            .partition_key_indices
+            .iter()
+            .map(|i| {
+                (
+                    &column_schemas[*i].name,
+                    column_schemas[*i].data_type.clone(),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+        let timezone = query_context.timezone();
+        let convert_exprs = |exprs: &[Expr]| {
+            exprs
+                .iter()
+                .map(|expr| convert_one_expr(expr, &column_name_and_type, &timezone))
+                .collect::<Result<Vec<_>>>()
+        };
+
+        let (from_exprs, into_exprs) = match alter_table.alter_operation() {
+            AlterTableOperation::SplitPartition { from, into } => (
+                convert_exprs(std::slice::from_ref(from))?,
+                convert_exprs(into)?,
+            ),
+            AlterTableOperation::MergePartition { from } => {
+                let from_exprs = convert_exprs(from)?;
+                // The merged partition covers all the partitions to merge.
+                let merged = from_exprs
+                    .iter()
+                    .cloned()
+                    .reduce(|lhs, rhs| {
+                        PartitionExpr::new(Operand::Expr(lhs), RestrictedOp::Or, Operand::Expr(rhs))
+                    })
+                    .context(InvalidPartitionRuleSnafu {
+                        reason: "no partition to merge",
+                    })?;
+                (from_exprs, vec![merged])
+            }
+            operation => {
+                return InvalidSqlSnafu {
+                    err_msg: format!("{operation} is not a repartition operation"),
+                }
+                .fail()
+            }
+        };
+
+        let partitions = self
+            .partition_manager
+            .find_table_partitions(table_id)
+            .await
+            .context(error::FindTablePartitionRuleSnafu {
+                table_name: &table_name,
+            })?;
+        for expr in &from_exprs {
+            ensure!(
+                partitions
+                    .iter()
+                    .any(|partition| partition.partition_expr.as_ref() == Some(expr)),
+                InvalidPartitionRuleSnafu {
+                    reason: format!("partition {expr} doesn't exist in table {table_name}"),
+                }
+            );
+        }
+
+        // Validates the partition rule after repartition.
+        let new_exprs = partitions
+            .into_iter()
+            .filter_map(|partition| partition.partition_expr)
+            .filter(|expr| !from_exprs.contains(expr))
+            .chain(into_exprs.iter().cloned())
+            .collect::<Vec<_>>();
+        MultiDimPartitionRule::try_new(partition_columns, vec![], new_exprs, true)
+            .context(InvalidPartitionSnafu)?;
+
+        let to_json = |exprs: &[PartitionExpr]| {
+            exprs
+                .iter()
+                .map(|expr| expr.as_json_str().context(InvalidPartitionSnafu))
+                .collect::<Result<Vec<_>>>()
+        };
+        let task = RepartitionTableTask {
+            catalog: catalog_name.clone(),
+            schema: schema_name.clone(),
+            table: table_name.clone(),
+            table_id,
+            from_partition_exprs: to_json(&from_exprs)?,
+            into_partition_exprs: to_json(&into_exprs)?,
+        };
+        info!("Repartitioning table {table_name}({table_id}), from: {from_exprs:?}, into: {into_exprs:?}");
+
+        let req = SubmitDdlTaskRequest {
+            query_context,
+            task: DdlTask::new_repartition_table(task),
+        };
+        self.procedure_executor
+            .submit_ddl_task(&ExecutorContext::default(), req)
+            .await
+            .context(error::ExecuteDdlSnafu)?;
+
+        // Invalidates local cache ASAP.
+        self.cache_invalidator
+            .invalidate(
+                &Context::default(),
+                &[
+                    CacheIdent::TableId(table_id),
+                    CacheIdent::TableName(TableName::new(catalog_name, schema_name, table_name)),
+                ],
+            )
+            .await
+            .context(error::InvalidateTableCacheSnafu)?;
+
+        Ok(Output::new_with_affected_rows(0))
+    }
+
     #[tracing::instrument(skip_all)]
     pub async fn alter_table_inner(
         &self,
@@ -1652,6 +1838,35 @@ pub fn verify_alter(
     Ok(true)
 }
 
+/// Sets the ids of the columns in the table info, the regions of the table are created
+/// with the ids.
+fn set_table_info_column_ids(
+    table_info: &mut RawTableInfo,
+    column_ids: &HashMap<String, ColumnId>,
+) -> Result<()> {
+    let meta = &mut table_info.meta;
+    meta.column_ids = meta
+        .schema
+        .column_schemas
+        .iter()
+        .map(|column| {
+            column_ids
+                .get(&column.name)
+                .copied()
+                .context(ColumnNotFoundSnafu { msg: &column.name })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    meta.next_column_id = meta
+        .column_ids
+        .iter()
+        .map(|id| id + 1)
+        .max()
+        .unwrap_or_default()
+        .max(meta.next_column_id);
+
+    Ok(())
+}
+
 pub fn create_table_info(
     create_table: &CreateTableExpr,
     partition_columns: Vec<String>,
@@ -12,14 +12,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
+use common_error::ext::BoxedError;
 use common_query::Output;
 use common_telemetry::tracing;
 use query::parser::QueryStatement;
 use session::context::QueryContextRef;
+use session::table_name::table_idents_to_full_name;
+use snafu::{ensure, OptionExt, ResultExt};
+use sql::ast::{Expr, Ident, ObjectNamePartExt, Value, ValueWithSpan};
+use sql::dialect::Dialect;
+use sql::parser::{ParseOptions, ParserContext};
 use sql::statements::insert::Insert;
 use sql::statements::statement::Statement;
+use sql::statements::update::Update;
+use sqlparser::ast::AssignmentTarget;
+use store_api::mito_engine_options::{APPEND_MODE_KEY, MERGE_MODE_KEY};
+use table::table_reference::TableReference;
 
-use crate::error::Result;
+use crate::error::{ExternalSnafu, InvalidUpdateSnafu, ParseSqlSnafu, Result, UnexpectedSnafu};
 use crate::statement::StatementExecutor;
 
 impl StatementExecutor {
@@ -36,4 +48,206 @@ impl StatementExecutor {
             self.plan_exec(statement, query_ctx).await
         }
     }
+
+    /// Executes `UPDATE` by reading the matched rows and writing them back.
+    ///
+    /// The statement is rewritten to `INSERT INTO t SELECT ... FROM t WHERE ...`.
+    /// Rows are written with the same primary key and time index, so they replace the
+    /// old rows on deduplication. Hence primary key and time index columns can't be
+    /// updated, and tables in append mode are not supported.
+    ///
+    /// Under `merge_mode = 'last_non_null'`, null values don't replace the old values on
+    /// deduplication. So assigning a `NULL` literal is rejected, and an assigned
+    /// expression that evaluates to null keeps the old value of the column.
+    #[tracing::instrument(skip_all)]
+    pub async fn update(&self, update: Box<Update>, query_ctx: QueryContextRef) -> Result<Output> {
+        let (catalog, schema, table_name) =
+            table_idents_to_full_name(update.table_name(), &query_ctx)
+                .map_err(BoxedError::new)
+                .context(ExternalSnafu)?;
+        let table = self
+            .get_table(&TableReference::full(&catalog, &schema, &table_name))
+            .await?;
+        let table_info = table.table_info();
+
+        ensure!(
+            table_info
+                .meta
+                .options
+                .extra_options
+                .get(APPEND_MODE_KEY)
+                .is_none_or(|append_mode| append_mode != "true"),
+            InvalidUpdateSnafu {
+                reason: format!("table `{table_name}` is in append mode, which can't be updated"),
+            }
+        );
+
+        let last_non_null = table_info
+            .meta
+            .options
+            .extra_options
+            .get(MERGE_MODE_KEY)
+            .is_some_and(|merge_mode| merge_mode.eq_ignore_ascii_case("last_non_null"));
+
+        let table_schema = table.schema();
+        let time_index = table_schema.timestamp_column().map(|column| &column.name);
+        let row_key_columns = table_info.meta.row_key_column_names().collect::<Vec<_>>();
+        let mut values = HashMap::with_capacity(update.assignments().len());
+        for assignment in update.assignments() {
+            let AssignmentTarget::ColumnName(name) = &assignment.target else {
+                return UnexpectedSnafu {
+                    violated: "tuple assignment in UPDATE is rejected by parser",
+                }
+                .fail();
+            };
+            let target = name
+                .0
+                .last()
+                .map(|part| part.to_string_unquoted())
+                .unwrap_or_default();
+            let column = table_schema
+                .column_schemas()
+                .iter()
+                .find(|column| column.name == target)
+                .or_else(|| {
+                    table_schema
+                        .column_schemas()
+                        .iter()
+                        .find(|column| column.name.eq_ignore_ascii_case(&target))
+                })
+                .with_context(|| InvalidUpdateSnafu {
+                    reason: format!("column `{target}` not found in table `{table_name}`"),
+                })?;
+            ensure!(
+                !row_key_columns.contains(&&column.name) && time_index != Some(&column.name),
+                InvalidUpdateSnafu {
+                    reason: format!(
+                        "can't update primary key or time index column `{}`",
+                        column.name
+                    ),
+                }
+            );
+            ensure!(
+                !(last_non_null
+                    && matches!(
+                        &assignment.value,
+                        Expr::Value(ValueWithSpan {
+                            value: Value::Null,
+                            ..
+                        })
+                    )),
+                InvalidUpdateSnafu {
+                    reason: format!(
+                        "can't set column `{}` to NULL in table `{table_name}` with merge mode `last_non_null`",
+                        column.name
+                    ),
+                }
+            );
+            ensure!(
+                values
+                    .insert(column.name.as_str(), &assignment.value)
+                    .is_none(),
+                InvalidUpdateSnafu {
+                    reason: format!("column `{}` is assigned more than once", column.name),
+                }
+            );
+        }
+
+        let columns = table_schema
+            .column_schemas()
+            .iter()
+            .map(|column| column.name.as_str())
+            .collect::<Vec<_>>();
+        let sql = build_update_sql(&update, &columns, &values, query_ctx.sql_dialect());
+        let mut statements = ParserContext::create_with_dialect(
+            &sql,
+            query_ctx.sql_dialect(),
+            ParseOptions::default(),
+        )
+        .context(ParseSqlSnafu)?;
+        let Some(Statement::Insert(insert)) = statements.pop() else {
+            return UnexpectedSnafu {
+                violated: format!("UPDATE is rewritten to an invalid statement: {sql}"),
+            }
+            .fail();
+        };
+
+        self.insert(insert, query_ctx).await
+    }
+}
+
+/// Builds the `INSERT INTO ... SELECT` statement of `update`. Every column of the table
+/// is selected, either as is or as the assigned expression in `values`.
+fn build_update_sql(
+    update: &Update,
+    columns: &[&str],
+    values: &HashMap<&str, &Expr>,
+    dialect: &dyn Dialect,
+) -> String {
+    let quote = if dialect.is_delimited_identifier_start('"') {
+        '"'
+    } else {
+        '`'
+    };
+    let column_list = columns
+        .iter()
+        .map(|column| Ident::with_quote(quote, *column).to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let projection = columns
+        .iter()
+        .map(|column| match values.get(column) {
+            Some(expr) => format!("{expr}"),
+            None => Ident::with_quote(quote, *column).to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut sql = format!(
+        "INSERT INTO {} ({column_list}) SELECT {projection} FROM {}",
+        update.table_name(),
+        update.table()
+    );
+    if let Some(selection) = update.selection() {
+        sql.push_str(&format!(" WHERE {selection}"));
+    }
+    sql
+}
+
+#[cfg(test)]
+mod tests {
+    use sql::dialect::{GreptimeDbDialect, MySqlDialect};
+
+    use super::*;
+
+    fn parse_update(sql: &str) -> Update {
+        let mut statements =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap();
+        let Some(Statement::Update(update)) = statements.pop() else {
+            unreachable!()
+        };
+        *update
+    }
+
+    #[test]
+    fn test_build_update_sql() {
+        let update = parse_update("UPDATE monitor AS m SET cpu = m.cpu * 2 WHERE host = 'h1'");
+        let value = &update.assignments()[0].value;
+        let values = HashMap::from([("cpu", value)]);
+        let columns = ["host", "ts", "cpu", "memory"];
+
+        assert_eq!(
+            r#"INSERT INTO monitor ("host", "ts", "cpu", "memory") SELECT "host", "ts", m.cpu * 2, "memory" FROM monitor AS m WHERE host = 'h1'"#,
+            build_update_sql(&update, &columns, &values, &GreptimeDbDialect {})
+        );
+
+        let update = parse_update("UPDATE monitor SET memory = NULL");
+        let value = &update.assignments()[0].value;
+        let values = HashMap::from([("memory", value)]);
+        assert_eq!(
+            "INSERT INTO monitor (`host`, `ts`, `cpu`, `memory`) SELECT `host`, `ts`, `cpu`, NULL FROM monitor",
+            build_update_sql(&update, &columns, &values, &MySqlDialect {})
+        );
+    }
 }
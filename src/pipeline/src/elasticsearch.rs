@@ -0,0 +1,773 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Translates Elasticsearch ingest pipelines into GreptimeDB pipelines.
+//!
+//! Supported processors are `set`, `rename`, `remove`, `grok`, `date`, `convert`,
+//! `lowercase`, `uppercase`, `json` and `split`. Steps that can't be translated
+//! faithfully, including conditional (`if`) steps and `on_failure` handlers, are
+//! reported instead of being dropped.
+//!
+//! Field names are used as top-level keys, dotted names are not expanded into
+//! nested objects.
+
+use serde_json::{Map, Value as JsonValue};
+use snafu::{ensure, OptionExt};
+use yaml_rust::yaml::Hash;
+use yaml_rust::{Yaml, YamlEmitter};
+
+use crate::error::{InvalidIngestPipelineSnafu, Result, UnsupportedIngestProcessorsSnafu};
+use crate::etl::processor::date::{DEFAULT_FORMATS, PROCESSOR_DATE};
+use crate::etl::processor::epoch::PROCESSOR_EPOCH;
+use crate::etl::processor::grok::PROCESSOR_GROK;
+use crate::etl::processor::json_parse::PROCESSOR_JSON_PARSE;
+use crate::etl::processor::letter::PROCESSOR_LETTER;
+use crate::etl::processor::vrl_processor::PROCESSOR_VRL;
+
+/// The timestamp column if the pipeline has no single date processor.
+const DEFAULT_TIMESTAMP_FIELD: &str = "greptime_timestamp";
+/// The default target field of the date processor of Elasticsearch.
+const DEFAULT_DATE_TARGET: &str = "@timestamp";
+/// Options that don't affect the processed documents.
+const IGNORED_OPTIONS: &[&str] = &["tag", "description"];
+
+type StepResult<T> = std::result::Result<T, String>;
+
+/// Translates the Elasticsearch ingest pipeline definition in `pipeline` into
+/// a GreptimeDB pipeline in yaml.
+pub fn translate_ingest_pipeline(pipeline: &JsonValue) -> Result<String> {
+    let pipeline = pipeline.as_object().context(InvalidIngestPipelineSnafu {
+        reason: "pipeline must be an object",
+    })?;
+
+    let mut translator = Translator::default();
+    if let Some(processors) = pipeline.get("processors") {
+        let processors = processors.as_array().context(InvalidIngestPipelineSnafu {
+            reason: "processors must be an array",
+        })?;
+        for (i, processor) in processors.iter().enumerate() {
+            translator.translate(i, processor);
+        }
+    }
+    if pipeline.contains_key("on_failure") {
+        translator
+            .unsupported
+            .push("pipeline: on_failure handlers are not supported".to_string());
+    }
+    ensure!(
+        translator.unsupported.is_empty(),
+        UnsupportedIngestProcessorsSnafu {
+            processors: translator.unsupported,
+        }
+    );
+
+    let mut doc = Hash::new();
+    doc.insert(ystr("version"), Yaml::Integer(2));
+    if let Some(description) = pipeline.get("description").and_then(JsonValue::as_str) {
+        doc.insert(ystr("description"), ystr(description));
+    }
+    // Uses the output of the only date processor as the time index, otherwise the
+    // ingestion time.
+    if translator.timestamps != 1 {
+        translator.push(
+            PROCESSOR_VRL,
+            vec![(
+                "source",
+                ystr(format!("{} = now()\n.", vrl_path(DEFAULT_TIMESTAMP_FIELD))),
+            )],
+        );
+        doc.insert(
+            ystr("transform"),
+            Yaml::Array(vec![yaml_map(vec![
+                ("field", ystr(DEFAULT_TIMESTAMP_FIELD)),
+                ("type", ystr("time")),
+                ("index", ystr("timestamp")),
+            ])]),
+        );
+    }
+    doc.insert(ystr("processors"), Yaml::Array(translator.processors));
+
+    let mut out = String::new();
+    YamlEmitter::new(&mut out)
+        .dump(&Yaml::Hash(doc))
+        .map_err(|e| {
+            InvalidIngestPipelineSnafu {
+                reason: format!("failed to emit pipeline: {e:?}"),
+            }
+            .build()
+        })?;
+    Ok(out)
+}
+
+#[derive(Default)]
+struct Translator {
+    processors: Vec<Yaml>,
+    /// The number of translated date processors.
+    timestamps: usize,
+    unsupported: Vec<String>,
+}
+
+/// The options of a processor, which tracks the options used by the translation.
+struct Step<'a> {
+    options: &'a Map<String, JsonValue>,
+    used: Vec<&'a str>,
+}
+
+impl<'a> Step<'a> {
+    fn get(&mut self, name: &'a str) -> Option<&'a JsonValue> {
+        self.used.push(name);
+        self.options.get(name)
+    }
+
+    fn string(&mut self, name: &'a str) -> StepResult<Option<String>> {
+        match self.get(name) {
+            None => Ok(None),
+            Some(JsonValue::String(s)) => Ok(Some(s.clone())),
+            Some(_) => Err(format!("`{name}` must be a string")),
+        }
+    }
+
+    fn required_string(&mut self, name: &'a str) -> StepResult<String> {
+        self.string(name)?
+            .ok_or_else(|| format!("`{name}` is required"))
+    }
+
+    /// Returns a string or an array of strings as a list.
+    fn strings(&mut self, name: &'a str) -> StepResult<Option<Vec<String>>> {
+        match self.get(name) {
+            None => Ok(None),
+            Some(JsonValue::String(s)) => Ok(Some(vec![s.clone()])),
+            Some(JsonValue::Array(values)) => values
+                .iter()
+                .map(|v| v.as_str().map(|s| s.to_string()))
+                .collect::<Option<Vec<_>>>()
+                .map(Some)
+                .ok_or_else(|| format!("`{name}` must be an array of strings")),
+            Some(_) => Err(format!("`{name}` must be a string or an array of strings")),
+        }
+    }
+
+    fn bool(&mut self, name: &'a str, default: bool) -> StepResult<bool> {
+        match self.get(name) {
+            None => Ok(default),
+            Some(JsonValue::Bool(b)) => Ok(*b),
+            Some(_) => Err(format!("`{name}` must be a boolean")),
+        }
+    }
+
+    fn unused(&self) -> Vec<&'a str> {
+        self.options
+            .keys()
+            .map(String::as_str)
+            .filter(|k| !self.used.contains(k) && !IGNORED_OPTIONS.iter().any(|o| o == k))
+            .collect()
+    }
+}
+
+impl Translator {
+    fn translate(&mut self, index: usize, processor: &JsonValue) {
+        let Some((kind, options)) = processor
+            .as_object()
+            .filter(|p| p.len() == 1)
+            .and_then(|p| p.iter().next())
+        else {
+            self.unsupported.push(format!(
+                "[{index}]: processor must be an object with a single key"
+            ));
+            return;
+        };
+        let Some(options) = options.as_object() else {
+            self.unsupported
+                .push(format!("[{index}] {kind}: options must be an object"));
+            return;
+        };
+
+        let mut step = Step {
+            options,
+            used: vec![],
+        };
+        let result = match kind.as_str() {
+            "set" => self.set(&mut step),
+            "rename" => self.rename(&mut step),
+            "remove" => self.remove(&mut step),
+            "convert" => self.convert(&mut step),
+            "lowercase" => self.letter(&mut step, "lower"),
+            "uppercase" => self.letter(&mut step, "upper"),
+            "grok" => self.grok(&mut step),
+            "date" => self.date(&mut step),
+            "json" => self.json(&mut step),
+            "split" => self.split(&mut step),
+            _ => Err("processor is not supported".to_string()),
+        };
+
+        match result {
+            Ok(()) => {
+                for option in step.unused() {
+                    self.unsupported.push(format!(
+                        "[{index}] {kind}: option `{option}` is not supported"
+                    ));
+                }
+            }
+            Err(reason) => self.unsupported.push(format!("[{index}] {kind}: {reason}")),
+        }
+    }
+
+    fn push(&mut self, kind: &str, options: Vec<(&str, Yaml)>) {
+        let mut processor = Hash::new();
+        processor.insert(ystr(kind), yaml_map(options));
+        self.processors.push(Yaml::Hash(processor));
+    }
+
+    fn push_vrl(&mut self, source: String) {
+        self.push(PROCESSOR_VRL, vec![("source", ystr(format!("{source}.")))]);
+    }
+
+    /// Returns whether missing fields are ignored. `ignore_failure` is treated
+    /// as `ignore_missing` as missing fields are the common failures.
+    fn ignore_missing(step: &mut Step) -> StepResult<bool> {
+        Ok(step.bool("ignore_missing", false)? || step.bool("ignore_failure", false)?)
+    }
+
+    fn set(&mut self, step: &mut Step) -> StepResult<()> {
+        let path = vrl_path(&step.required_string("field")?);
+        let value = match (step.get("value"), step.string("copy_from")?) {
+            (Some(value), None) => vrl_value(value)?,
+            (None, Some(copy_from)) => vrl_path(&copy_from),
+            _ => return Err("exactly one of `value` and `copy_from` is required".to_string()),
+        };
+        let override_value = step.bool("override", true)?;
+        let ignore_empty_value = step.bool("ignore_empty_value", false)?;
+        step.bool("ignore_failure", false)?;
+
+        let mut conditions = vec![];
+        if !override_value {
+            conditions.push(format!("{path} == null"));
+        }
+        if ignore_empty_value {
+            conditions.push("value != null && value != \"\"".to_string());
+        }
+        let source = if conditions.is_empty() {
+            format!("value = {value}\n{path} = value\n")
+        } else {
+            format!(
+                "value = {value}\nif {} {{\n  {path} = value\n}}\n",
+                conditions.join(" && ")
+            )
+        };
+        self.push_vrl(source);
+        Ok(())
+    }
+
+    fn rename(&mut self, step: &mut Step) -> StepResult<()> {
+        let field = step.required_string("field")?;
+        let target = vrl_path(&step.required_string("target_field")?);
+        let ignore_missing = Self::ignore_missing(step)?;
+        step.bool("override", false)?;
+
+        let path = vrl_path(&field);
+        let source = if ignore_missing {
+            format!("if exists({path}) {{\n  {target} = del({path})\n}}\n")
+        } else {
+            format!("{}{target} = del({path})\n", assert_exists(&field))
+        };
+        self.push_vrl(source);
+        Ok(())
+    }
+
+    fn remove(&mut self, step: &mut Step) -> StepResult<()> {
+        let fields = step
+            .strings("field")?
+            .ok_or_else(|| "`field` is required".to_string())?;
+        let ignore_missing = Self::ignore_missing(step)?;
+
+        let mut source = String::new();
+        for field in fields {
+            if !ignore_missing {
+                source.push_str(&assert_exists(&field));
+            }
+            source.push_str(&format!("del({})\n", vrl_path(&field)));
+        }
+        self.push_vrl(source);
+        Ok(())
+    }
+
+    fn convert(&mut self, step: &mut Step) -> StepResult<()> {
+        let field = step.required_string("field")?;
+        let function = match step.required_string("type")?.as_str() {
+            "integer" | "long" => "to_int",
+            "float" | "double" => "to_float",
+            "string" => "to_string",
+            "boolean" => "to_bool",
+            ty => return Err(format!("type `{ty}` is not supported")),
+        };
+        let target = vrl_path(
+            &step
+                .string("target_field")?
+                .unwrap_or_else(|| field.clone()),
+        );
+        let ignore_missing = Self::ignore_missing(step)?;
+
+        let path = vrl_path(&field);
+        let source = if ignore_missing {
+            format!("if exists({path}) {{\n  {target} = {function}!({path})\n}}\n")
+        } else {
+            format!("{}{target} = {function}!({path})\n", assert_exists(&field))
+        };
+        self.push_vrl(source);
+        Ok(())
+    }
+
+    fn letter(&mut self, step: &mut Step, method: &str) -> StepResult<()> {
+        let field = step.required_string("field")?;
+        let target = step.string("target_field")?;
+        let ignore_missing = Self::ignore_missing(step)?;
+
+        self.push(
+            PROCESSOR_LETTER,
+            vec![
+                ("field", field_spec(&field, target.as_deref())),
+                ("method", ystr(method)),
+                ("ignore_missing", Yaml::Boolean(ignore_missing)),
+            ],
+        );
+        Ok(())
+    }
+
+    fn grok(&mut self, step: &mut Step) -> StepResult<()> {
+        let field = step.required_string("field")?;
+        let patterns = step
+            .strings("patterns")?
+            .ok_or_else(|| "`patterns` is required".to_string())?;
+        let ignore_missing = Self::ignore_missing(step)?;
+        // Options only for debugging or the legacy pattern set.
+        step.get("trace_match");
+        step.get("ecs_compatibility");
+
+        let mut options = vec![
+            ("field", ystr(field)),
+            (
+                "patterns",
+                Yaml::Array(patterns.into_iter().map(ystr).collect()),
+            ),
+            ("ignore_missing", Yaml::Boolean(ignore_missing)),
+        ];
+        if let Some(definitions) = step.get("pattern_definitions") {
+            let definitions = definitions
+                .as_object()
+                .ok_or_else(|| "`pattern_definitions` must be an object".to_string())?;
+            let mut map = Hash::new();
+            for (name, definition) in definitions {
+                let definition = definition
+                    .as_str()
+                    .ok_or_else(|| format!("pattern definition `{name}` must be a string"))?;
+                map.insert(ystr(name.as_str()), ystr(definition));
+            }
+            options.push(("pattern_definitions", Yaml::Hash(map)));
+        }
+        self.push(PROCESSOR_GROK, options);
+        Ok(())
+    }
+
+    fn date(&mut self, step: &mut Step) -> StepResult<()> {
+        let field = step.required_string("field")?;
+        let target = step
+            .string("target_field")?
+            .unwrap_or_else(|| DEFAULT_DATE_TARGET.to_string());
+        let formats = step
+            .strings("formats")?
+            .ok_or_else(|| "`formats` is required".to_string())?;
+        let timezone = step.string("timezone")?;
+        if let Some(locale) = step.string("locale")? {
+            if !locale.to_lowercase().starts_with("en") {
+                return Err(format!("locale `{locale}` is not supported"));
+            }
+        }
+        // The timestamp is stored as a time index rather than a formatted string.
+        step.get("output_format");
+        step.bool("ignore_failure", false)?;
+
+        let field = field_spec(&field, Some(target.as_str()));
+        // Epoch formats are handled by the epoch processor.
+        match formats.as_slice() {
+            [format] if format == "UNIX" || format == "UNIX_MS" => {
+                let resolution = if format == "UNIX" {
+                    "second"
+                } else {
+                    "millisecond"
+                };
+                self.push(
+                    PROCESSOR_EPOCH,
+                    vec![("field", field), ("resolution", ystr(resolution))],
+                );
+                self.timestamps += 1;
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        let mut strftime_formats = vec![];
+        for format in &formats {
+            match format.as_str() {
+                "ISO8601" => strftime_formats.extend(DEFAULT_FORMATS.iter().map(|f| f.to_string())),
+                "UNIX" | "UNIX_MS" | "TAI64N" => {
+                    return Err(format!(
+                        "format `{format}` can only be used as the only format"
+                    ))
+                }
+                _ => strftime_formats.push(java_to_strftime(format)?),
+            }
+        }
+
+        let mut options = vec![
+            ("field", field),
+            (
+                "formats",
+                Yaml::Array(strftime_formats.into_iter().map(ystr).collect()),
+            ),
+        ];
+        if let Some(timezone) = timezone {
+            options.push(("timezone", ystr(timezone)));
+        }
+        self.push(PROCESSOR_DATE, options);
+        self.timestamps += 1;
+        Ok(())
+    }
+
+    fn json(&mut self, step: &mut Step) -> StepResult<()> {
+        let field = step.required_string("field")?;
+        let target = step.string("target_field")?;
+        let add_to_root = step.bool("add_to_root", false)?;
+        step.bool("ignore_failure", false)?;
+
+        if add_to_root {
+            if target.is_some() {
+                return Err("`target_field` can't be used with `add_to_root`".to_string());
+            }
+            self.push_vrl(format!(
+                ". = merge(., object!(parse_json!(string!({}))))\n",
+                vrl_path(&field)
+            ));
+        } else {
+            self.push(
+                PROCESSOR_JSON_PARSE,
+                vec![("field", field_spec(&field, target.as_deref()))],
+            );
+        }
+        Ok(())
+    }
+
+    fn split(&mut self, step: &mut Step) -> StepResult<()> {
+        let field = step.required_string("field")?;
+        let separator = step.required_string("separator")?;
+        let target = vrl_path(
+            &step
+                .string("target_field")?
+                .unwrap_or_else(|| field.clone()),
+        );
+        let ignore_missing = Self::ignore_missing(step)?;
+        // Trailing empty strings are always kept.
+        step.bool("preserve_trailing", false)?;
+
+        let path = vrl_path(&field);
+        let regex = format!("r'{}'", separator.replace('\'', "\\'"));
+        let source = if ignore_missing {
+            format!("if exists({path}) {{\n  {target} = split(string!({path}), {regex})\n}}\n")
+        } else {
+            format!("{target} = split(string!({path}), {regex})\n")
+        };
+        self.push_vrl(source);
+        Ok(())
+    }
+}
+
+fn ystr(s: impl Into<String>) -> Yaml {
+    Yaml::String(s.into())
+}
+
+fn yaml_map(entries: Vec<(&str, Yaml)>) -> Yaml {
+    let mut map = Hash::new();
+    for (k, v) in entries {
+        map.insert(ystr(k), v);
+    }
+    Yaml::Hash(map)
+}
+
+/// Returns the field of processors, in the form of `input, target`.
+fn field_spec(field: &str, target: Option<&str>) -> Yaml {
+    match target {
+        Some(target) if target != field => ystr(format!("{field}, {target}")),
+        _ => ystr(field),
+    }
+}
+
+/// Returns the VRL path of the top-level field, e.g. `."user.name"`.
+fn vrl_path(field: &str) -> String {
+    format!(".{}", JsonValue::String(field.to_string()))
+}
+
+/// Returns a VRL raw string literal, which is not templated.
+fn vrl_string(s: &str) -> String {
+    format!("s'{}'", s.replace('\'', "\\'"))
+}
+
+fn assert_exists(field: &str) -> String {
+    format!(
+        "assert!(exists({}), {})\n",
+        vrl_path(field),
+        vrl_string(&format!("field {field} doesn't exist"))
+    )
+}
+
+/// Translates the value of the set processor, which may contain mustache
+/// templates of fields, e.g. `{{host.name}}`.
+fn vrl_value(value: &JsonValue) -> StepResult<String> {
+    let JsonValue::String(s) = value else {
+        return Ok(vrl_literal(value));
+    };
+    let trimmed = s.trim_start_matches('{').trim_end_matches('}');
+    if trimmed == "_ingest.timestamp" && s.starts_with("{{") {
+        return Ok("now()".to_string());
+    }
+    if !s.contains("{{") {
+        return Ok(vrl_string(s));
+    }
+
+    let mut parts = vec![];
+    let mut rest = s.as_str();
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            parts.push(vrl_string(&rest[..start]));
+        }
+        let triple = rest[start..].starts_with("{{{");
+        let (open, close) = if triple { (3, "}}}") } else { (2, "}}") };
+        let after = &rest[start + open..];
+        let end = after
+            .find(close)
+            .ok_or_else(|| format!("unclosed template in `{s}`"))?;
+        let name = after[..end].trim();
+        if name.starts_with("_ingest.") {
+            return Err(format!("template `{name}` is not supported"));
+        }
+        parts.push(format!("(to_string({}) ?? \"\")", vrl_path(name)));
+        rest = &after[end + close.len()..];
+    }
+    if !rest.is_empty() {
+        parts.push(vrl_string(rest));
+    }
+    Ok(parts.join(" + "))
+}
+
+fn vrl_literal(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(s) => vrl_string(s),
+        JsonValue::Array(values) => format!(
+            "[{}]",
+            values
+                .iter()
+                .map(vrl_literal)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        JsonValue::Object(map) => format!(
+            "{{{}}}",
+            map.iter()
+                .map(|(k, v)| format!("{}: {}", JsonValue::String(k.clone()), vrl_literal(v)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        // Null, booleans and numbers are the same in VRL and JSON.
+        _ => value.to_string(),
+    }
+}
+
+/// Translates a Java `DateTimeFormatter` pattern into a strftime format.
+fn java_to_strftime(pattern: &str) -> StepResult<String> {
+    let mut result = String::new();
+    let chars = pattern.chars().collect::<Vec<_>>();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\'' {
+            // Quoted literal, where `''` is a single quote.
+            let mut j = i + 1;
+            if chars.get(j) == Some(&'\'') {
+                result.push('\'');
+                i += 2;
+                continue;
+            }
+            while j < chars.len() && chars[j] != '\'' {
+                push_literal(&mut result, chars[j]);
+                j += 1;
+            }
+            i = j + 1;
+            continue;
+        }
+        if !c.is_ascii_alphabetic() {
+            push_literal(&mut result, c);
+            i += 1;
+            continue;
+        }
+
+        let mut n = 1;
+        while chars.get(i + n) == Some(&c) {
+            n += 1;
+        }
+        let spec = match (c, n) {
+            ('y' | 'u', 2) => "%y",
+            ('y' | 'u', _) => "%Y",
+            ('M', 1 | 2) => "%m",
+            ('M', 3) => "%b",
+            ('M', _) => "%B",
+            ('d', _) => "%d",
+            ('H' | 'k', _) => "%H",
+            ('h' | 'K', _) => "%I",
+            ('m', _) => "%M",
+            ('s', _) => "%S",
+            ('S', 3) => "%3f",
+            ('S', 6) => "%6f",
+            ('S', 9) => "%9f",
+            ('a', _) => "%p",
+            ('E', 1..=3) => "%a",
+            ('E', _) => "%A",
+            ('Z' | 'X' | 'x', 1 | 2) => "%z",
+            ('Z' | 'X' | 'x', _) => "%:z",
+            _ => {
+                return Err(format!(
+                    "pattern `{}` of date format `{pattern}` is not supported",
+                    c.to_string().repeat(n)
+                ))
+            }
+        };
+        result.push_str(spec);
+        i += n;
+    }
+    Ok(result)
+}
+
+fn push_literal(result: &mut String, c: char) {
+    if c == '%' {
+        result.push_str("%%");
+    } else {
+        result.push(c);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{parse, Content};
+
+    fn translate(pipeline: JsonValue) -> String {
+        let yaml = translate_ingest_pipeline(&pipeline).unwrap();
+        // The translated pipeline must be valid.
+        parse(&Content::Yaml(&yaml)).unwrap();
+        yaml
+    }
+
+    #[test]
+    fn test_java_to_strftime() {
+        assert_eq!(
+            "%d/%b/%Y:%H:%M:%S %z",
+            java_to_strftime("dd/MMM/yyyy:HH:mm:ss Z").unwrap()
+        );
+        assert_eq!(
+            "%Y-%m-%dT%H:%M:%S.%3f%:z",
+            java_to_strftime("yyyy-MM-dd'T'HH:mm:ss.SSSXXX").unwrap()
+        );
+        java_to_strftime("yyyy-MM-dd G").unwrap_err();
+    }
+
+    #[test]
+    fn test_vrl_value() {
+        assert_eq!("s'it\\'s'", vrl_value(&json!("it's")).unwrap());
+        assert_eq!(
+            "now()",
+            vrl_value(&json!("{{{_ingest.timestamp}}}")).unwrap()
+        );
+        assert_eq!(
+            "s'host: ' + (to_string(.\"host.name\") ?? \"\")",
+            vrl_value(&json!("host: {{host.name}}")).unwrap()
+        );
+        assert_eq!(
+            "{\"a\": [1, true, null]}",
+            vrl_value(&json!({"a": [1, true, null]})).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_translate() {
+        let yaml = translate(json!({
+            "description": "nginx access logs",
+            "processors": [
+                {"grok": {
+                    "field": "message",
+                    "patterns": ["%{IPORHOST:client_ip} %{WORD:method} %{NUMBER:status:int} %{GREEDYDATA:rest}"],
+                    "pattern_definitions": {"METHOD": "GET|POST"}
+                }},
+                {"set": {"field": "service", "value": "nginx"}},
+                {"set": {"field": "event.ingested", "value": "{{_ingest.timestamp}}", "override": false}},
+                {"rename": {"field": "rest", "target_field": "extra", "ignore_missing": true}},
+                {"remove": {"field": ["message"], "tag": "cleanup"}},
+                {"convert": {"field": "status", "type": "string", "target_field": "status_text"}},
+                {"lowercase": {"field": "method"}},
+                {"json": {"field": "extra", "target_field": "extra_json", "ignore_failure": true}},
+                {"split": {"field": "service", "separator": "\\s+", "target_field": "tags"}},
+                {"date": {"field": "time", "formats": ["ISO8601", "dd/MMM/yyyy:HH:mm:ss Z"]}}
+            ]
+        }));
+        assert!(yaml.contains("description: nginx access logs"));
+        // The only date processor is used as the time index.
+        assert!(!yaml.contains("transform"));
+
+        let yaml = translate(json!({
+            "processors": [
+                {"date": {"field": "ts", "formats": ["UNIX_MS"]}},
+                {"date": {"field": "ts2", "formats": ["UNIX"], "target_field": "ts2"}}
+            ]
+        }));
+        assert!(yaml.contains(DEFAULT_TIMESTAMP_FIELD));
+
+        translate(json!({"processors": []}));
+    }
+
+    #[test]
+    fn test_translate_unsupported() {
+        let err = translate_ingest_pipeline(&json!({
+            "processors": [
+                {"set": {"field": "a", "value": 1}},
+                {"script": {"source": "ctx.a = 1"}},
+                {"set": {"field": "b", "value": 1, "if": "ctx.a == 1"}},
+                {"convert": {"field": "c", "type": "ip"}}
+            ],
+            "on_failure": [{"set": {"field": "error", "value": "{{_ingest.on_failure_message}}"}}]
+        }))
+        .unwrap_err();
+        let crate::error::Error::UnsupportedIngestProcessors { processors, .. } = err else {
+            panic!("unexpected error: {err:?}");
+        };
+        assert_eq!(
+            vec![
+                "[1] script: processor is not supported",
+                "[2] set: option `if` is not supported",
+                "[3] convert: type `ip` is not supported",
+                "pipeline: on_failure handlers are not supported",
+            ],
+            processors
+        );
+
+        translate_ingest_pipeline(&json!({"processors": {}})).unwrap_err();
+    }
+}
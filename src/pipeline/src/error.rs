@@ -354,6 +354,100 @@ pub enum Error {
         #[snafu(implicit)]
         location: Location,
     },
+    #[snafu(display("Grok pattern definition not found: {name}"))]
+    GrokPatternNotFound {
+        name: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Grok pattern definition {name} references itself"))]
+    GrokRecursivePattern {
+        name: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Invalid type {ty} of grok capture {field}, expect one of int, long, float, double, boolean, string"))]
+    GrokInvalidCaptureType {
+        field: String,
+        ty: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Failed to cast grok capture {field} with value {value} to {ty}"))]
+    GrokCastCapture {
+        field: String,
+        value: String,
+        ty: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("No grok pattern matches the value of field {field}"))]
+    GrokNoMatchingPattern {
+        field: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Separator '{name}' of kv processor must not be empty"))]
+    KvEmptySeparator {
+        name: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Invalid syslog message '{input}': {reason}"))]
+    SyslogInvalidMessage {
+        input: String,
+        reason: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Database of geoip processor is required"))]
+    GeoIpDatabaseRequired {
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Failed to open geoip database {path}"))]
+    GeoIpOpenDatabase {
+        path: String,
+        #[snafu(source)]
+        error: maxminddb::MaxMindDBError,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Property {property} is not supported by geoip database {database_type}"))]
+    GeoIpInvalidProperty {
+        property: String,
+        database_type: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Invalid ip address: {value}"))]
+    GeoIpInvalidIp {
+        value: String,
+        #[snafu(source)]
+        error: std::net::AddrParseError,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Failed to lookup ip address {ip} in geoip database"))]
+    GeoIpLookup {
+        ip: std::net::IpAddr,
+        #[snafu(source)]
+        error: maxminddb::MaxMindDBError,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Invalid Elasticsearch ingest pipeline: {reason}"))]
+    InvalidIngestPipeline {
+        reason: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Unsupported Elasticsearch ingest processors: {}", processors.join("; ")))]
+    UnsupportedIngestProcessors {
+        processors: Vec<String>,
+        #[snafu(implicit)]
+        location: Location,
+    },
     #[snafu(display("Invalid method: {s}"))]
     UrlEncodingInvalidMethod {
         s: String,
@@ -609,6 +703,28 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("No previous version of pipeline {} to roll back to", name))]
+    NoPreviousPipelineVersion {
+        name: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Invalid pipeline test case: {}", reason))]
+    InvalidPipelineTestCase {
+        reason: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Pipeline test case `{}` failed: {}", name, reason))]
+    PipelineTestCaseFailed {
+        name: String,
+        reason: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
     #[snafu(display(
         "Multiple pipelines with different schemas found, but none under current schema. Please replicate one of them or delete until only one schema left. schemas: {}",
         schemas
@@ -791,11 +907,15 @@ impl ErrorExt for Error {
             InsertPipeline { source, .. } => source.status_code(),
             CollectRecords { source, .. } => source.status_code(),
             PipelineNotFound { .. }
+            | NoPreviousPipelineVersion { .. }
+            | InvalidPipelineTestCase { .. }
+            | PipelineTestCaseFailed { .. }
             | InvalidPipelineVersion { .. }
             | InvalidCustomTimeIndex { .. }
             | TimeIndexMustBeNonNull { .. } => StatusCode::InvalidArguments,
             MultiPipelineWithDiffSchema { .. } | ValueMustBeMap { .. } => StatusCode::IllegalState,
             BuildDfLogicalPlan { .. } | RecordBatchLenNotMatch { .. } => StatusCode::Internal,
+            GeoIpLookup { .. } => StatusCode::Internal,
             ExecuteInternalStatement { source, .. } => source.status_code(),
             DataFrame { source, .. } => source.status_code(),
             Catalog { source, .. } => source.status_code(),
@@ -848,6 +968,19 @@ impl ErrorExt for Error {
             | RegexNamedGroupNotFound { .. }
             | RegexNoValidField { .. }
             | RegexNoValidPattern { .. }
+            | GrokPatternNotFound { .. }
+            | GrokRecursivePattern { .. }
+            | GrokInvalidCaptureType { .. }
+            | GrokCastCapture { .. }
+            | GrokNoMatchingPattern { .. }
+            | KvEmptySeparator { .. }
+            | SyslogInvalidMessage { .. }
+            | GeoIpDatabaseRequired { .. }
+            | GeoIpOpenDatabase { .. }
+            | GeoIpInvalidProperty { .. }
+            | GeoIpInvalidIp { .. }
+            | InvalidIngestPipeline { .. }
+            | UnsupportedIngestProcessors { .. }
             | UrlEncodingInvalidMethod { .. }
             | DigestPatternInvalid { .. }
             | TransformOnFailureInvalidValue { .. }
@@ -16,14 +16,17 @@
 pub mod ctx_req;
 pub mod field;
 pub mod processor;
+pub mod test_case;
 pub mod transform;
 pub mod value;
 
-use api::v1::Row;
+use api::v1::helper::time_index_column_schema;
+use api::v1::{ColumnDataType, Row};
 use common_time::timestamp::TimeUnit;
 use itertools::Itertools;
 use processor::{Processor, Processors};
 use snafu::{ensure, OptionExt, ResultExt};
+use test_case::PipelineTestCase;
 use transform::Transforms;
 use vrl::core::Value as VrlValue;
 use yaml_rust::{Yaml, YamlLoader};
@@ -45,6 +48,7 @@ const TRANSFORM: &str = "transform";
 const TRANSFORMS: &str = "transforms";
 const DISPATCHER: &str = "dispatcher";
 const TABLESUFFIX: &str = "table_suffix";
+const TESTS: &str = "tests";
 
 pub enum Content<'a> {
     Json(&'a str),
@@ -122,6 +126,12 @@ pub fn parse(input: &Content) -> Result<Pipeline> {
                 None
             };
 
+            let test_cases = if !doc[TESTS].is_badvalue() {
+                test_case::parse_test_cases(&doc[TESTS])?
+            } else {
+                vec![]
+            };
+
             Ok(Pipeline {
                 doc_version,
                 description,
@@ -129,6 +139,7 @@ pub fn parse(input: &Content) -> Result<Pipeline> {
                 transformer,
                 dispatcher,
                 tablesuffix,
+                test_cases,
             })
         }
         Content::Json(_) => unimplemented!(),
@@ -191,6 +202,7 @@ pub struct Pipeline {
     dispatcher: Option<Dispatcher>,
     transformer: TransformerMode,
     tablesuffix: Option<TableSuffixTemplate>,
+    test_cases: Vec<PipelineTestCase>,
 }
 
 #[derive(Debug, Clone)]
@@ -335,6 +347,34 @@ impl Pipeline {
         &self.transformer
     }
 
+    pub fn test_cases(&self) -> &[PipelineTestCase] {
+        &self.test_cases
+    }
+
+    /// Returns the initial schema of the rows produced by the pipeline.
+    pub fn schema_info(&self) -> SchemaInfo {
+        match &self.transformer {
+            TransformerMode::GreptimeTransformer(greptime_transformer) => {
+                SchemaInfo::from_schema_list(greptime_transformer.schemas().clone())
+            }
+            TransformerMode::AutoTransform(ts_name, timeunit) => {
+                let timeunit = match timeunit {
+                    TimeUnit::Second => ColumnDataType::TimestampSecond,
+                    TimeUnit::Millisecond => ColumnDataType::TimestampMillisecond,
+                    TimeUnit::Microsecond => ColumnDataType::TimestampMicrosecond,
+                    TimeUnit::Nanosecond => ColumnDataType::TimestampNanosecond,
+                };
+
+                let mut schema_info = SchemaInfo::default();
+                schema_info
+                    .schema
+                    .push(time_index_column_schema(ts_name, timeunit));
+
+                schema_info
+            }
+        }
+    }
+
     // the method is for test purpose
     pub fn schemas(&self) -> Option<&Vec<greptime_proto::v1::ColumnSchema>> {
         match &self.transformer {
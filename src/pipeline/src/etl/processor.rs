@@ -20,15 +20,20 @@ pub mod digest;
 pub mod dissect;
 pub mod epoch;
 pub mod filter;
+pub mod geoip;
+pub mod grok;
 pub mod gsub;
 pub mod join;
 pub mod json_parse;
 pub mod json_path;
+pub mod kv;
 pub mod letter;
 pub mod regex;
 pub mod select;
 pub mod simple_extract;
+pub mod syslog;
 pub mod urlencoding;
+pub mod user_agent;
 pub mod vrl_processor;
 
 use std::str::FromStr;
@@ -41,13 +46,18 @@ use digest::DigestProcessor;
 use dissect::DissectProcessor;
 use enum_dispatch::enum_dispatch;
 use epoch::EpochProcessor;
+use grok::GrokProcessor;
+use geoip::GeoIpProcessor;
 use gsub::GsubProcessor;
 use join::JoinProcessor;
 use json_path::JsonPathProcessor;
+use kv::KvProcessor;
 use letter::LetterProcessor;
 use regex::RegexProcessor;
 use snafu::{OptionExt, ResultExt};
+use syslog::SyslogProcessor;
 use urlencoding::UrlEncodingProcessor;
+use user_agent::UserAgentProcessor;
 use vrl::value::Value as VrlValue;
 
 use crate::error::{
@@ -134,11 +144,16 @@ pub enum ProcessorKind {
     Cmcd(CmcdProcessor),
     Csv(CsvProcessor),
     Dissect(DissectProcessor),
+    Grok(GrokProcessor),
     Gsub(GsubProcessor),
     Join(JoinProcessor),
+    Kv(KvProcessor),
     Letter(LetterProcessor),
     Regex(RegexProcessor),
+    Syslog(SyslogProcessor),
     UrlEncoding(UrlEncodingProcessor),
+    UserAgent(UserAgentProcessor),
+    GeoIp(GeoIpProcessor),
     Epoch(EpochProcessor),
     Date(DateProcessor),
     JsonPath(JsonPathProcessor),
@@ -207,10 +222,17 @@ fn parse_processor(doc: &yaml_rust::Yaml) -> Result<ProcessorKind> {
         dissect::PROCESSOR_DISSECT => ProcessorKind::Dissect(DissectProcessor::try_from(value)?),
         epoch::PROCESSOR_EPOCH => ProcessorKind::Epoch(EpochProcessor::try_from(value)?),
         date::PROCESSOR_DATE => ProcessorKind::Date(DateProcessor::try_from(value)?),
+        grok::PROCESSOR_GROK => ProcessorKind::Grok(GrokProcessor::try_from(value)?),
         gsub::PROCESSOR_GSUB => ProcessorKind::Gsub(GsubProcessor::try_from(value)?),
         join::PROCESSOR_JOIN => ProcessorKind::Join(JoinProcessor::try_from(value)?),
+        kv::PROCESSOR_KV => ProcessorKind::Kv(KvProcessor::try_from(value)?),
         letter::PROCESSOR_LETTER => ProcessorKind::Letter(LetterProcessor::try_from(value)?),
         regex::PROCESSOR_REGEX => ProcessorKind::Regex(RegexProcessor::try_from(value)?),
+        syslog::PROCESSOR_SYSLOG => ProcessorKind::Syslog(SyslogProcessor::try_from(value)?),
+        user_agent::PROCESSOR_USER_AGENT => {
+            ProcessorKind::UserAgent(UserAgentProcessor::try_from(value)?)
+        }
+        geoip::PROCESSOR_GEOIP => ProcessorKind::GeoIp(GeoIpProcessor::try_from(value)?),
         urlencoding::PROCESSOR_URL_ENCODING => {
             ProcessorKind::UrlEncoding(UrlEncodingProcessor::try_from(value)?)
         }
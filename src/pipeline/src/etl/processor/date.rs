@@ -39,7 +39,7 @@ const LOCALE_NAME: &str = "locale";
 const OUTPUT_FORMAT_NAME: &str = "output_format"; // default with input format
 
 lazy_static! {
-    static ref DEFAULT_FORMATS: Vec<Arc<String>> = vec![
+    pub(crate) static ref DEFAULT_FORMATS: Vec<Arc<String>> = vec![
                     // timezone with colon
                     "%Y-%m-%dT%H:%M:%S%:z",
                     "%Y-%m-%dT%H:%M:%S%.3f%:z",
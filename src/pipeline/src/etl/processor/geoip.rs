@@ -0,0 +1,451 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use maxminddb::{geoip2, MaxMindDBError, Reader};
+use ordered_float::NotNan;
+use snafu::{ensure, OptionExt, ResultExt};
+use vrl::prelude::Bytes;
+use vrl::value::{KeyString, Value as VrlValue};
+
+use crate::error::{
+    Error, GeoIpDatabaseRequiredSnafu, GeoIpInvalidIpSnafu, GeoIpInvalidPropertySnafu,
+    GeoIpLookupSnafu, GeoIpOpenDatabaseSnafu, KeyMustBeStringSnafu, ProcessorExpectStringSnafu,
+    ProcessorMissingFieldSnafu, Result, ValueMustBeMapSnafu,
+};
+use crate::etl::field::Fields;
+use crate::etl::processor::{
+    yaml_bool, yaml_new_field, yaml_new_fields, yaml_string, yaml_strings, Processor, FIELDS_NAME,
+    FIELD_NAME, IGNORE_MISSING_NAME,
+};
+
+pub(crate) const PROCESSOR_GEOIP: &str = "geoip";
+
+const DATABASE_NAME: &str = "database";
+const PROPERTIES_NAME: &str = "properties";
+
+const CONTINENT_CODE: &str = "continent_code";
+const CONTINENT_NAME: &str = "continent_name";
+const COUNTRY_ISO_CODE: &str = "country_iso_code";
+const COUNTRY_NAME: &str = "country_name";
+const REGION_ISO_CODE: &str = "region_iso_code";
+const REGION_NAME: &str = "region_name";
+const CITY_NAME: &str = "city_name";
+const POSTAL_CODE: &str = "postal_code";
+const TIMEZONE: &str = "timezone";
+const LATITUDE: &str = "latitude";
+const LONGITUDE: &str = "longitude";
+const LOCATION: &str = "location";
+const ASN: &str = "asn";
+const ORGANIZATION_NAME: &str = "organization_name";
+
+/// Properties of the City and Country databases.
+const CITY_PROPERTIES: &[&str] = &[
+    CONTINENT_CODE,
+    CONTINENT_NAME,
+    COUNTRY_ISO_CODE,
+    COUNTRY_NAME,
+    REGION_ISO_CODE,
+    REGION_NAME,
+    CITY_NAME,
+    POSTAL_CODE,
+    TIMEZONE,
+    LATITUDE,
+    LONGITUDE,
+    LOCATION,
+];
+
+/// Properties of the ASN databases.
+const ASN_PROPERTIES: &[&str] = &[ASN, ORGANIZATION_NAME];
+
+/// The language of the place names.
+const LANGUAGE: &str = "en";
+
+fn string(s: &str) -> VrlValue {
+    VrlValue::Bytes(Bytes::from(s.to_string()))
+}
+
+fn english_name(names: &Option<BTreeMap<&str, &str>>) -> Option<VrlValue> {
+    names.as_ref()?.get(LANGUAGE).map(|name| string(name))
+}
+
+/// Looks up ip addresses in a MaxMind format (mmdb) database, e.g. GeoLite2-City
+/// or GeoLite2-ASN, and adds the geo location or the autonomous system of them.
+///
+/// The keys of the outputs are prefixed by the target field, or the input field
+/// if the target is not set, e.g. `client_ip_country_name`. The `location` is a
+/// WKT point, the same as the output of `wkt_point_from_latlng`.
+pub struct GeoIpProcessor {
+    fields: Fields,
+    database: String,
+    reader: Arc<Reader<Vec<u8>>>,
+    properties: Vec<String>,
+    ignore_missing: bool,
+}
+
+impl std::fmt::Debug for GeoIpProcessor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GeoIpProcessor")
+            .field("fields", &self.fields)
+            .field("database", &self.database)
+            .field("properties", &self.properties)
+            .field("ignore_missing", &self.ignore_missing)
+            .finish()
+    }
+}
+
+impl TryFrom<&yaml_rust::yaml::Hash> for GeoIpProcessor {
+    type Error = Error;
+
+    fn try_from(value: &yaml_rust::yaml::Hash) -> Result<Self> {
+        let mut fields = Fields::default();
+        let mut database = None;
+        let mut properties = None;
+        let mut ignore_missing = false;
+
+        for (k, v) in value.iter() {
+            let key = k
+                .as_str()
+                .with_context(|| KeyMustBeStringSnafu { k: k.clone() })?;
+            match key {
+                FIELD_NAME => {
+                    fields = Fields::one(yaml_new_field(v, FIELD_NAME)?);
+                }
+                FIELDS_NAME => {
+                    fields = yaml_new_fields(v, FIELDS_NAME)?;
+                }
+                DATABASE_NAME => {
+                    database = Some(yaml_string(v, DATABASE_NAME)?);
+                }
+                PROPERTIES_NAME => {
+                    properties = Some(yaml_strings(v, PROPERTIES_NAME)?);
+                }
+                IGNORE_MISSING_NAME => {
+                    ignore_missing = yaml_bool(v, IGNORE_MISSING_NAME)?;
+                }
+                _ => {}
+            }
+        }
+
+        let database = database.context(GeoIpDatabaseRequiredSnafu)?;
+        let reader = Reader::open_readfile(&database).context(GeoIpOpenDatabaseSnafu {
+            path: database.clone(),
+        })?;
+
+        let database_type = &reader.metadata.database_type;
+        let supported = if is_asn_database(database_type) {
+            ASN_PROPERTIES
+        } else {
+            CITY_PROPERTIES
+        };
+        let properties = match properties {
+            Some(properties) => {
+                for property in &properties {
+                    ensure!(
+                        supported.contains(&property.as_str()),
+                        GeoIpInvalidPropertySnafu {
+                            property,
+                            database_type,
+                        }
+                    );
+                }
+                properties
+            }
+            None => supported.iter().map(|p| p.to_string()).collect(),
+        };
+
+        Ok(GeoIpProcessor {
+            fields,
+            database,
+            reader: Arc::new(reader),
+            properties,
+            ignore_missing,
+        })
+    }
+}
+
+fn is_asn_database(database_type: &str) -> bool {
+    database_type.contains("ASN")
+}
+
+impl GeoIpProcessor {
+    /// Returns all properties of `ip` found in the database.
+    fn lookup(&self, ip: IpAddr) -> Result<Vec<(&'static str, VrlValue)>> {
+        let reader = &self.reader;
+        let mut properties = vec![];
+        if is_asn_database(&reader.metadata.database_type) {
+            let asn: geoip2::Asn = match reader.lookup(ip) {
+                Ok(asn) => asn,
+                Err(MaxMindDBError::AddressNotFoundError(_)) => return Ok(properties),
+                Err(e) => return Err(e).context(GeoIpLookupSnafu { ip }),
+            };
+            if let Some(number) = asn.autonomous_system_number {
+                properties.push((ASN, VrlValue::Integer(number as i64)));
+            }
+            if let Some(organization) = asn.autonomous_system_organization {
+                properties.push((ORGANIZATION_NAME, string(organization)));
+            }
+            return Ok(properties);
+        }
+
+        let city: geoip2::City = match reader.lookup(ip) {
+            Ok(city) => city,
+            Err(MaxMindDBError::AddressNotFoundError(_)) => return Ok(properties),
+            Err(e) => return Err(e).context(GeoIpLookupSnafu { ip }),
+        };
+        if let Some(continent) = &city.continent {
+            if let Some(code) = continent.code {
+                properties.push((CONTINENT_CODE, string(code)));
+            }
+            if let Some(name) = english_name(&continent.names) {
+                properties.push((CONTINENT_NAME, name));
+            }
+        }
+        if let Some(country) = &city.country {
+            if let Some(iso_code) = country.iso_code {
+                properties.push((COUNTRY_ISO_CODE, string(iso_code)));
+            }
+            if let Some(name) = english_name(&country.names) {
+                properties.push((COUNTRY_NAME, name));
+            }
+        }
+        // The first subdivision is the largest one, e.g. the state or province.
+        if let Some(region) = city.subdivisions.as_ref().and_then(|s| s.first()) {
+            if let Some(iso_code) = region.iso_code {
+                properties.push((REGION_ISO_CODE, string(iso_code)));
+            }
+            if let Some(name) = english_name(&region.names) {
+                properties.push((REGION_NAME, name));
+            }
+        }
+        if let Some(name) = city.city.as_ref().and_then(|c| english_name(&c.names)) {
+            properties.push((CITY_NAME, name));
+        }
+        if let Some(code) = city.postal.as_ref().and_then(|p| p.code) {
+            properties.push((POSTAL_CODE, string(code)));
+        }
+        if let Some(location) = &city.location {
+            if let Some(timezone) = location.time_zone {
+                properties.push((TIMEZONE, string(timezone)));
+            }
+            if let (Some(lat), Some(lng)) = (location.latitude, location.longitude) {
+                if let (Ok(lat_value), Ok(lng_value)) = (NotNan::new(lat), NotNan::new(lng)) {
+                    properties.push((LATITUDE, VrlValue::Float(lat_value)));
+                    properties.push((LONGITUDE, VrlValue::Float(lng_value)));
+                    properties.push((LOCATION, string(&format!("POINT({lng} {lat})"))));
+                }
+            }
+        }
+
+        Ok(properties)
+    }
+
+    fn process(&self, prefix: &str, val: &str) -> Result<BTreeMap<KeyString, VrlValue>> {
+        let ip = val
+            .trim()
+            .parse::<IpAddr>()
+            .context(GeoIpInvalidIpSnafu { value: val })?;
+
+        let mut result = BTreeMap::new();
+        for (property, value) in self.lookup(ip)? {
+            if self.properties.iter().any(|p| p == property) {
+                result.insert(KeyString::from(format!("{prefix}_{property}")), value);
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl Processor for GeoIpProcessor {
+    fn kind(&self) -> &str {
+        PROCESSOR_GEOIP
+    }
+
+    fn ignore_missing(&self) -> bool {
+        self.ignore_missing
+    }
+
+    fn exec_mut(&self, mut val: VrlValue) -> Result<VrlValue> {
+        for field in self.fields.iter() {
+            let index = field.input_field();
+            let val = val.as_object_mut().context(ValueMustBeMapSnafu)?;
+            match val.get(index) {
+                Some(VrlValue::Bytes(s)) => {
+                    let result = self.process(
+                        field.target_or_input_field(),
+                        String::from_utf8_lossy(s).as_ref(),
+                    )?;
+                    val.extend(result);
+                }
+                Some(VrlValue::Null) | None => {
+                    if !self.ignore_missing {
+                        return ProcessorMissingFieldSnafu {
+                            processor: self.kind(),
+                            field: field.input_field(),
+                        }
+                        .fail();
+                    }
+                }
+                Some(v) => {
+                    return ProcessorExpectStringSnafu {
+                        processor: self.kind(),
+                        v: v.clone(),
+                    }
+                    .fail();
+                }
+            }
+        }
+
+        Ok(val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_processor(yaml: &str) -> Result<GeoIpProcessor> {
+        let processor_yaml = yaml_rust::YamlLoader::load_from_str(yaml)
+            .unwrap()
+            .pop()
+            .unwrap();
+        GeoIpProcessor::try_from(processor_yaml.as_hash().unwrap())
+    }
+
+    fn fixture(name: &str) -> String {
+        format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"))
+    }
+
+    fn exec(processor: &GeoIpProcessor, ip: &str) -> VrlValue {
+        let input = VrlValue::Object(BTreeMap::from([(KeyString::from("client_ip"), string(ip))]));
+        processor.exec_mut(input).unwrap()
+    }
+
+    fn float(value: f64) -> VrlValue {
+        VrlValue::Float(NotNan::new(value).unwrap())
+    }
+
+    #[test]
+    fn test_lookup_city() {
+        let yaml = format!(
+            "field: client_ip, geo\ndatabase: {}",
+            fixture("GeoLite2-City-Test.mmdb")
+        );
+        let processor = new_processor(&yaml).unwrap();
+
+        let expected = VrlValue::Object(BTreeMap::from([
+            (KeyString::from("client_ip"), string("216.160.83.56")),
+            (KeyString::from("geo_continent_code"), string("NA")),
+            (
+                KeyString::from("geo_continent_name"),
+                string("North America"),
+            ),
+            (KeyString::from("geo_country_iso_code"), string("US")),
+            (KeyString::from("geo_country_name"), string("United States")),
+            (KeyString::from("geo_region_iso_code"), string("WA")),
+            (KeyString::from("geo_region_name"), string("Washington")),
+            (KeyString::from("geo_city_name"), string("Milton")),
+            (KeyString::from("geo_postal_code"), string("98354")),
+            (
+                KeyString::from("geo_timezone"),
+                string("America/Los_Angeles"),
+            ),
+            (KeyString::from("geo_latitude"), float(47.2513)),
+            (KeyString::from("geo_longitude"), float(-122.3149)),
+            (
+                KeyString::from("geo_location"),
+                string("POINT(-122.3149 47.2513)"),
+            ),
+        ]));
+        assert_eq!(expected, exec(&processor, "216.160.83.56"));
+
+        // The ip address is not in the database.
+        let expected = VrlValue::Object(BTreeMap::from([(
+            KeyString::from("client_ip"),
+            string("8.8.8.8"),
+        )]));
+        assert_eq!(expected, exec(&processor, "8.8.8.8"));
+    }
+
+    #[test]
+    fn test_lookup_city_properties() {
+        let yaml = format!(
+            "field: client_ip\ndatabase: {}\nproperties: [country_iso_code, city_name]",
+            fixture("GeoLite2-City-Test.mmdb")
+        );
+        let processor = new_processor(&yaml).unwrap();
+
+        let expected = VrlValue::Object(BTreeMap::from([
+            (KeyString::from("client_ip"), string("81.2.69.142")),
+            (KeyString::from("client_ip_country_iso_code"), string("GB")),
+            (KeyString::from("client_ip_city_name"), string("London")),
+        ]));
+        assert_eq!(expected, exec(&processor, "81.2.69.142"));
+
+        // The ASN properties are not in the City database.
+        let yaml = format!(
+            "field: client_ip\ndatabase: {}\nproperties: [asn]",
+            fixture("GeoLite2-City-Test.mmdb")
+        );
+        let err = new_processor(&yaml).unwrap_err();
+        assert!(matches!(err, Error::GeoIpInvalidProperty { .. }));
+    }
+
+    #[test]
+    fn test_lookup_asn() {
+        let yaml = format!(
+            "field: client_ip\ndatabase: {}",
+            fixture("GeoLite2-ASN-Test.mmdb")
+        );
+        let processor = new_processor(&yaml).unwrap();
+
+        let expected = VrlValue::Object(BTreeMap::from([
+            (KeyString::from("client_ip"), string("1.128.0.1")),
+            (KeyString::from("client_ip_asn"), VrlValue::Integer(1221)),
+            (
+                KeyString::from("client_ip_organization_name"),
+                string("Telstra Pty Ltd"),
+            ),
+        ]));
+        assert_eq!(expected, exec(&processor, "1.128.0.1"));
+    }
+
+    #[test]
+    fn test_lookup_invalid_ip() {
+        let yaml = format!(
+            "field: client_ip\ndatabase: {}",
+            fixture("GeoLite2-City-Test.mmdb")
+        );
+        let processor = new_processor(&yaml).unwrap();
+        let input = VrlValue::Object(BTreeMap::from([(
+            KeyString::from("client_ip"),
+            string("not an ip"),
+        )]));
+        let err = processor.exec_mut(input).unwrap_err();
+        assert!(matches!(err, Error::GeoIpInvalidIp { .. }));
+    }
+
+    #[test]
+    fn test_invalid_database() {
+        let err = new_processor("field: client_ip").unwrap_err();
+        assert!(matches!(err, Error::GeoIpDatabaseRequired { .. }));
+
+        let err =
+            new_processor("field: client_ip\ndatabase: /not/exist/GeoLite2-City.mmdb").unwrap_err();
+        assert!(matches!(err, Error::GeoIpOpenDatabase { .. }));
+    }
+}
@@ -0,0 +1,486 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod patterns;
+
+pub(crate) const PROCESSOR_GROK: &str = "grok";
+
+const PATTERN_DEFINITIONS_NAME: &str = "pattern_definitions";
+
+/// The prefix of the regex group names generated for grok captures, as the
+/// semantic names of captures are not always valid group names, e.g. `source.ip`.
+const GROUP_PREFIX: &str = "__grok";
+
+use std::collections::{BTreeMap, HashMap};
+
+use lazy_static::lazy_static;
+use ordered_float::NotNan;
+use regex::Regex;
+use snafu::{ensure, OptionExt, ResultExt};
+use vrl::prelude::Bytes;
+use vrl::value::{KeyString, Value as VrlValue};
+
+use crate::error::{
+    Error, FieldMustBeTypeSnafu, GrokCastCaptureSnafu, GrokInvalidCaptureTypeSnafu,
+    GrokNoMatchingPatternSnafu, GrokPatternNotFoundSnafu, GrokRecursivePatternSnafu,
+    KeyMustBeStringSnafu, ProcessorExpectStringSnafu, ProcessorMissingFieldSnafu,
+    RegexNoValidFieldSnafu, RegexNoValidPatternSnafu, RegexSnafu, Result, ValueMustBeMapSnafu,
+};
+use crate::etl::field::Fields;
+use crate::etl::processor::grok::patterns::BUILTIN_PATTERNS;
+use crate::etl::processor::{
+    yaml_bool, yaml_new_field, yaml_new_fields, yaml_string, yaml_strings, Processor, FIELDS_NAME,
+    FIELD_NAME, IGNORE_MISSING_NAME, PATTERNS_NAME, PATTERN_NAME,
+};
+
+lazy_static! {
+    /// Matches `%{SYNTAX}`, `%{SYNTAX:SEMANTIC}` and `%{SYNTAX:SEMANTIC:TYPE}`.
+    static ref REFERENCE_REGEX: Regex = Regex::new(
+        r"%\{(?P<name>[A-Za-z0-9_]+)(?::(?P<field>[^:}]+))?(?::(?P<ty>[A-Za-z]+))?\}"
+    )
+    .unwrap();
+}
+
+/// The type to cast a captured value to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CaptureType {
+    String,
+    Int,
+    Float,
+    Boolean,
+}
+
+impl CaptureType {
+    fn parse(field: &str, ty: &str) -> Result<Self> {
+        match ty {
+            "string" => Ok(CaptureType::String),
+            "int" | "long" => Ok(CaptureType::Int),
+            "float" | "double" => Ok(CaptureType::Float),
+            "bool" | "boolean" => Ok(CaptureType::Boolean),
+            _ => GrokInvalidCaptureTypeSnafu { field, ty }.fail(),
+        }
+    }
+
+    fn cast(&self, field: &str, value: &str) -> Result<VrlValue> {
+        let cast_error = || {
+            GrokCastCaptureSnafu {
+                field,
+                value,
+                ty: format!("{self:?}").to_lowercase(),
+            }
+            .build()
+        };
+        let value = match self {
+            CaptureType::String => VrlValue::Bytes(Bytes::from(value.to_string())),
+            CaptureType::Int => VrlValue::Integer(value.parse().map_err(|_| cast_error())?),
+            CaptureType::Float => {
+                let v: f64 = value.parse().map_err(|_| cast_error())?;
+                VrlValue::Float(NotNan::new(v).map_err(|_| cast_error())?)
+            }
+            CaptureType::Boolean => VrlValue::Boolean(value.parse().map_err(|_| cast_error())?),
+        };
+        Ok(value)
+    }
+}
+
+#[derive(Debug)]
+struct Capture {
+    /// The name of the regex group.
+    group: String,
+    /// The semantic name of the capture.
+    field: String,
+    ty: CaptureType,
+}
+
+/// A grok expression compiled to a regex.
+#[derive(Debug)]
+struct GrokPattern {
+    regex: Regex,
+    captures: Vec<Capture>,
+}
+
+impl GrokPattern {
+    fn compile(origin: &str, definitions: &HashMap<String, String>) -> Result<Self> {
+        let mut captures = vec![];
+        let expanded = expand(origin, definitions, &mut vec![], &mut captures)?;
+        let regex = Regex::new(&expanded).context(RegexSnafu { pattern: origin })?;
+        // Named groups in plain regex syntax, e.g. `(?<id>\d+)`, are captured as strings.
+        for name in regex.capture_names().flatten() {
+            if !name.starts_with(GROUP_PREFIX) {
+                captures.push(Capture {
+                    group: name.to_string(),
+                    field: name.to_string(),
+                    ty: CaptureType::String,
+                });
+            }
+        }
+
+        Ok(GrokPattern { regex, captures })
+    }
+
+    /// Returns the captured values, or `None` if the pattern doesn't match.
+    fn process(
+        &self,
+        prefix: Option<&str>,
+        val: &str,
+    ) -> Result<Option<BTreeMap<KeyString, VrlValue>>> {
+        let Some(groups) = self.regex.captures(val) else {
+            return Ok(None);
+        };
+
+        let mut result = BTreeMap::new();
+        for capture in &self.captures {
+            if let Some(m) = groups.name(&capture.group) {
+                let key = match prefix {
+                    Some(prefix) => format!("{prefix}_{}", capture.field),
+                    None => capture.field.clone(),
+                };
+                let value = capture.ty.cast(&capture.field, m.as_str())?;
+                result.insert(KeyString::from(key), value);
+            }
+        }
+        Ok(Some(result))
+    }
+}
+
+/// Expands the pattern references in `pattern` recursively into a regex.
+///
+/// `stack` holds the names of the patterns being expanded to detect recursive definitions.
+fn expand(
+    pattern: &str,
+    definitions: &HashMap<String, String>,
+    stack: &mut Vec<String>,
+    captures: &mut Vec<Capture>,
+) -> Result<String> {
+    let mut expanded = String::with_capacity(pattern.len());
+    let mut last = 0;
+    for reference in REFERENCE_REGEX.captures_iter(pattern) {
+        let whole = reference.get(0).unwrap();
+        expanded.push_str(&pattern[last..whole.start()]);
+        last = whole.end();
+
+        let name = &reference["name"];
+        ensure!(
+            !stack.iter().any(|n| n == name),
+            GrokRecursivePatternSnafu { name }
+        );
+        let definition = definitions
+            .get(name)
+            .map(String::as_str)
+            .or_else(|| BUILTIN_PATTERNS.get(name).copied())
+            .context(GrokPatternNotFoundSnafu { name })?;
+        stack.push(name.to_string());
+        let inner = expand(definition, definitions, stack, captures)?;
+        stack.pop();
+
+        match reference.name("field") {
+            Some(field) => {
+                let field = field.as_str();
+                let ty = match reference.name("ty") {
+                    Some(ty) => CaptureType::parse(field, ty.as_str())?,
+                    None => CaptureType::String,
+                };
+                let group = format!("{GROUP_PREFIX}{}", captures.len());
+                expanded.push_str(&format!("(?P<{group}>{inner})"));
+                captures.push(Capture {
+                    group,
+                    field: field.to_string(),
+                    ty,
+                });
+            }
+            None => {
+                expanded.push_str("(?:");
+                expanded.push_str(&inner);
+                expanded.push(')');
+            }
+        }
+    }
+    expanded.push_str(&pattern[last..]);
+
+    Ok(expanded)
+}
+
+impl TryFrom<&yaml_rust::yaml::Hash> for GrokProcessor {
+    type Error = Error;
+
+    fn try_from(value: &yaml_rust::yaml::Hash) -> Result<Self> {
+        let mut fields = Fields::default();
+        let mut patterns = vec![];
+        let mut definitions = HashMap::new();
+        let mut ignore_missing = false;
+
+        for (k, v) in value.iter() {
+            let key = k
+                .as_str()
+                .with_context(|| KeyMustBeStringSnafu { k: k.clone() })?;
+            match key {
+                FIELD_NAME => {
+                    fields = Fields::one(yaml_new_field(v, FIELD_NAME)?);
+                }
+                FIELDS_NAME => {
+                    fields = yaml_new_fields(v, FIELDS_NAME)?;
+                }
+                PATTERN_NAME => {
+                    patterns.push(yaml_string(v, PATTERN_NAME)?);
+                }
+                PATTERNS_NAME => {
+                    patterns.extend(yaml_strings(v, PATTERNS_NAME)?);
+                }
+                PATTERN_DEFINITIONS_NAME => {
+                    let map = v.as_hash().context(FieldMustBeTypeSnafu {
+                        field: PATTERN_DEFINITIONS_NAME,
+                        ty: "map",
+                    })?;
+                    for (name, definition) in map {
+                        let name = name
+                            .as_str()
+                            .with_context(|| KeyMustBeStringSnafu { k: name.clone() })?;
+                        let definition = yaml_string(definition, PATTERN_DEFINITIONS_NAME)?;
+                        definitions.insert(name.to_string(), definition);
+                    }
+                }
+                IGNORE_MISSING_NAME => {
+                    ignore_missing = yaml_bool(v, IGNORE_MISSING_NAME)?;
+                }
+                _ => {}
+            }
+        }
+
+        // Compiles the patterns after all definitions are collected.
+        let patterns = patterns
+            .iter()
+            .map(|pattern| GrokPattern::compile(pattern, &definitions))
+            .collect::<Result<Vec<_>>>()?;
+
+        let processor = GrokProcessor {
+            fields,
+            patterns,
+            ignore_missing,
+        };
+
+        processor.check()
+    }
+}
+
+/// Extracts structured fields from a string with grok expressions.
+///
+/// The patterns are tried in order and the captures of the first matched pattern
+/// are merged into the document. Only support string value.
+#[derive(Debug, Default)]
+pub struct GrokProcessor {
+    fields: Fields,
+    patterns: Vec<GrokPattern>,
+    ignore_missing: bool,
+}
+
+impl GrokProcessor {
+    fn check(self) -> Result<Self> {
+        if self.fields.is_empty() {
+            return RegexNoValidFieldSnafu {
+                processor: PROCESSOR_GROK,
+            }
+            .fail();
+        }
+
+        if self.patterns.is_empty() {
+            return RegexNoValidPatternSnafu {
+                processor: PROCESSOR_GROK,
+            }
+            .fail();
+        }
+
+        Ok(self)
+    }
+
+    fn process(
+        &self,
+        field: &str,
+        prefix: Option<&str>,
+        val: &str,
+    ) -> Result<BTreeMap<KeyString, VrlValue>> {
+        for pattern in &self.patterns {
+            if let Some(result) = pattern.process(prefix, val)? {
+                return Ok(result);
+            }
+        }
+        GrokNoMatchingPatternSnafu { field }.fail()
+    }
+}
+
+impl Processor for GrokProcessor {
+    fn kind(&self) -> &str {
+        PROCESSOR_GROK
+    }
+
+    fn ignore_missing(&self) -> bool {
+        self.ignore_missing
+    }
+
+    fn exec_mut(&self, mut val: VrlValue) -> Result<VrlValue> {
+        for field in self.fields.iter() {
+            let index = field.input_field();
+            let val = val.as_object_mut().context(ValueMustBeMapSnafu)?;
+            match val.get(index) {
+                Some(VrlValue::Bytes(s)) => {
+                    let result = self.process(
+                        index,
+                        field.target_field(),
+                        String::from_utf8_lossy(s).as_ref(),
+                    )?;
+                    val.extend(result);
+                }
+                Some(VrlValue::Null) | None => {
+                    if !self.ignore_missing {
+                        return ProcessorMissingFieldSnafu {
+                            processor: self.kind(),
+                            field: field.input_field(),
+                        }
+                        .fail();
+                    }
+                }
+                Some(v) => {
+                    return ProcessorExpectStringSnafu {
+                        processor: self.kind(),
+                        v: v.clone(),
+                    }
+                    .fail();
+                }
+            }
+        }
+
+        Ok(val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_processor(yaml: &str) -> Result<GrokProcessor> {
+        let processor_yaml = yaml_rust::YamlLoader::load_from_str(yaml)
+            .unwrap()
+            .pop()
+            .unwrap();
+        GrokProcessor::try_from(processor_yaml.as_hash().unwrap())
+    }
+
+    fn string(s: &str) -> VrlValue {
+        VrlValue::Bytes(Bytes::from(s.to_string()))
+    }
+
+    #[test]
+    fn test_builtin_patterns_compile() {
+        let definitions = HashMap::new();
+        for name in BUILTIN_PATTERNS.keys() {
+            GrokPattern::compile(&format!("%{{{name}}}"), &definitions)
+                .unwrap_or_else(|e| panic!("failed to compile {name}: {e:?}"));
+        }
+    }
+
+    #[test]
+    fn test_combined_apache_log() {
+        let processor = new_processor(
+            r#"field: message
+pattern: '%{COMBINEDAPACHELOG}'"#,
+        )
+        .unwrap();
+
+        let log = r#"127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /apache_pb.gif HTTP/1.0" 200 2326 "http://www.example.com/start.html" "Mozilla/4.08 [en] (Win98; I ;Nav)""#;
+        let result = processor.process("message", None, log).unwrap();
+        let expected = [
+            ("clientip", "127.0.0.1"),
+            ("ident", "-"),
+            ("auth", "frank"),
+            ("timestamp", "10/Oct/2000:13:55:36 -0700"),
+            ("verb", "GET"),
+            ("request", "/apache_pb.gif"),
+            ("httpversion", "1.0"),
+            ("response", "200"),
+            ("bytes", "2326"),
+            ("referrer", r#""http://www.example.com/start.html""#),
+            ("agent", r#""Mozilla/4.08 [en] (Win98; I ;Nav)""#),
+        ]
+        .into_iter()
+        .map(|(k, v)| (KeyString::from(k), string(v)))
+        .collect::<BTreeMap<_, _>>();
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_syslog_base() {
+        let processor = new_processor(
+            r#"field: message
+pattern: '%{SYSLOGBASE} %{GREEDYDATA:msg}'"#,
+        )
+        .unwrap();
+
+        let log = "Mar  7 00:00:01 myhost sshd[1234]: Accepted publickey for root";
+        let result = processor.process("message", None, log).unwrap();
+        assert_eq!(string("Mar  7 00:00:01"), result["timestamp"]);
+        assert_eq!(string("myhost"), result["logsource"]);
+        assert_eq!(string("sshd"), result["program"]);
+        assert_eq!(string("1234"), result["pid"]);
+        assert_eq!(string("Accepted publickey for root"), result["msg"]);
+    }
+
+    #[test]
+    fn test_typed_captures_and_definitions() {
+        let processor = new_processor(
+            r#"field: message
+patterns:
+  - '%{STATUS:status} took %{NUMBER:duration:float}ms, %{INT:bytes:int} bytes, cached: %{WORD:cached:boolean}'
+  - '%{STATUS:status} (?P<reason>.+)'
+pattern_definitions:
+  STATUS: '(?:OK|FAILED)'"#,
+        )
+        .unwrap();
+
+        let result = processor
+            .process("message", None, "OK took 1.5ms, 1024 bytes, cached: true")
+            .unwrap();
+        assert_eq!(string("OK"), result["status"]);
+        assert_eq!(
+            VrlValue::Float(NotNan::new(1.5).unwrap()),
+            result["duration"]
+        );
+        assert_eq!(VrlValue::Integer(1024), result["bytes"]);
+        assert_eq!(VrlValue::Boolean(true), result["cached"]);
+
+        // Falls back to the second pattern.
+        let result = processor
+            .process("message", Some("req"), "FAILED connection reset")
+            .unwrap();
+        assert_eq!(string("FAILED"), result["req_status"]);
+        assert_eq!(string("connection reset"), result["req_reason"]);
+
+        processor
+            .process("message", None, "UNKNOWN status")
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_invalid_patterns() {
+        // Unknown pattern.
+        new_processor("field: message\npattern: '%{NOT_A_PATTERN:a}'").unwrap_err();
+        // Recursive definitions.
+        new_processor(
+            "field: message\npattern: '%{A}'\npattern_definitions:\n  A: '%{B}'\n  B: '%{A}'",
+        )
+        .unwrap_err();
+        // Unknown type.
+        new_processor("field: message\npattern: '%{INT:a:decimal}'").unwrap_err();
+        // Missing pattern.
+        new_processor("field: message").unwrap_err();
+    }
+}
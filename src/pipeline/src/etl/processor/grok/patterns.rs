@@ -0,0 +1,213 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The standard grok pattern library, ported from the legacy patterns of Logstash.
+//!
+//! Patterns are compiled by the `regex` crate, which supports neither look-around
+//! nor atomic groups, so those constructs are removed from the original patterns.
+
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+
+const PATTERNS: &[(&str, &str)] = &[
+    // Basic
+    ("USERNAME", r#"[a-zA-Z0-9._-]+"#),
+    ("USER", r#"%{USERNAME}"#),
+    (
+        "EMAILLOCALPART",
+        r#"[a-zA-Z0-9!#$%&'*+/=?^_`{|}~-]+(?:\.[a-zA-Z0-9!#$%&'*+/=?^_`{|}~-]+)*"#,
+    ),
+    ("EMAILADDRESS", r#"%{EMAILLOCALPART}@%{HOSTNAME}"#),
+    ("INT", r#"(?:[+-]?(?:[0-9]+))"#),
+    ("BASE10NUM", r#"[+-]?(?:[0-9]+(?:\.[0-9]+)?|\.[0-9]+)"#),
+    ("NUMBER", r#"(?:%{BASE10NUM})"#),
+    ("BASE16NUM", r#"[+-]?(?:0x)?(?:[0-9A-Fa-f]+)"#),
+    (
+        "BASE16FLOAT",
+        r#"\b[+-]?(?:0x)?(?:(?:[0-9A-Fa-f]+(?:\.[0-9A-Fa-f]*)?)|(?:\.[0-9A-Fa-f]+))\b"#,
+    ),
+    ("POSINT", r#"\b(?:[1-9][0-9]*)\b"#),
+    ("NONNEGINT", r#"\b(?:[0-9]+)\b"#),
+    ("WORD", r#"\b\w+\b"#),
+    ("NOTSPACE", r#"\S+"#),
+    ("SPACE", r#"\s*"#),
+    ("DATA", r#".*?"#),
+    ("GREEDYDATA", r#".*"#),
+    (
+        "QUOTEDSTRING",
+        r#"(?:"(?:\\.|[^\\"])*"|'(?:\\.|[^\\'])*'|`(?:\\.|[^\\`])*`)"#,
+    ),
+    ("QS", r#"%{QUOTEDSTRING}"#),
+    (
+        "UUID",
+        r#"[A-Fa-f0-9]{8}-(?:[A-Fa-f0-9]{4}-){3}[A-Fa-f0-9]{12}"#,
+    ),
+    (
+        "URN",
+        r#"urn:[0-9A-Za-z][0-9A-Za-z-]{0,31}:(?:%[0-9a-fA-F]{2}|[0-9A-Za-z()+,.:=@;$_!*'/?#-])+"#,
+    ),
+    // Networking
+    ("MAC", r#"(?:%{CISCOMAC}|%{WINDOWSMAC}|%{COMMONMAC})"#),
+    ("CISCOMAC", r#"(?:(?:[A-Fa-f0-9]{4}\.){2}[A-Fa-f0-9]{4})"#),
+    ("WINDOWSMAC", r#"(?:(?:[A-Fa-f0-9]{2}-){5}[A-Fa-f0-9]{2})"#),
+    ("COMMONMAC", r#"(?:(?:[A-Fa-f0-9]{2}:){5}[A-Fa-f0-9]{2})"#),
+    (
+        "IPV6",
+        r#"(?:(?:(?:[0-9A-Fa-f]{1,4}:){7}(?:[0-9A-Fa-f]{1,4}|:))|(?:(?:[0-9A-Fa-f]{1,4}:){6}(?::[0-9A-Fa-f]{1,4}|(?:%{IPV4_OCTET}(?:\.%{IPV4_OCTET}){3})|:))|(?:(?:[0-9A-Fa-f]{1,4}:){5}(?:(?:(?::[0-9A-Fa-f]{1,4}){1,2})|:(?:%{IPV4_OCTET}(?:\.%{IPV4_OCTET}){3})|:))|(?:(?:[0-9A-Fa-f]{1,4}:){4}(?:(?:(?::[0-9A-Fa-f]{1,4}){1,3})|(?:(?::[0-9A-Fa-f]{1,4})?:(?:%{IPV4_OCTET}(?:\.%{IPV4_OCTET}){3}))|:))|(?:(?:[0-9A-Fa-f]{1,4}:){3}(?:(?:(?::[0-9A-Fa-f]{1,4}){1,4})|(?:(?::[0-9A-Fa-f]{1,4}){0,2}:(?:%{IPV4_OCTET}(?:\.%{IPV4_OCTET}){3}))|:))|(?:(?:[0-9A-Fa-f]{1,4}:){2}(?:(?:(?::[0-9A-Fa-f]{1,4}){1,5})|(?:(?::[0-9A-Fa-f]{1,4}){0,3}:(?:%{IPV4_OCTET}(?:\.%{IPV4_OCTET}){3}))|:))|(?:(?:[0-9A-Fa-f]{1,4}:){1}(?:(?:(?::[0-9A-Fa-f]{1,4}){1,6})|(?:(?::[0-9A-Fa-f]{1,4}){0,4}:(?:%{IPV4_OCTET}(?:\.%{IPV4_OCTET}){3}))|:))|(?::(?:(?:(?::[0-9A-Fa-f]{1,4}){1,7})|(?:(?::[0-9A-Fa-f]{1,4}){0,5}:(?:%{IPV4_OCTET}(?:\.%{IPV4_OCTET}){3}))|:)))(?:%[0-9A-Za-z]+)?"#,
+    ),
+    (
+        "IPV4_OCTET",
+        r#"(?:25[0-5]|2[0-4][0-9]|1[0-9]{2}|[1-9]?[0-9])"#,
+    ),
+    ("IPV4", r#"(?:%{IPV4_OCTET}\.){3}%{IPV4_OCTET}"#),
+    ("IP", r#"(?:%{IPV6}|%{IPV4})"#),
+    (
+        "HOSTNAME",
+        r#"\b(?:[0-9A-Za-z][0-9A-Za-z-]{0,62})(?:\.(?:[0-9A-Za-z][0-9A-Za-z-]{0,62}))*(?:\.|\b)"#,
+    ),
+    ("IPORHOST", r#"(?:%{IP}|%{HOSTNAME})"#),
+    ("HOSTPORT", r#"%{IPORHOST}:%{POSINT}"#),
+    // Paths
+    ("PATH", r#"(?:%{UNIXPATH}|%{WINPATH})"#),
+    ("UNIXPATH", r#"(?:/[\w_%!$@:.,+~-]*)+"#),
+    (
+        "TTY",
+        r#"(?:/dev/(?:pts|tty(?:[pq])?)(?:\w+)?/?(?:[0-9]+))"#,
+    ),
+    ("WINPATH", r#"(?:[A-Za-z]+:|\\)(?:\\[^\\?*]*)+"#),
+    ("URIPROTO", r#"[A-Za-z](?:[A-Za-z0-9+\-.]+)+"#),
+    ("URIHOST", r#"%{IPORHOST}(?::%{POSINT})?"#),
+    ("URIPATH", r#"(?:/[A-Za-z0-9$.+!*'(){},~:;=@#%&_\-]*)+"#),
+    ("URIQUERY", r#"[A-Za-z0-9$.+!*'|(){},~@#%&/=:;_?\-\[\]<>]*"#),
+    ("URIPARAM", r#"\?%{URIQUERY}"#),
+    ("URIPATHPARAM", r#"%{URIPATH}(?:\?%{URIQUERY})?"#),
+    (
+        "URI",
+        r#"%{URIPROTO}://(?:%{USER}(?::[^@]*)?@)?(?:%{URIHOST})?(?:%{URIPATH}(?:\?%{URIQUERY})?)?"#,
+    ),
+    // Months: January, Feb, 3, 03, 12, December
+    (
+        "MONTH",
+        r#"\b(?:[Jj]an(?:uary|uar)?|[Ff]eb(?:ruary|ruar)?|[Mm](?:a|ä)?r(?:ch|z)?|[Aa]pr(?:il)?|[Mm]a(?:y|i)?|[Jj]un(?:e|i)?|[Jj]ul(?:y|i)?|[Aa]ug(?:ust)?|[Ss]ep(?:tember)?|[Oo](?:c|k)?t(?:ober)?|[Nn]ov(?:ember)?|[Dd]e(?:c|z)(?:ember)?)\b"#,
+    ),
+    ("MONTHNUM", r#"(?:0?[1-9]|1[0-2])"#),
+    ("MONTHNUM2", r#"(?:0[1-9]|1[0-2])"#),
+    (
+        "MONTHDAY",
+        r#"(?:(?:0[1-9])|(?:[12][0-9])|(?:3[01])|[1-9])"#,
+    ),
+    // Days: Monday, Tue, Thu, etc...
+    (
+        "DAY",
+        r#"(?:Mon(?:day)?|Tue(?:sday)?|Wed(?:nesday)?|Thu(?:rsday)?|Fri(?:day)?|Sat(?:urday)?|Sun(?:day)?)"#,
+    ),
+    // Years, hours, minutes and seconds
+    ("YEAR", r#"(?:\d\d){1,2}"#),
+    ("HOUR", r#"(?:2[0123]|[01]?[0-9])"#),
+    ("MINUTE", r#"(?:[0-5][0-9])"#),
+    ("SECOND", r#"(?:(?:[0-5]?[0-9]|60)(?:[:.,][0-9]+)?)"#),
+    ("TIME", r#"%{HOUR}:%{MINUTE}(?::%{SECOND})"#),
+    // datestamp is YYYY/MM/DD-HH:MM:SS.UUUU (or something like it)
+    ("DATE_US", r#"%{MONTHNUM}[/-]%{MONTHDAY}[/-]%{YEAR}"#),
+    ("DATE_EU", r#"%{MONTHDAY}[./-]%{MONTHNUM}[./-]%{YEAR}"#),
+    ("ISO8601_TIMEZONE", r#"(?:Z|[+-]%{HOUR}(?::?%{MINUTE}))"#),
+    ("ISO8601_SECOND", r#"%{SECOND}"#),
+    (
+        "TIMESTAMP_ISO8601",
+        r#"%{YEAR}-%{MONTHNUM}-%{MONTHDAY}[T ]%{HOUR}:?%{MINUTE}(?::?%{SECOND})?%{ISO8601_TIMEZONE}?"#,
+    ),
+    ("DATE", r#"(?:%{DATE_US}|%{DATE_EU})"#),
+    ("DATESTAMP", r#"%{DATE}[- ]%{TIME}"#),
+    ("TZ", r#"(?:[APMCE][SD]T|UTC)"#),
+    (
+        "DATESTAMP_RFC822",
+        r#"%{DAY} %{MONTH} %{MONTHDAY} %{YEAR} %{TIME} %{TZ}"#,
+    ),
+    (
+        "DATESTAMP_RFC2822",
+        r#"%{DAY}, %{MONTHDAY} %{MONTH} %{YEAR} %{TIME} %{ISO8601_TIMEZONE}"#,
+    ),
+    (
+        "DATESTAMP_OTHER",
+        r#"%{DAY} %{MONTH} %{MONTHDAY} %{TIME} %{TZ} %{YEAR}"#,
+    ),
+    (
+        "DATESTAMP_EVENTLOG",
+        r#"%{YEAR}%{MONTHNUM2}%{MONTHDAY}%{HOUR}%{MINUTE}%{SECOND}"#,
+    ),
+    // Syslog
+    ("SYSLOGTIMESTAMP", r#"%{MONTH} +%{MONTHDAY} %{TIME}"#),
+    ("PROG", r#"[\x21-\x5a\x5c\x5e-\x7e]+"#),
+    ("SYSLOGPROG", r#"%{PROG:program}(?:\[%{POSINT:pid}\])?"#),
+    ("SYSLOGHOST", r#"%{IPORHOST}"#),
+    (
+        "SYSLOGFACILITY",
+        r#"<%{NONNEGINT:facility}.%{NONNEGINT:priority}>"#,
+    ),
+    (
+        "SYSLOGBASE",
+        r#"%{SYSLOGTIMESTAMP:timestamp} (?:%{SYSLOGFACILITY} )?%{SYSLOGHOST:logsource} %{SYSLOGPROG}:"#,
+    ),
+    ("SYSLOGLINE", r#"%{SYSLOGBASE} ?%{GREEDYDATA:message}"#),
+    ("SYSLOG5424PRI", r#"<%{NONNEGINT:syslog5424_pri}>"#),
+    ("SYSLOG5424PRINTASCII", r#"[!-~]+"#),
+    ("SYSLOG5424SD", r#"\[%{DATA}\]+"#),
+    (
+        "SYSLOG5424BASE",
+        r#"%{SYSLOG5424PRI}%{NONNEGINT:syslog5424_ver} +(?:%{TIMESTAMP_ISO8601:syslog5424_ts}|-) +(?:%{IPORHOST:syslog5424_host}|-) +(?:%{SYSLOG5424PRINTASCII:syslog5424_app}|-) +(?:%{SYSLOG5424PRINTASCII:syslog5424_proc}|-) +(?:%{SYSLOG5424PRINTASCII:syslog5424_msgid}|-) +(?:%{SYSLOG5424SD:syslog5424_sd}|-|)"#,
+    ),
+    (
+        "SYSLOG5424LINE",
+        r#"%{SYSLOG5424BASE} +%{GREEDYDATA:syslog5424_msg}"#,
+    ),
+    // Log formats
+    ("HTTPDATE", r#"%{MONTHDAY}/%{MONTH}/%{YEAR}:%{TIME} %{INT}"#),
+    ("HTTPDUSER", r#"(?:%{EMAILADDRESS}|%{USER})"#),
+    (
+        "HTTPDERROR_DATE",
+        r#"%{DAY} %{MONTH} %{MONTHDAY} %{TIME} %{YEAR}"#,
+    ),
+    (
+        "COMMONAPACHELOG",
+        r#"%{IPORHOST:clientip} %{HTTPDUSER:ident} %{USER:auth} \[%{HTTPDATE:timestamp}\] "(?:%{WORD:verb} %{NOTSPACE:request}(?: HTTP/%{NUMBER:httpversion})?|%{DATA:rawrequest})" %{NUMBER:response} (?:%{NUMBER:bytes}|-)"#,
+    ),
+    (
+        "COMBINEDAPACHELOG",
+        r#"%{COMMONAPACHELOG} %{QS:referrer} %{QS:agent}"#,
+    ),
+    (
+        "HTTPD20_ERRORLOG",
+        r#"\[%{HTTPDERROR_DATE:timestamp}\] \[%{LOGLEVEL:loglevel}\] (?:\[client %{IPORHOST:clientip}\] )?%{GREEDYDATA:message}"#,
+    ),
+    (
+        "HTTPD24_ERRORLOG",
+        r#"\[%{HTTPDERROR_DATE:timestamp}\] \[%{WORD:module}:%{LOGLEVEL:loglevel}\] \[pid %{POSINT:pid}(?::tid %{NUMBER:tid})?\](?: \(%{POSINT:proxy_errorcode}\)%{DATA:proxy_message}:)?(?: \[client %{IPORHOST:clientip}:%{POSINT:clientport}\])?(?: %{DATA:errorcode}:)? %{GREEDYDATA:message}"#,
+    ),
+    (
+        "HTTPD_ERRORLOG",
+        r#"(?:%{HTTPD20_ERRORLOG}|%{HTTPD24_ERRORLOG})"#,
+    ),
+    // Log levels
+    (
+        "LOGLEVEL",
+        r#"(?:[Aa]lert|ALERT|[Tt]race|TRACE|[Dd]ebug|DEBUG|[Nn]otice|NOTICE|[Ii]nfo?(?:rmation)?|INFO?(?:RMATION)?|[Ww]arn?(?:ing)?|WARN?(?:ING)?|[Ee]rr?(?:or)?|ERR?(?:OR)?|[Cc]rit?(?:ical)?|CRIT?(?:ICAL)?|[Ff]atal|FATAL|[Ss]evere|SEVERE|EMERG(?:ENCY)?|[Ee]merg(?:ency)?)"#,
+    ),
+];
+
+lazy_static! {
+    /// The built-in grok patterns by name.
+    pub(crate) static ref BUILTIN_PATTERNS: HashMap<&'static str, &'static str> =
+        PATTERNS.iter().copied().collect();
+}
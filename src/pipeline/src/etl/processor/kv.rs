@@ -0,0 +1,365 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashSet};
+
+use snafu::{ensure, OptionExt};
+use vrl::prelude::Bytes;
+use vrl::value::{KeyString, Value as VrlValue};
+
+use crate::error::{
+    Error, KeyMustBeStringSnafu, KvEmptySeparatorSnafu, ProcessorExpectStringSnafu,
+    ProcessorMissingFieldSnafu, Result, ValueMustBeMapSnafu,
+};
+use crate::etl::field::Fields;
+use crate::etl::processor::{
+    yaml_bool, yaml_new_field, yaml_new_fields, yaml_string, yaml_strings, Processor, FIELDS_NAME,
+    FIELD_NAME, IGNORE_MISSING_NAME,
+};
+
+pub(crate) const PROCESSOR_KV: &str = "kv";
+
+const FIELD_SPLIT_NAME: &str = "field_split";
+const VALUE_SPLIT_NAME: &str = "value_split";
+const INCLUDE_KEYS_NAME: &str = "include_keys";
+const EXCLUDE_KEYS_NAME: &str = "exclude_keys";
+const PREFIX_NAME: &str = "prefix";
+const QUOTE_CHARS_NAME: &str = "quote_chars";
+
+const DEFAULT_FIELD_SPLIT: &str = " ";
+const DEFAULT_VALUE_SPLIT: &str = "=";
+const DEFAULT_QUOTE_CHARS: &str = "\"'";
+
+/// Parses `key=value` pairs, e.g. logfmt lines, into fields.
+///
+/// Values can be quoted with `"` or `'`, or the characters of `quote_chars`, to contain
+/// separators, and `\` escapes the next character in quoted values. A quote only starts
+/// a quoted value right after the value separator, so quotes inside values, e.g.
+/// `user=O'Brien`, are kept as is. Pairs without the value separator are ignored.
+///
+/// The pairs are merged into the document, or into an object named by the target
+/// field if one is given.
+#[derive(Debug)]
+pub struct KvProcessor {
+    fields: Fields,
+    field_split: String,
+    value_split: String,
+    include_keys: Option<HashSet<String>>,
+    exclude_keys: HashSet<String>,
+    prefix: Option<String>,
+    quote_chars: Vec<char>,
+    ignore_missing: bool,
+}
+
+impl Default for KvProcessor {
+    fn default() -> Self {
+        KvProcessor {
+            fields: Fields::default(),
+            field_split: DEFAULT_FIELD_SPLIT.to_string(),
+            value_split: DEFAULT_VALUE_SPLIT.to_string(),
+            include_keys: None,
+            exclude_keys: HashSet::new(),
+            prefix: None,
+            quote_chars: DEFAULT_QUOTE_CHARS.chars().collect(),
+            ignore_missing: false,
+        }
+    }
+}
+
+impl TryFrom<&yaml_rust::yaml::Hash> for KvProcessor {
+    type Error = Error;
+
+    fn try_from(value: &yaml_rust::yaml::Hash) -> Result<Self> {
+        let mut processor = KvProcessor::default();
+
+        for (k, v) in value.iter() {
+            let key = k
+                .as_str()
+                .with_context(|| KeyMustBeStringSnafu { k: k.clone() })?;
+            match key {
+                FIELD_NAME => {
+                    processor.fields = Fields::one(yaml_new_field(v, FIELD_NAME)?);
+                }
+                FIELDS_NAME => {
+                    processor.fields = yaml_new_fields(v, FIELDS_NAME)?;
+                }
+                FIELD_SPLIT_NAME => {
+                    processor.field_split = yaml_string(v, FIELD_SPLIT_NAME)?;
+                }
+                VALUE_SPLIT_NAME => {
+                    processor.value_split = yaml_string(v, VALUE_SPLIT_NAME)?;
+                }
+                INCLUDE_KEYS_NAME => {
+                    processor.include_keys =
+                        Some(yaml_strings(v, INCLUDE_KEYS_NAME)?.into_iter().collect());
+                }
+                EXCLUDE_KEYS_NAME => {
+                    processor.exclude_keys =
+                        yaml_strings(v, EXCLUDE_KEYS_NAME)?.into_iter().collect();
+                }
+                PREFIX_NAME => {
+                    processor.prefix = Some(yaml_string(v, PREFIX_NAME)?);
+                }
+                QUOTE_CHARS_NAME => {
+                    processor.quote_chars = yaml_string(v, QUOTE_CHARS_NAME)?.chars().collect();
+                }
+                IGNORE_MISSING_NAME => {
+                    processor.ignore_missing = yaml_bool(v, IGNORE_MISSING_NAME)?;
+                }
+                _ => {}
+            }
+        }
+
+        ensure!(
+            !processor.field_split.is_empty(),
+            KvEmptySeparatorSnafu {
+                name: FIELD_SPLIT_NAME
+            }
+        );
+        ensure!(
+            !processor.value_split.is_empty(),
+            KvEmptySeparatorSnafu {
+                name: VALUE_SPLIT_NAME
+            }
+        );
+
+        Ok(processor)
+    }
+}
+
+impl KvProcessor {
+    /// Splits `val` into pairs by the field separator, ignoring the separators in quoted values.
+    fn split_unquoted<'a>(&self, val: &'a str) -> Vec<&'a str> {
+        let split = self.field_split.as_str();
+        let mut parts = vec![];
+        let mut quote = None;
+        let mut escaped = false;
+        let mut start = 0;
+        let mut pos = 0;
+        while pos < val.len() {
+            let c = val[pos..].chars().next().unwrap();
+            if escaped {
+                escaped = false;
+            } else if let Some(q) = quote {
+                if c == '\\' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                }
+            } else if self.quote_chars.contains(&c) && self.starts_value(&val[start..pos]) {
+                quote = Some(c);
+            } else if val[pos..].starts_with(split) {
+                parts.push(&val[start..pos]);
+                pos += split.len();
+                start = pos;
+                continue;
+            }
+            pos += c.len_utf8();
+        }
+        parts.push(&val[start..]);
+        parts
+    }
+
+    /// Returns true if the value of the pair starts after `prefix`, i.e. `prefix` ends
+    /// with its first value separator.
+    fn starts_value(&self, prefix: &str) -> bool {
+        prefix
+            .find(self.value_split.as_str())
+            .is_some_and(|i| i + self.value_split.len() == prefix.len())
+    }
+
+    /// Removes the surrounding quotes of `val` and unescapes it.
+    fn unquote(&self, val: &str) -> String {
+        let Some(q) = val.chars().next().filter(|c| self.quote_chars.contains(c)) else {
+            return val.to_string();
+        };
+        if val.len() < 2 || !val.ends_with(q) {
+            return val.to_string();
+        }
+
+        let mut result = String::with_capacity(val.len() - 2);
+        let mut chars = val[1..val.len() - 1].chars();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                if let Some(next) = chars.next() {
+                    result.push(next);
+                    continue;
+                }
+            }
+            result.push(c);
+        }
+        result
+    }
+
+    fn process(&self, val: &str) -> BTreeMap<KeyString, VrlValue> {
+        let mut result = BTreeMap::new();
+        for pair in self.split_unquoted(val) {
+            let Some((key, value)) = pair.split_once(self.value_split.as_str()) else {
+                continue;
+            };
+            let key = key.trim();
+            if key.is_empty() || self.exclude_keys.contains(key) {
+                continue;
+            }
+            if let Some(include_keys) = &self.include_keys {
+                if !include_keys.contains(key) {
+                    continue;
+                }
+            }
+
+            let key = match &self.prefix {
+                Some(prefix) => format!("{prefix}{key}"),
+                None => key.to_string(),
+            };
+            let value = self.unquote(value.trim());
+            result.insert(KeyString::from(key), VrlValue::Bytes(Bytes::from(value)));
+        }
+        result
+    }
+}
+
+impl Processor for KvProcessor {
+    fn kind(&self) -> &str {
+        PROCESSOR_KV
+    }
+
+    fn ignore_missing(&self) -> bool {
+        self.ignore_missing
+    }
+
+    fn exec_mut(&self, mut val: VrlValue) -> Result<VrlValue> {
+        for field in self.fields.iter() {
+            let index = field.input_field();
+            let val = val.as_object_mut().context(ValueMustBeMapSnafu)?;
+            match val.get(index) {
+                Some(VrlValue::Bytes(s)) => {
+                    let result = self.process(String::from_utf8_lossy(s).as_ref());
+                    match field.target_field() {
+                        Some(target) => {
+                            val.insert(KeyString::from(target), VrlValue::Object(result));
+                        }
+                        None => val.extend(result),
+                    }
+                }
+                Some(VrlValue::Null) | None => {
+                    if !self.ignore_missing {
+                        return ProcessorMissingFieldSnafu {
+                            processor: self.kind(),
+                            field: field.input_field(),
+                        }
+                        .fail();
+                    }
+                }
+                Some(v) => {
+                    return ProcessorExpectStringSnafu {
+                        processor: self.kind(),
+                        v: v.clone(),
+                    }
+                    .fail();
+                }
+            }
+        }
+
+        Ok(val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_processor(yaml: &str) -> Result<KvProcessor> {
+        let processor_yaml = yaml_rust::YamlLoader::load_from_str(yaml)
+            .unwrap()
+            .pop()
+            .unwrap();
+        KvProcessor::try_from(processor_yaml.as_hash().unwrap())
+    }
+
+    fn to_map(pairs: &[(&str, &str)]) -> BTreeMap<KeyString, VrlValue> {
+        pairs
+            .iter()
+            .map(|(k, v)| {
+                (
+                    KeyString::from(*k),
+                    VrlValue::Bytes(Bytes::from(v.to_string())),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_logfmt() {
+        let processor = new_processor("field: message").unwrap();
+        let result = processor.process(
+            r#"level=info  msg="request done, status=200" path=/api/v1 quote="say \"hi\"" flag"#,
+        );
+        let expected = to_map(&[
+            ("level", "info"),
+            ("msg", "request done, status=200"),
+            ("path", "/api/v1"),
+            ("quote", r#"say "hi""#),
+        ]);
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_separators_and_keys() {
+        let processor = new_processor(
+            r#"field: message
+field_split: "&"
+value_split: ":"
+include_keys: [a, b, c]
+exclude_keys: [c]
+prefix: "kv_""#,
+        )
+        .unwrap();
+        let result = processor.process("a:1&b:'x&y'&c:3&d:4");
+        let expected = to_map(&[("kv_a", "1"), ("kv_b", "x&y")]);
+        assert_eq!(expected, result);
+
+        new_processor("field: message\nvalue_split: ''").unwrap_err();
+    }
+
+    #[test]
+    fn test_embedded_quotes() {
+        let processor = new_processor("field: message").unwrap();
+        let result = processor.process(r#"user=O'Brien level=info msg=it's"fine" a='b c'"#);
+        let expected = to_map(&[
+            ("user", "O'Brien"),
+            ("level", "info"),
+            ("msg", r#"it's"fine""#),
+            ("a", "b c"),
+        ]);
+        assert_eq!(expected, result);
+
+        // Only the first value separator of a pair starts the value.
+        let result = processor.process("a=b='c d' e=f");
+        let expected = to_map(&[("a", "b='c"), ("e", "f")]);
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_quote_chars() {
+        let processor = new_processor("field: message\nquote_chars: \"|\"").unwrap();
+        let result = processor.process(r#"a=|x y| b="c d""#);
+        let expected = to_map(&[("a", "x y"), ("b", "\"c")]);
+        assert_eq!(expected, result);
+
+        let processor = new_processor("field: message\nquote_chars: ''").unwrap();
+        let result = processor.process("a='x y'");
+        let expected = to_map(&[("a", "'x")]);
+        assert_eq!(expected, result);
+    }
+}
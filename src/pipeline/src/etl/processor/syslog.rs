@@ -0,0 +1,516 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use snafu::{OptionExt, ResultExt};
+use vrl::prelude::Bytes;
+use vrl::value::{KeyString, Value as VrlValue};
+
+use crate::error::{
+    DateParseTimezoneSnafu, Error, KeyMustBeStringSnafu, ProcessorExpectStringSnafu,
+    ProcessorMissingFieldSnafu, Result, SyslogInvalidMessageSnafu, ValueMustBeMapSnafu,
+};
+use crate::etl::field::Fields;
+use crate::etl::processor::{
+    yaml_bool, yaml_new_field, yaml_new_fields, yaml_string, Processor, FIELDS_NAME, FIELD_NAME,
+    IGNORE_MISSING_NAME,
+};
+
+pub(crate) const PROCESSOR_SYSLOG: &str = "syslog";
+
+const TIMEZONE_NAME: &str = "timezone"; // default UTC
+
+const PRIORITY_KEY: &str = "priority";
+const FACILITY_KEY: &str = "facility";
+const SEVERITY_KEY: &str = "severity";
+const VERSION_KEY: &str = "version";
+const TIMESTAMP_KEY: &str = "timestamp";
+const HOSTNAME_KEY: &str = "hostname";
+const APPNAME_KEY: &str = "appname";
+const PROCID_KEY: &str = "procid";
+const MSGID_KEY: &str = "msgid";
+const STRUCTURED_DATA_KEY: &str = "structured_data";
+const MESSAGE_KEY: &str = "message";
+
+/// The nil value of RFC 5424 header fields.
+const NIL_VALUE: &str = "-";
+
+const FACILITIES: [&str; 24] = [
+    "kern",
+    "user",
+    "mail",
+    "daemon",
+    "auth",
+    "syslog",
+    "lpr",
+    "news",
+    "uucp",
+    "cron",
+    "authpriv",
+    "ftp",
+    "ntp",
+    "security",
+    "console",
+    "solaris-cron",
+    "local0",
+    "local1",
+    "local2",
+    "local3",
+    "local4",
+    "local5",
+    "local6",
+    "local7",
+];
+
+const SEVERITIES: [&str; 8] = [
+    "emerg", "alert", "crit", "err", "warning", "notice", "info", "debug",
+];
+
+type ParseResult<T> = std::result::Result<T, &'static str>;
+
+/// Parses RFC 5424 and RFC 3164 (BSD) syslog messages.
+///
+/// The extracted fields are merged into the document, or into an object named by
+/// the target field if one is given. RFC 3164 timestamps carry neither the year
+/// nor the timezone, so they are assumed to be in the current year of `timezone`.
+#[derive(Debug)]
+pub struct SyslogProcessor {
+    fields: Fields,
+    timezone: Tz,
+    ignore_missing: bool,
+}
+
+impl Default for SyslogProcessor {
+    fn default() -> Self {
+        SyslogProcessor {
+            fields: Fields::default(),
+            timezone: Tz::UTC,
+            ignore_missing: false,
+        }
+    }
+}
+
+impl TryFrom<&yaml_rust::yaml::Hash> for SyslogProcessor {
+    type Error = Error;
+
+    fn try_from(value: &yaml_rust::yaml::Hash) -> Result<Self> {
+        let mut fields = Fields::default();
+        let mut timezone = Tz::UTC;
+        let mut ignore_missing = false;
+
+        for (k, v) in value.iter() {
+            let key = k
+                .as_str()
+                .with_context(|| KeyMustBeStringSnafu { k: k.clone() })?;
+            match key {
+                FIELD_NAME => {
+                    fields = Fields::one(yaml_new_field(v, FIELD_NAME)?);
+                }
+                FIELDS_NAME => {
+                    fields = yaml_new_fields(v, FIELDS_NAME)?;
+                }
+                TIMEZONE_NAME => {
+                    let value = yaml_string(v, TIMEZONE_NAME)?;
+                    timezone = value
+                        .parse::<Tz>()
+                        .context(DateParseTimezoneSnafu { value })?;
+                }
+                IGNORE_MISSING_NAME => {
+                    ignore_missing = yaml_bool(v, IGNORE_MISSING_NAME)?;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(SyslogProcessor {
+            fields,
+            timezone,
+            ignore_missing,
+        })
+    }
+}
+
+fn insert_string(result: &mut BTreeMap<KeyString, VrlValue>, key: &str, value: &str) {
+    result.insert(
+        KeyString::from(key),
+        VrlValue::Bytes(Bytes::from(value.to_string())),
+    );
+}
+
+/// Inserts a RFC 5424 header field unless it is nil.
+fn insert_header(result: &mut BTreeMap<KeyString, VrlValue>, key: &str, value: &str) {
+    if value != NIL_VALUE {
+        insert_string(result, key, value);
+    }
+}
+
+/// Splits the next space separated token from `s`.
+fn next_token<'a>(s: &'a str, missing: &'static str) -> ParseResult<(&'a str, &'a str)> {
+    s.split_once(' ').ok_or(missing)
+}
+
+impl SyslogProcessor {
+    fn parse(&self, input: &str) -> Result<BTreeMap<KeyString, VrlValue>> {
+        let mut result = BTreeMap::new();
+        self.parse_inner(input, &mut result)
+            .map_err(|reason| SyslogInvalidMessageSnafu { input, reason }.build())?;
+        Ok(result)
+    }
+
+    fn parse_inner(
+        &self,
+        input: &str,
+        result: &mut BTreeMap<KeyString, VrlValue>,
+    ) -> ParseResult<()> {
+        let mut rest = input.trim_start();
+        let mut has_priority = false;
+        if let Some(after) = rest.strip_prefix('<') {
+            let (priority, after) = after.split_once('>').ok_or("unclosed priority")?;
+            let priority = priority
+                .parse::<u8>()
+                .ok()
+                .filter(|p| (*p as usize) < FACILITIES.len() * SEVERITIES.len())
+                .ok_or("invalid priority")?;
+            result.insert(
+                KeyString::from(PRIORITY_KEY),
+                VrlValue::Integer(priority as i64),
+            );
+            insert_string(result, FACILITY_KEY, FACILITIES[priority as usize / 8]);
+            insert_string(result, SEVERITY_KEY, SEVERITIES[priority as usize % 8]);
+            has_priority = true;
+            rest = after;
+        }
+
+        // RFC 5424 messages have a version right after the priority.
+        match rest.split_once(' ') {
+            Some((version, after))
+                if has_priority
+                    && !version.is_empty()
+                    && version.len() <= 2
+                    && version.bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                result.insert(
+                    KeyString::from(VERSION_KEY),
+                    VrlValue::Integer(version.parse().unwrap()),
+                );
+                Self::parse_rfc5424(after, result)
+            }
+            _ => self.parse_rfc3164(rest, result),
+        }
+    }
+
+    /// Parses `TIMESTAMP HOSTNAME APP-NAME PROCID MSGID STRUCTURED-DATA [MSG]`.
+    fn parse_rfc5424(rest: &str, result: &mut BTreeMap<KeyString, VrlValue>) -> ParseResult<()> {
+        let (timestamp, rest) = next_token(rest, "missing timestamp")?;
+        if timestamp != NIL_VALUE {
+            let timestamp =
+                DateTime::parse_from_rfc3339(timestamp).map_err(|_| "invalid timestamp")?;
+            result.insert(
+                KeyString::from(TIMESTAMP_KEY),
+                VrlValue::Timestamp(timestamp.to_utc()),
+            );
+        }
+        let (hostname, rest) = next_token(rest, "missing hostname")?;
+        insert_header(result, HOSTNAME_KEY, hostname);
+        let (appname, rest) = next_token(rest, "missing app name")?;
+        insert_header(result, APPNAME_KEY, appname);
+        let (procid, rest) = next_token(rest, "missing proc id")?;
+        insert_header(result, PROCID_KEY, procid);
+        let (msgid, rest) = next_token(rest, "missing msg id")?;
+        insert_header(result, MSGID_KEY, msgid);
+
+        let rest = match rest.strip_prefix(NIL_VALUE) {
+            Some(rest) => rest,
+            None => {
+                let (structured_data, rest) = Self::parse_structured_data(rest)?;
+                result.insert(
+                    KeyString::from(STRUCTURED_DATA_KEY),
+                    VrlValue::Object(structured_data),
+                );
+                rest
+            }
+        };
+
+        if let Some(message) = rest.strip_prefix(' ') {
+            let message = message.trim_start_matches('\u{feff}');
+            if !message.is_empty() {
+                insert_string(result, MESSAGE_KEY, message);
+            }
+        } else if !rest.is_empty() {
+            return Err("invalid structured data");
+        }
+        Ok(())
+    }
+
+    /// Parses `[id name="value" ...][id ...]` into `{id: {name: value}}`.
+    fn parse_structured_data(s: &str) -> ParseResult<(BTreeMap<KeyString, VrlValue>, &str)> {
+        let mut structured_data = BTreeMap::new();
+        let mut rest = s;
+        if !rest.starts_with('[') {
+            return Err("invalid structured data");
+        }
+
+        while let Some(element) = rest.strip_prefix('[') {
+            let id_end = element.find([' ', ']']).ok_or("unclosed structured data")?;
+            let id = &element[..id_end];
+            let mut params = BTreeMap::new();
+            let mut remain = &element[id_end..];
+            loop {
+                remain = remain.trim_start_matches(' ');
+                if let Some(after) = remain.strip_prefix(']') {
+                    remain = after;
+                    break;
+                }
+
+                let (name, value) = remain
+                    .split_once("=\"")
+                    .ok_or("invalid structured data param")?;
+                let mut param = String::new();
+                let mut end = None;
+                let mut chars = value.char_indices();
+                while let Some((i, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            if let Some((_, next)) = chars.next() {
+                                // Only `"`, `\` and `]` are escaped.
+                                if !matches!(next, '"' | '\\' | ']') {
+                                    param.push('\\');
+                                }
+                                param.push(next);
+                            }
+                        }
+                        '"' => {
+                            end = Some(i);
+                            break;
+                        }
+                        _ => param.push(c),
+                    }
+                }
+                let end = end.ok_or("unclosed structured data param")?;
+                params.insert(KeyString::from(name), VrlValue::Bytes(Bytes::from(param)));
+                remain = &value[end + 1..];
+            }
+            structured_data.insert(KeyString::from(id), VrlValue::Object(params));
+            rest = remain;
+        }
+
+        Ok((structured_data, rest))
+    }
+
+    /// Parses `TIMESTAMP HOSTNAME TAG: MSG`, where the tag is `app[pid]` or `app`.
+    fn parse_rfc3164(
+        &self,
+        rest: &str,
+        result: &mut BTreeMap<KeyString, VrlValue>,
+    ) -> ParseResult<()> {
+        let (timestamp, rest) = self.parse_rfc3164_timestamp(rest)?;
+        result.insert(
+            KeyString::from(TIMESTAMP_KEY),
+            VrlValue::Timestamp(timestamp),
+        );
+
+        let (hostname, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+        if hostname.is_empty() {
+            return Err("missing hostname");
+        }
+        insert_string(result, HOSTNAME_KEY, hostname);
+
+        let message = match rest.split_once(':') {
+            Some((tag, message)) if !tag.is_empty() && !tag.contains(' ') => {
+                match tag.strip_suffix(']').and_then(|t| t.split_once('[')) {
+                    Some((appname, procid)) => {
+                        insert_string(result, APPNAME_KEY, appname);
+                        insert_string(result, PROCID_KEY, procid);
+                    }
+                    None => insert_string(result, APPNAME_KEY, tag),
+                }
+                message.trim_start_matches(' ')
+            }
+            _ => rest,
+        };
+        if !message.is_empty() {
+            insert_string(result, MESSAGE_KEY, message);
+        }
+        Ok(())
+    }
+
+    /// Parses the `Mmm dd hh:mm:ss` timestamp, or a RFC 3339 timestamp used by
+    /// some syslog daemons in place of it.
+    fn parse_rfc3164_timestamp<'a>(&self, s: &'a str) -> ParseResult<(DateTime<Utc>, &'a str)> {
+        let (token, rest) = s.split_once(' ').unwrap_or((s, ""));
+        if let Ok(timestamp) = DateTime::parse_from_rfc3339(token) {
+            return Ok((timestamp.to_utc(), rest));
+        }
+
+        let (month, rest) = next_token(s, "missing timestamp")?;
+        let (day, rest) = next_token(rest.trim_start_matches(' '), "missing timestamp")?;
+        let (time, rest) = next_token(rest, "missing timestamp")?;
+
+        let now = Utc::now().with_timezone(&self.timezone);
+        let parse = |year: i32| {
+            NaiveDateTime::parse_from_str(
+                &format!("{year} {month} {day} {time}"),
+                "%Y %b %d %H:%M:%S",
+            )
+            .ok()
+            .and_then(|dt| dt.and_local_timezone(self.timezone).earliest())
+        };
+        let mut timestamp = parse(now.year()).ok_or("invalid timestamp")?;
+        // The message may be logged in the last year, e.g. parsing December logs in January.
+        if timestamp > now + Duration::days(1) {
+            timestamp = parse(now.year() - 1).ok_or("invalid timestamp")?;
+        }
+        Ok((timestamp.to_utc(), rest))
+    }
+}
+
+impl Processor for SyslogProcessor {
+    fn kind(&self) -> &str {
+        PROCESSOR_SYSLOG
+    }
+
+    fn ignore_missing(&self) -> bool {
+        self.ignore_missing
+    }
+
+    fn exec_mut(&self, mut val: VrlValue) -> Result<VrlValue> {
+        for field in self.fields.iter() {
+            let index = field.input_field();
+            let val = val.as_object_mut().context(ValueMustBeMapSnafu)?;
+            match val.get(index) {
+                Some(VrlValue::Bytes(s)) => {
+                    let result = self.parse(String::from_utf8_lossy(s).as_ref())?;
+                    match field.target_field() {
+                        Some(target) => {
+                            val.insert(KeyString::from(target), VrlValue::Object(result));
+                        }
+                        None => val.extend(result),
+                    }
+                }
+                Some(VrlValue::Null) | None => {
+                    if !self.ignore_missing {
+                        return ProcessorMissingFieldSnafu {
+                            processor: self.kind(),
+                            field: field.input_field(),
+                        }
+                        .fail();
+                    }
+                }
+                Some(v) => {
+                    return ProcessorExpectStringSnafu {
+                        processor: self.kind(),
+                        v: v.clone(),
+                    }
+                    .fail();
+                }
+            }
+        }
+
+        Ok(val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Timelike;
+
+    use super::*;
+
+    fn string(s: &str) -> VrlValue {
+        VrlValue::Bytes(Bytes::from(s.to_string()))
+    }
+
+    #[test]
+    fn test_rfc5424() {
+        let processor = SyslogProcessor::default();
+        let result = processor
+            .parse(r#"<165>1 2003-10-11T22:14:15.003Z mymachine.example.com evntslog - ID47 [exampleSDID@32473 iut="3" eventSource="Application \"x\""][examplePriority@32473 class="high"] An application event log entry"#)
+            .unwrap();
+
+        assert_eq!(VrlValue::Integer(165), result["priority"]);
+        assert_eq!(string("local4"), result["facility"]);
+        assert_eq!(string("notice"), result["severity"]);
+        assert_eq!(VrlValue::Integer(1), result["version"]);
+        assert_eq!(
+            VrlValue::Timestamp("2003-10-11T22:14:15.003Z".parse().unwrap()),
+            result["timestamp"]
+        );
+        assert_eq!(string("mymachine.example.com"), result["hostname"]);
+        assert_eq!(string("evntslog"), result["appname"]);
+        assert!(!result.contains_key("procid"));
+        assert_eq!(string("ID47"), result["msgid"]);
+        let VrlValue::Object(sd) = &result["structured_data"] else {
+            panic!("structured data must be an object");
+        };
+        let VrlValue::Object(example) = &sd["exampleSDID@32473"] else {
+            panic!("structured data element must be an object");
+        };
+        assert_eq!(string("3"), example["iut"]);
+        assert_eq!(string("Application \"x\""), example["eventSource"]);
+        assert!(sd.contains_key("examplePriority@32473"));
+        assert_eq!(string("An application event log entry"), result["message"]);
+
+        let result = processor
+            .parse("<34>1 2003-10-11T22:14:15.003Z - - - - -")
+            .unwrap();
+        assert!(!result.contains_key("hostname"));
+        assert!(!result.contains_key("message"));
+
+        processor
+            .parse("<34>1 2003-10-11T22:14:15.003Z host")
+            .unwrap_err();
+        processor.parse("<300>Oct 11 22:14:15 host").unwrap_err();
+    }
+
+    #[test]
+    fn test_rfc3164() {
+        let processor = SyslogProcessor::default();
+        let result = processor
+            .parse(
+                "<34>Oct  1 22:14:15 mymachine su[123]: 'su root' failed for lonvick on /dev/pts/8",
+            )
+            .unwrap();
+
+        assert_eq!(VrlValue::Integer(34), result["priority"]);
+        assert_eq!(string("auth"), result["facility"]);
+        assert_eq!(string("crit"), result["severity"]);
+        let VrlValue::Timestamp(ts) = result["timestamp"] else {
+            panic!("timestamp must be parsed");
+        };
+        assert_eq!((10, 1, 22), (ts.month(), ts.day(), ts.hour()));
+        assert_eq!(string("mymachine"), result["hostname"]);
+        assert_eq!(string("su"), result["appname"]);
+        assert_eq!(string("123"), result["procid"]);
+        assert_eq!(
+            string("'su root' failed for lonvick on /dev/pts/8"),
+            result["message"]
+        );
+
+        let result = processor
+            .parse("2024-05-01T10:00:00+08:00 host kernel: oops")
+            .unwrap();
+        assert!(!result.contains_key("priority"));
+        assert_eq!(
+            VrlValue::Timestamp("2024-05-01T02:00:00Z".parse().unwrap()),
+            result["timestamp"]
+        );
+        assert_eq!(string("kernel"), result["appname"]);
+        assert_eq!(string("oops"), result["message"]);
+
+        processor.parse("not a syslog message").unwrap_err();
+    }
+}
@@ -0,0 +1,400 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use common_telemetry::warn;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use snafu::OptionExt;
+use vrl::prelude::Bytes;
+use vrl::value::{KeyString, Value as VrlValue};
+use yaml_rust::{Yaml, YamlLoader};
+
+use crate::error::{
+    Error, KeyMustBeStringSnafu, ProcessorExpectStringSnafu, ProcessorMissingFieldSnafu, Result,
+    ValueMustBeMapSnafu,
+};
+use crate::etl::field::Fields;
+use crate::etl::processor::{
+    yaml_bool, yaml_new_field, yaml_new_fields, Processor, FIELDS_NAME, FIELD_NAME,
+    IGNORE_MISSING_NAME,
+};
+
+pub(crate) const PROCESSOR_USER_AGENT: &str = "user_agent";
+
+const BROWSER_NAME_KEY: &str = "browser_name";
+const BROWSER_VERSION_KEY: &str = "browser_version";
+const OS_NAME_KEY: &str = "os_name";
+const OS_VERSION_KEY: &str = "os_version";
+const DEVICE_NAME_KEY: &str = "device_name";
+
+/// The name of unknown browsers, operating systems and devices.
+const UNKNOWN: &str = "Other";
+
+const REGEXES: &str = include_str!("user_agent/regexes.yaml");
+
+lazy_static! {
+    static ref DATABASE: RegexDatabase =
+        RegexDatabase::load(REGEXES).expect("the embedded user agent regexes must be valid");
+}
+
+/// A regex of the database, with the replacements of the name and version parts.
+#[derive(Debug)]
+struct Parser {
+    regex: Regex,
+    replacements: Vec<Option<String>>,
+}
+
+impl Parser {
+    /// Loads the parser, returns `Ok(None)` if the regex is not supported by the regex crate.
+    fn load(yaml: &Yaml, replacement_keys: &[&str]) -> std::result::Result<Option<Self>, ()> {
+        let mut regex = yaml["regex"].as_str().ok_or(())?.to_string();
+        if yaml["regex_flag"].as_str() == Some("i") {
+            regex = format!("(?i){regex}");
+        }
+        let replacements = replacement_keys
+            .iter()
+            .map(|key| yaml[*key].as_str().map(|s| s.to_string()))
+            .collect();
+
+        match Regex::new(&regex) {
+            Ok(regex) => Ok(Some(Parser {
+                regex,
+                replacements,
+            })),
+            Err(e) => {
+                // A few upstream regexes use look-around or backreferences.
+                warn!("Skip the unsupported user agent regex {}: {}", regex, e);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Returns the name and version parts if the regex matches.
+    fn parse(&self, ua: &str) -> Option<Vec<Option<String>>> {
+        let captures = self.regex.captures(ua)?;
+        let parts = self
+            .replacements
+            .iter()
+            .enumerate()
+            .map(|(i, replacement)| {
+                let part = match replacement {
+                    Some(replacement) => expand(replacement, &captures),
+                    None => captures
+                        .get(i + 1)
+                        .map(|m| m.as_str().to_string())
+                        .unwrap_or_default(),
+                };
+                let part = part.trim();
+                (!part.is_empty()).then(|| part.to_string())
+            })
+            .collect();
+        Some(parts)
+    }
+}
+
+/// Replaces `$1`..`$9` in `replacement` with the capture groups.
+fn expand(replacement: &str, captures: &Captures) -> String {
+    let mut result = String::with_capacity(replacement.len());
+    let mut chars = replacement.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '$' {
+            if let Some(group) = chars.peek().and_then(|d| d.to_digit(10)) {
+                chars.next();
+                if let Some(m) = captures.get(group as usize) {
+                    result.push_str(m.as_str());
+                }
+                continue;
+            }
+        }
+        result.push(c);
+    }
+    result
+}
+
+#[derive(Debug)]
+struct RegexDatabase {
+    user_agent: Vec<Parser>,
+    os: Vec<Parser>,
+    device: Vec<Parser>,
+}
+
+impl RegexDatabase {
+    fn load(s: &str) -> Option<Self> {
+        let yaml = YamlLoader::load_from_str(s).ok()?.pop()?;
+        let load_parsers = |name: &str, replacement_keys: &[&str]| {
+            let parsers = yaml[name]
+                .as_vec()?
+                .iter()
+                .map(|parser| Parser::load(parser, replacement_keys))
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .ok()?;
+            Some(parsers.into_iter().flatten().collect::<Vec<_>>())
+        };
+
+        Some(RegexDatabase {
+            user_agent: load_parsers(
+                "user_agent_parsers",
+                &[
+                    "family_replacement",
+                    "v1_replacement",
+                    "v2_replacement",
+                    "v3_replacement",
+                ],
+            )?,
+            os: load_parsers(
+                "os_parsers",
+                &[
+                    "os_replacement",
+                    "os_v1_replacement",
+                    "os_v2_replacement",
+                    "os_v3_replacement",
+                ],
+            )?,
+            device: load_parsers("device_parsers", &["device_replacement"])?,
+        })
+    }
+}
+
+/// Returns the name and the dot joined version of the first matched parser.
+fn parse_with(parsers: &[Parser], ua: &str) -> (String, Option<String>) {
+    let Some(mut parts) = parsers.iter().find_map(|parser| parser.parse(ua)) else {
+        return (UNKNOWN.to_string(), None);
+    };
+
+    let name = parts
+        .first_mut()
+        .and_then(Option::take)
+        .unwrap_or_else(|| UNKNOWN.to_string());
+    let version = parts
+        .into_iter()
+        .skip(1)
+        .map_while(|part| part)
+        .collect::<Vec<_>>()
+        .join(".");
+    (name, (!version.is_empty()).then_some(version))
+}
+
+/// Parses user agent strings into the browser, operating system and device
+/// with an embedded regex database. The embedded database is a subset of the
+/// ua-parser (uap-core) regexes, `scripts/fetch-uap-regexes.sh` replaces it with
+/// the full upstream database.
+///
+/// The keys of the outputs are prefixed by the target field, or the input field
+/// if the target is not set, e.g. `user_agent_browser_name`.
+#[derive(Debug, Default)]
+pub struct UserAgentProcessor {
+    fields: Fields,
+    ignore_missing: bool,
+}
+
+impl TryFrom<&yaml_rust::yaml::Hash> for UserAgentProcessor {
+    type Error = Error;
+
+    fn try_from(value: &yaml_rust::yaml::Hash) -> Result<Self> {
+        let mut fields = Fields::default();
+        let mut ignore_missing = false;
+
+        for (k, v) in value.iter() {
+            let key = k
+                .as_str()
+                .with_context(|| KeyMustBeStringSnafu { k: k.clone() })?;
+            match key {
+                FIELD_NAME => {
+                    fields = Fields::one(yaml_new_field(v, FIELD_NAME)?);
+                }
+                FIELDS_NAME => {
+                    fields = yaml_new_fields(v, FIELDS_NAME)?;
+                }
+                IGNORE_MISSING_NAME => {
+                    ignore_missing = yaml_bool(v, IGNORE_MISSING_NAME)?;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(UserAgentProcessor {
+            fields,
+            ignore_missing,
+        })
+    }
+}
+
+impl UserAgentProcessor {
+    fn process(&self, prefix: &str, ua: &str) -> BTreeMap<KeyString, VrlValue> {
+        let mut result = BTreeMap::new();
+        let mut insert = |key: &str, value: Option<String>| {
+            if let Some(value) = value {
+                result.insert(
+                    KeyString::from(format!("{prefix}_{key}")),
+                    VrlValue::Bytes(Bytes::from(value)),
+                );
+            }
+        };
+
+        let (browser, browser_version) = parse_with(&DATABASE.user_agent, ua);
+        insert(BROWSER_NAME_KEY, Some(browser));
+        insert(BROWSER_VERSION_KEY, browser_version);
+        let (os, os_version) = parse_with(&DATABASE.os, ua);
+        insert(OS_NAME_KEY, Some(os));
+        insert(OS_VERSION_KEY, os_version);
+        let (device, _) = parse_with(&DATABASE.device, ua);
+        insert(DEVICE_NAME_KEY, Some(device));
+
+        result
+    }
+}
+
+impl Processor for UserAgentProcessor {
+    fn kind(&self) -> &str {
+        PROCESSOR_USER_AGENT
+    }
+
+    fn ignore_missing(&self) -> bool {
+        self.ignore_missing
+    }
+
+    fn exec_mut(&self, mut val: VrlValue) -> Result<VrlValue> {
+        for field in self.fields.iter() {
+            let index = field.input_field();
+            let val = val.as_object_mut().context(ValueMustBeMapSnafu)?;
+            match val.get(index) {
+                Some(VrlValue::Bytes(s)) => {
+                    let result = self.process(
+                        field.target_or_input_field(),
+                        String::from_utf8_lossy(s).as_ref(),
+                    );
+                    val.extend(result);
+                }
+                Some(VrlValue::Null) | None => {
+                    if !self.ignore_missing {
+                        return ProcessorMissingFieldSnafu {
+                            processor: self.kind(),
+                            field: field.input_field(),
+                        }
+                        .fail();
+                    }
+                }
+                Some(v) => {
+                    return ProcessorExpectStringSnafu {
+                        processor: self.kind(),
+                        v: v.clone(),
+                    }
+                    .fail();
+                }
+            }
+        }
+
+        Ok(val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(ua: &str) -> BTreeMap<String, String> {
+        UserAgentProcessor::default()
+            .process("ua", ua)
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.as_str().unwrap().to_string()))
+            .collect()
+    }
+
+    fn expected(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (format!("ua_{k}"), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_user_agent() {
+        let cases = [
+            (
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
+                expected(&[
+                    ("browser_name", "Chrome"),
+                    ("browser_version", "120.0.0"),
+                    ("os_name", "Windows"),
+                    ("os_version", "10"),
+                    ("device_name", "Other"),
+                ]),
+            ),
+            (
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36 Edg/120.0.2210.91",
+                expected(&[
+                    ("browser_name", "Edge"),
+                    ("browser_version", "120.0.2210"),
+                    ("os_name", "Windows"),
+                    ("os_version", "10"),
+                    ("device_name", "Other"),
+                ]),
+            ),
+            (
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 17_1_2 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.1 Mobile/15E148 Safari/604.1",
+                expected(&[
+                    ("browser_name", "Mobile Safari"),
+                    ("browser_version", "17.1"),
+                    ("os_name", "iOS"),
+                    ("os_version", "17.1.2"),
+                    ("device_name", "iPhone"),
+                ]),
+            ),
+            (
+                "Mozilla/5.0 (Linux; Android 13; SM-S918B) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/119.0.6045.163 Mobile Safari/537.36",
+                expected(&[
+                    ("browser_name", "Chrome Mobile"),
+                    ("browser_version", "119.0.6045"),
+                    ("os_name", "Android"),
+                    ("os_version", "13"),
+                    ("device_name", "Samsung SM-S918B"),
+                ]),
+            ),
+            (
+                "Mozilla/5.0 (Macintosh; Intel Mac OS X 10.15; rv:121.0) Gecko/20100101 Firefox/121.0",
+                expected(&[
+                    ("browser_name", "Firefox"),
+                    ("browser_version", "121.0"),
+                    ("os_name", "Mac OS X"),
+                    ("os_version", "10.15"),
+                    ("device_name", "Mac"),
+                ]),
+            ),
+            (
+                "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)",
+                expected(&[
+                    ("browser_name", "Googlebot"),
+                    ("browser_version", "2.1"),
+                    ("os_name", "Other"),
+                    ("device_name", "Spider"),
+                ]),
+            ),
+            (
+                "curl/8.4.0",
+                expected(&[
+                    ("browser_name", "curl"),
+                    ("browser_version", "8.4.0"),
+                    ("os_name", "Other"),
+                    ("device_name", "Other"),
+                ]),
+            ),
+        ];
+
+        for (ua, expected) in cases {
+            assert_eq!(expected, parse(ua), "user agent: {ua}");
+        }
+    }
+}
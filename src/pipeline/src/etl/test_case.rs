@@ -0,0 +1,345 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::value::ValueData;
+use common_time::Timestamp;
+use datatypes::value::column_data_to_json;
+use serde_json::Value as JsonValue;
+use session::context::Channel;
+use snafu::{ensure, OptionExt};
+use vrl::value::Value as VrlValue;
+use yaml_rust::Yaml;
+
+use crate::error::{Error, InvalidPipelineTestCaseSnafu, PipelineTestCaseFailedSnafu, Result};
+use crate::etl::value::yaml_to_vrl_value;
+use crate::etl::PipelineExecOutput;
+use crate::{GreptimePipelineParams, Pipeline, PipelineContext, PipelineDefinition, PipelineRef};
+
+const NAME: &str = "name";
+const INPUT: &str = "input";
+const EXPECTED: &str = "expected";
+
+/// A test case embedded in the `tests` section of a pipeline, e.g.
+///
+/// ```yaml
+/// tests:
+///   - name: access log
+///     input:
+///       message: "127.0.0.1 200"
+///     expected:
+///       - ip: 127.0.0.1
+///         status: 200
+/// ```
+///
+/// The input is a single object or an array of objects. Each expected row only
+/// lists the columns to check, and a `null` means the column must be empty.
+/// Timestamps can be written either as a string or as the raw integer value.
+/// Inputs that are filtered out or dispatched to other pipelines produce no rows.
+#[derive(Debug, Clone)]
+pub struct PipelineTestCase {
+    pub name: String,
+    pub input: Vec<VrlValue>,
+    pub expected: Vec<Vec<(String, Yaml)>>,
+}
+
+/// Parses the `tests` section of a pipeline.
+pub(crate) fn parse_test_cases(value: &Yaml) -> Result<Vec<PipelineTestCase>> {
+    let cases = value.as_vec().context(InvalidPipelineTestCaseSnafu {
+        reason: "tests must be an array",
+    })?;
+
+    cases
+        .iter()
+        .enumerate()
+        .map(|(i, case)| parse_test_case(i, case))
+        .collect()
+}
+
+fn parse_test_case(index: usize, value: &Yaml) -> Result<PipelineTestCase> {
+    ensure!(
+        value.as_hash().is_some(),
+        InvalidPipelineTestCaseSnafu {
+            reason: format!("test case #{index} must be an object"),
+        }
+    );
+
+    let name = match &value[NAME] {
+        Yaml::BadValue => format!("#{index}"),
+        v => v
+            .as_str()
+            .with_context(|| InvalidPipelineTestCaseSnafu {
+                reason: format!("name of test case #{index} must be a string"),
+            })?
+            .to_string(),
+    };
+
+    let input = match yaml_to_vrl_value(&value[INPUT])? {
+        VrlValue::Array(values) => values,
+        v @ VrlValue::Object(_) => vec![v],
+        _ => {
+            return InvalidPipelineTestCaseSnafu {
+                reason: format!("input of test case `{name}` must be an object or an array"),
+            }
+            .fail();
+        }
+    };
+    ensure!(
+        input.iter().all(|v| v.is_object()),
+        InvalidPipelineTestCaseSnafu {
+            reason: format!("input of test case `{name}` must only contain objects"),
+        }
+    );
+
+    let rows = value[EXPECTED]
+        .as_vec()
+        .with_context(|| InvalidPipelineTestCaseSnafu {
+            reason: format!("expected of test case `{name}` must be an array of rows"),
+        })?;
+    let mut expected = Vec::with_capacity(rows.len());
+    for row in rows {
+        let row = row
+            .as_hash()
+            .with_context(|| InvalidPipelineTestCaseSnafu {
+                reason: format!("expected rows of test case `{name}` must be objects"),
+            })?;
+        let mut columns = Vec::with_capacity(row.len());
+        for (column, v) in row {
+            let column = column
+                .as_str()
+                .with_context(|| InvalidPipelineTestCaseSnafu {
+                    reason: format!("column names of test case `{name}` must be strings"),
+                })?;
+            ensure!(
+                !matches!(v, Yaml::Array(_) | Yaml::Hash(_)),
+                InvalidPipelineTestCaseSnafu {
+                    reason: format!(
+                        "expected value of column `{column}` in test case `{name}` must be a scalar"
+                    ),
+                }
+            );
+            columns.push((column.to_string(), v.clone()));
+        }
+        expected.push(columns);
+    }
+
+    Ok(PipelineTestCase {
+        name,
+        input,
+        expected,
+    })
+}
+
+/// Runs all test cases of the pipeline, returns an error on the first mismatch.
+pub(crate) fn run_test_cases(pipeline: &PipelineRef) -> Result<()> {
+    let definition = PipelineDefinition::Resolved(pipeline.clone());
+    let params = GreptimePipelineParams::default();
+    let pipeline_ctx = PipelineContext::new(&definition, &params, Channel::Unknown);
+
+    for test_case in pipeline.test_cases() {
+        test_case.run(pipeline, &pipeline_ctx)?;
+    }
+    Ok(())
+}
+
+impl PipelineTestCase {
+    fn run(&self, pipeline: &Pipeline, pipeline_ctx: &PipelineContext<'_>) -> Result<()> {
+        let mut schema_info = pipeline.schema_info();
+        let mut rows = vec![];
+        for input in &self.input {
+            let output = pipeline
+                .exec_mut(input.clone(), pipeline_ctx, &mut schema_info)
+                .map_err(|e| self.failed(e.to_string()))?;
+            if let PipelineExecOutput::Transformed(output) = output {
+                rows.push(output.row);
+            }
+        }
+
+        if rows.len() != self.expected.len() {
+            return Err(self.failed(format!(
+                "expected {} rows, got {}",
+                self.expected.len(),
+                rows.len()
+            )));
+        }
+
+        for (i, (row, expected)) in rows.iter().zip(&self.expected).enumerate() {
+            for (column, expected) in expected {
+                let actual = schema_info
+                    .index
+                    .get(column)
+                    .and_then(|index| row.values.get(*index))
+                    .and_then(|v| v.value_data.as_ref());
+                if !value_matches(expected, actual) {
+                    let actual = actual.map_or(JsonValue::Null, |v| column_data_to_json(v.clone()));
+                    return Err(self.failed(format!(
+                        "row {i}, column `{column}`: expected {}, got {actual}",
+                        display_expected(expected),
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn failed(&self, reason: String) -> Error {
+        PipelineTestCaseFailedSnafu {
+            name: &self.name,
+            reason,
+        }
+        .build()
+    }
+}
+
+fn display_expected(expected: &Yaml) -> String {
+    yaml_to_vrl_value(expected)
+        .map(|v| v.to_string())
+        .unwrap_or_else(|_| format!("{expected:?}"))
+}
+
+fn timestamp_value(value: &ValueData) -> Option<Timestamp> {
+    match value {
+        ValueData::TimestampSecondValue(v) => Some(Timestamp::new_second(*v)),
+        ValueData::TimestampMillisecondValue(v) => Some(Timestamp::new_millisecond(*v)),
+        ValueData::TimestampMicrosecondValue(v) => Some(Timestamp::new_microsecond(*v)),
+        ValueData::TimestampNanosecondValue(v) => Some(Timestamp::new_nanosecond(*v)),
+        _ => None,
+    }
+}
+
+fn value_matches(expected: &Yaml, actual: Option<&ValueData>) -> bool {
+    let Some(actual) = actual else {
+        return matches!(expected, Yaml::Null);
+    };
+
+    if let Some(ts) = timestamp_value(actual) {
+        return match expected {
+            Yaml::Integer(v) => ts.value() == *v,
+            Yaml::String(s) => Timestamp::from_str_utc(s).is_ok_and(|v| v == ts),
+            _ => false,
+        };
+    }
+
+    match (expected, column_data_to_json(actual.clone())) {
+        (Yaml::Boolean(e), JsonValue::Bool(a)) => *e == a,
+        (Yaml::String(e), JsonValue::String(a)) => *e == a,
+        (Yaml::Integer(e), JsonValue::Number(a)) => {
+            a.as_i64() == Some(*e) || a.as_f64() == Some(*e as f64)
+        }
+        (Yaml::Real(e), JsonValue::Number(a)) => e.parse::<f64>().ok() == a.as_f64(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::etl::{parse, Content};
+
+    fn new_pipeline(tests: &str) -> Result<PipelineRef> {
+        let yaml = format!(
+            r#"
+processors:
+  - dissect:
+      field: message
+      pattern: "%{{ip}} %{{status}}"
+transform:
+  - field: ip
+    type: string
+  - field: status
+    type: int32
+{tests}"#
+        );
+        parse(&Content::Yaml(&yaml)).map(Arc::new)
+    }
+
+    #[test]
+    fn test_run_test_cases() {
+        let pipeline = new_pipeline(
+            r#"
+tests:
+  - name: access log
+    input:
+      - message: "127.0.0.1 200"
+      - message: "::1 404"
+    expected:
+      - ip: 127.0.0.1
+        status: 200
+      - status: 404
+"#,
+        )
+        .unwrap();
+        assert_eq!(pipeline.test_cases().len(), 1);
+        run_test_cases(&pipeline).unwrap();
+
+        let pipeline = new_pipeline(
+            r#"
+tests:
+  - input:
+      message: "127.0.0.1 200"
+    expected:
+      - status: 500
+"#,
+        )
+        .unwrap();
+        let err = run_test_cases(&pipeline).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Pipeline test case `#0` failed: row 0, column `status`: expected 500, got 200"
+        );
+
+        let pipeline = new_pipeline(
+            r#"
+tests:
+  - name: missing rows
+    input:
+      message: "127.0.0.1 200"
+    expected: []
+"#,
+        )
+        .unwrap();
+        let err = run_test_cases(&pipeline).unwrap_err();
+        assert!(err.to_string().contains("expected 0 rows, got 1"));
+    }
+
+    #[test]
+    fn test_invalid_test_cases() {
+        let err = new_pipeline("tests: {}").unwrap_err();
+        assert!(matches!(err, Error::InvalidPipelineTestCase { .. }));
+
+        let err = new_pipeline(
+            r#"
+tests:
+  - input: 1
+    expected: []
+"#,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::InvalidPipelineTestCase { .. }));
+
+        let err = new_pipeline(
+            r#"
+tests:
+  - input:
+      message: "127.0.0.1 200"
+    expected:
+      - status: [200]
+"#,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::InvalidPipelineTestCase { .. }));
+    }
+}
@@ -15,12 +15,14 @@
 #![feature(string_from_utf8_lossy_owned)]
 
 mod dispatcher;
+mod elasticsearch;
 pub mod error;
 mod etl;
 mod manager;
 mod metrics;
 mod tablesuffix;
 
+pub use elasticsearch::translate_ingest_pipeline;
 pub use etl::ctx_req::{ContextOpt, ContextReq};
 pub use etl::processor::Processor;
 pub use etl::transform::transformer::greptime::{GreptimePipelineParams, SchemaInfo};
@@ -268,6 +268,64 @@ impl PipelineOperator {
             .await
     }
 
+    /// List all versions of a pipeline, the latest first.
+    pub async fn list_pipeline_versions(
+        &self,
+        name: &str,
+        query_ctx: QueryContextRef,
+    ) -> Result<Vec<TimestampNanosecond>> {
+        let schema = query_ctx.current_schema();
+        self.create_pipeline_table_if_not_exists(query_ctx.clone())
+            .await?;
+
+        self.get_pipeline_table_from_cache(query_ctx.current_catalog())
+            .context(PipelineTableNotFoundSnafu)?
+            .list_pipeline_versions(&schema, name)
+            .await
+    }
+
+    /// Diff two versions of a pipeline, returns a unified diff from `from` to `to`.
+    pub async fn diff_pipeline(
+        &self,
+        name: &str,
+        from: PipelineVersion,
+        to: PipelineVersion,
+        query_ctx: QueryContextRef,
+    ) -> Result<String> {
+        let schema = query_ctx.current_schema();
+        self.create_pipeline_table_if_not_exists(query_ctx.clone())
+            .await?;
+
+        self.get_pipeline_table_from_cache(query_ctx.current_catalog())
+            .context(PipelineTableNotFoundSnafu)?
+            .diff_pipeline(&schema, name, from, to)
+            .await
+    }
+
+    /// Roll back a pipeline to a previous version by name.
+    /// If the version is not set, the version before the latest one is used.
+    pub async fn rollback_pipeline(
+        &self,
+        name: &str,
+        version: PipelineVersion,
+        query_ctx: QueryContextRef,
+    ) -> Result<PipelineInfo> {
+        let schema = query_ctx.current_schema();
+        self.create_pipeline_table_if_not_exists(query_ctx.clone())
+            .await?;
+
+        let timer = Instant::now();
+        self.get_pipeline_table_from_cache(query_ctx.current_catalog())
+            .context(PipelineTableNotFoundSnafu)?
+            .rollback_pipeline(&schema, name, version)
+            .inspect(|re| {
+                METRIC_PIPELINE_CREATE_HISTOGRAM
+                    .with_label_values(&[&re.is_ok().to_string()])
+                    .observe(timer.elapsed().as_secs_f64())
+            })
+            .await
+    }
+
     /// Compile a pipeline.
     pub fn build_pipeline(pipeline: &str) -> Result<Pipeline> {
         PipelineTable::compile_pipeline(pipeline)
@@ -36,6 +36,7 @@ use operator::statement::StatementExecutorRef;
 use query::dataframe::DataFrame;
 use query::QueryEngineRef;
 use session::context::{QueryContextBuilder, QueryContextRef};
+use similar::TextDiff;
 use snafu::{ensure, OptionExt, ResultExt};
 use table::metadata::TableInfo;
 use table::table::adapter::DfTableProviderAdapter;
@@ -44,8 +45,10 @@ use table::TableRef;
 use crate::error::{
     BuildDfLogicalPlanSnafu, CastTypeSnafu, CollectRecordsSnafu, DataFrameSnafu, Error,
     ExecuteInternalStatementSnafu, InsertPipelineSnafu, InvalidPipelineVersionSnafu,
-    MultiPipelineWithDiffSchemaSnafu, PipelineNotFoundSnafu, RecordBatchLenNotMatchSnafu, Result,
+    MultiPipelineWithDiffSchemaSnafu, NoPreviousPipelineVersionSnafu, PipelineNotFoundSnafu,
+    RecordBatchLenNotMatchSnafu, Result,
 };
+use crate::etl::test_case::run_test_cases;
 use crate::etl::{parse, Content, Pipeline};
 use crate::manager::pipeline_cache::PipelineCache;
 use crate::manager::{PipelineInfo, PipelineVersion};
@@ -59,6 +62,8 @@ const PIPELINE_TABLE_PIPELINE_CONTENT_TYPE_COLUMN_NAME: &str = "content_type";
 const PIPELINE_TABLE_PIPELINE_CONTENT_COLUMN_NAME: &str = "pipeline";
 pub(crate) const PIPELINE_TABLE_CREATED_AT_COLUMN_NAME: &str = "created_at";
 pub(crate) const EMPTY_SCHEMA_NAME: &str = "";
+/// The content type of rolled back pipelines, only yaml pipelines are supported for now.
+const PIPELINE_CONTENT_TYPE_YAML: &str = "yaml";
 
 /// PipelineTable is a table that stores the pipeline schema and content.
 /// Every catalog has its own pipeline table.
@@ -365,6 +370,8 @@ impl PipelineTable {
         pipeline: &str,
     ) -> Result<PipelineInfo> {
         let compiled_pipeline = Arc::new(Self::compile_pipeline(pipeline)?);
+        // reject the pipeline if any of its embedded test cases fails
+        run_test_cases(&compiled_pipeline)?;
         // we will use the version in the future
         let version = self
             .insert_pipeline_to_pipeline_table(name, content_type, pipeline)
@@ -391,6 +398,77 @@ impl PipelineTable {
         Ok((version, compiled_pipeline))
     }
 
+    /// List all versions of a pipeline visible under the schema, the latest first.
+    pub async fn list_pipeline_versions(
+        &self,
+        schema: &str,
+        name: &str,
+    ) -> Result<Vec<TimestampNanosecond>> {
+        let pipelines = self.find_pipeline(name, None).await?;
+        Ok(pipelines
+            .into_iter()
+            .filter(|(_, s, _)| s == EMPTY_SCHEMA_NAME || s == schema)
+            .map(|(_, _, version)| version)
+            .collect())
+    }
+
+    /// Diff two versions of a pipeline.
+    /// Returns the unified diff from the content of `from` to the content of `to`.
+    pub async fn diff_pipeline(
+        &self,
+        schema: &str,
+        name: &str,
+        from: PipelineVersion,
+        to: PipelineVersion,
+    ) -> Result<String> {
+        let (from_pipeline, from_version) = self.get_pipeline_str(schema, name, from).await?;
+        let (to_pipeline, to_version) = self.get_pipeline_str(schema, name, to).await?;
+
+        Ok(TextDiff::from_lines(&from_pipeline, &to_pipeline)
+            .unified_diff()
+            .header(
+                &from_version.0.to_iso8601_string(),
+                &to_version.0.to_iso8601_string(),
+            )
+            .to_string())
+    }
+
+    /// Roll back a pipeline to a previous version.
+    /// The content of that version is inserted again, so it becomes the latest version
+    /// and the history is kept. If the version is not set, the one before the latest is used.
+    pub async fn rollback_pipeline(
+        &self,
+        schema: &str,
+        name: &str,
+        version: PipelineVersion,
+    ) -> Result<PipelineInfo> {
+        let version = match version {
+            Some(version) => version,
+            None => {
+                let versions = self.list_pipeline_versions(schema, name).await?;
+                ensure!(
+                    !versions.is_empty(),
+                    PipelineNotFoundSnafu {
+                        name,
+                        version: None
+                    }
+                );
+                *versions
+                    .get(1)
+                    .context(NoPreviousPipelineVersionSnafu { name })?
+            }
+        };
+
+        let (pipeline, _) = self.get_pipeline_str(schema, name, Some(version)).await?;
+        info!(
+            "Roll back pipeline, name: {:?}, version: {:?}",
+            name,
+            version.0.to_iso8601_string()
+        );
+        self.insert_and_compile(name, PIPELINE_CONTENT_TYPE_YAML, &pipeline)
+            .await
+    }
+
     pub async fn delete_pipeline(
         &self,
         name: &str,
@@ -0,0 +1,203 @@
+#!/usr/bin/env python3
+# Copyright 2023 Greptime Team
+#
+# Licensed under the Apache License, Version 2.0 (the "License");
+# you may not use this file except in compliance with the License.
+# You may obtain a copy of the License at
+#
+#     http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing, software
+# distributed under the License is distributed on an "AS IS" BASIS,
+# WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+# See the License for the specific language governing permissions and
+# limitations under the License.
+
+"""Generates the small MaxMind DB (mmdb) files used by the tests of the geoip processor.
+
+The files follow the MaxMind DB format 2.0 (https://maxmind.github.io/MaxMind-DB/)
+with IPv4 search trees and 24 bit records. Run it in this directory:
+
+    python3 generate-geoip-mmdb.py
+"""
+
+import ipaddress
+import struct
+
+RECORD_SIZE = 24
+METADATA_MARKER = b"\xab\xcd\xefMaxMind.com"
+BUILD_EPOCH = 1700000000
+
+CITY_NETWORKS = {
+    "81.2.69.0/24": {
+        "city": {"geoname_id": 2643743, "names": {"en": "London"}},
+        "continent": {"code": "EU", "geoname_id": 6255148, "names": {"en": "Europe"}},
+        "country": {"geoname_id": 2635167, "iso_code": "GB", "names": {"en": "United Kingdom"}},
+        "location": {
+            "accuracy_radius": 100,
+            "latitude": 51.5142,
+            "longitude": -0.0931,
+            "time_zone": "Europe/London",
+        },
+        "subdivisions": [
+            {"geoname_id": 6269131, "iso_code": "ENG", "names": {"en": "England"}},
+        ],
+    },
+    "216.160.83.0/24": {
+        "city": {"geoname_id": 5803556, "names": {"en": "Milton"}},
+        "continent": {"code": "NA", "geoname_id": 6255149, "names": {"en": "North America"}},
+        "country": {"geoname_id": 6252001, "iso_code": "US", "names": {"en": "United States"}},
+        "location": {
+            "accuracy_radius": 22,
+            "latitude": 47.2513,
+            "longitude": -122.3149,
+            "time_zone": "America/Los_Angeles",
+        },
+        "postal": {"code": "98354"},
+        "subdivisions": [
+            {"geoname_id": 5815135, "iso_code": "WA", "names": {"en": "Washington"}},
+        ],
+    },
+}
+
+ASN_NETWORKS = {
+    "1.128.0.0/11": {
+        "autonomous_system_number": 1221,
+        "autonomous_system_organization": "Telstra Pty Ltd",
+    },
+}
+
+
+class Uint16(int):
+    pass
+
+
+class Uint32(int):
+    pass
+
+
+class Uint64(int):
+    pass
+
+
+def encode_control(type_num, size):
+    if size < 29:
+        size_bits, extra = size, b""
+    elif size < 29 + 256:
+        size_bits, extra = 29, bytes([size - 29])
+    elif size < 285 + 65536:
+        size_bits, extra = 30, struct.pack(">H", size - 285)
+    else:
+        size_bits, extra = 31, struct.pack(">I", size - 65821)[1:]
+    if type_num <= 7:
+        return bytes([(type_num << 5) | size_bits]) + extra
+    return bytes([size_bits, type_num - 7]) + extra
+
+
+def encode_uint(type_num, value):
+    payload = value.to_bytes((value.bit_length() + 7) // 8, "big")
+    return encode_control(type_num, len(payload)) + payload
+
+
+def encode(value):
+    if isinstance(value, str):
+        payload = value.encode("utf-8")
+        return encode_control(2, len(payload)) + payload
+    if isinstance(value, float):
+        return encode_control(3, 8) + struct.pack(">d", value)
+    if isinstance(value, Uint16):
+        return encode_uint(5, value)
+    if isinstance(value, Uint64):
+        return encode_uint(9, value)
+    if isinstance(value, int):
+        return encode_uint(6, value)
+    if isinstance(value, dict):
+        out = encode_control(7, len(value))
+        for k, v in value.items():
+            out += encode(k) + encode(v)
+        return out
+    if isinstance(value, list):
+        out = encode_control(11, len(value))
+        for v in value:
+            out += encode(v)
+        return out
+    raise TypeError(f"unsupported value {value!r}")
+
+
+def typed(value):
+    """Uses the types of the official databases for some keys."""
+    if isinstance(value, dict):
+        out = {}
+        for k, v in value.items():
+            if k == "accuracy_radius":
+                out[k] = Uint16(v)
+            else:
+                out[k] = typed(v)
+        return out
+    if isinstance(value, list):
+        return [typed(v) for v in value]
+    return value
+
+
+def build_tree(networks):
+    """Returns the nodes of the search tree, each node is a pair of records.
+
+    A record is ("node", index), ("data", offset) or ("empty",).
+    """
+    nodes = [[("empty",), ("empty",)]]
+    for network, offset in networks:
+        bits = int(network.network_address)
+        node = 0
+        for depth in range(network.prefixlen):
+            bit = (bits >> (31 - depth)) & 1
+            if depth == network.prefixlen - 1:
+                nodes[node][bit] = ("data", offset)
+            else:
+                record = nodes[node][bit]
+                if record[0] != "node":
+                    nodes.append([("empty",), ("empty",)])
+                    nodes[node][bit] = ("node", len(nodes) - 1)
+                node = nodes[node][bit][1]
+    return nodes
+
+
+def write_database(path, database_type, records):
+    data = b""
+    networks = []
+    for network, record in records.items():
+        networks.append((ipaddress.ip_network(network), len(data)))
+        data += encode(typed(record))
+
+    nodes = build_tree(networks)
+    node_count = len(nodes)
+
+    def record_value(record):
+        if record[0] == "node":
+            return record[1]
+        if record[0] == "data":
+            return node_count + 16 + record[1]
+        return node_count
+
+    tree = b""
+    for left, right in nodes:
+        tree += record_value(left).to_bytes(3, "big") + record_value(right).to_bytes(3, "big")
+
+    metadata = {
+        "binary_format_major_version": Uint16(2),
+        "binary_format_minor_version": Uint16(0),
+        "build_epoch": Uint64(BUILD_EPOCH),
+        "database_type": database_type,
+        "description": {"en": f"Test database of {database_type} for the geoip processor"},
+        "ip_version": Uint16(4),
+        "languages": ["en"],
+        "node_count": Uint32(node_count),
+        "record_size": Uint16(RECORD_SIZE),
+    }
+
+    with open(path, "wb") as f:
+        f.write(tree + b"\x00" * 16 + data + METADATA_MARKER + encode(metadata))
+
+
+if __name__ == "__main__":
+    write_database("GeoLite2-City-Test.mmdb", "GeoLite2-City", CITY_NETWORKS)
+    write_database("GeoLite2-ASN-Test.mmdb", "GeoLite2-ASN", ASN_NETWORKS)
@@ -0,0 +1,135 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use greptime_proto::v1::value::ValueData::{I64Value, StringValue};
+use greptime_proto::v1::{ColumnDataType, SemanticType};
+
+#[test]
+fn test_grok_combined_apache_log() {
+    let input_value_str = r#"
+    [
+      {
+        "message": "127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] \"GET /apache_pb.gif HTTP/1.0\" 200 2326 \"-\" \"curl/7.68.0\""
+      }
+    ]
+"#;
+
+    let pipeline_yaml = r#"
+processors:
+  - grok:
+      field: message
+      pattern: "%{COMBINEDAPACHELOG}"
+
+transform:
+  - fields:
+      - clientip
+      - verb
+      - request
+    type: string
+  - field: bytes
+    type: int64
+"#;
+
+    let output = common::parse_and_exec(input_value_str, pipeline_yaml);
+
+    assert_eq!(
+        output.schema,
+        vec![
+            common::make_column_schema(
+                "clientip".to_string(),
+                ColumnDataType::String,
+                SemanticType::Field,
+            ),
+            common::make_column_schema(
+                "verb".to_string(),
+                ColumnDataType::String,
+                SemanticType::Field,
+            ),
+            common::make_column_schema(
+                "request".to_string(),
+                ColumnDataType::String,
+                SemanticType::Field,
+            ),
+            common::make_column_schema(
+                "bytes".to_string(),
+                ColumnDataType::Int64,
+                SemanticType::Field,
+            ),
+            common::make_column_schema(
+                "greptime_timestamp".to_string(),
+                ColumnDataType::TimestampNanosecond,
+                SemanticType::Timestamp,
+            ),
+        ]
+    );
+
+    let values = &output.rows[0].values;
+    assert_eq!(
+        values[0].value_data,
+        Some(StringValue("127.0.0.1".to_string()))
+    );
+    assert_eq!(values[1].value_data, Some(StringValue("GET".to_string())));
+    assert_eq!(
+        values[2].value_data,
+        Some(StringValue("/apache_pb.gif".to_string()))
+    );
+    assert_eq!(values[3].value_data, Some(I64Value(2326)));
+}
+
+#[test]
+fn test_grok_pattern_definitions_and_fallback() {
+    let input_value_str = r#"
+    [
+      {
+        "message": "user=alice took 42ms"
+      },
+      {
+        "message": "anonymous took 7ms"
+      }
+    ]
+"#;
+
+    let pipeline_yaml = r#"
+processors:
+  - grok:
+      field: message
+      patterns:
+        - "user=%{USERNAME:user} took %{DURATION}"
+        - "%{WORD:user} took %{DURATION}"
+      pattern_definitions:
+        DURATION: "%{INT:latency:int}ms"
+
+transform:
+  - field: user
+    type: string
+  - field: latency
+    type: int64
+"#;
+
+    let output = common::parse_and_exec(input_value_str, pipeline_yaml);
+
+    assert_eq!(output.rows.len(), 2);
+    assert_eq!(
+        output.rows[0].values[0].value_data,
+        Some(StringValue("alice".to_string()))
+    );
+    assert_eq!(output.rows[0].values[1].value_data, Some(I64Value(42)));
+    assert_eq!(
+        output.rows[1].values[0].value_data,
+        Some(StringValue("anonymous".to_string()))
+    );
+    assert_eq!(output.rows[1].values[1].value_data, Some(I64Value(7)));
+}
@@ -0,0 +1,58 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use greptime_proto::v1::value::ValueData::{I64Value, StringValue};
+
+#[test]
+fn test_kv_logfmt() {
+    let input_value_str = r#"
+    [
+      {
+        "message": "level=warn msg=\"disk is almost full\" used=93 host=web-1"
+      }
+    ]
+"#;
+
+    let pipeline_yaml = r#"
+processors:
+  - kv:
+      field: message
+      exclude_keys:
+        - host
+
+transform:
+  - fields:
+      - level
+      - msg
+    type: string
+  - field: used
+    type: int64
+"#;
+
+    let output = common::parse_and_exec(input_value_str, pipeline_yaml);
+
+    let values = &output.rows[0].values;
+    assert_eq!(values[0].value_data, Some(StringValue("warn".to_string())));
+    assert_eq!(
+        values[1].value_data,
+        Some(StringValue("disk is almost full".to_string()))
+    );
+    assert_eq!(values[2].value_data, Some(I64Value(93)));
+    assert!(output
+        .schema
+        .iter()
+        .all(|column| column.column_name != "host"));
+}
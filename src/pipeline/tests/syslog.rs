@@ -0,0 +1,77 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use greptime_proto::v1::value::ValueData::{StringValue, TimestampNanosecondValue, U8Value};
+
+#[test]
+fn test_syslog_rfc5424() {
+    let input_value_str = r#"
+    [
+      {
+        "message": "<165>1 2003-10-11T22:14:15.003Z mymachine.example.com evntslog - ID47 - An application event"
+      }
+    ]
+"#;
+
+    let pipeline_yaml = r#"
+processors:
+  - syslog:
+      field: message
+
+transform:
+  - field: priority
+    type: uint8
+  - fields:
+      - facility
+      - severity
+      - hostname
+      - appname
+      - message
+    type: string
+  - field: timestamp
+    type: time
+    index: timestamp
+"#;
+
+    let output = common::parse_and_exec(input_value_str, pipeline_yaml);
+
+    let values = &output.rows[0].values;
+    assert_eq!(values[0].value_data, Some(U8Value(165)));
+    assert_eq!(
+        values[1].value_data,
+        Some(StringValue("local4".to_string()))
+    );
+    assert_eq!(
+        values[2].value_data,
+        Some(StringValue("notice".to_string()))
+    );
+    assert_eq!(
+        values[3].value_data,
+        Some(StringValue("mymachine.example.com".to_string()))
+    );
+    assert_eq!(
+        values[4].value_data,
+        Some(StringValue("evntslog".to_string()))
+    );
+    assert_eq!(
+        values[5].value_data,
+        Some(StringValue("An application event".to_string()))
+    );
+    assert_eq!(
+        values[6].value_data,
+        Some(TimestampNanosecondValue(1065910455003000000))
+    );
+}
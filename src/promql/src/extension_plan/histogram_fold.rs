@@ -55,11 +55,13 @@ use futures::{ready, Stream, StreamExt};
 /// - `field` will become a [ListArray] of [f64]
 /// - other columns will be sampled every `bucket_num` element, but their types won't change.
 ///
-/// Due to the folding or sampling, the output rows number will become `input_rows` / `bucket_num`.
+/// Due to the folding or sampling, the output rows number will become the number of
+/// histograms in the input.
 ///
 /// # Requirement
 /// - Input should be sorted on `<tag list>, ts, le ASC`.
-/// - The value set of `le` should be same. I.e., buckets of every series should be same.
+/// - Buckets of every histogram end with `+Inf`. The value set of `le` may differ between
+///   histograms, e.g. native histograms that only have their populated buckets.
 ///
 /// [1]: https://prometheus.io/docs/concepts/metric_types/#histogram
 #[derive(Debug, PartialEq, Hash, Eq)]
@@ -72,6 +74,8 @@ pub struct HistogramFold {
     input: LogicalPlan,
     field_column: String,
     quantile: OrderedF64,
+    /// Evaluates `histogram_fraction(lower, upper)` instead of the quantile if set.
+    fraction: Option<(OrderedF64, OrderedF64)>,
     output_schema: DFSchemaRef,
 }
 
@@ -93,11 +97,19 @@ impl UserDefinedLogicalNodeCore for HistogramFold {
     }
 
     fn fmt_for_explain(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "HistogramFold: le={}, field={}, quantile={}",
-            self.le_column, self.field_column, self.quantile
-        )
+        if let Some((lower, upper)) = self.fraction {
+            write!(
+                f,
+                "HistogramFold: le={}, field={}, fraction=[{}, {}]",
+                self.le_column, self.field_column, lower, upper
+            )
+        } else {
+            write!(
+                f,
+                "HistogramFold: le={}, field={}, quantile={}",
+                self.le_column, self.field_column, self.quantile
+            )
+        }
     }
 
     fn with_exprs_and_inputs(
@@ -111,6 +123,7 @@ impl UserDefinedLogicalNodeCore for HistogramFold {
             input: inputs.into_iter().next().unwrap(),
             field_column: self.field_column.clone(),
             quantile: self.quantile,
+            fraction: self.fraction,
             // This method cannot return error. Otherwise we should re-calculate
             // the output schema
             output_schema: self.output_schema.clone(),
@@ -135,10 +148,18 @@ impl HistogramFold {
             input,
             field_column,
             quantile: quantile.into(),
+            fraction: None,
             output_schema,
         })
     }
 
+    /// Evaluates the fraction of observations between `lower` and `upper`
+    /// (`histogram_fraction` in PromQL) instead of the quantile.
+    pub fn with_fraction(mut self, lower: f64, upper: f64) -> Self {
+        self.fraction = Some((lower.into(), upper.into()));
+        self
+    }
+
     pub const fn name() -> &'static str {
         "HistogramFold"
     }
@@ -194,6 +215,9 @@ impl HistogramFold {
             ts_column_index,
             input: exec_input,
             quantile: self.quantile.into(),
+            fraction: self
+                .fraction
+                .map(|(lower, upper)| (lower.into(), upper.into())),
             output_schema,
             metric: ExecutionPlanMetricsSet::new(),
             properties,
@@ -241,7 +265,11 @@ impl PartialOrd for HistogramFold {
             Some(core::cmp::Ordering::Equal) => {}
             ord => return ord,
         }
-        self.quantile.partial_cmp(&other.quantile)
+        match self.quantile.partial_cmp(&other.quantile) {
+            Some(core::cmp::Ordering::Equal) => {}
+            ord => return ord,
+        }
+        self.fraction.partial_cmp(&other.fraction)
     }
 }
 
@@ -255,6 +283,7 @@ pub struct HistogramFoldExec {
     field_column_index: usize,
     ts_column_index: usize,
     quantile: f64,
+    fraction: Option<(f64, f64)>,
     metric: ExecutionPlanMetricsSet,
     properties: PlanProperties,
 }
@@ -331,6 +360,7 @@ impl ExecutionPlan for HistogramFoldExec {
             le_column_index: self.le_column_index,
             ts_column_index: self.ts_column_index,
             quantile: self.quantile,
+            fraction: self.fraction,
             output_schema: self.output_schema.clone(),
             field_column_index: self.field_column_index,
             properties: self.properties.clone(),
@@ -355,8 +385,8 @@ impl ExecutionPlan for HistogramFoldExec {
             le_column_index: self.le_column_index,
             field_column_index: self.field_column_index,
             quantile: self.quantile,
+            fraction: self.fraction,
             normal_indices: normal_indices.into_iter().collect(),
-            bucket_size: None,
             input_buffer: vec![],
             input,
             output_schema,
@@ -422,11 +452,19 @@ impl DisplayAs for HistogramFoldExec {
             DisplayFormatType::Default
             | DisplayFormatType::Verbose
             | DisplayFormatType::TreeRender => {
-                write!(
-                    f,
-                    "HistogramFoldExec: le=@{}, field=@{}, quantile={}",
-                    self.le_column_index, self.field_column_index, self.quantile
-                )
+                if let Some((lower, upper)) = self.fraction {
+                    write!(
+                        f,
+                        "HistogramFoldExec: le=@{}, field=@{}, fraction=[{}, {}]",
+                        self.le_column_index, self.field_column_index, lower, upper
+                    )
+                } else {
+                    write!(
+                        f,
+                        "HistogramFoldExec: le=@{}, field=@{}, quantile={}",
+                        self.le_column_index, self.field_column_index, self.quantile
+                    )
+                }
             }
         }
     }
@@ -437,9 +475,9 @@ pub struct HistogramFoldStream {
     le_column_index: usize,
     field_column_index: usize,
     quantile: f64,
+    fraction: Option<(f64, f64)>,
     /// Columns need not folding. This indices is based on input schema
     normal_indices: Vec<usize>,
-    bucket_size: Option<usize>,
     /// Expected output batch size
     batch_size: usize,
     output_schema: SchemaRef,
@@ -493,17 +531,14 @@ impl HistogramFoldStream {
         &mut self,
         input: RecordBatch,
     ) -> DataFusionResult<Option<DataFusionResult<RecordBatch>>> {
-        let Some(bucket_num) = self.calculate_bucket_num(&input)? else {
-            return Ok(None);
-        };
-
-        if self.input_buffered_rows + input.num_rows() < bucket_num {
-            // not enough rows to fold
-            self.push_input_buf(input);
+        let has_inf = !self.find_positive_inf(&input)?.is_empty();
+        self.push_input_buf(input);
+        if !has_inf {
+            // the buffered histogram is not complete
             return Ok(None);
         }
 
-        self.fold_buf(bucket_num, input)?;
+        self.fold_buf()?;
         if self.output_buffered_rows >= self.batch_size {
             return Ok(self.take_output_buf()?.map(Ok));
         }
@@ -534,36 +569,20 @@ impl HistogramFoldStream {
         Ok(builders)
     }
 
-    fn calculate_bucket_num(&mut self, batch: &RecordBatch) -> DataFusionResult<Option<usize>> {
-        if let Some(size) = self.bucket_size {
-            return Ok(Some(size));
-        }
-
-        let inf_pos = self.find_positive_inf(batch)?;
-        if inf_pos == batch.num_rows() {
-            // no positive inf found, append to buffer and wait for next batch
-            self.push_input_buf(batch.clone());
-            return Ok(None);
-        }
-
-        // else we found the positive inf.
-        // calculate the bucket size
-        let bucket_size = inf_pos + self.input_buffered_rows + 1;
-        Ok(Some(bucket_size))
-    }
-
-    /// Fold record batches from input buffer and put to output buffer
-    fn fold_buf(&mut self, bucket_num: usize, input: RecordBatch) -> DataFusionResult<()> {
-        self.push_input_buf(input);
+    /// Fold every complete histogram in the input buffer and put to output buffer.
+    ///
+    /// A histogram ends at its `+Inf` bucket, so each of them may have a different number of buckets.
+    fn fold_buf(&mut self) -> DataFusionResult<()> {
         // TODO(ruihang): this concat is avoidable.
         let batch = concat_batches(&self.input.schema(), self.input_buffer.drain(..).as_ref())?;
-        let mut remaining_rows = self.input_buffered_rows;
+        let inf_positions = self.find_positive_inf(&batch)?;
         let mut cursor = 0;
 
         let gt_schema = GtSchema::try_from(self.input.schema()).unwrap();
         let batch = GtRecordBatch::try_from_df_record_batch(Arc::new(gt_schema), batch).unwrap();
 
-        while remaining_rows >= bucket_num {
+        for inf_pos in inf_positions {
+            let bucket_num = inf_pos + 1 - cursor;
             // "sample" normal columns
             for normal_index in &self.normal_indices {
                 let val = batch.column(*normal_index).get(cursor);
@@ -572,8 +591,8 @@ impl HistogramFoldStream {
             // "fold" `le` and field columns
             let le_array = batch.column(self.le_column_index);
             let field_array = batch.column(self.field_column_index);
-            let mut bucket = vec![];
-            let mut counters = vec![];
+            let mut bucket = Vec::with_capacity(bucket_num);
+            let mut counters = Vec::with_capacity(bucket_num);
             for bias in 0..bucket_num {
                 let le_str_val = le_array.get(cursor + bias);
                 let le_str_val_ref = le_str_val.as_value_ref();
@@ -593,14 +612,18 @@ impl HistogramFoldStream {
                 counters.push(counter);
             }
             // ignore invalid data
-            let result = Self::evaluate_row(self.quantile, &bucket, &counters).unwrap_or(f64::NAN);
+            let result = match self.fraction {
+                Some((lower, upper)) => Self::evaluate_fraction(lower, upper, &bucket, &counters),
+                None => Self::evaluate_row(self.quantile, &bucket, &counters),
+            }
+            .unwrap_or(f64::NAN);
             self.output_buffer[self.field_column_index].push_value_ref(ValueRef::from(result));
             cursor += bucket_num;
-            remaining_rows -= bucket_num;
             self.output_buffered_rows += 1;
         }
 
-        let remaining_input_batch = batch.into_df_record_batch().slice(cursor, remaining_rows);
+        let batch = batch.into_df_record_batch();
+        let remaining_input_batch = batch.slice(cursor, batch.num_rows() - cursor);
         self.input_buffered_rows = remaining_input_batch.num_rows();
         self.input_buffer.push(remaining_input_batch);
 
@@ -639,16 +662,8 @@ impl HistogramFoldStream {
             .map_err(|e| DataFusionError::ArrowError(Box::new(e), None))
     }
 
-    /// Find the first `+Inf` which indicates the end of the bucket group
-    ///
-    /// If the return value equals to batch's num_rows means the it's not found
-    /// in this batch
-    fn find_positive_inf(&self, batch: &RecordBatch) -> DataFusionResult<usize> {
-        // fuse this function. It should not be called when the
-        // bucket size is already know.
-        if let Some(bucket_size) = self.bucket_size {
-            return Ok(bucket_size);
-        }
+    /// Find the positions of every `+Inf`, each of them indicates the end of a bucket group
+    fn find_positive_inf(&self, batch: &RecordBatch) -> DataFusionResult<Vec<usize>> {
         let string_le_array = batch.column(self.le_column_index);
         let float_le_array = compute::cast(&string_le_array, &DataType::Float64).map_err(|e| {
             DataFusionError::Execution(format!(
@@ -665,15 +680,11 @@ impl HistogramFoldStream {
                     float_le_array.data_type()
                 ))
             })?;
-        for (i, v) in le_as_f64_array.iter().enumerate() {
-            if let Some(v) = v
-                && v == f64::INFINITY
-            {
-                return Ok(i);
-            }
-        }
-
-        Ok(batch.num_rows())
+        Ok(le_as_f64_array
+            .iter()
+            .enumerate()
+            .filter_map(|(i, v)| (v == Some(f64::INFINITY)).then_some(i))
+            .collect())
     }
 
     /// Evaluate the field column and return the result
@@ -727,6 +738,61 @@ impl HistogramFoldStream {
                     * (expected_pos - lower_count))
         }
     }
+
+    /// Evaluate the fraction of observations between `lower` and `upper`.
+    ///
+    /// Observations are assumed to be uniformly distributed inside each bucket,
+    /// the same as `histogram_fraction` over native histograms in Prometheus.
+    fn evaluate_fraction(
+        lower: f64,
+        upper: f64,
+        bucket: &[f64],
+        counter: &[f64],
+    ) -> DataFusionResult<f64> {
+        // check bucket
+        if bucket.len() <= 1 {
+            return Ok(f64::NAN);
+        }
+        if bucket.last().unwrap().is_finite() {
+            return Err(DataFusionError::Execution(
+                "last bucket should be +Inf".to_string(),
+            ));
+        }
+        if bucket.len() != counter.len() {
+            return Err(DataFusionError::Execution(
+                "bucket and counter should have the same length".to_string(),
+            ));
+        }
+        let total = *counter.last().unwrap();
+        if total == 0.0 || lower.is_nan() || upper.is_nan() {
+            return Ok(f64::NAN);
+        }
+        if lower >= upper {
+            return Ok(0.0);
+        }
+
+        // number of observations less than or equal to `value`
+        let rank = |value: f64| -> f64 {
+            let mut lower_bound = bucket[0].min(0.0);
+            let mut lower_count = 0.0;
+            for (upper_bound, upper_count) in bucket.iter().zip(counter) {
+                if value < *upper_bound {
+                    if upper_bound.is_infinite() || value <= lower_bound {
+                        // cannot interpolate inside the `+Inf` bucket
+                        return lower_count;
+                    }
+                    return lower_count
+                        + (upper_count - lower_count) * (value - lower_bound)
+                            / (upper_bound - lower_bound);
+                }
+                lower_bound = *upper_bound;
+                lower_count = *upper_count;
+            }
+            lower_count
+        };
+
+        Ok((rank(upper) - rank(lower)) / total)
+    }
 }
 
 #[cfg(test)]
@@ -824,6 +890,7 @@ mod test {
             le_column_index: 1,
             field_column_index: 2,
             quantile: 0.4,
+            fraction: None,
             ts_column_index: 9999, // not exist but doesn't matter
             input: memory_exec,
             output_schema,
@@ -853,6 +920,92 @@ mod test {
         assert_eq!(result_literal, expected);
     }
 
+    #[tokio::test]
+    async fn fold_sparse_buckets() {
+        // Native histograms only have their populated buckets, so histograms of
+        // different series and timestamps have different bucket sets.
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("host", DataType::Utf8, true),
+            Field::new("le", DataType::Utf8, true),
+            Field::new("val", DataType::Float64, true),
+        ]));
+        let batch = |hosts: Vec<&str>, les: Vec<&str>, vals: Vec<f64>| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(StringArray::from(hosts)) as _,
+                    Arc::new(StringArray::from(les)) as _,
+                    Arc::new(Float64Array::from(vals)) as _,
+                ],
+            )
+            .unwrap()
+        };
+        let batches = vec![
+            batch(
+                vec!["host_1", "host_1", "host_1", "host_1"],
+                vec!["1", "2", "+Inf", "1"],
+                vec![2.0, 4.0, 4.0, 4.0],
+            ),
+            batch(
+                vec!["host_1", "host_2", "host_2", "host_2"],
+                vec!["+Inf", "0.5", "1", "2"],
+                vec![4.0, 0.0, 2.0, 2.0],
+            ),
+            batch(vec!["host_2", "host_2"], vec!["4", "+Inf"], vec![6.0, 8.0]),
+        ];
+        let memory_exec = Arc::new(DataSourceExec::new(Arc::new(
+            MemorySourceConfig::try_new(&[batches], schema, None).unwrap(),
+        )));
+        let output_schema: SchemaRef = Arc::new(
+            (*HistogramFold::convert_schema(
+                &Arc::new(memory_exec.schema().to_dfschema().unwrap()),
+                "le",
+            )
+            .unwrap()
+            .as_ref())
+            .clone()
+            .into(),
+        );
+        let properties = PlanProperties::new(
+            EquivalenceProperties::new(output_schema.clone()),
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Incremental,
+            Boundedness::Bounded,
+        );
+        let fold_exec = Arc::new(HistogramFoldExec {
+            le_column_index: 1,
+            field_column_index: 2,
+            quantile: 0.5,
+            fraction: None,
+            ts_column_index: 9999, // not exist but doesn't matter
+            input: memory_exec,
+            output_schema,
+            metric: ExecutionPlanMetricsSet::new(),
+            properties,
+        });
+
+        let session_context = SessionContext::default();
+        let result = datafusion::physical_plan::collect(fold_exec, session_context.task_ctx())
+            .await
+            .unwrap();
+        let result = concat_batches(&result[0].schema(), &result).unwrap();
+        let hosts = result
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap()
+            .iter()
+            .map(|v| v.unwrap())
+            .collect::<Vec<_>>();
+        let values = result
+            .column(1)
+            .as_primitive::<Float64Type>()
+            .values()
+            .to_vec();
+        assert_eq!(hosts, vec!["host_1", "host_1", "host_2"]);
+        assert_eq!(values, vec![1.0, 0.5, 3.0]);
+    }
+
     #[test]
     fn confirm_schema() {
         let input_schema = Schema::new(vec![
@@ -944,6 +1097,36 @@ mod test {
         }
     }
 
+    #[test]
+    fn evaluate_fraction_normal_case() {
+        let bucket = [0.1, 0.5, 1.0, f64::INFINITY];
+        let counters = [10.0, 30.0, 40.0, 50.0];
+
+        let cases = [
+            ((0.0, 0.1), 0.2),
+            ((0.1, 0.5), 0.4),
+            ((0.0, 1.0), 0.8),
+            ((0.3, 0.75), 0.3),
+            ((f64::NEG_INFINITY, f64::INFINITY), 1.0),
+            ((1.0, f64::INFINITY), 0.2),
+            ((0.5, 0.5), 0.0),
+            ((0.5, 0.1), 0.0),
+        ];
+        for ((lower, upper), expected) in cases {
+            let result =
+                HistogramFoldStream::evaluate_fraction(lower, upper, &bucket, &counters).unwrap();
+            assert!(
+                (result - expected).abs() < 1e-10,
+                "lower: {lower}, upper: {upper}, expected: {expected}, result: {result}"
+            );
+        }
+
+        let result =
+            HistogramFoldStream::evaluate_fraction(0.0, 1.0, &bucket, &[0.0, 0.0, 0.0, 0.0])
+                .unwrap();
+        assert!(result.is_nan());
+    }
+
     #[test]
     #[should_panic]
     fn evaluate_out_of_order_input() {
@@ -105,6 +105,16 @@ impl RangeManipulate {
         "__internal_range_end".to_string()
     }
 
+    /// Returns the step of the evaluation in milliseconds.
+    pub fn interval(&self) -> Millisecond {
+        self.interval
+    }
+
+    /// Returns the range of the range vector in milliseconds.
+    pub fn range(&self) -> Millisecond {
+        self.range
+    }
+
     fn range_timestamp_name(&self) -> String {
         Self::build_timestamp_range_name(&self.time_index)
     }
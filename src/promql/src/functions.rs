@@ -27,8 +27,9 @@ mod round;
 mod test_util;
 
 pub use aggr_over_time::{
-    AbsentOverTime, AvgOverTime, CountOverTime, LastOverTime, MaxOverTime, MinOverTime,
-    PresentOverTime, StddevOverTime, StdvarOverTime, SumOverTime,
+    AbsentOverTime, AvgOverTime, CountOverTime, LastOverTime, MadOverTime, MaxOverTime,
+    MinOverTime, PresentOverTime, StddevOverTime, StdvarOverTime, SumOverTime, TsOfLastOverTime,
+    TsOfMaxOverTime, TsOfMinOverTime,
 };
 pub use changes::Changes;
 use datafusion::arrow::array::{ArrayRef, Float64Array, TimestampMillisecondArray};
@@ -186,6 +186,90 @@ pub fn stddev_over_time(_: &TimestampMillisecondArray, values: &Float64Array) ->
     }
 }
 
+/// The median absolute deviation of the values in the specified interval.
+#[range_fn(
+    name = MadOverTime,
+    ret = Float64Array,
+    display_name = prom_mad_over_time
+)]
+pub fn mad_over_time(_: &TimestampMillisecondArray, values: &Float64Array) -> Option<f64> {
+    let values = values.iter().flatten().collect::<Vec<_>>();
+    let center = median(values.clone())?;
+    median(values.into_iter().map(|v| (v - center).abs()).collect())
+}
+
+/// The timestamp (in seconds) of the maximum value in the specified interval.
+/// The last one is returned if there are multiple maximum values.
+#[range_fn(
+    name = TsOfMaxOverTime,
+    ret = Float64Array,
+    display_name = prom_ts_of_max_over_time
+)]
+pub fn ts_of_max_over_time(
+    times: &TimestampMillisecondArray,
+    values: &Float64Array,
+) -> Option<f64> {
+    ts_of_extreme(times, values, |value, extreme| value >= extreme)
+}
+
+/// The timestamp (in seconds) of the minimum value in the specified interval.
+/// The last one is returned if there are multiple minimum values.
+#[range_fn(
+    name = TsOfMinOverTime,
+    ret = Float64Array,
+    display_name = prom_ts_of_min_over_time
+)]
+pub fn ts_of_min_over_time(
+    times: &TimestampMillisecondArray,
+    values: &Float64Array,
+) -> Option<f64> {
+    ts_of_extreme(times, values, |value, extreme| value <= extreme)
+}
+
+/// The timestamp (in seconds) of the most recent point in the specified interval.
+#[range_fn(
+    name = TsOfLastOverTime,
+    ret = Float64Array,
+    display_name = prom_ts_of_last_over_time
+)]
+pub fn ts_of_last_over_time(times: &TimestampMillisecondArray, _: &Float64Array) -> Option<f64> {
+    times.values().last().map(|ts| *ts as f64 / 1000.0)
+}
+
+/// Median of `values` with linear interpolation, the same as `quantile(0.5, ...)`.
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    if values.len() % 2 == 1 {
+        Some(values[mid])
+    } else {
+        Some((values[mid - 1] + values[mid]) / 2.0)
+    }
+}
+
+/// Returns the timestamp (in seconds) of the value that `replace(value, extreme)` holds
+/// against all other values. NaN values are only used if there is nothing else.
+fn ts_of_extreme(
+    times: &TimestampMillisecondArray,
+    values: &Float64Array,
+    replace: impl Fn(f64, f64) -> bool,
+) -> Option<f64> {
+    let mut extreme: Option<(f64, i64)> = None;
+    for (value, ts) in values.iter().zip(times.iter()) {
+        let (Some(value), Some(ts)) = (value, ts) else {
+            continue;
+        };
+        match extreme {
+            Some((current, _)) if !current.is_nan() && !replace(value, current) => {}
+            _ => extreme = Some((value, ts)),
+        }
+    }
+    extreme.map(|(_, ts)| ts as f64 / 1000.0)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -503,4 +587,96 @@ mod test {
             vec![Some(0.0), Some(3.249615361854384)],
         );
     }
+
+    #[test]
+    fn calculate_mad_over_time() {
+        let (ts_array, value_array) = build_test_range_arrays();
+        simple_range_udf_runner(
+            MadOverTime::scalar_udf(),
+            ts_array,
+            value_array,
+            vec![],
+            vec![
+                Some(37.6543215),
+                Some(19.070249),
+                Some(0.0),
+                None,
+                None,
+                Some(14.238538),
+                Some(12.975651),
+                Some(11.579691),
+                Some(0.0),
+                None,
+            ],
+        );
+    }
+
+    #[test]
+    fn calculate_ts_of_max_over_time() {
+        let (ts_array, value_array) = build_test_range_arrays();
+        simple_range_udf_runner(
+            TsOfMaxOverTime::scalar_udf(),
+            ts_array,
+            value_array,
+            vec![],
+            vec![
+                Some(3.0),
+                Some(3.0),
+                Some(3.0),
+                None,
+                None,
+                Some(9.0),
+                Some(9.0),
+                Some(15.0),
+                Some(17.0),
+                None,
+            ],
+        );
+    }
+
+    #[test]
+    fn calculate_ts_of_min_over_time() {
+        let (ts_array, value_array) = build_test_range_arrays();
+        simple_range_udf_runner(
+            TsOfMinOverTime::scalar_udf(),
+            ts_array,
+            value_array,
+            vec![],
+            vec![
+                Some(1.0),
+                Some(1.0),
+                Some(3.0),
+                None,
+                None,
+                Some(7.0),
+                Some(11.0),
+                Some(11.0),
+                Some(17.0),
+                None,
+            ],
+        );
+    }
+
+    #[test]
+    fn calculate_ts_of_last_over_time() {
+        let (ts_array, value_array) = build_test_range_arrays();
+        simple_range_udf_runner(
+            TsOfLastOverTime::scalar_udf(),
+            ts_array,
+            value_array,
+            vec![],
+            vec![
+                Some(3.0),
+                Some(9.0),
+                Some(3.0),
+                None,
+                None,
+                Some(11.0),
+                Some(13.0),
+                Some(15.0),
+                Some(17.0),
+                None,
+            ],
+        );
+    }
 }
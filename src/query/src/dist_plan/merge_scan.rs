@@ -290,7 +290,7 @@ impl MergeScanExec {
                     header: Some(RegionRequestHeader {
                         tracing_context: tracing_context.to_w3c(),
                         dbname: dbname.clone(),
-                        query_context: Some(query_ctx.as_ref().into()),
+                        query_context: Some(query_ctx.to_query_pb()),
                     }),
                     region_id,
                     plan: plan.clone(),
@@ -202,7 +202,10 @@ impl ConstraintPruner {
                     | ConcreteDataType::Struct(_)
                     | ConcreteDataType::Json(_)
                     | ConcreteDataType::Null(_)
-                    | ConcreteDataType::Vector(_) => {
+                    | ConcreteDataType::Vector(_)
+                    | ConcreteDataType::Uuid(_)
+                    | ConcreteDataType::Map(_)
+                    | ConcreteDataType::Ip(_) => {
                         debug!("Unsupported data type {datatype}");
                         return false;
                     }
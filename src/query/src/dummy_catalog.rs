@@ -35,7 +35,9 @@ use session::context::{QueryContext, QueryContextRef};
 use snafu::ResultExt;
 use store_api::metadata::RegionMetadataRef;
 use store_api::region_engine::RegionEngineRef;
-use store_api::storage::{RegionId, ScanRequest, TimeSeriesDistribution, TimeSeriesRowSelector};
+use store_api::storage::{
+    JsonPathHint, RegionId, RollupHint, ScanRequest, TimeSeriesDistribution, TimeSeriesRowSelector,
+};
 use table::metadata::{TableId, TableInfoRef};
 use table::table::scan::RegionScanExec;
 use table::TableRef;
@@ -255,6 +257,16 @@ impl DummyTableProvider {
         self.scan_request.lock().unwrap().series_row_selector = Some(selector);
     }
 
+    /// Sets the rollup hint of the query to the provider.
+    pub fn with_rollup_hint(&self, hint: RollupHint) {
+        self.scan_request.lock().unwrap().rollup = Some(hint);
+    }
+
+    /// Sets paths of JSON columns the query accesses to the provider.
+    pub fn with_json_path_hint(&self, hint: JsonPathHint) {
+        self.scan_request.lock().unwrap().json_paths = Some(hint);
+    }
+
     pub fn with_sequence(&self, sequence: u64) {
         self.scan_request.lock().unwrap().sequence = Some(sequence);
     }
@@ -24,7 +24,9 @@ use datafusion_expr::{
 };
 use datafusion_sql::TableReference;
 use datatypes::schema::Schema;
-use log_query::{BinaryOperator, EqualValue, LogExpr, LogQuery, TimeFilter};
+use log_query::{
+    BinaryOperator, Direction, EqualValue, LogExpr, LogQuery, TimeFilter, RANGE_BUCKET_COLUMN,
+};
 use snafu::{OptionExt, ResultExt};
 use table::table::adapter::DfTableProviderAdapter;
 
@@ -91,6 +93,22 @@ impl LogQueryPlanner {
                 .context(DataFusionPlanningSnafu)?;
         }
 
+        let timestamp_col = schema
+            .timestamp_column()
+            .with_context(|| TimeIndexNotFoundSnafu {})?
+            .name
+            .clone();
+
+        // Sort by the time index before projections and limit, so the limit keeps
+        // the earliest or the latest rows.
+        if let Some(direction) = query.direction {
+            plan_builder = plan_builder
+                .sort(vec![
+                    col(&timestamp_col).sort(direction == Direction::Forward, false)
+                ])
+                .context(DataFusionPlanningSnafu)?;
+        }
+
         // Apply projections
         if !query.columns.is_empty() {
             let projected_columns = query.columns.iter().map(col).collect::<Vec<_>>();
@@ -109,7 +127,7 @@ impl LogQueryPlanner {
 
         // Apply log expressions
         for expr in &query.exprs {
-            plan_builder = self.process_log_expr(plan_builder, expr)?;
+            plan_builder = self.process_log_expr(plan_builder, expr, &timestamp_col)?;
         }
 
         // Build the final plan
@@ -229,10 +247,11 @@ impl LogQueryPlanner {
             log_query::ContentFilter::Contains(value) => Ok(Some(col_expr.like(lit(
                 ScalarValue::Utf8(Some(format!("%{}%", escape_like_pattern(value)))),
             )))),
-            log_query::ContentFilter::Regex(_pattern) => Err(UnimplementedSnafu {
-                feature: "regex filter",
-            }
-            .build()),
+            log_query::ContentFilter::Regex(pattern) => Ok(Some(Expr::BinaryExpr(BinaryExpr {
+                left: Box::new(col_expr),
+                op: Operator::RegexMatch,
+                right: Box::new(lit(ScalarValue::Utf8(Some(pattern.clone())))),
+            }))),
             log_query::ContentFilter::Exist => Ok(Some(col_expr.is_not_null())),
             log_query::ContentFilter::Between {
                 start,
@@ -486,6 +505,7 @@ impl LogQueryPlanner {
         &self,
         plan_builder: LogicalPlanBuilder,
         expr: &LogExpr,
+        timestamp_col: &str,
     ) -> Result<LogicalPlanBuilder> {
         let mut plan_builder = plan_builder;
 
@@ -494,14 +514,27 @@ impl LogQueryPlanner {
                 name,
                 args,
                 by,
-                range: _range,
+                range,
                 alias,
             } => {
                 let schema = plan_builder.schema();
-                let (mut aggr_expr, group_exprs) = self.build_aggr_func(schema, name, args, by)?;
+                let (mut aggr_expr, mut group_exprs) =
+                    self.build_aggr_func(schema, name, args, by)?;
                 if let Some(alias) = alias {
                     aggr_expr = aggr_expr.alias(alias);
                 }
+                if let Some(range) = range {
+                    let bucket_expr = self.build_scalar_func(
+                        schema,
+                        "date_bin",
+                        &[
+                            LogExpr::Literal(range.clone()),
+                            LogExpr::NamedIdent(timestamp_col.to_string()),
+                        ],
+                        &Some(RANGE_BUCKET_COLUMN.to_string()),
+                    )?;
+                    group_exprs.insert(0, bucket_expr);
+                }
 
                 plan_builder = plan_builder
                     .aggregate(group_exprs, [aggr_expr.clone()])
@@ -718,6 +751,7 @@ mod tests {
                 skip: None,
                 fetch: Some(100),
             },
+            direction: None,
             context: Context::None,
             columns: vec![],
             exprs: vec![],
@@ -817,6 +851,78 @@ mod tests {
         assert_eq!(format!("{:?}", expr), format!("{:?}", expected_expr));
     }
 
+    #[tokio::test]
+    async fn test_build_regex_filter() {
+        let table_provider =
+            build_test_table_provider(&[("public".to_string(), "test_table".to_string())]).await;
+        let session_state = SessionStateBuilder::new().with_default_features().build();
+        let planner = LogQueryPlanner::new(table_provider, session_state);
+        let schema = mock_schema();
+
+        let column_filter = ColumnFilters {
+            expr: Box::new(LogExpr::NamedIdent("message".to_string())),
+            filters: vec![ContentFilter::Regex("^err(or)?$".to_string())],
+        };
+
+        let expr = planner
+            .build_column_filter(&column_filter, schema.arrow_schema())
+            .unwrap()
+            .unwrap();
+
+        let expected_expr = Expr::BinaryExpr(BinaryExpr {
+            left: Box::new(col("message")),
+            op: Operator::RegexMatch,
+            right: Box::new(lit(ScalarValue::Utf8(Some("^err(or)?$".to_string())))),
+        });
+
+        assert_eq!(format!("{:?}", expr), format!("{:?}", expected_expr));
+    }
+
+    #[tokio::test]
+    async fn test_query_to_plan_with_ranged_aggr_func() {
+        let table_provider =
+            build_test_table_provider(&[("public".to_string(), "test_table".to_string())]).await;
+        let session_state = SessionStateBuilder::new().with_default_features().build();
+        let mut planner = LogQueryPlanner::new(table_provider, session_state);
+
+        let log_query = LogQuery {
+            table: TableName::new(DEFAULT_CATALOG_NAME, "public", "test_table"),
+            time_filter: TimeFilter {
+                start: Some("2021-01-01T00:00:00Z".to_string()),
+                end: Some("2021-01-02T00:00:00Z".to_string()),
+                span: None,
+            },
+            filters: Default::default(),
+            limit: Limit::default(),
+            direction: None,
+            context: Context::None,
+            columns: vec![],
+            exprs: vec![LogExpr::AggrFunc {
+                name: "count".to_string(),
+                args: vec![LogExpr::NamedIdent("message".to_string())],
+                by: vec![LogExpr::NamedIdent("host".to_string())],
+                range: Some("5 minutes".to_string()),
+                alias: Some("count_result".to_string()),
+            }],
+        };
+
+        let plan = planner.query_to_plan(log_query).await.unwrap();
+        let LogicalPlan::Aggregate(aggregate) = &plan else {
+            panic!("expect aggregate plan, got {plan:?}");
+        };
+        assert_eq!(aggregate.group_expr.len(), 2);
+        assert!(
+            matches!(&aggregate.group_expr[0], Expr::Alias(alias) if alias.name == RANGE_BUCKET_COLUMN)
+        );
+        let fields = plan
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(fields, vec![RANGE_BUCKET_COLUMN, "host", "count_result"]);
+    }
+
     #[tokio::test]
     async fn test_query_to_plan_with_only_skip() {
         let table_provider =
@@ -839,6 +945,7 @@ mod tests {
                 skip: Some(10),
                 fetch: None,
             },
+            direction: None,
             context: Context::None,
             columns: vec![],
             exprs: vec![],
@@ -874,6 +981,7 @@ mod tests {
                 skip: None,
                 fetch: None,
             },
+            direction: None,
             context: Context::None,
             columns: vec![],
             exprs: vec![],
@@ -887,6 +995,41 @@ mod tests {
         assert_eq!(plan.display_indent_schema().to_string(), expected);
     }
 
+    #[tokio::test]
+    async fn test_query_to_plan_with_direction() {
+        let table_provider =
+            build_test_table_provider(&[("public".to_string(), "test_table".to_string())]).await;
+        let session_state = SessionStateBuilder::new().with_default_features().build();
+        let mut planner = LogQueryPlanner::new(table_provider, session_state);
+
+        let log_query = LogQuery {
+            table: TableName::new(DEFAULT_CATALOG_NAME, "public", "test_table"),
+            time_filter: TimeFilter {
+                start: Some("2021-01-01T00:00:00Z".to_string()),
+                end: Some("2021-01-02T00:00:00Z".to_string()),
+                span: None,
+            },
+            filters: Default::default(),
+            limit: Limit {
+                skip: None,
+                fetch: Some(10),
+            },
+            direction: Some(Direction::Backward),
+            context: Context::None,
+            columns: vec!["message".to_string()],
+            exprs: vec![],
+        };
+
+        let plan = planner.query_to_plan(log_query).await.unwrap();
+        let expected = "Limit: skip=0, fetch=10 [message:Utf8]\
+\n  Projection: greptime.public.test_table.message [message:Utf8]\
+\n    Sort: greptime.public.test_table.timestamp DESC NULLS LAST [message:Utf8, timestamp:Timestamp(Millisecond, None), host:Utf8;N, is_active:Boolean;N]\
+\n      Filter: greptime.public.test_table.timestamp >= Utf8(\"2021-01-01T00:00:00Z\") AND greptime.public.test_table.timestamp <= Utf8(\"2021-01-02T00:00:00Z\") [message:Utf8, timestamp:Timestamp(Millisecond, None), host:Utf8;N, is_active:Boolean;N]\
+\n        TableScan: greptime.public.test_table [message:Utf8, timestamp:Timestamp(Millisecond, None), host:Utf8;N, is_active:Boolean;N]";
+
+        assert_eq!(plan.display_indent_schema().to_string(), expected);
+    }
+
     #[test]
     fn test_escape_pattern() {
         assert_eq!(escape_like_pattern("test"), "test");
@@ -914,6 +1057,7 @@ mod tests {
                 skip: None,
                 fetch: Some(100),
             },
+            direction: None,
             context: Context::None,
             columns: vec![],
             exprs: vec![LogExpr::AggrFunc {
@@ -953,6 +1097,7 @@ mod tests {
                 skip: None,
                 fetch: Some(100),
             },
+            direction: None,
             context: Context::None,
             columns: vec![],
             exprs: vec![LogExpr::ScalarFunc {
@@ -1024,6 +1169,7 @@ mod tests {
                 skip: Some(0),
                 fetch: None,
             },
+            direction: None,
             context: Context::None,
             columns: vec![],
             exprs: vec![
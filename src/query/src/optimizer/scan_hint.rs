@@ -12,26 +12,39 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
 use api::v1::SemanticType;
 use arrow_schema::SortOptions;
 use common_recordbatch::OrderOption;
 use datafusion::datasource::DefaultTableSource;
 use datafusion_common::tree_node::{Transformed, TreeNode, TreeNodeRecursion, TreeNodeVisitor};
-use datafusion_common::{Column, Result};
+use datafusion_common::{Column, Result, ScalarValue};
 use datafusion_expr::expr::Sort;
 use datafusion_expr::{utils, Expr, LogicalPlan};
 use datafusion_optimizer::{OptimizerConfig, OptimizerRule};
-use store_api::storage::{TimeSeriesDistribution, TimeSeriesRowSelector};
+use promql::extension_plan::RangeManipulate;
+use store_api::storage::{
+    JsonPathHint, RollupAggregator, RollupHint, TimeSeriesDistribution, TimeSeriesRowSelector,
+};
 
 use crate::dummy_catalog::DummyTableProvider;
 
+/// Names of functions that read a scalar at a path of JSON values.
+const JSON_PATH_ACCESSORS: [&str; 4] = [
+    "json_get_int",
+    "json_get_float",
+    "json_get_bool",
+    "json_get_string",
+];
+
 /// This rule will traverse the plan to collect necessary hints for leaf
 /// table scan node and set them in [`ScanRequest`]. Hints include:
 /// - the nearest order requirement to the leaf table scan node as ordering hint.
 /// - the group by columns when all aggregate functions are `last_value` as
 ///   time series row selector hint.
+/// - the step and the range function of a PromQL range query as rollup hint.
+/// - the paths of JSON columns the query accesses as JSON path hint.
 ///
 /// [`ScanRequest`]: store_api::storage::ScanRequest
 #[derive(Debug)]
@@ -92,6 +105,16 @@ impl ScanHintRule {
                             );
                         }
 
+                        // set rollup hint
+                        if let Some(hint) = &visitor.rollup_hint {
+                            adapter.with_rollup_hint(*hint);
+                        }
+
+                        // set JSON path hint
+                        if let Some(hint) = visitor.json_path_hint() {
+                            adapter.with_json_path_hint(hint);
+                        }
+
                         transformed = true;
                     }
                 }
@@ -196,12 +219,27 @@ struct ScanHintVisitor {
     /// This field stores saved `group_by` columns when all aggregate functions are `last_value`
     /// and the `order_by` column which should be time index.
     ts_row_selector: Option<(HashSet<Column>, Column)>,
+    /// The aggregator of range functions in the projection of a PromQL query.
+    /// It's `None` if range functions have different aggregators.
+    range_aggregator: Option<RollupAggregator>,
+    /// Rollup hint of a PromQL range query.
+    rollup_hint: Option<RollupHint>,
+    /// Paths in JSON path accessors, keyed by column name.
+    json_paths: BTreeMap<String, BTreeSet<String>>,
+    /// Columns referenced other than by JSON path accessors.
+    bare_columns: HashSet<String>,
+    /// Whether the plan has a node we don't know how to track JSON paths in.
+    json_hint_disabled: bool,
+    /// Whether the root node has been visited.
+    visited_root: bool,
 }
 
 impl TreeNodeVisitor<'_> for ScanHintVisitor {
     type Node = LogicalPlan;
 
     fn f_down(&mut self, node: &Self::Node) -> Result<TreeNodeRecursion> {
+        self.collect_json_paths(node)?;
+
         // Get order requirement from sort plan
         if let LogicalPlan::Sort(sort) = node {
             self.order_expr = Some(sort.expr.clone());
@@ -268,6 +306,32 @@ impl TreeNodeVisitor<'_> for ScanHintVisitor {
             }
         }
 
+        // Get rollup hint from PromQL range functions
+        if let LogicalPlan::Projection(projection) = node {
+            let aggregators = projection
+                .expr
+                .iter()
+                .filter_map(range_function_name)
+                .map(range_function_aggregator)
+                .collect::<Option<HashSet<_>>>();
+            if let Some(aggregators) = aggregators
+                && aggregators.len() == 1
+            {
+                self.range_aggregator = aggregators.into_iter().next();
+            }
+        }
+        if let LogicalPlan::Extension(extension) = node
+            && let Some(range_manipulate) =
+                extension.node.as_any().downcast_ref::<RangeManipulate>()
+            && self.rollup_hint.is_none()
+        {
+            // Rows of a tier can't be finer than the range.
+            self.rollup_hint = Some(RollupHint {
+                step_millis: range_manipulate.interval().min(range_manipulate.range()),
+                aggregator: self.range_aggregator,
+            });
+        }
+
         if self.ts_row_selector.is_some()
             && (matches!(node, LogicalPlan::Subquery(_)) || node.inputs().len() > 1)
         {
@@ -292,7 +356,105 @@ impl TreeNodeVisitor<'_> for ScanHintVisitor {
 
 impl ScanHintVisitor {
     fn need_rewrite(&self) -> bool {
-        self.order_expr.is_some() || self.ts_row_selector.is_some()
+        self.order_expr.is_some()
+            || self.ts_row_selector.is_some()
+            || self.rollup_hint.is_some()
+            || self.json_path_hint().is_some()
+    }
+
+    /// Collects paths in JSON path accessors of `node`.
+    fn collect_json_paths(&mut self, node: &LogicalPlan) -> Result<()> {
+        if !self.visited_root {
+            // The query returns whole values of columns in the output.
+            self.visited_root = true;
+            self.bare_columns
+                .extend(node.schema().fields().iter().map(|f| f.name().clone()));
+        }
+        if !matches!(
+            node,
+            LogicalPlan::TableScan(_)
+                | LogicalPlan::Projection(_)
+                | LogicalPlan::Filter(_)
+                | LogicalPlan::Aggregate(_)
+                | LogicalPlan::Sort(_)
+                | LogicalPlan::Limit(_)
+                | LogicalPlan::SubqueryAlias(_)
+        ) {
+            self.json_hint_disabled = true;
+            return Ok(());
+        }
+
+        for expr in node.expressions() {
+            expr.apply(|expr| {
+                if let Some((column, path)) = json_accessor_path(expr) {
+                    self.json_paths
+                        .entry(column.name.clone())
+                        .or_default()
+                        .insert(path.to_string());
+                    return Ok(TreeNodeRecursion::Jump);
+                }
+                if let Expr::Column(column) = expr {
+                    self.bare_columns.insert(column.name.clone());
+                }
+                Ok(TreeNodeRecursion::Continue)
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Returns the paths of JSON columns only accessed by JSON path accessors.
+    fn json_path_hint(&self) -> Option<JsonPathHint> {
+        if self.json_hint_disabled {
+            return None;
+        }
+        let paths = self
+            .json_paths
+            .iter()
+            .filter(|(column, _)| !self.bare_columns.contains(*column))
+            .map(|(column, paths)| (column.clone(), paths.clone()))
+            .collect::<BTreeMap<_, _>>();
+        (!paths.is_empty()).then_some(JsonPathHint { paths })
+    }
+}
+
+/// Returns the column and the path if `expr` is a JSON path accessor with a literal path.
+fn json_accessor_path(expr: &Expr) -> Option<(&Column, &str)> {
+    let Expr::ScalarFunction(func) = expr else {
+        return None;
+    };
+    if !JSON_PATH_ACCESSORS.contains(&func.name()) {
+        return None;
+    }
+    match func.args.as_slice() {
+        [Expr::Column(column), Expr::Literal(ScalarValue::Utf8(Some(path)), _)] => {
+            Some((column, path))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the name of the scalar function in `expr` if it's a PromQL range function.
+fn range_function_name(expr: &Expr) -> Option<&str> {
+    let expr = match expr {
+        Expr::Alias(alias) => alias.expr.as_ref(),
+        expr => expr,
+    };
+    let Expr::ScalarFunction(func) = expr else {
+        return None;
+    };
+    let name = func.func.name();
+    (name.starts_with("prom_") && name.ends_with("_over_time")).then_some(name)
+}
+
+/// Returns the rollup aggregator that keeps the result of the range function `name`.
+fn range_function_aggregator(name: &str) -> Option<RollupAggregator> {
+    match name {
+        "prom_avg_over_time" => Some(RollupAggregator::Avg),
+        "prom_min_over_time" => Some(RollupAggregator::Min),
+        "prom_max_over_time" => Some(RollupAggregator::Max),
+        "prom_sum_over_time" => Some(RollupAggregator::Sum),
+        "prom_last_over_time" => Some(RollupAggregator::Last),
+        _ => None,
     }
 }
 
@@ -300,10 +462,14 @@ impl ScanHintVisitor {
 mod test {
     use std::sync::Arc;
 
+    use common_function::function::FunctionRef;
+    use common_function::function_factory::ScalarFunctionFactory;
+    use common_function::scalars::json::json_get::JsonGetInt;
     use datafusion::functions_aggregate::first_last::last_value_udaf;
-    use datafusion_expr::expr::{AggregateFunction, AggregateFunctionParams};
-    use datafusion_expr::{col, LogicalPlanBuilder};
+    use datafusion_expr::expr::{AggregateFunction, AggregateFunctionParams, ScalarFunction};
+    use datafusion_expr::{col, lit, LogicalPlanBuilder};
     use datafusion_optimizer::OptimizerContext;
+    use promql::functions::AvgOverTime;
     use store_api::storage::RegionId;
 
     use super::*;
@@ -372,4 +538,69 @@ mod test {
         let scan_req = provider.scan_request();
         let _ = scan_req.series_row_selector.unwrap();
     }
+
+    fn json_get_int(column: &str, path: &str) -> Expr {
+        let udf = ScalarFunctionFactory::from(Arc::new(JsonGetInt) as FunctionRef)
+            .provide(Default::default());
+        Expr::ScalarFunction(ScalarFunction::new_udf(
+            Arc::new(udf),
+            vec![col(column), lit(path)],
+        ))
+    }
+
+    #[test]
+    fn set_json_path_hint() {
+        let provider = Arc::new(mock_table_provider(RegionId::new(1, 1)));
+        let table_source = Arc::new(DefaultTableSource::new(provider.clone()));
+        let plan = LogicalPlanBuilder::scan("t", table_source, None)
+            .unwrap()
+            .filter(json_get_int("v0", "$.a.b").gt(lit(1i64)))
+            .unwrap()
+            .project(vec![col("ts"), json_get_int("v0", "c").alias("c")])
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let context = OptimizerContext::default();
+        ScanHintRule.rewrite(plan, &context).unwrap();
+
+        let scan_req = provider.scan_request();
+        assert_eq!(
+            JsonPathHint {
+                paths: BTreeMap::from([(
+                    "v0".to_string(),
+                    BTreeSet::from(["$.a.b".to_string(), "c".to_string()])
+                )])
+            },
+            scan_req.json_paths.unwrap()
+        );
+
+        // The query returns the whole column.
+        let provider = Arc::new(mock_table_provider(RegionId::new(1, 1)));
+        let table_source = Arc::new(DefaultTableSource::new(provider.clone()));
+        let plan = LogicalPlanBuilder::scan("t", table_source, None)
+            .unwrap()
+            .filter(json_get_int("v0", "$.a.b").gt(lit(1i64)))
+            .unwrap()
+            .build()
+            .unwrap();
+        ScanHintRule.rewrite(plan, &context).unwrap();
+        assert!(provider.scan_request().json_paths.is_none());
+    }
+
+    #[test]
+    fn test_range_function_aggregator() {
+        let expr = Expr::ScalarFunction(ScalarFunction::new_udf(
+            Arc::new(AvgOverTime::scalar_udf()),
+            vec![col("ts_range"), col("v0")],
+        ))
+        .alias("value");
+        let name = range_function_name(&expr).unwrap();
+        assert_eq!("prom_avg_over_time", name);
+        assert_eq!(Some(RollupAggregator::Avg), range_function_aggregator(name));
+
+        assert_eq!(None, range_function_name(&col("v0")));
+        assert_eq!(None, range_function_aggregator("prom_stddev_over_time"));
+        assert_eq!(None, range_function_aggregator("prom_count_over_time"));
+    }
 }
@@ -28,8 +28,10 @@ use store_api::metadata::{
     ColumnMetadata, RegionMetadata, RegionMetadataBuilder, RegionMetadataRef,
 };
 use store_api::region_engine::{
+    BackupRegionRequest, BackupRegionResponse, CopyRegionFromRequest, CopyRegionFromResponse,
     RegionEngine, RegionManifestInfo, RegionRole, RegionScannerRef, RegionStatistic,
-    SetRegionRoleStateResponse, SettableRegionRoleState, SyncManifestResponse,
+    RestoreRegionRequest, RestoreRegionResponse, SetRegionRoleStateResponse,
+    SettableRegionRoleState, SyncManifestResponse,
 };
 use store_api::region_request::RegionRequest;
 use store_api::storage::{ConcreteDataType, RegionId, ScanRequest, SequenceNumber};
@@ -117,6 +119,27 @@ impl RegionEngine for MetaRegionEngine {
         unimplemented!()
     }
 
+    async fn backup_region(
+        &self,
+        _request: BackupRegionRequest,
+    ) -> Result<BackupRegionResponse, BoxedError> {
+        unimplemented!()
+    }
+
+    async fn restore_region(
+        &self,
+        _request: RestoreRegionRequest,
+    ) -> Result<RestoreRegionResponse, BoxedError> {
+        unimplemented!()
+    }
+
+    async fn copy_region_from(
+        &self,
+        _request: CopyRegionFromRequest,
+    ) -> Result<CopyRegionFromResponse, BoxedError> {
+        unimplemented!()
+    }
+
     fn role(&self, _region_id: RegionId) -> Option<RegionRole> {
         None
     }
@@ -16,13 +16,15 @@ use common_time::timestamp::{TimeUnit, Timestamp};
 use common_time::Timezone;
 use datafusion::config::ConfigOptions;
 use datafusion_common::tree_node::{Transformed, TreeNode, TreeNodeRewriter};
-use datafusion_common::{DFSchemaRef, DataFusionError, Result, ScalarValue};
+use datafusion_common::{Column, DFSchemaRef, DataFusionError, Result, ScalarValue};
 use datafusion_expr::expr::InList;
 use datafusion_expr::{
     Between, BinaryExpr, Expr, ExprSchemable, Filter, LogicalPlan, Operator, TableScan,
 };
 use datatypes::arrow::compute;
 use datatypes::arrow::datatypes::DataType;
+use datatypes::schema::{ColumnExtType, TYPE_KEY};
+use datatypes::types::parse_string_to_uuid_type_value;
 use session::context::QueryContextRef;
 
 use crate::optimizer::ExtensionAnalyzerRule;
@@ -34,6 +36,7 @@ use crate::QueryEngineContext;
 /// Specifically:
 /// - string literal of timestamp is converted to `Expr::Literal(ScalarValue::TimestampMillis)`
 /// - string literal of boolean is converted to `Expr::Literal(ScalarValue::Boolean)`
+/// - string literal compared with a uuid column is parsed into `Expr::Literal(ScalarValue::Binary)`
 pub struct TypeConversionRule;
 
 impl ExtensionAnalyzerRule for TypeConversionRule {
@@ -137,6 +140,20 @@ impl TypeConverter {
         None
     }
 
+    /// Whether the expr is a column of uuid type, which is a binary column in arrow
+    fn is_uuid_column(&self, expr: &Expr) -> bool {
+        let Expr::Column(col) = expr else {
+            return false;
+        };
+        self.schema.field_from_column(col).is_ok_and(|field| {
+            field
+                .metadata()
+                .get(TYPE_KEY)
+                .and_then(|t| t.parse::<ColumnExtType>().ok())
+                == Some(ColumnExtType::Uuid)
+        })
+    }
+
     fn cast_scalar_value(
         &self,
         value: &ScalarValue,
@@ -165,6 +182,19 @@ impl TypeConverter {
     }
 
     fn convert_type<'b>(&self, left: &'b Expr, right: &'b Expr) -> Result<(Expr, Expr)> {
+        // otherwise the string would be compared with the uuid bytes as is
+        match (left, right) {
+            (Expr::Column(col), Expr::Literal(value, _)) if self.is_uuid_column(left) => {
+                let casted_right = string_to_uuid(col, value)?;
+                return Ok((left.clone(), Expr::Literal(casted_right, None)));
+            }
+            (Expr::Literal(value, _), Expr::Column(col)) if self.is_uuid_column(right) => {
+                let casted_left = string_to_uuid(col, value)?;
+                return Ok((Expr::Literal(casted_left, None), right.clone()));
+            }
+            _ => {}
+        }
+
         let left_type = self.column_type(left);
         let right_type = self.column_type(right);
 
@@ -304,13 +334,30 @@ fn string_to_timestamp_ms(string: &str, timezone: Option<&Timezone>) -> Result<S
     Ok(scalar)
 }
 
+/// Parses a string literal into the binary form of uuid, other literals are kept as is.
+fn string_to_uuid(col: &Column, value: &ScalarValue) -> Result<ScalarValue> {
+    match value {
+        ScalarValue::Utf8(Some(v))
+        | ScalarValue::LargeUtf8(Some(v))
+        | ScalarValue::Utf8View(Some(v)) => {
+            let uuid = parse_string_to_uuid_type_value(v).map_err(|e| {
+                DataFusionError::Plan(format!(
+                    "column:{col:?}. Casting value:{value:?} to Uuid is invalid: {e}"
+                ))
+            })?;
+            Ok(ScalarValue::Binary(Some(uuid)))
+        }
+        _ => Ok(value.clone()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
     use std::sync::Arc;
 
     use datafusion_common::arrow::datatypes::Field;
-    use datafusion_common::{Column, DFSchema};
+    use datafusion_common::DFSchema;
     use datafusion_expr::{Literal, LogicalPlanBuilder};
     use datafusion_sql::TableReference;
     use session::context::QueryContext;
@@ -447,6 +494,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_convert_uuid_str() {
+        let col_name = "id";
+        let field = Field::new(col_name, DataType::Binary, true).with_metadata(HashMap::from([(
+            TYPE_KEY.to_string(),
+            ColumnExtType::Uuid.to_string(),
+        )]));
+        let schema = Arc::new(
+            DFSchema::new_with_metadata(
+                vec![(None::<TableReference>, Arc::new(field))],
+                HashMap::new(),
+            )
+            .unwrap(),
+        );
+        let mut converter = TypeConverter {
+            schema,
+            query_ctx: QueryContext::arc(),
+        };
+
+        let uuid = ScalarValue::Binary(Some(vec![
+            0x67, 0xe5, 0x50, 0x44, 0x10, 0xb1, 0x42, 0x6f, 0x92, 0x47, 0xbb, 0x68, 0x0e, 0x5f,
+            0xe0, 0xc8,
+        ]));
+        assert_eq!(
+            Expr::Column(Column::from_name(col_name)).eq(uuid.clone().lit()),
+            converter
+                .f_up(
+                    Expr::Column(Column::from_name(col_name))
+                        .eq("67e55044-10b1-426f-9247-bb680e5fe0c8".lit())
+                )
+                .unwrap()
+                .data
+        );
+        assert_eq!(
+            uuid.lit().eq(Expr::Column(Column::from_name(col_name))),
+            converter
+                .f_up(
+                    "67E5504410B1426F9247BB680E5FE0C8"
+                        .lit()
+                        .eq(Expr::Column(Column::from_name(col_name)))
+                )
+                .unwrap()
+                .data
+        );
+        assert!(converter
+            .f_up(Expr::Column(Column::from_name(col_name)).eq("not a uuid".lit()))
+            .is_err());
+    }
+
     #[test]
     fn test_retrieve_type_from_aggr_plan() {
         let plan = LogicalPlanBuilder::values(vec![vec![
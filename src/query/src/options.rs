@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use common_base::readable_size::ReadableSize;
 use serde::{Deserialize, Serialize};
 
 /// Query engine config
@@ -22,6 +23,13 @@ pub struct QueryOptions {
     pub parallelism: usize,
     /// Whether to allow query fallback when push down fails.
     pub allow_query_fallback: bool,
+    /// Memory shared by all queries. Default to 0, which means unlimited.
+    pub memory_pool_size: ReadableSize,
+    /// Memory limit of a single query in each node. Default to 0, which means unlimited.
+    pub per_query_memory_limit: ReadableSize,
+    /// Directories to spill intermediate data of sorts and aggregations to when
+    /// they exceed the memory limit. Default to empty, which uses the temp directory of the OS.
+    pub spill_dirs: Vec<String>,
 }
 
 #[allow(clippy::derivable_impls)]
@@ -30,6 +38,9 @@ impl Default for QueryOptions {
         Self {
             parallelism: 0,
             allow_query_fallback: false,
+            memory_pool_size: ReadableSize(0),
+            per_query_memory_limit: ReadableSize(0),
+            spill_dirs: Vec::new(),
         }
     }
 }
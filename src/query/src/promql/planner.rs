@@ -47,7 +47,7 @@ use datafusion::sql::TableReference;
 use datafusion_common::{DFSchema, NullEquality};
 use datafusion_expr::expr::WindowFunctionParams;
 use datafusion_expr::utils::conjunction;
-use datafusion_expr::{col, lit, ExprSchemable, Literal, SortExpr};
+use datafusion_expr::{col, lit, when, ExprSchemable, Literal, SortExpr};
 use datatypes::arrow::datatypes::{DataType as ArrowDataType, TimeUnit as ArrowTimeUnit};
 use datatypes::data_type::ConcreteDataType;
 use itertools::Itertools;
@@ -58,10 +58,11 @@ use promql::extension_plan::{
 };
 use promql::functions::{
     quantile_udaf, AbsentOverTime, AvgOverTime, Changes, CountOverTime, Delta, Deriv, HoltWinters,
-    IDelta, Increase, LastOverTime, MaxOverTime, MinOverTime, PredictLinear, PresentOverTime,
-    QuantileOverTime, Rate, Resets, Round, StddevOverTime, StdvarOverTime, SumOverTime,
+    IDelta, Increase, LastOverTime, MadOverTime, MaxOverTime, MinOverTime, PredictLinear,
+    PresentOverTime, QuantileOverTime, Rate, Resets, Round, StddevOverTime, StdvarOverTime,
+    SumOverTime, TsOfLastOverTime, TsOfMaxOverTime, TsOfMinOverTime,
 };
-use promql_parser::label::{MatchOp, Matcher, Matchers, METRIC_NAME};
+use promql_parser::label::{Labels, MatchOp, Matcher, Matchers, METRIC_NAME};
 use promql_parser::parser::token::TokenType;
 use promql_parser::parser::{
     token, AggregateExpr, BinModifier, BinaryExpr as PromBinaryExpr, Call, EvalStmt,
@@ -96,10 +97,20 @@ const SCALAR_FUNCTION: &str = "scalar";
 const SPECIAL_ABSENT_FUNCTION: &str = "absent";
 /// `histogram_quantile` function in PromQL
 const SPECIAL_HISTOGRAM_QUANTILE: &str = "histogram_quantile";
+/// `histogram_fraction` function in PromQL
+const SPECIAL_HISTOGRAM_FRACTION: &str = "histogram_fraction";
+/// `histogram_count` function in PromQL
+const SPECIAL_HISTOGRAM_COUNT: &str = "histogram_count";
+/// `histogram_sum` function in PromQL
+const SPECIAL_HISTOGRAM_SUM: &str = "histogram_sum";
 /// `vector` function in PromQL
 const SPECIAL_VECTOR_FUNCTION: &str = "vector";
 /// `le` column for conventional histogram.
 const LE_COLUMN_NAME: &str = "le";
+/// Native histograms are stored as conventional histograms with these metric name suffixes.
+const HISTOGRAM_BUCKET_SUFFIX: &str = "_bucket";
+const HISTOGRAM_COUNT_SUFFIX: &str = "_count";
+const HISTOGRAM_SUM_SUFFIX: &str = "_sum";
 
 /// Static regex for validating label names according to Prometheus specification.
 /// Label names must match the regex: [a-zA-Z_][a-zA-Z0-9_]*
@@ -194,6 +205,86 @@ pub fn normalize_matcher(mut matcher: Matcher) -> Matcher {
     matcher
 }
 
+/// Appends `suffix` to the metric names of all selectors in `expr`, unless the
+/// name already ends with it. Returns whether any selector is renamed.
+fn rename_histogram_metric(expr: &mut PromExpr, suffix: &str) -> bool {
+    fn rename_selector(vs: &mut VectorSelector, suffix: &str) -> bool {
+        if let Some(name) = &mut vs.name {
+            if name.ends_with(suffix) {
+                return false;
+            }
+            name.push_str(suffix);
+            return true;
+        }
+        let mut renamed = false;
+        for matcher in &mut vs.matchers.matchers {
+            if matcher.name == METRIC_NAME
+                && matcher.op == MatchOp::Equal
+                && !matcher.value.ends_with(suffix)
+            {
+                matcher.value.push_str(suffix);
+                renamed = true;
+            }
+        }
+        renamed
+    }
+
+    match expr {
+        PromExpr::VectorSelector(vs) => rename_selector(vs, suffix),
+        PromExpr::MatrixSelector(MatrixSelector { vs, .. }) => rename_selector(vs, suffix),
+        PromExpr::Aggregate(AggregateExpr { expr, .. })
+        | PromExpr::Paren(ParenExpr { expr })
+        | PromExpr::Unary(UnaryExpr { expr, .. })
+        | PromExpr::Subquery(SubqueryExpr { expr, .. }) => rename_histogram_metric(expr, suffix),
+        PromExpr::Binary(PromBinaryExpr { lhs, rhs, .. }) => {
+            let lhs_renamed = rename_histogram_metric(lhs, suffix);
+            rename_histogram_metric(rhs, suffix) || lhs_renamed
+        }
+        PromExpr::Call(Call { args, .. }) => args.args.iter_mut().fold(false, |renamed, arg| {
+            rename_histogram_metric(arg, suffix) || renamed
+        }),
+        PromExpr::NumberLiteral(_) | PromExpr::StringLiteral(_) | PromExpr::Extension(_) => false,
+    }
+}
+
+/// Keeps the `le` label in all aggregations of `expr`, e.g. `sum(x)` becomes
+/// `sum by (le) (x)`, so buckets are not aggregated together.
+fn group_by_le(expr: &mut PromExpr) {
+    match expr {
+        PromExpr::Aggregate(AggregateExpr { expr, modifier, .. }) => {
+            match modifier {
+                Some(LabelModifier::Include(labels)) => {
+                    if !labels.labels.iter().any(|l| l == LE_COLUMN_NAME) {
+                        labels.labels.push(LE_COLUMN_NAME.to_string());
+                    }
+                }
+                Some(LabelModifier::Exclude(labels)) => {
+                    labels.labels.retain(|l| l != LE_COLUMN_NAME);
+                }
+                None => {
+                    *modifier = Some(LabelModifier::Include(Labels {
+                        labels: vec![LE_COLUMN_NAME.to_string()],
+                    }));
+                }
+            }
+            group_by_le(expr);
+        }
+        PromExpr::Paren(ParenExpr { expr })
+        | PromExpr::Unary(UnaryExpr { expr, .. })
+        | PromExpr::Subquery(SubqueryExpr { expr, .. }) => group_by_le(expr),
+        PromExpr::Binary(PromBinaryExpr { lhs, rhs, .. }) => {
+            group_by_le(lhs);
+            group_by_le(rhs);
+        }
+        PromExpr::Call(Call { args, .. }) => args.args.iter_mut().for_each(|arg| group_by_le(arg)),
+        PromExpr::VectorSelector(_)
+        | PromExpr::MatrixSelector(_)
+        | PromExpr::NumberLiteral(_)
+        | PromExpr::StringLiteral(_)
+        | PromExpr::Extension(_) => {}
+    }
+}
+
 impl PromPlanner {
     pub async fn stmt_to_plan(
         table_provider: DfTableSourceProvider,
@@ -334,9 +425,10 @@ impl PromPlanner {
         let input = self.prom_expr_to_plan(expr, query_engine_state).await?;
 
         match (*op).id() {
-            token::T_TOPK | token::T_BOTTOMK => {
+            token::T_TOPK | token::T_BOTTOMK | token::T_LIMITK => {
                 self.prom_topk_bottomk_to_plan(aggr_expr, input).await
             }
+            token::T_LIMIT_RATIO => self.prom_limit_ratio_to_plan(aggr_expr, input).await,
             _ => {
                 // calculate columns to group by
                 // Need to append time index column into group by columns
@@ -381,7 +473,7 @@ impl PromPlanner {
         }
     }
 
-    /// Create logical plan for PromQL topk and bottomk expr.
+    /// Create logical plan for PromQL topk, bottomk and limitk expr.
     async fn prom_topk_bottomk_to_plan(
         &mut self,
         aggr_expr: &AggregateExpr,
@@ -457,6 +549,86 @@ impl PromPlanner {
             .context(DataFusionPlanningSnafu)
     }
 
+    /// Create logical plan for PromQL limit_ratio expr.
+    ///
+    /// Series in each group are ranked by their labels. A positive ratio `r` keeps
+    /// the first `r` of them and a negative ratio keeps the last `-r` of them, so
+    /// `limit_ratio(r, v)` and `limit_ratio(-(1-r), v)` are complementary.
+    async fn prom_limit_ratio_to_plan(
+        &mut self,
+        aggr_expr: &AggregateExpr,
+        input: LogicalPlan,
+    ) -> Result<LogicalPlan> {
+        let AggregateExpr {
+            op,
+            param,
+            modifier,
+            ..
+        } = aggr_expr;
+
+        let group_exprs = self.agg_modifier_to_col(input.schema(), modifier, false)?;
+
+        let ratio =
+            Self::get_param_as_literal_expr(param, Some(*op), Some(ArrowDataType::Float64))?;
+
+        let mut window_exprs = self.create_window_exprs(*op, group_exprs.clone(), &input)?;
+        let rank_column = window_exprs[0].schema_name().to_string();
+        let count_expr = DfExpr::WindowFunction(Box::new(WindowFunction {
+            fun: WindowFunctionDefinition::AggregateUDF(count_udaf()),
+            params: WindowFunctionParams {
+                args: vec![lit(1i64)],
+                partition_by: group_exprs.clone(),
+                order_by: vec![],
+                window_frame: WindowFrame::new(None),
+                null_treatment: None,
+                distinct: false,
+            },
+        }));
+        let count_expr = normalize_cols([count_expr], &input)
+            .context(DataFusionPlanningSnafu)?
+            .remove(0);
+        let count_column = count_expr.schema_name().to_string();
+        window_exprs.push(count_expr);
+
+        let as_f64 = |name: &str| {
+            DfExpr::Cast(Cast {
+                expr: Box::new(col(name)),
+                data_type: ArrowDataType::Float64,
+            })
+        };
+        // the 0-based position of the series in its group
+        let position = as_f64(&rank_column) - lit(1.0);
+        let count = as_f64(&count_column);
+        let filter = when(
+            ratio.clone().gt_eq(lit(0.0)),
+            position.clone().lt(ratio.clone() * count.clone()),
+        )
+        .otherwise(position.gt_eq((lit(1.0) + ratio) * count))
+        .context(DataFusionPlanningSnafu)?;
+
+        let mut sort_exprs = group_exprs;
+        sort_exprs.push(col(&rank_column));
+        let sort_exprs = sort_exprs.into_iter().map(|expr| expr.sort(true, false));
+
+        let project_fields = self
+            .create_field_column_exprs()?
+            .into_iter()
+            .chain(self.create_tag_column_exprs()?)
+            .chain(Some(self.create_time_index_column_expr()?));
+
+        LogicalPlanBuilder::from(input)
+            .window(window_exprs)
+            .context(DataFusionPlanningSnafu)?
+            .filter(filter)
+            .context(DataFusionPlanningSnafu)?
+            .sort(sort_exprs)
+            .context(DataFusionPlanningSnafu)?
+            .project(project_fields)
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)
+    }
+
     async fn prom_unary_expr_to_plan(
         &mut self,
         query_engine_state: &QueryEngineState,
@@ -853,8 +1025,15 @@ impl PromPlanner {
         let Call { func, args } = call_expr;
         // some special functions that are not expression but a plan
         match func.name {
-            SPECIAL_HISTOGRAM_QUANTILE => {
-                return self.create_histogram_plan(args, query_engine_state).await
+            SPECIAL_HISTOGRAM_QUANTILE | SPECIAL_HISTOGRAM_FRACTION => {
+                return self
+                    .create_histogram_plan(func.name, args, query_engine_state)
+                    .await
+            }
+            SPECIAL_HISTOGRAM_COUNT | SPECIAL_HISTOGRAM_SUM => {
+                return self
+                    .create_histogram_component_plan(func.name, args, query_engine_state)
+                    .await
             }
             SPECIAL_VECTOR_FUNCTION => return self.create_vector_plan(args).await,
             SCALAR_FUNCTION => return self.create_scalar_plan(args, query_engine_state).await,
@@ -1667,6 +1846,10 @@ impl PromPlanner {
             "stddev_over_time" => ScalarFunc::Udf(Arc::new(StddevOverTime::scalar_udf())),
             "stdvar_over_time" => ScalarFunc::Udf(Arc::new(StdvarOverTime::scalar_udf())),
             "quantile_over_time" => ScalarFunc::Udf(Arc::new(QuantileOverTime::scalar_udf())),
+            "mad_over_time" => ScalarFunc::Udf(Arc::new(MadOverTime::scalar_udf())),
+            "ts_of_max_over_time" => ScalarFunc::Udf(Arc::new(TsOfMaxOverTime::scalar_udf())),
+            "ts_of_min_over_time" => ScalarFunc::Udf(Arc::new(TsOfMinOverTime::scalar_udf())),
+            "ts_of_last_over_time" => ScalarFunc::Udf(Arc::new(TsOfLastOverTime::scalar_udf())),
             "predict_linear" => {
                 other_input_exprs[0] = DfExpr::Cast(Cast {
                     expr: Box::new(other_input_exprs[0].clone()),
@@ -1674,7 +1857,10 @@ impl PromPlanner {
                 });
                 ScalarFunc::Udf(Arc::new(PredictLinear::scalar_udf()))
             }
-            "holt_winters" => ScalarFunc::Udf(Arc::new(HoltWinters::scalar_udf())),
+            // `holt_winters` is renamed to `double_exponential_smoothing` in Prometheus 3.0
+            "holt_winters" | "double_exponential_smoothing" => {
+                ScalarFunc::Udf(Arc::new(HoltWinters::scalar_udf()))
+            }
             "time" => {
                 exprs.push(build_special_time_expr(
                     self.ctx.time_index_column.as_ref().unwrap(),
@@ -2276,10 +2462,12 @@ impl PromPlanner {
             token::T_GROUP => grouping_udaf(),
             token::T_STDDEV => stddev_pop_udaf(),
             token::T_STDVAR => var_pop_udaf(),
-            token::T_TOPK | token::T_BOTTOMK => UnsupportedExprSnafu {
-                name: format!("{op:?}"),
+            token::T_TOPK | token::T_BOTTOMK | token::T_LIMITK | token::T_LIMIT_RATIO => {
+                UnsupportedExprSnafu {
+                    name: format!("{op:?}"),
+                }
+                .fail()?
             }
-            .fail()?,
             _ => UnexpectedTokenSnafu { token: op }.fail()?,
         };
 
@@ -2400,16 +2588,25 @@ impl PromPlanner {
         group_exprs: Vec<DfExpr>,
         input_plan: &LogicalPlan,
     ) -> Result<Vec<DfExpr>> {
+        // `limitk` and `limit_ratio` pick series by labels instead of values
+        let by_value = matches!(op.id(), token::T_TOPK | token::T_BOTTOMK);
         ensure!(
             self.ctx.field_columns.len() == 1,
             UnsupportedExprSnafu {
-                name: "topk or bottomk on multi-value input"
+                name: if by_value {
+                    "topk or bottomk on multi-value input"
+                } else {
+                    "limitk or limit_ratio on multi-value input"
+                }
             }
         );
 
-        assert!(matches!(op.id(), token::T_TOPK | token::T_BOTTOMK));
+        assert!(matches!(
+            op.id(),
+            token::T_TOPK | token::T_BOTTOMK | token::T_LIMITK | token::T_LIMIT_RATIO
+        ));
 
-        let asc = matches!(op.id(), token::T_BOTTOMK);
+        let asc = !matches!(op.id(), token::T_TOPK);
 
         let tag_sort_exprs = self
             .create_tag_column_exprs()?
@@ -2424,7 +2621,9 @@ impl PromPlanner {
             .map(|col| {
                 let mut sort_exprs = Vec::with_capacity(self.ctx.tag_columns.len() + 1);
                 // Order by value in the specific order
-                sort_exprs.push(DfExpr::Column(Column::from(col)).sort(asc, true));
+                if by_value {
+                    sort_exprs.push(DfExpr::Column(Column::from(col)).sort(asc, true));
+                }
                 // Then tags if the values are equal,
                 // Try to ensure the relative stability of the output results.
                 sort_exprs.extend(tag_sort_exprs.clone());
@@ -2470,27 +2669,42 @@ impl PromPlanner {
         }
     }
 
-    /// Create a [SPECIAL_HISTOGRAM_QUANTILE] plan.
+    /// Create a [SPECIAL_HISTOGRAM_QUANTILE] or [SPECIAL_HISTOGRAM_FRACTION] plan.
+    ///
+    /// Native histograms are stored as conventional histograms. If the input has
+    /// no `le` label, it's re-planned over the `<name>_bucket` series of them.
     async fn create_histogram_plan(
         &mut self,
+        fn_name: &str,
         args: &PromFunctionArgs,
         query_engine_state: &QueryEngineState,
     ) -> Result<LogicalPlan> {
-        if args.args.len() != 2 {
+        let param_num = if fn_name == SPECIAL_HISTOGRAM_FRACTION {
+            3
+        } else {
+            2
+        };
+        if args.args.len() != param_num {
             return FunctionInvalidArgumentSnafu {
-                fn_name: SPECIAL_HISTOGRAM_QUANTILE.to_string(),
+                fn_name: fn_name.to_string(),
             }
             .fail();
         }
         #[allow(deprecated)]
-        let phi = Self::try_build_float_literal(&args.args[0]).with_context(|| {
-            FunctionInvalidArgumentSnafu {
-                fn_name: SPECIAL_HISTOGRAM_QUANTILE.to_string(),
-            }
-        })?;
+        let params = args.args[..param_num - 1]
+            .iter()
+            .map(|arg| Self::try_build_float_literal(arg))
+            .collect::<Option<Vec<_>>>()
+            .with_context(|| FunctionInvalidArgumentSnafu {
+                fn_name: fn_name.to_string(),
+            })?;
 
-        let input = args.args[1].as_ref().clone();
-        let input_plan = self.prom_expr_to_plan(&input, query_engine_state).await?;
+        let mut input = args.args[param_num - 1].as_ref().clone();
+        let mut input_plan = self.prom_expr_to_plan(&input, query_engine_state).await?;
+        if !self.ctx.has_le_tag() && rename_histogram_metric(&mut input, HISTOGRAM_BUCKET_SUFFIX) {
+            group_by_le(&mut input);
+            input_plan = self.prom_expr_to_plan(&input, query_engine_state).await?;
+        }
 
         if !self.ctx.has_le_tag() {
             // Return empty result instead of error when 'le' column is not found
@@ -2515,26 +2729,64 @@ impl PromPlanner {
             .field_columns
             .first()
             .with_context(|| FunctionInvalidArgumentSnafu {
-                fn_name: SPECIAL_HISTOGRAM_QUANTILE.to_string(),
+                fn_name: fn_name.to_string(),
             })?
             .clone();
         // remove le column from tag columns
         self.ctx.tag_columns.retain(|col| col != LE_COLUMN_NAME);
 
+        let fold = if fn_name == SPECIAL_HISTOGRAM_FRACTION {
+            HistogramFold::new(
+                LE_COLUMN_NAME.to_string(),
+                field_column,
+                time_index_column,
+                0.0,
+                input_plan,
+            )
+            .context(DataFusionPlanningSnafu)?
+            .with_fraction(params[0], params[1])
+        } else {
+            HistogramFold::new(
+                LE_COLUMN_NAME.to_string(),
+                field_column,
+                time_index_column,
+                params[0],
+                input_plan,
+            )
+            .context(DataFusionPlanningSnafu)?
+        };
+
         Ok(LogicalPlan::Extension(Extension {
-            node: Arc::new(
-                HistogramFold::new(
-                    LE_COLUMN_NAME.to_string(),
-                    field_column,
-                    time_index_column,
-                    phi,
-                    input_plan,
-                )
-                .context(DataFusionPlanningSnafu)?,
-            ),
+            node: Arc::new(fold),
         }))
     }
 
+    /// Create a [SPECIAL_HISTOGRAM_COUNT] or [SPECIAL_HISTOGRAM_SUM] plan.
+    ///
+    /// They are read from the `<name>_count` or `<name>_sum` series which
+    /// native histograms are stored as.
+    async fn create_histogram_component_plan(
+        &mut self,
+        fn_name: &str,
+        args: &PromFunctionArgs,
+        query_engine_state: &QueryEngineState,
+    ) -> Result<LogicalPlan> {
+        if args.args.len() != 1 {
+            return FunctionInvalidArgumentSnafu {
+                fn_name: fn_name.to_string(),
+            }
+            .fail();
+        }
+        let suffix = if fn_name == SPECIAL_HISTOGRAM_COUNT {
+            HISTOGRAM_COUNT_SUFFIX
+        } else {
+            HISTOGRAM_SUM_SUFFIX
+        };
+        let mut input = args.args[0].as_ref().clone();
+        rename_histogram_metric(&mut input, suffix);
+        self.prom_expr_to_plan(&input, query_engine_state).await
+    }
+
     /// Create a [SPECIAL_VECTOR_FUNCTION] plan
     async fn create_vector_plan(&mut self, args: &PromFunctionArgs) -> Result<LogicalPlan> {
         if args.args.len() != 1 {
@@ -4861,6 +5113,62 @@ Filter: up.field_0 IS NOT NULL [timestamp:Timestamp(Millisecond, None), field_0:
         assert_eq!(plan.display_indent_schema().to_string(), expected);
     }
 
+    #[tokio::test]
+    async fn test_prometheus_3_functions() {
+        let cases = [
+            (
+                r#"limitk(2, some_metric) by (tag_0)"#,
+                vec![
+                    "ORDER BY [some_metric.tag_0 ASC NULLS FIRST]",
+                    "<= Float64(2)",
+                ],
+            ),
+            (
+                r#"limit_ratio(0.5, some_metric)"#,
+                vec!["count(Int64(1)) PARTITION BY", "CASE WHEN"],
+            ),
+            (
+                r#"mad_over_time(some_metric[5m])"#,
+                vec!["prom_mad_over_time"],
+            ),
+            (
+                r#"ts_of_max_over_time(some_metric[5m])"#,
+                vec!["prom_ts_of_max_over_time"],
+            ),
+            (
+                r#"double_exponential_smoothing(some_metric[5m], 0.5, 0.5)"#,
+                vec!["prom_holt_winters"],
+            ),
+        ];
+
+        for (query, expected) in cases {
+            let eval_stmt = EvalStmt {
+                expr: parser::parse(query).unwrap(),
+                start: UNIX_EPOCH,
+                end: UNIX_EPOCH
+                    .checked_add(Duration::from_secs(100_000))
+                    .unwrap(),
+                interval: Duration::from_secs(5),
+                lookback_delta: Duration::from_secs(1),
+            };
+            let table_provider = build_test_table_provider(
+                &[(DEFAULT_SCHEMA_NAME.to_string(), "some_metric".to_string())],
+                1,
+                1,
+            )
+            .await;
+            let plan =
+                PromPlanner::stmt_to_plan(table_provider, &eval_stmt, &build_query_engine_state())
+                    .await
+                    .unwrap()
+                    .display_indent()
+                    .to_string();
+            for expected in expected {
+                assert!(plan.contains(expected), "query: {query}, plan: {plan}");
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_count_values_expr() {
         let mut eval_stmt = EvalStmt {
@@ -5049,4 +5357,53 @@ Filter: up.field_0 IS NOT NULL [timestamp:Timestamp(Millisecond, None), field_0:
             _ => panic!("Expected EmptyRelation, but got: {:?}", plan),
         }
     }
+    #[tokio::test]
+    async fn test_native_histogram_functions() {
+        let cases = [
+            (
+                "histogram_quantile(0.9, sum(rate(latency[5m])))",
+                "latency_bucket",
+                "HistogramFold: le=le, field=greptime_value, quantile=0.9",
+            ),
+            (
+                "histogram_fraction(0, 0.5, sum by (host) (rate(latency[5m])))",
+                "latency_bucket",
+                "HistogramFold: le=le, field=greptime_value, fraction=[0, 0.5]",
+            ),
+            (
+                "histogram_count(rate(latency[5m]))",
+                "latency_count",
+                "TableScan: latency_count",
+            ),
+            (
+                "histogram_sum(latency)",
+                "latency_sum",
+                "TableScan: latency_sum",
+            ),
+        ];
+
+        for (query, table, expected) in cases {
+            let eval_stmt = EvalStmt {
+                expr: parser::parse(query).unwrap(),
+                start: UNIX_EPOCH,
+                end: UNIX_EPOCH
+                    .checked_add(Duration::from_secs(100_000))
+                    .unwrap(),
+                interval: Duration::from_secs(5),
+                lookback_delta: Duration::from_secs(1),
+            };
+            let table_provider = build_test_table_provider_with_fields(
+                &[(DEFAULT_SCHEMA_NAME.to_string(), table.to_string())],
+                &["host", "le"],
+            )
+            .await;
+            let plan =
+                PromPlanner::stmt_to_plan(table_provider, &eval_stmt, &build_query_engine_state())
+                    .await
+                    .unwrap()
+                    .display_indent()
+                    .to_string();
+            assert!(plan.contains(expected), "query: {query}, plan: {plan}");
+        }
+    }
 }
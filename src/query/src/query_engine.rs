@@ -14,6 +14,7 @@
 
 mod context;
 mod default_serializer;
+mod memory_pool;
 pub mod options;
 mod state;
 use std::any::Any;
@@ -20,6 +20,7 @@ use datafusion::execution::context::{SessionState, TaskContext};
 use session::context::QueryContextRef;
 
 use crate::query_engine::default_serializer::DefaultPlanDecoder;
+use crate::query_engine::memory_pool::{query_runtime_env, QueryMemoryPools};
 
 #[derive(Debug)]
 pub struct QueryEngineContext {
@@ -50,6 +51,19 @@ impl QueryEngineContext {
         // pass tracing context in session_id
         let session_id = tracing_context.to_json();
 
+        // Allocates memory of the query from its own pool if memory is limited.
+        let runtime_env = match state.config().get_extension::<QueryMemoryPools>() {
+            Some(pools) => {
+                let pool = pools.get_or_create(
+                    &state.runtime_env().memory_pool,
+                    self.query_ctx.query_id(),
+                    self.query_ctx.process_id(),
+                );
+                query_runtime_env(state.runtime_env(), pool)
+            }
+            None => state.runtime_env().clone(),
+        };
+
         Arc::new(TaskContext::new(
             Some(dbname),
             session_id,
@@ -57,7 +71,7 @@ impl QueryEngineContext {
             state.scalar_functions().clone(),
             state.aggregate_functions().clone(),
             state.window_functions().clone(),
-            state.runtime_env().clone(),
+            runtime_env,
         ))
     }
 
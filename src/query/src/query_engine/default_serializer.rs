@@ -30,8 +30,9 @@ use datafusion_expr::UserDefinedLogicalNode;
 use greptime_proto::substrait_extension::MergeScan as PbMergeScan;
 use promql::functions::{
     quantile_udaf, AbsentOverTime, AvgOverTime, Changes, CountOverTime, Delta, Deriv, HoltWinters,
-    IDelta, Increase, LastOverTime, MaxOverTime, MinOverTime, PredictLinear, PresentOverTime,
-    QuantileOverTime, Rate, Resets, Round, StddevOverTime, StdvarOverTime, SumOverTime,
+    IDelta, Increase, LastOverTime, MadOverTime, MaxOverTime, MinOverTime, PredictLinear,
+    PresentOverTime, QuantileOverTime, Rate, Resets, Round, StddevOverTime, StdvarOverTime,
+    SumOverTime, TsOfLastOverTime, TsOfMaxOverTime, TsOfMinOverTime,
 };
 use prost::Message;
 use session::context::QueryContextRef;
@@ -162,6 +163,10 @@ impl SubstraitPlanDecoder for DefaultPlanDecoder {
         let _ = session_state.register_udf(Arc::new(StddevOverTime::scalar_udf()));
         let _ = session_state.register_udf(Arc::new(StdvarOverTime::scalar_udf()));
         let _ = session_state.register_udf(Arc::new(QuantileOverTime::scalar_udf()));
+        let _ = session_state.register_udf(Arc::new(MadOverTime::scalar_udf()));
+        let _ = session_state.register_udf(Arc::new(TsOfMaxOverTime::scalar_udf()));
+        let _ = session_state.register_udf(Arc::new(TsOfMinOverTime::scalar_udf()));
+        let _ = session_state.register_udf(Arc::new(TsOfLastOverTime::scalar_udf()));
         let _ = session_state.register_udf(Arc::new(PredictLinear::scalar_udf()));
         let _ = session_state.register_udf(Arc::new(HoltWinters::scalar_udf()));
 
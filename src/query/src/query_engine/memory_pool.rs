@@ -0,0 +1,312 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Memory pools to limit the memory used by queries.
+//!
+//! All queries share a global pool, which gives each spilling operator a fair share of
+//! the memory. Each query also allocates from its own [QueryMemoryPool] on top of the
+//! global pool, which enforces the per-query limit and reports the process id of the
+//! query when memory is exhausted. All task contexts of a query in a node, including the
+//! region scans of the query in a datanode, share the same [QueryMemoryPool], so the
+//! per-query limit applies to the memory a query uses in each node. Operators that support
+//! spilling (e.g. sorts and aggregations) spill to the spill directories when they fail
+//! to allocate memory.
+
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+use common_base::readable_size::ReadableSize;
+use common_telemetry::warn;
+use datafusion::error::{DataFusionError, Result as DfResult};
+use datafusion::execution::disk_manager::{DiskManagerBuilder, DiskManagerMode};
+use datafusion::execution::memory_pool::{
+    FairSpillPool, MemoryConsumer, MemoryPool, MemoryReservation, TrackConsumersPool,
+    UnboundedMemoryPool,
+};
+use datafusion::execution::runtime_env::{RuntimeEnv, RuntimeEnvBuilder};
+
+use crate::options::QueryOptions;
+
+/// Number of top memory consumers to report when the global pool is exhausted.
+const TOP_CONSUMERS_TO_REPORT: usize = 5;
+
+/// Memory pools of running queries in the node, keyed by query id.
+///
+/// It's stored in the session config if memory is limited.
+#[derive(Debug)]
+pub(crate) struct QueryMemoryPools {
+    /// Memory limit of a single query in bytes, 0 means unlimited.
+    per_query_limit: usize,
+    pools: Mutex<HashMap<u64, Weak<QueryMemoryPool>>>,
+}
+
+impl QueryMemoryPools {
+    /// Returns the pools with limits in `options`, or `None` if memory is unlimited.
+    pub(crate) fn from_options(options: &QueryOptions) -> Option<Self> {
+        if options.memory_pool_size.as_bytes() == 0
+            && options.per_query_memory_limit.as_bytes() == 0
+        {
+            return None;
+        }
+        Some(Self::new(options.per_query_memory_limit.as_bytes() as usize))
+    }
+
+    fn new(per_query_limit: usize) -> Self {
+        Self {
+            per_query_limit,
+            pools: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the pool of the query `query_id`, or creates one on top of `global`
+    /// if the query doesn't have a pool in the node.
+    ///
+    /// The pool is removed once all task contexts and reservations of the query are dropped.
+    pub(crate) fn get_or_create(
+        &self,
+        global: &Arc<dyn MemoryPool>,
+        query_id: u64,
+        process_id: u32,
+    ) -> Arc<QueryMemoryPool> {
+        let mut pools = self.pools.lock().unwrap();
+        if let Some(pool) = pools.get(&query_id).and_then(Weak::upgrade) {
+            return pool;
+        }
+
+        // Removes pools of finished queries.
+        pools.retain(|_, pool| pool.strong_count() > 0);
+        let pool = Arc::new(QueryMemoryPool::new(
+            global.clone(),
+            self.per_query_limit,
+            process_id,
+        ));
+        pools.insert(query_id, Arc::downgrade(&pool));
+        pool
+    }
+}
+
+/// Builds the runtime env shared by all queries.
+///
+/// Spills to the temp directory of the OS if it fails to use the spill directories.
+pub(crate) fn build_runtime_env(options: &QueryOptions) -> DfResult<Arc<RuntimeEnv>> {
+    let memory_pool: Arc<dyn MemoryPool> = if options.memory_pool_size.as_bytes() > 0 {
+        Arc::new(TrackConsumersPool::new(
+            FairSpillPool::new(options.memory_pool_size.as_bytes() as usize),
+            // Safety: the number is not zero.
+            NonZeroUsize::new(TOP_CONSUMERS_TO_REPORT).unwrap(),
+        ))
+    } else {
+        Arc::new(UnboundedMemoryPool::default())
+    };
+    let build = |disk_mode| {
+        RuntimeEnvBuilder::new()
+            .with_memory_pool(memory_pool.clone())
+            .with_disk_manager_builder(DiskManagerBuilder::default().with_mode(disk_mode))
+            .build_arc()
+    };
+    if options.spill_dirs.is_empty() {
+        return build(DiskManagerMode::OsTmpDirectory);
+    }
+
+    let dirs = options.spill_dirs.iter().map(PathBuf::from).collect();
+    build(DiskManagerMode::Directories(dirs)).or_else(|e| {
+        warn!(e; "Failed to use spill directories {:?}", options.spill_dirs);
+        build(DiskManagerMode::OsTmpDirectory)
+    })
+}
+
+/// Returns the runtime env for a query that allocates memory from the `pool` of the query.
+pub(crate) fn query_runtime_env(
+    runtime_env: &Arc<RuntimeEnv>,
+    pool: Arc<QueryMemoryPool>,
+) -> Arc<RuntimeEnv> {
+    Arc::new(RuntimeEnv {
+        memory_pool: pool,
+        disk_manager: runtime_env.disk_manager.clone(),
+        cache_manager: runtime_env.cache_manager.clone(),
+        object_store_registry: runtime_env.object_store_registry.clone(),
+    })
+}
+
+/// Memory pool of a single query.
+#[derive(Debug)]
+pub(crate) struct QueryMemoryPool {
+    /// The global pool to allocate memory from.
+    inner: Arc<dyn MemoryPool>,
+    /// Memory limit of the query in bytes, 0 means unlimited.
+    limit: usize,
+    /// Memory reserved by the query in bytes.
+    reserved: AtomicUsize,
+    process_id: u32,
+}
+
+impl QueryMemoryPool {
+    pub(crate) fn new(inner: Arc<dyn MemoryPool>, limit: usize, process_id: u32) -> Self {
+        Self {
+            inner,
+            limit,
+            reserved: AtomicUsize::new(0),
+            process_id,
+        }
+    }
+}
+
+impl MemoryPool for QueryMemoryPool {
+    fn register(&self, consumer: &MemoryConsumer) {
+        self.inner.register(consumer)
+    }
+
+    fn unregister(&self, consumer: &MemoryConsumer) {
+        self.inner.unregister(consumer)
+    }
+
+    fn grow(&self, reservation: &MemoryReservation, additional: usize) {
+        self.reserved.fetch_add(additional, Ordering::Relaxed);
+        self.inner.grow(reservation, additional)
+    }
+
+    fn shrink(&self, reservation: &MemoryReservation, shrink: usize) {
+        self.reserved.fetch_sub(shrink, Ordering::Relaxed);
+        self.inner.shrink(reservation, shrink)
+    }
+
+    fn try_grow(&self, reservation: &MemoryReservation, additional: usize) -> DfResult<()> {
+        self.reserved
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |reserved| {
+                let new_reserved = reserved.checked_add(additional)?;
+                (self.limit == 0 || new_reserved <= self.limit).then_some(new_reserved)
+            })
+            .map_err(|reserved| {
+                DataFusionError::ResourcesExhausted(format!(
+                    "Query {} exceeds the memory limit {}, failed to allocate additional {} for {} with {} already allocated by the query",
+                    self.process_id,
+                    ReadableSize(self.limit as u64),
+                    ReadableSize(additional as u64),
+                    reservation.consumer().name(),
+                    ReadableSize(reserved as u64),
+                ))
+            })?;
+
+        self.inner.try_grow(reservation, additional).map_err(|e| {
+            self.reserved.fetch_sub(additional, Ordering::Relaxed);
+            match e {
+                DataFusionError::ResourcesExhausted(msg) => {
+                    DataFusionError::ResourcesExhausted(format!(
+                        "Query {} failed to allocate memory from the global memory pool: {}",
+                        self.process_id, msg
+                    ))
+                }
+                e => e,
+            }
+        })
+    }
+
+    fn reserved(&self) -> usize {
+        self.reserved.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::execution::memory_pool::GreedyMemoryPool;
+
+    use super::*;
+
+    #[test]
+    fn test_query_memory_pool() {
+        let global: Arc<dyn MemoryPool> = Arc::new(GreedyMemoryPool::new(100));
+        let pool: Arc<dyn MemoryPool> = Arc::new(QueryMemoryPool::new(global.clone(), 50, 42));
+
+        let mut r1 = MemoryConsumer::new("r1").register(&pool);
+        r1.try_grow(40).unwrap();
+        assert_eq!(40, pool.reserved());
+        assert_eq!(40, global.reserved());
+
+        // Exceeds the limit of the query.
+        let err = r1.try_grow(20).unwrap_err();
+        assert!(matches!(err, DataFusionError::ResourcesExhausted(_)));
+        assert!(err
+            .to_string()
+            .contains("Query 42 exceeds the memory limit"));
+        assert_eq!(40, pool.reserved());
+
+        // Exceeds the global pool.
+        let other = QueryMemoryPool::new(global.clone(), 0, 43);
+        let other: Arc<dyn MemoryPool> = Arc::new(other);
+        let mut r2 = MemoryConsumer::new("r2").register(&other);
+        let err = r2.try_grow(70).unwrap_err();
+        assert!(matches!(err, DataFusionError::ResourcesExhausted(_)));
+        assert!(err
+            .to_string()
+            .contains("Query 43 failed to allocate memory"));
+        assert_eq!(0, other.reserved());
+        r2.try_grow(60).unwrap();
+
+        r1.shrink(30);
+        assert_eq!(10, pool.reserved());
+        drop(r1);
+        drop(r2);
+        assert_eq!(0, pool.reserved());
+        assert_eq!(0, global.reserved());
+    }
+
+    #[test]
+    fn test_query_memory_pools() {
+        assert!(QueryMemoryPools::from_options(&QueryOptions::default()).is_none());
+        let options = QueryOptions {
+            memory_pool_size: ReadableSize::gb(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            0,
+            QueryMemoryPools::from_options(&options)
+                .unwrap()
+                .per_query_limit
+        );
+
+        let global: Arc<dyn MemoryPool> = Arc::new(FairSpillPool::new(100));
+        let pools = QueryMemoryPools::new(50);
+        let pool = pools.get_or_create(&global, 1, 42);
+        // Task contexts of the same query share the pool.
+        let pool_of_scan = pools.get_or_create(&global, 1, 42);
+        assert!(Arc::ptr_eq(&pool, &pool_of_scan));
+        let other = pools.get_or_create(&global, 2, 43);
+        assert!(!Arc::ptr_eq(&pool, &other));
+
+        let pool: Arc<dyn MemoryPool> = pool;
+        let pool_of_scan: Arc<dyn MemoryPool> = pool_of_scan;
+        let mut r1 = MemoryConsumer::new("r1").register(&pool);
+        let mut r2 = MemoryConsumer::new("r2").register(&pool_of_scan);
+        r1.try_grow(30).unwrap();
+        // Exceeds the limit of the query with memory allocated by another scan.
+        let err = r2.try_grow(30).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Query 42 exceeds the memory limit"));
+        r2.try_grow(20).unwrap();
+
+        drop(r1);
+        drop(r2);
+        drop(pool);
+        drop(pool_of_scan);
+        drop(other);
+        // Creates a new pool after the query finishes and removes pools of finished queries.
+        let pool = pools.get_or_create(&global, 1, 42);
+        assert_eq!(0, pool.reserved());
+        assert_eq!(1, pools.pools.lock().unwrap().len());
+    }
+}
@@ -58,6 +58,7 @@ use crate::optimizer::type_conversion::TypeConversionRule;
 use crate::optimizer::windowed_sort::WindowedSortPhysicalRule;
 use crate::optimizer::ExtensionAnalyzerRule;
 use crate::options::QueryOptions as QueryOptionsNew;
+use crate::query_engine::memory_pool::{build_runtime_env, QueryMemoryPools};
 use crate::query_engine::options::QueryOptions;
 use crate::query_engine::DefaultSerializer;
 use crate::range_select::planner::RangeSelectPlanner;
@@ -97,8 +98,14 @@ impl QueryEngineState {
         plugins: Plugins,
         options: QueryOptionsNew,
     ) -> Self {
-        let runtime_env = Arc::new(RuntimeEnv::default());
+        let runtime_env = build_runtime_env(&options).unwrap_or_else(|e| {
+            warn!(e; "Failed to build runtime env of the query engine, fallback to the default");
+            Arc::new(RuntimeEnv::default())
+        });
         let mut session_config = SessionConfig::new().with_create_default_catalog_and_schema(false);
+        if let Some(pools) = QueryMemoryPools::from_options(&options) {
+            session_config = session_config.with_extension(Arc::new(pools));
+        }
         if options.parallelism > 0 {
             session_config = session_config.with_target_partitions(options.parallelism);
         }
@@ -26,7 +26,8 @@ use common_telemetry::{debug, error};
 use headers::ContentType;
 use once_cell::sync::Lazy;
 use pipeline::{
-    GreptimePipelineParams, PipelineDefinition, GREPTIME_INTERNAL_IDENTITY_PIPELINE_NAME,
+    translate_ingest_pipeline, GreptimePipelineParams, PipelineDefinition,
+    GREPTIME_INTERNAL_IDENTITY_PIPELINE_NAME,
 };
 use serde_json::{json, Deserializer, Value};
 use session::context::{Channel, QueryContext};
@@ -34,12 +35,12 @@ use snafu::{ensure, ResultExt};
 use vrl::value::Value as VrlValue;
 
 use crate::error::{
-    status_code_to_http_status, InvalidElasticsearchInputSnafu, ParseJsonSnafu,
-    Result as ServersResult,
+    status_code_to_http_status, InvalidElasticsearchInputSnafu, InvalidParameterSnafu,
+    ParseJsonSnafu, PipelineSnafu, Result as ServersResult,
 };
 use crate::http::event::{
     extract_pipeline_params_map_from_headers, ingest_logs_inner, LogIngesterQueryParams, LogState,
-    PipelineIngestRequest,
+    PipelineIngestRequest, GREPTIME_INTERNAL_PIPELINE_NAME_PREFIX,
 };
 use crate::http::header::constants::GREPTIME_PIPELINE_NAME_HEADER_NAME;
 use crate::metrics::{
@@ -118,6 +119,64 @@ pub async fn handle_bulk_api_with_index(
     do_handle_bulk_api(log_state, Some(index), params, query_ctx, headers, payload).await
 }
 
+/// Process `PUT /_ingest/pipeline/{id}` requests. The Elasticsearch ingest pipeline is
+/// translated and stored as the GreptimeDB pipeline `id`, so `_bulk?pipeline={id}` uses it.
+/// Reference: https://www.elastic.co/guide/en/elasticsearch/reference/current/put-pipeline-api.html.
+#[axum_macros::debug_handler]
+pub async fn handle_put_ingest_pipeline(
+    State(log_state): State<LogState>,
+    Path(id): Path<String>,
+    Extension(query_ctx): Extension<QueryContext>,
+    payload: String,
+) -> impl IntoResponse {
+    match put_ingest_pipeline(log_state, &id, query_ctx, &payload).await {
+        Ok(()) => (
+            StatusCode::OK,
+            elasticsearch_headers(),
+            axum::Json(json!({ "acknowledged": true })),
+        ),
+        Err(e) => {
+            error!(e; "Failed to put ingest pipeline {}", id);
+            let status = status_code_to_http_status(&e.status_code());
+            (
+                status,
+                elasticsearch_headers(),
+                axum::Json(json!({
+                    "error": {
+                        "type": "illegal_argument_exception",
+                        "reason": e.output_msg(),
+                    },
+                    "status": status.as_u16(),
+                })),
+            )
+        }
+    }
+}
+
+async fn put_ingest_pipeline(
+    log_state: LogState,
+    id: &str,
+    mut query_ctx: QueryContext,
+    payload: &str,
+) -> ServersResult<()> {
+    ensure!(
+        !id.starts_with(GREPTIME_INTERNAL_PIPELINE_NAME_PREFIX),
+        InvalidParameterSnafu {
+            reason: "pipeline id cannot start with greptime_",
+        }
+    );
+    let definition: Value = serde_json::from_str(payload).context(ParseJsonSnafu)?;
+    let pipeline = translate_ingest_pipeline(&definition).context(PipelineSnafu)?;
+    debug!("Translated ingest pipeline {}: {}", id, pipeline);
+
+    query_ctx.set_channel(Channel::Elasticsearch);
+    log_state
+        .log_handler
+        .insert_pipeline(id, "yaml", &pipeline, Arc::new(query_ctx))
+        .await?;
+    Ok(())
+}
+
 async fn do_handle_bulk_api(
     log_state: LogState,
     index: Option<String>,
@@ -146,6 +146,14 @@ pub enum Error {
     #[snafu(display("Failed to describe statement"))]
     DescribeStatement { source: BoxedError },
 
+    #[snafu(display("Invalid LogQL query"))]
+    LogQL {
+        #[snafu(source)]
+        source: log_query::error::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
     #[snafu(display("Pipeline error"))]
     Pipeline {
         #[snafu(source)]
@@ -271,6 +279,53 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Failed to read rule file: {}", path))]
+    ReadRuleFile {
+        path: String,
+        #[snafu(source)]
+        error: std::io::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Failed to parse rule file: {}", path))]
+    ParseRuleFile {
+        path: String,
+        #[snafu(source)]
+        error: serde_yaml::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Invalid rule config, msg: {}", msg))]
+    InvalidRuleConfig {
+        msg: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Failed to load alert states"))]
+    LoadAlertState {
+        #[snafu(implicit)]
+        location: Location,
+        source: BoxedError,
+    },
+
+    #[snafu(display("Failed to persist alert states of rule: {}", rule))]
+    PersistAlertState {
+        rule: String,
+        #[snafu(implicit)]
+        location: Location,
+        source: BoxedError,
+    },
+
+    #[snafu(display("Failed to campaign for the rule evaluation leader"))]
+    CampaignRuleLeader {
+        #[snafu(implicit)]
+        location: Location,
+        source: BoxedError,
+    },
+
     #[snafu(display("Failed to compress prometheus remote request"))]
     CompressPromRemoteRequest {
         #[snafu(implicit)]
@@ -373,6 +428,23 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Failed to validate the catalog and schema of table: {}", table))]
+    ValidateTable {
+        table: String,
+        #[snafu(implicit)]
+        location: Location,
+        source: query::error::Error,
+    },
+
+    #[snafu(display("COPY failed after {} rows were inserted", rows))]
+    CopyPartiallyInserted {
+        rows: usize,
+        #[snafu(implicit)]
+        location: Location,
+        #[snafu(source(from(Error, Box::new)))]
+        source: Box<Error>,
+    },
+
     #[snafu(display("Cannot find requested table: {}.{}.{}", catalog, schema, table))]
     TableNotFound {
         catalog: String,
@@ -675,10 +747,16 @@ impl ErrorExt for Error {
             | ExecutePlan { source, .. }
             | ExecuteGrpcQuery { source, .. }
             | ExecuteGrpcRequest { source, .. }
-            | CheckDatabaseValidity { source, .. } => source.status_code(),
+            | CheckDatabaseValidity { source, .. }
+            | LoadAlertState { source, .. }
+            | PersistAlertState { source, .. }
+            | CampaignRuleLeader { source, .. } => source.status_code(),
 
+            LogQL { source, .. } => source.status_code(),
             Pipeline { source, .. } => source.status_code(),
             CommonMeta { source, .. } => source.status_code(),
+            ValidateTable { source, .. } => source.status_code(),
+            CopyPartiallyInserted { source, .. } => source.status_code(),
 
             NotSupported { .. }
             | InvalidParameter { .. }
@@ -692,6 +770,9 @@ impl ErrorExt for Error {
             | DecompressZstdPromRemoteRequest { .. }
             | InvalidPromRemoteRequest { .. }
             | InvalidExportMetricsConfig { .. }
+            | ReadRuleFile { .. }
+            | ParseRuleFile { .. }
+            | InvalidRuleConfig { .. }
             | InvalidFlightTicket { .. }
             | InvalidPrepareStatement { .. }
             | DataFrame { .. }
@@ -88,22 +88,7 @@ pub async fn check_auth(
         return Ok(true);
     }
 
-    let auth_schema = extract_header(
-        headers,
-        &[AUTHORIZATION_HEADER, http::header::AUTHORIZATION.as_str()],
-    )?
-    .map(|x| {
-        if x.len() > 5 && x[0..5].eq_ignore_ascii_case("Basic") {
-            x.try_into()
-        } else {
-            // compatible with old version
-            format!("Basic {}", x).as_str().try_into()
-        }
-    })
-    .transpose()?
-    .map(|x: crate::http::authorize::AuthScheme| x.into());
-
-    let auth_schema = auth_schema.context(NotFoundAuthHeaderSnafu)?;
+    let auth_schema = extract_auth_scheme(headers)?.context(NotFoundAuthHeaderSnafu)?;
     let header = RequestHeader {
         authorization: Some(AuthHeader {
             auth_scheme: Some(auth_schema),
@@ -122,6 +107,25 @@ pub async fn check_auth(
     }
 }
 
+/// Extracts the auth scheme from the `authorization` header of the metadata.
+pub fn extract_auth_scheme(headers: &MetadataMap) -> TonicResult<Option<AuthScheme>> {
+    let auth_scheme = extract_header(
+        headers,
+        &[AUTHORIZATION_HEADER, http::header::AUTHORIZATION.as_str()],
+    )?
+    .map(|x| {
+        if x.len() > 5 && x[0..5].eq_ignore_ascii_case("Basic") {
+            x.try_into()
+        } else {
+            // compatible with old version
+            format!("Basic {}", x).as_str().try_into()
+        }
+    })
+    .transpose()?
+    .map(|x: crate::http::authorize::AuthScheme| x.into());
+    Ok(auth_scheme)
+}
+
 /// Authenticate the user based on the header and query context.
 pub async fn auth(
     user_provider: Option<UserProviderRef>,
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod sql;
 mod stream;
 
 use std::pin::Pin;
@@ -42,9 +43,10 @@ use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status, Streaming};
 
 use crate::error::{InvalidParameterSnafu, ParseJsonSnafu, Result, ToJsonSnafu};
+pub(crate) use crate::grpc::flight::sql::FlightSqlStateRef;
 pub use crate::grpc::flight::stream::FlightRecordBatchStream;
 use crate::grpc::greptime_handler::{get_request_type, GreptimeRequestHandler};
-use crate::grpc::{context_auth, FlightCompression, TonicResult};
+use crate::grpc::{FlightCompression, TonicResult};
 use crate::{error, hint_headers};
 
 pub type TonicStream<T> = Pin<Box<dyn Stream<Item = TonicResult<T>> + Send + 'static>>;
@@ -52,6 +54,22 @@ pub type TonicStream<T> = Pin<Box<dyn Stream<Item = TonicResult<T>> + Send + 'st
 /// A subset of [FlightService]
 #[async_trait]
 pub trait FlightCraft: Send + Sync + 'static {
+    async fn handshake(
+        &self,
+        request: Request<Streaming<HandshakeRequest>>,
+    ) -> TonicResult<Response<TonicStream<HandshakeResponse>>> {
+        let _ = request;
+        Err(Status::unimplemented("Not yet implemented"))
+    }
+
+    async fn get_flight_info(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> TonicResult<Response<FlightInfo>> {
+        let _ = request;
+        Err(Status::unimplemented("Not yet implemented"))
+    }
+
     async fn do_get(
         &self,
         request: Request<Ticket>,
@@ -64,6 +82,22 @@ pub trait FlightCraft: Send + Sync + 'static {
         let _ = request;
         Err(Status::unimplemented("Not yet implemented"))
     }
+
+    async fn do_action(
+        &self,
+        request: Request<Action>,
+    ) -> TonicResult<Response<TonicStream<arrow_flight::Result>>> {
+        let _ = request;
+        Err(Status::unimplemented("Not yet implemented"))
+    }
+
+    async fn list_actions(
+        &self,
+        request: Request<Empty>,
+    ) -> TonicResult<Response<TonicStream<ActionType>>> {
+        let _ = request;
+        Err(Status::unimplemented("Not yet implemented"))
+    }
 }
 
 pub type FlightCraftRef = Arc<dyn FlightCraft>;
@@ -78,6 +112,20 @@ impl<T: FlightCraft> From<T> for FlightCraftWrapper<T> {
 
 #[async_trait]
 impl FlightCraft for FlightCraftRef {
+    async fn handshake(
+        &self,
+        request: Request<Streaming<HandshakeRequest>>,
+    ) -> TonicResult<Response<TonicStream<HandshakeResponse>>> {
+        self.as_ref().handshake(request).await
+    }
+
+    async fn get_flight_info(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> TonicResult<Response<FlightInfo>> {
+        self.as_ref().get_flight_info(request).await
+    }
+
     async fn do_get(
         &self,
         request: Request<Ticket>,
@@ -91,6 +139,20 @@ impl FlightCraft for FlightCraftRef {
     ) -> TonicResult<Response<TonicStream<PutResult>>> {
         self.as_ref().do_put(request).await
     }
+
+    async fn do_action(
+        &self,
+        request: Request<Action>,
+    ) -> TonicResult<Response<TonicStream<arrow_flight::Result>>> {
+        self.as_ref().do_action(request).await
+    }
+
+    async fn list_actions(
+        &self,
+        request: Request<Empty>,
+    ) -> TonicResult<Response<TonicStream<ActionType>>> {
+        self.as_ref().list_actions(request).await
+    }
 }
 
 #[async_trait]
@@ -99,9 +161,9 @@ impl<T: FlightCraft> FlightService for FlightCraftWrapper<T> {
 
     async fn handshake(
         &self,
-        _: Request<Streaming<HandshakeRequest>>,
+        request: Request<Streaming<HandshakeRequest>>,
     ) -> TonicResult<Response<Self::HandshakeStream>> {
-        Err(Status::unimplemented("Not yet implemented"))
+        self.0.handshake(request).await
     }
 
     type ListFlightsStream = TonicStream<FlightInfo>;
@@ -115,9 +177,9 @@ impl<T: FlightCraft> FlightService for FlightCraftWrapper<T> {
 
     async fn get_flight_info(
         &self,
-        _: Request<FlightDescriptor>,
+        request: Request<FlightDescriptor>,
     ) -> TonicResult<Response<FlightInfo>> {
-        Err(Status::unimplemented("Not yet implemented"))
+        self.0.get_flight_info(request).await
     }
 
     async fn poll_flight_info(
@@ -160,26 +222,50 @@ impl<T: FlightCraft> FlightService for FlightCraftWrapper<T> {
 
     type DoActionStream = TonicStream<arrow_flight::Result>;
 
-    async fn do_action(&self, _: Request<Action>) -> TonicResult<Response<Self::DoActionStream>> {
-        Err(Status::unimplemented("Not yet implemented"))
+    async fn do_action(
+        &self,
+        request: Request<Action>,
+    ) -> TonicResult<Response<Self::DoActionStream>> {
+        self.0.do_action(request).await
     }
 
     type ListActionsStream = TonicStream<ActionType>;
 
     async fn list_actions(
         &self,
-        _: Request<Empty>,
+        request: Request<Empty>,
     ) -> TonicResult<Response<Self::ListActionsStream>> {
-        Err(Status::unimplemented("Not yet implemented"))
+        self.0.list_actions(request).await
     }
 }
 
 #[async_trait]
 impl FlightCraft for GreptimeRequestHandler {
+    async fn handshake(
+        &self,
+        request: Request<Streaming<HandshakeRequest>>,
+    ) -> TonicResult<Response<TonicStream<HandshakeResponse>>> {
+        self.flight_sql_handshake(request).await
+    }
+
+    async fn get_flight_info(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> TonicResult<Response<FlightInfo>> {
+        match sql::decode_command(&request.get_ref().cmd) {
+            Some(command) => self.flight_sql_get_flight_info(request, command?).await,
+            None => Err(Status::unimplemented("Not yet implemented")),
+        }
+    }
+
     async fn do_get(
         &self,
         request: Request<Ticket>,
     ) -> TonicResult<Response<TonicStream<FlightData>>> {
+        if let Some(command) = sql::decode_command(&request.get_ref().ticket) {
+            return self.flight_sql_do_get(request, command?).await;
+        }
+
         let hints = hint_headers::extract_hints(request.metadata());
 
         let ticket = request.into_inner().ticket;
@@ -211,15 +297,28 @@ impl FlightCraft for GreptimeRequestHandler {
         &self,
         request: Request<Streaming<FlightData>>,
     ) -> TonicResult<Response<TonicStream<PutResult>>> {
-        let (headers, _, stream) = request.into_parts();
-
-        let query_ctx = context_auth::create_query_context_from_grpc_metadata(&headers)?;
-        context_auth::check_auth(self.user_provider.clone(), &headers, query_ctx.clone()).await?;
+        let (headers, _, mut stream) = request.into_parts();
+        // Authenticates before reading anything from the client.
+        let query_ctx = self.flight_sql_query_context(&headers).await?;
+
+        let first = stream.message().await?;
+        let command = first
+            .as_ref()
+            .and_then(|x| x.flight_descriptor.as_ref())
+            .and_then(|x| sql::decode_command(&x.cmd));
+        if let Some(command) = command {
+            // Parameters are sent as record batches, starting with their schema.
+            let has_parameters = first.as_ref().is_some_and(|x| !x.data_header.is_empty());
+            return self
+                .flight_sql_do_put(query_ctx, command?, has_parameters)
+                .await;
+        }
 
         const MAX_PENDING_RESPONSES: usize = 32;
         let (tx, rx) = mpsc::channel::<TonicResult<DoPutResponse>>(MAX_PENDING_RESPONSES);
 
         let stream = PutRecordBatchRequestStream {
+            pending: first,
             flight_data_stream: stream,
             state: PutRecordBatchRequestStreamState::Init(
                 query_ctx.current_catalog().to_string(),
@@ -242,6 +341,20 @@ impl FlightCraft for GreptimeRequestHandler {
             .boxed();
         Ok(Response::new(response))
     }
+
+    async fn do_action(
+        &self,
+        request: Request<Action>,
+    ) -> TonicResult<Response<TonicStream<arrow_flight::Result>>> {
+        self.flight_sql_do_action(request).await
+    }
+
+    async fn list_actions(
+        &self,
+        _: Request<Empty>,
+    ) -> TonicResult<Response<TonicStream<ActionType>>> {
+        Ok(Response::new(self.flight_sql_list_actions()))
+    }
 }
 
 pub(crate) struct PutRecordBatchRequest {
@@ -268,6 +381,8 @@ impl PutRecordBatchRequest {
 }
 
 pub(crate) struct PutRecordBatchRequestStream {
+    /// The first message, already read to tell Flight SQL commands apart.
+    pending: Option<FlightData>,
     flight_data_stream: Streaming<FlightData>,
     state: PutRecordBatchRequestStreamState,
 }
@@ -297,7 +412,10 @@ impl Stream for PutRecordBatchRequestStream {
             Ok(descriptor.path.remove(0))
         }
 
-        let poll = ready!(self.flight_data_stream.poll_next_unpin(cx));
+        let poll = match self.pending.take() {
+            Some(flight_data) => Some(Ok(flight_data)),
+            None => ready!(self.flight_data_stream.poll_next_unpin(cx)),
+        };
 
         let result = match &mut self.state {
             PutRecordBatchRequestStreamState::Init(catalog, schema) => match poll {
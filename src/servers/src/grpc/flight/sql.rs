@@ -0,0 +1,867 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [Arrow Flight SQL](https://arrow.apache.org/docs/format/FlightSql.html) on the Flight service.
+//!
+//! Flight SQL commands are `Any` messages carried in flight descriptors, tickets and actions.
+//! They are told apart from GreptimeDB's own tickets by the type url of the `Any` message.
+//!
+//! Statements are executed in `DoGet`, by the tickets that carry the queries, so they can be
+//! fetched from any frontend. Clients authenticate by basic credentials, or by the bearer
+//! token issued in the handshake.
+//!
+//! Bearer tokens and prepared statements are kept in the memory of the frontend that issued
+//! them, so clients must send the later calls using them to the same frontend. Prepared
+//! statements can't bind parameters, `DoPut` with parameters is `Unimplemented`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use api::v1::greptime_request::Request as GreptimeRequestType;
+use api::v1::query_request::Query;
+use api::v1::{AuthHeader, QueryRequest, RequestHeader};
+use arrow::array::{RecordBatch, StringArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow_flight::encode::FlightDataEncoderBuilder;
+use arrow_flight::error::FlightError;
+use arrow_flight::sql::metadata::{SqlInfoData, SqlInfoDataBuilder};
+use arrow_flight::sql::{
+    ActionClosePreparedStatementRequest, ActionCreatePreparedStatementRequest,
+    ActionCreatePreparedStatementResult, Any, Command, CommandGetDbSchemas, CommandGetTables,
+    CommandPreparedStatementQuery, CommandPreparedStatementUpdate, CommandStatementQuery,
+    CommandStatementUpdate, DoPutUpdateResult, ProstMessageExt, SqlInfo, SqlSupportedTransaction,
+    TicketStatementQuery,
+};
+use arrow_flight::{
+    Action, ActionType, FlightData, FlightDescriptor, FlightEndpoint, FlightInfo, HandshakeRequest,
+    HandshakeResponse, PutResult, Ticket,
+};
+use auth::UserInfoRef;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use bytes::Bytes;
+use common_query::{Output, OutputData};
+use common_recordbatch::{RecordBatchStream, RecordBatches, SendableRecordBatchStream};
+use datatypes::value::Value;
+use futures::{future, stream, TryStreamExt};
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use prost::Message;
+use rand::RngCore;
+use session::context::{Channel, QueryContextRef};
+use snafu::ResultExt;
+use tonic::metadata::MetadataMap;
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::error::{ArrowSnafu, AuthSnafu, CollectRecordbatchSnafu};
+use crate::grpc::flight::TonicStream;
+use crate::grpc::greptime_handler::{create_query_context, GreptimeRequestHandler};
+use crate::grpc::{context_auth, TonicResult};
+use crate::hint_headers;
+use crate::http::header::constants::{GREPTIME_DB_HEADER_NAME, GREPTIME_TIMEZONE_HEADER_NAME};
+use crate::http::AUTHORIZATION_HEADER;
+
+const FLIGHT_SQL_TYPE_URL_PREFIX: &str = "type.googleapis.com/arrow.flight.protocol.sql.";
+
+const CREATE_PREPARED_STATEMENT: &str = "CreatePreparedStatement";
+const CLOSE_PREPARED_STATEMENT: &str = "ClosePreparedStatement";
+
+/// Tokens issued in handshakes expire if not used in time.
+const SESSION_TTL: Duration = Duration::from_secs(3600);
+/// Prepared statements are closed if not used in time.
+const PREPARED_STATEMENT_TTL: Duration = Duration::from_secs(3600);
+/// The least recently used prepared statements of a user are closed beyond the limit.
+const MAX_PREPARED_STATEMENTS_PER_USER: usize = 256;
+
+lazy_static! {
+    static ref SQL_INFO: SqlInfoData = {
+        let mut builder = SqlInfoDataBuilder::new();
+        builder.append(SqlInfo::FlightSqlServerName, "GreptimeDB");
+        builder.append(SqlInfo::FlightSqlServerVersion, common_version::version());
+        builder.append(SqlInfo::FlightSqlServerReadOnly, false);
+        builder.append(SqlInfo::FlightSqlServerSql, true);
+        builder.append(SqlInfo::FlightSqlServerSubstrait, false);
+        builder.append(
+            SqlInfo::FlightSqlServerTransaction,
+            SqlSupportedTransaction::None as i32,
+        );
+        builder.append(SqlInfo::SqlIdentifierQuoteChar, "`");
+        builder.build().unwrap()
+    };
+}
+
+/// Decodes a Flight SQL command, returns `None` if the bytes are not one.
+pub(crate) fn decode_command(bytes: &[u8]) -> Option<TonicResult<Command>> {
+    let any = Any::decode(bytes).ok()?;
+    if !any.type_url.starts_with(FLIGHT_SQL_TYPE_URL_PREFIX) {
+        return None;
+    }
+    Some(Command::try_from(any).map_err(|e| Status::invalid_argument(e.to_string())))
+}
+
+struct Session {
+    user_info: UserInfoRef,
+    last_used: Instant,
+}
+
+struct PreparedStatement {
+    /// The user who prepared the statement, the only one allowed to use it.
+    username: String,
+    query: String,
+    last_used: Instant,
+}
+
+/// Sessions of handshakes and prepared statements.
+#[derive(Default)]
+pub(crate) struct FlightSqlState {
+    /// Sessions by the bearer tokens issued in handshakes.
+    sessions: Mutex<HashMap<String, Session>>,
+    prepared_statements: Mutex<HashMap<Bytes, PreparedStatement>>,
+}
+
+impl FlightSqlState {
+    /// Creates a session of the authenticated user and returns its token.
+    fn new_session(&self, user_info: UserInfoRef) -> String {
+        let mut token = [0u8; 32];
+        rand::rng().fill_bytes(&mut token);
+        let token = URL_SAFE_NO_PAD.encode(token);
+
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|_, session| session.last_used.elapsed() < SESSION_TTL);
+        let _ = sessions.insert(
+            token.clone(),
+            Session {
+                user_info,
+                last_used: Instant::now(),
+            },
+        );
+        token
+    }
+
+    fn session(&self, token: &str) -> Option<UserInfoRef> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions.get_mut(token)?;
+        if session.last_used.elapsed() >= SESSION_TTL {
+            let _ = sessions.remove(token);
+            return None;
+        }
+        session.last_used = Instant::now();
+        Some(session.user_info.clone())
+    }
+
+    fn add_prepared_statement(&self, username: &str, query: String) -> Bytes {
+        let mut statements = self.prepared_statements.lock().unwrap();
+        statements.retain(|_, statement| statement.last_used.elapsed() < PREPARED_STATEMENT_TTL);
+        let mut owned = statements
+            .iter()
+            .filter(|(_, statement)| statement.username == username)
+            .map(|(handle, statement)| (statement.last_used, handle.clone()))
+            .collect::<Vec<_>>();
+        if owned.len() >= MAX_PREPARED_STATEMENTS_PER_USER {
+            owned.sort_unstable();
+            for (_, handle) in &owned[..=owned.len() - MAX_PREPARED_STATEMENTS_PER_USER] {
+                let _ = statements.remove(handle);
+            }
+        }
+
+        let handle = Bytes::from(uuid::Uuid::new_v4().to_string());
+        let _ = statements.insert(
+            handle.clone(),
+            PreparedStatement {
+                username: username.to_string(),
+                query,
+                last_used: Instant::now(),
+            },
+        );
+        handle
+    }
+
+    fn prepared_statement(&self, username: &str, handle: &Bytes) -> TonicResult<String> {
+        let mut statements = self.prepared_statements.lock().unwrap();
+        match statements.get_mut(handle) {
+            Some(statement)
+                if statement.username == username
+                    && statement.last_used.elapsed() < PREPARED_STATEMENT_TTL =>
+            {
+                statement.last_used = Instant::now();
+                Ok(statement.query.clone())
+            }
+            _ => Err(Status::not_found("prepared statement not found")),
+        }
+    }
+
+    fn close_prepared_statement(&self, username: &str, handle: &Bytes) {
+        let mut statements = self.prepared_statements.lock().unwrap();
+        if statements
+            .get(handle)
+            .is_some_and(|statement| statement.username == username)
+        {
+            let _ = statements.remove(handle);
+        }
+    }
+}
+
+pub(crate) type FlightSqlStateRef = Arc<FlightSqlState>;
+
+impl GreptimeRequestHandler {
+    /// Authenticates the user and issues a bearer token for later calls.
+    pub(crate) async fn flight_sql_handshake(
+        &self,
+        request: Request<Streaming<HandshakeRequest>>,
+    ) -> TonicResult<Response<TonicStream<HandshakeResponse>>> {
+        let query_ctx = self.flight_sql_query_context(request.metadata()).await?;
+        // No token is needed if authentication is disabled.
+        let token = self
+            .user_provider
+            .as_ref()
+            .map(|_| self.flight_sql.new_session(query_ctx.current_user()));
+
+        let handshake = HandshakeResponse {
+            protocol_version: 0,
+            payload: token.clone().map(Bytes::from).unwrap_or_default(),
+        };
+        let mut response =
+            Response::new(Box::pin(stream::once(future::ok(handshake))) as TonicStream<_>);
+        if let Some(token) = token {
+            let value = format!("Bearer {token}")
+                .parse()
+                .map_err(|_| Status::internal("invalid token"))?;
+            let _ = response.metadata_mut().insert("authorization", value);
+        }
+        Ok(response)
+    }
+
+    /// Builds the query context of the request, authenticated by the bearer token issued in
+    /// the handshake or the basic credentials.
+    pub(crate) async fn flight_sql_query_context(
+        &self,
+        headers: &MetadataMap,
+    ) -> TonicResult<QueryContextRef> {
+        let header = RequestHeader {
+            dbname: context_auth::extract_header(headers, &[GREPTIME_DB_HEADER_NAME])?
+                .unwrap_or_default()
+                .to_string(),
+            timezone: context_auth::extract_header(headers, &[GREPTIME_TIMEZONE_HEADER_NAME])?
+                .unwrap_or_default()
+                .to_string(),
+            ..Default::default()
+        };
+        let hints = hint_headers::extract_hints(headers);
+        let query_ctx = create_query_context(Channel::Grpc, Some(&header), hints)?;
+
+        let Some(user_provider) = &self.user_provider else {
+            query_ctx.set_current_user(auth::userinfo_by_name(None));
+            return Ok(query_ctx);
+        };
+        let authorization = context_auth::extract_header(
+            headers,
+            &[AUTHORIZATION_HEADER, http::header::AUTHORIZATION.as_str()],
+        )?;
+        let user_info = match authorization.and_then(bearer_token) {
+            Some(token) => {
+                let user_info = self
+                    .flight_sql
+                    .session(token)
+                    .ok_or_else(|| Status::unauthenticated("invalid or expired token"))?;
+                user_provider
+                    .authorize(
+                        query_ctx.current_catalog(),
+                        &query_ctx.current_schema(),
+                        &user_info,
+                    )
+                    .await
+                    .context(AuthSnafu)?;
+                user_info
+            }
+            None => {
+                let header = RequestHeader {
+                    authorization: context_auth::extract_auth_scheme(headers)?.map(|auth_scheme| {
+                        AuthHeader {
+                            auth_scheme: Some(auth_scheme),
+                        }
+                    }),
+                    ..Default::default()
+                };
+                context_auth::auth(self.user_provider.clone(), Some(&header), &query_ctx)
+                    .await
+                    .map_err(|_| Status::unauthenticated("auth failed"))?
+            }
+        };
+        query_ctx.set_current_user(user_info);
+        Ok(query_ctx)
+    }
+
+    pub(crate) async fn flight_sql_get_flight_info(
+        &self,
+        request: Request<FlightDescriptor>,
+        command: Command,
+    ) -> TonicResult<Response<FlightInfo>> {
+        let (headers, _, descriptor) = request.into_parts();
+        let query_ctx = self.flight_sql_query_context(&headers).await?;
+        // The schema of a statement is only known on execution, it's sent with the results.
+        let (schema, ticket) = match command {
+            Command::CommandStatementQuery(CommandStatementQuery { query, .. }) => {
+                (None, statement_ticket(query))
+            }
+            Command::CommandPreparedStatementQuery(CommandPreparedStatementQuery {
+                prepared_statement_handle,
+            }) => {
+                let query = self.flight_sql.prepared_statement(
+                    query_ctx.current_user().username(),
+                    &prepared_statement_handle,
+                )?;
+                (None, statement_ticket(query))
+            }
+            // Metadata is fetched by `DoGet` with the command itself as the ticket.
+            Command::CommandGetCatalogs(command) => (
+                Some(command.into_builder().build().context(ArrowSnafu)?.schema()),
+                descriptor.cmd.clone(),
+            ),
+            Command::CommandGetDbSchemas(command) => (
+                Some(command.into_builder().build().context(ArrowSnafu)?.schema()),
+                descriptor.cmd.clone(),
+            ),
+            Command::CommandGetTables(command) => (
+                Some(command.into_builder().build().context(ArrowSnafu)?.schema()),
+                descriptor.cmd.clone(),
+            ),
+            Command::CommandGetTableTypes(_) => {
+                (Some(table_types_schema()), descriptor.cmd.clone())
+            }
+            Command::CommandGetSqlInfo(command) => (
+                Some(
+                    SQL_INFO
+                        .record_batch(command.info)
+                        .context(ArrowSnafu)?
+                        .schema(),
+                ),
+                descriptor.cmd.clone(),
+            ),
+            other => return Err(unsupported(&other)),
+        };
+
+        let mut info = FlightInfo::new()
+            .with_endpoint(FlightEndpoint::new().with_ticket(Ticket::new(ticket)))
+            .with_descriptor(descriptor);
+        if let Some(schema) = schema {
+            info = info.try_with_schema(&schema).context(ArrowSnafu)?;
+        }
+        Ok(Response::new(info))
+    }
+
+    pub(crate) async fn flight_sql_do_get(
+        &self,
+        request: Request<Ticket>,
+        command: Command,
+    ) -> TonicResult<Response<TonicStream<FlightData>>> {
+        let query_ctx = &self.flight_sql_query_context(request.metadata()).await?;
+        let batch = match command {
+            Command::TicketStatementQuery(TicketStatementQuery { statement_handle }) => {
+                let query = String::from_utf8(statement_handle.to_vec())
+                    .map_err(|_| Status::invalid_argument("invalid statement ticket"))?;
+                let output = self.execute_sql(query_ctx, query).await?;
+                return Ok(Response::new(output_to_flight_data(output)));
+            }
+            Command::CommandGetCatalogs(command) => {
+                let mut builder = command.into_builder();
+                let rows = self
+                    .query_strings(
+                        query_ctx,
+                        "SELECT DISTINCT catalog_name FROM information_schema.schemata",
+                    )
+                    .await?;
+                for row in rows {
+                    builder.append(&row[0]);
+                }
+                builder.build()
+            }
+            Command::CommandGetDbSchemas(command) => {
+                let sql = db_schemas_sql(&command);
+                let mut builder = command.into_builder();
+                for row in self.query_strings(query_ctx, &sql).await? {
+                    builder.append(&row[0], &row[1]);
+                }
+                builder.build()
+            }
+            Command::CommandGetTables(command) => {
+                let sql = tables_sql(&command);
+                let include_schema = command.include_schema;
+                let mut builder = command.into_builder();
+                for row in self.query_strings(query_ctx, &sql).await? {
+                    let schema = if include_schema {
+                        self.table_schema(query_ctx, &row[0], &row[1], &row[2])
+                            .await?
+                    } else {
+                        Arc::new(Schema::empty())
+                    };
+                    builder
+                        .append(&row[0], &row[1], &row[2], &row[3], &schema)
+                        .context(ArrowSnafu)?;
+                }
+                builder.build()
+            }
+            Command::CommandGetTableTypes(_) => {
+                let rows = self
+                    .query_strings(
+                        query_ctx,
+                        "SELECT DISTINCT table_type FROM information_schema.tables ORDER BY table_type",
+                    )
+                    .await?;
+                RecordBatch::try_new(
+                    table_types_schema(),
+                    vec![Arc::new(StringArray::from_iter_values(
+                        rows.into_iter().map(|mut row| row.remove(0)),
+                    ))],
+                )
+            }
+            Command::CommandGetSqlInfo(command) => SQL_INFO.record_batch(command.info),
+            other => return Err(unsupported(&other)),
+        };
+        let batch = batch.context(ArrowSnafu)?;
+        Ok(Response::new(batches_to_flight_data(
+            batch.schema(),
+            stream::once(future::ok(batch)),
+        )))
+    }
+
+    pub(crate) async fn flight_sql_do_put(
+        &self,
+        query_ctx: QueryContextRef,
+        command: Command,
+        has_parameters: bool,
+    ) -> TonicResult<Response<TonicStream<PutResult>>> {
+        let query = match command {
+            // Clients bind parameters by `DoPut` of the query command, or send them with the update.
+            Command::CommandPreparedStatementQuery(_) => return Err(bind_parameters_unsupported()),
+            Command::CommandPreparedStatementUpdate(_) if has_parameters => {
+                return Err(bind_parameters_unsupported())
+            }
+            Command::CommandStatementUpdate(CommandStatementUpdate { query, .. }) => query,
+            Command::CommandPreparedStatementUpdate(CommandPreparedStatementUpdate {
+                prepared_statement_handle,
+            }) => self.flight_sql.prepared_statement(
+                query_ctx.current_user().username(),
+                &prepared_statement_handle,
+            )?,
+            other => return Err(unsupported(&other)),
+        };
+
+        let output = self.execute_sql(&query_ctx, query).await?;
+        let record_count = match output.data {
+            OutputData::AffectedRows(rows) => rows as i64,
+            _ => 0,
+        };
+        let result = PutResult {
+            app_metadata: DoPutUpdateResult { record_count }.encode_to_vec().into(),
+        };
+        Ok(Response::new(
+            Box::pin(stream::once(future::ok(result))) as TonicStream<_>
+        ))
+    }
+
+    pub(crate) async fn flight_sql_do_action(
+        &self,
+        request: Request<Action>,
+    ) -> TonicResult<Response<TonicStream<arrow_flight::Result>>> {
+        let (headers, _, action) = request.into_parts();
+        let query_ctx = self.flight_sql_query_context(&headers).await?;
+        let username = query_ctx.current_user().username().to_string();
+
+        let body = match action.r#type.as_str() {
+            CREATE_PREPARED_STATEMENT => {
+                let request: ActionCreatePreparedStatementRequest = unpack_action(&action.body)?;
+                let handle = self
+                    .flight_sql
+                    .add_prepared_statement(&username, request.query);
+                // The dataset schema is left out, it's returned with the results.
+                let result = ActionCreatePreparedStatementResult {
+                    prepared_statement_handle: handle,
+                    ..Default::default()
+                };
+                Some(result.as_any().encode_to_vec())
+            }
+            CLOSE_PREPARED_STATEMENT => {
+                let request: ActionClosePreparedStatementRequest = unpack_action(&action.body)?;
+                self.flight_sql
+                    .close_prepared_statement(&username, &request.prepared_statement_handle);
+                None
+            }
+            other => {
+                return Err(Status::unimplemented(format!(
+                    "Action {other} is not supported"
+                )))
+            }
+        };
+
+        let results = body.map(|body| {
+            Ok(arrow_flight::Result {
+                body: Bytes::from(body),
+            })
+        });
+        Ok(Response::new(
+            Box::pin(stream::iter(results)) as TonicStream<_>
+        ))
+    }
+
+    pub(crate) fn flight_sql_list_actions(&self) -> TonicStream<ActionType> {
+        let actions = [
+            (
+                CREATE_PREPARED_STATEMENT,
+                "Creates a reusable prepared statement resource on the server.",
+            ),
+            (
+                CLOSE_PREPARED_STATEMENT,
+                "Closes a reusable prepared statement resource on the server.",
+            ),
+        ]
+        .map(|(r#type, description)| {
+            Ok(ActionType {
+                r#type: r#type.to_string(),
+                description: description.to_string(),
+            })
+        });
+        Box::pin(stream::iter(actions))
+    }
+
+    async fn execute_sql(&self, query_ctx: &QueryContextRef, sql: String) -> TonicResult<Output> {
+        let request = GreptimeRequestType::Query(QueryRequest {
+            query: Some(Query::Sql(sql)),
+        });
+        Ok(self
+            .handle_authenticated_request(request, query_ctx.clone())
+            .await?)
+    }
+
+    /// Executes the query and returns the rows of strings.
+    async fn query_strings(
+        &self,
+        query_ctx: &QueryContextRef,
+        sql: &str,
+    ) -> TonicResult<Vec<Vec<String>>> {
+        let output = self.execute_sql(query_ctx, sql.to_string()).await?;
+        let batches = match output.data {
+            OutputData::Stream(stream) => RecordBatches::try_collect(stream)
+                .await
+                .context(CollectRecordbatchSnafu)?,
+            OutputData::RecordBatches(batches) => batches,
+            OutputData::AffectedRows(_) => return Ok(vec![]),
+        };
+        let rows = batches
+            .iter()
+            .flat_map(|batch| batch.rows())
+            .map(|row| {
+                row.into_iter()
+                    .map(|value| match value {
+                        Value::String(s) => s.as_utf8().to_string(),
+                        Value::Null => String::new(),
+                        other => other.to_string(),
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(rows)
+    }
+
+    async fn table_schema(
+        &self,
+        query_ctx: &QueryContextRef,
+        catalog: &str,
+        schema: &str,
+        table: &str,
+    ) -> TonicResult<SchemaRef> {
+        let sql = format!(
+            "SELECT * FROM {}.{}.{} LIMIT 0",
+            quote_identifier(catalog),
+            quote_identifier(schema),
+            quote_identifier(table)
+        );
+        let output = self.execute_sql(query_ctx, sql).await?;
+        let schema = match &output.data {
+            OutputData::Stream(stream) => stream.schema().arrow_schema().clone(),
+            OutputData::RecordBatches(batches) => batches.schema().arrow_schema().clone(),
+            OutputData::AffectedRows(_) => Arc::new(Schema::empty()),
+        };
+        Ok(schema)
+    }
+}
+
+/// Returns the token of a bearer authorization header.
+fn bearer_token(authorization: &str) -> Option<&str> {
+    let (scheme, token) = authorization.split_once(' ')?;
+    scheme
+        .eq_ignore_ascii_case("Bearer")
+        .then_some(token.trim())
+}
+
+/// The ticket to execute the `query` in `DoGet`.
+fn statement_ticket(query: String) -> Bytes {
+    TicketStatementQuery {
+        statement_handle: query.into(),
+    }
+    .as_any()
+    .encode_to_vec()
+    .into()
+}
+
+fn bind_parameters_unsupported() -> Status {
+    Status::unimplemented("Binding parameters to prepared statements is not supported")
+}
+
+fn unsupported(command: &Command) -> Status {
+    Status::unimplemented(format!("{} is not supported", command.type_url()))
+}
+
+fn unpack_action<T: ProstMessageExt>(body: &[u8]) -> TonicResult<T> {
+    Any::decode(body)
+        .map_err(|e| Status::invalid_argument(e.to_string()))?
+        .unpack()
+        .map_err(|e| Status::invalid_argument(e.to_string()))?
+        .ok_or_else(|| Status::invalid_argument("unexpected action body"))
+}
+
+fn db_schemas_sql(command: &CommandGetDbSchemas) -> String {
+    let mut filters = Vec::new();
+    if let Some(catalog) = &command.catalog {
+        filters.push(format!("catalog_name = {}", quote_literal(catalog)));
+    }
+    if let Some(pattern) = &command.db_schema_filter_pattern {
+        filters.push(format!("schema_name LIKE {}", quote_literal(pattern)));
+    }
+    with_filters(
+        "SELECT catalog_name, schema_name FROM information_schema.schemata",
+        &filters,
+        "catalog_name, schema_name",
+    )
+}
+
+fn tables_sql(command: &CommandGetTables) -> String {
+    let mut filters = Vec::new();
+    if let Some(catalog) = &command.catalog {
+        filters.push(format!("table_catalog = {}", quote_literal(catalog)));
+    }
+    if let Some(pattern) = &command.db_schema_filter_pattern {
+        filters.push(format!("table_schema LIKE {}", quote_literal(pattern)));
+    }
+    if let Some(pattern) = &command.table_name_filter_pattern {
+        filters.push(format!("table_name LIKE {}", quote_literal(pattern)));
+    }
+    if !command.table_types.is_empty() {
+        filters.push(format!(
+            "table_type IN ({})",
+            command
+                .table_types
+                .iter()
+                .map(|t| quote_literal(t))
+                .join(", ")
+        ));
+    }
+    with_filters(
+        "SELECT table_catalog, table_schema, table_name, table_type FROM information_schema.tables",
+        &filters,
+        "table_catalog, table_schema, table_name",
+    )
+}
+
+fn with_filters(select: &str, filters: &[String], order_by: &str) -> String {
+    if filters.is_empty() {
+        format!("{select} ORDER BY {order_by}")
+    } else {
+        format!(
+            "{select} WHERE {} ORDER BY {order_by}",
+            filters.join(" AND ")
+        )
+    }
+}
+
+fn quote_literal(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+fn quote_identifier(s: &str) -> String {
+    format!("`{}`", s.replace('`', "``"))
+}
+
+fn table_types_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![Field::new(
+        "table_type",
+        DataType::Utf8,
+        false,
+    )]))
+}
+
+fn affected_rows_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![Field::new(
+        "affected_rows",
+        DataType::UInt64,
+        false,
+    )]))
+}
+
+fn output_to_flight_data(output: Output) -> TonicStream<FlightData> {
+    let stream: SendableRecordBatchStream = match output.data {
+        OutputData::Stream(stream) => stream,
+        OutputData::RecordBatches(batches) => batches.as_stream(),
+        OutputData::AffectedRows(rows) => {
+            let schema = affected_rows_schema();
+            let batch = RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(UInt64Array::from(vec![rows as u64]))],
+            )
+            .map_err(FlightError::Arrow);
+            return batches_to_flight_data(schema, stream::once(future::ready(batch)));
+        }
+    };
+    let schema = stream.schema().arrow_schema().clone();
+    let batches = stream
+        .map_ok(|batch| batch.into_df_record_batch())
+        .map_err(|e| FlightError::ExternalError(Box::new(e)));
+    batches_to_flight_data(schema, batches)
+}
+
+fn batches_to_flight_data<S>(schema: SchemaRef, batches: S) -> TonicStream<FlightData>
+where
+    S: futures::Stream<Item = Result<RecordBatch, FlightError>> + Send + 'static,
+{
+    let stream = FlightDataEncoderBuilder::new()
+        .with_schema(schema)
+        .build(batches)
+        .map_err(Status::from);
+    Box::pin(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use api::v1::GreptimeRequest;
+    use arrow_flight::decode::FlightRecordBatchStream;
+
+    use super::*;
+
+    #[test]
+    fn test_decode_command() {
+        let request = GreptimeRequest {
+            header: Some(RequestHeader {
+                dbname: "greptime-public".to_string(),
+                ..Default::default()
+            }),
+            request: Some(GreptimeRequestType::Query(QueryRequest {
+                query: Some(Query::Sql("SELECT 1".to_string())),
+            })),
+        };
+        assert!(decode_command(&request.encode_to_vec()).is_none());
+        assert!(decode_command(&[]).is_none());
+
+        let command = CommandStatementQuery {
+            query: "SELECT 1".to_string(),
+            transaction_id: None,
+        };
+        let decoded = decode_command(&command.as_any().encode_to_vec())
+            .unwrap()
+            .unwrap();
+        assert!(matches!(decoded, Command::CommandStatementQuery(c) if c == command));
+    }
+
+    #[test]
+    fn test_metadata_sql() {
+        let command = CommandGetTables {
+            catalog: Some("greptime".to_string()),
+            db_schema_filter_pattern: Some("pub%".to_string()),
+            table_name_filter_pattern: Some("it's".to_string()),
+            table_types: vec!["BASE TABLE".to_string(), "VIEW".to_string()],
+            include_schema: false,
+        };
+        assert_eq!(
+            "SELECT table_catalog, table_schema, table_name, table_type FROM information_schema.tables \
+            WHERE table_catalog = 'greptime' AND table_schema LIKE 'pub%' AND table_name LIKE 'it''s' \
+            AND table_type IN ('BASE TABLE', 'VIEW') ORDER BY table_catalog, table_schema, table_name",
+            tables_sql(&command)
+        );
+
+        let command = CommandGetDbSchemas {
+            catalog: None,
+            db_schema_filter_pattern: None,
+        };
+        assert_eq!(
+            "SELECT catalog_name, schema_name FROM information_schema.schemata ORDER BY catalog_name, schema_name",
+            db_schemas_sql(&command)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_output_to_flight_data() {
+        let stream =
+            output_to_flight_data(Output::new_with_affected_rows(3)).map_err(FlightError::from);
+        let batches = FlightRecordBatchStream::new_from_flight_data(stream)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(1, batches.len());
+        let rows = batches[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        assert_eq!(3, rows.value(0));
+    }
+
+    #[test]
+    fn test_prepared_statements() {
+        let state = FlightSqlState::default();
+        let handle = state.add_prepared_statement("alice", "SELECT 1".to_string());
+        assert_eq!(
+            "SELECT 1",
+            state.prepared_statement("alice", &handle).unwrap()
+        );
+        // Other users can't use or close the statement.
+        assert!(state.prepared_statement("bob", &handle).is_err());
+        state.close_prepared_statement("bob", &handle);
+        assert!(state.prepared_statement("alice", &handle).is_ok());
+        state.close_prepared_statement("alice", &handle);
+        assert!(state.prepared_statement("alice", &handle).is_err());
+
+        let handles = (0..=MAX_PREPARED_STATEMENTS_PER_USER)
+            .map(|i| state.add_prepared_statement("alice", format!("SELECT {i}")))
+            .collect::<Vec<_>>();
+        let _ = state.add_prepared_statement("bob", "SELECT 1".to_string());
+        assert_eq!(
+            MAX_PREPARED_STATEMENTS_PER_USER + 1,
+            state.prepared_statements.lock().unwrap().len()
+        );
+        assert!(state
+            .prepared_statement("alice", handles.last().unwrap())
+            .is_ok());
+    }
+
+    #[test]
+    fn test_sessions() {
+        let state = FlightSqlState::default();
+        let token = state.new_session(auth::userinfo_by_name(Some("alice".to_string())));
+        assert_eq!("alice", state.session(&token).unwrap().username());
+        assert!(state.session("unknown").is_none());
+
+        assert_eq!(
+            Some(token.as_str()),
+            bearer_token(&format!("Bearer {token}"))
+        );
+        assert_eq!(None, bearer_token("Basic Z3JlcHRpbWU6Z3JlcHRpbWU="));
+    }
+
+    #[test]
+    fn test_sql_info() {
+        let batch = SQL_INFO
+            .record_batch([SqlInfo::FlightSqlServerName as u32])
+            .unwrap();
+        assert_eq!(1, batch.num_rows());
+    }
+}
@@ -18,6 +18,7 @@ use std::str::FromStr;
 use std::time::Instant;
 
 use api::helper::request_type;
+use api::v1::greptime_request::Request as GreptimeRequestType;
 use api::v1::{GreptimeRequest, RequestHeader};
 use auth::UserProviderRef;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
@@ -42,7 +43,7 @@ use tokio::sync::mpsc;
 use tokio::sync::mpsc::error::TrySendError;
 
 use crate::error::{InvalidQuerySnafu, JoinTaskSnafu, Result, UnknownHintSnafu};
-use crate::grpc::flight::{PutRecordBatchRequest, PutRecordBatchRequestStream};
+use crate::grpc::flight::{FlightSqlStateRef, PutRecordBatchRequest, PutRecordBatchRequestStream};
 use crate::grpc::{context_auth, FlightCompression, TonicResult};
 use crate::metrics;
 use crate::metrics::METRIC_SERVER_GRPC_DB_REQUEST_TIMER;
@@ -54,6 +55,7 @@ pub struct GreptimeRequestHandler {
     pub(crate) user_provider: Option<UserProviderRef>,
     runtime: Option<Runtime>,
     pub(crate) flight_compression: FlightCompression,
+    pub(crate) flight_sql: FlightSqlStateRef,
 }
 
 impl GreptimeRequestHandler {
@@ -68,6 +70,7 @@ impl GreptimeRequestHandler {
             user_provider,
             runtime,
             flight_compression,
+            flight_sql: Default::default(),
         }
     }
 
@@ -86,6 +89,15 @@ impl GreptimeRequestHandler {
         let user_info = context_auth::auth(self.user_provider.clone(), header, &query_ctx).await?;
         query_ctx.set_current_user(user_info);
 
+        self.handle_authenticated_request(query, query_ctx).await
+    }
+
+    /// Handles the request of the user already authenticated in the `query_ctx`.
+    pub(crate) async fn handle_authenticated_request(
+        &self,
+        query: GreptimeRequestType,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
         let handler = self.handler.clone();
         let request_type = request_type(&query).to_string();
         let db = query_ctx.get_db_string();
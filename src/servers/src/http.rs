@@ -61,8 +61,8 @@ use crate::http::influxdb::{influxdb_health, influxdb_ping, influxdb_write_v1, i
 use crate::http::otlp::OtlpState;
 use crate::http::prom_store::PromStoreState;
 use crate::http::prometheus::{
-    build_info_query, format_query, instant_query, label_values_query, labels_query, parse_query,
-    range_query, series_query,
+    alerts_query, build_info_query, format_query, instant_query, label_values_query, labels_query,
+    parse_query, range_query, rules_query, series_query,
 };
 use crate::http::result::arrow_result::ArrowResponse;
 use crate::http::result::csv_result::CsvResponse;
@@ -74,6 +74,7 @@ use crate::http::result::null_result::NullResponse;
 use crate::interceptor::LogIngestInterceptorRef;
 use crate::metrics::http_metrics_layer;
 use crate::metrics_handler::MetricsHandler;
+use crate::prom_rule::RuleManagerRef;
 use crate::prometheus_handler::PrometheusHandlerRef;
 use crate::query_handler::sql::ServerSqlQueryHandlerRef;
 use crate::query_handler::{
@@ -95,6 +96,7 @@ pub mod influxdb;
 pub mod jaeger;
 pub mod logs;
 pub mod loki;
+pub mod loki_query;
 pub mod mem_prof;
 pub mod opentsdb;
 pub mod otlp;
@@ -632,6 +634,16 @@ impl HttpServerBuilder {
         }
     }
 
+    /// Adds the Prometheus `rules` and `alerts` APIs next to the other Prometheus HTTP APIs.
+    pub fn with_prometheus_rule_handler(self, manager: RuleManagerRef) -> Self {
+        Self {
+            router: self
+                .router
+                .merge(HttpServer::route_prometheus_rules(manager)),
+            ..self
+        }
+    }
+
     pub fn with_otlp_handler(
         self,
         handler: OpenTelemetryProtocolHandlerRef,
@@ -954,8 +966,17 @@ impl HttpServer {
                     axum::Json(serde_json::json!({})),
                 )),
             )
-            // Return fake response for Elasticsearch ingest pipeline request.
+            // Translate Elasticsearch ingest pipelines into GreptimeDB pipelines.
             // See: https://www.elastic.co/guide/en/elasticsearch/reference/8.8/put-pipeline-api.html.
+            .route(
+                "/_ingest/pipeline/{id}",
+                routing::put(elasticsearch::handle_put_ingest_pipeline).fallback((
+                    HttpStatusCode::OK,
+                    elasticsearch::elasticsearch_headers(),
+                    axum::Json(serde_json::json!({})),
+                )),
+            )
+            // Return fake response for other Elasticsearch ingest pipeline requests.
             .route(
                 "/_ingest/{*path}",
                 routing::any((
@@ -1035,6 +1056,18 @@ impl HttpServer {
                 "/pipelines/{pipeline_name}",
                 routing::delete(event::delete_pipeline),
             )
+            .route(
+                "/pipelines/{pipeline_name}/versions",
+                routing::get(event::list_pipeline_versions),
+            )
+            .route(
+                "/pipelines/{pipeline_name}/_diff",
+                routing::get(event::diff_pipeline),
+            )
+            .route(
+                "/pipelines/{pipeline_name}/_rollback",
+                routing::post(event::rollback_pipeline),
+            )
             .route("/pipelines/_dryrun", routing::post(event::pipeline_dryrun))
             .layer(
                 ServiceBuilder::new()
@@ -1064,6 +1097,20 @@ impl HttpServer {
     fn route_logs<S>(log_handler: LogQueryHandlerRef) -> Router<S> {
         Router::new()
             .route("/logs", routing::get(logs::logs).post(logs::logs))
+            .route(
+                "/loki/api/v1/query",
+                routing::get(loki_query::query).post(loki_query::query),
+            )
+            .route(
+                "/loki/api/v1/query_range",
+                routing::get(loki_query::query_range).post(loki_query::query_range),
+            )
+            .route("/loki/api/v1/labels", routing::get(loki_query::labels))
+            .route(
+                "/loki/api/v1/label/{name}/values",
+                routing::get(loki_query::label_values),
+            )
+            .route("/loki/api/v1/series", routing::get(loki_query::series))
             .with_state(log_handler)
     }
 
@@ -1090,6 +1137,14 @@ impl HttpServer {
             .with_state(prometheus_handler)
     }
 
+    fn route_prometheus_rules<S>(manager: RuleManagerRef) -> Router<S> {
+        let prefix = format!("/{HTTP_API_VERSION}/prometheus/api/v1");
+        Router::new()
+            .route(&format!("{prefix}/rules"), routing::get(rules_query))
+            .route(&format!("{prefix}/alerts"), routing::get(alerts_query))
+            .with_state(manager)
+    }
+
     /// Route Prometheus remote [read] and [write] API. In other places the related modules are
     /// called `prom_store`.
     ///
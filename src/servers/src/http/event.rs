@@ -62,7 +62,7 @@ use crate::metrics::{
 use crate::pipeline::run_pipeline;
 use crate::query_handler::PipelineHandlerRef;
 
-const GREPTIME_INTERNAL_PIPELINE_NAME_PREFIX: &str = "greptime_";
+pub(crate) const GREPTIME_INTERNAL_PIPELINE_NAME_PREFIX: &str = "greptime_";
 const GREPTIME_PIPELINE_SKIP_ERROR_KEY: &str = "skip_error";
 
 lazy_static! {
@@ -83,6 +83,8 @@ pub struct LogIngesterQueryParams {
     /// The table where log data will be written to.
     pub table: Option<String>,
     /// The pipeline that will be used for log ingestion.
+    /// `pipeline` is accepted for the `_bulk` API of Elasticsearch.
+    #[serde(alias = "pipeline")]
     pub pipeline_name: Option<String>,
     /// The version of the pipeline to be used for log ingestion.
     pub version: Option<String>,
@@ -110,6 +112,15 @@ pub struct LogIngesterQueryParams {
     pub skip_error: Option<bool>,
 }
 
+/// PipelineDiffQueryParams is used for query params of the pipeline diff API.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PipelineDiffQueryParams {
+    /// The version to diff from.
+    pub from: Option<String>,
+    /// The version to diff to. The latest version is used if it's not set.
+    pub to: Option<String>,
+}
+
 /// LogIngestRequest is the internal request for log ingestion. The raw log input can be transformed into multiple LogIngestRequests.
 /// Multiple LogIngestRequests will be ingested into the same database with the same pipeline.
 #[derive(Debug, PartialEq)]
@@ -292,6 +303,113 @@ pub async fn delete_pipeline(
         })
 }
 
+#[axum_macros::debug_handler]
+pub async fn list_pipeline_versions(
+    State(state): State<LogState>,
+    Extension(mut query_ctx): Extension<QueryContext>,
+    Path(pipeline_name): Path<String>,
+) -> Result<GreptimedbManageResponse> {
+    let start = Instant::now();
+    let handler = state.log_handler;
+    ensure!(
+        !pipeline_name.is_empty(),
+        InvalidParameterSnafu {
+            reason: "pipeline_name is required in path",
+        }
+    );
+
+    query_ctx.set_channel(Channel::Log);
+    let query_ctx = Arc::new(query_ctx);
+
+    let versions = handler
+        .list_pipeline_versions(&pipeline_name, query_ctx)
+        .await?;
+
+    Ok(GreptimedbManageResponse::from_pipeline_versions(
+        pipeline_name,
+        versions
+            .into_iter()
+            .map(|version| version.0.to_timezone_aware_string(None))
+            .collect(),
+        start.elapsed().as_millis() as u64,
+    ))
+}
+
+#[axum_macros::debug_handler]
+pub async fn diff_pipeline(
+    State(state): State<LogState>,
+    Extension(mut query_ctx): Extension<QueryContext>,
+    Query(query_params): Query<PipelineDiffQueryParams>,
+    Path(pipeline_name): Path<String>,
+) -> Result<GreptimedbManageResponse> {
+    let start = Instant::now();
+    let handler = state.log_handler;
+    ensure!(
+        !pipeline_name.is_empty(),
+        InvalidParameterSnafu {
+            reason: "pipeline_name is required in path",
+        }
+    );
+
+    let from_str = query_params.from.context(InvalidParameterSnafu {
+        reason: "from is required",
+    })?;
+    let from = to_pipeline_version(Some(&from_str)).context(PipelineSnafu)?;
+    let to = to_pipeline_version(query_params.to.as_deref()).context(PipelineSnafu)?;
+
+    query_ctx.set_channel(Channel::Log);
+    let query_ctx = Arc::new(query_ctx);
+
+    let diff = handler
+        .diff_pipeline(&pipeline_name, from, to, query_ctx)
+        .await?;
+
+    Ok(GreptimedbManageResponse::from_pipeline_diff(
+        pipeline_name,
+        diff,
+        start.elapsed().as_millis() as u64,
+    ))
+}
+
+#[axum_macros::debug_handler]
+pub async fn rollback_pipeline(
+    State(state): State<LogState>,
+    Extension(mut query_ctx): Extension<QueryContext>,
+    Query(query_params): Query<LogIngesterQueryParams>,
+    Path(pipeline_name): Path<String>,
+) -> Result<GreptimedbManageResponse> {
+    let start = Instant::now();
+    let handler = state.log_handler;
+    ensure!(
+        !pipeline_name.is_empty(),
+        InvalidParameterSnafu {
+            reason: "pipeline_name is required in path",
+        }
+    );
+
+    // Rolls back to the version before the latest one if the version is not set.
+    let version = to_pipeline_version(query_params.version.as_deref()).context(PipelineSnafu)?;
+
+    query_ctx.set_channel(Channel::Log);
+    let query_ctx = Arc::new(query_ctx);
+
+    handler
+        .rollback_pipeline(&pipeline_name, version, query_ctx)
+        .await
+        .map(|pipeline| {
+            GreptimedbManageResponse::from_pipeline(
+                pipeline_name,
+                pipeline.0.to_timezone_aware_string(None),
+                start.elapsed().as_millis() as u64,
+                None,
+            )
+        })
+        .map_err(|e| {
+            error!(e; "failed to roll back pipeline");
+            e
+        })
+}
+
 /// Transform NDJSON array into a single array
 /// always return an array
 fn transform_ndjson_array_factory(
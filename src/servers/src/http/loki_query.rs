@@ -0,0 +1,924 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Loki compatible [query API] backed by [`LogQueryHandler`](crate::query_handler::LogQueryHandler).
+//!
+//! LogQL queries are parsed and lowered into [`LogQuery`] by [`log_query::logql`].
+//! Metric queries are evaluated at `start`, `start + step`, ... until `end`, each sample
+//! aggregates the logs in `(t - range, t]` like Loki does.
+//!
+//! [query API]: https://grafana.com/docs/loki/latest/reference/loki-http-api/#query-endpoints
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use chrono::{DateTime, Duration, Utc};
+use common_error::ext::ErrorExt;
+use common_query::prelude::GREPTIME_TIMESTAMP;
+use common_query::{Output, OutputData};
+use common_recordbatch::{util, RecordBatch};
+use common_telemetry::{error, tracing};
+use common_time::timestamp::TimeUnit;
+use datatypes::value::Value;
+use log_query::logql::{
+    parse_duration, EvalParams, LogQLExpr, MetricExpr, SeriesSamples, LINE_COLUMN,
+    METRIC_VALUE_COLUMN,
+};
+use log_query::{Direction, Filters, Limit, LogExpr, LogQuery, TimeFilter, RANGE_BUCKET_COLUMN};
+use serde::{Deserialize, Serialize};
+use session::context::{Channel, QueryContext, QueryContextRef};
+use snafu::{ensure, OptionExt, ResultExt};
+use table::table_name::TableName;
+
+use crate::error::{
+    CatalogSnafu, CollectRecordbatchSnafu, Error, InvalidQuerySnafu, LogQLSnafu, Result,
+    TableNotFoundSnafu,
+};
+use crate::http::extractor::LogTableName;
+use crate::http::prometheus::Matches;
+use crate::metrics::METRIC_LOKI_QUERY_ELAPSED;
+use crate::query_handler::LogQueryHandlerRef;
+
+const LOKI_TABLE_NAME: &str = "loki_logs";
+const DEFAULT_LOG_LIMIT: usize = 100;
+/// Loki looks back one hour when `start` is not given.
+const DEFAULT_LOOKBACK_HOURS: i64 = 1;
+/// Max number of samples of a series in a range query, the same as Loki.
+const MAX_POINTS_PER_SERIES: i64 = 11_000;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LokiQueryParams {
+    query: Option<String>,
+    limit: Option<usize>,
+    time: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    step: Option<String>,
+    direction: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LokiLabelParams {
+    start: Option<String>,
+    end: Option<String>,
+    query: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LokiSeriesParams {
+    start: Option<String>,
+    end: Option<String>,
+    #[serde(flatten)]
+    matches: Matches,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LokiResponse<T> {
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LokiQueryData {
+    result_type: &'static str,
+    result: LokiQueryResult,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum LokiQueryResult {
+    Streams(Vec<LokiStream>),
+    Matrix(Vec<LokiMatrixSeries>),
+    Vector(Vec<LokiVectorSample>),
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct LokiStream {
+    stream: BTreeMap<String, String>,
+    /// Pairs of nanosecond timestamp and log line.
+    values: Vec<(String, String)>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct LokiMatrixSeries {
+    metric: BTreeMap<String, String>,
+    /// Pairs of second timestamp and sample value.
+    values: Vec<(f64, String)>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct LokiVectorSample {
+    metric: BTreeMap<String, String>,
+    value: (f64, String),
+}
+
+fn success<T: Serialize>(data: T) -> Response {
+    Json(LokiResponse {
+        status: "success",
+        data: Some(data),
+        error: None,
+    })
+    .into_response()
+}
+
+fn error_response(err: Error) -> Response {
+    error!(err; "Failed to handle Loki query");
+    (
+        crate::error::status_code_to_http_status(&err.status_code()),
+        Json(LokiResponse::<()> {
+            status: "error",
+            data: None,
+            error: Some(err.output_msg()),
+        }),
+    )
+        .into_response()
+}
+
+/// Handle the `/loki/api/v1/query` request.
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "loki", request_type = "query"))]
+pub async fn query(
+    State(handler): State<LogQueryHandlerRef>,
+    Query(params): Query<LokiQueryParams>,
+    Extension(query_ctx): Extension<QueryContext>,
+    LogTableName(table_name): LogTableName,
+) -> Response {
+    let query_ctx = loki_query_context(query_ctx);
+    let _timer = METRIC_LOKI_QUERY_ELAPSED
+        .with_label_values(&[query_ctx.get_db_string().as_str(), "/query"])
+        .start_timer();
+
+    match do_query(handler, params, query_ctx, table_name, true).await {
+        Ok(data) => success(data),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Handle the `/loki/api/v1/query_range` request.
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "loki", request_type = "query_range"))]
+pub async fn query_range(
+    State(handler): State<LogQueryHandlerRef>,
+    Query(params): Query<LokiQueryParams>,
+    Extension(query_ctx): Extension<QueryContext>,
+    LogTableName(table_name): LogTableName,
+) -> Response {
+    let query_ctx = loki_query_context(query_ctx);
+    let _timer = METRIC_LOKI_QUERY_ELAPSED
+        .with_label_values(&[query_ctx.get_db_string().as_str(), "/query_range"])
+        .start_timer();
+
+    match do_query(handler, params, query_ctx, table_name, false).await {
+        Ok(data) => success(data),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Handle the `/loki/api/v1/labels` request.
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "loki", request_type = "labels"))]
+pub async fn labels(
+    State(handler): State<LogQueryHandlerRef>,
+    Extension(query_ctx): Extension<QueryContext>,
+    LogTableName(table_name): LogTableName,
+) -> Response {
+    let query_ctx = loki_query_context(query_ctx);
+    let _timer = METRIC_LOKI_QUERY_ELAPSED
+        .with_label_values(&[query_ctx.get_db_string().as_str(), "/labels"])
+        .start_timer();
+
+    let table_name = loki_table_name(&query_ctx, table_name);
+    match stream_labels(&handler, &table_name, &query_ctx).await {
+        Ok(labels) => success(labels),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Handle the `/loki/api/v1/label/{name}/values` request.
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "loki", request_type = "label_values"))]
+pub async fn label_values(
+    State(handler): State<LogQueryHandlerRef>,
+    Path(label_name): Path<String>,
+    Query(params): Query<LokiLabelParams>,
+    Extension(query_ctx): Extension<QueryContext>,
+    LogTableName(table_name): LogTableName,
+) -> Response {
+    let query_ctx = loki_query_context(query_ctx);
+    let _timer = METRIC_LOKI_QUERY_ELAPSED
+        .with_label_values(&[query_ctx.get_db_string().as_str(), "/label_values"])
+        .start_timer();
+
+    let result: Result<BTreeSet<String>> = async {
+        let table_name = loki_table_name(&query_ctx, table_name);
+        let time_filter = parse_time_range(params.start.as_deref(), params.end.as_deref())?;
+        let filters = match &params.query {
+            Some(query) => selector_filters(query)?,
+            None => Filters::default(),
+        };
+        let label_sets = distinct_label_sets(
+            &handler,
+            table_name,
+            time_filter,
+            filters,
+            vec![label_name],
+            &query_ctx,
+        )
+        .await?;
+        Ok(label_sets
+            .into_iter()
+            .filter_map(|mut set| set.pop_first().map(|(_, v)| v))
+            .collect::<BTreeSet<_>>())
+    }
+    .await;
+
+    match result {
+        Ok(values) => success(values),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Handle the `/loki/api/v1/series` request.
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "loki", request_type = "series"))]
+pub async fn series(
+    State(handler): State<LogQueryHandlerRef>,
+    Query(params): Query<LokiSeriesParams>,
+    Extension(query_ctx): Extension<QueryContext>,
+    LogTableName(table_name): LogTableName,
+) -> Response {
+    let query_ctx = loki_query_context(query_ctx);
+    let _timer = METRIC_LOKI_QUERY_ELAPSED
+        .with_label_values(&[query_ctx.get_db_string().as_str(), "/series"])
+        .start_timer();
+
+    let result: Result<Vec<BTreeMap<String, String>>> = async {
+        let table_name = loki_table_name(&query_ctx, table_name);
+        let time_filter = parse_time_range(params.start.as_deref(), params.end.as_deref())?;
+        let labels = stream_labels(&handler, &table_name, &query_ctx).await?;
+        let mut series = BTreeSet::new();
+        for selector in &params.matches.0 {
+            series.extend(
+                distinct_label_sets(
+                    &handler,
+                    table_name.clone(),
+                    time_filter.clone(),
+                    selector_filters(selector)?,
+                    labels.clone(),
+                    &query_ctx,
+                )
+                .await?,
+            );
+        }
+        Ok(series.into_iter().collect::<Vec<_>>())
+    }
+    .await;
+
+    match result {
+        Ok(series) => success(series),
+        Err(err) => error_response(err),
+    }
+}
+
+fn loki_query_context(mut query_ctx: QueryContext) -> QueryContextRef {
+    query_ctx.set_channel(Channel::Loki);
+    Arc::new(query_ctx)
+}
+
+fn loki_table_name(query_ctx: &QueryContext, table_name: Option<String>) -> TableName {
+    TableName::new(
+        query_ctx.current_catalog(),
+        query_ctx.current_schema(),
+        table_name.unwrap_or_else(|| LOKI_TABLE_NAME.to_string()),
+    )
+}
+
+async fn do_query(
+    handler: LogQueryHandlerRef,
+    params: LokiQueryParams,
+    query_ctx: QueryContextRef,
+    table_name: Option<String>,
+    instant: bool,
+) -> Result<LokiQueryData> {
+    let query = params.query.as_deref().context(InvalidQuerySnafu {
+        reason: "query parameter is required",
+    })?;
+    let expr = LogQLExpr::parse(query).context(LogQLSnafu)?;
+    let table_name = loki_table_name(&query_ctx, table_name);
+    let labels = stream_labels(&handler, &table_name, &query_ctx).await?;
+    let eval_params = eval_params(&params, &expr, instant)?;
+
+    let log_query = expr
+        .to_log_query(table_name, &eval_params, GREPTIME_TIMESTAMP, &labels)
+        .context(LogQLSnafu)?;
+    let batches = execute(&handler, log_query, &query_ctx).await?;
+
+    match expr {
+        LogQLExpr::Log(_) => Ok(LokiQueryData {
+            result_type: "streams",
+            result: LokiQueryResult::Streams(streams_from_batches(
+                &batches,
+                &labels,
+                eval_params.direction == Direction::Forward,
+            )),
+        }),
+        LogQLExpr::Metric(metric) => {
+            let buckets = buckets_from_batches(&batches, &metric, &eval_params);
+            let samples = metric.eval(&eval_params, &buckets);
+            if instant {
+                Ok(LokiQueryData {
+                    result_type: "vector",
+                    result: LokiQueryResult::Vector(vector_from_samples(samples)),
+                })
+            } else {
+                Ok(LokiQueryData {
+                    result_type: "matrix",
+                    result: LokiQueryResult::Matrix(matrix_from_samples(samples)),
+                })
+            }
+        }
+    }
+}
+
+/// Returns the evaluation parameters of a query.
+///
+/// An instant metric query is evaluated once at `time`, while an instant log query
+/// returns the logs in the hour before `time`.
+fn eval_params(params: &LokiQueryParams, expr: &LogQLExpr, instant: bool) -> Result<EvalParams> {
+    let direction = match params.direction.as_deref() {
+        None | Some("backward") => Direction::Backward,
+        Some("forward") => Direction::Forward,
+        Some(direction) => {
+            return InvalidQuerySnafu {
+                reason: format!("invalid direction: {direction}"),
+            }
+            .fail()
+        }
+    };
+    let limit = params.limit.unwrap_or(DEFAULT_LOG_LIMIT);
+
+    if instant {
+        let time = match params.time.as_deref() {
+            Some(time) => parse_loki_time(time)?,
+            None => Utc::now(),
+        };
+        let (start, step) = match expr {
+            LogQLExpr::Metric(metric) => (time, metric.range()),
+            LogQLExpr::Log(_) => (
+                time - Duration::hours(DEFAULT_LOOKBACK_HOURS),
+                std::time::Duration::from_secs(1),
+            ),
+        };
+        return Ok(EvalParams {
+            start,
+            end: time,
+            step,
+            limit,
+            direction,
+        });
+    }
+
+    let end = match params.end.as_deref() {
+        Some(end) => parse_loki_time(end)?,
+        None => Utc::now(),
+    };
+    let start = match params.start.as_deref() {
+        Some(start) => parse_loki_time(start)?,
+        None => end - Duration::hours(DEFAULT_LOOKBACK_HOURS),
+    };
+    let step = match params.step.as_deref() {
+        Some(step) => parse_step(step)?,
+        // The default step of Loki, which returns at most 250 samples.
+        None => ((end - start) / 250)
+            .to_std()
+            .unwrap_or_default()
+            .max(std::time::Duration::from_secs(1)),
+    };
+    if expr.is_metric() {
+        let points = (end - start).num_milliseconds() / (step.as_millis() as i64).max(1);
+        ensure!(
+            points <= MAX_POINTS_PER_SERIES,
+            InvalidQuerySnafu {
+                reason: format!(
+                    "exceeded maximum resolution of {MAX_POINTS_PER_SERIES} points per series, try decreasing the query resolution (?step=XX)"
+                ),
+            }
+        );
+    }
+    Ok(EvalParams {
+        start,
+        end,
+        step,
+        limit,
+        direction,
+    })
+}
+
+/// Parses the step of a range query, which is either a duration like `15s` or float seconds.
+fn parse_step(s: &str) -> Result<std::time::Duration> {
+    let step = match s.parse::<f64>() {
+        Ok(secs) if secs.is_finite() && secs > 0.0 => {
+            Some(std::time::Duration::from_secs_f64(secs))
+        }
+        Ok(_) => None,
+        Err(_) => parse_duration(s),
+    };
+    step.filter(|step| !step.is_zero())
+        .with_context(|| InvalidQuerySnafu {
+            reason: format!("invalid step: {s}"),
+        })
+}
+
+async fn execute(
+    handler: &LogQueryHandlerRef,
+    log_query: LogQuery,
+    query_ctx: &QueryContextRef,
+) -> Result<Vec<RecordBatch>> {
+    let output: Output = handler.query(log_query, query_ctx.clone()).await?;
+    match output.data {
+        OutputData::Stream(stream) => util::collect(stream).await.context(CollectRecordbatchSnafu),
+        OutputData::RecordBatches(batches) => Ok(batches.take()),
+        OutputData::AffectedRows(_) => Ok(vec![]),
+    }
+}
+
+/// Returns the tag columns of the log table, which are the stream labels.
+async fn stream_labels(
+    handler: &LogQueryHandlerRef,
+    table_name: &TableName,
+    query_ctx: &QueryContextRef,
+) -> Result<Vec<String>> {
+    let table = handler
+        .catalog_manager(query_ctx)?
+        .table(
+            &table_name.catalog_name,
+            &table_name.schema_name,
+            &table_name.table_name,
+            Some(query_ctx.as_ref()),
+        )
+        .await
+        .context(CatalogSnafu)?
+        .with_context(|| TableNotFoundSnafu {
+            catalog: &table_name.catalog_name,
+            schema: &table_name.schema_name,
+            table: &table_name.table_name,
+        })?;
+    let labels = table
+        .table_info()
+        .meta
+        .row_key_column_names()
+        .cloned()
+        .collect();
+    Ok(labels)
+}
+
+/// Returns the distinct combinations of `labels` in the rows matching `filters`.
+async fn distinct_label_sets(
+    handler: &LogQueryHandlerRef,
+    table_name: TableName,
+    time_filter: TimeFilter,
+    filters: Filters,
+    labels: Vec<String>,
+    query_ctx: &QueryContextRef,
+) -> Result<Vec<BTreeMap<String, String>>> {
+    let log_query = LogQuery {
+        table: table_name,
+        time_filter,
+        limit: Limit {
+            skip: None,
+            fetch: Some(usize::MAX),
+        },
+        filters,
+        exprs: vec![LogExpr::AggrFunc {
+            name: "count".to_string(),
+            args: vec![LogExpr::NamedIdent(GREPTIME_TIMESTAMP.to_string())],
+            range: None,
+            by: labels.iter().cloned().map(LogExpr::NamedIdent).collect(),
+            alias: None,
+        }],
+        ..Default::default()
+    };
+    let batches = execute(handler, log_query, query_ctx).await?;
+
+    let mut label_sets = Vec::new();
+    for batch in &batches {
+        for row in batch.rows() {
+            let set = labels
+                .iter()
+                .zip(row)
+                .filter_map(|(name, value)| value.as_string().map(|v| (name.clone(), v)))
+                .collect::<BTreeMap<_, _>>();
+            if !set.is_empty() {
+                label_sets.push(set);
+            }
+        }
+    }
+    Ok(label_sets)
+}
+
+fn selector_filters(query: &str) -> Result<Filters> {
+    match LogQLExpr::parse(query).context(LogQLSnafu)? {
+        LogQLExpr::Log(selector) => selector.to_filters().context(LogQLSnafu),
+        LogQLExpr::Metric(_) => InvalidQuerySnafu {
+            reason: format!("expect a stream selector, got metric query: {query}"),
+        }
+        .fail(),
+    }
+}
+
+/// Parses a Loki timestamp, which is either RFC3339, Unix nanoseconds or Unix seconds with fractions.
+fn parse_loki_time(s: &str) -> Result<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    if let Ok(nanos) = s.parse::<i64>() {
+        // Small integers are seconds, e.g. `1700000000`.
+        return Ok(if nanos.abs() < 1_000_000_000_000 {
+            DateTime::from_timestamp(nanos, 0)
+        } else {
+            Some(DateTime::from_timestamp_nanos(nanos))
+        }
+        .unwrap_or_default());
+    }
+    if let Ok(secs) = s.parse::<f64>() {
+        if let Some(dt) = DateTime::from_timestamp_micros((secs * 1_000_000.0) as i64) {
+            return Ok(dt);
+        }
+    }
+    InvalidQuerySnafu {
+        reason: format!("invalid timestamp: {s}"),
+    }
+    .fail()
+}
+
+fn parse_time_range(start: Option<&str>, end: Option<&str>) -> Result<TimeFilter> {
+    let end = match end {
+        Some(end) => parse_loki_time(end)?,
+        None => Utc::now(),
+    };
+    let start = match start {
+        Some(start) => parse_loki_time(start)?,
+        None => end - Duration::hours(DEFAULT_LOOKBACK_HOURS),
+    };
+    Ok(time_filter(start, end))
+}
+
+fn time_filter(start: DateTime<Utc>, end: DateTime<Utc>) -> TimeFilter {
+    TimeFilter {
+        start: Some(start.to_rfc3339()),
+        end: Some(end.to_rfc3339()),
+        span: None,
+    }
+}
+
+fn column_index(batch: &RecordBatch, name: &str) -> Option<usize> {
+    batch
+        .schema
+        .column_schemas()
+        .iter()
+        .position(|column| column.name == name)
+}
+
+fn value_to_nanos(value: &Value) -> Option<i64> {
+    value
+        .as_timestamp()
+        .and_then(|ts| ts.convert_to(TimeUnit::Nanosecond))
+        .map(|ts| ts.value())
+}
+
+fn streams_from_batches(
+    batches: &[RecordBatch],
+    labels: &[String],
+    forward: bool,
+) -> Vec<LokiStream> {
+    let mut streams: BTreeMap<BTreeMap<String, String>, Vec<(i64, String)>> = BTreeMap::new();
+    for batch in batches {
+        let (Some(ts_idx), Some(line_idx)) = (
+            column_index(batch, GREPTIME_TIMESTAMP),
+            column_index(batch, LINE_COLUMN),
+        ) else {
+            continue;
+        };
+        let label_indices = labels
+            .iter()
+            .filter_map(|label| column_index(batch, label).map(|idx| (label, idx)))
+            .collect::<Vec<_>>();
+
+        for row in batch.rows() {
+            let Some(ts) = value_to_nanos(&row[ts_idx]) else {
+                continue;
+            };
+            let line = row[line_idx].as_string().unwrap_or_default();
+            let stream = label_indices
+                .iter()
+                .filter_map(|(label, idx)| row[*idx].as_string().map(|v| ((*label).clone(), v)))
+                .collect();
+            streams.entry(stream).or_default().push((ts, line));
+        }
+    }
+
+    streams
+        .into_iter()
+        .map(|(stream, mut values)| {
+            values.sort_by_key(|(ts, _)| *ts);
+            if !forward {
+                values.reverse();
+            }
+            LokiStream {
+                stream,
+                values: values
+                    .into_iter()
+                    .map(|(ts, line)| (ts.to_string(), line))
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Collects the buckets of the range aggregation of each series, keyed by the bucket end.
+fn buckets_from_batches(
+    batches: &[RecordBatch],
+    metric: &MetricExpr,
+    params: &EvalParams,
+) -> SeriesSamples {
+    let mut buckets = SeriesSamples::new();
+    for batch in batches {
+        let (Some(bucket_idx), Some(value_idx)) = (
+            column_index(batch, RANGE_BUCKET_COLUMN),
+            column_index(batch, METRIC_VALUE_COLUMN),
+        ) else {
+            continue;
+        };
+        // Other columns are the labels the range aggregation groups by.
+        let label_indices = batch
+            .schema
+            .column_schemas()
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| *idx != bucket_idx && *idx != value_idx)
+            .map(|(idx, column)| (column.name.clone(), idx))
+            .collect::<Vec<_>>();
+
+        for row in batch.rows() {
+            let (Some(bucket_start), Some(value)) = (
+                value_to_nanos(&row[bucket_idx]),
+                row[value_idx].as_f64_lossy(),
+            ) else {
+                continue;
+            };
+            let labels = label_indices
+                .iter()
+                .filter_map(|(label, idx)| row[*idx].as_string().map(|v| (label.clone(), v)))
+                .collect();
+            *buckets
+                .entry(labels)
+                .or_default()
+                .entry(metric.bucket_end(params, bucket_start))
+                .or_default() += value;
+        }
+    }
+    buckets
+}
+
+fn nanos_to_secs(nanos: i64) -> f64 {
+    nanos as f64 / 1_000_000_000.0
+}
+
+fn matrix_from_samples(samples: SeriesSamples) -> Vec<LokiMatrixSeries> {
+    samples
+        .into_iter()
+        .map(|(metric, values)| LokiMatrixSeries {
+            metric,
+            values: values
+                .into_iter()
+                .map(|(ts, value)| (nanos_to_secs(ts), value.to_string()))
+                .collect(),
+        })
+        .collect()
+}
+
+/// Returns the samples of an instant query, which has only one evaluation time.
+fn vector_from_samples(samples: SeriesSamples) -> Vec<LokiVectorSample> {
+    samples
+        .into_iter()
+        .filter_map(|(metric, values)| {
+            values
+                .into_iter()
+                .next_back()
+                .map(|(ts, value)| LokiVectorSample {
+                    metric,
+                    value: (nanos_to_secs(ts), value.to_string()),
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{Int64Vector, StringVector, TimestampNanosecondVector};
+
+    use super::*;
+
+    fn batch(columns: Vec<(&str, ConcreteDataType, datatypes::vectors::VectorRef)>) -> RecordBatch {
+        let schema = Arc::new(Schema::new(
+            columns
+                .iter()
+                .map(|(name, ty, _)| ColumnSchema::new(*name, ty.clone(), true))
+                .collect(),
+        ));
+        RecordBatch::new(schema, columns.into_iter().map(|(_, _, v)| v)).unwrap()
+    }
+
+    #[test]
+    fn test_parse_loki_time() {
+        let expected = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(parse_loki_time("2024-01-01T00:00:00Z").unwrap(), expected);
+        assert_eq!(parse_loki_time("1704067200").unwrap(), expected);
+        assert_eq!(parse_loki_time("1704067200000000000").unwrap(), expected);
+        assert_eq!(parse_loki_time("1704067200.0").unwrap(), expected);
+        assert!(parse_loki_time("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_streams_from_batches() {
+        let batch = batch(vec![
+            (
+                GREPTIME_TIMESTAMP,
+                ConcreteDataType::timestamp_nanosecond_datatype(),
+                Arc::new(TimestampNanosecondVector::from_vec(vec![1, 3, 2])),
+            ),
+            (
+                LINE_COLUMN,
+                ConcreteDataType::string_datatype(),
+                Arc::new(StringVector::from(vec!["a", "c", "b"])),
+            ),
+            (
+                "app",
+                ConcreteDataType::string_datatype(),
+                Arc::new(StringVector::from(vec!["foo", "bar", "foo"])),
+            ),
+        ]);
+
+        let streams = streams_from_batches(&[batch], &["app".to_string()], false);
+        assert_eq!(
+            streams,
+            vec![
+                LokiStream {
+                    stream: BTreeMap::from([("app".to_string(), "bar".to_string())]),
+                    values: vec![("3".to_string(), "c".to_string())],
+                },
+                LokiStream {
+                    stream: BTreeMap::from([("app".to_string(), "foo".to_string())]),
+                    values: vec![
+                        ("2".to_string(), "b".to_string()),
+                        ("1".to_string(), "a".to_string())
+                    ],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_eval_params() {
+        let expr = LogQLExpr::parse(r#"rate({app="foo"}[2m])"#).unwrap();
+        let params = LokiQueryParams {
+            query: None,
+            limit: None,
+            time: None,
+            start: Some("120".to_string()),
+            end: Some("240".to_string()),
+            step: Some("1m".to_string()),
+            direction: None,
+        };
+        let result = eval_params(&params, &expr, false).unwrap();
+        assert_eq!(result.step, std::time::Duration::from_secs(60));
+        assert_eq!(result.direction, Direction::Backward);
+        assert_eq!(result.eval_times().count(), 3);
+
+        let params = LokiQueryParams {
+            step: Some("0.5".to_string()),
+            direction: Some("forward".to_string()),
+            ..params
+        };
+        let result = eval_params(&params, &expr, false).unwrap();
+        assert_eq!(result.step, std::time::Duration::from_millis(500));
+        assert_eq!(result.direction, Direction::Forward);
+
+        // The instant query is evaluated once.
+        let params = LokiQueryParams {
+            time: Some("240".to_string()),
+            ..params
+        };
+        let result = eval_params(&params, &expr, true).unwrap();
+        assert_eq!(result.eval_times().collect::<Vec<_>>(), vec![240 * SEC]);
+
+        for (step, direction) in [("0", "forward"), ("1m", "sideways"), ("0.001", "forward")] {
+            let params = LokiQueryParams {
+                start: Some("120".to_string()),
+                end: Some("240".to_string()),
+                step: Some(step.to_string()),
+                direction: Some(direction.to_string()),
+                ..Default::default()
+            };
+            assert!(eval_params(&params, &expr, false).is_err());
+        }
+    }
+
+    const SEC: i64 = 1_000_000_000;
+
+    #[test]
+    fn test_metric_from_batches() {
+        let LogQLExpr::Metric(metric) = LogQLExpr::parse(r#"rate({app="foo"}[2m])"#).unwrap()
+        else {
+            unreachable!()
+        };
+        let params = EvalParams {
+            start: DateTime::from_timestamp(120, 0).unwrap(),
+            end: DateTime::from_timestamp(240, 0).unwrap(),
+            step: std::time::Duration::from_secs(60),
+            limit: DEFAULT_LOG_LIMIT,
+            direction: Direction::Backward,
+        };
+        // Buckets of a minute start one nanosecond after the evaluation times.
+        let batch = batch(vec![
+            (
+                RANGE_BUCKET_COLUMN,
+                ConcreteDataType::timestamp_nanosecond_datatype(),
+                Arc::new(TimestampNanosecondVector::from_vec(vec![
+                    120 * SEC + 1,
+                    1,
+                    60 * SEC + 1,
+                ])),
+            ),
+            (
+                "app",
+                ConcreteDataType::string_datatype(),
+                Arc::new(StringVector::from(vec!["foo", "foo", "foo"])),
+            ),
+            (
+                METRIC_VALUE_COLUMN,
+                ConcreteDataType::int64_datatype(),
+                Arc::new(Int64Vector::from_vec(vec![90, 30, 60])),
+            ),
+        ]);
+        let app = BTreeMap::from([("app".to_string(), "foo".to_string())]);
+
+        let buckets = buckets_from_batches(&[batch], &metric, &params);
+        assert_eq!(
+            buckets,
+            SeriesSamples::from([(
+                app.clone(),
+                BTreeMap::from([(60 * SEC, 30.0), (120 * SEC, 60.0), (180 * SEC, 90.0)])
+            )])
+        );
+
+        let matrix = matrix_from_samples(metric.eval(&params, &buckets));
+        assert_eq!(
+            matrix,
+            vec![LokiMatrixSeries {
+                metric: app.clone(),
+                values: vec![
+                    (120.0, "0.75".to_string()),
+                    (180.0, "1.25".to_string()),
+                    (240.0, "0.75".to_string())
+                ],
+            }]
+        );
+
+        let params = EvalParams {
+            start: params.end,
+            ..params
+        };
+        assert_eq!(
+            vector_from_samples(metric.eval(&params, &buckets)),
+            vec![LokiVectorSample {
+                metric: app,
+                value: (240.0, "0.75".to_string()),
+            }]
+        );
+    }
+}
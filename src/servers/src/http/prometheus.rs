@@ -57,6 +57,7 @@ use crate::error::{
     TableNotFoundSnafu, UnexpectedResultSnafu,
 };
 use crate::http::header::collect_plan_metrics;
+use crate::prom_rule::{RuleManagerRef, RuleType};
 use crate::prom_store::{DATABASE_LABEL, FIELD_NAME_LABEL, METRIC_NAME_LABEL, SCHEMA_LABEL};
 use crate::prometheus_handler::PrometheusHandlerRef;
 
@@ -98,6 +99,79 @@ pub struct PromData {
     pub result: PromQueryResult,
 }
 
+/// Response of the `/api/v1/rules` API.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct RuleDiscovery {
+    pub groups: Vec<PromRuleGroup>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PromRuleGroup {
+    pub name: String,
+    pub file: String,
+    pub rules: Vec<PromRule>,
+    /// Evaluation interval in seconds.
+    pub interval: f64,
+    pub last_evaluation: String,
+    pub evaluation_time: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum PromRule {
+    Alerting(PromAlertingRule),
+    Recording(PromRecordingRule),
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PromAlertingRule {
+    /// `inactive`, `pending` or `firing`.
+    pub state: String,
+    pub name: String,
+    pub query: String,
+    /// The `for` duration in seconds.
+    pub duration: f64,
+    pub labels: BTreeMap<String, String>,
+    pub annotations: BTreeMap<String, String>,
+    pub alerts: Vec<PromAlert>,
+    pub health: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    pub evaluation_time: f64,
+    pub last_evaluation: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PromRecordingRule {
+    pub name: String,
+    pub query: String,
+    pub labels: BTreeMap<String, String>,
+    pub health: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    pub evaluation_time: f64,
+    pub last_evaluation: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PromAlert {
+    pub labels: BTreeMap<String, String>,
+    pub annotations: BTreeMap<String, String>,
+    pub state: String,
+    pub active_at: String,
+    pub value: String,
+}
+
+/// Response of the `/api/v1/alerts` API.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct AlertDiscovery {
+    pub alerts: Vec<PromAlert>,
+}
+
 #[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
 #[serde(untagged)]
 pub enum PrometheusResponse {
@@ -107,6 +181,8 @@ pub enum PrometheusResponse {
     LabelValues(Vec<String>),
     FormatQuery(String),
     BuildInfo(OwnedBuildInfo),
+    RuleDiscovery(RuleDiscovery),
+    AlertDiscovery(AlertDiscovery),
     #[serde(skip_deserializing)]
     ParseResult(promql_parser::parser::Expr),
     #[default]
@@ -197,6 +273,48 @@ pub async fn build_info_query() -> PrometheusJsonResponse {
     PrometheusJsonResponse::success(PrometheusResponse::BuildInfo(build_info.into()))
 }
 
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RulesQuery {
+    #[serde(rename = "type")]
+    rule_type: Option<String>,
+}
+
+#[axum_macros::debug_handler]
+#[tracing::instrument(
+    skip_all,
+    fields(protocol = "prometheus", request_type = "rules_query")
+)]
+pub async fn rules_query(
+    State(manager): State<RuleManagerRef>,
+    Query(params): Query<RulesQuery>,
+) -> PrometheusJsonResponse {
+    let rule_type = match params.rule_type.as_deref() {
+        None | Some("") => None,
+        Some("alert") => Some(RuleType::Alerting),
+        Some("record") => Some(RuleType::Recording),
+        Some(other) => {
+            return PrometheusJsonResponse::error(
+                StatusCode::InvalidArguments,
+                format!("unsupported rule type: {other}"),
+            )
+        }
+    };
+    PrometheusJsonResponse::success(PrometheusResponse::RuleDiscovery(
+        manager.rule_discovery(rule_type),
+    ))
+}
+
+#[axum_macros::debug_handler]
+#[tracing::instrument(
+    skip_all,
+    fields(protocol = "prometheus", request_type = "alerts_query")
+)]
+pub async fn alerts_query(State(manager): State<RuleManagerRef>) -> PrometheusJsonResponse {
+    PrometheusJsonResponse::success(PrometheusResponse::AlertDiscovery(
+        manager.alert_discovery(),
+    ))
+}
+
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct InstantQuery {
     query: Option<String>,
@@ -435,7 +553,7 @@ async fn do_range_query(
 }
 
 #[derive(Debug, Default, Serialize)]
-struct Matches(Vec<String>);
+pub(crate) struct Matches(pub(crate) Vec<String>);
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct LabelsQuery {
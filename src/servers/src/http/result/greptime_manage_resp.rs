@@ -55,6 +55,29 @@ impl GreptimedbManageResponse {
         }
     }
 
+    pub fn from_pipeline_versions(
+        name: String,
+        versions: Vec<String>,
+        execution_time_ms: u64,
+    ) -> Self {
+        let pipelines = versions
+            .into_iter()
+            .map(|version| PipelineOutput {
+                name: name.clone(),
+                version,
+                pipeline: None,
+            })
+            .collect();
+        Self::from_pipelines(pipelines, execution_time_ms)
+    }
+
+    pub fn from_pipeline_diff(name: String, diff: String, execution_time_ms: u64) -> Self {
+        GreptimedbManageResponse {
+            manage_result: ManageResult::PipelineDiff(PipelineDiffOutput { name, diff }),
+            execution_time_ms,
+        }
+    }
+
     pub fn with_execution_time(mut self, execution_time: u64) -> Self {
         self.execution_time_ms = execution_time;
         self
@@ -69,6 +92,7 @@ impl GreptimedbManageResponse {
 #[serde(untagged)]
 pub enum ManageResult {
     Pipelines { pipelines: Vec<PipelineOutput> },
+    PipelineDiff(PipelineDiffOutput),
     // todo(shuiyisong): refactor scripts api
     Scripts(),
 }
@@ -81,6 +105,13 @@ pub struct PipelineOutput {
     pipeline: Option<String>,
 }
 
+/// The unified diff between two versions of a pipeline.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PipelineDiffOutput {
+    name: String,
+    diff: String,
+}
+
 impl IntoResponse for GreptimedbManageResponse {
     fn into_response(self) -> axum::response::Response {
         let execution_time = self.execution_time_ms;
@@ -140,4 +171,22 @@ mod tests {
             r#"{"pipelines":[{"name":"test_name","version":"test_version"}],"execution_time_ms":42}"#
         );
     }
+
+    #[tokio::test]
+    async fn test_pipeline_diff_into_response() {
+        let resp = GreptimedbManageResponse::from_pipeline_diff(
+            "test_name".to_string(),
+            "-a\n+b\n".to_string(),
+            42,
+        );
+
+        let body_bytes = to_bytes(resp.into_response().into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body_str = String::from_utf8_lossy(body_bytes.to_byte_slice());
+        assert_eq!(
+            body_str,
+            r#"{"name":"test_name","diff":"-a\n+b\n","execution_time_ms":42}"#
+        );
+    }
 }
@@ -41,7 +41,9 @@ pub mod otel_arrow;
 pub mod otlp;
 mod pipeline;
 pub mod postgres;
+pub mod prom_histogram;
 pub mod prom_row_builder;
+pub mod prom_rule;
 pub mod prom_store;
 pub mod prometheus;
 pub mod prometheus_handler;
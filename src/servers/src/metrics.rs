@@ -191,6 +191,12 @@ lazy_static! {
             &[METRIC_DB_LABEL, METRIC_RESULT_LABEL]
         )
         .unwrap();
+    pub static ref METRIC_LOKI_QUERY_ELAPSED: HistogramVec = register_histogram_vec!(
+        "greptime_servers_loki_query_elapsed",
+        "servers loki query elapsed",
+        &[METRIC_DB_LABEL, METRIC_PATH_LABEL]
+    )
+    .unwrap();
     pub static ref METRIC_ELASTICSEARCH_LOGS_INGESTION_ELAPSED: HistogramVec =
         register_histogram_vec!(
             "greptime_servers_elasticsearch_logs_ingestion_elapsed",
@@ -21,7 +21,10 @@ use common_recordbatch::{RecordBatch, SendableRecordBatchStream};
 use common_telemetry::{debug, error};
 use datatypes::prelude::{ConcreteDataType, Value};
 use datatypes::schema::SchemaRef;
-use datatypes::types::json_type_value_to_string;
+use datatypes::types::{
+    ip_type_value_to_string, json_type_value_to_string, map_type_value_to_string,
+    uuid_type_value_to_string,
+};
 use futures::StreamExt;
 use opensrv_mysql::{
     Column, ColumnFlags, ColumnType, ErrorKind, OkResponse, QueryResultWriter, RowWriter,
@@ -217,6 +220,18 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
                                 .context(ConvertSqlValueSnafu)?;
                             row_writer.write_col(s)?;
                         }
+                        ConcreteDataType::Uuid(_) => {
+                            let s = uuid_type_value_to_string(&v).context(ConvertSqlValueSnafu)?;
+                            row_writer.write_col(s)?;
+                        }
+                        ConcreteDataType::Map(_) => {
+                            let s = map_type_value_to_string(&v).context(ConvertSqlValueSnafu)?;
+                            row_writer.write_col(s)?;
+                        }
+                        ConcreteDataType::Ip(_) => {
+                            let s = ip_type_value_to_string(&v).context(ConvertSqlValueSnafu)?;
+                            row_writer.write_col(s)?;
+                        }
                         _ => {
                             row_writer.write_col(v.deref())?;
                         }
@@ -295,6 +310,9 @@ pub(crate) fn create_mysql_column(
         ConcreteDataType::Decimal128(_) => Ok(ColumnType::MYSQL_TYPE_DECIMAL),
         ConcreteDataType::Json(_) => Ok(ColumnType::MYSQL_TYPE_JSON),
         ConcreteDataType::Vector(_) => Ok(ColumnType::MYSQL_TYPE_BLOB),
+        ConcreteDataType::Uuid(_) => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        ConcreteDataType::Map(_) => Ok(ColumnType::MYSQL_TYPE_JSON),
+        ConcreteDataType::Ip(_) => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
         _ => error::UnsupportedDataTypeSnafu {
             data_type,
             reason: "not implemented",
@@ -17,13 +17,11 @@ use std::sync::Arc;
 
 use ahash::{HashMap, HashMapExt};
 use api::greptime_proto;
-use api::v1::helper::time_index_column_schema;
-use api::v1::{ColumnDataType, RowInsertRequest, Rows};
-use common_time::timestamp::TimeUnit;
+use api::v1::{RowInsertRequest, Rows};
 use pipeline::{
     identity_pipeline, unwrap_or_continue_if_err, ContextReq, DispatchedTo, Pipeline,
-    PipelineContext, PipelineDefinition, PipelineExecOutput, SchemaInfo, TransformedOutput,
-    TransformerMode, GREPTIME_INTERNAL_IDENTITY_PIPELINE_NAME,
+    PipelineContext, PipelineDefinition, PipelineExecOutput, TransformedOutput,
+    GREPTIME_INTERNAL_IDENTITY_PIPELINE_NAME,
 };
 use session::context::{Channel, QueryContextRef};
 use snafu::ResultExt;
@@ -121,26 +119,7 @@ async fn run_custom_pipeline(
     let mut transformed_map = HashMap::new();
     let mut dispatched: BTreeMap<DispatchedTo, Vec<VrlValue>> = BTreeMap::new();
 
-    let mut schema_info = match pipeline.transformer() {
-        TransformerMode::GreptimeTransformer(greptime_transformer) => {
-            SchemaInfo::from_schema_list(greptime_transformer.schemas().clone())
-        }
-        TransformerMode::AutoTransform(ts_name, timeunit) => {
-            let timeunit = match timeunit {
-                TimeUnit::Second => ColumnDataType::TimestampSecond,
-                TimeUnit::Millisecond => ColumnDataType::TimestampMillisecond,
-                TimeUnit::Microsecond => ColumnDataType::TimestampMicrosecond,
-                TimeUnit::Nanosecond => ColumnDataType::TimestampNanosecond,
-            };
-
-            let mut schema_info = SchemaInfo::default();
-            schema_info
-                .schema
-                .push(time_index_column_schema(ts_name, timeunit));
-
-            schema_info
-        }
-    };
+    let mut schema_info = pipeline.schema_info();
 
     for pipeline_map in pipeline_maps {
         let result = pipeline
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod auth_handler;
+mod copy;
 mod fixtures;
 mod handler;
 mod server;
@@ -28,11 +29,12 @@ pub(crate) const METADATA_SCHEMA: &str = "schema";
 
 use std::collections::HashMap;
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use ::auth::UserProviderRef;
 use derive_builder::Builder;
 use pgwire::api::auth::{ServerParameterProvider, StartupHandler};
+use pgwire::api::copy::CopyHandler;
 use pgwire::api::query::{ExtendedQueryHandler, SimpleQueryHandler};
 use pgwire::api::{ClientInfo, ErrorHandler, PgWireServerHandlers};
 pub use server::PostgresServer;
@@ -40,8 +42,10 @@ use session::context::Channel;
 use session::Session;
 
 use self::auth_handler::PgLoginVerifier;
+use self::copy::CopyInState;
 use self::handler::DefaultQueryParser;
 use crate::query_handler::sql::ServerSqlQueryHandlerRef;
+use crate::query_handler::PostgresCopyHandlerRef;
 
 pub(crate) struct GreptimeDBStartupParameters {
     version: String,
@@ -72,17 +76,22 @@ impl ServerParameterProvider for GreptimeDBStartupParameters {
 
 pub struct PostgresServerHandlerInner {
     query_handler: ServerSqlQueryHandlerRef,
+    copy_handler: Option<PostgresCopyHandlerRef>,
     login_verifier: PgLoginVerifier,
     force_tls: bool,
     param_provider: Arc<GreptimeDBStartupParameters>,
 
     session: Arc<Session>,
     query_parser: Arc<DefaultQueryParser>,
+    /// The ongoing `COPY ... FROM STDIN` of the connection.
+    copy_in: Mutex<Option<CopyInState>>,
 }
 
 #[derive(Builder)]
 pub(crate) struct MakePostgresServerHandler {
     query_handler: ServerSqlQueryHandlerRef,
+    #[builder(default)]
+    copy_handler: Option<PostgresCopyHandlerRef>,
     user_provider: Option<UserProviderRef>,
     #[builder(default = "Arc::new(GreptimeDBStartupParameters::new())")]
     param_provider: Arc<GreptimeDBStartupParameters>,
@@ -107,6 +116,10 @@ impl PgWireServerHandlers for PostgresServerHandler {
     fn error_handler(&self) -> Arc<impl ErrorHandler> {
         self.0.clone()
     }
+
+    fn copy_handler(&self) -> Arc<impl CopyHandler> {
+        self.0.clone()
+    }
 }
 
 impl MakePostgresServerHandler {
@@ -119,12 +132,14 @@ impl MakePostgresServerHandler {
         ));
         let handler = PostgresServerHandlerInner {
             query_handler: self.query_handler.clone(),
+            copy_handler: self.copy_handler.clone(),
             login_verifier: PgLoginVerifier::new(self.user_provider.clone()),
             force_tls: self.force_tls,
             param_provider: self.param_provider.clone(),
 
             session: session.clone(),
             query_parser: Arc::new(DefaultQueryParser::new(self.query_handler.clone(), session)),
+            copy_in: Mutex::new(None),
         };
         PostgresServerHandler(Arc::new(handler))
     }
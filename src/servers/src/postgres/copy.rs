@@ -0,0 +1,1453 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `COPY ... FROM STDIN` and `COPY ... TO STDOUT` of the PostgreSQL wire protocol.
+//!
+//! These statements are intercepted before they reach the SQL engine: rows sent by the client
+//! in `CopyData` messages are decoded and inserted into the table in batches, and query results
+//! are streamed back to the client as `CopyData` messages.
+
+use std::fmt::{Debug, Write};
+use std::mem;
+
+use async_trait::async_trait;
+use bytes::{BufMut, Bytes, BytesMut};
+use common_query::OutputData;
+use common_recordbatch::{RecordBatchStream, SendableRecordBatchStream};
+use common_sql::convert::sql_value_to_value;
+use common_time::{Date, Timestamp, Timezone};
+use datatypes::prelude::{ConcreteDataType, Value};
+use datatypes::schema::{ColumnSchema, SchemaRef};
+use datatypes::types::json_type_value_to_string;
+use futures::{future, stream, Sink, SinkExt, StreamExt};
+use itertools::Itertools;
+use pgwire::api::copy::CopyHandler;
+use pgwire::api::results::{CopyResponse, Response, Tag};
+use pgwire::api::ClientInfo;
+use pgwire::error::{ErrorInfo, PgWireError, PgWireResult};
+use pgwire::messages::copy::{CopyData, CopyDone, CopyFail};
+use pgwire::messages::PgWireBackendMessage;
+use query::query_engine::options::validate_catalog_and_schema;
+use session::context::QueryContextRef;
+use snafu::{ensure, IntoError, OptionExt, ResultExt};
+use sqlparser::ast::{
+    CopyLegacyCsvOption, CopyLegacyOption, CopyOption, CopySource, CopyTarget, Ident,
+    ObjectNamePart, Statement, Value as SqlValue,
+};
+use sqlparser::dialect::PostgreSqlDialect;
+use sqlparser::parser::Parser;
+use table::requests::InsertRequest as TableInsertRequest;
+use table::table_name::TableName;
+
+use crate::error::{
+    ConvertSqlValueSnafu, CopyPartiallyInsertedSnafu, InvalidParameterSnafu, NotSupportedSnafu,
+    Result, TableNotFoundSnafu, ValidateTableSnafu,
+};
+use crate::postgres::types::PgErrorSeverity;
+use crate::postgres::utils::convert_err;
+use crate::postgres::PostgresServerHandlerInner;
+use crate::query_handler::PostgresCopyHandlerRef;
+
+/// Number of copied rows buffered before they are inserted.
+const COPY_BATCH_SIZE: usize = 4096;
+/// Signature at the beginning of the binary format.
+const BINARY_SIGNATURE: &[u8] = b"PGCOPY\n\xff\r\n\0";
+/// Length of the signature, the flags field and the header extension length.
+const BINARY_HEADER_LEN: usize = BINARY_SIGNATURE.len() + 8;
+/// Microseconds from the unix epoch to the PostgreSQL epoch (2000-01-01).
+const PG_EPOCH_MICROS: i64 = 946_684_800_000_000;
+/// Days from the unix epoch to the PostgreSQL epoch (2000-01-01).
+const PG_EPOCH_DAYS: i32 = 10_957;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CopyFormat {
+    Text,
+    Csv,
+    Binary,
+}
+
+impl CopyFormat {
+    /// The overall format code sent in `CopyInResponse` and `CopyOutResponse`.
+    fn code(&self) -> i8 {
+        match self {
+            CopyFormat::Text | CopyFormat::Csv => 0,
+            CopyFormat::Binary => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CopyOptions {
+    format: CopyFormat,
+    delimiter: u8,
+    null: String,
+    header: bool,
+    quote: u8,
+    escape: u8,
+}
+
+impl CopyOptions {
+    /// Creates options with the PostgreSQL defaults of the format.
+    fn new(format: CopyFormat) -> Self {
+        let (delimiter, null) = match format {
+            CopyFormat::Csv => (b',', ""),
+            CopyFormat::Text | CopyFormat::Binary => (b'\t', "\\N"),
+        };
+        Self {
+            format,
+            delimiter,
+            null: null.to_string(),
+            header: false,
+            quote: b'"',
+            escape: b'"',
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PgCopy {
+    /// `COPY <table> [(<columns>)] FROM STDIN`
+    From {
+        table_name: Vec<String>,
+        columns: Vec<String>,
+        options: CopyOptions,
+    },
+    /// `COPY {<table> [(<columns>)] | (<query>)} TO STDOUT`
+    To { query: String, options: CopyOptions },
+}
+
+/// Parses `COPY ... FROM STDIN` and `COPY ... TO STDOUT`.
+///
+/// Returns `None` if the query is not such a statement, e.g. `COPY` to or from files, which
+/// is left to the SQL engine.
+pub(crate) fn parse_copy(query: &str) -> Option<Result<PgCopy>> {
+    let is_copy = query
+        .trim_start()
+        .get(..4)
+        .is_some_and(|keyword| keyword.eq_ignore_ascii_case("copy"));
+    if !is_copy {
+        return None;
+    }
+
+    let mut statements = Parser::parse_sql(&PostgreSqlDialect {}, query).ok()?;
+    if statements.len() != 1 {
+        return None;
+    }
+    let Statement::Copy {
+        source,
+        to,
+        target,
+        options,
+        legacy_options,
+        ..
+    } = statements.remove(0)
+    else {
+        return None;
+    };
+    match (to, target) {
+        (false, CopyTarget::Stdin) | (true, CopyTarget::Stdout) => {}
+        _ => return None,
+    }
+
+    Some(build_copy(source, to, options, legacy_options))
+}
+
+fn build_copy(
+    source: CopySource,
+    to: bool,
+    options: Vec<CopyOption>,
+    legacy_options: Vec<CopyLegacyOption>,
+) -> Result<PgCopy> {
+    let options = copy_options(options, legacy_options)?;
+    match source {
+        CopySource::Table {
+            table_name,
+            columns,
+        } if !to => Ok(PgCopy::From {
+            table_name: table_name
+                .0
+                .iter()
+                .map(|ObjectNamePart::Identifier(ident)| canonicalize_ident(ident))
+                .collect(),
+            columns: columns.iter().map(canonicalize_ident).collect(),
+            options,
+        }),
+        CopySource::Table {
+            table_name,
+            columns,
+        } => {
+            let projection = if columns.is_empty() {
+                "*".to_string()
+            } else {
+                columns.iter().join(", ")
+            };
+            Ok(PgCopy::To {
+                query: format!("SELECT {projection} FROM {table_name}"),
+                options,
+            })
+        }
+        CopySource::Query(query) if to => Ok(PgCopy::To {
+            query: query.to_string(),
+            options,
+        }),
+        CopySource::Query(_) => InvalidParameterSnafu {
+            reason: "COPY FROM STDIN requires a table",
+        }
+        .fail(),
+    }
+}
+
+/// Unquoted identifiers are case-insensitive, as in the SQL parser.
+fn canonicalize_ident(ident: &Ident) -> String {
+    if ident.quote_style.is_some() {
+        ident.value.clone()
+    } else {
+        ident.value.to_lowercase()
+    }
+}
+
+fn copy_options(
+    options: Vec<CopyOption>,
+    legacy_options: Vec<CopyLegacyOption>,
+) -> Result<CopyOptions> {
+    let mut format = CopyFormat::Text;
+    for option in &options {
+        if let CopyOption::Format(ident) = option {
+            format = match ident.value.to_lowercase().as_str() {
+                "text" => CopyFormat::Text,
+                "csv" => CopyFormat::Csv,
+                "binary" => CopyFormat::Binary,
+                other => {
+                    return InvalidParameterSnafu {
+                        reason: format!("COPY format \"{other}\" not recognized"),
+                    }
+                    .fail()
+                }
+            };
+        }
+    }
+    for option in &legacy_options {
+        match option {
+            CopyLegacyOption::Binary => format = CopyFormat::Binary,
+            CopyLegacyOption::Csv(_) => format = CopyFormat::Csv,
+            _ => {}
+        }
+    }
+
+    let mut copy_options = CopyOptions::new(format);
+    for option in options {
+        match option {
+            CopyOption::Format(_) => {}
+            CopyOption::Delimiter(c) => copy_options.delimiter = single_byte_option(c)?,
+            CopyOption::Null(null) => copy_options.null = null,
+            CopyOption::Header(header) => copy_options.header = header,
+            CopyOption::Quote(c) => copy_options.quote = single_byte_option(c)?,
+            CopyOption::Escape(c) => copy_options.escape = single_byte_option(c)?,
+            other => {
+                return NotSupportedSnafu {
+                    feat: format!("COPY option {other}"),
+                }
+                .fail()
+            }
+        }
+    }
+    for option in legacy_options {
+        match option {
+            CopyLegacyOption::Binary => {}
+            CopyLegacyOption::Delimiter(c) => copy_options.delimiter = single_byte_option(c)?,
+            CopyLegacyOption::Null(null) => copy_options.null = null,
+            CopyLegacyOption::Csv(csv_options) => {
+                for csv_option in csv_options {
+                    match csv_option {
+                        CopyLegacyCsvOption::Header => copy_options.header = true,
+                        CopyLegacyCsvOption::Quote(c) => {
+                            copy_options.quote = single_byte_option(c)?
+                        }
+                        CopyLegacyCsvOption::Escape(c) => {
+                            copy_options.escape = single_byte_option(c)?
+                        }
+                        other => {
+                            return NotSupportedSnafu {
+                                feat: format!("COPY option {other}"),
+                            }
+                            .fail()
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(copy_options)
+}
+
+fn single_byte_option(c: char) -> Result<u8> {
+    u8::try_from(c)
+        .ok()
+        .filter(u8::is_ascii)
+        .with_context(|| InvalidParameterSnafu {
+            reason: format!("COPY option must be a single one-byte character, got '{c}'"),
+        })
+}
+
+/// Resolves the maybe qualified table name with the current catalog and schema.
+///
+/// Like `INSERT`, the table must be in the catalog of the session.
+fn resolve_table_name(parts: &[String], query_ctx: &QueryContextRef) -> Result<TableName> {
+    let table_name = match parts {
+        [table] => Ok(TableName::new(
+            query_ctx.current_catalog(),
+            query_ctx.current_schema(),
+            table,
+        )),
+        [schema, table] => Ok(TableName::new(query_ctx.current_catalog(), schema, table)),
+        [catalog, schema, table] => Ok(TableName::new(catalog, schema, table)),
+        _ => InvalidParameterSnafu {
+            reason: format!("invalid table name: {}", parts.join(".")),
+        }
+        .fail(),
+    }?;
+    validate_catalog_and_schema(&table_name.catalog_name, &table_name.schema_name, query_ctx)
+        .with_context(|_| ValidateTableSnafu {
+            table: table_name.to_string(),
+        })?;
+    Ok(table_name)
+}
+
+/// A field of the copied data, `None` is null.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CopyField {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+type CopyRecord = Vec<Option<CopyField>>;
+
+/// Decodes records from the chunks of `CopyData`, which may split records arbitrarily.
+struct CopyDecoder {
+    options: CopyOptions,
+    buffer: Vec<u8>,
+    /// Whether the header line or the binary header has been consumed.
+    header_done: bool,
+    /// Whether the end-of-data marker has been seen, the rest of data is ignored.
+    finished: bool,
+}
+
+impl CopyDecoder {
+    fn new(options: CopyOptions) -> Self {
+        Self {
+            options,
+            buffer: Vec::new(),
+            header_done: false,
+            finished: false,
+        }
+    }
+
+    /// Appends `data` and returns all complete records. The remaining bytes are all decoded
+    /// once `eof` is set.
+    fn decode(&mut self, data: &[u8], eof: bool) -> Result<Vec<CopyRecord>> {
+        if self.finished {
+            return Ok(vec![]);
+        }
+        self.buffer.extend_from_slice(data);
+
+        let (mut records, consumed, finished) = match self.options.format {
+            CopyFormat::Text => decode_text(&self.buffer, &self.options, eof)?,
+            CopyFormat::Csv => decode_csv(&self.buffer, &self.options, eof)?,
+            CopyFormat::Binary => {
+                let (records, consumed, finished) =
+                    decode_binary(&self.buffer, self.header_done, eof)?;
+                if consumed > 0 {
+                    self.header_done = true;
+                }
+                (records, consumed, finished)
+            }
+        };
+        self.buffer.drain(..consumed);
+        if finished {
+            self.finished = true;
+            self.buffer.clear();
+        }
+
+        if self.options.format != CopyFormat::Binary
+            && self.options.header
+            && !self.header_done
+            && !records.is_empty()
+        {
+            let _ = records.remove(0);
+            self.header_done = true;
+        }
+        Ok(records)
+    }
+}
+
+/// Decodes lines of the text format, returns the records, the consumed length and whether the
+/// end-of-data marker is seen.
+fn decode_text(
+    data: &[u8],
+    options: &CopyOptions,
+    eof: bool,
+) -> Result<(Vec<CopyRecord>, usize, bool)> {
+    let end = if eof {
+        data.len()
+    } else {
+        match data.iter().rposition(|b| *b == b'\n') {
+            Some(pos) => pos + 1,
+            None => return Ok((vec![], 0, false)),
+        }
+    };
+
+    let mut records = Vec::new();
+    for line in data[..end].split_inclusive(|b| *b == b'\n') {
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if line == b"\\." {
+            return Ok((records, end, true));
+        }
+        records.push(decode_text_line(line, options)?);
+    }
+    Ok((records, end, false))
+}
+
+fn decode_text_line(line: &[u8], options: &CopyOptions) -> Result<CopyRecord> {
+    let mut fields = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < line.len() {
+        if line[i] == b'\\' {
+            i += 2;
+            continue;
+        }
+        if line[i] == options.delimiter {
+            fields.push(decode_text_field(&line[start..i], options)?);
+            start = i + 1;
+        }
+        i += 1;
+    }
+    fields.push(decode_text_field(&line[start..], options)?);
+    Ok(fields)
+}
+
+fn decode_text_field(raw: &[u8], options: &CopyOptions) -> Result<Option<CopyField>> {
+    if raw == options.null.as_bytes() {
+        return Ok(None);
+    }
+
+    let mut field = Vec::with_capacity(raw.len());
+    let mut bytes = raw.iter().copied().peekable();
+    while let Some(b) = bytes.next() {
+        if b != b'\\' {
+            field.push(b);
+            continue;
+        }
+        match bytes.next() {
+            Some(b'b') => field.push(0x08),
+            Some(b'f') => field.push(0x0c),
+            Some(b'n') => field.push(b'\n'),
+            Some(b'r') => field.push(b'\r'),
+            Some(b't') => field.push(b'\t'),
+            Some(b'v') => field.push(0x0b),
+            Some(digit @ b'0'..=b'7') => {
+                let mut value = digit - b'0';
+                for _ in 0..2 {
+                    match bytes.peek().copied() {
+                        Some(digit @ b'0'..=b'7') => {
+                            value = value.wrapping_mul(8).wrapping_add(digit - b'0');
+                            let _ = bytes.next();
+                        }
+                        _ => break,
+                    }
+                }
+                field.push(value);
+            }
+            Some(b'x') if bytes.peek().is_some_and(u8::is_ascii_hexdigit) => {
+                let mut value = 0u8;
+                for _ in 0..2 {
+                    match bytes.peek().and_then(|b| (*b as char).to_digit(16)) {
+                        Some(digit) => {
+                            value = value * 16 + digit as u8;
+                            let _ = bytes.next();
+                        }
+                        None => break,
+                    }
+                }
+                field.push(value);
+            }
+            Some(other) => field.push(other),
+            None => field.push(b'\\'),
+        }
+    }
+    to_text(field).map(|text| Some(CopyField::Text(text)))
+}
+
+/// Decodes records of the CSV format, a record may span lines within quotes.
+fn decode_csv(
+    data: &[u8],
+    options: &CopyOptions,
+    eof: bool,
+) -> Result<(Vec<CopyRecord>, usize, bool)> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = Vec::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut consumed = 0;
+
+    let mut i = 0;
+    while i < data.len() {
+        let b = data[i];
+        if in_quotes {
+            if b == options.escape
+                && data
+                    .get(i + 1)
+                    .is_some_and(|next| *next == options.quote || *next == options.escape)
+            {
+                field.push(data[i + 1]);
+                i += 2;
+                continue;
+            }
+            if b == options.quote {
+                in_quotes = false;
+            } else {
+                field.push(b);
+            }
+            i += 1;
+            continue;
+        }
+
+        if b == options.quote {
+            in_quotes = true;
+            quoted = true;
+        } else if b == options.delimiter {
+            record.push(csv_field(mem::take(&mut field), quoted, options)?);
+            quoted = false;
+        } else if b == b'\n' || (b == b'\r' && data.get(i + 1) == Some(&b'\n')) {
+            if b == b'\r' {
+                i += 1;
+            }
+            if record.is_empty() && !quoted && field == b"\\." {
+                return Ok((records, data.len(), true));
+            }
+            record.push(csv_field(mem::take(&mut field), quoted, options)?);
+            records.push(mem::take(&mut record));
+            quoted = false;
+            consumed = i + 1;
+        } else {
+            field.push(b);
+        }
+        i += 1;
+    }
+
+    if eof && consumed < data.len() {
+        ensure!(
+            !in_quotes,
+            InvalidParameterSnafu {
+                reason: "unterminated CSV quoted field",
+            }
+        );
+        if record.is_empty() && !quoted && field == b"\\." {
+            return Ok((records, data.len(), true));
+        }
+        record.push(csv_field(field, quoted, options)?);
+        records.push(record);
+        consumed = data.len();
+    }
+    Ok((records, consumed, false))
+}
+
+/// An unquoted field matching the null string is null, a quoted one never is.
+fn csv_field(field: Vec<u8>, quoted: bool, options: &CopyOptions) -> Result<Option<CopyField>> {
+    if !quoted && field == options.null.as_bytes() {
+        return Ok(None);
+    }
+    to_text(field).map(|text| Some(CopyField::Text(text)))
+}
+
+fn to_text(field: Vec<u8>) -> Result<String> {
+    String::from_utf8(field).map_err(|e| {
+        InvalidParameterSnafu {
+            reason: format!("invalid UTF-8 in COPY data: {e}"),
+        }
+        .build()
+    })
+}
+
+/// Decodes tuples of the binary format, returns the tuples, the consumed length and whether
+/// the trailer is seen.
+fn decode_binary(
+    data: &[u8],
+    header_done: bool,
+    eof: bool,
+) -> Result<(Vec<CopyRecord>, usize, bool)> {
+    let mut pos = 0;
+    if !header_done {
+        if data.len() < BINARY_HEADER_LEN {
+            ensure!(
+                !eof,
+                InvalidParameterSnafu {
+                    reason: "COPY file signature not recognized",
+                }
+            );
+            return Ok((vec![], 0, false));
+        }
+        ensure!(
+            data.starts_with(BINARY_SIGNATURE),
+            InvalidParameterSnafu {
+                reason: "COPY file signature not recognized",
+            }
+        );
+        let extension_len = read_i32(data, BINARY_SIGNATURE.len() + 4).unwrap_or_default();
+        ensure!(
+            extension_len >= 0,
+            InvalidParameterSnafu {
+                reason: "invalid COPY file header",
+            }
+        );
+        pos = BINARY_HEADER_LEN + extension_len as usize;
+        if data.len() < pos {
+            return Ok((vec![], 0, false));
+        }
+    }
+
+    let mut records = Vec::new();
+    'tuples: while let Some(field_count) = read_i16(data, pos) {
+        if field_count == -1 {
+            return Ok((records, data.len(), true));
+        }
+        ensure!(
+            field_count >= 0,
+            InvalidParameterSnafu {
+                reason: format!("invalid field count {field_count} in COPY data"),
+            }
+        );
+
+        let mut cursor = pos + 2;
+        let mut record = Vec::with_capacity(field_count as usize);
+        for _ in 0..field_count {
+            let Some(len) = read_i32(data, cursor) else {
+                break 'tuples;
+            };
+            cursor += 4;
+            if len < 0 {
+                record.push(None);
+                continue;
+            }
+            let end = cursor + len as usize;
+            if data.len() < end {
+                break 'tuples;
+            }
+            record.push(Some(CopyField::Binary(data[cursor..end].to_vec())));
+            cursor = end;
+        }
+        records.push(record);
+        pos = cursor;
+    }
+
+    ensure!(
+        !eof || pos == data.len(),
+        InvalidParameterSnafu {
+            reason: "unexpected EOF in COPY data",
+        }
+    );
+    Ok((records, pos, false))
+}
+
+fn read_i16(data: &[u8], pos: usize) -> Option<i16> {
+    let bytes = data.get(pos..pos + 2)?;
+    Some(i16::from_be_bytes(bytes.try_into().ok()?))
+}
+
+fn read_i32(data: &[u8], pos: usize) -> Option<i32> {
+    let bytes = data.get(pos..pos + 4)?;
+    Some(i32::from_be_bytes(bytes.try_into().ok()?))
+}
+
+fn field_to_value(
+    column: &ColumnSchema,
+    field: Option<CopyField>,
+    timezone: &Timezone,
+) -> Result<Value> {
+    match field {
+        None => Ok(Value::Null),
+        Some(CopyField::Text(text)) => text_to_value(column, text, timezone),
+        Some(CopyField::Binary(bytes)) => binary_to_value(column, &bytes, timezone),
+    }
+}
+
+fn text_to_value(column: &ColumnSchema, text: String, timezone: &Timezone) -> Result<Value> {
+    let sql_value = match &column.data_type {
+        ConcreteDataType::Boolean(_) => {
+            return parse_bool(&text)
+                .map(Value::Boolean)
+                .with_context(|| InvalidParameterSnafu {
+                    reason: format!(
+                        "invalid input syntax for type boolean of column {}: \"{text}\"",
+                        column.name
+                    ),
+                });
+        }
+        ConcreteDataType::Binary(_) => match text.strip_prefix("\\x") {
+            Some(hex) => SqlValue::HexStringLiteral(hex.to_string()),
+            None => SqlValue::SingleQuotedString(text),
+        },
+        _ => SqlValue::SingleQuotedString(text),
+    };
+    sql_value_to_value(
+        &column.name,
+        &column.data_type,
+        &sql_value,
+        Some(timezone),
+        None,
+        true,
+    )
+    .map_err(|e| {
+        InvalidParameterSnafu {
+            reason: e.to_string(),
+        }
+        .build()
+    })
+}
+
+fn parse_bool(text: &str) -> Option<bool> {
+    match text.trim().to_lowercase().as_str() {
+        "t" | "true" | "y" | "yes" | "on" | "1" => Some(true),
+        "f" | "false" | "n" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+fn binary_to_value(column: &ColumnSchema, bytes: &[u8], timezone: &Timezone) -> Result<Value> {
+    let invalid = || {
+        InvalidParameterSnafu {
+            reason: format!(
+                "invalid binary data of {} bytes for column {} of type {}",
+                bytes.len(),
+                column.name,
+                column.data_type
+            ),
+        }
+        .build()
+    };
+    let int = || -> Result<i64> {
+        match bytes.len() {
+            1 => Ok(bytes[0] as i8 as i64),
+            2 => Ok(i16::from_be_bytes([bytes[0], bytes[1]]) as i64),
+            4 => Ok(i32::from_be_bytes(bytes.try_into().map_err(|_| invalid())?) as i64),
+            8 => Ok(i64::from_be_bytes(bytes.try_into().map_err(|_| invalid())?)),
+            _ => Err(invalid()),
+        }
+    };
+
+    let value = match &column.data_type {
+        ConcreteDataType::Boolean(_) => match bytes {
+            [b] => Value::Boolean(*b != 0),
+            _ => return Err(invalid()),
+        },
+        ConcreteDataType::Int8(_) => Value::Int8(int()?.try_into().map_err(|_| invalid())?),
+        ConcreteDataType::Int16(_) => Value::Int16(int()?.try_into().map_err(|_| invalid())?),
+        ConcreteDataType::Int32(_) => Value::Int32(int()?.try_into().map_err(|_| invalid())?),
+        ConcreteDataType::Int64(_) => Value::Int64(int()?),
+        ConcreteDataType::UInt8(_) => Value::UInt8(int()?.try_into().map_err(|_| invalid())?),
+        ConcreteDataType::UInt16(_) => Value::UInt16(int()?.try_into().map_err(|_| invalid())?),
+        ConcreteDataType::UInt32(_) => Value::UInt32(int()?.try_into().map_err(|_| invalid())?),
+        ConcreteDataType::UInt64(_) => Value::UInt64(int()?.try_into().map_err(|_| invalid())?),
+        ConcreteDataType::Float32(_) => match bytes.try_into() {
+            Ok(bytes) => Value::from(f32::from_be_bytes(bytes)),
+            Err(_) => return Err(invalid()),
+        },
+        ConcreteDataType::Float64(_) => match bytes.len() {
+            4 => Value::from(f32::from_be_bytes(bytes.try_into().map_err(|_| invalid())?) as f64),
+            8 => Value::from(f64::from_be_bytes(bytes.try_into().map_err(|_| invalid())?)),
+            _ => return Err(invalid()),
+        },
+        ConcreteDataType::String(_) => Value::from(to_text(bytes.to_vec())?),
+        ConcreteDataType::Binary(_) => Value::from(bytes),
+        ConcreteDataType::Json(_) => {
+            // jsonb is prefixed by its version number.
+            let json = bytes.strip_prefix(&[1]).unwrap_or(bytes);
+            return text_to_value(column, to_text(json.to_vec())?, timezone);
+        }
+        ConcreteDataType::Timestamp(t) => {
+            let micros = int()?.checked_add(PG_EPOCH_MICROS).ok_or_else(invalid)?;
+            let timestamp = Timestamp::new_microsecond(micros)
+                .convert_to(t.unit())
+                .ok_or_else(invalid)?;
+            Value::Timestamp(timestamp)
+        }
+        ConcreteDataType::Date(_) => {
+            let days = i32::try_from(int()?).map_err(|_| invalid())?;
+            Value::Date(Date::new(
+                days.checked_add(PG_EPOCH_DAYS).ok_or_else(invalid)?,
+            ))
+        }
+        other => {
+            return NotSupportedSnafu {
+                feat: format!("binary COPY of type {other}"),
+            }
+            .fail()
+        }
+    };
+    Ok(value)
+}
+
+/// State of an ongoing `COPY ... FROM STDIN`.
+pub(crate) struct CopyInState {
+    table_name: TableName,
+    columns: Vec<ColumnSchema>,
+    decoder: CopyDecoder,
+    query_ctx: QueryContextRef,
+    rows: Vec<Vec<Value>>,
+    copied: usize,
+    /// The first error, reported when the client finishes copying.
+    error: Option<crate::error::Error>,
+}
+
+impl CopyInState {
+    /// Decodes `data` into rows and inserts them once a batch is full, or all the rest on `eof`.
+    async fn copy(
+        &mut self,
+        handler: &PostgresCopyHandlerRef,
+        data: &[u8],
+        eof: bool,
+    ) -> Result<()> {
+        let timezone = self.query_ctx.timezone();
+        for record in self.decoder.decode(data, eof)? {
+            ensure!(
+                record.len() == self.columns.len(),
+                InvalidParameterSnafu {
+                    reason: format!(
+                        "expected {} columns in COPY data, got {}",
+                        self.columns.len(),
+                        record.len()
+                    ),
+                }
+            );
+            let row = record
+                .into_iter()
+                .zip(&self.columns)
+                .map(|(field, column)| field_to_value(column, field, &timezone))
+                .collect::<Result<Vec<_>>>()?;
+            self.rows.push(row);
+            if self.rows.len() >= COPY_BATCH_SIZE {
+                self.flush(handler).await?;
+            }
+        }
+        if eof && !self.rows.is_empty() {
+            self.flush(handler).await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self, handler: &PostgresCopyHandlerRef) -> Result<()> {
+        let rows = mem::take(&mut self.rows);
+        let mut builders = self
+            .columns
+            .iter()
+            .map(|column| column.data_type.create_mutable_vector(rows.len()))
+            .collect::<Vec<_>>();
+        for row in &rows {
+            for (builder, value) in builders.iter_mut().zip(row) {
+                builder
+                    .try_push_value_ref(value.as_value_ref())
+                    .context(ConvertSqlValueSnafu)?;
+            }
+        }
+        let columns_values = self
+            .columns
+            .iter()
+            .zip(builders.iter_mut())
+            .map(|(column, builder)| (column.name.clone(), builder.to_vector()))
+            .collect();
+
+        let request = TableInsertRequest {
+            catalog_name: self.table_name.catalog_name.clone(),
+            schema_name: self.table_name.schema_name.clone(),
+            table_name: self.table_name.table_name.clone(),
+            columns_values,
+        };
+        self.copied += handler.insert(request, self.query_ctx.clone()).await?;
+        Ok(())
+    }
+
+    /// Batches are committed as they fill up, so a failure after some of them
+    /// reports how many rows are already in the table.
+    fn into_error(self, error: crate::error::Error) -> crate::error::Error {
+        if self.copied == 0 {
+            error
+        } else {
+            CopyPartiallyInsertedSnafu { rows: self.copied }.into_error(error)
+        }
+    }
+}
+
+impl PostgresServerHandlerInner {
+    pub(crate) async fn do_copy<'a>(
+        &self,
+        copy: PgCopy,
+        query_ctx: QueryContextRef,
+    ) -> Result<Response<'a>> {
+        match copy {
+            PgCopy::From {
+                table_name,
+                columns,
+                options,
+            } => {
+                self.copy_from(&table_name, &columns, options, query_ctx)
+                    .await
+            }
+            PgCopy::To { query, options } => self.copy_to(&query, options, query_ctx).await,
+        }
+    }
+
+    async fn copy_from<'a>(
+        &self,
+        table_name: &[String],
+        columns: &[String],
+        options: CopyOptions,
+        query_ctx: QueryContextRef,
+    ) -> Result<Response<'a>> {
+        let handler = self.copy_handler.as_ref().context(NotSupportedSnafu {
+            feat: "COPY FROM STDIN",
+        })?;
+        let table_name = resolve_table_name(table_name, &query_ctx)?;
+        let table = handler
+            .get_table(&table_name, query_ctx.clone())
+            .await?
+            .with_context(|| TableNotFoundSnafu {
+                catalog: &table_name.catalog_name,
+                schema: &table_name.schema_name,
+                table: &table_name.table_name,
+            })?;
+
+        let schema = table.schema();
+        let columns = if columns.is_empty() {
+            schema.column_schemas().to_vec()
+        } else {
+            columns
+                .iter()
+                .map(|column| {
+                    schema
+                        .column_schema_by_name(column)
+                        .cloned()
+                        .with_context(|| InvalidParameterSnafu {
+                            reason: format!(
+                                "column \"{column}\" of relation \"{}\" does not exist",
+                                table_name.table_name
+                            ),
+                        })
+                })
+                .collect::<Result<Vec<_>>>()?
+        };
+
+        let format = options.format.code();
+        let column_count = columns.len();
+        *self.copy_in.lock().unwrap() = Some(CopyInState {
+            table_name,
+            columns,
+            decoder: CopyDecoder::new(options),
+            query_ctx,
+            rows: Vec::new(),
+            copied: 0,
+            error: None,
+        });
+        Ok(Response::CopyIn(CopyResponse::new(
+            format,
+            column_count,
+            stream::empty(),
+        )))
+    }
+
+    async fn copy_to<'a>(
+        &self,
+        query: &str,
+        options: CopyOptions,
+        query_ctx: QueryContextRef,
+    ) -> Result<Response<'a>> {
+        ensure!(
+            options.format != CopyFormat::Binary,
+            NotSupportedSnafu {
+                feat: "COPY TO STDOUT in binary format",
+            }
+        );
+
+        let mut outputs = self.query_handler.do_query(query, query_ctx.clone()).await;
+        ensure!(
+            outputs.len() == 1,
+            InvalidParameterSnafu {
+                reason: "COPY TO STDOUT requires a single query",
+            }
+        );
+        let stream: SendableRecordBatchStream = match outputs.remove(0)?.data {
+            OutputData::Stream(stream) => stream,
+            OutputData::RecordBatches(recordbatches) => recordbatches.as_stream(),
+            OutputData::AffectedRows(_) => {
+                return InvalidParameterSnafu {
+                    reason: "COPY TO STDOUT requires a query returning rows",
+                }
+                .fail()
+            }
+        };
+
+        let schema = stream.schema();
+        let column_count = schema.num_columns();
+        let header = (options.format == CopyFormat::Csv && options.header).then(|| {
+            let mut buf = BytesMut::new();
+            let names = schema
+                .column_schemas()
+                .iter()
+                .map(|column| Some(column.name.clone()))
+                .collect::<Vec<_>>();
+            encode_csv_row(&names, &options, &mut buf);
+            Ok::<_, PgWireError>(buf.freeze())
+        });
+
+        let data = stream::iter(header)
+            .chain(stream.map(move |batch| {
+                let batch = batch.map_err(convert_err)?;
+                let mut buf = BytesMut::new();
+                for row in batch.rows() {
+                    encode_row(&query_ctx, &row, &schema, &options, &mut buf)
+                        .map_err(convert_err)?;
+                }
+                Ok(buf.freeze())
+            }))
+            .filter(|chunk: &PgWireResult<Bytes>| {
+                future::ready(!matches!(chunk, Ok(chunk) if chunk.is_empty()))
+            })
+            .map(|chunk| chunk.map(CopyData::new));
+
+        Ok(Response::CopyOut(CopyResponse::new(
+            CopyFormat::Text.code(),
+            column_count,
+            data,
+        )))
+    }
+}
+
+fn encode_row(
+    query_ctx: &QueryContextRef,
+    row: &[Value],
+    schema: &SchemaRef,
+    options: &CopyOptions,
+    buf: &mut BytesMut,
+) -> Result<()> {
+    let fields = row
+        .iter()
+        .zip(schema.column_schemas())
+        .map(|(value, column)| value_to_text(query_ctx, value, &column.data_type))
+        .collect::<Result<Vec<_>>>()?;
+    match options.format {
+        CopyFormat::Csv => encode_csv_row(&fields, options, buf),
+        CopyFormat::Text | CopyFormat::Binary => encode_text_row(&fields, options, buf),
+    }
+    Ok(())
+}
+
+fn value_to_text(
+    query_ctx: &QueryContextRef,
+    value: &Value,
+    data_type: &ConcreteDataType,
+) -> Result<Option<String>> {
+    let text = match value {
+        Value::Null => return Ok(None),
+        Value::Boolean(b) => if *b { "t" } else { "f" }.to_string(),
+        Value::String(s) => s.as_utf8().to_string(),
+        Value::Binary(bytes) => match data_type {
+            ConcreteDataType::Json(json) => {
+                json_type_value_to_string(bytes, &json.format).context(ConvertSqlValueSnafu)?
+            }
+            _ => {
+                let mut hex = String::with_capacity(2 + bytes.len() * 2);
+                hex.push_str("\\x");
+                for b in bytes.iter() {
+                    let _ = write!(hex, "{b:02x}");
+                }
+                hex
+            }
+        },
+        Value::Date(date) => date
+            .to_chrono_date()
+            .map(|date| date.to_string())
+            .unwrap_or_else(|| value.to_string()),
+        Value::Timestamp(ts) => ts
+            .to_chrono_datetime_with_timezone(Some(&query_ctx.timezone()))
+            .map(|datetime| datetime.to_string())
+            .unwrap_or_else(|| value.to_string()),
+        other => other.to_string(),
+    };
+    Ok(Some(text))
+}
+
+fn encode_text_row(fields: &[Option<String>], options: &CopyOptions, buf: &mut BytesMut) {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            buf.put_u8(options.delimiter);
+        }
+        let Some(field) = field else {
+            buf.put_slice(options.null.as_bytes());
+            continue;
+        };
+        for b in field.bytes() {
+            match b {
+                b'\\' => buf.put_slice(b"\\\\"),
+                b'\n' => buf.put_slice(b"\\n"),
+                b'\r' => buf.put_slice(b"\\r"),
+                b'\t' => buf.put_slice(b"\\t"),
+                b if b == options.delimiter => {
+                    buf.put_u8(b'\\');
+                    buf.put_u8(b);
+                }
+                b => buf.put_u8(b),
+            }
+        }
+    }
+    buf.put_u8(b'\n');
+}
+
+fn encode_csv_row(fields: &[Option<String>], options: &CopyOptions, buf: &mut BytesMut) {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            buf.put_u8(options.delimiter);
+        }
+        let Some(field) = field else {
+            buf.put_slice(options.null.as_bytes());
+            continue;
+        };
+        let needs_quote = field == &options.null
+            || field == "\\."
+            || field
+                .bytes()
+                .any(|b| b == options.delimiter || b == options.quote || b == b'\n' || b == b'\r');
+        if !needs_quote {
+            buf.put_slice(field.as_bytes());
+            continue;
+        }
+        buf.put_u8(options.quote);
+        for b in field.bytes() {
+            if b == options.quote || b == options.escape {
+                buf.put_u8(options.escape);
+            }
+            buf.put_u8(b);
+        }
+        buf.put_u8(options.quote);
+    }
+    buf.put_u8(b'\n');
+}
+
+#[async_trait]
+impl CopyHandler for PostgresServerHandlerInner {
+    async fn on_copy_data<C>(&self, _client: &mut C, copy_data: CopyData) -> PgWireResult<()>
+    where
+        C: ClientInfo + Sink<PgWireBackendMessage> + Unpin + Send + Sync,
+        C::Error: Debug,
+        PgWireError: From<<C as Sink<PgWireBackendMessage>>::Error>,
+    {
+        let state = self.copy_in.lock().unwrap().take();
+        let (Some(handler), Some(mut state)) = (&self.copy_handler, state) else {
+            return Ok(());
+        };
+        // Keeps consuming the data after an error, the error is reported on `CopyDone`.
+        if state.error.is_none() {
+            if let Err(e) = state.copy(handler, &copy_data.data, false).await {
+                state.error = Some(e);
+            }
+        }
+        *self.copy_in.lock().unwrap() = Some(state);
+        Ok(())
+    }
+
+    async fn on_copy_done<C>(&self, client: &mut C, _done: CopyDone) -> PgWireResult<()>
+    where
+        C: ClientInfo + Sink<PgWireBackendMessage> + Unpin + Send + Sync,
+        C::Error: Debug,
+        PgWireError: From<<C as Sink<PgWireBackendMessage>>::Error>,
+    {
+        let state = self.copy_in.lock().unwrap().take();
+        let (Some(handler), Some(mut state)) = (&self.copy_handler, state) else {
+            return Ok(());
+        };
+        if let Some(e) = state.error.take() {
+            return Err(convert_err(state.into_error(e)));
+        }
+        if let Err(e) = state.copy(handler, &[], true).await {
+            return Err(convert_err(state.into_error(e)));
+        }
+
+        client
+            .send(PgWireBackendMessage::CommandComplete(
+                Tag::new("COPY").with_rows(state.copied).into(),
+            ))
+            .await?;
+        Ok(())
+    }
+
+    async fn on_copy_fail<C>(&self, _client: &mut C, fail: CopyFail) -> PgWireError
+    where
+        C: ClientInfo + Sink<PgWireBackendMessage> + Unpin + Send + Sync,
+        C::Error: Debug,
+        PgWireError: From<<C as Sink<PgWireBackendMessage>>::Error>,
+    {
+        let _ = self.copy_in.lock().unwrap().take();
+        PgWireError::UserError(Box::new(ErrorInfo::new(
+            PgErrorSeverity::Error.to_string(),
+            // query_canceled
+            "57014".to_string(),
+            format!("COPY from stdin failed: {}", fail.message),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use session::context::QueryContext;
+
+    use super::*;
+
+    fn copy_from(table_name: &[&str], columns: &[&str], options: CopyOptions) -> PgCopy {
+        PgCopy::From {
+            table_name: table_name.iter().map(|s| s.to_string()).collect(),
+            columns: columns.iter().map(|s| s.to_string()).collect(),
+            options,
+        }
+    }
+
+    fn text(s: &str) -> Option<CopyField> {
+        Some(CopyField::Text(s.to_string()))
+    }
+
+    #[test]
+    fn test_parse_copy() {
+        assert!(parse_copy("SELECT 1").is_none());
+        assert!(parse_copy("COPY t TO '/tmp/t.csv' WITH (FORMAT csv)").is_none());
+        assert!(parse_copy("COPY DATABASE public TO '/tmp/public'").is_none());
+
+        let copy = parse_copy("COPY Foo.\"Bar\" (A, \"b\") FROM STDIN")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            copy_from(
+                &["foo", "Bar"],
+                &["a", "b"],
+                CopyOptions::new(CopyFormat::Text)
+            ),
+            copy
+        );
+
+        let copy = parse_copy("copy t from stdin (format csv, header true, delimiter ';')")
+            .unwrap()
+            .unwrap();
+        let mut options = CopyOptions::new(CopyFormat::Csv);
+        options.header = true;
+        options.delimiter = b';';
+        assert_eq!(copy_from(&["t"], &[], options), copy);
+
+        let copy = parse_copy("COPY t FROM STDIN WITH BINARY")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            copy_from(&["t"], &[], CopyOptions::new(CopyFormat::Binary)),
+            copy
+        );
+
+        let copy = parse_copy("COPY t (a, b) TO STDOUT (FORMAT csv)")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            PgCopy::To {
+                query: "SELECT a, b FROM t".to_string(),
+                options: CopyOptions::new(CopyFormat::Csv),
+            },
+            copy
+        );
+
+        let copy = parse_copy("COPY (SELECT * FROM t WHERE a > 1) TO STDOUT")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            PgCopy::To {
+                query: "SELECT * FROM t WHERE a > 1".to_string(),
+                options: CopyOptions::new(CopyFormat::Text),
+            },
+            copy
+        );
+
+        assert!(parse_copy("COPY t FROM STDIN (FORMAT parquet)")
+            .unwrap()
+            .is_err());
+    }
+
+    #[test]
+    fn test_resolve_table_name() {
+        let query_ctx = Arc::new(QueryContext::with("greptime", "public"));
+        let parts = |parts: &[&str]| parts.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            TableName::new("greptime", "public", "t"),
+            resolve_table_name(&parts(&["t"]), &query_ctx).unwrap()
+        );
+        assert_eq!(
+            TableName::new("greptime", "s", "t"),
+            resolve_table_name(&parts(&["s", "t"]), &query_ctx).unwrap()
+        );
+        assert_eq!(
+            TableName::new("greptime", "s", "t"),
+            resolve_table_name(&parts(&["greptime", "s", "t"]), &query_ctx).unwrap()
+        );
+        assert!(resolve_table_name(&parts(&["other", "s", "t"]), &query_ctx).is_err());
+        assert!(resolve_table_name(&parts(&["a", "b", "c", "d"]), &query_ctx).is_err());
+    }
+
+    #[test]
+    fn test_decode_text() {
+        let mut decoder = CopyDecoder::new(CopyOptions::new(CopyFormat::Text));
+        assert!(decoder.decode(b"1\ta\\tb\t\\N\n2\t", false).unwrap().len() == 1);
+        let records = decoder.decode(b"x\\\\y\t\\101\n\\.\n3\tz\n", true).unwrap();
+        assert_eq!(vec![vec![text("2"), text("x\\y"), text("A")]], records);
+        assert!(decoder.decode(b"4\tw\n", true).unwrap().is_empty());
+
+        let mut decoder = CopyDecoder::new(CopyOptions::new(CopyFormat::Text));
+        let records = decoder.decode(b"1\ta\\tb\t\\N", true).unwrap();
+        assert_eq!(vec![vec![text("1"), text("a\tb"), None]], records);
+    }
+
+    #[test]
+    fn test_decode_csv() {
+        let mut options = CopyOptions::new(CopyFormat::Csv);
+        options.header = true;
+        let mut decoder = CopyDecoder::new(options);
+        assert!(decoder.decode(b"a,b,c\n1,\"x", false).unwrap().is_empty());
+        let records = decoder
+            .decode(b",\ny\"\"\",\r\n2,\"\",z\n3,", false)
+            .unwrap();
+        assert_eq!(
+            vec![
+                vec![text("1"), text("x,\ny\""), None],
+                vec![text("2"), text(""), text("z")],
+            ],
+            records
+        );
+        let records = decoder.decode(b"\"\\N\",", true).unwrap();
+        assert_eq!(vec![vec![text("3"), text("\\N"), None]], records);
+
+        let mut decoder = CopyDecoder::new(CopyOptions::new(CopyFormat::Csv));
+        assert!(decoder.decode(b"1,\"x\n", true).is_err());
+    }
+
+    #[test]
+    fn test_decode_binary() {
+        let mut data = BINARY_SIGNATURE.to_vec();
+        data.extend_from_slice(&0i32.to_be_bytes());
+        data.extend_from_slice(&0i32.to_be_bytes());
+        data.extend_from_slice(&2i16.to_be_bytes());
+        data.extend_from_slice(&4i32.to_be_bytes());
+        data.extend_from_slice(&42i32.to_be_bytes());
+        data.extend_from_slice(&(-1i32).to_be_bytes());
+        data.extend_from_slice(&(-1i16).to_be_bytes());
+
+        let mut decoder = CopyDecoder::new(CopyOptions::new(CopyFormat::Binary));
+        let (head, tail) = data.split_at(25);
+        assert!(decoder.decode(head, false).unwrap().is_empty());
+        let records = decoder.decode(tail, false).unwrap();
+        assert_eq!(
+            vec![vec![
+                Some(CopyField::Binary(42i32.to_be_bytes().to_vec())),
+                None
+            ]],
+            records
+        );
+        assert!(decoder.decode(&[], true).unwrap().is_empty());
+
+        let mut decoder = CopyDecoder::new(CopyOptions::new(CopyFormat::Binary));
+        assert!(decoder.decode(b"PGCOPY\nnot a header", true).is_err());
+    }
+
+    #[test]
+    fn test_field_to_value() {
+        let timezone = Timezone::from_tz_string("UTC").unwrap();
+        let column = |data_type| ColumnSchema::new("c", data_type, true);
+
+        let cases = [
+            (
+                ConcreteDataType::int32_datatype(),
+                text("42"),
+                Value::Int32(42),
+            ),
+            (
+                ConcreteDataType::boolean_datatype(),
+                text("t"),
+                Value::Boolean(true),
+            ),
+            (
+                ConcreteDataType::binary_datatype(),
+                text("\\x0102"),
+                Value::from(vec![1u8, 2]),
+            ),
+            (
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                text("2024-01-01 00:00:00"),
+                Value::Timestamp(Timestamp::new_millisecond(1_704_067_200_000)),
+            ),
+            (
+                ConcreteDataType::int64_datatype(),
+                Some(CopyField::Binary(7i32.to_be_bytes().to_vec())),
+                Value::Int64(7),
+            ),
+            (
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                Some(CopyField::Binary(0i64.to_be_bytes().to_vec())),
+                Value::Timestamp(Timestamp::new_millisecond(946_684_800_000)),
+            ),
+            (
+                ConcreteDataType::date_datatype(),
+                Some(CopyField::Binary(1i32.to_be_bytes().to_vec())),
+                Value::Date(Date::new(10_958)),
+            ),
+            (ConcreteDataType::string_datatype(), None, Value::Null),
+        ];
+        for (data_type, field, expected) in cases {
+            assert_eq!(
+                expected,
+                field_to_value(&column(data_type), field, &timezone).unwrap()
+            );
+        }
+
+        assert!(field_to_value(
+            &column(ConcreteDataType::boolean_datatype()),
+            text("maybe"),
+            &timezone
+        )
+        .is_err());
+        assert!(field_to_value(
+            &column(ConcreteDataType::int8_datatype()),
+            Some(CopyField::Binary(1000i16.to_be_bytes().to_vec())),
+            &timezone
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_encode_rows() {
+        let fields = vec![Some("a\tb\\".to_string()), None, Some(String::new())];
+
+        let mut buf = BytesMut::new();
+        encode_text_row(&fields, &CopyOptions::new(CopyFormat::Text), &mut buf);
+        assert_eq!(&b"a\\tb\\\\\t\\N\t\n"[..], &buf[..]);
+
+        let fields = vec![Some("a,\"b\"".to_string()), None, Some(String::new())];
+        let mut buf = BytesMut::new();
+        encode_csv_row(&fields, &CopyOptions::new(CopyFormat::Csv), &mut buf);
+        assert_eq!(&b"\"a,\"\"b\"\"\",,\"\"\n"[..], &buf[..]);
+    }
+}
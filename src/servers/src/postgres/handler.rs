@@ -43,7 +43,7 @@ use sql::parser::{ParseOptions, ParserContext};
 use crate::error::{DataFusionSnafu, Result};
 use crate::postgres::types::*;
 use crate::postgres::utils::convert_err;
-use crate::postgres::{fixtures, PostgresServerHandlerInner};
+use crate::postgres::{copy, fixtures, PostgresServerHandlerInner};
 use crate::query_handler::sql::ServerSqlQueryHandlerRef;
 use crate::SqlPlan;
 
@@ -70,6 +70,16 @@ impl SimpleQueryHandler for PostgresServerHandlerInner {
         let query = fixtures::rewrite_sql(query);
         let query = query.as_ref();
 
+        if let Some(copy) = copy::parse_copy(query) {
+            let copy = copy.map_err(convert_err)?;
+            let resp = self
+                .do_copy(copy, query_ctx.clone())
+                .await
+                .map_err(convert_err)?;
+            send_warning_opt(client, query_ctx).await?;
+            return Ok(vec![resp]);
+        }
+
         if let Some(resps) = fixtures::process(query, query_ctx.clone()) {
             send_warning_opt(client, query_ctx).await?;
             Ok(resps)
@@ -29,6 +29,7 @@ use tokio_rustls::TlsAcceptor;
 use crate::error::Result;
 use crate::postgres::{MakePostgresServerHandler, MakePostgresServerHandlerBuilder};
 use crate::query_handler::sql::ServerSqlQueryHandlerRef;
+use crate::query_handler::PostgresCopyHandlerRef;
 use crate::server::{AbortableStream, BaseTcpServer, Server};
 use crate::tls::ReloadableTlsServerConfig;
 
@@ -45,6 +46,7 @@ impl PostgresServer {
     /// Creates a new Postgres server with provided query_handler and async runtime
     pub fn new(
         query_handler: ServerSqlQueryHandlerRef,
+        copy_handler: Option<PostgresCopyHandlerRef>,
         force_tls: bool,
         tls_server_config: Arc<ReloadableTlsServerConfig>,
         keep_alive_secs: u64,
@@ -55,6 +57,7 @@ impl PostgresServer {
         let make_handler = Arc::new(
             MakePostgresServerHandlerBuilder::default()
                 .query_handler(query_handler.clone())
+                .copy_handler(copy_handler)
                 .user_provider(user_provider.clone())
                 .force_tls(force_tls)
                 .build()
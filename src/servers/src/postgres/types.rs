@@ -15,7 +15,9 @@
 mod bytea;
 mod datetime;
 mod error;
+mod inet;
 mod interval;
+mod uuid;
 
 use std::collections::HashMap;
 use std::ops::Deref;
@@ -27,7 +29,9 @@ use datafusion_expr::LogicalPlan;
 use datatypes::arrow::datatypes::DataType as ArrowDataType;
 use datatypes::prelude::{ConcreteDataType, Value};
 use datatypes::schema::Schema;
-use datatypes::types::{json_type_value_to_string, IntervalType, TimestampType};
+use datatypes::types::{
+    json_type_value_to_string, map_type_value_to_string, IntervalType, TimestampType,
+};
 use datatypes::value::ListValue;
 use pgwire::api::portal::{Format, Portal};
 use pgwire::api::results::{DataRowEncoder, FieldInfo};
@@ -40,7 +44,9 @@ use snafu::ResultExt;
 use self::bytea::{EscapeOutputBytea, HexOutputBytea};
 use self::datetime::{StylingDate, StylingDateTime};
 pub use self::error::{PgErrorCode, PgErrorSeverity};
+use self::inet::PgInet;
 use self::interval::PgInterval;
+use self::uuid::PgUuid;
 use crate::error::{self as server_error, DataFusionSnafu, Error, Result};
 use crate::postgres::utils::convert_err;
 use crate::SqlPlan;
@@ -343,6 +349,34 @@ fn encode_array(
                 .collect::<PgWireResult<Vec<Option<String>>>>()?;
             builder.encode_field(&array)
         }
+        &ConcreteDataType::Uuid(_) => {
+            let array = value_list
+                .items()
+                .iter()
+                .map(|v| match v {
+                    Value::Null => Ok(None),
+                    Value::Binary(v) => PgUuid::try_from_slice(v).map(Some).map_err(convert_err),
+                    _ => Err(convert_err(Error::Internal {
+                        err_msg: format!("Invalid list item type, find {v:?}, expected uuid",),
+                    })),
+                })
+                .collect::<PgWireResult<Vec<Option<PgUuid>>>>()?;
+            builder.encode_field(&array)
+        }
+        &ConcreteDataType::Ip(_) => {
+            let array = value_list
+                .items()
+                .iter()
+                .map(|v| match v {
+                    Value::Null => Ok(None),
+                    Value::Binary(v) => PgInet::try_from_slice(v).map(Some).map_err(convert_err),
+                    _ => Err(convert_err(Error::Internal {
+                        err_msg: format!("Invalid list item type, find {v:?}, expected ip",),
+                    })),
+                })
+                .collect::<PgWireResult<Vec<Option<PgInet>>>>()?;
+            builder.encode_field(&array)
+        }
         _ => Err(convert_err(Error::Internal {
             err_msg: format!(
                 "cannot write array type {:?} in postgres protocol: unimplemented",
@@ -377,6 +411,18 @@ pub(super) fn encode_value(
                 let s = json_type_value_to_string(v, &j.format).map_err(convert_err)?;
                 builder.encode_field(&s)
             }
+            ConcreteDataType::Uuid(_) => {
+                let uuid = PgUuid::try_from_slice(v).map_err(convert_err)?;
+                builder.encode_field(&uuid)
+            }
+            ConcreteDataType::Map(_) => {
+                let s = map_type_value_to_string(v).map_err(convert_err)?;
+                builder.encode_field(&s)
+            }
+            ConcreteDataType::Ip(_) => {
+                let ip = PgInet::try_from_slice(v).map_err(convert_err)?;
+                builder.encode_field(&ip)
+            }
             _ => {
                 let bytea_output = query_ctx.configuration_parameter().postgres_bytea_output();
                 match *bytea_output {
@@ -448,7 +494,9 @@ pub(super) fn type_gt_to_pg(origin: &ConcreteDataType) -> Result<Type> {
         &ConcreteDataType::Time(_) => Ok(Type::TIME),
         &ConcreteDataType::Interval(_) => Ok(Type::INTERVAL),
         &ConcreteDataType::Decimal128(_) => Ok(Type::NUMERIC),
-        &ConcreteDataType::Json(_) => Ok(Type::JSON),
+        &ConcreteDataType::Json(_) | &ConcreteDataType::Map(_) => Ok(Type::JSON),
+        &ConcreteDataType::Uuid(_) => Ok(Type::UUID),
+        &ConcreteDataType::Ip(_) => Ok(Type::INET),
         ConcreteDataType::List(list) => match list.item_type() {
             &ConcreteDataType::Null(_) => Ok(Type::UNKNOWN),
             &ConcreteDataType::Boolean(_) => Ok(Type::BOOL_ARRAY),
@@ -467,8 +515,11 @@ pub(super) fn type_gt_to_pg(origin: &ConcreteDataType) -> Result<Type> {
             &ConcreteDataType::Decimal128(_) => Ok(Type::NUMERIC_ARRAY),
             &ConcreteDataType::Json(_) => Ok(Type::JSON_ARRAY),
             &ConcreteDataType::Duration(_) => Ok(Type::INTERVAL_ARRAY),
+            &ConcreteDataType::Uuid(_) => Ok(Type::UUID_ARRAY),
+            &ConcreteDataType::Ip(_) => Ok(Type::INET_ARRAY),
             &ConcreteDataType::Dictionary(_)
             | &ConcreteDataType::Vector(_)
+            | &ConcreteDataType::Map(_)
             | &ConcreteDataType::List(_)
             | &ConcreteDataType::Struct(_) => server_error::UnsupportedDataTypeSnafu {
                 data_type: origin,
@@ -573,6 +624,14 @@ pub(super) fn parameter_to_string(portal: &Portal<SqlPlan>, idx: usize) -> PgWir
             .parameter::<PgInterval>(idx, param_type)?
             .map(|v| v.to_string())
             .unwrap_or_else(|| "".to_owned())),
+        &Type::UUID => Ok(portal
+            .parameter::<PgUuid>(idx, param_type)?
+            .map(|v| format!("'{v}'"))
+            .unwrap_or_else(|| "".to_owned())),
+        &Type::INET => Ok(portal
+            .parameter::<PgInet>(idx, param_type)?
+            .map(|v| format!("'{v}'"))
+            .unwrap_or_else(|| "".to_owned())),
         _ => Err(invalid_parameter_error(
             "unsupported_parameter_type",
             Some(param_type.to_string()),
@@ -940,6 +999,48 @@ pub(super) fn parameters_to_scalar_values(
                     ScalarValue::Binary(data)
                 }
             }
+            &Type::UUID => {
+                let data = portal.parameter::<PgUuid>(idx, &client_type)?;
+                if let Some(server_type) = &server_type {
+                    match server_type {
+                        ConcreteDataType::String(_) => {
+                            ScalarValue::Utf8(data.map(|d| d.to_string()))
+                        }
+                        ConcreteDataType::Binary(_) => {
+                            ScalarValue::Binary(data.map(|d| d.as_bytes().to_vec()))
+                        }
+                        _ => {
+                            return Err(invalid_parameter_error(
+                                "invalid_parameter_type",
+                                Some(format!("Expected: {}, found: {}", server_type, client_type)),
+                            ));
+                        }
+                    }
+                } else {
+                    ScalarValue::Binary(data.map(|d| d.as_bytes().to_vec()))
+                }
+            }
+            &Type::INET => {
+                let data = portal.parameter::<PgInet>(idx, &client_type)?;
+                if let Some(server_type) = &server_type {
+                    match server_type {
+                        ConcreteDataType::String(_) => {
+                            ScalarValue::Utf8(data.map(|d| d.to_string()))
+                        }
+                        ConcreteDataType::Binary(_) => {
+                            ScalarValue::Binary(data.map(|d| d.to_bytes()))
+                        }
+                        _ => {
+                            return Err(invalid_parameter_error(
+                                "invalid_parameter_type",
+                                Some(format!("Expected: {}, found: {}", server_type, client_type)),
+                            ));
+                        }
+                    }
+                } else {
+                    ScalarValue::Binary(data.map(|d| d.to_bytes()))
+                }
+            }
             &Type::JSONB => {
                 let data = portal.parameter::<serde_json::Value>(idx, &client_type)?;
                 if let Some(server_type) = &server_type {
@@ -1066,6 +1167,16 @@ mod test {
                 ConcreteDataType::interval_month_day_nano_datatype(),
                 true,
             ),
+            ColumnSchema::new("uuids", ConcreteDataType::uuid_datatype(), true),
+            ColumnSchema::new("ips", ConcreteDataType::ip_datatype(), true),
+            ColumnSchema::new(
+                "maps",
+                ConcreteDataType::map_datatype(
+                    ConcreteDataType::string_datatype(),
+                    ConcreteDataType::string_datatype(),
+                ),
+                true,
+            ),
         ];
         let pg_field_info = vec![
             FieldInfo::new("nulls".into(), None, None, Type::UNKNOWN, FieldFormat::Text),
@@ -1122,6 +1233,9 @@ mod test {
                 Type::INTERVAL,
                 FieldFormat::Text,
             ),
+            FieldInfo::new("uuids".into(), None, None, Type::UUID, FieldFormat::Text),
+            FieldInfo::new("ips".into(), None, None, Type::INET, FieldFormat::Text),
+            FieldInfo::new("maps".into(), None, None, Type::JSON, FieldFormat::Text),
         ];
         let schema = Schema::new(column_schemas);
         let fs = schema_to_pg(&schema, &Format::UnifiedText).unwrap();
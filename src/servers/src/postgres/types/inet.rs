@@ -0,0 +1,164 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Display;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use bytes::BufMut;
+use datatypes::types::{ip_addr_to_ip_type_value, ip_type_value_to_ip_addr};
+use pgwire::types::ToSqlText;
+use postgres_types::{to_sql_checked, FromSql, IsNull, ToSql, Type};
+
+use crate::error;
+
+/// Address family of IPv4 in postgres inet binary format.
+const PGSQL_AF_INET: u8 = 2;
+/// Address family of IPv6 in postgres inet binary format.
+const PGSQL_AF_INET6: u8 = 3;
+
+/// An ip address value in postgres wire protocol.
+///
+/// The binary format is the address family, the netmask bits, the `is_cidr` flag, the address
+/// length and then the address bytes. The text format is the address string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PgInet(pub IpAddr);
+
+impl PgInet {
+    /// Creates a [PgInet] from the bytes of an ip type value.
+    pub fn try_from_slice(bytes: &[u8]) -> error::Result<Self> {
+        ip_type_value_to_ip_addr(bytes).map(PgInet).map_err(|e| {
+            error::InternalSnafu {
+                err_msg: format!("Invalid ip value {bytes:?}: {e}"),
+            }
+            .build()
+        })
+    }
+
+    /// Returns the bytes of the ip type value.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        ip_addr_to_ip_type_value(self.0).to_vec()
+    }
+}
+
+impl Display for PgInet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl ToSql for PgInet {
+    to_sql_checked!();
+
+    fn to_sql(
+        &self,
+        _: &Type,
+        out: &mut bytes::BytesMut,
+    ) -> std::result::Result<IsNull, Box<dyn snafu::Error + Sync + Send>>
+    where
+        Self: Sized,
+    {
+        match self.0 {
+            IpAddr::V4(v4) => {
+                out.put_slice(&[PGSQL_AF_INET, 32, 0, 4]);
+                out.put_slice(&v4.octets());
+            }
+            IpAddr::V6(v6) => {
+                out.put_slice(&[PGSQL_AF_INET6, 128, 0, 16]);
+                out.put_slice(&v6.octets());
+            }
+        }
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool
+    where
+        Self: Sized,
+    {
+        matches!(ty, &Type::INET)
+    }
+}
+
+impl<'a> FromSql<'a> for PgInet {
+    fn from_sql(
+        _: &Type,
+        raw: &'a [u8],
+    ) -> std::result::Result<Self, Box<dyn snafu::Error + Sync + Send>> {
+        let (header, addr) = raw.split_at_checked(4).ok_or("invalid inet value")?;
+        let addr = match (header[0], header[3]) {
+            (PGSQL_AF_INET, 4) => IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(addr)?)),
+            (PGSQL_AF_INET6, 16) => IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(addr)?)),
+            _ => return Err("invalid inet value".into()),
+        };
+        Ok(PgInet(addr))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty, &Type::INET)
+    }
+}
+
+impl ToSqlText for PgInet {
+    fn to_sql_text(
+        &self,
+        ty: &Type,
+        out: &mut bytes::BytesMut,
+    ) -> std::result::Result<IsNull, Box<dyn snafu::Error + Sync + Send>>
+    where
+        Self: Sized,
+    {
+        let fmt = match ty {
+            &Type::INET => self.to_string(),
+            _ => return Err("unsupported type".into()),
+        };
+
+        out.put_slice(fmt.as_bytes());
+        Ok(IsNull::No)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+    use datatypes::types::parse_string_to_ip_type_value;
+
+    use super::*;
+
+    #[test]
+    fn test_pg_inet_encode_decode() {
+        let bytes = parse_string_to_ip_type_value("10.0.0.1").unwrap();
+        let pg_inet = PgInet::try_from_slice(&bytes).unwrap();
+        assert_eq!("10.0.0.1", pg_inet.to_string());
+        assert_eq!(bytes, pg_inet.to_bytes());
+
+        let mut out = BytesMut::new();
+        pg_inet.to_sql(&Type::INET, &mut out).unwrap();
+        assert_eq!(&[2, 32, 0, 4, 10, 0, 0, 1], &out[..]);
+        assert_eq!(pg_inet, PgInet::from_sql(&Type::INET, &out).unwrap());
+
+        let mut out = BytesMut::new();
+        pg_inet.to_sql_text(&Type::INET, &mut out).unwrap();
+        assert_eq!(b"10.0.0.1", &out[..]);
+
+        let bytes = parse_string_to_ip_type_value("2001:db8::1").unwrap();
+        let pg_inet = PgInet::try_from_slice(&bytes).unwrap();
+        let mut out = BytesMut::new();
+        pg_inet.to_sql(&Type::INET, &mut out).unwrap();
+        assert_eq!(&[3, 128, 0, 16], &out[..4]);
+        assert_eq!(&bytes[..], &out[4..]);
+        assert_eq!(pg_inet, PgInet::from_sql(&Type::INET, &out).unwrap());
+
+        assert!(PgInet::try_from_slice(&[1, 2, 3]).is_err());
+        assert!(PgInet::from_sql(&Type::INET, &[2, 32, 0, 16, 1]).is_err());
+    }
+}
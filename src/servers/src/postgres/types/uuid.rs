@@ -0,0 +1,130 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Display;
+
+use bytes::BufMut;
+use pgwire::types::ToSqlText;
+use postgres_types::{to_sql_checked, FromSql, IsNull, ToSql, Type};
+use uuid::Uuid;
+
+use crate::error;
+
+/// A uuid value in postgres wire protocol.
+///
+/// The binary format is the 16 raw bytes and the text format is the hyphenated form.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PgUuid(pub Uuid);
+
+impl PgUuid {
+    /// Creates a [PgUuid] from the bytes of a uuid type value.
+    pub fn try_from_slice(bytes: &[u8]) -> error::Result<Self> {
+        Uuid::from_slice(bytes).map(PgUuid).map_err(|e| {
+            error::InternalSnafu {
+                err_msg: format!("Invalid uuid value {bytes:?}: {e}"),
+            }
+            .build()
+        })
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl Display for PgUuid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.hyphenated())
+    }
+}
+
+impl ToSql for PgUuid {
+    to_sql_checked!();
+
+    fn to_sql(
+        &self,
+        _: &Type,
+        out: &mut bytes::BytesMut,
+    ) -> std::result::Result<IsNull, Box<dyn snafu::Error + Sync + Send>>
+    where
+        Self: Sized,
+    {
+        out.put_slice(self.0.as_bytes());
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool
+    where
+        Self: Sized,
+    {
+        matches!(ty, &Type::UUID)
+    }
+}
+
+impl<'a> FromSql<'a> for PgUuid {
+    fn from_sql(
+        _: &Type,
+        raw: &'a [u8],
+    ) -> std::result::Result<Self, Box<dyn snafu::Error + Sync + Send>> {
+        Ok(PgUuid(Uuid::from_slice(raw)?))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty, &Type::UUID)
+    }
+}
+
+impl ToSqlText for PgUuid {
+    fn to_sql_text(
+        &self,
+        ty: &Type,
+        out: &mut bytes::BytesMut,
+    ) -> std::result::Result<IsNull, Box<dyn snafu::Error + Sync + Send>>
+    where
+        Self: Sized,
+    {
+        let fmt = match ty {
+            &Type::UUID => self.to_string(),
+            _ => return Err("unsupported type".into()),
+        };
+
+        out.put_slice(fmt.as_bytes());
+        Ok(IsNull::No)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+
+    use super::*;
+
+    #[test]
+    fn test_pg_uuid_encode_decode() {
+        let uuid = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        let pg_uuid = PgUuid::try_from_slice(uuid.as_bytes()).unwrap();
+        assert_eq!("67e55044-10b1-426f-9247-bb680e5fe0c8", pg_uuid.to_string());
+
+        let mut out = BytesMut::new();
+        pg_uuid.to_sql(&Type::UUID, &mut out).unwrap();
+        assert_eq!(uuid.as_bytes().as_slice(), &out[..]);
+        assert_eq!(pg_uuid, PgUuid::from_sql(&Type::UUID, &out).unwrap());
+
+        let mut out = BytesMut::new();
+        pg_uuid.to_sql_text(&Type::UUID, &mut out).unwrap();
+        assert_eq!(b"67e55044-10b1-426f-9247-bb680e5fe0c8", &out[..]);
+
+        assert!(PgUuid::try_from_slice(&[1, 2, 3]).is_err());
+    }
+}
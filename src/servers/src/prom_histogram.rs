@@ -0,0 +1,463 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Native histograms in Prometheus remote write.
+//!
+//! A native (sparse) histogram is stored as a conventional histogram, i.e. series
+//! `<name>_bucket{le="..."}`, `<name>_count` and `<name>_sum`. The bucket boundaries
+//! of the exponential schema are exact, so `histogram_quantile` and other histogram
+//! functions work on them like on any conventional histogram.
+//!
+//! Buckets are stored in a fixed layout of each schema: exponential schemas finer than
+//! [`MAX_STORED_SCHEMA`] are downscaled to it, so every histogram shares the same grid of
+//! boundaries, and the empty buckets between the lowest and the highest populated one are
+//! emitted too. Histograms with custom buckets emit every custom bound.
+//!
+//! The messages follow `prompb/types.proto` of Prometheus.
+
+use crate::repeated_field::Clear;
+
+/// Schema of histograms with custom bucket boundaries (NHCB).
+const CUSTOM_BUCKETS_SCHEMA: i32 = -53;
+/// Max schema of stored buckets, each power of two is split into 8 buckets.
+///
+/// Finer histograms are downscaled to this schema to bound the number of `le` values.
+const MAX_STORED_SCHEMA: i32 = 3;
+
+/// `Histogram` message in Prometheus remote write protocol.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PromHistogram {
+    #[prost(oneof = "HistogramCount", tags = "1, 2")]
+    pub count: Option<HistogramCount>,
+    #[prost(double, tag = "3")]
+    pub sum: f64,
+    /// The resolution of the buckets, from -4 to 8. Bucket boundaries are
+    /// powers of `2^(2^-schema)`.
+    #[prost(sint32, tag = "4")]
+    pub schema: i32,
+    #[prost(double, tag = "5")]
+    pub zero_threshold: f64,
+    #[prost(oneof = "HistogramZeroCount", tags = "6, 7")]
+    pub zero_count: Option<HistogramZeroCount>,
+    #[prost(message, repeated, tag = "8")]
+    pub negative_spans: Vec<BucketSpan>,
+    /// Deltas of absolute counts for integer histograms.
+    #[prost(sint64, repeated, tag = "9")]
+    pub negative_deltas: Vec<i64>,
+    /// Absolute counts for float histograms.
+    #[prost(double, repeated, tag = "10")]
+    pub negative_counts: Vec<f64>,
+    #[prost(message, repeated, tag = "11")]
+    pub positive_spans: Vec<BucketSpan>,
+    #[prost(sint64, repeated, tag = "12")]
+    pub positive_deltas: Vec<i64>,
+    #[prost(double, repeated, tag = "13")]
+    pub positive_counts: Vec<f64>,
+    #[prost(int32, tag = "14")]
+    pub reset_hint: i32,
+    #[prost(int64, tag = "15")]
+    pub timestamp: i64,
+    /// Upper bounds of buckets for the custom buckets schema.
+    #[prost(double, repeated, tag = "16")]
+    pub custom_values: Vec<f64>,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Oneof)]
+pub enum HistogramCount {
+    #[prost(uint64, tag = "1")]
+    CountInt(u64),
+    #[prost(double, tag = "2")]
+    CountFloat(f64),
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Oneof)]
+pub enum HistogramZeroCount {
+    #[prost(uint64, tag = "6")]
+    ZeroCountInt(u64),
+    #[prost(double, tag = "7")]
+    ZeroCountFloat(f64),
+}
+
+/// A run of consecutive buckets. `offset` is the gap to the previous span,
+/// or the index of the first bucket for the first span.
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct BucketSpan {
+    #[prost(sint32, tag = "1")]
+    pub offset: i32,
+    #[prost(uint32, tag = "2")]
+    pub length: u32,
+}
+
+impl Clear for PromHistogram {
+    fn clear(&mut self) {
+        self.count = None;
+        self.sum = 0.0;
+        self.schema = 0;
+        self.zero_threshold = 0.0;
+        self.zero_count = None;
+        self.negative_spans.clear();
+        self.negative_deltas.clear();
+        self.negative_counts.clear();
+        self.positive_spans.clear();
+        self.positive_deltas.clear();
+        self.positive_counts.clear();
+        self.reset_hint = 0;
+        self.timestamp = 0;
+        self.custom_values.clear();
+    }
+}
+
+impl PromHistogram {
+    /// Whether this histogram uses float counts instead of integer counts.
+    fn is_float(&self) -> bool {
+        matches!(self.count, Some(HistogramCount::CountFloat(_)))
+    }
+
+    /// The total number of observations.
+    pub fn count(&self) -> f64 {
+        match self.count {
+            Some(HistogramCount::CountInt(count)) => count as f64,
+            Some(HistogramCount::CountFloat(count)) => count,
+            None => 0.0,
+        }
+    }
+
+    fn zero_count(&self) -> f64 {
+        match self.zero_count {
+            Some(HistogramZeroCount::ZeroCountInt(count)) => count as f64,
+            Some(HistogramZeroCount::ZeroCountFloat(count)) => count,
+            None => 0.0,
+        }
+    }
+
+    /// Expands spans and counts into `(bucket index, count)` pairs.
+    fn expand_buckets(
+        spans: &[BucketSpan],
+        deltas: &[i64],
+        counts: &[f64],
+        is_float: bool,
+    ) -> Vec<(i32, f64)> {
+        let mut buckets = Vec::new();
+        let mut index = 0i32;
+        let mut cursor = 0usize;
+        let mut current = 0i64;
+        for (span_idx, span) in spans.iter().enumerate() {
+            if span_idx == 0 {
+                index = span.offset;
+            } else {
+                index += span.offset;
+            }
+            for _ in 0..span.length {
+                let count = if is_float {
+                    counts.get(cursor).copied()
+                } else {
+                    deltas.get(cursor).map(|delta| {
+                        current += delta;
+                        current as f64
+                    })
+                };
+                let Some(count) = count else {
+                    return buckets;
+                };
+                buckets.push((index, count));
+                index += 1;
+                cursor += 1;
+            }
+        }
+        buckets
+    }
+
+    /// Returns the schema the buckets of this histogram are stored in.
+    fn stored_schema(&self) -> i32 {
+        if self.schema == CUSTOM_BUCKETS_SCHEMA {
+            CUSTOM_BUCKETS_SCHEMA
+        } else {
+            self.schema.min(MAX_STORED_SCHEMA)
+        }
+    }
+
+    /// Downscales sorted `buckets` by `shift` schemas and fills the gaps between them
+    /// with empty buckets.
+    fn complete_layout(buckets: Vec<(i32, f64)>, shift: i32) -> Vec<(i32, f64)> {
+        let mut layout: Vec<(i32, f64)> = Vec::with_capacity(buckets.len());
+        for (index, count) in buckets {
+            // Bucket `index` covers (base^(index-1), base^index], so `2^shift` buckets
+            // starting from `index - 1 = k * 2^shift` are merged.
+            let index = ((index - 1) >> shift) + 1;
+            match layout.last_mut() {
+                Some((last, last_count)) if *last == index => *last_count += count,
+                Some((last, _)) => {
+                    let last = *last;
+                    layout.extend((last + 1..index).map(|i| (i, 0.0)));
+                    layout.push((index, count));
+                }
+                None => layout.push((index, count)),
+            }
+        }
+        layout
+    }
+
+    /// Returns the upper bound of the bucket at `index` in `schema`.
+    fn upper_bound(&self, schema: i32, index: i32) -> f64 {
+        if schema == CUSTOM_BUCKETS_SCHEMA {
+            return usize::try_from(index)
+                .ok()
+                .and_then(|i| self.custom_values.get(i).copied())
+                .unwrap_or(f64::INFINITY);
+        }
+        // bucket `index` covers (base^(index-1), base^index], base = 2^(2^-schema)
+        (index as f64 * 2f64.powi(-schema)).exp2()
+    }
+
+    /// Converts this histogram into cumulative buckets of a conventional histogram.
+    ///
+    /// Returns `(le, cumulative count)` pairs sorted by `le`, ending with `+Inf`.
+    pub fn to_conventional_buckets(&self) -> Vec<(f64, f64)> {
+        let is_float = self.is_float();
+        let schema = self.stored_schema();
+        let shift = self.schema - schema;
+        let mut buckets = Vec::new();
+
+        // Negative bucket `index` covers [-base^index, -base^(index-1)), from the
+        // largest index to the smallest in ascending order of upper bounds.
+        let negatives = Self::complete_layout(
+            Self::expand_buckets(
+                &self.negative_spans,
+                &self.negative_deltas,
+                &self.negative_counts,
+                is_float,
+            ),
+            shift,
+        );
+        for (index, count) in negatives.into_iter().rev() {
+            buckets.push((-self.upper_bound(schema, index - 1), count));
+        }
+        let mut positives = Self::expand_buckets(
+            &self.positive_spans,
+            &self.positive_deltas,
+            &self.positive_counts,
+            is_float,
+        );
+        if schema == CUSTOM_BUCKETS_SCHEMA {
+            // Every custom bound is emitted, even if no span covers it.
+            let mut all = (0..self.custom_values.len() as i32)
+                .map(|i| (i, 0.0))
+                .collect::<Vec<_>>();
+            for (index, count) in positives {
+                match usize::try_from(index).ok().and_then(|i| all.get_mut(i)) {
+                    Some((_, c)) => *c += count,
+                    None => all.push((index, count)),
+                }
+            }
+            positives = all;
+        } else {
+            buckets.push((self.zero_threshold, self.zero_count()));
+            positives = Self::complete_layout(positives, shift);
+        }
+        for (index, count) in positives {
+            buckets.push((self.upper_bound(schema, index), count));
+        }
+
+        let mut cumulative = 0.0;
+        let mut result: Vec<(f64, f64)> = Vec::with_capacity(buckets.len() + 1);
+        for (le, count) in buckets {
+            cumulative += count;
+            match result.last_mut() {
+                // Merge buckets with the same bound, e.g. the zero bucket and the first positive one.
+                Some((last_le, last_count)) if *last_le == le => *last_count = cumulative,
+                _ if le.is_infinite() => {}
+                _ => result.push((le, cumulative)),
+            }
+        }
+        result.push((f64::INFINITY, self.count().max(cumulative)));
+        result
+    }
+}
+
+/// Formats `le` like Prometheus client libraries, e.g. `0.25`, `1`, `+Inf`.
+pub fn format_le(le: f64) -> String {
+    if le == f64::INFINITY {
+        "+Inf".to_string()
+    } else if le == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        le.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integer_histogram_to_conventional_buckets() {
+        // schema 0: bucket `i` covers (2^(i-1), 2^i]
+        let histogram = PromHistogram {
+            count: Some(HistogramCount::CountInt(10)),
+            sum: 42.0,
+            schema: 0,
+            zero_threshold: 0.001,
+            zero_count: Some(HistogramZeroCount::ZeroCountInt(1)),
+            positive_spans: vec![
+                BucketSpan {
+                    offset: 0,
+                    length: 2,
+                },
+                BucketSpan {
+                    offset: 1,
+                    length: 1,
+                },
+            ],
+            // absolute counts: 2, 3, 1
+            positive_deltas: vec![2, 1, -2],
+            negative_spans: vec![BucketSpan {
+                offset: 1,
+                length: 1,
+            }],
+            negative_deltas: vec![3],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            histogram.to_conventional_buckets(),
+            vec![
+                (-1.0, 3.0),
+                (0.001, 4.0),
+                (1.0, 6.0),
+                (2.0, 9.0),
+                // The empty bucket between populated ones is also emitted.
+                (4.0, 9.0),
+                (8.0, 10.0),
+                (f64::INFINITY, 10.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_float_histogram_to_conventional_buckets() {
+        // schema 1: base is sqrt(2)
+        let histogram = PromHistogram {
+            count: Some(HistogramCount::CountFloat(3.5)),
+            schema: 1,
+            positive_spans: vec![BucketSpan {
+                offset: 1,
+                length: 2,
+            }],
+            positive_counts: vec![1.5, 2.0],
+            ..Default::default()
+        };
+
+        let buckets = histogram.to_conventional_buckets();
+        assert_eq!(buckets.len(), 4);
+        assert_eq!(buckets[0], (0.0, 0.0));
+        assert!((buckets[1].0 - 2f64.sqrt()).abs() < 1e-12);
+        assert_eq!(buckets[1].1, 1.5);
+        assert!((buckets[2].0 - 2.0).abs() < 1e-12);
+        assert_eq!(buckets[2].1, 3.5);
+        assert_eq!(buckets[3], (f64::INFINITY, 3.5));
+    }
+
+    #[test]
+    fn test_custom_buckets_to_conventional_buckets() {
+        let histogram = PromHistogram {
+            count: Some(HistogramCount::CountInt(6)),
+            schema: CUSTOM_BUCKETS_SCHEMA,
+            positive_spans: vec![BucketSpan {
+                offset: 0,
+                length: 3,
+            }],
+            positive_deltas: vec![1, 1, 1],
+            custom_values: vec![0.1, 0.5],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            histogram.to_conventional_buckets(),
+            vec![(0.1, 1.0), (0.5, 3.0), (f64::INFINITY, 6.0)]
+        );
+    }
+
+    #[test]
+    fn test_downscale_to_stored_schema() {
+        // schema 5 is downscaled to schema 3, every 4 buckets are merged into one.
+        let histogram = PromHistogram {
+            count: Some(HistogramCount::CountInt(7)),
+            schema: 5,
+            positive_spans: vec![BucketSpan {
+                offset: 3,
+                length: 3,
+            }],
+            // absolute counts of bucket 3, 4 and 5: 1, 2, 4
+            positive_deltas: vec![1, 1, 2],
+            ..Default::default()
+        };
+
+        let buckets = histogram.to_conventional_buckets();
+        assert_eq!(buckets.len(), 4);
+        assert_eq!(buckets[0], (0.0, 0.0));
+        // bucket 1 of schema 3 covers buckets 1..=4 of schema 5
+        assert!((buckets[1].0 - 2f64.powf(1.0 / 8.0)).abs() < 1e-12);
+        assert_eq!(buckets[1].1, 3.0);
+        assert!((buckets[2].0 - 2f64.powf(2.0 / 8.0)).abs() < 1e-12);
+        assert_eq!(buckets[2].1, 7.0);
+        assert_eq!(buckets[3], (f64::INFINITY, 7.0));
+
+        // Histograms of different schemas share the same boundaries.
+        let histogram = PromHistogram {
+            count: Some(HistogramCount::CountInt(3)),
+            schema: 8,
+            positive_spans: vec![BucketSpan {
+                offset: 33,
+                length: 1,
+            }],
+            positive_deltas: vec![3],
+            ..Default::default()
+        };
+        let les = histogram
+            .to_conventional_buckets()
+            .into_iter()
+            .map(|(le, _)| le)
+            .collect::<Vec<_>>();
+        assert_eq!(les.len(), 3);
+        assert!((les[1] - 2f64.powf(2.0 / 8.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_custom_buckets_emit_every_bound() {
+        let histogram = PromHistogram {
+            count: Some(HistogramCount::CountInt(2)),
+            schema: CUSTOM_BUCKETS_SCHEMA,
+            positive_spans: vec![BucketSpan {
+                offset: 1,
+                length: 1,
+            }],
+            positive_deltas: vec![2],
+            custom_values: vec![0.1, 0.5, 1.0],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            histogram.to_conventional_buckets(),
+            vec![(0.1, 0.0), (0.5, 2.0), (1.0, 2.0), (f64::INFINITY, 2.0)]
+        );
+    }
+
+    #[test]
+    fn test_format_le() {
+        assert_eq!(format_le(f64::INFINITY), "+Inf");
+        assert_eq!(format_le(0.25), "0.25");
+        assert_eq!(format_le(1.0), "1");
+        assert_eq!(format_le(-2.0), "-2");
+    }
+}
@@ -0,0 +1,1200 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Prometheus recording and alerting rules.
+//!
+//! Rule groups are loaded from standard Prometheus [rule files] and evaluated
+//! periodically by the PromQL engine. Results of recording rules are written back
+//! into metric engine tables. Like Prometheus, the state of alerting rules is
+//! written as the `ALERTS` and `ALERTS_FOR_STATE` series, so it can be queried
+//! as any other metric.
+//!
+//! The pending and firing alerts are also persisted by an [AlertStateStore] after
+//! every evaluation. Only the node elected by [RuleLeaderElection] evaluates rules,
+//! so rules are evaluated once in a cluster with multiple frontends. A node restores
+//! the persisted alerts before it starts evaluating as the leader, so `for` timers
+//! survive restarts and leader changes.
+//!
+//! [rule files]: https://prometheus.io/docs/prometheus/latest/configuration/recording_rules/
+
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use api::prom_store::remote::{Label, Sample, TimeSeries, WriteRequest};
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat};
+use common_runtime::JoinHandle;
+use common_telemetry::{error, info, warn};
+use common_time::Timestamp;
+use lazy_static::lazy_static;
+use parking_lot::{Mutex, RwLock};
+use promql_parser::parser::value::ValueType;
+use query::parser::{PromQuery, DEFAULT_LOOKBACK_STRING};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use session::context::{QueryContextBuilder, QueryContextRef};
+use snafu::{ensure, ResultExt};
+use tokio::time::{self, MissedTickBehavior};
+
+use crate::error::{
+    InvalidRuleConfigSnafu, ParseJsonSnafu, ParseRuleFileSnafu, ReadRuleFileSnafu, Result,
+    ToJsonSnafu, UnexpectedResultSnafu,
+};
+use crate::http::prometheus::{
+    retrieve_metric_name_and_result_type, AlertDiscovery, PromAlert, PromAlertingRule, PromData,
+    PromQueryResult, PromRecordingRule, PromRule, PromRuleGroup, PrometheusJsonResponse,
+    PrometheusResponse, RuleDiscovery,
+};
+use crate::prom_store::{to_grpc_row_insert_requests, METRIC_NAME_LABEL};
+use crate::prometheus_handler::PrometheusHandlerRef;
+use crate::query_handler::PromStoreProtocolHandlerRef;
+
+/// The metric name of alert states, e.g. `ALERTS{alertname="...", alertstate="firing"} 1`.
+pub const ALERTS_METRIC_NAME: &str = "ALERTS";
+/// The metric name of the time alerts became active, in seconds.
+pub const ALERTS_FOR_STATE_METRIC_NAME: &str = "ALERTS_FOR_STATE";
+pub const ALERT_NAME_LABEL: &str = "alertname";
+pub const ALERT_STATE_LABEL: &str = "alertstate";
+
+/// Prometheus reports zero time for rules never evaluated.
+const ZERO_TIME_RFC3339: &str = "0001-01-01T00:00:00Z";
+
+/// The interval to campaign for or renew the rule evaluation leadership.
+pub const RULE_LEADER_CAMPAIGN_INTERVAL: Duration = Duration::from_secs(5);
+
+lazy_static! {
+    /// Matches `{{ $value }}` and `{{ $labels.<name> }}` in label and annotation templates.
+    static ref TEMPLATE_REGEX: Regex =
+        Regex::new(r"\{\{\s*\$(value|labels\.([a-zA-Z_][a-zA-Z0-9_]*))\s*\}\}").unwrap();
+}
+
+type Labels = BTreeMap<String, String>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RuleOptions {
+    pub enable: bool,
+    /// Paths of the Prometheus rule files to load.
+    pub rule_files: Vec<String>,
+    /// The interval to evaluate rule groups without their own `interval`.
+    #[serde(with = "humantime_serde")]
+    pub evaluation_interval: Duration,
+    /// The database to evaluate rules in and write results to.
+    pub db: String,
+}
+
+impl Default for RuleOptions {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            rule_files: vec![],
+            evaluation_interval: Duration::from_secs(60),
+            db: "public".to_string(),
+        }
+    }
+}
+
+/// The content of a Prometheus rule file.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct RuleGroupsConfig {
+    pub groups: Vec<RuleGroupConfig>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct RuleGroupConfig {
+    pub name: String,
+    #[serde(default, with = "humantime_serde")]
+    pub interval: Option<Duration>,
+    pub rules: Vec<RuleConfig>,
+}
+
+/// A recording rule (with `record`) or an alerting rule (with `alert`).
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct RuleConfig {
+    #[serde(default)]
+    pub record: Option<String>,
+    #[serde(default)]
+    pub alert: Option<String>,
+    pub expr: String,
+    #[serde(default, rename = "for", with = "humantime_serde")]
+    pub for_duration: Option<Duration>,
+    #[serde(default)]
+    pub labels: Labels,
+    #[serde(default)]
+    pub annotations: Labels,
+}
+
+/// Type of rules used to filter the rules API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleType {
+    Alerting,
+    Recording,
+}
+
+pub type AlertStateStoreRef = Arc<dyn AlertStateStore>;
+
+/// Persists the pending and firing alerts of alerting rules.
+#[async_trait]
+pub trait AlertStateStore: Send + Sync {
+    /// Loads the persisted alerts as `(group, rule, alerts)`, the alerts are in JSON.
+    async fn load(&self) -> Result<Vec<(String, String, String)>>;
+
+    /// Replaces the persisted alerts of the rule `rule` in the group `group`.
+    async fn save(&self, group: &str, rule: &str, alerts: String) -> Result<()>;
+}
+
+pub type RuleLeaderElectionRef = Arc<dyn RuleLeaderElection>;
+
+/// Elects the only node that evaluates rules among all frontends.
+#[async_trait]
+pub trait RuleLeaderElection: Send + Sync {
+    /// Campaigns for or renews the leadership, returns whether this node is the leader.
+    ///
+    /// It's called every [RULE_LEADER_CAMPAIGN_INTERVAL].
+    async fn campaign(&self) -> Result<bool>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum AlertState {
+    Pending,
+    Firing,
+}
+
+impl AlertState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AlertState::Pending => "pending",
+            AlertState::Firing => "firing",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Alert {
+    labels: Labels,
+    annotations: Labels,
+    state: AlertState,
+    /// The time in milliseconds the alert became pending.
+    active_at: i64,
+    value: f64,
+}
+
+impl Alert {
+    fn to_prom_alert(&self) -> PromAlert {
+        PromAlert {
+            labels: self.labels.clone(),
+            annotations: self.annotations.clone(),
+            state: self.state.as_str().to_string(),
+            active_at: millis_to_rfc3339(Some(self.active_at)),
+            value: self.value.to_string(),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum RuleKind {
+    Recording,
+    Alerting {
+        for_duration: Duration,
+        annotations: Labels,
+    },
+}
+
+#[derive(Debug, Default)]
+struct RuleState {
+    last_error: Option<String>,
+    /// The time in milliseconds of the last evaluation.
+    last_evaluation: Option<i64>,
+    evaluation_time: Duration,
+    /// Active alerts of alerting rules, keyed by their labels.
+    alerts: BTreeMap<Labels, Alert>,
+}
+
+impl RuleState {
+    fn health(&self) -> String {
+        match (&self.last_evaluation, &self.last_error) {
+            (None, _) => "unknown",
+            (Some(_), None) => "ok",
+            (Some(_), Some(_)) => "err",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Debug)]
+struct Rule {
+    /// The `record` or `alert` name of the rule.
+    name: String,
+    expr: String,
+    metric_name: Option<String>,
+    value_type: ValueType,
+    labels: Labels,
+    kind: RuleKind,
+    state: RwLock<RuleState>,
+}
+
+impl Rule {
+    fn try_new(config: RuleConfig) -> Result<Self> {
+        let (name, kind) = match (config.record, config.alert) {
+            (Some(record), None) => {
+                ensure!(
+                    is_valid_metric_name(&record),
+                    InvalidRuleConfigSnafu {
+                        msg: format!("invalid recording rule name: {record}"),
+                    }
+                );
+                ensure!(
+                    config.for_duration.is_none() && config.annotations.is_empty(),
+                    InvalidRuleConfigSnafu {
+                        msg: format!(
+                            "recording rule {record} must not have `for` or `annotations`"
+                        ),
+                    }
+                );
+                (record, RuleKind::Recording)
+            }
+            (None, Some(alert)) => {
+                ensure!(
+                    !alert.is_empty(),
+                    InvalidRuleConfigSnafu {
+                        msg: "alerting rule name must not be empty",
+                    }
+                );
+                let kind = RuleKind::Alerting {
+                    for_duration: config.for_duration.unwrap_or_default(),
+                    annotations: config.annotations,
+                };
+                (alert, kind)
+            }
+            _ => {
+                return InvalidRuleConfigSnafu {
+                    msg: format!(
+                        "exactly one of `record` or `alert` must be set, expr: {}",
+                        config.expr
+                    ),
+                }
+                .fail()
+            }
+        };
+
+        let (metric_name, value_type) = retrieve_metric_name_and_result_type(&config.expr)?;
+        ensure!(
+            matches!(value_type, ValueType::Vector | ValueType::Scalar),
+            InvalidRuleConfigSnafu {
+                msg: format!(
+                    "rule {name} must evaluate to a vector or scalar, but got {value_type}"
+                ),
+            }
+        );
+
+        Ok(Self {
+            name,
+            expr: config.expr,
+            metric_name,
+            value_type,
+            labels: config.labels,
+            kind,
+            state: RwLock::new(RuleState::default()),
+        })
+    }
+
+    /// Runs the rule expression as an instant query at `ts` in milliseconds.
+    async fn query(
+        &self,
+        handler: &PrometheusHandlerRef,
+        ctx: QueryContextRef,
+        ts: i64,
+    ) -> Result<Vec<(Labels, f64)>> {
+        let time = format!("{:.3}", ts as f64 / 1000.0);
+        let query = PromQuery {
+            query: self.expr.clone(),
+            start: time.clone(),
+            end: time,
+            step: "1s".to_string(),
+            lookback: DEFAULT_LOOKBACK_STRING.to_string(),
+        };
+        let result = handler.do_query(&query, ctx).await;
+        let response = PrometheusJsonResponse::from_query_result(
+            result,
+            self.metric_name.clone(),
+            self.value_type,
+        )
+        .await;
+        if let Some(reason) = response.error {
+            return UnexpectedResultSnafu { reason }.fail();
+        }
+
+        let samples = match response.data {
+            PrometheusResponse::PromData(PromData {
+                result: PromQueryResult::Vector(vector),
+                ..
+            }) => vector
+                .into_iter()
+                .filter_map(|series| {
+                    let (_, value) = series.value?;
+                    Some((series.metric, value.parse().ok()?))
+                })
+                .collect(),
+            PrometheusResponse::PromData(PromData {
+                result: PromQueryResult::Scalar(Some((_, value))),
+                ..
+            }) => value
+                .parse()
+                .map(|value| vec![(Labels::new(), value)])
+                .unwrap_or_default(),
+            _ => vec![],
+        };
+        Ok(samples)
+    }
+
+    /// Applies evaluated samples at `ts` to this rule, returns the series to write.
+    fn apply(&self, samples: Vec<(Labels, f64)>, ts: i64) -> Vec<TimeSeries> {
+        match &self.kind {
+            RuleKind::Recording => samples
+                .into_iter()
+                .map(|(mut labels, value)| {
+                    labels.remove(METRIC_NAME_LABEL);
+                    labels.extend(self.labels.clone());
+                    labels.insert(METRIC_NAME_LABEL.to_string(), self.name.clone());
+                    new_series(labels, value, ts)
+                })
+                .collect(),
+            RuleKind::Alerting {
+                for_duration,
+                annotations,
+            } => {
+                let mut state = self.state.write();
+                update_alerts(
+                    &mut state.alerts,
+                    &self.name,
+                    &self.labels,
+                    annotations,
+                    *for_duration,
+                    samples,
+                    ts,
+                );
+                state
+                    .alerts
+                    .values()
+                    .flat_map(|alert| alert_series(alert, ts))
+                    .collect()
+            }
+        }
+    }
+
+    /// Saves the alerts of an alerting rule in the group `group` to `store`.
+    async fn persist_alerts(&self, group: &str, store: &AlertStateStoreRef) -> Result<()> {
+        if !matches!(self.kind, RuleKind::Alerting { .. }) {
+            return Ok(());
+        }
+        let alerts = {
+            let state = self.state.read();
+            serde_json::to_string(&state.alerts.values().collect::<Vec<_>>())
+                .context(ToJsonSnafu)?
+        };
+        store.save(group, &self.name, alerts).await
+    }
+
+    /// Restores the alerts of an alerting rule from the persisted JSON `alerts`.
+    fn restore_alerts(&self, alerts: &str) -> Result<()> {
+        if !matches!(self.kind, RuleKind::Alerting { .. }) {
+            return Ok(());
+        }
+        let alerts: Vec<Alert> = serde_json::from_str(alerts).context(ParseJsonSnafu)?;
+        self.state.write().alerts = alerts
+            .into_iter()
+            .map(|alert| (alert.labels.clone(), alert))
+            .collect();
+        Ok(())
+    }
+
+    fn to_prom_rule(&self) -> PromRule {
+        let state = self.state.read();
+        match &self.kind {
+            RuleKind::Recording => PromRule::Recording(PromRecordingRule {
+                name: self.name.clone(),
+                query: self.expr.clone(),
+                labels: self.labels.clone(),
+                health: state.health(),
+                last_error: state.last_error.clone(),
+                evaluation_time: state.evaluation_time.as_secs_f64(),
+                last_evaluation: millis_to_rfc3339(state.last_evaluation),
+            }),
+            RuleKind::Alerting {
+                for_duration,
+                annotations,
+            } => {
+                let alerts: Vec<_> = state.alerts.values().map(Alert::to_prom_alert).collect();
+                let rule_state = if state
+                    .alerts
+                    .values()
+                    .any(|alert| alert.state == AlertState::Firing)
+                {
+                    AlertState::Firing.as_str()
+                } else if !alerts.is_empty() {
+                    AlertState::Pending.as_str()
+                } else {
+                    "inactive"
+                };
+                PromRule::Alerting(PromAlertingRule {
+                    state: rule_state.to_string(),
+                    name: self.name.clone(),
+                    query: self.expr.clone(),
+                    duration: for_duration.as_secs_f64(),
+                    labels: self.labels.clone(),
+                    annotations: annotations.clone(),
+                    alerts,
+                    health: state.health(),
+                    last_error: state.last_error.clone(),
+                    evaluation_time: state.evaluation_time.as_secs_f64(),
+                    last_evaluation: millis_to_rfc3339(state.last_evaluation),
+                })
+            }
+        }
+    }
+
+    fn rule_type(&self) -> RuleType {
+        match self.kind {
+            RuleKind::Recording => RuleType::Recording,
+            RuleKind::Alerting { .. } => RuleType::Alerting,
+        }
+    }
+}
+
+/// A group of rules evaluated sequentially at the same interval.
+#[derive(Debug)]
+struct RuleGroup {
+    name: String,
+    file: String,
+    interval: Duration,
+    rules: Vec<Rule>,
+    /// The time in milliseconds and the duration of the last evaluation.
+    last_evaluation: RwLock<Option<(i64, Duration)>>,
+}
+
+impl RuleGroup {
+    fn try_new(file: &str, config: RuleGroupConfig, default_interval: Duration) -> Result<Self> {
+        ensure!(
+            !config.name.is_empty(),
+            InvalidRuleConfigSnafu {
+                msg: format!("rule group name must not be empty in file {file}"),
+            }
+        );
+        let interval = config.interval.unwrap_or(default_interval);
+        ensure!(
+            !interval.is_zero(),
+            InvalidRuleConfigSnafu {
+                msg: format!("interval of rule group {} must be positive", config.name),
+            }
+        );
+        let rules = config
+            .rules
+            .into_iter()
+            .map(Rule::try_new)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            name: config.name,
+            file: file.to_string(),
+            interval,
+            rules,
+            last_evaluation: RwLock::new(None),
+        })
+    }
+
+    /// Evaluates all rules at `ts` in milliseconds. Rules are evaluated in order,
+    /// so later rules can use the results of earlier recording rules.
+    async fn eval(
+        &self,
+        query_handler: &PrometheusHandlerRef,
+        write_handler: &PromStoreProtocolHandlerRef,
+        state_store: &AlertStateStoreRef,
+        ctx: &QueryContextRef,
+        ts: i64,
+    ) {
+        let group_start = Instant::now();
+        for rule in &self.rules {
+            let start = Instant::now();
+            let result = async {
+                let samples = rule.query(query_handler, ctx.clone(), ts).await?;
+                write_series(write_handler, rule.apply(samples, ts), ctx.clone()).await?;
+                rule.persist_alerts(&self.name, state_store).await
+            }
+            .await;
+
+            let mut state = rule.state.write();
+            state.last_evaluation = Some(ts);
+            state.evaluation_time = start.elapsed();
+            state.last_error = match result {
+                Ok(()) => None,
+                Err(e) => {
+                    error!(e; "Failed to evaluate rule {} in group {}", rule.name, self.name);
+                    Some(e.to_string())
+                }
+            };
+        }
+        *self.last_evaluation.write() = Some((ts, group_start.elapsed()));
+    }
+
+    fn to_prom_rule_group(&self, rule_type: Option<RuleType>) -> PromRuleGroup {
+        let last_evaluation = *self.last_evaluation.read();
+        PromRuleGroup {
+            name: self.name.clone(),
+            file: self.file.clone(),
+            rules: self
+                .rules
+                .iter()
+                .filter(|rule| rule_type.is_none_or(|t| rule.rule_type() == t))
+                .map(Rule::to_prom_rule)
+                .collect(),
+            interval: self.interval.as_secs_f64(),
+            last_evaluation: millis_to_rfc3339(last_evaluation.map(|(ts, _)| ts)),
+            evaluation_time: last_evaluation
+                .map(|(_, elapsed)| elapsed.as_secs_f64())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+pub type RuleManagerRef = Arc<RuleManager>;
+
+/// Loads rule groups and evaluates them periodically.
+#[derive(Debug)]
+pub struct RuleManager {
+    db: String,
+    groups: Vec<Arc<RuleGroup>>,
+    /// Whether this node is the elected node to evaluate rules.
+    leader: Arc<AtomicBool>,
+    /// The background tasks started by [RuleManager::start].
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl RuleManager {
+    /// Loads rule files in `config`, returns `None` if rules are not enabled.
+    pub fn try_new(config: &RuleOptions) -> Result<Option<Self>> {
+        if !config.enable {
+            return Ok(None);
+        }
+        ensure!(
+            !config.evaluation_interval.is_zero(),
+            InvalidRuleConfigSnafu {
+                msg: "Expected rule evaluation_interval greater than zero",
+            }
+        );
+        ensure!(
+            !config.db.is_empty(),
+            InvalidRuleConfigSnafu {
+                msg: "Expected rule `db` not empty",
+            }
+        );
+
+        let mut groups = Vec::new();
+        for path in &config.rule_files {
+            let content = std::fs::read_to_string(path).context(ReadRuleFileSnafu { path })?;
+            groups.extend(parse_rule_groups(
+                path,
+                &content,
+                config.evaluation_interval,
+            )?);
+        }
+
+        Ok(Some(Self::new(config.db.clone(), groups)))
+    }
+
+    fn new(db: String, groups: Vec<RuleGroup>) -> Self {
+        Self {
+            db,
+            groups: groups.into_iter().map(Arc::new).collect(),
+            leader: Arc::new(AtomicBool::new(false)),
+            tasks: Mutex::new(vec![]),
+        }
+    }
+
+    /// Starts a background task to campaign for the leadership with `election`, and a
+    /// background task for each rule group. Rules are only evaluated by the leader,
+    /// by `query_handler`, and the results are written by `write_handler`.
+    pub fn start(
+        &self,
+        query_handler: PrometheusHandlerRef,
+        write_handler: PromStoreProtocolHandlerRef,
+        state_store: AlertStateStoreRef,
+        election: RuleLeaderElectionRef,
+    ) {
+        let ctx = Arc::new(
+            QueryContextBuilder::default()
+                .current_schema(self.db.clone())
+                .build(),
+        );
+        let mut tasks = self.tasks.lock();
+        tasks.push(common_runtime::spawn_global(campaign_leader(
+            self.groups.clone(),
+            self.leader.clone(),
+            state_store.clone(),
+            election,
+        )));
+        for group in &self.groups {
+            tasks.push(common_runtime::spawn_global(eval_rule_group(
+                group.clone(),
+                self.leader.clone(),
+                query_handler.clone(),
+                write_handler.clone(),
+                state_store.clone(),
+                ctx.clone(),
+            )));
+        }
+    }
+
+    /// Stops evaluating rules by aborting the background tasks.
+    pub fn stop(&self) {
+        for task in self.tasks.lock().drain(..) {
+            task.abort();
+        }
+        self.leader.store(false, Ordering::Relaxed);
+        info!("Stopped evaluating Prometheus rules");
+    }
+
+    /// Returns the rule groups for the `/api/v1/rules` API.
+    pub fn rule_discovery(&self, rule_type: Option<RuleType>) -> RuleDiscovery {
+        RuleDiscovery {
+            groups: self
+                .groups
+                .iter()
+                .map(|group| group.to_prom_rule_group(rule_type))
+                .collect(),
+        }
+    }
+
+    /// Returns the active alerts for the `/api/v1/alerts` API.
+    pub fn alert_discovery(&self) -> AlertDiscovery {
+        AlertDiscovery {
+            alerts: self
+                .groups
+                .iter()
+                .flat_map(|group| &group.rules)
+                .flat_map(|rule| {
+                    rule.state
+                        .read()
+                        .alerts
+                        .values()
+                        .map(Alert::to_prom_alert)
+                        .collect::<Vec<_>>()
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Parses rule groups from the content of the rule file `file`.
+fn parse_rule_groups(
+    file: &str,
+    content: &str,
+    default_interval: Duration,
+) -> Result<Vec<RuleGroup>> {
+    let config: RuleGroupsConfig =
+        serde_yaml::from_str(content).context(ParseRuleFileSnafu { path: file })?;
+
+    let mut names = HashSet::new();
+    config
+        .groups
+        .into_iter()
+        .map(|group| {
+            ensure!(
+                names.insert(group.name.clone()),
+                InvalidRuleConfigSnafu {
+                    msg: format!("duplicated rule group {} in file {file}", group.name),
+                }
+            );
+            RuleGroup::try_new(file, group, default_interval)
+        })
+        .collect()
+}
+
+/// Campaigns for the leadership periodically. The persisted alerts are restored
+/// before this node starts evaluating rules as the leader.
+async fn campaign_leader(
+    groups: Vec<Arc<RuleGroup>>,
+    leader: Arc<AtomicBool>,
+    state_store: AlertStateStoreRef,
+    election: RuleLeaderElectionRef,
+) {
+    let mut interval = time::interval(RULE_LEADER_CAMPAIGN_INTERVAL);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    loop {
+        interval.tick().await;
+        let is_leader = match election.campaign().await {
+            Ok(is_leader) => is_leader,
+            Err(e) => {
+                // Stops evaluating if the leadership is unknown, to avoid duplicated evaluations.
+                error!(e; "Failed to campaign for the rule evaluation leader");
+                false
+            }
+        };
+
+        let was_leader = leader.load(Ordering::Relaxed);
+        if is_leader && !was_leader {
+            match restore_alerts(&groups, &state_store).await {
+                Ok(()) => {
+                    info!("Became the rule evaluation leader");
+                    leader.store(true, Ordering::Relaxed);
+                }
+                Err(e) => error!(e; "Failed to restore alert states, retry later"),
+            }
+        } else if !is_leader && was_leader {
+            info!("Lost the rule evaluation leadership");
+            leader.store(false, Ordering::Relaxed);
+            // The alerts are evaluated by the new leader now.
+            for rule in groups.iter().flat_map(|group| &group.rules) {
+                rule.state.write().alerts.clear();
+            }
+        }
+    }
+}
+
+/// Restores the persisted alerts of all alerting rules in `groups`.
+async fn restore_alerts(groups: &[Arc<RuleGroup>], state_store: &AlertStateStoreRef) -> Result<()> {
+    let persisted = state_store
+        .load()
+        .await?
+        .into_iter()
+        .map(|(group, rule, alerts)| ((group, rule), alerts))
+        .collect::<HashMap<_, _>>();
+    for group in groups {
+        for rule in &group.rules {
+            let Some(alerts) = persisted.get(&(group.name.clone(), rule.name.clone())) else {
+                continue;
+            };
+            if let Err(e) = rule.restore_alerts(alerts) {
+                warn!(e; "Failed to restore alerts of rule {} in group {}", rule.name, group.name);
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn eval_rule_group(
+    group: Arc<RuleGroup>,
+    leader: Arc<AtomicBool>,
+    query_handler: PrometheusHandlerRef,
+    write_handler: PromStoreProtocolHandlerRef,
+    state_store: AlertStateStoreRef,
+    ctx: QueryContextRef,
+) {
+    info!(
+        "Start evaluating rule group: {}, file: {}, interval: {:?}",
+        group.name, group.file, group.interval
+    );
+    let mut interval = time::interval(group.interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    // Pass the first tick. Because the first tick completes immediately.
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        if !leader.load(Ordering::Relaxed) {
+            continue;
+        }
+        let ts = Timestamp::current_millis().value();
+        group
+            .eval(&query_handler, &write_handler, &state_store, &ctx, ts)
+            .await;
+    }
+}
+
+async fn write_series(
+    handler: &PromStoreProtocolHandlerRef,
+    series: Vec<TimeSeries>,
+    ctx: QueryContextRef,
+) -> Result<()> {
+    if series.is_empty() {
+        return Ok(());
+    }
+    let request = WriteRequest {
+        timeseries: series,
+        ..Default::default()
+    };
+    let (requests, _) = to_grpc_row_insert_requests(&request)?;
+    let _ = handler.write(requests, ctx, true).await?;
+    Ok(())
+}
+
+/// Updates `alerts` of the rule `alert_name` by samples evaluated at `ts`.
+///
+/// New alerts become pending, and fire once they have been active for `for_duration`.
+/// Alerts absent from `samples` are resolved and removed.
+fn update_alerts(
+    alerts: &mut BTreeMap<Labels, Alert>,
+    alert_name: &str,
+    rule_labels: &Labels,
+    rule_annotations: &Labels,
+    for_duration: Duration,
+    samples: Vec<(Labels, f64)>,
+    ts: i64,
+) {
+    let mut active = HashSet::with_capacity(samples.len());
+    for (mut metric, value) in samples {
+        metric.remove(METRIC_NAME_LABEL);
+        let mut labels = metric.clone();
+        labels.extend(
+            rule_labels
+                .iter()
+                .map(|(k, v)| (k.clone(), expand_template(v, &metric, value))),
+        );
+        labels.insert(ALERT_NAME_LABEL.to_string(), alert_name.to_string());
+        let annotations = rule_annotations
+            .iter()
+            .map(|(k, v)| (k.clone(), expand_template(v, &metric, value)))
+            .collect();
+
+        let _ = active.insert(labels.clone());
+        match alerts.entry(labels) {
+            Entry::Occupied(mut entry) => {
+                let alert = entry.get_mut();
+                alert.value = value;
+                alert.annotations = annotations;
+            }
+            Entry::Vacant(entry) => {
+                let labels = entry.key().clone();
+                let _ = entry.insert(Alert {
+                    labels,
+                    annotations,
+                    state: AlertState::Pending,
+                    active_at: ts,
+                    value,
+                });
+            }
+        }
+    }
+
+    alerts.retain(|labels, _| active.contains(labels));
+    for alert in alerts.values_mut() {
+        if alert.state == AlertState::Pending
+            && ts - alert.active_at >= for_duration.as_millis() as i64
+        {
+            alert.state = AlertState::Firing;
+        }
+    }
+}
+
+/// Returns the `ALERTS` and `ALERTS_FOR_STATE` series of an active alert.
+fn alert_series(alert: &Alert, ts: i64) -> [TimeSeries; 2] {
+    let mut labels = alert.labels.clone();
+    labels.insert(
+        METRIC_NAME_LABEL.to_string(),
+        ALERTS_FOR_STATE_METRIC_NAME.to_string(),
+    );
+    let for_state = new_series(labels.clone(), alert.active_at as f64 / 1000.0, ts);
+
+    labels.insert(
+        METRIC_NAME_LABEL.to_string(),
+        ALERTS_METRIC_NAME.to_string(),
+    );
+    labels.insert(
+        ALERT_STATE_LABEL.to_string(),
+        alert.state.as_str().to_string(),
+    );
+    [new_series(labels, 1.0, ts), for_state]
+}
+
+fn new_series(labels: Labels, value: f64, ts: i64) -> TimeSeries {
+    TimeSeries {
+        labels: labels
+            .into_iter()
+            .map(|(name, value)| Label { name, value })
+            .collect(),
+        samples: vec![Sample {
+            value,
+            timestamp: ts,
+        }],
+        ..Default::default()
+    }
+}
+
+/// Expands `{{ $value }}` and `{{ $labels.<name> }}` in `template`.
+fn expand_template(template: &str, labels: &Labels, value: f64) -> String {
+    TEMPLATE_REGEX
+        .replace_all(template, |caps: &Captures| match caps.get(2) {
+            Some(name) => labels.get(name.as_str()).cloned().unwrap_or_default(),
+            None => value.to_string(),
+        })
+        .into_owned()
+}
+
+fn is_valid_metric_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == ':')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+}
+
+fn millis_to_rfc3339(millis: Option<i64>) -> String {
+    millis
+        .and_then(DateTime::from_timestamp_millis)
+        .map(|time| time.to_rfc3339_opts(SecondsFormat::Millis, true))
+        .unwrap_or_else(|| ZERO_TIME_RFC3339.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULE_FILE: &str = r#"
+groups:
+  - name: example
+    interval: 30s
+    rules:
+      - record: job:http_requests:rate5m
+        expr: sum by (job) (rate(http_requests_total[5m]))
+        labels:
+          source: rule
+      - alert: HighRequestRate
+        expr: job:http_requests:rate5m > 10
+        for: 2m
+        labels:
+          severity: page
+        annotations:
+          summary: "High request rate of {{ $labels.job }}: {{ $value }}"
+  - name: default_interval
+    rules:
+      - record: up:sum
+        expr: sum(up)
+"#;
+
+    fn labels(pairs: &[(&str, &str)]) -> Labels {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_rule_groups() {
+        let groups = parse_rule_groups("rules.yml", RULE_FILE, Duration::from_secs(60)).unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].name, "example");
+        assert_eq!(groups[0].interval, Duration::from_secs(30));
+        assert_eq!(groups[0].rules.len(), 2);
+        assert_eq!(groups[0].rules[0].rule_type(), RuleType::Recording);
+        assert_eq!(groups[0].rules[1].rule_type(), RuleType::Alerting);
+        assert!(matches!(
+            groups[0].rules[1].kind,
+            RuleKind::Alerting { for_duration, .. } if for_duration == Duration::from_secs(120)
+        ));
+        assert_eq!(groups[1].interval, Duration::from_secs(60));
+
+        let discovery =
+            RuleManager::new("public".to_string(), groups).rule_discovery(Some(RuleType::Alerting));
+        assert_eq!(discovery.groups.len(), 2);
+        assert_eq!(discovery.groups[0].rules.len(), 1);
+        assert!(discovery.groups[1].rules.is_empty());
+        let PromRule::Alerting(rule) = &discovery.groups[0].rules[0] else {
+            unreachable!()
+        };
+        assert_eq!(rule.state, "inactive");
+        assert_eq!(rule.health, "unknown");
+        assert_eq!(rule.last_evaluation, ZERO_TIME_RFC3339);
+    }
+
+    #[test]
+    fn test_invalid_rule_groups() {
+        let cases = [
+            // both record and alert
+            "groups: [{name: a, rules: [{record: a, alert: b, expr: up}]}]",
+            // neither record nor alert
+            "groups: [{name: a, rules: [{expr: up}]}]",
+            // invalid metric name
+            "groups: [{name: a, rules: [{record: 'a-b', expr: up}]}]",
+            // invalid expr
+            "groups: [{name: a, rules: [{record: a, expr: 'sum('}]}]",
+            // range vector
+            "groups: [{name: a, rules: [{record: a, expr: 'up[5m]'}]}]",
+            // duplicated groups
+            "groups: [{name: a, rules: []}, {name: a, rules: []}]",
+            // malformed yaml
+            "groups: {",
+        ];
+        for case in cases {
+            assert!(
+                parse_rule_groups("rules.yml", case, Duration::from_secs(60)).is_err(),
+                "{case}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_recording_rule_series() {
+        let rule = Rule::try_new(RuleConfig {
+            record: Some("job:up:sum".to_string()),
+            expr: "sum by (job) (up)".to_string(),
+            labels: labels(&[("source", "rule")]),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let series = rule.apply(vec![(labels(&[("job", "node")]), 3.0)], 1000);
+        assert_eq!(
+            series,
+            vec![new_series(
+                labels(&[
+                    ("__name__", "job:up:sum"),
+                    ("job", "node"),
+                    ("source", "rule")
+                ]),
+                3.0,
+                1000
+            )]
+        );
+    }
+
+    #[test]
+    fn test_alert_state_transition() {
+        let rule = Rule::try_new(RuleConfig {
+            alert: Some("HighLoad".to_string()),
+            expr: "load > 1".to_string(),
+            for_duration: Some(Duration::from_secs(60)),
+            labels: labels(&[("severity", "page")]),
+            annotations: labels(&[("summary", "load of {{ $labels.host }} is {{$value}}")]),
+            ..Default::default()
+        })
+        .unwrap();
+        let sample = |value| vec![(labels(&[("__name__", "load"), ("host", "a")]), value)];
+        let alert_labels = labels(&[
+            ("alertname", "HighLoad"),
+            ("host", "a"),
+            ("severity", "page"),
+        ]);
+
+        // becomes pending
+        let series = rule.apply(sample(2.0), 0);
+        assert_eq!(series.len(), 2);
+        let alert = rule.state.read().alerts[&alert_labels].clone();
+        assert_eq!(alert.state, AlertState::Pending);
+        assert_eq!(alert.annotations["summary"], "load of a is 2");
+        assert!(series[0].labels.contains(&Label {
+            name: ALERT_STATE_LABEL.to_string(),
+            value: "pending".to_string(),
+        }));
+
+        // still pending before `for` elapses
+        let _ = rule.apply(sample(3.0), 30_000);
+        let alert = rule.state.read().alerts[&alert_labels].clone();
+        assert_eq!(alert.state, AlertState::Pending);
+        assert_eq!(alert.active_at, 0);
+        assert_eq!(alert.annotations["summary"], "load of a is 3");
+
+        // fires after `for` elapses
+        let series = rule.apply(sample(3.0), 60_000);
+        assert_eq!(
+            rule.state.read().alerts[&alert_labels].state,
+            AlertState::Firing
+        );
+        assert_eq!(series[1].samples[0].value, 0.0);
+        let PromRule::Alerting(prom_rule) = rule.to_prom_rule() else {
+            unreachable!()
+        };
+        assert_eq!(prom_rule.state, "firing");
+        assert_eq!(prom_rule.alerts.len(), 1);
+
+        // resolved
+        let series = rule.apply(vec![], 90_000);
+        assert!(series.is_empty());
+        assert!(rule.state.read().alerts.is_empty());
+    }
+
+    #[derive(Default)]
+    struct MemoryAlertStateStore {
+        alerts: Mutex<BTreeMap<(String, String), String>>,
+    }
+
+    #[async_trait]
+    impl AlertStateStore for MemoryAlertStateStore {
+        async fn load(&self) -> Result<Vec<(String, String, String)>> {
+            Ok(self
+                .alerts
+                .lock()
+                .iter()
+                .map(|((group, rule), alerts)| (group.clone(), rule.clone(), alerts.clone()))
+                .collect())
+        }
+
+        async fn save(&self, group: &str, rule: &str, alerts: String) -> Result<()> {
+            let _ = self
+                .alerts
+                .lock()
+                .insert((group.to_string(), rule.to_string()), alerts);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_restore_alerts() {
+        let store: AlertStateStoreRef = Arc::new(MemoryAlertStateStore::default());
+        let new_groups = || {
+            parse_rule_groups("rules.yml", RULE_FILE, Duration::from_secs(60))
+                .unwrap()
+                .into_iter()
+                .map(Arc::new)
+                .collect::<Vec<_>>()
+        };
+        let sample = || vec![(labels(&[("job", "api")]), 20.0)];
+
+        let groups = new_groups();
+        for rule in &groups[0].rules {
+            let _ = rule.apply(sample(), 0);
+            rule.persist_alerts(&groups[0].name, &store).await.unwrap();
+        }
+        // Only alerting rules are persisted.
+        assert_eq!(store.load().await.unwrap().len(), 1);
+
+        // Restarts and restores the pending alert.
+        let groups = new_groups();
+        restore_alerts(&groups, &store).await.unwrap();
+        let rule = &groups[0].rules[1];
+        let alerts = rule.state.read().alerts.clone();
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts
+            .values()
+            .all(|alert| alert.state == AlertState::Pending && alert.active_at == 0));
+
+        // Fires once `for` elapses since the alert became active before the restart.
+        let _ = rule.apply(sample(), 120_000);
+        assert!(rule
+            .state
+            .read()
+            .alerts
+            .values()
+            .all(|alert| alert.state == AlertState::Firing));
+    }
+
+    #[test]
+    fn test_alert_without_for_fires_immediately() {
+        let mut alerts = BTreeMap::new();
+        update_alerts(
+            &mut alerts,
+            "Up",
+            &Labels::new(),
+            &Labels::new(),
+            Duration::ZERO,
+            vec![(Labels::new(), 1.0)],
+            1000,
+        );
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts
+            .values()
+            .all(|alert| alert.state == AlertState::Firing));
+    }
+}
@@ -50,6 +50,9 @@ pub const SCHEMA_LABEL_BYTES: &[u8] = b"__schema__";
 pub const PHYSICAL_TABLE_LABEL: &str = "__physical_table__";
 pub const PHYSICAL_TABLE_LABEL_BYTES: &[u8] = b"__physical_table__";
 
+/// The bucket label of conventional histograms, native histograms are stored with it.
+pub const LE_LABEL_BYTES: &[u8] = b"le";
+
 /// The same as `FIELD_COLUMN_MATCHER` in `promql` crate
 pub const FIELD_NAME_LABEL: &str = "__field__";
 
@@ -34,9 +34,11 @@ use crate::error::InternalSnafu;
 use crate::http::event::PipelineIngestRequest;
 use crate::http::PromValidationMode;
 use crate::pipeline::run_pipeline;
+use crate::prom_histogram::{format_le, PromHistogram};
 use crate::prom_row_builder::{PromCtx, TablesBuilder};
 use crate::prom_store::{
-    DATABASE_LABEL_BYTES, METRIC_NAME_LABEL_BYTES, PHYSICAL_TABLE_LABEL_BYTES, SCHEMA_LABEL_BYTES,
+    DATABASE_LABEL_BYTES, LE_LABEL_BYTES, METRIC_NAME_LABEL_BYTES, PHYSICAL_TABLE_LABEL_BYTES,
+    SCHEMA_LABEL_BYTES,
 };
 use crate::query_handler::PipelineHandlerRef;
 use crate::repeated_field::{Clear, RepeatedField};
@@ -154,6 +156,7 @@ pub struct PromTimeSeries {
 
     pub labels: RepeatedField<PromLabel>,
     pub samples: RepeatedField<Sample>,
+    pub histograms: RepeatedField<PromHistogram>,
 }
 
 impl Clear for PromTimeSeries {
@@ -161,6 +164,7 @@ impl Clear for PromTimeSeries {
         self.table_name.clear();
         self.labels.clear();
         self.samples.clear();
+        self.histograms.clear();
     }
 }
 
@@ -234,6 +238,20 @@ impl PromTimeSeries {
             }
             // todo(hl): exemplars are skipped temporarily
             3u32 => prost::encoding::skip_field(wire_type, tag, buf, Default::default()),
+            4u32 => {
+                let histogram = self.histograms.push_default();
+                merge(
+                    WireType::LengthDelimited,
+                    histogram,
+                    buf,
+                    Default::default(),
+                )
+                .map_err(|mut error| {
+                    error.push(STRUCT_NAME, "histograms");
+                    error
+                })?;
+                Ok(())
+            }
             _ => prost::encoding::skip_field(wire_type, tag, buf, Default::default()),
         }
     }
@@ -251,6 +269,14 @@ impl PromTimeSeries {
             physical_table: self.physical_table.take(),
         };
 
+        if !self.histograms.is_empty() {
+            self.add_histograms_to_table_data(table_builders, &prom_ctx, prom_validation_mode)?;
+            if self.samples.is_empty() {
+                self.table_name.clear();
+                return Ok(());
+            }
+        }
+
         let table_data = table_builders.get_or_create_table_builder(
             prom_ctx,
             std::mem::take(&mut self.table_name),
@@ -265,6 +291,68 @@ impl PromTimeSeries {
 
         Ok(())
     }
+
+    /// Writes native histograms as conventional histogram series, i.e. tables
+    /// `<name>_bucket` (with an extra `le` label), `<name>_count` and `<name>_sum`.
+    fn add_histograms_to_table_data(
+        &self,
+        table_builders: &mut TablesBuilder,
+        prom_ctx: &PromCtx,
+        prom_validation_mode: PromValidationMode,
+    ) -> Result<(), DecodeError> {
+        let label_num = self.labels.len();
+        let row_num = self.histograms.len();
+
+        let mut count_samples = Vec::with_capacity(row_num);
+        let mut sum_samples = Vec::with_capacity(row_num);
+        let bucket_table = table_builders.get_or_create_table_builder(
+            prom_ctx.clone(),
+            format!("{}_bucket", self.table_name),
+            label_num + 1,
+            row_num,
+        );
+        let mut bucket_labels = self.labels.as_slice().to_vec();
+        bucket_labels.push(PromLabel {
+            name: Bytes::from_static(LE_LABEL_BYTES),
+            value: Bytes::new(),
+        });
+        for histogram in self.histograms.iter() {
+            for (le, count) in histogram.to_conventional_buckets() {
+                if let Some(label) = bucket_labels.last_mut() {
+                    label.value = Bytes::from(format_le(le));
+                }
+                bucket_table.add_labels_and_samples(
+                    &bucket_labels,
+                    &[Sample {
+                        value: count,
+                        timestamp: histogram.timestamp,
+                    }],
+                    prom_validation_mode,
+                )?;
+            }
+            count_samples.push(Sample {
+                value: histogram.count(),
+                timestamp: histogram.timestamp,
+            });
+            sum_samples.push(Sample {
+                value: histogram.sum,
+                timestamp: histogram.timestamp,
+            });
+        }
+
+        for (suffix, samples) in [("_count", count_samples), ("_sum", sum_samples)] {
+            table_builders
+                .get_or_create_table_builder(
+                    prom_ctx.clone(),
+                    format!("{}{}", self.table_name, suffix),
+                    label_num,
+                    row_num,
+                )
+                .add_labels_and_samples(self.labels.as_slice(), &samples, prom_validation_mode)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Default, Debug)]
@@ -330,6 +418,7 @@ impl PromWriteRequest {
                     // clear state
                     self.series.labels.clear();
                     self.series.samples.clear();
+                    self.series.histograms.clear();
                 }
                 3u32 => {
                     // todo(hl): metadata are skipped.
@@ -389,6 +478,14 @@ impl PromSeriesProcessor {
         series: &mut PromTimeSeries,
         prom_validation_mode: PromValidationMode,
     ) -> Result<(), DecodeError> {
+        if !series.histograms.is_empty() {
+            warn!(
+                "Native histograms are not supported in pipeline, skipped {} histogram samples of {}",
+                series.histograms.len(),
+                series.table_name
+            );
+        }
+
         let mut vec_pipeline_map = Vec::new();
         let mut pipeline_map = BTreeMap::new();
         for l in series.labels.iter() {
@@ -553,6 +650,65 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_decode_native_histogram() {
+        use prost::encoding::{bytes, encode_key, encode_varint, message, WireType};
+
+        use crate::prom_histogram::{BucketSpan, HistogramCount, PromHistogram};
+
+        fn encode_label(name: &str, value: &str, buf: &mut Vec<u8>) {
+            let mut label = vec![];
+            bytes::encode(1, &name.as_bytes().to_vec(), &mut label);
+            bytes::encode(2, &value.as_bytes().to_vec(), &mut label);
+            encode_key(1, WireType::LengthDelimited, buf);
+            encode_varint(label.len() as u64, buf);
+            buf.extend(label);
+        }
+
+        let histogram = PromHistogram {
+            count: Some(HistogramCount::CountInt(3)),
+            sum: 5.0,
+            schema: 0,
+            positive_spans: vec![BucketSpan {
+                offset: 1,
+                length: 2,
+            }],
+            positive_deltas: vec![1, 1],
+            timestamp: 1000,
+            ..Default::default()
+        };
+        let mut series = vec![];
+        encode_label("__name__", "latency", &mut series);
+        encode_label("host", "h1", &mut series);
+        message::encode(4, &histogram, &mut series);
+
+        let mut data = vec![];
+        encode_key(1, WireType::LengthDelimited, &mut data);
+        encode_varint(series.len() as u64, &mut data);
+        data.extend(series);
+
+        let mut request = PromWriteRequest::default();
+        let mut p = PromSeriesProcessor::default_processor();
+        request
+            .merge(Bytes::from(data), PromValidationMode::Strict, &mut p)
+            .unwrap();
+        let req = request.as_row_insert_requests();
+        let mut tables = req
+            .all_req()
+            .map(|r| (r.table_name, r.rows.unwrap().rows.len()))
+            .collect::<Vec<_>>();
+        tables.sort();
+        // buckets: le=0 (zero bucket), le=2, le=4, le=+Inf
+        assert_eq!(
+            vec![
+                ("latency_bucket".to_string(), 4),
+                ("latency_count".to_string(), 1),
+                ("latency_sum".to_string(), 1),
+            ],
+            tables
+        );
+    }
+
     #[test]
     fn test_decode_string_strict_mode_valid_utf8() {
         let valid_utf8 = Bytes::from("hello world");
@@ -33,6 +33,7 @@ use api::prom_store::remote::ReadRequest;
 use api::v1::RowInsertRequests;
 use async_trait::async_trait;
 use catalog::CatalogManager;
+use common_base::AffectedRows;
 use common_query::Output;
 use datatypes::timestamp::TimestampNanosecond;
 use headers::HeaderValue;
@@ -43,6 +44,9 @@ use otel_arrow_rust::proto::opentelemetry::collector::metrics::v1::ExportMetrics
 use pipeline::{GreptimePipelineParams, Pipeline, PipelineInfo, PipelineVersion, PipelineWay};
 use serde_json::Value;
 use session::context::{QueryContext, QueryContextRef};
+use table::requests::InsertRequest as TableInsertRequest;
+use table::table_name::TableName;
+use table::TableRef;
 
 use crate::error::Result;
 use crate::http::jaeger::QueryTraceParams;
@@ -56,6 +60,7 @@ pub type OpenTelemetryProtocolHandlerRef = Arc<dyn OpenTelemetryProtocolHandler
 pub type PipelineHandlerRef = Arc<dyn PipelineHandler + Send + Sync>;
 pub type LogQueryHandlerRef = Arc<dyn LogQueryHandler + Send + Sync>;
 pub type JaegerQueryHandlerRef = Arc<dyn JaegerQueryHandler + Send + Sync>;
+pub type PostgresCopyHandlerRef = Arc<dyn PostgresCopyHandler + Send + Sync>;
 
 #[async_trait]
 pub trait InfluxdbLineProtocolHandler {
@@ -174,6 +179,30 @@ pub trait PipelineHandler {
         version: PipelineVersion,
         query_ctx: QueryContextRef,
     ) -> Result<(String, TimestampNanosecond)>;
+
+    /// List all versions of a pipeline by name, the latest first.
+    async fn list_pipeline_versions(
+        &self,
+        name: &str,
+        query_ctx: QueryContextRef,
+    ) -> Result<Vec<TimestampNanosecond>>;
+
+    /// Get the unified diff between two versions of a pipeline.
+    async fn diff_pipeline(
+        &self,
+        name: &str,
+        from: PipelineVersion,
+        to: PipelineVersion,
+        query_ctx: QueryContextRef,
+    ) -> Result<String>;
+
+    /// Roll back a pipeline to a previous version.
+    async fn rollback_pipeline(
+        &self,
+        name: &str,
+        version: PipelineVersion,
+        query_ctx: QueryContextRef,
+    ) -> Result<PipelineInfo>;
 }
 
 /// Handle log query requests.
@@ -221,3 +250,21 @@ pub trait JaegerQueryHandler {
         query_params: QueryTraceParams,
     ) -> Result<Output>;
 }
+
+/// Handle `COPY ... FROM STDIN` requests of the PostgreSQL protocol.
+#[async_trait]
+pub trait PostgresCopyHandler {
+    /// Gets the table that rows are copied into.
+    async fn get_table(
+        &self,
+        table_name: &TableName,
+        ctx: QueryContextRef,
+    ) -> Result<Option<TableRef>>;
+
+    /// Inserts a batch of copied rows.
+    async fn insert(
+        &self,
+        request: TableInsertRequest,
+        ctx: QueryContextRef,
+    ) -> Result<AffectedRows>;
+}
@@ -66,6 +66,7 @@ fn create_postgres_server(
 
     Ok(Box::new(PostgresServer::new(
         instance,
+        None,
         tls.should_force_tls(),
         tls_server_config,
         0,
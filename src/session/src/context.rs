@@ -12,10 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::hash_map::RandomState;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
+use std::hash::BuildHasher;
 use std::net::SocketAddr;
-use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, LazyLock, RwLock};
 use std::time::Duration;
 
 use api::v1::region::RegionRequestHeader;
@@ -38,6 +41,23 @@ use crate::{MutableInner, ReadPreference};
 pub type QueryContextRef = Arc<QueryContext>;
 pub type ConnInfoRef = Arc<ConnInfo>;
 
+/// Extension key to pass the query id to datanodes.
+const QUERY_ID_EXTENSION_KEY: &str = "greptime_query_id";
+/// Extension key to pass the process id to datanodes.
+const PROCESS_ID_EXTENSION_KEY: &str = "greptime_process_id";
+
+/// Returns a new query id.
+///
+/// The high 32 bits are random for each process so ids from different frontends
+/// are unlikely to collide in a datanode.
+fn next_query_id() -> u64 {
+    static PREFIX: LazyLock<u64> =
+        LazyLock::new(|| RandomState::new().hash_one(std::process::id()) << 32);
+    static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+
+    *PREFIX | NEXT_ID.fetch_add(1, Ordering::Relaxed) as u64
+}
+
 const CURSOR_COUNT_WARNING_LIMIT: usize = 10;
 
 #[derive(Debug, Builder, Clone)]
@@ -68,6 +88,8 @@ pub struct QueryContext {
     /// Process id for managing on-going queries
     #[builder(default)]
     process_id: u32,
+    /// Id of the query, which is the same in all nodes executing the query.
+    query_id: u64,
     /// Connection information
     #[builder(default)]
     conn_info: ConnInfo,
@@ -157,9 +179,24 @@ impl From<&RegionRequestHeader> for QueryContext {
 }
 
 impl From<api::v1::QueryContext> for QueryContext {
-    fn from(ctx: api::v1::QueryContext) -> Self {
+    fn from(mut ctx: api::v1::QueryContext) -> Self {
         let sequences = ctx.snapshot_seqs.as_ref();
-        QueryContextBuilder::default()
+        let mut builder = QueryContextBuilder::default();
+        if let Some(query_id) = ctx
+            .extensions
+            .remove(QUERY_ID_EXTENSION_KEY)
+            .and_then(|id| id.parse().ok())
+        {
+            builder = builder.query_id(query_id);
+        }
+        if let Some(process_id) = ctx
+            .extensions
+            .remove(PROCESS_ID_EXTENSION_KEY)
+            .and_then(|id| id.parse().ok())
+        {
+            builder = builder.process_id(process_id);
+        }
+        builder
             .current_catalog(ctx.current_catalog)
             .current_schema(ctx.current_schema)
             .timezone(parse_timezone(Some(&ctx.timezone)))
@@ -447,6 +484,27 @@ impl QueryContext {
         self.process_id
     }
 
+    /// Returns the id of the query.
+    pub fn query_id(&self) -> u64 {
+        self.query_id
+    }
+
+    /// Converts the context to protobuf to execute the query in datanodes.
+    ///
+    /// Unlike the [From] conversion, it also passes the query id and the process id.
+    pub fn to_query_pb(&self) -> api::v1::QueryContext {
+        let mut ctx = api::v1::QueryContext::from(self);
+        ctx.extensions.insert(
+            QUERY_ID_EXTENSION_KEY.to_string(),
+            self.query_id.to_string(),
+        );
+        ctx.extensions.insert(
+            PROCESS_ID_EXTENSION_KEY.to_string(),
+            self.process_id.to_string(),
+        );
+        ctx
+    }
+
     /// Get client information
     pub fn conn_info(&self) -> &ConnInfo {
         &self.conn_info
@@ -481,6 +539,7 @@ impl QueryContextBuilder {
                 .unwrap_or_else(|| Arc::new(ConfigurationVariables::default())),
             channel,
             process_id: self.process_id.unwrap_or_default(),
+            query_id: self.query_id.unwrap_or_else(next_query_id),
             conn_info: self.conn_info.unwrap_or_default(),
             protocol_ctx: self.protocol_ctx.unwrap_or_default(),
         }
@@ -686,4 +745,24 @@ mod test {
         let context = QueryContext::with(DEFAULT_CATALOG_NAME, "test");
         assert_eq!("test", context.get_db_string());
     }
+
+    #[test]
+    fn test_context_to_pb() {
+        let context = QueryContextBuilder::default()
+            .process_id(42)
+            .set_extension("key".to_string(), "value".to_string())
+            .build();
+        assert_ne!(context.query_id(), QueryContext::arc().query_id());
+
+        let decoded = QueryContext::from(context.to_query_pb());
+        assert_eq!(context.query_id(), decoded.query_id());
+        assert_eq!(42, decoded.process_id());
+        assert_eq!(context.extensions(), decoded.extensions());
+
+        let pb = api::v1::QueryContext::from(&context);
+        assert_eq!(context.extensions(), pb.extensions);
+        let decoded = QueryContext::from(pb);
+        assert_ne!(context.query_id(), decoded.query_id());
+        assert_eq!(0, decoded.process_id());
+    }
 }
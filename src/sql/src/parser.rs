@@ -23,7 +23,7 @@ use sqlparser::tokenizer::{Token, TokenWithSpan};
 
 use crate::ast::{Expr, ObjectName};
 use crate::error::{self, Result, SyntaxSnafu};
-use crate::parsers::tql_parser;
+use crate::parsers::{backup_parser, tql_parser};
 use crate::statements::kill::Kill;
 use crate::statements::statement::Statement;
 use crate::statements::transform_statements;
@@ -142,6 +142,8 @@ impl ParserContext<'_> {
 
                 Keyword::DELETE => self.parse_delete(),
 
+                Keyword::UPDATE => self.parse_update(),
+
                 Keyword::DESCRIBE | Keyword::DESC => {
                     let _ = self.parser.next_token();
                     self.parse_describe()
@@ -167,12 +169,26 @@ impl ParserContext<'_> {
 
                 Keyword::ADMIN => self.parse_admin_command(),
 
+                Keyword::GRANT => self.parse_grant(),
+
+                Keyword::REVOKE => self.parse_revoke(),
+
                 Keyword::NoKeyword
                     if w.quote_style.is_none() && w.value.to_uppercase() == tql_parser::TQL =>
                 {
                     self.parse_tql(false)
                 }
 
+                _ if w.quote_style.is_none() && w.value.to_uppercase() == backup_parser::BACKUP => {
+                    self.parse_backup()
+                }
+
+                _ if w.quote_style.is_none()
+                    && w.value.to_uppercase() == backup_parser::RESTORE =>
+                {
+                    self.parse_restore()
+                }
+
                 Keyword::DECLARE => self.parse_declare_cursor(),
 
                 Keyword::FETCH => self.parse_fetch_cursor(),
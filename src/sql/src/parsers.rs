@@ -12,8 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub(crate) mod access_control_parser;
 pub(crate) mod admin_parser;
 mod alter_parser;
+pub(crate) mod backup_parser;
 pub(crate) mod copy_parser;
 pub(crate) mod create_parser;
 pub(crate) mod cursor_parser;
@@ -31,5 +33,6 @@ pub(crate) mod set_var_parser;
 pub(crate) mod show_parser;
 pub(crate) mod tql_parser;
 pub(crate) mod truncate_parser;
+pub(crate) mod update_parser;
 pub mod utils;
 pub mod with_tql_parser;
@@ -0,0 +1,372 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snafu::ResultExt;
+use sqlparser::ast::{Ident, ObjectName};
+use sqlparser::keywords::Keyword;
+use sqlparser::tokenizer::Token;
+
+use crate::error::{self, Result};
+use crate::parser::ParserContext;
+use crate::statements::access_control::{
+    CreateRole, CreateUser, DropRole, DropUser, Grant, Grantable, Privilege, PrivilegeLevel, Revoke,
+};
+use crate::statements::statement::Statement;
+
+const IDENTIFIED: &str = "IDENTIFIED";
+
+/// Parses account and privilege statements:
+/// - `CREATE USER [IF NOT EXISTS] <user> [IDENTIFIED BY '<password>']`
+/// - `DROP USER [IF EXISTS] <user>`
+/// - `CREATE ROLE [IF NOT EXISTS] <role>`
+/// - `DROP ROLE [IF EXISTS] <role>`
+/// - `GRANT <privileges> ON <level> TO <grantee>` and `GRANT <role> TO <user>`
+/// - `REVOKE <privileges> ON <level> FROM <grantee>` and `REVOKE <role> FROM <user>`
+impl ParserContext<'_> {
+    pub(crate) fn parse_create_user(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let if_not_exists = self.parse_if_not_exist()?;
+        let name = self.parse_account_name("a user name")?;
+
+        let password = if self.parse_identified_by() {
+            let password =
+                self.parser
+                    .parse_literal_string()
+                    .with_context(|_| error::UnexpectedSnafu {
+                        expected: "a password string literal",
+                        actual: self.peek_token_as_string(),
+                    })?;
+            Some(password)
+        } else {
+            None
+        };
+
+        Ok(Statement::CreateUser(CreateUser {
+            name,
+            password,
+            if_not_exists,
+        }))
+    }
+
+    pub(crate) fn parse_drop_user(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+        let name = self.parse_account_name("a user name")?;
+
+        Ok(Statement::DropUser(DropUser { name, if_exists }))
+    }
+
+    pub(crate) fn parse_create_role(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let if_not_exists = self.parse_if_not_exist()?;
+        let name = self.parse_account_name("a role name")?;
+
+        Ok(Statement::CreateRole(CreateRole {
+            name,
+            if_not_exists,
+        }))
+    }
+
+    pub(crate) fn parse_drop_role(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+        let name = self.parse_account_name("a role name")?;
+
+        Ok(Statement::DropRole(DropRole { name, if_exists }))
+    }
+
+    pub(crate) fn parse_grant(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let grantable = self.parse_grantable()?;
+        self.parser
+            .expect_keyword(Keyword::TO)
+            .context(error::UnexpectedSnafu {
+                expected: "TO",
+                actual: self.peek_token_as_string(),
+            })?;
+        let grantee = self.parse_account_name("a grantee")?;
+
+        Ok(Statement::Grant(Grant { grantable, grantee }))
+    }
+
+    pub(crate) fn parse_revoke(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let grantable = self.parse_grantable()?;
+        self.parser
+            .expect_keyword(Keyword::FROM)
+            .context(error::UnexpectedSnafu {
+                expected: "FROM",
+                actual: self.peek_token_as_string(),
+            })?;
+        let grantee = self.parse_account_name("a grantee")?;
+
+        Ok(Statement::Revoke(Revoke { grantable, grantee }))
+    }
+
+    fn parse_account_name(&mut self, expected: &str) -> Result<Ident> {
+        let name = self
+            .parser
+            .parse_identifier()
+            .with_context(|_| error::UnexpectedSnafu {
+                expected,
+                actual: self.peek_token_as_string(),
+            })?;
+        Ok(Self::canonicalize_identifier(name))
+    }
+
+    /// Consumes `IDENTIFIED BY` if present.
+    fn parse_identified_by(&mut self) -> bool {
+        match self.parser.peek_token().token {
+            Token::Word(w) if w.value.eq_ignore_ascii_case(IDENTIFIED) => {
+                let _ = self.parser.next_token();
+                self.parser.parse_keyword(Keyword::BY)
+            }
+            _ => false,
+        }
+    }
+
+    fn parse_grantable(&mut self) -> Result<Grantable> {
+        if self.parser.parse_keyword(Keyword::ALL) {
+            let _ = self.parser.parse_keyword(Keyword::PRIVILEGES);
+            let level = self.parse_privilege_level()?;
+            return Ok(Grantable::Privileges {
+                privileges: Privilege::ALL.to_vec(),
+                level,
+            });
+        }
+
+        if Self::token_to_privilege(&self.parser.peek_token().token).is_none() {
+            let role = self.parse_account_name("privileges or a role name")?;
+            return Ok(Grantable::Role(role));
+        }
+
+        let mut privileges = vec![];
+        loop {
+            let token = self.parser.next_token();
+            let Some(privilege) = Self::token_to_privilege(&token.token) else {
+                return error::UnexpectedSnafu {
+                    expected: "a privilege",
+                    actual: token.to_string(),
+                }
+                .fail();
+            };
+            if !privileges.contains(&privilege) {
+                privileges.push(privilege);
+            }
+            if !self.parser.consume_token(&Token::Comma) {
+                break;
+            }
+        }
+        let level = self.parse_privilege_level()?;
+
+        Ok(Grantable::Privileges { privileges, level })
+    }
+
+    fn token_to_privilege(token: &Token) -> Option<Privilege> {
+        let Token::Word(w) = token else {
+            return None;
+        };
+        match w.keyword {
+            Keyword::SELECT => Some(Privilege::Select),
+            Keyword::INSERT => Some(Privilege::Insert),
+            Keyword::DELETE => Some(Privilege::Delete),
+            Keyword::CREATE => Some(Privilege::Create),
+            Keyword::ALTER => Some(Privilege::Alter),
+            Keyword::DROP => Some(Privilege::Drop),
+            _ => None,
+        }
+    }
+
+    /// Parses `ON *.*`, `ON DATABASE <db>`, `ON <db>.*` or `ON [TABLE] [<db>.]<table>`.
+    fn parse_privilege_level(&mut self) -> Result<PrivilegeLevel> {
+        self.parser
+            .expect_keyword(Keyword::ON)
+            .context(error::UnexpectedSnafu {
+                expected: "ON",
+                actual: self.peek_token_as_string(),
+            })?;
+
+        if self.parser.consume_token(&Token::Mul) {
+            self.expect_any_suffix()?;
+            return Ok(PrivilegeLevel::Global);
+        }
+
+        if self.parser.parse_keyword(Keyword::DATABASE) {
+            let database = self.parse_account_name("a database name")?;
+            return Ok(PrivilegeLevel::Database(database));
+        }
+
+        let _ = self.parser.parse_keyword(Keyword::TABLE);
+        let first = self.parse_account_name("a database or table name")?;
+        if !self.parser.consume_token(&Token::Period) {
+            return Ok(PrivilegeLevel::Table(ObjectName::from(vec![first])));
+        }
+        if self.parser.consume_token(&Token::Mul) {
+            return Ok(PrivilegeLevel::Database(first));
+        }
+        let table = self.parse_account_name("a table name")?;
+
+        Ok(PrivilegeLevel::Table(ObjectName::from(vec![first, table])))
+    }
+
+    /// Expects the `.*` after a `*`.
+    fn expect_any_suffix(&mut self) -> Result<()> {
+        if self.parser.consume_token(&Token::Period) && self.parser.consume_token(&Token::Mul) {
+            return Ok(());
+        }
+        error::UnexpectedSnafu {
+            expected: "*.*",
+            actual: self.peek_token_as_string(),
+        }
+        .fail()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::GreptimeDbDialect;
+    use crate::parser::ParseOptions;
+
+    fn parse(sql: &str) -> Statement {
+        let mut stmts =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap();
+        assert_eq!(1, stmts.len());
+        stmts.pop().unwrap()
+    }
+
+    #[test]
+    fn test_parse_user_and_role() {
+        let stmt = parse("CREATE USER IF NOT EXISTS Alice IDENTIFIED BY 'secret'");
+        assert_eq!(
+            Statement::CreateUser(CreateUser {
+                name: Ident::new("alice"),
+                password: Some("secret".to_string()),
+                if_not_exists: true,
+            }),
+            stmt
+        );
+        assert_eq!(
+            "CREATE USER IF NOT EXISTS alice IDENTIFIED BY '******'",
+            stmt.to_string()
+        );
+
+        let stmt = parse("CREATE USER bob");
+        assert_eq!("CREATE USER bob", stmt.to_string());
+
+        let stmt = parse("DROP USER IF EXISTS bob");
+        assert_eq!(
+            Statement::DropUser(DropUser {
+                name: Ident::new("bob"),
+                if_exists: true,
+            }),
+            stmt
+        );
+
+        let stmt = parse("CREATE ROLE reader");
+        assert_eq!(
+            Statement::CreateRole(CreateRole {
+                name: Ident::new("reader"),
+                if_not_exists: false,
+            }),
+            stmt
+        );
+        assert_eq!("DROP ROLE reader", parse("DROP ROLE reader").to_string());
+    }
+
+    #[test]
+    fn test_parse_grant() {
+        let stmt = parse("GRANT SELECT, INSERT ON public.* TO alice");
+        assert_eq!(
+            Statement::Grant(Grant {
+                grantable: Grantable::Privileges {
+                    privileges: vec![Privilege::Select, Privilege::Insert],
+                    level: PrivilegeLevel::Database(Ident::new("public")),
+                },
+                grantee: Ident::new("alice"),
+            }),
+            stmt
+        );
+        assert_eq!(
+            "GRANT SELECT, INSERT ON public.* TO alice",
+            stmt.to_string()
+        );
+
+        let stmt = parse("GRANT ALL PRIVILEGES ON *.* TO admin");
+        assert_eq!(
+            "GRANT SELECT, INSERT, DELETE, CREATE, ALTER, DROP ON *.* TO admin",
+            stmt.to_string()
+        );
+
+        let stmt = parse("GRANT DROP ON TABLE public.monitor TO alice");
+        assert_eq!(
+            Statement::Grant(Grant {
+                grantable: Grantable::Privileges {
+                    privileges: vec![Privilege::Drop],
+                    level: PrivilegeLevel::Table(ObjectName::from(vec![
+                        Ident::new("public"),
+                        Ident::new("monitor")
+                    ])),
+                },
+                grantee: Ident::new("alice"),
+            }),
+            stmt
+        );
+
+        let stmt = parse("GRANT CREATE ON DATABASE test TO alice");
+        assert_eq!("GRANT CREATE ON test.* TO alice", stmt.to_string());
+
+        let stmt = parse("GRANT reader TO alice");
+        assert_eq!(
+            Statement::Grant(Grant {
+                grantable: Grantable::Role(Ident::new("reader")),
+                grantee: Ident::new("alice"),
+            }),
+            stmt
+        );
+
+        assert!(ParserContext::create_with_dialect(
+            "GRANT SELECT ON *.t TO alice",
+            &GreptimeDbDialect {},
+            ParseOptions::default()
+        )
+        .is_err());
+        assert!(ParserContext::create_with_dialect(
+            "GRANT SELECT ON t alice",
+            &GreptimeDbDialect {},
+            ParseOptions::default()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_revoke() {
+        let stmt = parse("REVOKE DELETE ON monitor FROM alice");
+        assert_eq!(
+            Statement::Revoke(Revoke {
+                grantable: Grantable::Privileges {
+                    privileges: vec![Privilege::Delete],
+                    level: PrivilegeLevel::Table(ObjectName::from(vec![Ident::new("monitor")])),
+                },
+                grantee: Ident::new("alice"),
+            }),
+            stmt
+        );
+        assert_eq!("REVOKE DELETE ON monitor FROM alice", stmt.to_string());
+
+        let stmt = parse("REVOKE reader FROM alice");
+        assert_eq!("REVOKE reader FROM alice", stmt.to_string());
+    }
+}
@@ -125,6 +125,8 @@ impl ParserContext<'_> {
                     self.parse_alter_table_modify()?
                 } else if w.value.eq_ignore_ascii_case("UNSET") {
                     self.parse_alter_table_unset()?
+                } else if w.value.eq_ignore_ascii_case("SPLIT") {
+                    self.parse_alter_table_split_partition()?
                 } else {
                     match w.keyword {
                         Keyword::ADD => self.parse_alter_table_add()?,
@@ -138,6 +140,7 @@ impl ParserContext<'_> {
                             );
                             AlterTableOperation::DropColumn { name }
                         }
+                        Keyword::MERGE => self.parse_alter_table_merge_partition()?,
                         Keyword::RENAME => {
                             let _ = self.parser.next_token();
                             let new_table_name_obj_raw =
@@ -178,6 +181,53 @@ impl ParserContext<'_> {
         Ok(AlterTable::new(table_name, alter_operation))
     }
 
+    /// Parses `SPLIT PARTITION (<from_expr>) INTO (<into_expr>, ...)`.
+    fn parse_alter_table_split_partition(&mut self) -> Result<AlterTableOperation> {
+        let _ = self.parser.next_token();
+        self.parser
+            .expect_keyword(Keyword::PARTITION)
+            .context(error::SyntaxSnafu)?;
+        let mut from = self.parse_comma_separated(Self::parse_partition_entry)?;
+        ensure!(
+            from.len() == 1,
+            error::InvalidSqlSnafu {
+                msg: "SPLIT PARTITION expects exactly one partition to split",
+            }
+        );
+        self.parser
+            .expect_keyword(Keyword::INTO)
+            .context(error::SyntaxSnafu)?;
+        let into = self.parse_comma_separated(Self::parse_partition_entry)?;
+        ensure!(
+            into.len() >= 2,
+            error::InvalidSqlSnafu {
+                msg: "SPLIT PARTITION expects at least two partitions to split into",
+            }
+        );
+
+        Ok(AlterTableOperation::SplitPartition {
+            from: from.remove(0),
+            into,
+        })
+    }
+
+    /// Parses `MERGE PARTITION (<from_expr>, ...)`.
+    fn parse_alter_table_merge_partition(&mut self) -> Result<AlterTableOperation> {
+        let _ = self.parser.next_token();
+        self.parser
+            .expect_keyword(Keyword::PARTITION)
+            .context(error::SyntaxSnafu)?;
+        let from = self.parse_comma_separated(Self::parse_partition_entry)?;
+        ensure!(
+            from.len() >= 2,
+            error::InvalidSqlSnafu {
+                msg: "MERGE PARTITION expects at least two partitions to merge",
+            }
+        );
+
+        Ok(AlterTableOperation::MergePartition { from })
+    }
+
     fn parse_alter_table_unset(&mut self) -> Result<AlterTableOperation> {
         let _ = self.parser.next_token();
         let keys = self
@@ -1342,4 +1392,69 @@ mod tests {
             "Invalid SQL syntax: sql parser error: Unexpected keyword, expect SET, got: `DROP`"
         );
     }
+
+    #[test]
+    fn test_parse_alter_split_partition() {
+        let sql = "ALTER TABLE test_table SPLIT PARTITION (a < 10) INTO (a < 5, a >= 5 AND a < 10)";
+        let mut result =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap();
+        assert_eq!(1, result.len());
+
+        let Statement::AlterTable(alter_table) = result.remove(0) else {
+            unreachable!()
+        };
+        assert_eq!("test_table", alter_table.table_name().to_string());
+        let AlterTableOperation::SplitPartition { from, into } = alter_table.alter_operation()
+        else {
+            unreachable!()
+        };
+        assert_eq!("a < 10", from.to_string());
+        assert_eq!(
+            vec!["a < 5", "a >= 5 AND a < 10"],
+            into.iter().map(|x| x.to_string()).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "ALTER TABLE test_table SPLIT PARTITION (a < 10) INTO (a < 5, a >= 5 AND a < 10)",
+            alter_table.to_string()
+        );
+
+        let sql = "ALTER TABLE test_table SPLIT PARTITION (a < 10) INTO (a < 10)";
+        let result =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap_err();
+        assert_eq!(
+            "Invalid SQL, error: SPLIT PARTITION expects at least two partitions to split into",
+            result.output_msg()
+        );
+    }
+
+    #[test]
+    fn test_parse_alter_merge_partition() {
+        let sql = "ALTER TABLE test_table MERGE PARTITION (a < 5, a >= 5 AND a < 10)";
+        let mut result =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap();
+        assert_eq!(1, result.len());
+
+        let Statement::AlterTable(alter_table) = result.remove(0) else {
+            unreachable!()
+        };
+        let AlterTableOperation::MergePartition { from } = alter_table.alter_operation() else {
+            unreachable!()
+        };
+        assert_eq!(2, from.len());
+        assert_eq!(
+            "ALTER TABLE test_table MERGE PARTITION (a < 5, a >= 5 AND a < 10)",
+            alter_table.to_string()
+        );
+
+        let sql = "ALTER TABLE test_table MERGE PARTITION (a < 5)";
+        assert!(ParserContext::create_with_dialect(
+            sql,
+            &GreptimeDbDialect {},
+            ParseOptions::default()
+        )
+        .is_err());
+    }
 }
@@ -0,0 +1,171 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use snafu::ResultExt;
+use sqlparser::ast::ObjectName;
+use sqlparser::keywords::Keyword;
+
+use crate::error::{self, Result};
+use crate::parser::ParserContext;
+use crate::statements::backup::{BackupDatabase, RestoreDatabase};
+use crate::statements::statement::Statement;
+use crate::statements::OptionMap;
+use crate::util::parse_option_string;
+
+pub const BACKUP: &str = "BACKUP";
+pub const RESTORE: &str = "RESTORE";
+
+/// The parsed `db [TO|FROM] 'location' [WITH (..)] [CONNECTION (..)]`.
+struct BackupArgs {
+    database_name: ObjectName,
+    location: String,
+    with: OptionMap,
+    connection: OptionMap,
+}
+
+// BACKUP DATABASE db TO 's3://bucket/backups/' WITH (name = 'b1');
+// RESTORE DATABASE db FROM 's3://bucket/backups/' WITH (name = 'b1');
+impl ParserContext<'_> {
+    pub(crate) fn parse_backup(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let args = self.parse_backup_args(Keyword::TO)?;
+
+        Ok(Statement::BackupDatabase(BackupDatabase {
+            database_name: args.database_name,
+            location: args.location,
+            with: args.with,
+            connection: args.connection,
+        }))
+    }
+
+    pub(crate) fn parse_restore(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let args = self.parse_backup_args(Keyword::FROM)?;
+
+        Ok(Statement::RestoreDatabase(RestoreDatabase {
+            database_name: args.database_name,
+            location: args.location,
+            with: args.with,
+            connection: args.connection,
+        }))
+    }
+
+    fn parse_backup_args(&mut self, direction: Keyword) -> Result<BackupArgs> {
+        self.parser
+            .expect_keyword(Keyword::DATABASE)
+            .context(error::SyntaxSnafu)?;
+        let database_name = self
+            .parse_object_name()
+            .with_context(|_| error::UnexpectedSnafu {
+                expected: "a database name",
+                actual: self.peek_token_as_string(),
+            })?;
+        let database_name = Self::canonicalize_object_name(database_name);
+
+        self.parser
+            .expect_keyword(direction)
+            .context(error::SyntaxSnafu)?;
+        let location =
+            self.parser
+                .parse_literal_string()
+                .with_context(|_| error::UnexpectedSnafu {
+                    expected: "a backup location",
+                    actual: self.peek_token_as_string(),
+                })?;
+
+        let with = self
+            .parser
+            .parse_options(Keyword::WITH)
+            .context(error::SyntaxSnafu)?
+            .into_iter()
+            .map(parse_option_string)
+            .collect::<Result<HashMap<_, _>>>()?;
+        let connection = self
+            .parser
+            .parse_options(Keyword::CONNECTION)
+            .context(error::SyntaxSnafu)?
+            .into_iter()
+            .map(parse_option_string)
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        Ok(BackupArgs {
+            database_name,
+            location,
+            with: with.into(),
+            connection: connection.into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::GreptimeDbDialect;
+    use crate::parser::ParseOptions;
+
+    #[test]
+    fn test_parse_backup_database() {
+        let sql = "BACKUP DATABASE public TO 's3://bucket/backups/' WITH (name = 'b1', base = 'b0') CONNECTION (REGION = 'us-west-2')";
+        let mut result =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap();
+        assert_eq!(1, result.len());
+
+        let Statement::BackupDatabase(backup) = result.remove(0) else {
+            unreachable!()
+        };
+        assert_eq!("public", backup.database_name.to_string());
+        assert_eq!("s3://bucket/backups/", backup.location);
+        assert_eq!(Some("b1"), backup.with.get("name").map(String::as_str));
+        assert_eq!(Some("b0"), backup.with.get("base").map(String::as_str));
+        assert_eq!(
+            Some("us-west-2"),
+            backup.connection.get("region").map(String::as_str)
+        );
+        assert_eq!(
+            "BACKUP DATABASE public TO 's3://bucket/backups/' WITH (base = 'b0', name = 'b1') CONNECTION (region = 'us-west-2')",
+            backup.to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_restore_database() {
+        let sql = "restore database Public from '/tmp/backups/' with (name = 'b1')";
+        let mut result =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap();
+        assert_eq!(1, result.len());
+
+        let Statement::RestoreDatabase(restore) = result.remove(0) else {
+            unreachable!()
+        };
+        assert_eq!("public", restore.database_name.to_string());
+        assert_eq!("/tmp/backups/", restore.location);
+        assert_eq!(Some("b1"), restore.with.get("name").map(String::as_str));
+        assert!(restore.connection.is_empty());
+    }
+
+    #[test]
+    fn test_parse_backup_database_error() {
+        let sql = "BACKUP TABLE t TO '/tmp/backups/'";
+        ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+            .unwrap_err();
+
+        let sql = "RESTORE DATABASE public TO '/tmp/backups/'";
+        ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+            .unwrap_err();
+    }
+}
@@ -73,6 +73,10 @@ impl<'a> ParserContext<'a> {
 
                 Keyword::EXTERNAL => self.parse_create_external_table(),
 
+                Keyword::USER => self.parse_create_user(),
+
+                Keyword::ROLE => self.parse_create_role(),
+
                 Keyword::OR => {
                     let _ = self.parser.next_token();
                     self.parser
@@ -413,7 +417,7 @@ impl<'a> ParserContext<'a> {
         }
     }
 
-    fn parse_if_not_exist(&mut self) -> Result<bool> {
+    pub(crate) fn parse_if_not_exist(&mut self) -> Result<bool> {
         match self.parser.peek_token().token {
             Token::Word(w) if Keyword::IF != w.keyword => return Ok(false),
             _ => {}
@@ -477,12 +481,12 @@ impl<'a> ParserContext<'a> {
         Ok(Some(Partitions { column_list, exprs }))
     }
 
-    fn parse_partition_entry(&mut self) -> Result<Expr> {
+    pub(crate) fn parse_partition_entry(&mut self) -> Result<Expr> {
         self.parser.parse_expr().context(error::SyntaxSnafu)
     }
 
     /// Parse a comma-separated list wrapped by "()", and of which all items accepted by `F`
-    fn parse_comma_separated<T, F>(&mut self, mut f: F) -> Result<Vec<T>>
+    pub(crate) fn parse_comma_separated<T, F>(&mut self, mut f: F) -> Result<Vec<T>>
     where
         F: FnMut(&mut ParserContext<'a>) -> Result<T>,
     {
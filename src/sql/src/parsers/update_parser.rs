@@ -0,0 +1,124 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snafu::{ensure, ResultExt};
+use sqlparser::ast::{AssignmentTarget, Statement as SpStatement, TableFactor};
+
+use crate::error::{self, Result};
+use crate::parser::ParserContext;
+use crate::statements::statement::Statement;
+use crate::statements::update::Update;
+
+/// UPDATE statement parser implementation
+impl ParserContext<'_> {
+    pub(crate) fn parse_update(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let spstatement = self.parser.parse_update().context(error::SyntaxSnafu)?;
+
+        match &spstatement {
+            SpStatement::Update {
+                table,
+                assignments,
+                from,
+                returning,
+                ..
+            } => {
+                ensure!(
+                    matches!(table.relation, TableFactor::Table { .. }) && table.joins.is_empty(),
+                    error::UnsupportedSnafu {
+                        keyword: "UPDATE with JOIN or derived table",
+                    }
+                );
+                ensure!(
+                    from.is_none(),
+                    error::UnsupportedSnafu {
+                        keyword: "UPDATE ... FROM",
+                    }
+                );
+                ensure!(
+                    returning.is_none(),
+                    error::UnsupportedSnafu {
+                        keyword: "UPDATE ... RETURNING",
+                    }
+                );
+                ensure!(
+                    assignments
+                        .iter()
+                        .all(|a| matches!(a.target, AssignmentTarget::ColumnName(_))),
+                    error::UnsupportedSnafu {
+                        keyword: "UPDATE with tuple assignment",
+                    }
+                );
+
+                Ok(Statement::Update(Box::new(Update { inner: spstatement })))
+            }
+            unexp => error::UnsupportedSnafu {
+                keyword: unexp.to_string(),
+            }
+            .fail(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use super::*;
+    use crate::dialect::GreptimeDbDialect;
+    use crate::parser::ParseOptions;
+
+    #[test]
+    pub fn test_parse_update() {
+        let sql = r"update my_table set v1 = v1 + 1, v2 = 'a' where host = 'h1' and ts > 1000;";
+        let mut result =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap();
+        assert_eq!(1, result.len());
+        assert_matches!(result[0], Statement::Update { .. });
+
+        let Statement::Update(update) = result.remove(0) else {
+            unreachable!()
+        };
+        assert_eq!("my_table", update.table_name().to_string());
+        assert_eq!(2, update.assignments().len());
+        assert_eq!(
+            "host = 'h1' AND ts > 1000",
+            update.selection().unwrap().to_string()
+        );
+        assert_eq!(
+            "UPDATE my_table SET v1 = v1 + 1, v2 = 'a' WHERE host = 'h1' AND ts > 1000",
+            update.inner.to_string()
+        );
+    }
+
+    #[test]
+    pub fn test_parse_unsupported_update() {
+        let sqls = [
+            "update t1 set v = t2.v from t2 where t1.k = t2.k",
+            "update t1 set v = 1 returning v",
+            "update t1 join t2 on t1.k = t2.k set t1.v = 1",
+            "update t1 set (a, b) = (1, 2)",
+            "update t1 set",
+        ];
+        for sql in sqls {
+            let result = ParserContext::create_with_dialect(
+                sql,
+                &GreptimeDbDialect {},
+                ParseOptions::default(),
+            );
+            assert!(result.is_err(), "sql: {sql}, result is: {result:?}");
+        }
+    }
+}
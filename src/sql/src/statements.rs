@@ -12,8 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod access_control;
 pub mod admin;
 pub mod alter;
+pub mod backup;
 pub mod copy;
 pub mod create;
 pub mod cursor;
@@ -31,6 +33,7 @@ pub mod statement;
 pub mod tql;
 pub(crate) mod transform;
 pub mod truncate;
+pub mod update;
 
 use api::helper::ColumnDataTypeWrapper;
 use api::v1::SemanticType;
@@ -38,10 +41,12 @@ use common_sql::default_constraint::parse_column_default_constraint;
 use common_time::timezone::Timezone;
 use datatypes::prelude::ConcreteDataType;
 use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema, COMMENT_KEY};
-use datatypes::types::TimestampType;
+use datatypes::types::{MapType, TimestampType};
 use datatypes::value::Value;
 use snafu::ResultExt;
 use sqlparser::ast::{ExactNumberInfo, Ident};
+use sqlparser::dialect::GenericDialect;
+use sqlparser::parser::Parser;
 
 use crate::ast::{
     ColumnDef, ColumnOption, DataType as SqlDataType, ObjectNamePartExt, TimezoneInfo,
@@ -55,8 +60,12 @@ use crate::error::{
 use crate::statements::create::Column;
 pub use crate::statements::option_map::OptionMap;
 pub(crate) use crate::statements::transform::transform_statements;
+use crate::statements::transform::type_alias::get_data_type_by_alias_name;
 
 const VECTOR_TYPE_NAME: &str = "VECTOR";
+const MAP_TYPE_NAME: &str = "MAP";
+const INET_TYPE_NAME: &str = "INET";
+const IP_TYPE_NAME: &str = "IP";
 
 pub fn value_to_sql_value(val: &Value) -> Result<SqlValue> {
     Ok(match val {
@@ -269,6 +278,39 @@ pub fn sql_data_type_to_concrete_data_type(data_type: &SqlDataType) -> Result<Co
             })?;
             Ok(ConcreteDataType::vector_datatype(dim))
         }
+        SqlDataType::Uuid => Ok(ConcreteDataType::uuid_datatype()),
+        SqlDataType::Map(k, v) => {
+            let key_type = sql_data_type_to_concrete_data_type(k)?;
+            let value_type = sql_data_type_to_concrete_data_type(v)?;
+            map_datatype(data_type, key_type, value_type)
+        }
+        // Map type, e.g. `MAP(STRING, UINT64)`
+        SqlDataType::Custom(name, d)
+            if name.0.as_slice().len() == 1
+                && name.0.as_slice()[0]
+                    .to_string_unquoted()
+                    .to_ascii_uppercase()
+                    == MAP_TYPE_NAME
+                && d.len() == 2 =>
+        {
+            let key_type = map_entry_type_from_name(data_type, &d[0])?;
+            let value_type = map_entry_type_from_name(data_type, &d[1])?;
+            map_datatype(data_type, key_type, value_type)
+        }
+        // Ip type, `INET` or `IP`
+        SqlDataType::Custom(name, d)
+            if name.0.as_slice().len() == 1
+                && matches!(
+                    name.0.as_slice()[0]
+                        .to_string_unquoted()
+                        .to_ascii_uppercase()
+                        .as_str(),
+                    INET_TYPE_NAME | IP_TYPE_NAME
+                )
+                && d.is_empty() =>
+        {
+            Ok(ConcreteDataType::ip_datatype())
+        }
         _ => error::SqlTypeNotSupportedSnafu {
             t: data_type.clone(),
         }
@@ -276,6 +318,38 @@ pub fn sql_data_type_to_concrete_data_type(data_type: &SqlDataType) -> Result<Co
     }
 }
 
+fn map_datatype(
+    data_type: &SqlDataType,
+    key_type: ConcreteDataType,
+    value_type: ConcreteDataType,
+) -> Result<ConcreteDataType> {
+    MapType::try_new(key_type, value_type)
+        .map(ConcreteDataType::Map)
+        .map_err(|_| {
+            error::SqlTypeNotSupportedSnafu {
+                t: data_type.clone(),
+            }
+            .build()
+        })
+}
+
+/// Resolves the key or value type of a map from its name, e.g. `STRING` or `UInt64`.
+fn map_entry_type_from_name(map_type: &SqlDataType, name: &str) -> Result<ConcreteDataType> {
+    let sql_type = match get_data_type_by_alias_name(name) {
+        Some(t) => t,
+        None => Parser::new(&GenericDialect {})
+            .try_with_sql(name)
+            .and_then(|mut p| p.parse_data_type())
+            .map_err(|_| {
+                error::SqlTypeNotSupportedSnafu {
+                    t: map_type.clone(),
+                }
+                .build()
+            })?,
+    };
+    sql_data_type_to_concrete_data_type(&sql_type)
+}
+
 pub fn concrete_data_type_to_sql_data_type(data_type: &ConcreteDataType) -> Result<SqlDataType> {
     match data_type {
         ConcreteDataType::Int64(_) => Ok(SqlDataType::BigInt(None)),
@@ -309,6 +383,15 @@ pub fn concrete_data_type_to_sql_data_type(data_type: &ConcreteDataType) -> Resu
             vec![Ident::new(VECTOR_TYPE_NAME)].into(),
             vec![v.dim.to_string()],
         )),
+        ConcreteDataType::Uuid(_) => Ok(SqlDataType::Uuid),
+        ConcreteDataType::Map(m) => Ok(SqlDataType::Custom(
+            vec![Ident::new(MAP_TYPE_NAME)].into(),
+            vec![m.key_type().name(), m.value_type().name()],
+        )),
+        ConcreteDataType::Ip(_) => Ok(SqlDataType::Custom(
+            vec![Ident::new(INET_TYPE_NAME)].into(),
+            vec![],
+        )),
         ConcreteDataType::Duration(_)
         | ConcreteDataType::Null(_)
         | ConcreteDataType::List(_)
@@ -415,6 +498,60 @@ mod tests {
             ),
             ConcreteDataType::vector_datatype(3),
         );
+        check_type(SqlDataType::Uuid, ConcreteDataType::uuid_datatype());
+        check_type(
+            SqlDataType::Custom(
+                vec![Ident::new(MAP_TYPE_NAME)].into(),
+                vec!["STRING".to_string(), "UINT64".to_string()],
+            ),
+            ConcreteDataType::map_datatype(
+                ConcreteDataType::string_datatype(),
+                ConcreteDataType::uint64_datatype(),
+            ),
+        );
+        check_type(
+            SqlDataType::Map(
+                Box::new(SqlDataType::Int(None)),
+                Box::new(SqlDataType::Double(ExactNumberInfo::None)),
+            ),
+            ConcreteDataType::map_datatype(
+                ConcreteDataType::int32_datatype(),
+                ConcreteDataType::float64_datatype(),
+            ),
+        );
+        assert!(sql_data_type_to_concrete_data_type(&SqlDataType::Custom(
+            vec![Ident::new(MAP_TYPE_NAME)].into(),
+            vec!["DOUBLE".to_string(), "STRING".to_string()],
+        ))
+        .is_err());
+        check_type(
+            SqlDataType::Custom(vec![Ident::new(INET_TYPE_NAME)].into(), vec![]),
+            ConcreteDataType::ip_datatype(),
+        );
+        check_type(
+            SqlDataType::Custom(vec![Ident::new("ip")].into(), vec![]),
+            ConcreteDataType::ip_datatype(),
+        );
+    }
+
+    #[test]
+    fn test_map_type_to_sql_data_type_roundtrip() {
+        let map_type = ConcreteDataType::map_datatype(
+            ConcreteDataType::string_datatype(),
+            ConcreteDataType::uint64_datatype(),
+        );
+        let sql_type = concrete_data_type_to_sql_data_type(&map_type).unwrap();
+        assert_eq!("MAP(String, UInt64)", sql_type.to_string());
+        check_type(sql_type, map_type);
+
+        let sql_type =
+            concrete_data_type_to_sql_data_type(&ConcreteDataType::uuid_datatype()).unwrap();
+        assert_eq!(SqlDataType::Uuid, sql_type);
+
+        let sql_type =
+            concrete_data_type_to_sql_data_type(&ConcreteDataType::ip_datatype()).unwrap();
+        assert_eq!("INET", sql_type.to_string());
+        check_type(sql_type, ConcreteDataType::ip_datatype());
     }
 
     #[test]
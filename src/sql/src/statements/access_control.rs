@@ -0,0 +1,201 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Display, Formatter};
+
+use itertools::Itertools;
+use serde::Serialize;
+use sqlparser::ast::{Ident, ObjectName};
+use sqlparser_derive::{Visit, VisitMut};
+
+/// `CREATE USER [IF NOT EXISTS] <name> [IDENTIFIED BY '<password>']`
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut, Serialize)]
+pub struct CreateUser {
+    pub name: Ident,
+    #[serde(skip_serializing)]
+    pub password: Option<String>,
+    pub if_not_exists: bool,
+}
+
+impl Display for CreateUser {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CREATE USER ")?;
+        if self.if_not_exists {
+            write!(f, "IF NOT EXISTS ")?;
+        }
+        write!(f, "{}", self.name)?;
+        // Never displays the password.
+        if self.password.is_some() {
+            write!(f, " IDENTIFIED BY '******'")?;
+        }
+        Ok(())
+    }
+}
+
+/// `DROP USER [IF EXISTS] <name>`
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut, Serialize)]
+pub struct DropUser {
+    pub name: Ident,
+    pub if_exists: bool,
+}
+
+impl Display for DropUser {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DROP USER ")?;
+        if self.if_exists {
+            write!(f, "IF EXISTS ")?;
+        }
+        write!(f, "{}", self.name)
+    }
+}
+
+/// `CREATE ROLE [IF NOT EXISTS] <name>`
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut, Serialize)]
+pub struct CreateRole {
+    pub name: Ident,
+    pub if_not_exists: bool,
+}
+
+impl Display for CreateRole {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CREATE ROLE ")?;
+        if self.if_not_exists {
+            write!(f, "IF NOT EXISTS ")?;
+        }
+        write!(f, "{}", self.name)
+    }
+}
+
+/// `DROP ROLE [IF EXISTS] <name>`
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut, Serialize)]
+pub struct DropRole {
+    pub name: Ident,
+    pub if_exists: bool,
+}
+
+impl Display for DropRole {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DROP ROLE ")?;
+        if self.if_exists {
+            write!(f, "IF EXISTS ")?;
+        }
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Privileges in `GRANT` and `REVOKE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Visit, VisitMut, Serialize)]
+pub enum Privilege {
+    Select,
+    Insert,
+    Delete,
+    Create,
+    Alter,
+    Drop,
+}
+
+impl Privilege {
+    /// All privileges, used by `ALL [PRIVILEGES]`.
+    pub const ALL: [Privilege; 6] = [
+        Privilege::Select,
+        Privilege::Insert,
+        Privilege::Delete,
+        Privilege::Create,
+        Privilege::Alter,
+        Privilege::Drop,
+    ];
+}
+
+impl Display for Privilege {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Privilege::Select => write!(f, "SELECT"),
+            Privilege::Insert => write!(f, "INSERT"),
+            Privilege::Delete => write!(f, "DELETE"),
+            Privilege::Create => write!(f, "CREATE"),
+            Privilege::Alter => write!(f, "ALTER"),
+            Privilege::Drop => write!(f, "DROP"),
+        }
+    }
+}
+
+/// The object that privileges are granted on.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut, Serialize)]
+pub enum PrivilegeLevel {
+    /// `*.*`
+    Global,
+    /// `<database>.*` or `DATABASE <database>`
+    Database(Ident),
+    /// `[<database>.]<table>`
+    Table(ObjectName),
+}
+
+impl Display for PrivilegeLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrivilegeLevel::Global => write!(f, "*.*"),
+            PrivilegeLevel::Database(database) => write!(f, "{database}.*"),
+            PrivilegeLevel::Table(table) => write!(f, "{table}"),
+        }
+    }
+}
+
+/// What is granted or revoked.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut, Serialize)]
+pub enum Grantable {
+    /// Privileges on an object.
+    Privileges {
+        privileges: Vec<Privilege>,
+        level: PrivilegeLevel,
+    },
+    /// A role.
+    Role(Ident),
+}
+
+impl Display for Grantable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Grantable::Privileges { privileges, level } => {
+                write!(f, "{} ON {level}", privileges.iter().join(", "))
+            }
+            Grantable::Role(role) => write!(f, "{role}"),
+        }
+    }
+}
+
+/// `GRANT <privileges> ON <level> TO <grantee>` or `GRANT <role> TO <user>`
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut, Serialize)]
+pub struct Grant {
+    pub grantable: Grantable,
+    pub grantee: Ident,
+}
+
+impl Display for Grant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GRANT {} TO {}", self.grantable, self.grantee)
+    }
+}
+
+/// `REVOKE <privileges> ON <level> FROM <grantee>` or `REVOKE <role> FROM <user>`
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut, Serialize)]
+pub struct Revoke {
+    pub grantable: Grantable,
+    pub grantee: Ident,
+}
+
+impl Display for Revoke {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "REVOKE {} FROM {}", self.grantable, self.grantee)
+    }
+}
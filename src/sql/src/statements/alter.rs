@@ -102,6 +102,15 @@ pub enum AlterTableOperation {
     SetDefaults {
         defaults: Vec<SetDefaultsOperation>,
     },
+    /// `SPLIT PARTITION (<from_expr>) INTO (<into_expr>, ...)`
+    SplitPartition {
+        from: Expr,
+        into: Vec<Expr>,
+    },
+    /// `MERGE PARTITION (<from_expr>, ...)`
+    MergePartition {
+        from: Vec<Expr>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut, Serialize)]
@@ -243,6 +252,16 @@ impl Display for AlterTableOperation {
                     .join(", ");
                 write!(f, "{defaults}")
             }
+            AlterTableOperation::SplitPartition { from, into } => {
+                write!(
+                    f,
+                    "SPLIT PARTITION ({from}) INTO ({})",
+                    into.iter().join(", ")
+                )
+            }
+            AlterTableOperation::MergePartition { from } => {
+                write!(f, "MERGE PARTITION ({})", from.iter().join(", "))
+            }
         }
     }
 }
@@ -0,0 +1,79 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Display;
+
+use serde::Serialize;
+use sqlparser::ast::ObjectName;
+use sqlparser_derive::{Visit, VisitMut};
+
+use crate::statements::OptionMap;
+
+/// BACKUP DATABASE db TO 'location' WITH (name = 'backup_name') [CONNECTION (...)]
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut, Serialize)]
+pub struct BackupDatabase {
+    pub database_name: ObjectName,
+    /// The location of the backup repository.
+    pub location: String,
+    pub with: OptionMap,
+    pub connection: OptionMap,
+}
+
+impl Display for BackupDatabase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "BACKUP DATABASE {} TO '{}'",
+            self.database_name, self.location
+        )?;
+        fmt_options(f, &self.with, &self.connection)
+    }
+}
+
+/// RESTORE DATABASE db FROM 'location' WITH (name = 'backup_name') [CONNECTION (...)]
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut, Serialize)]
+pub struct RestoreDatabase {
+    pub database_name: ObjectName,
+    /// The location of the backup repository.
+    pub location: String,
+    pub with: OptionMap,
+    pub connection: OptionMap,
+}
+
+impl Display for RestoreDatabase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "RESTORE DATABASE {} FROM '{}'",
+            self.database_name, self.location
+        )?;
+        fmt_options(f, &self.with, &self.connection)
+    }
+}
+
+fn fmt_options(
+    f: &mut std::fmt::Formatter<'_>,
+    with: &OptionMap,
+    connection: &OptionMap,
+) -> std::fmt::Result {
+    if !with.is_empty() {
+        let options = with.kv_pairs();
+        write!(f, " WITH ({})", options.join(", "))?;
+    }
+    if !connection.is_empty() {
+        let options = connection.kv_pairs();
+        write!(f, " CONNECTION ({})", options.join(", "))?;
+    }
+    Ok(())
+}
@@ -20,8 +20,12 @@ use sqlparser::ast::Statement as SpStatement;
 use sqlparser_derive::{Visit, VisitMut};
 
 use crate::error::{ConvertToDfStatementSnafu, Error};
+use crate::statements::access_control::{
+    CreateRole, CreateUser, DropRole, DropUser, Grant, Revoke,
+};
 use crate::statements::admin::Admin;
 use crate::statements::alter::{AlterDatabase, AlterTable};
+use crate::statements::backup::{BackupDatabase, RestoreDatabase};
 use crate::statements::copy::Copy;
 use crate::statements::create::{
     CreateDatabase, CreateExternalTable, CreateFlow, CreateTable, CreateTableLike, CreateView,
@@ -42,6 +46,7 @@ use crate::statements::show::{
 };
 use crate::statements::tql::Tql;
 use crate::statements::truncate::TruncateTable;
+use crate::statements::update::Update;
 
 /// Tokens parsed by `DFParser` are converted into these values.
 #[allow(clippy::large_enum_variant)]
@@ -53,6 +58,8 @@ pub enum Statement {
     Insert(Box<Insert>),
     // Delete
     Delete(Box<Delete>),
+    // Update
+    Update(Box<Update>),
     /// CREATE TABLE
     CreateTable(CreateTable),
     // CREATE EXTERNAL TABLE
@@ -149,6 +156,22 @@ pub enum Statement {
     Kill(Kill),
     // SHOW PROCESSLIST
     ShowProcesslist(ShowProcessList),
+    // CREATE USER
+    CreateUser(CreateUser),
+    // DROP USER
+    DropUser(DropUser),
+    // CREATE ROLE
+    CreateRole(CreateRole),
+    // DROP ROLE
+    DropRole(DropRole),
+    // GRANT
+    Grant(Grant),
+    // REVOKE
+    Revoke(Revoke),
+    // BACKUP DATABASE
+    BackupDatabase(BackupDatabase),
+    // RESTORE DATABASE
+    RestoreDatabase(RestoreDatabase),
 }
 
 impl Statement {
@@ -185,6 +208,7 @@ impl Statement {
             // Write operations
             Statement::Insert(_)
             | Statement::Delete(_)
+            | Statement::Update(_)
             | Statement::CreateTable(_)
             | Statement::CreateExternalTable(_)
             | Statement::CreateTableLike(_)
@@ -204,6 +228,14 @@ impl Statement {
             | Statement::DeclareCursor(_)
             | Statement::CloseCursor(_)
             | Statement::Kill(_)
+            | Statement::CreateUser(_)
+            | Statement::DropUser(_)
+            | Statement::CreateRole(_)
+            | Statement::DropRole(_)
+            | Statement::Grant(_)
+            | Statement::Revoke(_)
+            | Statement::BackupDatabase(_)
+            | Statement::RestoreDatabase(_)
             | Statement::Admin(_) => false,
 
             #[cfg(feature = "enterprise")]
@@ -221,6 +253,7 @@ impl Display for Statement {
             Statement::Query(s) => s.inner.fmt(f),
             Statement::Insert(s) => s.inner.fmt(f),
             Statement::Delete(s) => s.inner.fmt(f),
+            Statement::Update(s) => s.inner.fmt(f),
             Statement::CreateTable(s) => s.fmt(f),
             Statement::CreateExternalTable(s) => s.fmt(f),
             Statement::CreateTableLike(s) => s.fmt(f),
@@ -275,6 +308,14 @@ impl Display for Statement {
             Statement::CloseCursor(s) => s.fmt(f),
             Statement::Kill(k) => k.fmt(f),
             Statement::ShowProcesslist(s) => s.fmt(f),
+            Statement::CreateUser(s) => s.fmt(f),
+            Statement::DropUser(s) => s.fmt(f),
+            Statement::CreateRole(s) => s.fmt(f),
+            Statement::DropRole(s) => s.fmt(f),
+            Statement::Grant(s) => s.fmt(f),
+            Statement::Revoke(s) => s.fmt(f),
+            Statement::BackupDatabase(s) => s.fmt(f),
+            Statement::RestoreDatabase(s) => s.fmt(f),
         }
     }
 }
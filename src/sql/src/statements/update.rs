@@ -0,0 +1,57 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::Serialize;
+use sqlparser::ast::{Assignment, Expr, ObjectName, Statement, TableFactor, TableWithJoins};
+use sqlparser_derive::{Visit, VisitMut};
+
+/// UPDATE statement.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut, Serialize)]
+pub struct Update {
+    // Can only be sqlparser::ast::Statement::Update variant
+    pub inner: Statement,
+}
+
+impl Update {
+    /// Returns the updated table, along with its alias if any.
+    pub fn table(&self) -> &TableWithJoins {
+        match &self.inner {
+            Statement::Update { table, .. } => table,
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn table_name(&self) -> &ObjectName {
+        match &self.table().relation {
+            TableFactor::Table { name, .. } => name,
+            // Guarded by the parser.
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn assignments(&self) -> &[Assignment] {
+        match &self.inner {
+            Statement::Update { assignments, .. } => assignments,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the `WHERE` condition.
+    pub fn selection(&self) -> Option<&Expr> {
+        match &self.inner {
+            Statement::Update { selection, .. } => selection.as_ref(),
+            _ => unreachable!(),
+        }
+    }
+}
@@ -39,6 +39,22 @@ pub const TWCS_TIME_WINDOW: &str = "compaction.twcs.time_window";
 pub const TWCS_REMOTE_COMPACTION: &str = "compaction.twcs.remote_compaction";
 /// Option key for twcs fallback to local.
 pub const TWCS_FALLBACK_TO_LOCAL: &str = "compaction.twcs.fallback_to_local";
+/// Size-tiered compaction strategy.
+pub const COMPACTION_TYPE_SIZE_TIERED: &str = "size_tiered";
+/// Option key for size-tiered min file num in a tier to trigger a compaction.
+pub const SIZE_TIERED_MIN_THRESHOLD: &str = "compaction.size_tiered.min_threshold";
+/// Option key for size-tiered max file num to compact at once.
+pub const SIZE_TIERED_MAX_THRESHOLD: &str = "compaction.size_tiered.max_threshold";
+/// Option key for size-tiered min file size.
+pub const SIZE_TIERED_MIN_FILE_SIZE: &str = "compaction.size_tiered.min_file_size";
+/// Option key for size-tiered max output file size.
+pub const SIZE_TIERED_MAX_OUTPUT_FILE_SIZE: &str = "compaction.size_tiered.max_output_file_size";
+/// Option key for size-tiered time window.
+pub const SIZE_TIERED_TIME_WINDOW: &str = "compaction.size_tiered.time_window";
+/// Option key for size-tiered remote compaction.
+pub const SIZE_TIERED_REMOTE_COMPACTION: &str = "compaction.size_tiered.remote_compaction";
+/// Option key for size-tiered fallback to local.
+pub const SIZE_TIERED_FALLBACK_TO_LOCAL: &str = "compaction.size_tiered.fallback_to_local";
 /// Option key for memtable type.
 pub const MEMTABLE_TYPE: &str = "memtable.type";
 /// Option key for memtable partition tree primary key encoding.
@@ -53,6 +69,12 @@ pub const MEMTABLE_PARTITION_TREE_DATA_FREEZE_THRESHOLD: &str =
 /// Option key for memtable partition tree fork dictionary bytes.
 pub const MEMTABLE_PARTITION_TREE_FORK_DICTIONARY_BYTES: &str =
     "memtable.partition_tree.fork_dictionary_bytes";
+/// Option key for rollup tiers, e.g. `5m:avg,max;1h:avg`.
+pub const ROLLUP_KEY: &str = "rollup";
+/// Option key for the age of data to roll up.
+pub const ROLLUP_AFTER_KEY: &str = "rollup.after";
+/// Option key for the format to store JSON columns in SSTs, `jsonb` or `shredded`.
+pub const JSON_FORMAT_KEY: &str = "json.format";
 /// Option key for skipping WAL.
 pub const SKIP_WAL_KEY: &str = "skip_wal";
 // Note: Adding new options here should also check if this option should be removed in [metric_engine::engine::create::region_options_for_metadata_region].
@@ -67,6 +89,13 @@ pub fn is_mito_engine_option_key(key: &str) -> bool {
         TWCS_TIME_WINDOW,
         TWCS_REMOTE_COMPACTION,
         TWCS_FALLBACK_TO_LOCAL,
+        SIZE_TIERED_MIN_THRESHOLD,
+        SIZE_TIERED_MAX_THRESHOLD,
+        SIZE_TIERED_MIN_FILE_SIZE,
+        SIZE_TIERED_MAX_OUTPUT_FILE_SIZE,
+        SIZE_TIERED_TIME_WINDOW,
+        SIZE_TIERED_REMOTE_COMPACTION,
+        SIZE_TIERED_FALLBACK_TO_LOCAL,
         "storage",
         "index.inverted_index.ignore_column_ids",
         "index.inverted_index.segment_row_count",
@@ -78,6 +107,9 @@ pub fn is_mito_engine_option_key(key: &str) -> bool {
         MEMTABLE_PARTITION_TREE_PRIMARY_KEY_ENCODING,
         APPEND_MODE_KEY,
         MERGE_MODE_KEY,
+        ROLLUP_KEY,
+        ROLLUP_AFTER_KEY,
+        JSON_FORMAT_KEY,
     ]
     .contains(&key)
 }
@@ -94,6 +126,12 @@ mod tests {
             "compaction.twcs.trigger_file_num"
         ));
         assert!(is_mito_engine_option_key("compaction.twcs.time_window"));
+        assert!(is_mito_engine_option_key(
+            "compaction.size_tiered.min_threshold"
+        ));
+        assert!(is_mito_engine_option_key(
+            "compaction.size_tiered.max_output_file_size"
+        ));
         assert!(is_mito_engine_option_key("storage"));
         assert!(is_mito_engine_option_key(
             "index.inverted_index.ignore_column_ids"
@@ -113,6 +151,9 @@ mod tests {
             "memtable.partition_tree.fork_dictionary_bytes"
         ));
         assert!(is_mito_engine_option_key("append_mode"));
+        assert!(is_mito_engine_option_key("rollup"));
+        assert!(is_mito_engine_option_key("rollup.after"));
+        assert!(is_mito_engine_option_key("json.format"));
         assert!(!is_mito_engine_option_key("foo"));
     }
 }
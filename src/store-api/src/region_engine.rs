@@ -33,7 +33,7 @@ use serde::{Deserialize, Serialize};
 use tokio::sync::Semaphore;
 
 use crate::logstore::entry;
-use crate::metadata::RegionMetadataRef;
+use crate::metadata::{ColumnMetadata, RegionMetadataRef};
 use crate::region_request::{
     BatchRegionDdlRequest, RegionOpenRequest, RegionRequest, RegionSequencesRequest,
 };
@@ -687,6 +687,126 @@ impl SyncManifestResponse {
     }
 }
 
+/// The request to back up the files of a region to an external object store.
+///
+/// A backup first pins the files of every region by [BackupRegionRequest::Pin], then copies
+/// the pinned files of each region by [BackupRegionRequest::Copy]. Pinning all regions before
+/// copying any file keeps the versions of the regions close in time, no matter how long the
+/// copy takes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BackupRegionRequest {
+    /// Pins the files referenced by the current version of the region,
+    /// they are not purged until the pin is released.
+    Pin {
+        region_id: RegionId,
+        /// The name of the backup that holds the pin.
+        backup_name: String,
+    },
+    /// Copies the pinned files of the region and releases the pin.
+    Copy {
+        region_id: RegionId,
+        /// The name of the backup that holds the pin.
+        backup_name: String,
+        /// The url of the directory to copy the files to, e.g., `s3://bucket/backup/data/1024_0000000000/`.
+        location: String,
+        /// The connection options of the object store.
+        connection: HashMap<String, String>,
+        /// The snapshot of a previous backup of the region in the same directory.
+        ///
+        /// Files already in the base snapshot are not copied again.
+        base_snapshot: Option<String>,
+    },
+    /// Releases the pin without copying the files, e.g., the backup fails.
+    Release {
+        region_id: RegionId,
+        /// The name of the backup that holds the pin.
+        backup_name: String,
+    },
+}
+
+impl BackupRegionRequest {
+    /// Returns the region to back up.
+    pub fn region_id(&self) -> RegionId {
+        match self {
+            BackupRegionRequest::Pin { region_id, .. }
+            | BackupRegionRequest::Copy { region_id, .. }
+            | BackupRegionRequest::Release { region_id, .. } => *region_id,
+        }
+    }
+}
+
+/// The response of backing up a region.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BackupRegionResponse {
+    NotSupported,
+    Pinned {
+        /// The manifest version of the region pinned by the backup.
+        manifest_version: u64,
+        /// The columns of the pinned version, the region to restore must have the same column ids.
+        column_metadatas: Vec<ColumnMetadata>,
+    },
+    Backup {
+        /// The manifest version of the region pinned by the backup.
+        manifest_version: u64,
+        /// The number of files copied by the backup.
+        copied_files: usize,
+        /// The number of bytes copied by the backup.
+        copied_bytes: u64,
+        /// The engine specific snapshot of the region, it's required to restore the region.
+        snapshot: String,
+    },
+    Released,
+}
+
+/// The request to restore the files of a region from a backup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestoreRegionRequest {
+    /// The region to restore, it must be empty.
+    pub region_id: RegionId,
+    /// The url of the directory to copy the files from.
+    pub location: String,
+    /// The connection options of the object store.
+    pub connection: HashMap<String, String>,
+    /// The snapshot returned by [BackupRegionResponse::Backup].
+    pub snapshot: String,
+}
+
+/// The response of restoring a region.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RestoreRegionResponse {
+    NotSupported,
+    Restored {
+        /// The number of files restored to the region.
+        restored_files: usize,
+    },
+}
+
+/// The request to copy the files of other regions of the same table into a region.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CopyRegionFromRequest {
+    /// The region to copy the files into.
+    ///
+    /// The files are only copied if the region is empty.
+    pub region_id: RegionId,
+    /// The regions to copy the files from, they must not be written during the copy.
+    pub source_region_ids: Vec<RegionId>,
+    /// The JSON encoded partition expr of the region.
+    ///
+    /// The files are copied as is if it's `None`, otherwise only the rows
+    /// matching the expr are copied.
+    pub partition_expr: Option<String>,
+}
+
+/// The response of copying files into a region.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CopyRegionFromResponse {
+    NotSupported,
+    Copied {
+        /// The number of files added to the region, it's 0 if the files were copied before.
+        copied_files: usize,
+    },
+}
+
 #[async_trait]
 pub trait RegionEngine: Send + Sync {
     /// Name of this engine
@@ -796,6 +916,25 @@ pub trait RegionEngine: Send + Sync {
         manifest_info: RegionManifestInfo,
     ) -> Result<SyncManifestResponse, BoxedError>;
 
+    /// Pins the files of the region, copies them to an external object store or releases them,
+    /// see [BackupRegionRequest].
+    async fn backup_region(
+        &self,
+        request: BackupRegionRequest,
+    ) -> Result<BackupRegionResponse, BoxedError>;
+
+    /// Copies the files of a backup into the region and adds them to the region.
+    async fn restore_region(
+        &self,
+        request: RestoreRegionRequest,
+    ) -> Result<RestoreRegionResponse, BoxedError>;
+
+    /// Copies the files of other regions of the same table into the region and adds them to the region.
+    async fn copy_region_from(
+        &self,
+        request: CopyRegionFromRequest,
+    ) -> Result<CopyRegionFromResponse, BoxedError>;
+
     /// Sets region role state gracefully.
     ///
     /// After the call returns, the engine ensures no more write operations will succeed in the region.
@@ -25,5 +25,8 @@ pub use datatypes::schema::{
 };
 
 pub use self::descriptors::*;
-pub use self::requests::{ScanRequest, TimeSeriesDistribution, TimeSeriesRowSelector};
+pub use self::requests::{
+    JsonPathHint, RollupAggregator, RollupHint, ScanRequest, TimeSeriesDistribution,
+    TimeSeriesRowSelector,
+};
 pub use self::types::SequenceNumber;
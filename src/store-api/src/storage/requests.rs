@@ -12,11 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{Display, Formatter};
 
 use common_recordbatch::OrderOption;
 use datafusion_expr::expr::Expr;
-use strum::Display;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
 
 use crate::storage::SequenceNumber;
 
@@ -38,6 +40,61 @@ pub enum TimeSeriesDistribution {
     PerSeries,
 }
 
+/// Function to aggregate rows of a time-series in a rollup interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum RollupAggregator {
+    Avg,
+    Min,
+    Max,
+    Sum,
+    Count,
+    Last,
+}
+
+/// A hint on which rollup tier to read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RollupHint {
+    /// Step of the query in milliseconds. The coarsest tier whose interval
+    /// is not larger than the step is read.
+    pub step_millis: i64,
+    /// The aggregator the query applies to each step, if known.
+    pub aggregator: Option<RollupAggregator>,
+}
+
+impl Display for RollupHint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "step: {}ms", self.step_millis)?;
+        if let Some(aggregator) = &self.aggregator {
+            write!(f, ", aggregator: {}", aggregator)?;
+        }
+        Ok(())
+    }
+}
+
+/// A hint on paths of JSON columns to read.
+///
+/// A JSON column in the hint is only accessed by the paths, so the storage may
+/// return documents that only contain values of these paths.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct JsonPathHint {
+    /// Name of the JSON column to the paths accessed.
+    pub paths: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Display for JsonPathHint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, (column, paths)) in self.paths.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {:?}", column, paths)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Default, Clone, Debug, PartialEq, Eq)]
 pub struct ScanRequest {
     /// Indices of columns to read, `None` to read all columns. This indices is
@@ -63,6 +120,10 @@ pub struct ScanRequest {
     pub sst_min_sequence: Option<SequenceNumber>,
     /// Optional hint for the distribution of time-series data.
     pub distribution: Option<TimeSeriesDistribution>,
+    /// Optional hint to select the rollup tier to read.
+    pub rollup: Option<RollupHint>,
+    /// Optional hint on paths of JSON columns to read.
+    pub json_paths: Option<JsonPathHint>,
 }
 
 impl Display for ScanRequest {
@@ -135,6 +196,12 @@ impl Display for ScanRequest {
         if let Some(distribution) = &self.distribution {
             write!(f, "{}distribution: {}", delimiter.as_str(), distribution)?;
         }
+        if let Some(rollup) = &self.rollup {
+            write!(f, "{}rollup: {{ {} }}", delimiter.as_str(), rollup)?;
+        }
+        if let Some(json_paths) = &self.json_paths {
+            write!(f, "{}json_paths: {{ {} }}", delimiter.as_str(), json_paths)?;
+        }
         write!(f, " }}")
     }
 }
@@ -188,5 +255,31 @@ mod tests {
             request.to_string(),
             "ScanRequest { projection: [1, 2], limit: 10 }"
         );
+
+        let request = ScanRequest {
+            rollup: Some(RollupHint {
+                step_millis: 300_000,
+                aggregator: Some(RollupAggregator::Max),
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            request.to_string(),
+            "ScanRequest { rollup: { step: 300000ms, aggregator: max } }"
+        );
+
+        let request = ScanRequest {
+            json_paths: Some(JsonPathHint {
+                paths: BTreeMap::from([(
+                    "log".to_string(),
+                    BTreeSet::from(["$.a.b".to_string(), "c".to_string()]),
+                )]),
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            request.to_string(),
+            r#"ScanRequest { json_paths: { log: {"$.a.b", "c"} } }"#
+        );
     }
 }
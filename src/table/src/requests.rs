@@ -37,6 +37,8 @@ use store_api::metric_engine_consts::{
 };
 use store_api::mito_engine_options::{
     is_mito_engine_option_key, APPEND_MODE_KEY, COMPACTION_TYPE, MEMTABLE_TYPE, MERGE_MODE_KEY,
+    SIZE_TIERED_FALLBACK_TO_LOCAL, SIZE_TIERED_MAX_OUTPUT_FILE_SIZE, SIZE_TIERED_MAX_THRESHOLD,
+    SIZE_TIERED_MIN_FILE_SIZE, SIZE_TIERED_MIN_THRESHOLD, SIZE_TIERED_TIME_WINDOW,
     TWCS_FALLBACK_TO_LOCAL, TWCS_MAX_OUTPUT_FILE_SIZE, TWCS_TIME_WINDOW, TWCS_TRIGGER_FILE_NUM,
 };
 use store_api::region_request::{SetRegionOption, UnsetRegionOption};
@@ -89,6 +91,12 @@ static VALID_DB_OPT_KEYS: Lazy<HashSet<&str>> = Lazy::new(|| {
     set.insert(TWCS_TIME_WINDOW);
     set.insert(TWCS_TRIGGER_FILE_NUM);
     set.insert(TWCS_MAX_OUTPUT_FILE_SIZE);
+    set.insert(SIZE_TIERED_FALLBACK_TO_LOCAL);
+    set.insert(SIZE_TIERED_TIME_WINDOW);
+    set.insert(SIZE_TIERED_MIN_THRESHOLD);
+    set.insert(SIZE_TIERED_MAX_THRESHOLD);
+    set.insert(SIZE_TIERED_MIN_FILE_SIZE);
+    set.insert(SIZE_TIERED_MAX_OUTPUT_FILE_SIZE);
     set
 });
 
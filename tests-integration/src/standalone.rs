@@ -50,6 +50,7 @@ use frontend::frontend::Frontend;
 use frontend::instance::builder::FrontendBuilder;
 use frontend::instance::{Instance, StandaloneDatanodeManager};
 use meta_srv::metasrv::{FLOW_ID_SEQ, TABLE_ID_SEQ};
+use query::options::QueryOptions;
 use servers::grpc::GrpcOptions;
 use servers::server::ServerHandlers;
 use snafu::ResultExt;
@@ -78,6 +79,7 @@ pub struct GreptimeDbStandaloneBuilder {
     store_providers: Option<Vec<StorageType>>,
     default_store: Option<StorageType>,
     plugin: Option<Plugins>,
+    query_options: QueryOptions,
 }
 
 impl GreptimeDbStandaloneBuilder {
@@ -89,6 +91,7 @@ impl GreptimeDbStandaloneBuilder {
             default_store: None,
             datanode_wal_config: DatanodeWalConfig::default(),
             metasrv_wal_config: MetasrvWalConfig::default(),
+            query_options: QueryOptions::default(),
         }
     }
 
@@ -124,6 +127,12 @@ impl GreptimeDbStandaloneBuilder {
         self
     }
 
+    #[must_use]
+    pub fn with_query_options(mut self, query_options: QueryOptions) -> Self {
+        self.query_options = query_options;
+        self
+    }
+
     #[must_use]
     pub fn with_metasrv_wal_config(mut self, metasrv_wal_config: MetasrvWalConfig) -> Self {
         self.metasrv_wal_config = metasrv_wal_config;
@@ -336,6 +345,7 @@ impl GreptimeDbStandaloneBuilder {
                 threshold: Duration::from_secs(1),
                 ..Default::default()
             },
+            query: self.query_options.clone(),
             ..StandaloneOptions::default()
         };
 
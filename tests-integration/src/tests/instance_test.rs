@@ -17,7 +17,10 @@ use std::env;
 use std::sync::Arc;
 
 use client::{OutputData, DEFAULT_SCHEMA_NAME};
+use common_base::readable_size::ReadableSize;
 use common_catalog::consts::DEFAULT_CATALOG_NAME;
+use common_error::ext::ErrorExt;
+use common_error::status_code::StatusCode;
 use common_query::Output;
 use common_recordbatch::util;
 use common_test_util::recordbatch::check_output_stream;
@@ -26,11 +29,13 @@ use datatypes::vectors::{StringVector, TimestampMillisecondVector, UInt64Vector,
 use frontend::error::{Error, Result};
 use frontend::instance::Instance;
 use operator::error::Error as OperatorError;
+use query::options::QueryOptions;
 use rstest::rstest;
 use rstest_reuse::apply;
 use servers::query_handler::sql::SqlQueryHandler;
 use session::context::{QueryContext, QueryContextRef};
 
+use crate::standalone::GreptimeDbStandaloneBuilder;
 use crate::tests::test_util::{
     both_instances_cases, both_instances_cases_with_custom_storages, check_unordered_output_stream,
     distributed, distributed_with_multiple_object_stores, find_testing_resource, prepare_path,
@@ -2313,3 +2318,72 @@ async fn test_copy_parquet_map_to_binary(instance: Arc<dyn MockInstance>) {
 +----+-----------------------------------------+"#;
     check_output_stream(output, expected).await;
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_query_memory_limit() {
+    common_telemetry::init_default_ut_logging();
+    let spill_dir = temp_dir::create_temp_dir("test_query_memory_limit_spill");
+    let standalone = GreptimeDbStandaloneBuilder::new("test_query_memory_limit")
+        .with_query_options(QueryOptions {
+            per_query_memory_limit: ReadableSize::kb(512),
+            spill_dirs: vec![spill_dir.path().to_string_lossy().to_string()],
+            ..Default::default()
+        })
+        .build()
+        .await;
+    let instance = standalone.fe_instance();
+
+    let output = execute_sql(
+        instance,
+        "create table memory_test(host STRING, val DOUBLE, ts TIMESTAMP TIME INDEX, PRIMARY KEY(host))",
+    )
+    .await
+    .data;
+    assert!(matches!(output, OutputData::AffectedRows(0)));
+    // Inserts about 2MB of hosts.
+    let padding = "x".repeat(80);
+    for i in 0..20 {
+        let values = (0..1000)
+            .map(|j| format!("('host-{i}-{j}-{padding}', {j}, {j})"))
+            .collect::<Vec<_>>()
+            .join(",");
+        let output = execute_sql(
+            instance,
+            &format!("insert into memory_test(host, val, ts) values {values}"),
+        )
+        .await
+        .data;
+        assert!(matches!(output, OutputData::AffectedRows(1000)));
+    }
+
+    // The query is under the limit.
+    let output = execute_sql(instance, "select count(*) from memory_test")
+        .await
+        .data;
+    let expected = "\
++----------+
+| count(*) |
++----------+
+| 20000    |
++----------+";
+    check_output_stream(output, expected).await;
+
+    // Hash joins can't spill, so the query fails once its build side exceeds the limit.
+    let output = execute_sql(
+        instance,
+        "select count(*) from memory_test a join memory_test b on a.host = b.host",
+    )
+    .await
+    .data;
+    let OutputData::Stream(stream) = output else {
+        unreachable!()
+    };
+    let err = util::collect(stream).await.unwrap_err();
+    assert_eq!(StatusCode::RuntimeResourcesExhausted, err.status_code());
+    assert!(
+        err.output_msg()
+            .contains("exceeds the memory limit 512.0KiB"),
+        "{}",
+        err.output_msg()
+    );
+}
@@ -19,6 +19,13 @@ use api::v1::{
     CreateTableExpr, InsertRequest, InsertRequests, PromInstantQuery, PromRangeQuery,
     PromqlRequest, RequestHeader, SemanticType,
 };
+use arrow_flight::flight_service_client::FlightServiceClient;
+use arrow_flight::sql::client::FlightSqlServiceClient;
+use arrow_flight::sql::{
+    ActionCreatePreparedStatementRequest, ActionCreatePreparedStatementResult, Any,
+    CommandPreparedStatementQuery, CommandStatementUpdate, ProstMessageExt,
+};
+use arrow_flight::{Action, FlightData, FlightDescriptor, FlightInfo};
 use auth::user_provider_from_option;
 use client::{Client, Database, OutputData, DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_catalog::consts::MITO_ENGINE;
@@ -28,8 +35,11 @@ use common_recordbatch::RecordBatches;
 use common_runtime::runtime::{BuilderBuild, RuntimeTrait};
 use common_runtime::Runtime;
 use common_test_util::find_workspace_path;
+use datatypes::arrow::util::pretty::pretty_format_batches;
+use futures::{stream, TryStreamExt};
 use otel_arrow_rust::proto::opentelemetry::arrow::v1::arrow_metrics_service_client::ArrowMetricsServiceClient;
 use otel_arrow_rust::proto::opentelemetry::arrow::v1::BatchArrowRecords;
+use prost::Message;
 use servers::grpc::builder::GrpcServerBuilder;
 use servers::grpc::GrpcServerConfig;
 use servers::http::prometheus::{
@@ -42,6 +52,7 @@ use tests_integration::test_util::{
     setup_grpc_server, setup_grpc_server_with, setup_grpc_server_with_user_provider, StorageType,
 };
 use tonic::metadata::MetadataValue;
+use tonic::transport::Channel;
 use tonic::Request;
 
 #[macro_export]
@@ -85,6 +96,7 @@ macro_rules! grpc_tests {
                 test_grpc_message_size_limit_recv,
                 test_grpc_message_size_limit_send,
                 test_grpc_auth,
+                test_flight_sql,
                 test_health_check,
                 test_prom_gateway_query,
                 test_grpc_timezone,
@@ -281,6 +293,171 @@ pub async fn test_grpc_auth(store_type: StorageType) {
     let _ = fe_grpc_server.shutdown().await;
 }
 
+pub async fn test_flight_sql(store_type: StorageType) {
+    let user_provider = user_provider_from_option(
+        &"static_user_provider:cmd:greptime_user=greptime_pwd,other_user=other_pwd".to_string(),
+    )
+    .unwrap();
+    let (_db, fe_grpc_server) =
+        setup_grpc_server_with_user_provider(store_type, "test_flight_sql", Some(user_provider))
+            .await;
+    let addr = fe_grpc_server.bind_addr().unwrap().to_string();
+    let channel = Channel::from_shared(format!("http://{addr}"))
+        .unwrap()
+        .connect()
+        .await
+        .unwrap();
+
+    // 1. test auth
+    let mut client = FlightSqlServiceClient::new(channel.clone());
+    assert!(client.execute("SELECT 1".to_string(), None).await.is_err());
+    assert!(client
+        .handshake("greptime_user", "wrong_pwd")
+        .await
+        .is_err());
+    let _ = client
+        .handshake("greptime_user", "greptime_pwd")
+        .await
+        .unwrap();
+    let token = client.token().unwrap().clone();
+    // The token doesn't carry the credentials, which are not accepted as a token.
+    let forged = "Z3JlcHRpbWVfdXNlcjpncmVwdGltZV9wd2Q=";
+    assert_ne!(forged, token);
+    let mut forged_client = FlightSqlServiceClient::new(channel.clone());
+    forged_client.set_token(forged.to_string());
+    assert!(forged_client
+        .execute("SELECT 1".to_string(), None)
+        .await
+        .is_err());
+    // `DoPut` is rejected before reading the data.
+    let status = FlightServiceClient::new(channel.clone())
+        .do_put(stream::iter(vec![update_flight_data("SELECT 1")]))
+        .await
+        .unwrap_err();
+    assert_eq!(tonic::Code::Unauthenticated, status.code());
+
+    // 2. test statements
+    for sql in [
+        "CREATE TABLE flight_sql (ts TIMESTAMP TIME INDEX, val DOUBLE)",
+        "INSERT INTO flight_sql VALUES (1, 1.0), (2, 2.0)",
+    ] {
+        let request = with_token(stream::iter(vec![update_flight_data(sql)]), &token);
+        let mut results = FlightServiceClient::new(channel.clone())
+            .do_put(request)
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(results.message().await.unwrap().is_some());
+    }
+    let info = client
+        .execute("SELECT val FROM flight_sql ORDER BY ts".to_string(), None)
+        .await
+        .unwrap();
+    let expected = "\
++-----+
+| val |
++-----+
+| 1.0 |
+| 2.0 |
++-----+";
+    assert_eq!(expected, fetch_flight_sql(&mut client, info).await);
+
+    // 3. test prepared statements
+    let mut prepared = client
+        .prepare("SELECT count(*) AS c FROM flight_sql".to_string(), None)
+        .await
+        .unwrap();
+    let info = prepared.execute().await.unwrap();
+    let expected = "\
++---+
+| c |
++---+
+| 2 |
++---+";
+    assert_eq!(expected, fetch_flight_sql(&mut client, info).await);
+    prepared.close().await.unwrap();
+
+    // Prepared statements are only visible to the user who prepared them.
+    let action = Action::new(
+        "CreatePreparedStatement",
+        ActionCreatePreparedStatementRequest {
+            query: "SELECT 1".to_string(),
+            transaction_id: None,
+        }
+        .as_any()
+        .encode_to_vec(),
+    );
+    let mut results = FlightServiceClient::new(channel.clone())
+        .do_action(with_token(action, &token))
+        .await
+        .unwrap()
+        .into_inner();
+    let body = results.message().await.unwrap().unwrap().body;
+    let handle = Any::decode(body)
+        .unwrap()
+        .unpack::<ActionCreatePreparedStatementResult>()
+        .unwrap()
+        .unwrap()
+        .prepared_statement_handle;
+
+    let mut other_client = FlightSqlServiceClient::new(channel.clone());
+    let _ = other_client
+        .handshake("other_user", "other_pwd")
+        .await
+        .unwrap();
+    let other_token = other_client.token().unwrap().clone();
+    let descriptor = FlightDescriptor::new_cmd(
+        CommandPreparedStatementQuery {
+            prepared_statement_handle: handle.clone(),
+        }
+        .as_any()
+        .encode_to_vec(),
+    );
+    let status = FlightServiceClient::new(channel.clone())
+        .get_flight_info(with_token(descriptor.clone(), &other_token))
+        .await
+        .unwrap_err();
+    assert_eq!(tonic::Code::NotFound, status.code());
+    assert!(FlightServiceClient::new(channel.clone())
+        .get_flight_info(with_token(descriptor, &token))
+        .await
+        .is_ok());
+
+    let _ = fe_grpc_server.shutdown().await;
+}
+
+fn update_flight_data(sql: &str) -> FlightData {
+    let command = CommandStatementUpdate {
+        query: sql.to_string(),
+        transaction_id: None,
+    };
+    FlightData::new().with_descriptor(FlightDescriptor::new_cmd(command.as_any().encode_to_vec()))
+}
+
+fn with_token<T>(message: T, token: &str) -> Request<T> {
+    let mut request = Request::new(message);
+    let _ = request.metadata_mut().insert(
+        "authorization",
+        MetadataValue::try_from(format!("Bearer {token}")).unwrap(),
+    );
+    request
+}
+
+async fn fetch_flight_sql(
+    client: &mut FlightSqlServiceClient<Channel>,
+    info: FlightInfo,
+) -> String {
+    let ticket = info.endpoint[0].ticket.clone().unwrap();
+    let batches = client
+        .do_get(ticket)
+        .await
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    pretty_format_batches(&batches).unwrap().to_string()
+}
+
 pub async fn test_otel_arrow_auth(store_type: StorageType) {
     let user_provider = user_provider_from_option(
         &"static_user_provider:cmd:greptime_user=greptime_pwd".to_string(),
@@ -110,6 +110,7 @@ macro_rules! http_tests {
                 test_vm_proto_remote_write,
 
                 test_pipeline_api,
+                test_pipeline_versions_api,
                 test_test_pipeline_api,
                 test_plain_text_ingestion,
                 test_pipeline_auto_transform,
@@ -1374,6 +1375,19 @@ enable = true
 enable = true
 with_metric_engine = true
 
+[rule]
+enable = false
+rule_files = []
+evaluation_interval = "1m"
+db = "public"
+
+[promql_cache]
+enable = false
+cache_size = "256MiB"
+split_interval = "1h"
+freshness_lag = "10m"
+time_to_live = "10m"
+
 [wal]
 provider = "raft_engine"
 file_size = "128MiB"
@@ -1483,6 +1497,9 @@ ttl = "2months 29days 2h 52m 48s"
 [query]
 parallelism = 0
 allow_query_fallback = false
+memory_pool_size = "0KiB"
+per_query_memory_limit = "0KiB"
+spill_dirs = []
 
 [memory]
 enable_heap_profiling = true
@@ -2236,6 +2253,141 @@ transform:
     guard.remove_all().await;
 }
 
+pub async fn test_pipeline_versions_api(store_type: StorageType) {
+    common_telemetry::init_default_ut_logging();
+    let (app, mut guard) =
+        setup_test_http_app_with_frontend(store_type, "test_pipeline_versions_api").await;
+
+    // handshake
+    let client = TestClient::new(app).await;
+
+    let pipeline_v1 = r#"
+processors:
+  - date:
+      field: time
+      formats:
+        - "%Y-%m-%d %H:%M:%S%.3f"
+
+transform:
+  - field: log
+    type: string
+  - field: time
+    type: time
+    index: timestamp
+"#;
+    let pipeline_v2 = r#"
+processors:
+  - date:
+      field: time
+      formats:
+        - "%Y-%m-%d %H:%M:%S%.3f"
+
+transform:
+  - field: log
+    type: string
+    index: fulltext
+  - field: time
+    type: time
+    index: timestamp
+"#;
+
+    // 1. create two versions
+    let mut created = vec![];
+    for body in [pipeline_v1, pipeline_v2] {
+        let res = client
+            .post("/v1/pipelines/test")
+            .header("Content-Type", "application/x-yaml")
+            .body(body)
+            .send()
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let content = res.json::<Value>().await;
+        created.push(
+            content["pipelines"][0]["version"]
+                .as_str()
+                .unwrap()
+                .to_string(),
+        );
+    }
+
+    // 2. list versions, the latest first
+    let res = client.get("/v1/pipelines/test/versions").send().await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let content = res.json::<Value>().await;
+    let versions = content["pipelines"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["version"].as_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(versions, vec![created[1].clone(), created[0].clone()]);
+
+    let encoded_v1: String = url::form_urlencoded::byte_serialize(created[0].as_bytes()).collect();
+    let encoded_v2: String = url::form_urlencoded::byte_serialize(created[1].as_bytes()).collect();
+
+    // 3. diff the two versions
+    let res = client
+        .get(
+            format!(
+                "/v1/pipelines/test/_diff?from={}&to={}",
+                encoded_v1, encoded_v2
+            )
+            .as_str(),
+        )
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let content = res.json::<Value>().await;
+    assert_eq!(content["name"], "test");
+    let diff = content["diff"].as_str().unwrap();
+    assert!(diff.contains("+    index: fulltext\n"), "{diff}");
+    assert!(!diff.contains("\n-"), "{diff}");
+
+    // `from` is required
+    let res = client.get("/v1/pipelines/test/_diff").send().await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    // 4. roll back to the version before the latest one
+    let res = client.post("/v1/pipelines/test/_rollback").send().await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let content = res.json::<Value>().await;
+    let rollback_version = content["pipelines"][0]["version"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(!created.contains(&rollback_version));
+
+    let res = client.get("/v1/pipelines/test").send().await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let content = res.json::<Value>().await;
+    let pipeline_yaml = content["pipelines"][0]["pipeline"].as_str().unwrap();
+    assert_eq!(
+        YamlLoader::load_from_str(pipeline_yaml).unwrap(),
+        YamlLoader::load_from_str(pipeline_v1).unwrap()
+    );
+
+    // roll back to an explicit version
+    let res = client
+        .post(format!("/v1/pipelines/test/_rollback?version={}", encoded_v2).as_str())
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = client.get("/v1/pipelines/test/versions").send().await;
+    let content = res.json::<Value>().await;
+    assert_eq!(content["pipelines"].as_array().unwrap().len(), 4);
+
+    let res = client.get("/v1/pipelines/test").send().await;
+    let content = res.json::<Value>().await;
+    let pipeline_yaml = content["pipelines"][0]["pipeline"].as_str().unwrap();
+    assert_eq!(
+        YamlLoader::load_from_str(pipeline_yaml).unwrap(),
+        YamlLoader::load_from_str(pipeline_v2).unwrap()
+    );
+
+    guard.remove_all().await;
+}
+
 pub async fn test_identity_pipeline(store_type: StorageType) {
     common_telemetry::init_default_ut_logging();
     let (app, mut guard) =
@@ -5150,6 +5302,7 @@ pub async fn test_log_query(store_type: StorageType) {
             skip: None,
             fetch: Some(1),
         },
+        direction: None,
         columns: vec!["ts".to_string(), "message".to_string()],
         filters: Default::default(),
         context: Context::None,
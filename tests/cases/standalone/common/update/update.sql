@@ -0,0 +1,47 @@
+CREATE TABLE monitor (host STRING, ts TIMESTAMP, cpu DOUBLE, memory DOUBLE, TIME INDEX (ts), PRIMARY KEY(host));
+
+INSERT INTO monitor(ts, host, cpu, memory) VALUES
+(1655276557000, 'host1', 66.6, 1024),
+(1655276557000, 'host2', 66.6, 1024),
+(1655276558000, 'host1', 77.7, 2048),
+(1655276558000, 'host2', 77.7, 2048);
+
+UPDATE monitor SET cpu = cpu * 2 WHERE host = 'host1';
+
+UPDATE monitor SET memory = NULL, cpu = 0 WHERE ts = 1655276558000::timestamp;
+
+UPDATE monitor SET cpu = 1 WHERE host = 'host3';
+
+SELECT ts, host, cpu, memory FROM monitor ORDER BY host, ts;
+
+UPDATE monitor SET host = 'host3';
+
+UPDATE monitor SET ts = 0;
+
+UPDATE monitor SET not_exist = 1;
+
+UPDATE monitor SET cpu = 1, cpu = 2;
+
+DROP TABLE monitor;
+
+CREATE TABLE last_non_null_monitor (host STRING, ts TIMESTAMP, cpu DOUBLE, memory DOUBLE, TIME INDEX (ts), PRIMARY KEY(host))
+WITH('merge_mode'='last_non_null');
+
+INSERT INTO last_non_null_monitor(ts, host, cpu, memory) VALUES
+(1655276557000, 'host1', 66.6, 1024),
+(1655276557000, 'host2', 66.6, 1024);
+
+UPDATE last_non_null_monitor SET memory = NULL WHERE host = 'host1';
+
+UPDATE last_non_null_monitor SET cpu = 1 WHERE host = 'host1';
+
+SELECT ts, host, cpu, memory FROM last_non_null_monitor ORDER BY host, ts;
+
+DROP TABLE last_non_null_monitor;
+
+CREATE TABLE append_monitor (host STRING, ts TIMESTAMP, cpu DOUBLE, TIME INDEX (ts), PRIMARY KEY(host))
+WITH('append_mode'='true');
+
+UPDATE append_monitor SET cpu = 1;
+
+DROP TABLE append_monitor;